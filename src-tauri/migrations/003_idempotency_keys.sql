@@ -0,0 +1,21 @@
+-- Idempotency Keys Migration
+-- This migration adds a table that records client-supplied idempotency keys
+-- so retried create requests return the original transaction instead of a duplicate
+
+CREATE TABLE idempotency_keys (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    transaction_id TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at DATETIME NOT NULL,
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+
+    -- A key is scoped to the user that supplied it
+    UNIQUE(user_id, idempotency_key)
+);
+
+CREATE INDEX idx_idempotency_keys_user_key ON idempotency_keys(user_id, idempotency_key);
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
@@ -0,0 +1,21 @@
+-- Net Worth Snapshots Migration
+-- This migration adds point-in-time net worth snapshots so historical trending
+-- does not have to be recomputed from transactions on every request
+
+CREATE TABLE net_worth_snapshots (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    snapshot_date DATE NOT NULL,
+    total_assets DECIMAL(15,2) NOT NULL DEFAULT 0.00,
+    total_liabilities DECIMAL(15,2) NOT NULL DEFAULT 0.00,
+    net_worth DECIMAL(15,2) NOT NULL DEFAULT 0.00,
+    account_count INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+
+    -- One snapshot per user per day; recapturing a day replaces it
+    UNIQUE(user_id, snapshot_date)
+);
+
+CREATE INDEX idx_net_worth_snapshots_user_date ON net_worth_snapshots(user_id, snapshot_date);
@@ -0,0 +1,106 @@
+-- Transfer Voiding Migration
+-- This migration allows transfers and their linked transactions to be voided
+-- instead of deleted. SQLite cannot alter CHECK constraints in place, so the
+-- transactions table is rebuilt with 'voided' as an allowed status.
+--
+-- Migrations run inside a transaction where PRAGMA foreign_keys cannot be
+-- toggled, so tables that reference transactions are rebuilt first and dropped
+-- before transactions itself; otherwise dropping transactions would cascade.
+
+CREATE TABLE transactions_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    category_id TEXT,
+    amount DECIMAL(15,2) NOT NULL,
+    description TEXT NOT NULL,
+    notes TEXT,
+    transaction_date DATETIME NOT NULL,
+    transaction_type TEXT NOT NULL CHECK (transaction_type IN ('income', 'expense', 'transfer')),
+    status TEXT NOT NULL DEFAULT 'completed' CHECK (status IN ('pending', 'completed', 'cancelled', 'voided')),
+    reference_number TEXT, -- Check number, confirmation number, etc.
+    payee TEXT, -- Who the transaction was with
+    tags TEXT CHECK (tags IS NULL OR json_valid(tags)), -- JSON array of tags for flexible categorization
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id)
+);
+
+INSERT INTO transactions_new (
+    id, user_id, account_id, category_id, amount, description, notes,
+    transaction_date, transaction_type, status, reference_number, payee, tags,
+    created_at, updated_at
+)
+SELECT
+    id, user_id, account_id, category_id, amount, description, notes,
+    transaction_date, transaction_type, status, reference_number, payee, tags,
+    created_at, updated_at
+FROM transactions;
+
+-- Transfers gain the status and updated_at columns used by the application
+CREATE TABLE transfers_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    from_account_id TEXT NOT NULL,
+    to_account_id TEXT NOT NULL,
+    from_transaction_id TEXT NOT NULL,
+    to_transaction_id TEXT NOT NULL,
+    amount DECIMAL(15,2) NOT NULL,
+    description TEXT,
+    transfer_date DATETIME NOT NULL,
+    status TEXT NOT NULL DEFAULT 'completed' CHECK (status IN ('pending', 'completed', 'cancelled', 'voided')),
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_transaction_id) REFERENCES transactions_new(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_transaction_id) REFERENCES transactions_new(id) ON DELETE CASCADE
+);
+
+INSERT INTO transfers_new (
+    id, user_id, from_account_id, to_account_id, from_transaction_id, to_transaction_id,
+    amount, description, transfer_date, status, created_at, updated_at
+)
+SELECT
+    id, user_id, from_account_id, to_account_id, from_transaction_id, to_transaction_id,
+    amount, description, transfer_date, 'completed', created_at, created_at
+FROM transfers;
+
+CREATE TABLE idempotency_keys_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    transaction_id TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at DATETIME NOT NULL,
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions_new(id) ON DELETE CASCADE,
+
+    UNIQUE(user_id, idempotency_key)
+);
+
+INSERT INTO idempotency_keys_new (id, user_id, idempotency_key, transaction_id, created_at, expires_at)
+SELECT id, user_id, idempotency_key, transaction_id, created_at, expires_at
+FROM idempotency_keys;
+
+-- Drop dependents before the referenced table so no cascades fire
+DROP TABLE idempotency_keys;
+DROP TABLE transfers;
+DROP TABLE transactions;
+
+-- Renaming rewrites the REFERENCES clauses in dependent tables
+ALTER TABLE transactions_new RENAME TO transactions;
+ALTER TABLE transfers_new RENAME TO transfers;
+ALTER TABLE idempotency_keys_new RENAME TO idempotency_keys;
+
+CREATE INDEX idx_transactions_account_date ON transactions(account_id, transaction_date);
+CREATE INDEX idx_transactions_category ON transactions(category_id);
+CREATE INDEX idx_transactions_date ON transactions(transaction_date);
+CREATE INDEX idx_transactions_user ON transactions(user_id);
+CREATE INDEX idx_transfers_user ON transfers(user_id);
+CREATE INDEX idx_idempotency_keys_user_key ON idempotency_keys(user_id, idempotency_key);
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
@@ -0,0 +1,6 @@
+-- Account Opening Balance Migration
+-- This migration records an explicit opening balance per account so historical
+-- data can be imported starting from a non-zero balance
+
+ALTER TABLE accounts ADD COLUMN opening_balance DECIMAL(15,2) NOT NULL DEFAULT 0.00;
+ALTER TABLE accounts ADD COLUMN opening_balance_date DATE;
@@ -0,0 +1,8 @@
+-- Cross-User Transfers Migration
+-- This migration lets a transfer move money into another user's account.
+-- user_id remains the owner of the source account; to_user_id records the
+-- owner of the destination account and is NULL when both accounts share an owner.
+
+ALTER TABLE transfers ADD COLUMN to_user_id TEXT REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_transfers_to_user ON transfers(to_user_id);
@@ -0,0 +1,15 @@
+-- Minor-Unit Amounts Migration
+-- This migration adds an optional integer copy of each transaction amount in
+-- minor units (cents) so range filters compare numbers instead of text.
+-- New rows only get a value when FISCUS_STORE_AMOUNT_MINOR_UNITS is enabled.
+-- Existing plaintext amounts are backfilled here whatever the setting, scaled
+-- by 100 like `decimal_to_minor_units`, since they are already stored
+-- unencrypted; encrypted amounts cannot be backfilled and are left NULL.
+
+ALTER TABLE transactions ADD COLUMN amount_minor INTEGER;
+
+UPDATE transactions
+SET amount_minor = CAST(ROUND(CAST(amount AS REAL) * 100) AS INTEGER)
+WHERE amount NOT LIKE 'enc:%';
+
+CREATE INDEX idx_transactions_user_amount_minor ON transactions(user_id, amount_minor);
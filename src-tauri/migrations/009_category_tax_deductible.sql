@@ -0,0 +1,7 @@
+-- Tax-Deductible Categories Migration
+-- This migration lets users flag expense categories as tax deductible so
+-- yearly deductible totals can be reported for tax preparation.
+
+ALTER TABLE categories ADD COLUMN tax_deductible BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_categories_user_deductible ON categories(user_id, tax_deductible);
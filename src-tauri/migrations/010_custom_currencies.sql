@@ -0,0 +1,10 @@
+-- Custom Currencies Migration
+-- This migration stores user-defined currency codes (crypto, local scrip) that
+-- are accepted alongside the built-in ISO 4217 list. Rows are loaded into the
+-- runtime currency allowlist.
+
+CREATE TABLE custom_currencies (
+    code TEXT PRIMARY KEY,
+    minor_units INTEGER NOT NULL CHECK (minor_units >= 0 AND minor_units <= 18),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
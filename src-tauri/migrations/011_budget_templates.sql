@@ -0,0 +1,29 @@
+-- Budget Templates Migration
+-- This migration adds reusable budget templates: a named set of category
+-- allocations that can be instantiated into a new budget period in one step.
+-- Budgets gain a rollover flag that templates carry through.
+
+ALTER TABLE budgets ADD COLUMN rollover BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE budget_templates (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE budget_template_allocations (
+    id TEXT PRIMARY KEY,
+    template_id TEXT NOT NULL,
+    category_id TEXT NOT NULL,
+    allocated_amount TEXT NOT NULL,
+    rollover BOOLEAN NOT NULL DEFAULT 0,
+    notes TEXT,
+    FOREIGN KEY (template_id) REFERENCES budget_templates(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    UNIQUE(template_id, category_id)
+);
+
+CREATE INDEX idx_budget_templates_user ON budget_templates(user_id);
@@ -0,0 +1,17 @@
+-- Transaction Original Currency Migration
+-- This migration lets a transaction keep the amount and currency it was made in
+-- (e.g. a EUR purchase on a USD account) while its `amount` holds the value
+-- converted to the account currency. Conversion uses the exchange_rates table.
+
+ALTER TABLE transactions ADD COLUMN original_amount TEXT;
+ALTER TABLE transactions ADD COLUMN original_currency TEXT;
+
+-- One unit of base_currency is worth `rate` units of quote_currency on rate_date
+CREATE TABLE exchange_rates (
+    base_currency TEXT NOT NULL,
+    quote_currency TEXT NOT NULL,
+    rate TEXT NOT NULL,
+    rate_date DATE NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (base_currency, quote_currency, rate_date)
+);
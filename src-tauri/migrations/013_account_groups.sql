@@ -0,0 +1,19 @@
+-- Account Groups Migration
+-- This migration adds user-defined account groups (e.g. "Retirement",
+-- "Daily Spending"). An account belongs to at most one group; deleting a
+-- group leaves its accounts ungrouped.
+
+CREATE TABLE account_groups (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(user_id, name)
+);
+
+ALTER TABLE accounts ADD COLUMN group_id TEXT REFERENCES account_groups(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_account_groups_user ON account_groups(user_id);
+CREATE INDEX idx_accounts_group ON accounts(group_id);
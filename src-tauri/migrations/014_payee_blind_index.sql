@@ -0,0 +1,10 @@
+-- Payee Blind Index Migration
+-- Payees are now stored encrypted. To keep exact-match payee lookups possible
+-- without decrypting every row, each transaction also stores a deterministic
+-- blind index of its payee (HMAC of the normalised value under a per-user key).
+-- Rows written before this migration keep their plaintext payee and have no
+-- index until the payee is next updated.
+
+ALTER TABLE transactions ADD COLUMN payee_index TEXT;
+
+CREATE INDEX idx_transactions_payee_index ON transactions(user_id, payee_index);
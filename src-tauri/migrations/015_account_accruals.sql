@@ -0,0 +1,28 @@
+-- Account Accruals Migration
+-- This migration adds periodic interest and fee definitions for accounts.
+-- `apply_accruals` posts a transaction for each period that fell due since
+-- `last_applied`, then advances the marker so re-running it posts nothing twice.
+-- Interest uses `annual_rate` (a fraction, negative to charge interest);
+-- fees use the flat `amount`, stored encrypted like other amounts.
+
+CREATE TABLE account_accruals (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('interest', 'fee')),
+    amount TEXT,
+    annual_rate TEXT,
+    frequency TEXT NOT NULL CHECK (frequency IN ('monthly', 'quarterly', 'yearly')),
+    category_id TEXT,
+    start_date DATE NOT NULL,
+    last_applied DATE,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_account_accruals_user ON account_accruals(user_id, is_active);
@@ -0,0 +1,23 @@
+-- Wrapped User Keys Migration
+-- This migration persists users' encryption keys wrapped under a master key
+-- derived from their password. `user_key_wrapping` holds the salt for that
+-- derivation; `rekey_after_password_change` re-wraps every row and replaces
+-- the salt in one database transaction.
+
+CREATE TABLE user_key_wrapping (
+    user_id TEXT PRIMARY KEY,
+    kdf_salt TEXT NOT NULL, -- Base64 encoded Argon2id salt
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE wrapped_user_keys (
+    key_id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    key_identifier TEXT NOT NULL,
+    wrapped_key TEXT NOT NULL, -- JSON serialized EncryptedData, key ID bound as AAD
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_wrapped_user_keys_user ON wrapped_user_keys(user_id);
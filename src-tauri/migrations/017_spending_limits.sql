@@ -0,0 +1,8 @@
+-- Spending Limits Migration
+-- This migration adds optional monthly spending limits to accounts and categories.
+-- Unlike budgets, which only report, a limit blocks `create_transaction` from
+-- booking an expense that would take the calendar month's spend past it unless
+-- the request sets `override_limit`. Limits are stored encrypted like other amounts.
+
+ALTER TABLE accounts ADD COLUMN spending_limit TEXT;
+ALTER TABLE categories ADD COLUMN spending_limit TEXT;
@@ -0,0 +1,9 @@
+-- Transaction Refunds Migration
+-- This migration lets an income transaction record the expense it refunds, so
+-- reports can net the refund against the original purchase's category instead
+-- of counting both. A purchase may have several partial refunds, which together
+-- may not exceed its amount.
+
+ALTER TABLE transactions ADD COLUMN refunds_transaction_id TEXT REFERENCES transactions(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_refunds_transaction_id ON transactions(refunds_transaction_id);
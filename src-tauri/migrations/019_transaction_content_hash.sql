@@ -0,0 +1,11 @@
+-- Transaction Content Hash Migration
+-- Bank files often overlap, so importing them again would duplicate rows that
+-- have no shared external id. Each transaction now stores a hash of its
+-- account, date, amount and normalised description, keyed per user so the
+-- stored value cannot be checked against guessed amounts. Imports skip rows
+-- whose hash is already present. Rows written before this migration have no
+-- hash and are not matched.
+
+ALTER TABLE transactions ADD COLUMN content_hash TEXT;
+
+CREATE INDEX idx_transactions_content_hash ON transactions(user_id, content_hash);
@@ -0,0 +1,6 @@
+-- Goal Milestones Migration
+-- Progress updates report the milestones (percentages of the target) a goal
+-- newly crosses. The highest milestone already reported is stored so the
+-- same milestone does not fire again on later contributions.
+
+ALTER TABLE goals ADD COLUMN last_milestone INTEGER NOT NULL DEFAULT 0;
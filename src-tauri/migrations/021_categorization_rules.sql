@@ -0,0 +1,21 @@
+-- Categorization Rules Migration
+-- This migration adds user-defined rules that assign categories in bulk.
+-- `apply_categorization_rules` tries rules from the highest priority down and
+-- gives each transaction the category of the first rule it matches.
+-- Text rules match a payee or description substring; amount rules hold a
+-- `min..max` range. Patterns reveal spending habits, so they are encrypted.
+
+CREATE TABLE categorization_rules (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    match_field TEXT NOT NULL CHECK (match_field IN ('payee', 'description', 'amount')),
+    pattern TEXT NOT NULL,
+    category_id TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_categorization_rules_user ON categorization_rules(user_id, priority);
@@ -0,0 +1,7 @@
+-- Account Closing Migration
+-- This migration lets an account be closed with a reconciled final balance.
+-- Closed accounts keep their history but accept no new transactions.
+
+ALTER TABLE accounts ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+    CHECK (status IN ('active', 'closed'));
+ALTER TABLE accounts ADD COLUMN closing_date DATE;
@@ -0,0 +1,8 @@
+-- Account Defaults Migration
+-- This migration lets an account carry a default category and payee.
+-- New transactions on the account fall back to them when they leave
+-- their own category or payee unset.
+
+ALTER TABLE accounts ADD COLUMN default_category_id TEXT
+    REFERENCES categories(id) ON DELETE SET NULL;
+ALTER TABLE accounts ADD COLUMN default_payee TEXT;
@@ -0,0 +1,8 @@
+-- Wrapped Key Metadata Migration
+-- Wrapped keys are now loaded back into the key manager when the user logs
+-- in. Restoring a key needs the algorithm it is used with and whether it was
+-- its data type's active key; rows stored earlier were all active AES-256-GCM
+-- keys.
+
+ALTER TABLE wrapped_user_keys ADD COLUMN algorithm TEXT NOT NULL DEFAULT 'aes256_gcm';
+ALTER TABLE wrapped_user_keys ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,7 @@
+-- Key Wrapping KDF Migration
+-- The master key wrapping a user's keys is derived with the configured
+-- password KDF, which is stored next to its salt so a later change of
+-- configuration can still unwrap existing keys. Rows stored earlier were all
+-- derived with Argon2id.
+
+ALTER TABLE user_key_wrapping ADD COLUMN kdf_algorithm TEXT NOT NULL DEFAULT 'argon2id';
@@ -0,0 +1,118 @@
+/// Time sources for time-dependent logic
+///
+/// Rate limiting, session expiry and key rotation read the time through a
+/// [`Clock`] so tests can move time forward deterministically with
+/// [`MockClock`] instead of sleeping.
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of the current wall-clock and monotonic time
+pub trait Clock: Debug + Send + Sync {
+    /// Current wall-clock time
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Current monotonic time, for measuring elapsed durations
+    fn instant(&self) -> Instant;
+}
+
+/// Clock shared between the components that read it
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Clock backed by the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Shared handle to the system clock
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Clock that only moves when advanced explicitly
+///
+/// Both readings start at the time the clock was created and move together.
+#[derive(Debug)]
+pub struct MockClock {
+    start: DateTime<Utc>,
+    start_instant: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl MockClock {
+    /// Create a mock clock frozen at the current time
+    pub fn new() -> Self {
+        Self::starting_at(Utc::now())
+    }
+
+    /// Create a mock clock frozen at `start`
+    pub fn starting_at(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            start_instant: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Move the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        let mut elapsed = self.elapsed.lock().unwrap_or_else(|e| e.into_inner());
+        *elapsed += duration;
+    }
+
+    fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        // Durations beyond chrono's range saturate rather than panic
+        let elapsed = chrono::Duration::from_std(self.elapsed()).unwrap_or(chrono::Duration::MAX);
+        self.start
+            .checked_add_signed(elapsed)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    fn instant(&self) -> Instant {
+        self.start_instant + self.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_only_moves_when_advanced() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let start_instant = clock.instant();
+
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.instant(), start_instant);
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(clock.now() - start, chrono::Duration::seconds(90));
+        assert_eq!(
+            clock.instant().duration_since(start_instant),
+            Duration::from_secs(90)
+        );
+    }
+}
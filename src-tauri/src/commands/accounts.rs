@@ -1,14 +1,31 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde_json::Value;
 use std::collections::HashMap;
 use tauri::State;
 use uuid::Uuid;
 
 use crate::{
+    clock::SystemClock,
+    commands::{accruals::insert_generated_transaction, transactions::balance_delta},
     database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
-    dto::{AccountFilters, AccountSummaryResponse, CreateAccountRequest, UpdateAccountRequest},
-    error::{FiscusError, SecurityValidator, Validator},
-    models::Account,
-    utils::parse_decimal_from_json,
+    dto::{
+        AccountFilters, AccountGroupWithAccounts, AccountSummaryResponse, BalanceAuditResponse,
+        CreateAccountGroupRequest, CreateAccountRequest, GroupedAccountsResponse, Patch,
+        PayoffProjectionResponse, UpdateAccountRequest,
+    },
+    error::{FiscusError, FiscusResult, SecurityValidator, Validator},
+    models::{
+        Account, AccountGroup, AccountStatus, AccountType, Transaction, TransactionStatus,
+        TransactionType,
+    },
+    security::{
+        active_context, authorize_command, authorize_user, context_grants, require_recent_auth,
+        SecurityContext, PERMISSION_REVEAL_ACCOUNT_NUMBER, RECENT_AUTH_MAX_AGE,
+    },
+    services::events::{self, TransactionEvent},
+    utils::{no_rows_updated_error, parse_decimal_from_json, stale_write_guard},
+    with_transaction,
 };
 
 /// Create a new account
@@ -17,6 +34,8 @@ pub async fn create_account(
     request: CreateAccountRequest,
     db: State<'_, Database>,
 ) -> Result<Account, FiscusError> {
+    authorize_command("create_account").await?;
+
     // Validate input
     Validator::validate_uuid(&request.user_id.as_str(), "user_id")?;
     Validator::validate_uuid(&request.account_type_id, "account_type_id")?;
@@ -95,7 +114,143 @@ pub async fn create_account(
     DatabaseUtils::execute_non_query(&db, insert_query, encrypted_params).await?;
 
     // Return the created account
-    get_account_by_id(account_id, db).await
+    get_account_by_id(account_id, None, db).await
+}
+
+/// Create a new account with the same type, currency, group and defaults as an existing one
+///
+/// Only the account's structure is copied: the clone gets a fresh ID, starts
+/// at a zero balance with no transactions, and has no account number.
+#[tauri::command]
+pub async fn clone_account(
+    source_account_id: String,
+    user_id: String,
+    new_name: String,
+    db: State<'_, Database>,
+) -> Result<Account, FiscusError> {
+    authorize_command("clone_account").await?;
+
+    // Validate input
+    Validator::validate_uuid(&source_account_id, "source_account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_string(&new_name, "name", 1, 100)?;
+
+    let source = get_account_by_id(source_account_id, None, db.clone()).await?;
+    if source.user_id != user_id {
+        return Err(FiscusError::Authorization(
+            "Account access denied".to_string(),
+        ));
+    }
+
+    let account = cloned_account(&source, &new_name, chrono::Utc::now());
+    let params_with_mapping = account_insert_params(&account);
+    let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+        params_with_mapping,
+        &user_id,
+        "accounts",
+    )
+    .await?;
+
+    DatabaseUtils::execute_non_query(&db, CLONE_ACCOUNT_INSERT, encrypted_params).await?;
+
+    get_account_by_id(account.id, None, db).await
+}
+
+/// Insert for a cloned account, writing every column of `Account`
+const CLONE_ACCOUNT_INSERT: &str = r#"
+    INSERT INTO accounts (
+        id, user_id, account_type_id, name, balance, opening_balance, opening_balance_date,
+        currency, account_number, is_active, group_id, status, closing_date,
+        default_category_id, default_payee, created_at, updated_at
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+"#;
+
+/// Parameters of `CLONE_ACCOUNT_INSERT`, in column order and keyed for encryption
+fn account_insert_params(account: &Account) -> Vec<(String, Value)> {
+    let optional = |value: Option<String>| value.map(Value::String).unwrap_or(Value::Null);
+
+    vec![
+        ("id".to_string(), Value::String(account.id.clone())),
+        (
+            "user_id".to_string(),
+            Value::String(account.user_id.clone()),
+        ),
+        (
+            "account_type_id".to_string(),
+            Value::String(account.account_type_id.clone()),
+        ),
+        ("name".to_string(), Value::String(account.name.clone())),
+        (
+            "balance".to_string(),
+            Value::String(account.balance.to_string()),
+        ),
+        (
+            "opening_balance".to_string(),
+            Value::String(account.opening_balance.to_string()),
+        ),
+        (
+            "opening_balance_date".to_string(),
+            optional(account.opening_balance_date.map(|d| d.to_string())),
+        ),
+        (
+            "currency".to_string(),
+            Value::String(account.currency.clone()),
+        ),
+        (
+            "account_number".to_string(),
+            optional(account.account_number.clone()),
+        ),
+        ("is_active".to_string(), Value::Bool(account.is_active)),
+        ("group_id".to_string(), optional(account.group_id.clone())),
+        (
+            "status".to_string(),
+            Value::String(account.status.to_string()),
+        ),
+        (
+            "closing_date".to_string(),
+            optional(account.closing_date.map(|d| d.to_string())),
+        ),
+        (
+            "default_category_id".to_string(),
+            optional(account.default_category_id.clone()),
+        ),
+        (
+            "default_payee".to_string(),
+            optional(account.default_payee.clone()),
+        ),
+        (
+            "created_at".to_string(),
+            Value::String(account.created_at.to_rfc3339()),
+        ),
+        (
+            "updated_at".to_string(),
+            Value::String(account.updated_at.to_rfc3339()),
+        ),
+    ]
+}
+
+/// Copy `source`'s type, currency, group and defaults into a new, empty
+/// account named `name`
+fn cloned_account(source: &Account, name: &str, now: chrono::DateTime<chrono::Utc>) -> Account {
+    Account {
+        id: Uuid::new_v4().to_string(),
+        user_id: source.user_id.clone(),
+        account_type_id: source.account_type_id.clone(),
+        name: name.to_string(),
+        balance: Decimal::ZERO,
+        opening_balance: Decimal::ZERO,
+        opening_balance_date: None,
+        currency: source.currency.clone(),
+        account_number: None,
+        is_active: true,
+        group_id: source.group_id.clone(),
+        status: AccountStatus::Active,
+        closing_date: None,
+        default_category_id: source.default_category_id.clone(),
+        default_payee: source.default_payee.clone(),
+        created_at: now,
+        updated_at: now,
+    }
 }
 
 /// Get all accounts for a user with optional filtering
@@ -104,6 +259,8 @@ pub async fn get_accounts(
     filters: AccountFilters,
     db: State<'_, Database>,
 ) -> Result<Vec<Account>, FiscusError> {
+    authorize_command("get_accounts").await?;
+
     // Validate user
     Validator::validate_uuid(&filters.user_id.as_str(), "user_id")?;
     DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
@@ -125,8 +282,10 @@ pub async fn get_accounts(
     SecurityValidator::validate_account_filter_fields(&filter_map)?;
 
     let base_query = r#"
-        SELECT a.id, a.user_id, a.account_type_id, a.name, a.balance, a.currency, 
-               a.account_number, a.is_active, a.created_at, a.updated_at
+        SELECT a.id, a.user_id, a.account_type_id, a.name, a.balance, a.opening_balance,
+               a.opening_balance_date, a.currency, a.account_number, a.is_active, a.group_id,
+               a.status, a.closing_date, a.default_category_id, a.default_payee,
+               a.created_at, a.updated_at
         FROM accounts a
     "#;
 
@@ -148,7 +307,7 @@ pub async fn get_accounts(
     let final_query = format!("{base_query} {where_clause} {order_clause} {limit_clause}");
 
     // Use encrypted query to properly decrypt sensitive fields
-    let accounts: Vec<Account> = EncryptedDatabaseUtils::execute_encrypted_query(
+    let mut accounts: Vec<Account> = EncryptedDatabaseUtils::execute_encrypted_query(
         &db,
         &final_query,
         where_params,
@@ -157,15 +316,22 @@ pub async fn get_accounts(
     )
     .await?;
 
+    present_account_numbers(&mut accounts, filters.reveal_account_number).await;
     Ok(accounts)
 }
 
 /// Get a single account by ID
+///
+/// The account number is masked unless `reveal_account_number` is set and the
+/// session holds the reveal permission.
 #[tauri::command]
 pub async fn get_account_by_id(
     account_id: String,
+    reveal_account_number: Option<bool>,
     db: State<'_, Database>,
 ) -> Result<Account, FiscusError> {
+    authorize_command("get_account_by_id").await?;
+
     Validator::validate_uuid(&account_id, "account_id")?;
 
     // First, get the user_id for this account (this field is not encrypted)
@@ -186,14 +352,16 @@ pub async fn get_account_by_id(
         .ok_or_else(|| FiscusError::NotFound("Account not found".to_string()))?;
 
     let query = r#"
-        SELECT id, user_id, account_type_id, name, balance, currency,
-               account_number, is_active, created_at, updated_at
+        SELECT id, user_id, account_type_id, name, balance, opening_balance,
+               opening_balance_date, currency, account_number, is_active, group_id,
+               status, closing_date, default_category_id, default_payee,
+               created_at, updated_at
         FROM accounts
         WHERE id = ?1
     "#;
 
     // Use encrypted query to properly decrypt sensitive fields
-    let accounts: Vec<Account> = EncryptedDatabaseUtils::execute_encrypted_query(
+    let mut accounts: Vec<Account> = EncryptedDatabaseUtils::execute_encrypted_query(
         &db,
         query,
         vec![Value::String(account_id.clone())],
@@ -202,28 +370,53 @@ pub async fn get_account_by_id(
     )
     .await?;
 
+    present_account_numbers(&mut accounts, reveal_account_number.unwrap_or(false)).await;
     accounts
         .into_iter()
         .next()
         .ok_or_else(|| FiscusError::NotFound("Account not found".to_string()))
 }
 
-/// Update an account
-#[tauri::command]
-pub async fn update_account(
-    account_id: String,
-    user_id: String,
-    request: UpdateAccountRequest,
-    db: State<'_, Database>,
-) -> Result<Account, FiscusError> {
-    // Validate input
-    Validator::validate_uuid(&account_id, "account_id")?;
-    Validator::validate_uuid(&user_id, "user_id")?;
+/// Mask account numbers unless `reveal` was requested and the session may see them
+async fn present_account_numbers(accounts: &mut [Account], reveal: bool) {
+    let reveal = reveal_permitted(reveal, active_context().await.as_ref());
+    apply_account_number_visibility(accounts, reveal);
+}
 
-    // Validate ownership
-    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+/// Whether a request for full account numbers is granted under `context`
+fn reveal_permitted(requested: bool, context: Option<&SecurityContext>) -> bool {
+    requested && context_grants(context, PERMISSION_REVEAL_ACCOUNT_NUMBER)
+}
 
-    // Build update query dynamically with encrypted parameter mapping
+fn apply_account_number_visibility(accounts: &mut [Account], reveal: bool) {
+    if reveal {
+        return;
+    }
+    for account in accounts {
+        account.account_number = account.account_number.as_deref().map(mask_account_number);
+    }
+}
+
+/// `****` followed by the last four characters; shorter numbers are hidden entirely
+fn mask_account_number(number: &str) -> String {
+    let chars: Vec<char> = number.chars().collect();
+    if chars.len() <= 4 {
+        return "****".to_string();
+    }
+    let last_four: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{last_four}")
+}
+
+/// SET assignments and their encrypted-parameter mapping for an update
+type UpdateFields = (Vec<String>, Vec<(String, Value)>);
+
+/// Build the SET assignments and parameters for an account update
+///
+/// Unset fields are skipped; an explicit null account number or default
+/// becomes `column = NULL` without a bound parameter.
+fn build_account_update_fields(
+    request: &UpdateAccountRequest,
+) -> Result<UpdateFields, FiscusError> {
     let mut update_fields = Vec::new();
     let mut params_with_mapping = Vec::new();
     let mut param_index = 1;
@@ -242,21 +435,76 @@ pub async fn update_account(
         param_index += 1;
     }
 
-    if let Some(account_number) = &request.account_number {
-        update_fields.push(format!("`account_number` = ?{param_index}"));
-        params_with_mapping.push((
-            "account_number".to_string(),
-            Value::String(account_number.clone()),
-        ));
-        param_index += 1;
+    match &request.account_number {
+        Patch::Unset => {}
+        Patch::Null => update_fields.push("`account_number` = NULL".to_string()),
+        Patch::Set(account_number) => {
+            update_fields.push(format!("`account_number` = ?{param_index}"));
+            params_with_mapping.push((
+                "account_number".to_string(),
+                Value::String(account_number.clone()),
+            ));
+            param_index += 1;
+        }
+    }
+
+    match &request.default_category_id {
+        Patch::Unset => {}
+        Patch::Null => update_fields.push("`default_category_id` = NULL".to_string()),
+        Patch::Set(category_id) => {
+            Validator::validate_uuid(category_id, "default_category_id")?;
+            update_fields.push(format!("`default_category_id` = ?{param_index}"));
+            params_with_mapping.push((
+                "default_category_id".to_string(),
+                Value::String(category_id.clone()),
+            ));
+            param_index += 1;
+        }
+    }
+
+    match &request.default_payee {
+        Patch::Unset => {}
+        Patch::Null => update_fields.push("`default_payee` = NULL".to_string()),
+        Patch::Set(payee) => {
+            Validator::validate_string(payee, "default_payee", 1, 200)?;
+            update_fields.push(format!("`default_payee` = ?{param_index}"));
+            params_with_mapping.push(("default_payee".to_string(), Value::String(payee.clone())));
+            param_index += 1;
+        }
     }
 
     if let Some(is_active) = request.is_active {
         update_fields.push(format!("`is_active` = ?{param_index}"));
         params_with_mapping.push(("is_active".to_string(), Value::Bool(is_active)));
-        param_index += 1;
     }
 
+    Ok((update_fields, params_with_mapping))
+}
+
+/// Update an account
+#[tauri::command]
+pub async fn update_account(
+    account_id: String,
+    user_id: String,
+    request: UpdateAccountRequest,
+    db: State<'_, Database>,
+) -> Result<Account, FiscusError> {
+    authorize_command("update_account").await?;
+
+    // Validate input
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    // Validate ownership
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+    if let Patch::Set(category_id) = &request.default_category_id {
+        DatabaseUtils::validate_category_ownership(&db, category_id, &user_id).await?;
+    }
+
+    // Build update query dynamically with encrypted parameter mapping
+    let (mut update_fields, mut params_with_mapping) = build_account_update_fields(&request)?;
+    let mut param_index = params_with_mapping.len() + 1;
+
     if update_fields.is_empty() {
         return Err(FiscusError::InvalidInput("No fields to update".to_string()));
     }
@@ -272,10 +520,19 @@ pub async fn update_account(
     // Add account_id for WHERE clause
     params_with_mapping.push(("id".to_string(), Value::String(account_id.clone())));
 
+    // Reject the write if the account changed since the client read it
+    let mut where_clause = format!("id = ?{param_index}");
+    if let Some((condition, param)) =
+        stale_write_guard(request.expected_updated_at, param_index + 1)
+    {
+        where_clause.push_str(&condition);
+        params_with_mapping.push(("expected_updated_at".to_string(), param));
+    }
+
     let update_query = format!(
-        "UPDATE accounts SET {} WHERE id = ?{}",
+        "UPDATE accounts SET {} WHERE {}",
         update_fields.join(", "),
-        param_index
+        where_clause
     );
 
     // Encrypt sensitive parameters before update
@@ -290,20 +547,36 @@ pub async fn update_account(
         DatabaseUtils::execute_non_query(&db, &update_query, encrypted_params).await?;
 
     if affected_rows == 0 {
-        return Err(FiscusError::NotFound("Account not found".to_string()));
+        // A stale timestamp only means a conflict if the account is still there
+        let row_exists = request.expected_updated_at.is_some()
+            && DatabaseUtils::row_exists(&db, "accounts", &account_id, &user_id).await?;
+        return Err(no_rows_updated_error(
+            request.expected_updated_at,
+            row_exists,
+            "Account not found",
+        ));
     }
 
     // Return updated account
-    get_account_by_id(account_id, db).await
+    get_account_by_id(account_id, None, db).await
 }
 
 /// Delete an account (soft delete by setting is_active to false)
+///
+/// Requires the session to have authenticated recently.
 #[tauri::command]
 pub async fn delete_account(
     account_id: String,
     user_id: String,
     db: State<'_, Database>,
 ) -> Result<bool, FiscusError> {
+    authorize_command("delete_account").await?;
+    require_recent_auth(
+        active_context().await.as_ref(),
+        RECENT_AUTH_MAX_AGE,
+        &SystemClock,
+    )?;
+
     // Validate input
     Validator::validate_uuid(&account_id, "account_id")?;
     Validator::validate_uuid(&user_id, "user_id")?;
@@ -360,19 +633,27 @@ pub async fn delete_account(
 }
 
 /// Get account summary for a user
+///
+/// With `group_id` only the accounts in that group are counted.
 #[tauri::command]
 pub async fn get_account_summary(
     user_id: String,
+    group_id: Option<String>,
     db: State<'_, Database>,
 ) -> Result<AccountSummaryResponse, FiscusError> {
+    authorize_command("get_account_summary").await?;
+
     // Validate user
     Validator::validate_uuid(&user_id, "user_id")?;
+    if let Some(group_id) = &group_id {
+        Validator::validate_uuid(group_id, "group_id")?;
+    }
     DatabaseUtils::validate_user_exists(&db, &user_id).await?;
 
     // For aggregation on encrypted fields, we need to fetch all accounts first and decrypt them
     let accounts_query = r#"
         SELECT a.id, a.user_id, a.account_type_id, a.name, a.balance, a.currency,
-               a.account_number, a.is_active, a.created_at, a.updated_at, at.is_asset
+               a.account_number, a.is_active, a.group_id, a.created_at, a.updated_at, at.is_asset
         FROM accounts a
         JOIN account_types at ON a.account_type_id = at.id
         WHERE a.user_id = ?1 AND a.is_active = 1
@@ -389,32 +670,1408 @@ pub async fn get_account_summary(
         )
         .await?;
 
-    // Calculate summary from decrypted account data
-    let mut total_assets = rust_decimal::Decimal::ZERO;
-    let mut total_liabilities = rust_decimal::Decimal::ZERO;
-    let account_count = accounts_with_types.len() as i32;
+    Ok(summarize_accounts(
+        &accounts_with_types,
+        group_id.as_deref(),
+    ))
+}
+
+/// Summarise decrypted account rows, keeping only those in `group_id` when given
+fn summarize_accounts(
+    accounts: &[HashMap<String, serde_json::Value>],
+    group_id: Option<&str>,
+) -> AccountSummaryResponse {
+    let accounts: Vec<HashMap<String, serde_json::Value>> = accounts
+        .iter()
+        .filter(|account| {
+            group_id.is_none() || account.get("group_id").and_then(|v| v.as_str()) == group_id
+        })
+        .cloned()
+        .collect();
+
+    let account_count = accounts.len() as i32;
+    let (total_assets, total_liabilities) = bucket_account_balances(&accounts);
+
+    AccountSummaryResponse {
+        total_assets,
+        total_liabilities,
+        net_worth: total_assets - total_liabilities,
+        account_count,
+    }
+}
+
+/// Whether an account row (joined with `account_types`) is a liability.
+///
+/// SQLite returns `is_asset` as an integer, so both numeric and boolean values
+/// are accepted; rows without the column fall back to the built-in type ids.
+fn is_liability_account(account: &HashMap<String, serde_json::Value>) -> bool {
+    match account.get("is_asset") {
+        Some(Value::Bool(is_asset)) => !is_asset,
+        Some(Value::Number(n)) => n.as_i64() == Some(0),
+        Some(Value::String(s)) => matches!(s.as_str(), "0" | "false"),
+        _ => account
+            .get("account_type_id")
+            .and_then(|v| v.as_str())
+            .map(AccountType::is_liability_type_id)
+            .unwrap_or(false),
+    }
+}
+
+/// Split account balances into total assets and total liabilities.
+///
+/// Liability balances are counted by magnitude regardless of how they were
+/// entered, so a card owing 300 reduces net worth by 300 whether it is stored
+/// as `300` or `-300`.
+fn bucket_account_balances(accounts: &[HashMap<String, serde_json::Value>]) -> (Decimal, Decimal) {
+    accounts.iter().fold(
+        (Decimal::ZERO, Decimal::ZERO),
+        |(assets, liabilities), account| {
+            let balance = parse_decimal_from_json(account, "balance");
+            if is_liability_account(account) {
+                (assets, liabilities + balance.abs())
+            } else {
+                (assets + balance, liabilities)
+            }
+        },
+    )
+}
+
+/// Upper bound on simulated payoff months (100 years) to guarantee termination
+const MAX_PAYOFF_MONTHS: u32 = 1200;
 
-    for account in accounts_with_types {
-        let balance = parse_decimal_from_json(&account, "balance");
+/// Months and total interest to pay off `balance` at a fixed monthly payment
+///
+/// Interest accrues at `annual_apr / 12` on the outstanding balance, rounded
+/// to cents, before each payment; the final payment only covers what is left.
+/// Fails when the payment doesn't exceed the first month's interest, since the
+/// debt would then never shrink.
+fn simulate_payoff(
+    balance: Decimal,
+    monthly_payment: Decimal,
+    annual_apr: Decimal,
+) -> FiscusResult<(u32, Decimal)> {
+    let monthly_rate = annual_apr / Decimal::from(12);
 
-        let is_asset = account
-            .get("is_asset")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
+    let first_interest = (balance * monthly_rate).round_dp(2);
+    if balance > Decimal::ZERO && monthly_payment <= first_interest {
+        return Err(FiscusError::field_validation(
+            "monthly_payment",
+            "insufficient_payment",
+            format!(
+                "A monthly payment of {monthly_payment} does not cover the {first_interest} of monthly interest"
+            ),
+        ));
+    }
 
-        if is_asset {
-            total_assets += balance;
-        } else {
-            total_liabilities += balance.abs();
+    let mut remaining = balance;
+    let mut total_interest = Decimal::ZERO;
+    for month in 1..=MAX_PAYOFF_MONTHS {
+        if remaining <= Decimal::ZERO {
+            return Ok((month - 1, total_interest));
         }
+
+        let interest = (remaining * monthly_rate).round_dp(2);
+        total_interest += interest;
+        remaining = remaining + interest - monthly_payment.min(remaining + interest);
     }
 
-    let net_worth = total_assets - total_liabilities;
+    if remaining <= Decimal::ZERO {
+        return Ok((MAX_PAYOFF_MONTHS, total_interest));
+    }
 
-    Ok(AccountSummaryResponse {
-        total_assets,
-        total_liabilities,
-        net_worth,
-        account_count,
+    Err(FiscusError::field_validation(
+        "monthly_payment",
+        "insufficient_payment",
+        format!("The balance is not paid off within {MAX_PAYOFF_MONTHS} months"),
+    ))
+}
+
+/// Project when a liability account will be paid off with a fixed monthly payment
+///
+/// `annual_apr` is a fraction (e.g. `0.199` for 19.9%). The account balance is
+/// taken as the amount owed whatever its sign.
+#[tauri::command]
+pub async fn project_payoff(
+    account_id: String,
+    user_id: String,
+    monthly_payment: Decimal,
+    annual_apr: Decimal,
+    db: State<'_, Database>,
+) -> Result<PayoffProjectionResponse, FiscusError> {
+    authorize_command("project_payoff").await?;
+
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_amount(monthly_payment, false)?;
+
+    if annual_apr < Decimal::ZERO || annual_apr > Decimal::ONE {
+        return Err(FiscusError::field_validation(
+            "annual_apr",
+            "out_of_range",
+            "Annual APR must be between 0 and 1",
+        ));
+    }
+
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    let account_query = r#"
+        SELECT a.id, a.account_type_id, a.balance, at.is_asset
+        FROM accounts a
+        LEFT JOIN account_types at ON a.account_type_id = at.id
+        WHERE a.id = ?1 AND a.user_id = ?2
+    "#;
+
+    let account: HashMap<String, serde_json::Value> =
+        EncryptedDatabaseUtils::execute_encrypted_query(
+            &db,
+            account_query,
+            vec![
+                Value::String(account_id.clone()),
+                Value::String(user_id.clone()),
+            ],
+            &user_id,
+            "accounts",
+        )
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| FiscusError::NotFound("Account not found".to_string()))?;
+
+    if !is_liability_account(&account) {
+        return Err(FiscusError::field_validation(
+            "account_id",
+            "not_liability",
+            "Payoff projections are only available for liability accounts",
+        ));
+    }
+
+    let balance = parse_decimal_from_json(&account, "balance").abs();
+    let (months_to_payoff, total_interest) = simulate_payoff(balance, monthly_payment, annual_apr)?;
+
+    let payoff_date = chrono::Utc::now()
+        .date_naive()
+        .checked_add_months(chrono::Months::new(months_to_payoff))
+        .ok_or_else(|| FiscusError::Internal("Payoff date out of range".to_string()))?;
+
+    Ok(PayoffProjectionResponse {
+        account_id,
+        balance,
+        monthly_payment,
+        annual_apr,
+        months_to_payoff,
+        total_interest,
+        payoff_date,
     })
 }
+
+/// Recompute an account balance from its opening balance and the transactions since.
+///
+/// Voided and cancelled transactions are ignored. Transfer legs are stored with
+/// their signed amount (negative when money leaves the account).
+fn compute_balance_from_opening(
+    opening_balance: Decimal,
+    transactions: &[HashMap<String, serde_json::Value>],
+) -> Decimal {
+    transactions
+        .iter()
+        .filter(|tx| {
+            !matches!(
+                tx.get("status").and_then(|v| v.as_str()),
+                Some("voided") | Some("cancelled")
+            )
+        })
+        .fold(opening_balance, |balance, tx| {
+            let amount = parse_decimal_from_json(tx, "amount");
+            match tx.get("transaction_type").and_then(|v| v.as_str()) {
+                Some("income") => balance + amount,
+                Some("expense") => balance - amount,
+                Some("transfer") => balance + amount,
+                _ => balance,
+            }
+        })
+}
+
+/// Set an account's opening balance and recompute its current balance
+///
+/// The opening balance applies from the start of `as_of_date`; transactions
+/// dated on or after it are added on top to produce the current balance,
+/// the same calculation `repair_account_balance` uses, so any drift in the
+/// stored balance is corrected as well.
+#[tauri::command]
+pub async fn set_opening_balance(
+    account_id: String,
+    user_id: String,
+    amount: Decimal,
+    as_of_date: String,
+    db: State<'_, Database>,
+) -> Result<Account, FiscusError> {
+    authorize_command("set_opening_balance").await?;
+
+    // Validate input
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_amount(amount, true)?; // Liabilities may open negative
+    let as_of = Validator::validate_date(&as_of_date, "as_of_date")?;
+
+    // Validate ownership
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    with_transaction!(&*db, async {
+        let transactions = load_transactions_since(&db, &account_id, &user_id, Some(as_of)).await?;
+
+        let update_query = r#"
+            UPDATE accounts
+            SET opening_balance = ?1, opening_balance_date = ?2, balance = ?3, updated_at = ?4
+            WHERE id = ?5 AND user_id = ?6
+        "#;
+
+        let params_with_mapping = vec![
+            (
+                "opening_balance".to_string(),
+                Value::String(amount.to_string()),
+            ),
+            (
+                "opening_balance_date".to_string(),
+                Value::String(as_of.format("%Y-%m-%d").to_string()),
+            ),
+            (
+                "balance".to_string(),
+                Value::String(compute_balance_from_opening(amount, &transactions).to_string()),
+            ),
+            (
+                "updated_at".to_string(),
+                Value::String(chrono::Utc::now().to_rfc3339()),
+            ),
+            ("id".to_string(), Value::String(account_id.clone())),
+            ("user_id".to_string(), Value::String(user_id.clone())),
+        ];
+
+        let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            params_with_mapping,
+            &user_id,
+            "accounts",
+        )
+        .await?;
+
+        let affected_rows =
+            DatabaseUtils::execute_non_query(&db, update_query, encrypted_params).await?;
+
+        if affected_rows == 0 {
+            return Err(FiscusError::NotFound("Account not found".to_string()));
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    get_account_by_id(account_id, None, db).await
+}
+
+/// Compare an account's stored balance with its opening balance plus transactions
+///
+/// A non-zero `drift` means code paths that update the balance independently
+/// have diverged from the transaction history; see `repair_account_balance`.
+#[tauri::command]
+pub async fn audit_account_balance(
+    account_id: String,
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<BalanceAuditResponse, FiscusError> {
+    authorize_command("audit_account_balance").await?;
+
+    // Validate input
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    // Validate ownership
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    load_balance_audit(&db, &account_id, &user_id).await
+}
+
+/// Overwrite an account's stored balance with the balance implied by its transactions
+///
+/// The audit and the update run in one database transaction. The response
+/// reports the drift that was corrected.
+#[tauri::command]
+pub async fn repair_account_balance(
+    account_id: String,
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<BalanceAuditResponse, FiscusError> {
+    authorize_command("repair_account_balance").await?;
+
+    // Validate input
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    // Validate ownership
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    let audit = with_transaction!(&*db, async {
+        let mut audit = load_balance_audit(&db, &account_id, &user_id).await?;
+        if audit.drift.is_zero() {
+            return Ok::<BalanceAuditResponse, FiscusError>(audit);
+        }
+
+        let update_query = r#"
+            UPDATE accounts SET balance = ?1, updated_at = ?2
+            WHERE id = ?3 AND user_id = ?4
+        "#;
+        let params_with_mapping = vec![
+            (
+                "balance".to_string(),
+                Value::String(audit.expected_balance.to_string()),
+            ),
+            (
+                "updated_at".to_string(),
+                Value::String(chrono::Utc::now().to_rfc3339()),
+            ),
+            ("id".to_string(), Value::String(account_id.clone())),
+            ("user_id".to_string(), Value::String(user_id.clone())),
+        ];
+
+        let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            params_with_mapping,
+            &user_id,
+            "accounts",
+        )
+        .await?;
+
+        let affected_rows =
+            DatabaseUtils::execute_non_query(&db, update_query, encrypted_params).await?;
+        if affected_rows == 0 {
+            return Err(FiscusError::NotFound("Account not found".to_string()));
+        }
+
+        audit.repaired = true;
+        Ok::<BalanceAuditResponse, FiscusError>(audit)
+    })?;
+
+    Ok(audit)
+}
+
+/// Load an account and its transactions and audit the stored balance
+pub(crate) async fn load_balance_audit(
+    db: &Database,
+    account_id: &str,
+    user_id: &str,
+) -> FiscusResult<BalanceAuditResponse> {
+    let account_query = r#"
+        SELECT id, user_id, account_type_id, name, balance, opening_balance,
+               opening_balance_date, currency, account_number, is_active,
+               status, closing_date, default_category_id, default_payee,
+               created_at, updated_at
+        FROM accounts
+        WHERE id = ?1 AND user_id = ?2
+    "#;
+
+    let account: Account = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        account_query,
+        vec![
+            Value::String(account_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+        user_id,
+        "accounts",
+    )
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| FiscusError::NotFound("Account not found".to_string()))?;
+
+    // Reconcile against the opening balance and the transactions since its date
+    let transactions =
+        load_transactions_since(db, account_id, user_id, account.opening_balance_date).await?;
+
+    Ok(audit_balance(&account, &transactions))
+}
+
+/// Transactions on an account dated on or after `since`, every one when `None`
+async fn load_transactions_since(
+    db: &Database,
+    account_id: &str,
+    user_id: &str,
+    since: Option<NaiveDate>,
+) -> FiscusResult<Vec<HashMap<String, serde_json::Value>>> {
+    let transactions_query = r#"
+        SELECT id, amount, transaction_type, status
+        FROM transactions
+        WHERE account_id = ?1 AND user_id = ?2 AND date(transaction_date) >= ?3
+    "#;
+
+    EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        transactions_query,
+        vec![
+            Value::String(account_id.to_string()),
+            Value::String(user_id.to_string()),
+            Value::String(
+                since
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+            ),
+        ],
+        user_id,
+        "transactions",
+    )
+    .await
+}
+
+/// Compare `account.balance` with the balance recomputed from `transactions`
+fn audit_balance(
+    account: &Account,
+    transactions: &[HashMap<String, serde_json::Value>],
+) -> BalanceAuditResponse {
+    let expected_balance = compute_balance_from_opening(account.opening_balance, transactions);
+
+    BalanceAuditResponse {
+        account_id: account.id.clone(),
+        stored_balance: account.balance,
+        expected_balance,
+        drift: account.balance - expected_balance,
+        repaired: false,
+    }
+}
+
+/// Close an account with a reconciled final balance
+///
+/// If the stored balance differs from `final_balance`, an adjustment
+/// transaction dated `closing_date` makes up the difference. Unlike
+/// deactivating an account, closing it freezes its history: it accepts no
+/// new transactions, though everything already recorded stays readable.
+#[tauri::command]
+pub async fn close_account(
+    account_id: String,
+    user_id: String,
+    closing_date: String,
+    final_balance: Decimal,
+    db: State<'_, Database>,
+) -> Result<Account, FiscusError> {
+    authorize_command("close_account").await?;
+
+    // Validate input
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_amount(final_balance, true)?; // Liabilities may close negative
+    let closing = Validator::validate_date(&closing_date, "closing_date")?;
+    if closing > chrono::Utc::now().date_naive() {
+        return Err(FiscusError::field_validation(
+            "closing_date",
+            "future_date",
+            "Closing date cannot be in the future",
+        ));
+    }
+
+    // Validate ownership
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    let now = chrono::Utc::now();
+    let adjustment = with_transaction!(&*db, async {
+        let account = get_account_by_id(account_id.clone(), None, db.clone()).await?;
+        if account.status == AccountStatus::Closed {
+            return Err(FiscusError::Conflict(
+                "Account is already closed".to_string(),
+            ));
+        }
+
+        let later_query = r#"
+            SELECT COUNT(*) as count FROM transactions
+            WHERE account_id = ?1 AND user_id = ?2 AND date(transaction_date) > ?3
+            AND status NOT IN ('cancelled', 'voided')
+        "#;
+        let later: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+            &db,
+            later_query,
+            vec![
+                Value::String(account_id.clone()),
+                Value::String(user_id.clone()),
+                Value::String(closing.to_string()),
+            ],
+        )
+        .await?;
+        if later
+            .and_then(|row| row.get("count").and_then(|v| v.as_i64()))
+            .unwrap_or(0)
+            > 0
+        {
+            return Err(FiscusError::Conflict(
+                "Account has transactions dated after the closing date".to_string(),
+            ));
+        }
+
+        let adjustment = closing_adjustment(&account, closing, final_balance, now);
+        if let Some(ref transaction) = adjustment {
+            insert_generated_transaction(&db, transaction).await?;
+            // Post exactly what the adjustment records, not a figure from the earlier read
+            DatabaseUtils::adjust_account_balance(
+                &db,
+                &account_id,
+                balance_delta(transaction.amount, &transaction.transaction_type),
+            )
+            .await?;
+        }
+
+        let affected_rows = DatabaseUtils::execute_non_query(
+            &db,
+            r#"
+            UPDATE accounts SET status = ?1, closing_date = ?2, updated_at = ?3
+            WHERE id = ?4 AND user_id = ?5
+            "#,
+            vec![
+                Value::String(AccountStatus::Closed.to_string()),
+                Value::String(closing.to_string()),
+                Value::String(now.to_rfc3339()),
+                Value::String(account_id.clone()),
+                Value::String(user_id.clone()),
+            ],
+        )
+        .await?;
+        if affected_rows == 0 {
+            return Err(FiscusError::NotFound("Account not found".to_string()));
+        }
+
+        Ok::<Option<Transaction>, FiscusError>(adjustment)
+    })?;
+
+    if let Some(transaction) = adjustment {
+        events::publish([
+            TransactionEvent::TransactionCreated {
+                user_id: user_id.clone(),
+                transaction_id: transaction.id,
+                account_id: account_id.clone(),
+            },
+            TransactionEvent::BalanceChanged {
+                user_id: user_id.clone(),
+                account_id: account_id.clone(),
+            },
+        ]);
+    }
+
+    get_account_by_id(account_id, None, db).await
+}
+
+/// Transaction bringing `account` to `final_balance` on `closing_date`, if it differs
+fn closing_adjustment(
+    account: &Account,
+    closing_date: NaiveDate,
+    final_balance: Decimal,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<Transaction> {
+    let difference = final_balance - account.balance;
+    if difference.is_zero() {
+        return None;
+    }
+
+    let transaction_type = if difference > Decimal::ZERO {
+        TransactionType::Income
+    } else {
+        TransactionType::Expense
+    };
+
+    Some(Transaction {
+        id: Uuid::new_v4().to_string(),
+        user_id: account.user_id.clone(),
+        account_id: account.id.clone(),
+        category_id: None,
+        amount: difference.abs(),
+        description: "Closing balance adjustment".to_string(),
+        notes: None,
+        transaction_date: closing_date.and_time(chrono::NaiveTime::MIN).and_utc(),
+        transaction_type,
+        status: TransactionStatus::Completed,
+        reference_number: Some(format!("closing:{}", account.id)),
+        payee: None,
+        tags: None,
+        original_amount: None,
+        original_currency: None,
+        refunds_transaction_id: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Reject a new transaction on an account that has been closed
+pub(crate) async fn ensure_account_open(
+    db: &Database,
+    account_id: &str,
+    user_id: &str,
+    posting_date: NaiveDate,
+) -> FiscusResult<()> {
+    let row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        "SELECT status, closing_date FROM accounts WHERE id = ?1 AND user_id = ?2",
+        vec![
+            Value::String(account_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+    )
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(());
+    };
+    let status = row
+        .get("status")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    let closing_date = row
+        .get("closing_date")
+        .and_then(|v| v.as_str())
+        .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok());
+
+    check_account_open(status, closing_date, posting_date)
+}
+
+/// Closed accounts accept no new transactions, whatever their date
+///
+/// Backdated transactions are refused too, since they would change the
+/// final balance reconciled at closing.
+fn check_account_open(
+    status: AccountStatus,
+    closing_date: Option<NaiveDate>,
+    posting_date: NaiveDate,
+) -> FiscusResult<()> {
+    if status != AccountStatus::Closed {
+        return Ok(());
+    }
+
+    match closing_date {
+        Some(closed_on) if posting_date > closed_on => Err(FiscusError::Conflict(format!(
+            "Account was closed on {closed_on}; transactions after closing are not allowed"
+        ))),
+        Some(closed_on) => Err(FiscusError::Conflict(format!(
+            "Account was closed on {closed_on}; backdated transactions would change its reconciled final balance"
+        ))),
+        None => Err(FiscusError::Conflict("Account is closed".to_string())),
+    }
+}
+
+/// Category and payee new transactions on an account fall back to
+#[derive(Debug, Default)]
+pub(crate) struct AccountDefaults {
+    pub category_id: Option<String>,
+    pub payee: Option<String>,
+}
+
+/// Load the defaults configured on `account_id`; a missing account has none
+pub(crate) async fn load_account_defaults(
+    db: &Database,
+    account_id: &str,
+    user_id: &str,
+) -> FiscusResult<AccountDefaults> {
+    let rows: Vec<HashMap<String, Value>> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        "SELECT default_category_id, default_payee FROM accounts WHERE id = ?1 AND user_id = ?2",
+        vec![
+            Value::String(account_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+        user_id,
+        "accounts",
+    )
+    .await?;
+
+    let column = |name: &str| {
+        rows.first()
+            .and_then(|row| row.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    Ok(AccountDefaults {
+        category_id: column("default_category_id"),
+        payee: column("default_payee"),
+    })
+}
+
+/// Create a named group to organise accounts under
+#[tauri::command]
+pub async fn create_account_group(
+    request: CreateAccountGroupRequest,
+    db: State<'_, Database>,
+) -> Result<AccountGroup, FiscusError> {
+    authorize_command("create_account_group").await?;
+
+    let user_id = request.user_id.as_str();
+    let name = request.name.trim().to_string();
+    Validator::validate_string(&name, "name", 1, 100)?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let existing: Option<HashMap<String, serde_json::Value>> = DatabaseUtils::execute_query_single(
+        &db,
+        "SELECT id FROM account_groups WHERE user_id = ?1 AND name = ?2",
+        vec![Value::String(user_id.clone()), Value::String(name.clone())],
+    )
+    .await?;
+
+    if existing.is_some() {
+        return Err(FiscusError::Conflict(format!(
+            "An account group named '{name}' already exists"
+        )));
+    }
+
+    let now = chrono::Utc::now();
+    let group = AccountGroup {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        name,
+        created_at: now,
+        updated_at: now,
+    };
+
+    DatabaseUtils::execute_non_query(
+        &db,
+        r#"
+        INSERT INTO account_groups (id, user_id, name, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+        vec![
+            Value::String(group.id.clone()),
+            Value::String(group.user_id.clone()),
+            Value::String(group.name.clone()),
+            Value::String(now.to_rfc3339()),
+            Value::String(now.to_rfc3339()),
+        ],
+    )
+    .await?;
+
+    Ok(group)
+}
+
+/// Move an account into a group, or out of any group when `group_id` is `None`
+///
+/// The account and the group must both belong to `user_id`.
+#[tauri::command]
+pub async fn assign_account_to_group(
+    account_id: String,
+    user_id: String,
+    group_id: Option<String>,
+    db: State<'_, Database>,
+) -> Result<Account, FiscusError> {
+    authorize_command("assign_account_to_group").await?;
+
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    if let Some(group_id) = &group_id {
+        Validator::validate_uuid(group_id, "group_id")?;
+    }
+    authorize_user(&user_id).await?;
+
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+    if let Some(group_id) = &group_id {
+        fetch_account_group(&db, &user_id, group_id).await?;
+    }
+
+    DatabaseUtils::execute_non_query(
+        &db,
+        "UPDATE accounts SET group_id = ?1, updated_at = ?2 WHERE id = ?3 AND user_id = ?4",
+        vec![
+            group_id.map(Value::String).unwrap_or(Value::Null),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+            Value::String(account_id.clone()),
+            Value::String(user_id.clone()),
+        ],
+    )
+    .await?;
+
+    get_account_by_id(account_id, None, db).await
+}
+
+/// Get a user's accounts nested under their groups
+///
+/// Groups are ordered by name and include empty groups; accounts without a
+/// group are returned under `ungrouped`.
+#[tauri::command]
+pub async fn get_accounts_grouped(
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<GroupedAccountsResponse, FiscusError> {
+    authorize_command("get_accounts_grouped").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let groups: Vec<AccountGroup> = DatabaseUtils::execute_query(
+        &db,
+        r#"
+        SELECT id, user_id, name, created_at, updated_at
+        FROM account_groups
+        WHERE user_id = ?1
+        ORDER BY name
+        "#,
+        vec![Value::String(user_id.clone())],
+    )
+    .await?;
+
+    let mut accounts: Vec<Account> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        r#"
+        SELECT id, user_id, account_type_id, name, balance, opening_balance,
+               opening_balance_date, currency, account_number, is_active, group_id,
+               status, closing_date, default_category_id, default_payee,
+               created_at, updated_at
+        FROM accounts
+        WHERE user_id = ?1
+        ORDER BY name
+        "#,
+        vec![Value::String(user_id.clone())],
+        &user_id,
+        "accounts",
+    )
+    .await?;
+    apply_account_number_visibility(&mut accounts, false);
+
+    Ok(group_accounts(groups, accounts))
+}
+
+/// Look up an account group owned by `user_id`
+async fn fetch_account_group(
+    db: &Database,
+    user_id: &str,
+    group_id: &str,
+) -> FiscusResult<AccountGroup> {
+    let group: Option<AccountGroup> = DatabaseUtils::execute_query_single(
+        db,
+        r#"
+        SELECT id, user_id, name, created_at, updated_at
+        FROM account_groups
+        WHERE id = ?1 AND user_id = ?2
+        "#,
+        vec![
+            Value::String(group_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+    )
+    .await?;
+
+    group.ok_or_else(|| FiscusError::NotFound("Account group not found".to_string()))
+}
+
+/// Nest accounts under their groups, keeping group order
+///
+/// Accounts whose group is missing (e.g. deleted) are treated as ungrouped.
+fn group_accounts(groups: Vec<AccountGroup>, accounts: Vec<Account>) -> GroupedAccountsResponse {
+    let mut grouped: Vec<AccountGroupWithAccounts> = groups
+        .into_iter()
+        .map(|group| AccountGroupWithAccounts {
+            group,
+            accounts: Vec::new(),
+        })
+        .collect();
+    let mut ungrouped = Vec::new();
+
+    for account in accounts {
+        let entry = account
+            .group_id
+            .as_deref()
+            .and_then(|group_id| grouped.iter_mut().find(|entry| entry.group.id == group_id));
+
+        match entry {
+            Some(entry) => entry.accounts.push(account),
+            None => ungrouped.push(account),
+        }
+    }
+
+    GroupedAccountsResponse {
+        groups: grouped,
+        ungrouped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+    use serde_json::json;
+
+    fn tx(amount: &str, transaction_type: &str, status: &str) -> HashMap<String, Value> {
+        let mut row = HashMap::new();
+        row.insert("amount".to_string(), json!(amount));
+        row.insert("transaction_type".to_string(), json!(transaction_type));
+        row.insert("status".to_string(), json!(status));
+        row
+    }
+
+    #[test]
+    fn test_payoff_when_payment_covers_interest() {
+        // 1000 at 12% APR (1% a month) paid at 100/month
+        let (months, interest) = simulate_payoff(
+            Decimal::new(1000, 0),
+            Decimal::new(100, 0),
+            Decimal::new(12, 2),
+        )
+        .unwrap();
+
+        assert_eq!(months, 11);
+        assert_eq!(interest, Decimal::new(5898, 2));
+    }
+
+    #[test]
+    fn test_payoff_without_interest_is_linear() {
+        let (months, interest) =
+            simulate_payoff(Decimal::new(1000, 0), Decimal::new(250, 0), Decimal::ZERO).unwrap();
+
+        assert_eq!(months, 4);
+        assert_eq!(interest, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_payoff_rejects_payment_below_interest() {
+        // 1% of 10000 is 100 a month, so a 100 payment never reduces the debt
+        let result = simulate_payoff(
+            Decimal::new(10000, 0),
+            Decimal::new(100, 0),
+            Decimal::new(12, 2),
+        );
+
+        assert!(matches!(
+            result,
+            Err(FiscusError::FieldValidation { ref code, .. }) if code == "insufficient_payment"
+        ));
+    }
+
+    #[test]
+    fn test_clone_copies_structure_with_zero_balance() {
+        let mut source = TestUtils::create_test_account("user");
+        source.currency = "EUR".to_string();
+        source.balance = Decimal::new(123456, 2);
+        source.opening_balance = Decimal::new(50000, 2);
+        source.account_number = Some("****1234".to_string());
+        let now = chrono::Utc::now();
+
+        let clone = cloned_account(&source, "Joint Savings", now);
+
+        assert_ne!(clone.id, source.id);
+        assert_eq!(clone.user_id, source.user_id);
+        assert_eq!(clone.account_type_id, source.account_type_id);
+        assert_eq!(clone.currency, "EUR");
+        assert_eq!(clone.name, "Joint Savings");
+        assert_eq!(clone.balance, Decimal::ZERO);
+        assert_eq!(clone.opening_balance, Decimal::ZERO);
+        assert_eq!(clone.account_number, None);
+        assert_eq!(clone.created_at, now);
+        assert_eq!(clone.updated_at, now);
+    }
+
+    #[test]
+    fn test_clone_copies_settings_and_inserts_every_field() {
+        let mut source = TestUtils::create_test_account("user");
+        source.group_id = Some("group-1".to_string());
+        source.default_category_id = Some("category-1".to_string());
+        source.default_payee = Some("Landlord".to_string());
+        source.status = AccountStatus::Closed;
+        source.closing_date = Some(date("2024-06-30"));
+        source.opening_balance_date = Some(date("2024-01-01"));
+
+        let clone = cloned_account(&source, "Copy", chrono::Utc::now());
+        assert_eq!(clone.group_id.as_deref(), Some("group-1"));
+        assert_eq!(clone.default_category_id.as_deref(), Some("category-1"));
+        assert_eq!(clone.default_payee.as_deref(), Some("Landlord"));
+        assert_eq!(clone.status, AccountStatus::Active);
+        assert_eq!(clone.closing_date, None);
+        assert_eq!(clone.opening_balance_date, None);
+
+        let params: HashMap<String, Value> = account_insert_params(&clone).into_iter().collect();
+        assert_eq!(params["group_id"], Value::String("group-1".to_string()));
+        assert_eq!(
+            params["default_category_id"],
+            Value::String("category-1".to_string())
+        );
+        assert_eq!(
+            params["default_payee"],
+            Value::String("Landlord".to_string())
+        );
+        assert_eq!(params["status"], Value::String("active".to_string()));
+        assert_eq!(params["opening_balance"], Value::String("0".to_string()));
+
+        // One placeholder per parameter, naming each column once
+        assert_eq!(
+            CLONE_ACCOUNT_INSERT.matches("?").count(),
+            account_insert_params(&clone).len()
+        );
+        for column in params.keys() {
+            assert!(CLONE_ACCOUNT_INSERT.contains(column.as_str()), "{column}");
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_closed_account_rejects_new_transactions() {
+        let closed_on = Some(date("2024-06-30"));
+
+        assert!(check_account_open(AccountStatus::Active, None, date("2024-07-01")).is_ok());
+        assert!(matches!(
+            check_account_open(AccountStatus::Closed, closed_on, date("2024-07-01")),
+            Err(FiscusError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn test_closed_account_rejects_backdated_transactions() {
+        let closed_on = Some(date("2024-06-30"));
+
+        for posting_date in ["2024-06-30", "2024-01-15"] {
+            assert!(matches!(
+                check_account_open(AccountStatus::Closed, closed_on, date(posting_date)),
+                Err(FiscusError::Conflict(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_closing_adjustment_reaches_final_balance() {
+        let mut account = TestUtils::create_test_account("user");
+        account.balance = Decimal::new(12_050, 2);
+        let now = chrono::Utc::now();
+
+        for final_balance in [Decimal::new(10_000, 2), Decimal::new(15_000, 2)] {
+            let adjustment =
+                closing_adjustment(&account, date("2024-06-30"), final_balance, now).unwrap();
+            assert_ne!(adjustment.transaction_type, TransactionType::Transfer);
+            let delta = balance_delta(adjustment.amount, &adjustment.transaction_type);
+
+            assert_eq!(account.balance + delta, final_balance);
+            assert_eq!(adjustment.transaction_date.date_naive(), date("2024-06-30"));
+        }
+
+        assert!(closing_adjustment(&account, date("2024-06-30"), account.balance, now).is_none());
+    }
+
+    fn numbered_account(number: &str) -> Account {
+        let mut account = TestUtils::create_test_account("user");
+        account.account_number = Some(number.to_string());
+        account
+    }
+
+    #[test]
+    fn test_account_numbers_are_masked_by_default() {
+        let mut accounts = vec![numbered_account("12-3456-6789"), numbered_account("42")];
+        accounts.push(TestUtils::create_test_account("user"));
+
+        let reveal = reveal_permitted(false, None);
+        apply_account_number_visibility(&mut accounts, reveal);
+
+        assert_eq!(accounts[0].account_number.as_deref(), Some("****6789"));
+        assert_eq!(accounts[1].account_number.as_deref(), Some("****"));
+        assert_eq!(accounts[2].account_number, None);
+    }
+
+    #[test]
+    fn test_account_number_revealed_only_with_permission() {
+        let without_permission = SecurityContext::new("user".to_string());
+        let mut with_permission = SecurityContext::new("user".to_string());
+        with_permission
+            .permissions
+            .push(PERMISSION_REVEAL_ACCOUNT_NUMBER.to_string());
+
+        let mut denied = vec![numbered_account("12-3456-6789")];
+        apply_account_number_visibility(
+            &mut denied,
+            reveal_permitted(true, Some(&without_permission)),
+        );
+        assert_eq!(denied[0].account_number.as_deref(), Some("****6789"));
+
+        let mut granted = vec![numbered_account("12-3456-6789")];
+        apply_account_number_visibility(
+            &mut granted,
+            reveal_permitted(true, Some(&with_permission)),
+        );
+        assert_eq!(granted[0].account_number.as_deref(), Some("12-3456-6789"));
+
+        // Without a session a reveal request is never granted
+        let mut no_session = vec![numbered_account("12-3456-6789")];
+        apply_account_number_visibility(&mut no_session, reveal_permitted(true, None));
+        assert_eq!(no_session[0].account_number.as_deref(), Some("****6789"));
+
+        // The owner's own session may reveal, an observer's may not
+        let owner = SecurityContext::owner("user".to_string());
+        let observer = SecurityContext::read_only("user".to_string());
+        assert!(reveal_permitted(true, Some(&owner)));
+        assert!(!reveal_permitted(true, Some(&observer)));
+    }
+
+    #[test]
+    fn test_opening_balance_with_expense() {
+        let transactions = vec![tx("100", "expense", "completed")];
+        let balance = compute_balance_from_opening(Decimal::new(500, 0), &transactions);
+        assert_eq!(balance, Decimal::new(400, 0));
+    }
+
+    #[test]
+    fn test_balance_audit_reports_exact_drift() {
+        let mut account = TestUtils::create_test_account("user");
+        account.opening_balance = Decimal::new(500, 0);
+        let transactions = vec![
+            tx("100", "expense", "completed"),
+            tx("250.50", "income", "completed"),
+            tx("999", "expense", "cancelled"),
+        ];
+
+        // Stored balance is 12.34 too high
+        account.balance = Decimal::new(66284, 2);
+        let audit = audit_balance(&account, &transactions);
+        assert_eq!(audit.expected_balance, Decimal::new(65050, 2));
+        assert_eq!(audit.drift, Decimal::new(1234, 2));
+        assert!(!audit.repaired);
+
+        // Writing back the expected balance leaves no drift
+        account.balance = audit.expected_balance;
+        let audit = audit_balance(&account, &transactions);
+        assert!(audit.drift.is_zero());
+    }
+
+    #[test]
+    fn test_balance_audit_reports_negative_drift() {
+        let mut account = TestUtils::create_test_account("user");
+        account.opening_balance = Decimal::ZERO;
+        account.balance = Decimal::new(-40, 0);
+
+        let audit = audit_balance(&account, &[tx("60", "income", "completed")]);
+        assert_eq!(audit.expected_balance, Decimal::new(60, 0));
+        assert_eq!(audit.drift, Decimal::new(-100, 0));
+    }
+
+    #[test]
+    fn test_changing_opening_balance_recomputes() {
+        let transactions = vec![
+            tx("100", "expense", "completed"),
+            tx("250.50", "income", "completed"),
+            tx("-50", "transfer", "completed"),
+            tx("999", "expense", "voided"),
+        ];
+
+        let balance = compute_balance_from_opening(Decimal::new(500, 0), &transactions);
+        assert_eq!(balance, Decimal::new(60050, 2));
+
+        let balance = compute_balance_from_opening(Decimal::new(1000, 0), &transactions);
+        assert_eq!(balance, Decimal::new(110050, 2));
+    }
+
+    #[test]
+    fn test_opening_balance_change_reconciles_without_drift() {
+        let since_opening = vec![tx("100", "expense", "completed")];
+
+        let mut account = TestUtils::create_test_account("user");
+        account.opening_balance = Decimal::new(500, 0);
+        account.balance = compute_balance_from_opening(account.opening_balance, &since_opening);
+
+        assert_eq!(account.balance, Decimal::new(400, 0));
+        assert!(audit_balance(&account, &since_opening).drift.is_zero());
+    }
+
+    #[test]
+    fn test_opening_balance_change_corrects_existing_drift() {
+        let transactions = vec![tx("100", "expense", "completed")];
+        let mut account = TestUtils::create_test_account("user");
+        account.opening_balance = Decimal::new(500, 0);
+        account.balance = Decimal::new(410, 0); // 10 of drift
+        assert_eq!(
+            audit_balance(&account, &transactions).drift,
+            Decimal::new(10, 0)
+        );
+
+        account.opening_balance = Decimal::new(800, 0);
+        account.balance = compute_balance_from_opening(account.opening_balance, &transactions);
+
+        assert_eq!(account.balance, Decimal::new(700, 0));
+        assert!(audit_balance(&account, &transactions).drift.is_zero());
+    }
+
+    fn account(
+        account_type_id: &str,
+        balance: &str,
+        is_asset: Option<Value>,
+    ) -> HashMap<String, Value> {
+        let mut row = HashMap::new();
+        row.insert("account_type_id".to_string(), json!(account_type_id));
+        row.insert("balance".to_string(), json!(balance));
+        if let Some(is_asset) = is_asset {
+            row.insert("is_asset".to_string(), is_asset);
+        }
+        row
+    }
+
+    #[test]
+    fn test_net_worth_subtracts_credit_card_balance() {
+        let accounts = vec![
+            account("checking", "1500", Some(json!(1))),
+            account("credit_card", "300", Some(json!(0))),
+        ];
+
+        let (assets, liabilities) = bucket_account_balances(&accounts);
+        assert_eq!(assets, Decimal::new(1500, 0));
+        assert_eq!(liabilities, Decimal::new(300, 0));
+        assert_eq!(assets - liabilities, Decimal::new(1200, 0));
+    }
+
+    #[test]
+    fn test_negative_liability_balance_counts_by_magnitude() {
+        let accounts = vec![
+            account("checking", "1500", Some(json!(true))),
+            account("credit_card", "-300", Some(json!(false))),
+        ];
+
+        let (assets, liabilities) = bucket_account_balances(&accounts);
+        assert_eq!(assets - liabilities, Decimal::new(1200, 0));
+    }
+
+    #[test]
+    fn test_liability_falls_back_to_account_type_id() {
+        assert!(is_liability_account(&account("loan", "0", None)));
+        assert!(is_liability_account(&account("credit_card", "0", None)));
+        assert!(!is_liability_account(&account("savings", "0", None)));
+    }
+
+    fn update_request(json: &str) -> UpdateAccountRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_account_update_skips_absent_account_number() {
+        let (fields, params) =
+            build_account_update_fields(&update_request(r#"{"name": "Main"}"#)).unwrap();
+        assert_eq!(fields, vec!["`name` = ?1".to_string()]);
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_account_update_clears_null_account_number() {
+        let (fields, params) =
+            build_account_update_fields(&update_request(r#"{"account_number": null}"#)).unwrap();
+        assert_eq!(fields, vec!["`account_number` = NULL".to_string()]);
+        assert!(params.is_empty());
+
+        let (fields, params) =
+            build_account_update_fields(&update_request(r#"{"account_number": "1234"}"#)).unwrap();
+        assert_eq!(fields, vec!["`account_number` = ?1".to_string()]);
+        assert_eq!(params[0].0, "account_number");
+    }
+
+    #[test]
+    fn test_account_update_sets_and_clears_defaults() {
+        let (fields, params) = build_account_update_fields(&update_request(
+            r#"{"default_category_id": "550e8400-e29b-41d4-a716-446655440002", "default_payee": null}"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                "`default_category_id` = ?1".to_string(),
+                "`default_payee` = NULL".to_string()
+            ]
+        );
+        assert_eq!(params[0].0, "default_category_id");
+
+        assert!(build_account_update_fields(&update_request(
+            r#"{"default_category_id": "groceries"}"#
+        ))
+        .is_err());
+    }
+
+    mod groups {
+        use super::*;
+
+        fn group(user_id: &str, name: &str) -> AccountGroup {
+            let now = chrono::Utc::now();
+            AccountGroup {
+                id: Uuid::new_v4().to_string(),
+                user_id: user_id.to_string(),
+                name: name.to_string(),
+                created_at: now,
+                updated_at: now,
+            }
+        }
+
+        fn grouped_row(balance: &str, group_id: Option<&str>) -> HashMap<String, Value> {
+            let mut row = account("checking", balance, Some(json!(1)));
+            row.insert(
+                "group_id".to_string(),
+                group_id.map(|id| json!(id)).unwrap_or(Value::Null),
+            );
+            row
+        }
+
+        #[tokio::test]
+        async fn test_assigning_another_users_account_is_refused() {
+            let test_db = crate::test_database::TestDatabase::in_memory()
+                .await
+                .unwrap();
+            let app = test_db.app();
+            let owner = test_db.seed_user("group-owner").await.unwrap();
+            let account = test_db
+                .seed_account(&owner.id, Decimal::new(100000, 2))
+                .await
+                .unwrap();
+            let _session =
+                crate::test_database::sign_in("660e8400-e29b-41d4-a716-446655440001").await;
+
+            let result =
+                assign_account_to_group(account.id, owner.id, None, tauri::Manager::state(&app))
+                    .await;
+            assert!(matches!(result, Err(FiscusError::Authorization(_))));
+        }
+
+        #[test]
+        fn test_assigned_account_moves_under_its_group() {
+            let retirement = group("user", "Retirement");
+            let spending = group("user", "Daily Spending");
+            let mut ira = TestUtils::create_test_account("user");
+            let checking = TestUtils::create_test_account("user");
+
+            let before = group_accounts(
+                vec![retirement.clone(), spending.clone()],
+                vec![ira.clone(), checking.clone()],
+            );
+            assert!(before.groups.iter().all(|g| g.accounts.is_empty()));
+            assert_eq!(before.ungrouped.len(), 2);
+
+            ira.group_id = Some(retirement.id.clone());
+            let after = group_accounts(
+                vec![retirement.clone(), spending],
+                vec![ira.clone(), checking],
+            );
+
+            assert_eq!(after.groups[0].group.id, retirement.id);
+            assert_eq!(after.groups[0].accounts.len(), 1);
+            assert_eq!(after.groups[0].accounts[0].id, ira.id);
+            assert!(after.groups[1].accounts.is_empty());
+            assert_eq!(after.ungrouped.len(), 1);
+            assert!(after.ungrouped.iter().all(|a| a.id != ira.id));
+        }
+
+        #[test]
+        fn test_account_in_unknown_group_is_ungrouped() {
+            let mut account = TestUtils::create_test_account("user");
+            account.group_id = Some(Uuid::new_v4().to_string());
+
+            let grouped = group_accounts(vec![group("user", "Retirement")], vec![account]);
+
+            assert!(grouped.groups[0].accounts.is_empty());
+            assert_eq!(grouped.ungrouped.len(), 1);
+        }
+
+        #[test]
+        fn test_summary_filtered_by_group_counts_only_that_group() {
+            let retirement = Uuid::new_v4().to_string();
+            let spending = Uuid::new_v4().to_string();
+            let rows = vec![
+                grouped_row("10000.00", Some(&retirement)),
+                grouped_row("2500.00", Some(&retirement)),
+                grouped_row("800.00", Some(&spending)),
+                grouped_row("50.00", None),
+            ];
+
+            let summary = summarize_accounts(&rows, Some(&retirement));
+            assert_eq!(summary.account_count, 2);
+            assert_eq!(summary.total_assets, Decimal::new(1250000, 2));
+            assert_eq!(summary.net_worth, Decimal::new(1250000, 2));
+
+            let summary = summarize_accounts(&rows, None);
+            assert_eq!(summary.account_count, 4);
+            assert_eq!(summary.total_assets, Decimal::new(1335000, 2));
+        }
+    }
+}
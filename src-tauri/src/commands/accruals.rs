@@ -0,0 +1,631 @@
+use chrono::{Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    commands::{
+        currencies::currency_minor_units,
+        transactions::{amount_minor_value, store_amount_minor_units},
+    },
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
+    dto::CreateAccountAccrualRequest,
+    error::{FiscusError, FiscusResult, Validator},
+    models::{AccountAccrual, AccrualKind, Transaction, TransactionStatus, TransactionType},
+    security::authorize_command,
+    services::events::{self, TransactionEvent},
+    with_transaction,
+};
+
+/// Define a periodic interest or fee accrual on an account
+///
+/// Nothing is posted until `apply_accruals` runs; the first period falls due
+/// on `start_date`.
+#[tauri::command]
+pub async fn create_account_accrual(
+    request: CreateAccountAccrualRequest,
+    db: State<'_, Database>,
+) -> Result<AccountAccrual, FiscusError> {
+    authorize_command("create_account_accrual").await?;
+
+    let user_id = request.user_id.as_str();
+    Validator::validate_uuid(&request.account_id, "account_id")?;
+    Validator::validate_string(&request.name, "name", 1, 100)?;
+    if let Some(category_id) = &request.category_id {
+        Validator::validate_uuid(category_id, "category_id")?;
+    }
+    let start_date = Validator::validate_date(&request.start_date, "start_date")?;
+    validate_accrual_terms(&request.kind, request.amount, request.annual_rate)?;
+
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+    DatabaseUtils::validate_account_ownership(&db, &request.account_id, &user_id).await?;
+    if let Some(category_id) = &request.category_id {
+        DatabaseUtils::validate_category_ownership(&db, category_id, &user_id).await?;
+    }
+
+    let now = Utc::now();
+    let accrual = AccountAccrual {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        account_id: request.account_id,
+        name: request.name,
+        kind: request.kind,
+        amount: request.amount,
+        annual_rate: request.annual_rate,
+        frequency: request.frequency,
+        category_id: request.category_id,
+        start_date,
+        last_applied: None,
+        is_active: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let optional_decimal = |value: Option<Decimal>| {
+        value
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null)
+    };
+    let params_with_mapping = vec![
+        ("id".to_string(), Value::String(accrual.id.clone())),
+        (
+            "user_id".to_string(),
+            Value::String(accrual.user_id.clone()),
+        ),
+        (
+            "account_id".to_string(),
+            Value::String(accrual.account_id.clone()),
+        ),
+        ("name".to_string(), Value::String(accrual.name.clone())),
+        ("kind".to_string(), Value::String(accrual.kind.to_string())),
+        ("amount".to_string(), optional_decimal(accrual.amount)),
+        (
+            "annual_rate".to_string(),
+            optional_decimal(accrual.annual_rate),
+        ),
+        (
+            "frequency".to_string(),
+            Value::String(accrual.frequency.to_string()),
+        ),
+        (
+            "category_id".to_string(),
+            accrual
+                .category_id
+                .as_ref()
+                .map(|id| Value::String(id.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "start_date".to_string(),
+            Value::String(accrual.start_date.to_string()),
+        ),
+        ("created_at".to_string(), Value::String(now.to_rfc3339())),
+        ("updated_at".to_string(), Value::String(now.to_rfc3339())),
+    ];
+
+    let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+        params_with_mapping,
+        &accrual.user_id,
+        "account_accruals",
+    )
+    .await?;
+
+    DatabaseUtils::execute_non_query(
+        &db,
+        r#"
+        INSERT INTO account_accruals (
+            id, user_id, account_id, name, kind, amount, annual_rate, frequency,
+            category_id, start_date, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        "#,
+        encrypted_params,
+    )
+    .await?;
+
+    Ok(accrual)
+}
+
+/// Post every accrual period that fell due on or before `as_of`
+///
+/// Each due period becomes an income or expense transaction dated on its due
+/// date, and each accrual's `last_applied` marker moves to its latest posted
+/// date, so running this again for the same date posts nothing. Interest is
+/// computed on the account balance as it stands, compounding across periods
+/// applied in the same run; each account's balance is then updated once.
+#[tauri::command]
+pub async fn apply_accruals(
+    user_id: String,
+    as_of: String,
+    db: State<'_, Database>,
+) -> Result<Vec<Transaction>, FiscusError> {
+    authorize_command("apply_accruals").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    let as_of = Validator::validate_date(&as_of, "as_of")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let accruals: Vec<AccountAccrual> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        r#"
+        SELECT id, user_id, account_id, name, kind, amount, annual_rate, frequency,
+               category_id, start_date, last_applied, is_active, created_at, updated_at
+        FROM account_accruals
+        WHERE user_id = ?1 AND is_active = 1
+        ORDER BY start_date, id
+        "#,
+        vec![Value::String(user_id.clone())],
+        &user_id,
+        "account_accruals",
+    )
+    .await?;
+
+    let currency_rows: Vec<HashMap<String, Value>> = DatabaseUtils::execute_query(
+        &db,
+        "SELECT id, currency FROM accounts WHERE user_id = ?1",
+        vec![Value::String(user_id.clone())],
+    )
+    .await?;
+    let currencies: HashMap<&str, &str> = currency_rows
+        .iter()
+        .filter_map(|row| Some((row.get("id")?.as_str()?, row.get("currency")?.as_str()?)))
+        .collect();
+
+    let mut accruals_by_account: BTreeMap<String, Vec<AccountAccrual>> = BTreeMap::new();
+    for accrual in accruals {
+        accruals_by_account
+            .entry(accrual.account_id.clone())
+            .or_default()
+            .push(accrual);
+    }
+
+    // Work out every posting before writing anything
+    let now = Utc::now();
+    let mut runs = Vec::new();
+    for (account_id, accruals) in accruals_by_account {
+        let currency = currencies
+            .get(account_id.as_str())
+            .copied()
+            .unwrap_or("USD");
+        let minor_units = currency_minor_units(currency);
+        let opening_balance = DatabaseUtils::get_account_balance(&db, &account_id).await?;
+
+        let mut balance = opening_balance;
+        let mut transactions = Vec::new();
+        let mut markers = Vec::new();
+        for accrual in &accruals {
+            let due = due_dates(accrual, as_of);
+            let Some(&last_due) = due.last() else {
+                continue;
+            };
+
+            for (date, delta) in
+                due.iter()
+                    .zip(accrual_deltas(accrual, balance, due.len(), minor_units))
+            {
+                balance += delta;
+                transactions.extend(accrual_transaction(accrual, *date, delta, now));
+            }
+            markers.push((accrual.id.clone(), last_due));
+        }
+
+        if !markers.is_empty() {
+            runs.push((account_id, balance - opening_balance, transactions, markers));
+        }
+    }
+
+    with_transaction!(&*db, async {
+        for (account_id, delta, transactions, markers) in &runs {
+            for transaction in transactions {
+                insert_generated_transaction(&db, transaction).await?;
+            }
+
+            for (accrual_id, last_due) in markers {
+                DatabaseUtils::execute_non_query(
+                    &db,
+                    "UPDATE account_accruals SET last_applied = ?1, updated_at = ?2 WHERE id = ?3",
+                    vec![
+                        Value::String(last_due.to_string()),
+                        Value::String(now.to_rfc3339()),
+                        Value::String(accrual_id.clone()),
+                    ],
+                )
+                .await?;
+            }
+
+            if !delta.is_zero() {
+                DatabaseUtils::adjust_account_balance(&db, account_id, *delta).await?;
+            }
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    let mut change_events = Vec::new();
+    for (account_id, delta, transactions, _) in &runs {
+        change_events.extend(transactions.iter().map(|transaction| {
+            TransactionEvent::TransactionCreated {
+                user_id: user_id.clone(),
+                transaction_id: transaction.id.clone(),
+                account_id: account_id.clone(),
+            }
+        }));
+        if !delta.is_zero() {
+            change_events.push(TransactionEvent::BalanceChanged {
+                user_id: user_id.clone(),
+                account_id: account_id.clone(),
+            });
+        }
+    }
+    events::publish(change_events);
+
+    let transactions: Vec<Transaction> = runs
+        .into_iter()
+        .flat_map(|(_, _, transactions, _)| transactions)
+        .collect();
+    info!(
+        user_id = %user_id,
+        as_of = %as_of,
+        transaction_count = transactions.len(),
+        "Applied account accruals"
+    );
+
+    Ok(transactions)
+}
+
+/// Check that an accrual has exactly the terms its kind needs
+fn validate_accrual_terms(
+    kind: &AccrualKind,
+    amount: Option<Decimal>,
+    annual_rate: Option<Decimal>,
+) -> FiscusResult<()> {
+    match kind {
+        AccrualKind::Fee => {
+            let amount = amount.ok_or_else(|| {
+                FiscusError::field_validation("amount", "required", "Fees need an amount")
+            })?;
+            if amount <= Decimal::ZERO {
+                return Err(FiscusError::field_validation(
+                    "amount",
+                    "out_of_range",
+                    "Fee amount must be positive",
+                ));
+            }
+            if annual_rate.is_some() {
+                return Err(FiscusError::field_validation(
+                    "annual_rate",
+                    "invalid_value",
+                    "Fees are a flat amount and take no annual_rate",
+                ));
+            }
+        }
+        AccrualKind::Interest => {
+            let annual_rate = annual_rate.ok_or_else(|| {
+                FiscusError::field_validation(
+                    "annual_rate",
+                    "required",
+                    "Interest needs an annual_rate",
+                )
+            })?;
+            if annual_rate.is_zero() || annual_rate.abs() > Decimal::ONE {
+                return Err(FiscusError::field_validation(
+                    "annual_rate",
+                    "out_of_range",
+                    "Annual rate must be non-zero and between -1 and 1",
+                ));
+            }
+            if amount.is_some() {
+                return Err(FiscusError::field_validation(
+                    "amount",
+                    "invalid_value",
+                    "Interest is a rate and takes no amount",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Due dates after the accrual's last application, up to and including `as_of`
+///
+/// Dates step from `start_date` by whole periods, so an accrual starting on the
+/// 31st falls on the last day of shorter months without drifting earlier.
+pub(crate) fn due_dates(accrual: &AccountAccrual, as_of: NaiveDate) -> Vec<NaiveDate> {
+    let period = accrual.frequency.months();
+
+    (0u32..)
+        .map_while(|n| {
+            accrual
+                .start_date
+                .checked_add_months(Months::new(n.checked_mul(period)?))
+        })
+        .take_while(|date| *date <= as_of)
+        .filter(|date| !matches!(accrual.last_applied, Some(last) if *date <= last))
+        .collect()
+}
+
+/// Signed balance change for each of `periods` consecutive due periods
+///
+/// Fees charge their flat amount. Interest applies the periodic share of the
+/// annual rate to the running balance, rounded to the currency's minor units.
+pub(crate) fn accrual_deltas(
+    accrual: &AccountAccrual,
+    balance: Decimal,
+    periods: usize,
+    minor_units: u32,
+) -> Vec<Decimal> {
+    let mut balance = balance;
+
+    (0..periods)
+        .map(|_| {
+            let delta = match accrual.kind {
+                AccrualKind::Fee => -accrual.amount.unwrap_or_default(),
+                AccrualKind::Interest => {
+                    let periodic_rate = accrual.annual_rate.unwrap_or_default()
+                        / Decimal::from(accrual.frequency.periods_per_year());
+                    (balance * periodic_rate).round_dp(minor_units)
+                }
+            };
+            balance += delta;
+            delta
+        })
+        .collect()
+}
+
+/// Transaction posting one period of an accrual; a zero delta posts nothing
+fn accrual_transaction(
+    accrual: &AccountAccrual,
+    due_date: NaiveDate,
+    delta: Decimal,
+    now: chrono::DateTime<Utc>,
+) -> Option<Transaction> {
+    if delta.is_zero() {
+        return None;
+    }
+
+    let transaction_type = if delta > Decimal::ZERO {
+        TransactionType::Income
+    } else {
+        TransactionType::Expense
+    };
+
+    Some(Transaction {
+        id: Uuid::new_v4().to_string(),
+        user_id: accrual.user_id.clone(),
+        account_id: accrual.account_id.clone(),
+        category_id: accrual.category_id.clone(),
+        amount: delta.abs(),
+        description: accrual.name.clone(),
+        notes: None,
+        transaction_date: due_date.and_time(chrono::NaiveTime::MIN).and_utc(),
+        transaction_type,
+        status: TransactionStatus::Completed,
+        reference_number: Some(format!("accrual:{}:{due_date}", accrual.id)),
+        payee: None,
+        tags: None,
+        original_amount: None,
+        original_currency: None,
+        refunds_transaction_id: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Insert a transaction generated by the app, such as an accrual or a closing adjustment
+pub(crate) async fn insert_generated_transaction(
+    db: &Database,
+    transaction: &Transaction,
+) -> FiscusResult<()> {
+    let params_with_mapping = vec![
+        ("id".to_string(), Value::String(transaction.id.clone())),
+        (
+            "user_id".to_string(),
+            Value::String(transaction.user_id.clone()),
+        ),
+        (
+            "account_id".to_string(),
+            Value::String(transaction.account_id.clone()),
+        ),
+        (
+            "category_id".to_string(),
+            transaction
+                .category_id
+                .as_ref()
+                .map(|id| Value::String(id.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "amount".to_string(),
+            Value::String(transaction.amount.to_string()),
+        ),
+        (
+            "description".to_string(),
+            Value::String(transaction.description.clone()),
+        ),
+        (
+            "transaction_date".to_string(),
+            Value::String(transaction.transaction_date.to_rfc3339()),
+        ),
+        (
+            "transaction_type".to_string(),
+            Value::String(transaction.transaction_type.to_string()),
+        ),
+        (
+            "status".to_string(),
+            Value::String(transaction.status.to_string()),
+        ),
+        (
+            "reference_number".to_string(),
+            transaction
+                .reference_number
+                .as_ref()
+                .map(|r| Value::String(r.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "created_at".to_string(),
+            Value::String(transaction.created_at.to_rfc3339()),
+        ),
+        (
+            "updated_at".to_string(),
+            Value::String(transaction.updated_at.to_rfc3339()),
+        ),
+        (
+            "amount_minor".to_string(),
+            amount_minor_value(transaction.amount, store_amount_minor_units())?,
+        ),
+    ];
+
+    let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+        params_with_mapping,
+        &transaction.user_id,
+        "transactions",
+    )
+    .await?;
+
+    DatabaseUtils::execute_non_query(
+        db,
+        r#"
+        INSERT INTO transactions (
+            id, user_id, account_id, category_id, amount, description, transaction_date,
+            transaction_type, status, reference_number, created_at, updated_at, amount_minor
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        "#,
+        encrypted_params,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccrualFrequency;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn accrual(
+        kind: AccrualKind,
+        amount: Option<Decimal>,
+        annual_rate: Option<Decimal>,
+        start_date: NaiveDate,
+    ) -> AccountAccrual {
+        let now = Utc::now();
+        AccountAccrual {
+            id: Uuid::new_v4().to_string(),
+            user_id: "user".to_string(),
+            account_id: "account".to_string(),
+            name: "Monthly maintenance fee".to_string(),
+            kind,
+            amount,
+            annual_rate,
+            frequency: AccrualFrequency::Monthly,
+            category_id: None,
+            start_date,
+            last_applied: None,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_flat_monthly_fee_is_applied_once_per_month() {
+        let mut fee = accrual(
+            AccrualKind::Fee,
+            Some(Decimal::new(500, 2)),
+            None,
+            date(2024, 1, 31),
+        );
+
+        let due = due_dates(&fee, date(2024, 3, 31));
+        assert_eq!(
+            due,
+            vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)]
+        );
+        assert_eq!(
+            accrual_deltas(&fee, Decimal::new(100000, 2), due.len(), 2),
+            vec![Decimal::new(-500, 2); 3]
+        );
+
+        // Once applied, re-running within the same month posts nothing
+        fee.last_applied = due.last().copied();
+        assert!(due_dates(&fee, date(2024, 3, 31)).is_empty());
+        assert!(due_dates(&fee, date(2024, 4, 29)).is_empty());
+        assert_eq!(due_dates(&fee, date(2024, 4, 30)), vec![date(2024, 4, 30)]);
+    }
+
+    #[test]
+    fn test_interest_is_computed_on_current_balance() {
+        let interest = accrual(
+            AccrualKind::Interest,
+            None,
+            Some(Decimal::new(12, 2)), // 12% a year, 1% a month
+            date(2024, 1, 1),
+        );
+
+        let due = due_dates(&interest, date(2024, 2, 15));
+        assert_eq!(due.len(), 2);
+
+        // The second month earns interest on the first month's interest too
+        assert_eq!(
+            accrual_deltas(&interest, Decimal::new(100000, 2), due.len(), 2),
+            vec![Decimal::new(1000, 2), Decimal::new(1010, 2)]
+        );
+
+        let posted =
+            accrual_transaction(&interest, due[0], Decimal::new(1000, 2), Utc::now()).unwrap();
+        assert_eq!(posted.transaction_type, TransactionType::Income);
+        assert_eq!(posted.amount, Decimal::new(1000, 2));
+        assert_eq!(posted.transaction_date.date_naive(), date(2024, 1, 1));
+    }
+
+    #[test]
+    fn test_negative_interest_posts_an_expense() {
+        let interest = accrual(
+            AccrualKind::Interest,
+            None,
+            Some(Decimal::new(-6, 2)),
+            date(2024, 1, 1),
+        );
+
+        let deltas = accrual_deltas(&interest, Decimal::new(100000, 2), 1, 2);
+        assert_eq!(deltas, vec![Decimal::new(-500, 2)]);
+
+        let posted =
+            accrual_transaction(&interest, date(2024, 1, 1), deltas[0], Utc::now()).unwrap();
+        assert_eq!(posted.transaction_type, TransactionType::Expense);
+        assert_eq!(posted.amount, Decimal::new(500, 2));
+
+        // Nothing to post on an empty account
+        assert!(
+            accrual_transaction(&interest, date(2024, 1, 1), Decimal::ZERO, Utc::now()).is_none()
+        );
+    }
+
+    #[test]
+    fn test_accrual_terms_match_kind() {
+        assert!(
+            validate_accrual_terms(&AccrualKind::Fee, Some(Decimal::new(500, 2)), None).is_ok()
+        );
+        assert!(
+            validate_accrual_terms(&AccrualKind::Interest, None, Some(Decimal::new(5, 2))).is_ok()
+        );
+
+        assert!(validate_accrual_terms(&AccrualKind::Fee, None, None).is_err());
+        assert!(validate_accrual_terms(&AccrualKind::Fee, Some(Decimal::ZERO), None).is_err());
+        assert!(validate_accrual_terms(&AccrualKind::Interest, None, Some(Decimal::ZERO)).is_err());
+        assert!(validate_accrual_terms(
+            &AccrualKind::Interest,
+            Some(Decimal::ONE),
+            Some(Decimal::new(5, 2))
+        )
+        .is_err());
+    }
+}
@@ -5,12 +5,22 @@ use argon2::{
 use rand::rngs::OsRng;
 use serde_json::Value;
 use tauri::State;
+use tracing::warn;
 use uuid::Uuid;
 
 use crate::{
+    commands::{
+        encryption::{prepare_rekey, store_rekey, unlock_user_keys},
+        transactions::index_unindexed_payees,
+    },
     database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
-    dto::{ChangePasswordRequest, CreateUserRequest, LoginRequest, LoginResponse, UserResponse},
-    error::{FiscusError, FiscusResult, Validator},
+    dto::{
+        ChangePasswordRequest, CreateUserRequest, LoginRequest, LoginResponse,
+        ReauthenticateRequest, UserResponse,
+    },
+    error::{FiscusError, FiscusResult, PasswordPolicy, Validator},
+    security::{authorize_command, refresh_authentication, set_active_context, SecurityContext},
+    with_transaction,
 };
 
 #[cfg(test)]
@@ -22,9 +32,12 @@ pub async fn create_user(
     request: CreateUserRequest,
     db: State<'_, Database>,
 ) -> Result<UserResponse, FiscusError> {
+    authorize_command("create_user").await?;
+
     // Validate input
     Validator::validate_string(&request.username, "username", 3, 50)?;
     Validator::validate_string(request.password.expose(), "password", 8, 128)?;
+    Validator::validate_password_with_policy(request.password.expose(), PasswordPolicy::current())?;
 
     if let Some(ref email) = request.email {
         Validator::validate_email(email)?;
@@ -125,6 +138,8 @@ pub async fn login_user(
     request: LoginRequest,
     db: State<'_, Database>,
 ) -> Result<LoginResponse, FiscusError> {
+    authorize_command("login_user").await?;
+
     // Validate input
     Validator::validate_string(&request.username, "username", 1, 50)?;
     Validator::validate_string(request.password.expose(), "password", 1, 128)?;
@@ -176,6 +191,18 @@ pub async fn login_user(
         ));
     }
 
+    // Keys from earlier sessions are needed to read the user's data
+    unlock_user_keys(&db, &user_id, request.password.expose()).await?;
+
+    // Data commands are authorized against this session from now on
+    set_active_context(Some(SecurityContext::owner(user_id.clone()))).await;
+
+    // Payees stored before blind indexes existed become searchable; a failure
+    // here only delays that and must not block the login
+    if let Err(e) = index_unindexed_payees(&db, &user_id).await {
+        warn!(user_id = %user_id, error = %e, "Payee index backfill failed");
+    }
+
     // Create user response
     let user_response = UserResponse {
         id: user_data
@@ -227,6 +254,10 @@ pub async fn change_password(
         128,
     )?;
     Validator::validate_string(request.new_password.expose(), "new_password", 8, 128)?;
+    Validator::validate_password_with_policy(
+        request.new_password.expose(),
+        PasswordPolicy::current(),
+    )?;
 
     // Get current user data
     let user_query = "SELECT password_hash FROM users WHERE id = ?1";
@@ -255,6 +286,17 @@ pub async fn change_password(
     // Hash new password
     let new_password_hash = hash_password(request.new_password.expose())?;
 
+    // Re-wrap the stored keys before anything is written, so a failure
+    // leaves both the password and the wrapping unchanged
+    let user_id = request.user_id.as_str();
+    let rekey = prepare_rekey(
+        &db,
+        &user_id,
+        request.current_password.expose(),
+        request.new_password.expose(),
+    )
+    .await?;
+
     // Update password
     let update_query = "UPDATE users SET password_hash = ?1, updated_at = ?2 WHERE id = ?3";
 
@@ -266,22 +308,60 @@ pub async fn change_password(
             Value::String(new_password_hash),
         ),
         ("updated_at".to_string(), Value::String(now)),
-        ("id".to_string(), Value::String(request.user_id.to_string())),
+        ("id".to_string(), Value::String(user_id.clone())),
     ];
 
-    let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
-        params_with_mapping,
-        &request.user_id.as_str(),
-        "users",
-    )
-    .await?;
+    let encrypted_params =
+        EncryptedDatabaseUtils::encrypt_params_with_mapping(params_with_mapping, &user_id, "users")
+            .await?;
 
-    let affected_rows =
-        DatabaseUtils::execute_non_query(&db, update_query, encrypted_params).await?;
+    let affected_rows = with_transaction!(&*db, async {
+        let affected_rows =
+            DatabaseUtils::execute_non_query(&db, update_query, encrypted_params).await?;
+        if affected_rows > 0 {
+            store_rekey(&db, &rekey).await?;
+        }
+        Ok::<u64, FiscusError>(affected_rows)
+    })?;
 
     Ok(affected_rows > 0)
 }
 
+/// Confirm the user's password to refresh the session before a sensitive operation
+///
+/// Sensitive commands reject sessions that authenticated too long ago; the
+/// UI calls this with the re-entered password and then retries.
+#[tauri::command]
+pub async fn reauthenticate(
+    request: ReauthenticateRequest,
+    db: State<'_, Database>,
+) -> Result<bool, FiscusError> {
+    let user_id = request.user_id.as_str();
+    Validator::validate_string(request.password.expose(), "password", 1, 128)?;
+
+    let user_row: Option<std::collections::HashMap<String, Value>> =
+        DatabaseUtils::execute_query_single(
+            &db,
+            "SELECT password_hash FROM users WHERE id = ?1",
+            vec![Value::String(user_id.clone())],
+        )
+        .await?;
+    let stored_hash = user_row
+        .as_ref()
+        .and_then(|row| row.get("password_hash"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| FiscusError::Authentication("Invalid credentials".to_string()))?;
+
+    if !verify_password(request.password.expose(), stored_hash)? {
+        return Err(FiscusError::Authentication(
+            "Invalid credentials".to_string(),
+        ));
+    }
+
+    refresh_authentication(&user_id).await?;
+    Ok(true)
+}
+
 /// Get current user information
 #[tauri::command]
 pub async fn get_current_user(
@@ -358,7 +438,7 @@ fn hash_password(password: &str) -> FiscusResult<String> {
 }
 
 /// Verify a password against its hash
-fn verify_password(password: &str, hash: &str) -> FiscusResult<bool> {
+pub(crate) fn verify_password(password: &str, hash: &str) -> FiscusResult<bool> {
     let parsed_hash = PasswordHash::new(hash).map_err(FiscusError::from)?;
 
     Ok(Argon2::default()
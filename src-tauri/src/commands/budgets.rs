@@ -1,31 +1,42 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tauri::State;
 use uuid::Uuid;
 
 use crate::{
+    commands::currencies::currency_minor_units,
     database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
     dto::{
-        BudgetFilters, BudgetSummaryResponse, CreateBudgetPeriodRequest, CreateBudgetRequest,
-        UpdateBudgetRequest,
+        BudgetFilters, BudgetSummaryResponse, BudgetTemplateInstance, BudgetVsActualLine,
+        CreateBudgetPeriodRequest, CreateBudgetRequest, CreateBudgetTemplateRequest,
+        CurrentBudgetPeriodResponse, UpdateBudgetRequest,
     },
-    error::{FiscusError, SecurityValidator, Validator},
-    models::{Budget, BudgetPeriod},
+    error::{FiscusError, FiscusResult, SecurityValidator, Validator},
+    models::{Budget, BudgetPeriod, BudgetTemplate, BudgetTemplateAllocation, Transaction},
+    security::authorize_command,
     utils::parse_decimal_from_json,
+    with_transaction,
 };
 
+/// Largest factor `scale_budget_period` accepts
+const MAX_BUDGET_SCALE_FACTOR: Decimal = Decimal::TEN;
+
 /// Create a new budget period
 #[tauri::command]
 pub async fn create_budget_period(
     request: CreateBudgetPeriodRequest,
     db: State<'_, Database>,
 ) -> Result<BudgetPeriod, FiscusError> {
+    authorize_command("create_budget_period").await?;
+
     // Validate input
     Validator::validate_uuid(&request.user_id.as_str(), "user_id")?;
     Validator::validate_string(&request.name, "name", 1, 100)?;
 
-    let start_date = Validator::validate_date(&request.start_date)?;
-    let end_date = Validator::validate_date(&request.end_date)?;
+    let start_date = Validator::validate_date(&request.start_date, "start_date")?;
+    let end_date = Validator::validate_date(&request.end_date, "end_date")?;
 
     if end_date <= start_date {
         return Err(FiscusError::InvalidInput(
@@ -36,31 +47,13 @@ pub async fn create_budget_period(
     // Validate user exists
     DatabaseUtils::validate_user_exists(&db, &request.user_id.as_str()).await?;
 
-    // Check for overlapping budget periods
-    let overlap_query = r#"
-        SELECT id FROM budget_periods 
-        WHERE user_id = ?1 AND is_active = 1 
-        AND ((start_date <= ?2 AND end_date >= ?2) OR (start_date <= ?3 AND end_date >= ?3)
-             OR (start_date >= ?2 AND end_date <= ?3))
-    "#;
-
-    let overlap_result: Option<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query_single(
-            &db,
-            overlap_query,
-            vec![
-                Value::String(request.user_id.as_str()),
-                Value::String(request.start_date.clone()),
-                Value::String(request.end_date.clone()),
-            ],
-        )
-        .await?;
-
-    if overlap_result.is_some() {
-        return Err(FiscusError::Conflict(
-            "Budget period overlaps with existing period".to_string(),
-        ));
-    }
+    ensure_no_overlapping_period(
+        &db,
+        &request.user_id.as_str(),
+        &request.start_date,
+        &request.end_date,
+    )
+    .await?;
 
     let period_id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
@@ -87,6 +80,41 @@ pub async fn create_budget_period(
     get_budget_period_by_id(period_id, db).await
 }
 
+/// Reject a new period that overlaps an active period of the same user
+async fn ensure_no_overlapping_period(
+    db: &Database,
+    user_id: &str,
+    start_date: &str,
+    end_date: &str,
+) -> FiscusResult<()> {
+    let overlap_query = r#"
+        SELECT id FROM budget_periods 
+        WHERE user_id = ?1 AND is_active = 1 
+        AND ((start_date <= ?2 AND end_date >= ?2) OR (start_date <= ?3 AND end_date >= ?3)
+             OR (start_date >= ?2 AND end_date <= ?3))
+    "#;
+
+    let overlap_result: Option<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query_single(
+            db,
+            overlap_query,
+            vec![
+                Value::String(user_id.to_string()),
+                Value::String(start_date.to_string()),
+                Value::String(end_date.to_string()),
+            ],
+        )
+        .await?;
+
+    if overlap_result.is_some() {
+        return Err(FiscusError::Conflict(
+            "Budget period overlaps with existing period".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Get budget periods for a user
 #[tauri::command]
 pub async fn get_budget_periods(
@@ -94,6 +122,8 @@ pub async fn get_budget_periods(
     is_active: Option<bool>,
     db: State<'_, Database>,
 ) -> Result<Vec<BudgetPeriod>, FiscusError> {
+    authorize_command("get_budget_periods").await?;
+
     // Validate user
     Validator::validate_uuid(&user_id, "user_id")?;
     DatabaseUtils::validate_user_exists(&db, &user_id).await?;
@@ -125,6 +155,8 @@ pub async fn get_budget_period_by_id(
     period_id: String,
     db: State<'_, Database>,
 ) -> Result<BudgetPeriod, FiscusError> {
+    authorize_command("get_budget_period_by_id").await?;
+
     Validator::validate_uuid(&period_id, "period_id")?;
 
     let query = r#"
@@ -140,12 +172,84 @@ pub async fn get_budget_period_by_id(
     period.ok_or_else(|| FiscusError::NotFound("Budget period not found".to_string()))
 }
 
+/// Get the budget period containing `as_of` along with its neighbours
+///
+/// `as_of` defaults to today. When no active period covers the date the
+/// nearest periods before and after it are returned with `covered: false`.
+#[tauri::command]
+pub async fn get_current_budget_period(
+    user_id: String,
+    as_of: Option<String>,
+    db: State<'_, Database>,
+) -> Result<CurrentBudgetPeriodResponse, FiscusError> {
+    authorize_command("get_current_budget_period").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    let as_of = match as_of {
+        Some(date) => Validator::validate_date(&date, "as_of")?,
+        None => chrono::Utc::now().date_naive(),
+    };
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let query = r#"
+        SELECT id, user_id, name, start_date, end_date, is_active, created_at, updated_at
+        FROM budget_periods
+        WHERE user_id = ?1 AND is_active = 1
+        ORDER BY start_date ASC
+    "#;
+
+    let periods: Vec<BudgetPeriod> =
+        DatabaseUtils::execute_query(&db, query, vec![Value::String(user_id)]).await?;
+
+    Ok(locate_budget_period(periods, as_of))
+}
+
+/// Find the period covering `as_of` and the periods immediately around it
+fn locate_budget_period(
+    periods: Vec<BudgetPeriod>,
+    as_of: NaiveDate,
+) -> CurrentBudgetPeriodResponse {
+    let mut current = None;
+    let mut previous: Option<BudgetPeriod> = None;
+    let mut next: Option<BudgetPeriod> = None;
+
+    for period in periods {
+        if period.start_date <= as_of && as_of <= period.end_date {
+            if current.is_none() {
+                current = Some(period);
+            }
+        } else if period.end_date < as_of {
+            if !matches!(&previous, Some(p) if p.end_date >= period.end_date) {
+                previous = Some(period);
+            }
+        } else if !matches!(&next, Some(n) if n.start_date <= period.start_date) {
+            next = Some(period);
+        }
+    }
+
+    let days_remaining = current
+        .as_ref()
+        .map(|period| (period.end_date - as_of).num_days());
+
+    CurrentBudgetPeriodResponse {
+        as_of,
+        covered: current.is_some(),
+        current,
+        previous,
+        next,
+        days_remaining,
+    }
+}
+
 /// Create a new budget
 #[tauri::command]
 pub async fn create_budget(
     request: CreateBudgetRequest,
     db: State<'_, Database>,
 ) -> Result<Budget, FiscusError> {
+    authorize_command("create_budget").await?;
+
     // Validate input
     Validator::validate_uuid(&request.user_id.as_str(), "user_id")?;
     Validator::validate_uuid(&request.budget_period_id, "budget_period_id")?;
@@ -198,62 +302,83 @@ pub async fn create_budget(
         ));
     }
 
-    let budget_id = Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+    let now = chrono::Utc::now();
+    let budget = Budget {
+        id: Uuid::new_v4().to_string(),
+        user_id: request.user_id.as_str(),
+        budget_period_id: request.budget_period_id,
+        category_id: request.category_id,
+        allocated_amount: request.allocated_amount,
+        spent_amount: Decimal::ZERO,
+        notes: request.notes,
+        rollover: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    insert_budget(&db, &budget).await?;
+
+    // Return the created budget
+    get_budget_by_id(budget.id, db).await
+}
 
+/// Insert a budget row, encrypting its amounts
+async fn insert_budget(db: &Database, budget: &Budget) -> FiscusResult<()> {
     let insert_query = r#"
         INSERT INTO budgets (
             id, user_id, budget_period_id, category_id, allocated_amount, 
-            spent_amount, notes, created_at, updated_at
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            spent_amount, notes, rollover, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
     "#;
 
     // Use encrypted parameter mapping for sensitive fields
     let params_with_mapping = vec![
-        ("id".to_string(), Value::String(budget_id.clone())),
-        (
-            "user_id".to_string(),
-            Value::String(request.user_id.as_str()),
-        ),
+        ("id".to_string(), Value::String(budget.id.clone())),
+        ("user_id".to_string(), Value::String(budget.user_id.clone())),
         (
             "budget_period_id".to_string(),
-            Value::String(request.budget_period_id.clone()),
+            Value::String(budget.budget_period_id.clone()),
         ),
         (
             "category_id".to_string(),
-            Value::String(request.category_id.clone()),
+            Value::String(budget.category_id.clone()),
         ),
         (
             "allocated_amount".to_string(),
-            Value::String(request.allocated_amount.to_string()),
+            Value::String(budget.allocated_amount.to_string()),
         ),
         (
             "spent_amount".to_string(),
-            Value::String(rust_decimal::Decimal::ZERO.to_string()),
+            Value::String(budget.spent_amount.to_string()),
         ),
         (
             "notes".to_string(),
-            request
+            budget
                 .notes
                 .as_ref()
                 .map(|n| Value::String(n.clone()))
                 .unwrap_or(Value::Null),
         ),
-        ("created_at".to_string(), Value::String(now.clone())),
-        ("updated_at".to_string(), Value::String(now)),
+        ("rollover".to_string(), Value::Bool(budget.rollover)),
+        (
+            "created_at".to_string(),
+            Value::String(budget.created_at.to_rfc3339()),
+        ),
+        (
+            "updated_at".to_string(),
+            Value::String(budget.updated_at.to_rfc3339()),
+        ),
     ];
 
     let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
         params_with_mapping,
-        &request.user_id.as_str(),
+        &budget.user_id,
         "budgets",
     )
     .await?;
 
-    DatabaseUtils::execute_non_query(&db, insert_query, encrypted_params).await?;
-
-    // Return the created budget
-    get_budget_by_id(budget_id, db).await
+    DatabaseUtils::execute_non_query(db, insert_query, encrypted_params).await?;
+    Ok(())
 }
 
 /// Get budgets with filtering
@@ -262,6 +387,8 @@ pub async fn get_budgets(
     filters: BudgetFilters,
     db: State<'_, Database>,
 ) -> Result<Vec<Budget>, FiscusError> {
+    authorize_command("get_budgets").await?;
+
     // Validate user
     Validator::validate_uuid(&filters.user_id.as_str(), "user_id")?;
     DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
@@ -282,7 +409,7 @@ pub async fn get_budgets(
 
     let base_query = r#"
         SELECT id, user_id, budget_period_id, category_id, allocated_amount,
-               spent_amount, notes, created_at, updated_at
+               spent_amount, notes, rollover, created_at, updated_at
         FROM budgets
     "#;
 
@@ -320,6 +447,8 @@ pub async fn get_budget_by_id(
     budget_id: String,
     db: State<'_, Database>,
 ) -> Result<Budget, FiscusError> {
+    authorize_command("get_budget_by_id").await?;
+
     Validator::validate_uuid(&budget_id, "budget_id")?;
 
     // First, get the user_id for this budget (this field is not encrypted)
@@ -341,7 +470,7 @@ pub async fn get_budget_by_id(
 
     let query = r#"
         SELECT id, user_id, budget_period_id, category_id, allocated_amount,
-               spent_amount, notes, created_at, updated_at
+               spent_amount, notes, rollover, created_at, updated_at
         FROM budgets
         WHERE id = ?1
     "#;
@@ -370,6 +499,8 @@ pub async fn update_budget(
     request: UpdateBudgetRequest,
     db: State<'_, Database>,
 ) -> Result<Budget, FiscusError> {
+    authorize_command("update_budget").await?;
+
     // Validate input
     Validator::validate_uuid(&budget_id, "budget_id")?;
     Validator::validate_uuid(&user_id, "user_id")?;
@@ -461,6 +592,8 @@ pub async fn delete_budget(
     user_id: String,
     db: State<'_, Database>,
 ) -> Result<bool, FiscusError> {
+    authorize_command("delete_budget").await?;
+
     // Validate input
     Validator::validate_uuid(&budget_id, "budget_id")?;
     Validator::validate_uuid(&user_id, "user_id")?;
@@ -488,6 +621,147 @@ pub async fn delete_budget(
     Ok(affected_rows > 0)
 }
 
+/// Scale every budget allocation in a period by `factor`
+///
+/// Allocations are rounded to the minor units of the user's currency and
+/// updated in one database transaction. Returns the period's new total allocated.
+#[tauri::command]
+pub async fn scale_budget_period(
+    user_id: String,
+    budget_period_id: String,
+    factor: Decimal,
+    db: State<'_, Database>,
+) -> Result<Decimal, FiscusError> {
+    authorize_command("scale_budget_period").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_uuid(&budget_period_id, "budget_period_id")?;
+    validate_scale_factor(factor)?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let period: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        &db,
+        "SELECT id FROM budget_periods WHERE id = ?1 AND user_id = ?2",
+        vec![
+            Value::String(budget_period_id.clone()),
+            Value::String(user_id.clone()),
+        ],
+    )
+    .await?;
+    if period.is_none() {
+        return Err(FiscusError::NotFound("Budget period not found".to_string()));
+    }
+
+    let budgets: Vec<Budget> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        r#"
+        SELECT id, user_id, budget_period_id, category_id, allocated_amount,
+               spent_amount, notes, rollover, created_at, updated_at
+        FROM budgets
+        WHERE user_id = ?1 AND budget_period_id = ?2
+        "#,
+        vec![
+            Value::String(user_id.clone()),
+            Value::String(budget_period_id),
+        ],
+        &user_id,
+        "budgets",
+    )
+    .await?;
+
+    let currency = user_budget_currency(&db, &user_id).await?;
+    let scaled = scaled_allocations(&budgets, factor, currency_minor_units(&currency));
+    let now = Utc::now().to_rfc3339();
+    with_transaction!(&*db, async {
+        for (budget_id, allocated_amount) in &scaled {
+            let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+                vec![
+                    (
+                        "allocated_amount".to_string(),
+                        Value::String(allocated_amount.to_string()),
+                    ),
+                    ("updated_at".to_string(), Value::String(now.clone())),
+                    ("id".to_string(), Value::String(budget_id.clone())),
+                    ("user_id".to_string(), Value::String(user_id.clone())),
+                ],
+                &user_id,
+                "budgets",
+            )
+            .await?;
+
+            DatabaseUtils::execute_non_query(
+                &db,
+                "UPDATE budgets SET allocated_amount = ?1, updated_at = ?2 WHERE id = ?3 AND user_id = ?4",
+                encrypted_params,
+            )
+            .await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    Ok(scaled
+        .iter()
+        .map(|(_, allocated_amount)| *allocated_amount)
+        .sum())
+}
+
+/// Reject scale factors that are not positive or exceed `MAX_BUDGET_SCALE_FACTOR`
+fn validate_scale_factor(factor: Decimal) -> FiscusResult<()> {
+    if factor <= Decimal::ZERO || factor > MAX_BUDGET_SCALE_FACTOR {
+        return Err(FiscusError::field_validation(
+            "factor",
+            "out_of_range",
+            format!("factor must be greater than 0 and at most {MAX_BUDGET_SCALE_FACTOR}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Currency a user's budgets are kept in: the one most of their active accounts use
+///
+/// Budgets carry no currency of their own; users without accounts get USD.
+async fn user_budget_currency(db: &Database, user_id: &str) -> FiscusResult<String> {
+    let row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        r#"
+        SELECT currency FROM accounts
+        WHERE user_id = ?1 AND is_active = 1
+        GROUP BY currency
+        ORDER BY COUNT(*) DESC, currency
+        LIMIT 1
+        "#,
+        vec![Value::String(user_id.to_string())],
+    )
+    .await?;
+
+    Ok(row
+        .as_ref()
+        .and_then(|row| row.get("currency"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("USD")
+        .to_string())
+}
+
+/// New allocation of each budget scaled by `factor`, as `(budget_id, allocated_amount)`
+///
+/// Rounded half away from zero to `minor_units` decimal places, the
+/// precision of the budget's currency.
+fn scaled_allocations(
+    budgets: &[Budget],
+    factor: Decimal,
+    minor_units: u32,
+) -> Vec<(String, Decimal)> {
+    budgets
+        .iter()
+        .map(|budget| {
+            let scaled = (budget.allocated_amount * factor)
+                .round_dp_with_strategy(minor_units, RoundingStrategy::MidpointAwayFromZero);
+            (budget.id.clone(), scaled)
+        })
+        .collect()
+}
+
 /// Get budget summary for a user and period
 #[tauri::command]
 pub async fn get_budget_summary(
@@ -495,6 +769,8 @@ pub async fn get_budget_summary(
     budget_period_id: Option<String>,
     db: State<'_, Database>,
 ) -> Result<BudgetSummaryResponse, FiscusError> {
+    authorize_command("get_budget_summary").await?;
+
     // Validate user
     Validator::validate_uuid(&user_id, "user_id")?;
     DatabaseUtils::validate_user_exists(&db, &user_id).await?;
@@ -560,3 +836,797 @@ pub async fn get_budget_summary(
         categories_under_budget,
     })
 }
+
+/// Compare each category's allocation with its actual spending in a budget period
+///
+/// Spending is summed from expense transactions dated within the period.
+/// Budgeted categories without spending are included with zero spent; with
+/// `include_unbudgeted`, spending in categories without a budget is appended
+/// and flagged `unbudgeted`.
+#[tauri::command]
+pub async fn get_budget_vs_actual(
+    user_id: String,
+    budget_period_id: String,
+    include_unbudgeted: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<Vec<BudgetVsActualLine>, FiscusError> {
+    authorize_command("get_budget_vs_actual").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_uuid(&budget_period_id, "budget_period_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    // Amounts are stored encrypted, so they are decrypted and summed here
+    // rather than aggregated in SQL
+    let budgets_query = r#"
+        SELECT b.id, b.category_id, b.allocated_amount
+        FROM budgets b
+        JOIN categories c ON c.id = b.category_id
+        WHERE b.user_id = ?1 AND b.budget_period_id = ?2
+    "#;
+    let budget_rows: Vec<HashMap<String, Value>> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        budgets_query,
+        vec![
+            Value::String(user_id.clone()),
+            Value::String(budget_period_id.clone()),
+        ],
+        &user_id,
+        "budgets",
+    )
+    .await?;
+    let budgets: Vec<(String, Decimal)> = budget_rows
+        .iter()
+        .filter_map(|row| {
+            let category_id = row.get("category_id")?.as_str()?;
+            Some((
+                category_id.to_string(),
+                parse_decimal_from_json(row, "allocated_amount"),
+            ))
+        })
+        .collect();
+
+    let transactions_query = r#"
+        SELECT t.id, t.user_id, t.account_id, t.category_id, t.amount, t.description,
+               t.notes, t.transaction_date, t.transaction_type, t.status,
+               t.reference_number, t.payee, t.tags, t.created_at, t.updated_at
+        FROM transactions t
+        JOIN budget_periods bp ON bp.id = ?2 AND bp.user_id = ?1
+        WHERE t.user_id = ?1
+            AND t.transaction_type = 'expense'
+            AND t.status NOT IN ('cancelled', 'voided')
+            AND DATE(t.transaction_date) BETWEEN bp.start_date AND bp.end_date
+    "#;
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        transactions_query,
+        vec![
+            Value::String(user_id.clone()),
+            Value::String(budget_period_id),
+        ],
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    let category_query = "SELECT id, name FROM categories WHERE user_id = ?1";
+    let category_rows: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(&db, category_query, vec![Value::String(user_id)]).await?;
+    let categories: HashMap<String, String> = category_rows
+        .iter()
+        .filter_map(|row| {
+            let id = row.get("id")?.as_str()?;
+            let name = row.get("name")?.as_str()?;
+            Some((id.to_string(), name.to_string()))
+        })
+        .collect();
+
+    Ok(budget_vs_actual_lines(
+        &budgets,
+        &transactions,
+        &categories,
+        include_unbudgeted.unwrap_or(false),
+    ))
+}
+
+/// Pair each budget with its category's spending in the period
+///
+/// `budgets` holds each budget's category id and allocation, `categories`
+/// maps category ids to names. With `include_unbudgeted`, spending in
+/// categories without a budget gets its own line.
+fn budget_vs_actual_lines(
+    budgets: &[(String, Decimal)],
+    transactions: &[Transaction],
+    categories: &HashMap<String, String>,
+    include_unbudgeted: bool,
+) -> Vec<BudgetVsActualLine> {
+    let mut spent: HashMap<Option<&str>, Decimal> = HashMap::new();
+    for transaction in transactions {
+        *spent.entry(transaction.category_id.as_deref()).or_default() += transaction.amount;
+    }
+
+    let category_name = |category_id: Option<&str>| {
+        category_id
+            .and_then(|id| categories.get(id))
+            .cloned()
+            .unwrap_or_else(|| "Uncategorized".to_string())
+    };
+
+    let mut lines: Vec<BudgetVsActualLine> = budgets
+        .iter()
+        .map(|(category_id, allocated_amount)| {
+            budget_vs_actual_line(
+                Some(category_id.clone()),
+                category_name(Some(category_id)),
+                *allocated_amount,
+                spent
+                    .get(&Some(category_id.as_str()))
+                    .copied()
+                    .unwrap_or_default(),
+                false,
+            )
+        })
+        .collect();
+
+    if include_unbudgeted {
+        let budgeted: HashSet<&str> = budgets.iter().map(|(id, _)| id.as_str()).collect();
+        lines.extend(
+            spent
+                .iter()
+                .filter(|(category_id, _)| category_id.is_none_or(|id| !budgeted.contains(id)))
+                .map(|(category_id, spent_amount)| {
+                    budget_vs_actual_line(
+                        category_id.map(str::to_string),
+                        category_name(*category_id),
+                        Decimal::ZERO,
+                        *spent_amount,
+                        true,
+                    )
+                }),
+        );
+    }
+
+    lines.sort_by(|a, b| {
+        a.unbudgeted
+            .cmp(&b.unbudgeted)
+            .then_with(|| b.percent_used.cmp(&a.percent_used))
+            .then_with(|| a.category_name.cmp(&b.category_name))
+    });
+    lines
+}
+
+/// Derive remaining and percent used from a category's allocation and spending
+fn budget_vs_actual_line(
+    category_id: Option<String>,
+    category_name: String,
+    allocated_amount: Decimal,
+    spent_amount: Decimal,
+    unbudgeted: bool,
+) -> BudgetVsActualLine {
+    BudgetVsActualLine {
+        category_id,
+        category_name,
+        allocated_amount,
+        spent_amount,
+        remaining_amount: allocated_amount - spent_amount,
+        percent_used: percent_used(allocated_amount, spent_amount),
+        unbudgeted,
+    }
+}
+
+/// Share of the allocation spent, as a percentage rounded to two places
+///
+/// Zero when nothing was allocated, matching `get_budget_performance`.
+fn percent_used(allocated_amount: Decimal, spent_amount: Decimal) -> Decimal {
+    if allocated_amount <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    (spent_amount * Decimal::ONE_HUNDRED / allocated_amount).round_dp(2)
+}
+
+/// Save a reusable set of category allocations as a budget template
+#[tauri::command]
+pub async fn create_budget_template(
+    request: CreateBudgetTemplateRequest,
+    db: State<'_, Database>,
+) -> Result<BudgetTemplate, FiscusError> {
+    authorize_command("create_budget_template").await?;
+
+    let user_id = request.user_id.as_str();
+    Validator::validate_string(&request.name, "name", 1, 100)?;
+    validate_template_allocations(&request.category_allocations)?;
+
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+    for allocation in &request.category_allocations {
+        DatabaseUtils::validate_category_ownership(&db, &allocation.category_id, &user_id).await?;
+    }
+
+    let now = Utc::now();
+    let template = BudgetTemplate {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        name: request.name,
+        allocations: request.category_allocations,
+        created_at: now,
+        updated_at: now,
+    };
+
+    with_transaction!(&*db, async {
+        DatabaseUtils::execute_non_query(
+            &db,
+            r#"
+            INSERT INTO budget_templates (id, user_id, name, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            vec![
+                Value::String(template.id.clone()),
+                Value::String(template.user_id.clone()),
+                Value::String(template.name.clone()),
+                Value::String(now.to_rfc3339()),
+                Value::String(now.to_rfc3339()),
+            ],
+        )
+        .await?;
+
+        for allocation in &template.allocations {
+            let params_with_mapping = vec![
+                ("id".to_string(), Value::String(Uuid::new_v4().to_string())),
+                (
+                    "template_id".to_string(),
+                    Value::String(template.id.clone()),
+                ),
+                (
+                    "category_id".to_string(),
+                    Value::String(allocation.category_id.clone()),
+                ),
+                (
+                    "allocated_amount".to_string(),
+                    Value::String(allocation.allocated_amount.to_string()),
+                ),
+                ("rollover".to_string(), Value::Bool(allocation.rollover)),
+                (
+                    "notes".to_string(),
+                    allocation
+                        .notes
+                        .as_ref()
+                        .map(|n| Value::String(n.clone()))
+                        .unwrap_or(Value::Null),
+                ),
+            ];
+
+            let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+                params_with_mapping,
+                &template.user_id,
+                "budget_template_allocations",
+            )
+            .await?;
+
+            DatabaseUtils::execute_non_query(
+                &db,
+                r#"
+                INSERT INTO budget_template_allocations (
+                    id, template_id, category_id, allocated_amount, rollover, notes
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                encrypted_params,
+            )
+            .await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    Ok(template)
+}
+
+/// Create a budget period and one budget per template allocation, atomically
+///
+/// The period takes the template's name; each budget keeps its allocation,
+/// notes and rollover flag.
+#[tauri::command]
+pub async fn instantiate_budget_from_template(
+    user_id: String,
+    template_id: String,
+    start_date: String,
+    end_date: String,
+    db: State<'_, Database>,
+) -> Result<BudgetTemplateInstance, FiscusError> {
+    authorize_command("instantiate_budget_from_template").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_uuid(&template_id, "template_id")?;
+    let start = Validator::validate_date(&start_date, "start_date")?;
+    let end = Validator::validate_date(&end_date, "end_date")?;
+
+    if end <= start {
+        return Err(FiscusError::InvalidInput(
+            "End date must be after start date".to_string(),
+        ));
+    }
+
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let template = fetch_budget_template(&db, &user_id, &template_id).await?;
+    ensure_no_overlapping_period(&db, &user_id, &start_date, &end_date).await?;
+
+    // Categories may have been deleted since the template was saved
+    for allocation in &template.allocations {
+        DatabaseUtils::validate_category_ownership(&db, &allocation.category_id, &user_id).await?;
+    }
+
+    let now = Utc::now();
+    let budget_period = BudgetPeriod {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.clone(),
+        name: template.name.clone(),
+        start_date: start,
+        end_date: end,
+        is_active: true,
+        created_at: now,
+        updated_at: now,
+    };
+    let budgets = budgets_from_template(&template, &budget_period.id, now);
+
+    with_transaction!(&*db, async {
+        DatabaseUtils::execute_non_query(
+            &db,
+            r#"
+            INSERT INTO budget_periods (id, user_id, name, start_date, end_date, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            vec![
+                Value::String(budget_period.id.clone()),
+                Value::String(budget_period.user_id.clone()),
+                Value::String(budget_period.name.clone()),
+                Value::String(start_date.clone()),
+                Value::String(end_date.clone()),
+                Value::Bool(true),
+                Value::String(now.to_rfc3339()),
+                Value::String(now.to_rfc3339()),
+            ],
+        )
+        .await?;
+
+        for budget in &budgets {
+            insert_budget(&db, budget).await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    Ok(BudgetTemplateInstance {
+        budget_period,
+        budgets,
+    })
+}
+
+/// Check template allocations: at least one, valid ids and amounts, no repeated category
+fn validate_template_allocations(allocations: &[BudgetTemplateAllocation]) -> FiscusResult<()> {
+    if allocations.is_empty() {
+        return Err(FiscusError::field_validation(
+            "category_allocations",
+            "required",
+            "A budget template needs at least one category allocation",
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for allocation in allocations {
+        Validator::validate_uuid(&allocation.category_id, "category_id")?;
+        Validator::validate_amount(allocation.allocated_amount, false)?;
+
+        if !seen.insert(allocation.category_id.as_str()) {
+            return Err(FiscusError::field_validation(
+                "category_allocations",
+                "duplicate",
+                format!(
+                    "Category {} appears more than once in the template",
+                    allocation.category_id
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Load a template owned by `user_id` together with its decrypted allocations
+async fn fetch_budget_template(
+    db: &Database,
+    user_id: &str,
+    template_id: &str,
+) -> FiscusResult<BudgetTemplate> {
+    let template: Option<BudgetTemplate> = DatabaseUtils::execute_query_single(
+        db,
+        r#"
+        SELECT id, user_id, name, created_at, updated_at
+        FROM budget_templates
+        WHERE id = ?1 AND user_id = ?2
+        "#,
+        vec![
+            Value::String(template_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+    )
+    .await?;
+
+    let mut template =
+        template.ok_or_else(|| FiscusError::NotFound("Budget template not found".to_string()))?;
+
+    template.allocations = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        r#"
+        SELECT category_id, allocated_amount, rollover, notes
+        FROM budget_template_allocations
+        WHERE template_id = ?1
+        ORDER BY category_id
+        "#,
+        vec![Value::String(template_id.to_string())],
+        user_id,
+        "budget_template_allocations",
+    )
+    .await?;
+
+    Ok(template)
+}
+
+/// Budgets for a new period, one per template allocation
+fn budgets_from_template(
+    template: &BudgetTemplate,
+    budget_period_id: &str,
+    now: DateTime<Utc>,
+) -> Vec<Budget> {
+    template
+        .allocations
+        .iter()
+        .map(|allocation| Budget {
+            id: Uuid::new_v4().to_string(),
+            user_id: template.user_id.clone(),
+            budget_period_id: budget_period_id.to_string(),
+            category_id: allocation.category_id.clone(),
+            allocated_amount: allocation.allocated_amount,
+            spent_amount: Decimal::ZERO,
+            notes: allocation.notes.clone(),
+            rollover: allocation.rollover,
+            created_at: now,
+            updated_at: now,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(category: &str, allocated: &str, spent: &str, unbudgeted: bool) -> BudgetVsActualLine {
+        budget_vs_actual_line(
+            Some(format!("{category}-id")),
+            category.to_string(),
+            allocated.parse().unwrap(),
+            spent.parse().unwrap(),
+            unbudgeted,
+        )
+    }
+
+    #[test]
+    fn test_percent_used_math() {
+        let line = row("Groceries", "400.00", "150.00", false);
+
+        assert_eq!(line.percent_used, Decimal::new(3750, 2));
+        assert_eq!(line.remaining_amount, Decimal::new(25000, 2));
+        assert!(!line.unbudgeted);
+
+        // Thirds are rounded to two places
+        assert_eq!(
+            percent_used(Decimal::new(300, 0), Decimal::new(100, 0)),
+            Decimal::new(3333, 2)
+        );
+    }
+
+    #[test]
+    fn test_over_budget_reports_negative_remaining() {
+        let line = row("Dining", "200.00", "260.50", false);
+
+        assert_eq!(line.remaining_amount, Decimal::new(-6050, 2));
+        assert_eq!(line.percent_used, Decimal::new(13025, 2));
+    }
+
+    #[test]
+    fn test_budgeted_category_without_spending() {
+        let line = row("Travel", "500.00", "0", false);
+
+        assert_eq!(line.spent_amount, Decimal::ZERO);
+        assert_eq!(line.remaining_amount, Decimal::new(50000, 2));
+        assert_eq!(line.percent_used, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_unbudgeted_spending_is_flagged() {
+        let line = row("Hobbies", "0", "45.00", true);
+
+        assert!(line.unbudgeted);
+        assert_eq!(line.allocated_amount, Decimal::ZERO);
+        assert_eq!(line.remaining_amount, Decimal::new(-4500, 2));
+        assert_eq!(line.percent_used, Decimal::ZERO);
+    }
+
+    fn expense_in(category_id: Option<&str>, amount: i64) -> Transaction {
+        let mut transaction = crate::test_utils::TestUtils::create_test_transaction(
+            "user-id",
+            "account-id",
+            Decimal::new(amount, 2),
+            crate::models::TransactionType::Expense,
+        );
+        transaction.category_id = category_id.map(str::to_string);
+        transaction
+    }
+
+    #[test]
+    fn test_spending_is_summed_from_decrypted_amounts() {
+        let budgets = vec![
+            ("groceries".to_string(), Decimal::new(40000, 2)),
+            ("travel".to_string(), Decimal::new(50000, 2)),
+        ];
+        let transactions = vec![
+            expense_in(Some("groceries"), 10_000),
+            expense_in(Some("groceries"), 5_000),
+            expense_in(Some("hobbies"), 4_500),
+            expense_in(None, 1_000),
+        ];
+        let categories = HashMap::from([
+            ("groceries".to_string(), "Groceries".to_string()),
+            ("travel".to_string(), "Travel".to_string()),
+            ("hobbies".to_string(), "Hobbies".to_string()),
+        ]);
+
+        let lines = budget_vs_actual_lines(&budgets, &transactions, &categories, false);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].category_name, "Groceries");
+        assert_eq!(lines[0].spent_amount, Decimal::new(15000, 2));
+        assert_eq!(lines[0].percent_used, Decimal::new(3750, 2));
+        assert_eq!(lines[1].category_name, "Travel");
+        assert_eq!(lines[1].spent_amount, Decimal::ZERO);
+
+        let lines = budget_vs_actual_lines(&budgets, &transactions, &categories, true);
+        let unbudgeted: Vec<(&str, Decimal)> = lines
+            .iter()
+            .filter(|line| line.unbudgeted)
+            .map(|line| (line.category_name.as_str(), line.spent_amount))
+            .collect();
+        assert_eq!(
+            unbudgeted,
+            vec![
+                ("Hobbies", Decimal::new(4500, 2)),
+                ("Uncategorized", Decimal::new(1000, 2)),
+            ]
+        );
+    }
+
+    fn period(name: &str, start: &str, end: &str) -> BudgetPeriod {
+        let now = chrono::Utc::now();
+        BudgetPeriod {
+            id: format!("{name}-id"),
+            user_id: "user-id".to_string(),
+            name: name.to_string(),
+            start_date: NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap(),
+            end_date: NaiveDate::parse_from_str(end, "%Y-%m-%d").unwrap(),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn name(period: &Option<BudgetPeriod>) -> Option<&str> {
+        period.as_ref().map(|p| p.name.as_str())
+    }
+
+    fn periods() -> Vec<BudgetPeriod> {
+        vec![
+            period("January", "2024-01-01", "2024-01-31"),
+            period("February", "2024-02-01", "2024-02-29"),
+            // March is missing
+            period("April", "2024-04-01", "2024-04-30"),
+        ]
+    }
+
+    #[test]
+    fn test_current_period_for_date_inside_period() {
+        let located = locate_budget_period(periods(), date("2024-02-10"));
+
+        assert!(located.covered);
+        assert_eq!(name(&located.current), Some("February"));
+        assert_eq!(name(&located.previous), Some("January"));
+        assert_eq!(name(&located.next), Some("April"));
+        assert_eq!(located.days_remaining, Some(19));
+    }
+
+    #[test]
+    fn test_current_period_on_boundaries() {
+        let last_day = locate_budget_period(periods(), date("2024-01-31"));
+        assert!(last_day.covered);
+        assert_eq!(name(&last_day.current), Some("January"));
+        assert_eq!(name(&last_day.previous), None);
+        assert_eq!(name(&last_day.next), Some("February"));
+        assert_eq!(last_day.days_remaining, Some(0));
+
+        let first_day = locate_budget_period(periods(), date("2024-02-01"));
+        assert_eq!(name(&first_day.current), Some("February"));
+        assert_eq!(name(&first_day.previous), Some("January"));
+        assert_eq!(first_day.days_remaining, Some(28));
+    }
+
+    #[test]
+    fn test_gap_between_periods_returns_neighbours() {
+        let located = locate_budget_period(periods(), date("2024-03-15"));
+
+        assert!(!located.covered);
+        assert!(located.current.is_none());
+        assert_eq!(name(&located.previous), Some("February"));
+        assert_eq!(name(&located.next), Some("April"));
+        assert_eq!(located.days_remaining, None);
+    }
+
+    fn budget_with_allocation(allocated: Decimal) -> Budget {
+        let now = Utc::now();
+        Budget {
+            id: Uuid::new_v4().to_string(),
+            user_id: "user".to_string(),
+            budget_period_id: "period".to_string(),
+            category_id: Uuid::new_v4().to_string(),
+            allocated_amount: allocated,
+            spent_amount: Decimal::ZERO,
+            notes: None,
+            rollover: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_scaling_raises_each_allocation_with_cent_rounding() {
+        let budgets = vec![
+            budget_with_allocation(Decimal::new(10000, 2)),
+            budget_with_allocation(Decimal::new(3333, 2)),
+            budget_with_allocation(Decimal::new(1235, 2)),
+        ];
+
+        let scaled = scaled_allocations(&budgets, Decimal::new(11, 1), 2);
+
+        let amounts: Vec<Decimal> = scaled.iter().map(|(_, amount)| *amount).collect();
+        assert_eq!(
+            amounts,
+            vec![
+                Decimal::new(11000, 2),
+                Decimal::new(3666, 2), // 36.663
+                Decimal::new(1359, 2), // 13.585, half away from zero
+            ]
+        );
+        assert_eq!(scaled[0].0, budgets[0].id);
+
+        let total: Decimal = amounts.iter().sum();
+        assert_eq!(total, Decimal::new(16025, 2));
+    }
+
+    #[test]
+    fn test_scaled_allocations_round_to_currency_precision() {
+        let budgets = vec![budget_with_allocation(Decimal::new(1235, 0))];
+
+        // JPY has no minor units, so 1358.5 yen rounds to a whole yen
+        let yen = scaled_allocations(&budgets, Decimal::new(11, 1), currency_minor_units("JPY"));
+        assert_eq!(yen[0].1, Decimal::new(1359, 0));
+        assert_eq!(yen[0].1.scale(), 0);
+
+        // KWD keeps three decimal places
+        let budgets = vec![budget_with_allocation(Decimal::new(1235, 3))];
+        let dinar = scaled_allocations(&budgets, Decimal::new(11, 1), currency_minor_units("KWD"));
+        assert_eq!(dinar[0].1, Decimal::new(1359, 3));
+    }
+
+    #[test]
+    fn test_scale_factor_must_be_positive_and_bounded() {
+        assert!(validate_scale_factor(Decimal::new(11, 1)).is_ok());
+        assert!(validate_scale_factor(MAX_BUDGET_SCALE_FACTOR).is_ok());
+        assert!(validate_scale_factor(Decimal::ZERO).is_err());
+        assert!(validate_scale_factor(Decimal::new(-5, 1)).is_err());
+        assert!(validate_scale_factor(MAX_BUDGET_SCALE_FACTOR + Decimal::ONE).is_err());
+    }
+
+    mod templates {
+        use super::*;
+        use crate::database::DatabaseType;
+
+        const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+        const GROCERIES: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+        const RENT: &str = "6ba7b811-9dad-11d1-80b4-00c04fd430c8";
+        const TRAVEL: &str = "6ba7b812-9dad-11d1-80b4-00c04fd430c8";
+
+        fn allocation(
+            category_id: &str,
+            amount: Decimal,
+            rollover: bool,
+        ) -> BudgetTemplateAllocation {
+            BudgetTemplateAllocation {
+                category_id: category_id.to_string(),
+                allocated_amount: amount,
+                rollover,
+                notes: None,
+            }
+        }
+
+        fn monthly_template() -> BudgetTemplate {
+            let now = Utc::now();
+            BudgetTemplate {
+                id: Uuid::new_v4().to_string(),
+                user_id: USER_ID.to_string(),
+                name: "Monthly".to_string(),
+                allocations: vec![
+                    allocation(GROCERIES, Decimal::new(40000, 2), false),
+                    allocation(RENT, Decimal::new(150000, 2), false),
+                    allocation(TRAVEL, Decimal::new(12500, 2), true),
+                ],
+                created_at: now,
+                updated_at: now,
+            }
+        }
+
+        #[test]
+        fn test_instantiating_template_creates_budget_per_allocation() {
+            let template = monthly_template();
+            let period_id = Uuid::new_v4().to_string();
+
+            let budgets = budgets_from_template(&template, &period_id, Utc::now());
+
+            assert_eq!(budgets.len(), 3);
+            for (budget, allocation) in budgets.iter().zip(&template.allocations) {
+                assert_eq!(budget.budget_period_id, period_id);
+                assert_eq!(budget.user_id, USER_ID);
+                assert_eq!(budget.category_id, allocation.category_id);
+                assert_eq!(budget.allocated_amount, allocation.allocated_amount);
+                assert_eq!(budget.spent_amount, Decimal::ZERO);
+            }
+
+            // Rollover carries through from the template
+            let rollover: Vec<bool> = budgets.iter().map(|b| b.rollover).collect();
+            assert_eq!(rollover, vec![false, false, true]);
+        }
+
+        #[test]
+        fn test_template_allocations_are_validated() {
+            assert!(validate_template_allocations(&monthly_template().allocations).is_ok());
+
+            assert!(matches!(
+                validate_template_allocations(&[]),
+                Err(FiscusError::FieldValidation { ref code, .. }) if code == "required"
+            ));
+
+            let duplicated = vec![
+                allocation(GROCERIES, Decimal::new(100, 0), false),
+                allocation(GROCERIES, Decimal::new(200, 0), false),
+            ];
+            assert!(matches!(
+                validate_template_allocations(&duplicated),
+                Err(FiscusError::FieldValidation { ref code, .. }) if code == "duplicate"
+            ));
+
+            let negative = vec![allocation(RENT, Decimal::new(-100, 0), false)];
+            assert!(validate_template_allocations(&negative).is_err());
+        }
+
+        #[tokio::test]
+        async fn test_unknown_template_is_not_found() {
+            let db = Database::new(
+                "sqlite:budget-templates.db".to_string(),
+                DatabaseType::SQLite,
+            );
+
+            let result = fetch_budget_template(&db, USER_ID, &Uuid::new_v4().to_string()).await;
+
+            assert!(matches!(result, Err(FiscusError::NotFound(_))));
+        }
+    }
+}
@@ -1,15 +1,72 @@
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tauri::State;
+use tracing::warn;
 use uuid::Uuid;
 
 use crate::{
-    database::{Database, DatabaseUtils},
-    dto::{CategoryFilters, CreateCategoryRequest, UpdateCategoryRequest},
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
+    dto::{CategoryFilters, CategoryMergeResponse, CreateCategoryRequest, UpdateCategoryRequest},
+    env_config,
     error::{FiscusError, FiscusResult, SecurityValidator, Validator},
-    models::Category,
+    models::{Budget, Category},
+    security::authorize_command,
+    utils::env_usize,
+    with_transaction,
 };
 
+/// Environment variable overriding the maximum category nesting depth
+const MAX_CATEGORY_DEPTH_ENV: &str = "FISCUS_MAX_CATEGORY_DEPTH";
+
+/// Default maximum category nesting depth (a top-level category has depth 1)
+const DEFAULT_MAX_CATEGORY_DEPTH: usize = 5;
+
+/// Limits applied to the category hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CategoryConfig {
+    pub max_depth: usize,
+}
+
+impl Default for CategoryConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_CATEGORY_DEPTH,
+        }
+    }
+}
+
+impl CategoryConfig {
+    /// Configuration read from `FISCUS_MAX_CATEGORY_DEPTH`
+    pub fn from_env() -> Self {
+        Self {
+            max_depth: env_usize(MAX_CATEGORY_DEPTH_ENV, DEFAULT_MAX_CATEGORY_DEPTH),
+        }
+    }
+}
+
+env_config!(CategoryConfig);
+
+/// Maximum category nesting depth
+fn max_category_depth() -> usize {
+    CategoryConfig::current().max_depth
+}
+
+/// Other columns holding a category that a merge moves to the target, as `(table, column)`
+///
+/// Without this rules would cascade away with the source category and
+/// account defaults would be cleared.
+const MERGED_CATEGORY_REFERENCES: &[(&str, &str)] = &[
+    ("categorization_rules", "category_id"),
+    ("accounts", "default_category_id"),
+];
+
+/// Statement moving a user's `table.column` references from `?4` to `?1`
+fn reassign_category_reference_query(table: &str, column: &str) -> String {
+    format!(
+        "UPDATE {table} SET {column} = ?1, updated_at = ?2 WHERE user_id = ?3 AND {column} = ?4"
+    )
+}
+
 /// Create a new category
 #[tauri::command]
 pub async fn create_category(
@@ -35,6 +92,9 @@ pub async fn create_category(
     if let Some(ref parent_id) = request.parent_category_id {
         DatabaseUtils::validate_category_ownership(&db, parent_id, &request.user_id.as_str())
             .await?;
+
+        let parents = load_category_parents(&db, &request.user_id.as_str()).await?;
+        validate_parent_assignment(None, parent_id, &parents, max_category_depth())?;
     }
 
     // Check if category name already exists for this user
@@ -62,8 +122,8 @@ pub async fn create_category(
     let insert_query = r#"
         INSERT INTO categories (
             id, user_id, name, description, color, icon, parent_category_id, 
-            is_income, is_active, created_at, updated_at
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            is_income, is_active, created_at, updated_at, tax_deductible
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
     "#;
 
     let params = vec![
@@ -94,6 +154,7 @@ pub async fn create_category(
         Value::Bool(true),
         Value::String(now.clone()),
         Value::String(now),
+        Value::Bool(request.tax_deductible),
     ];
 
     DatabaseUtils::execute_non_query(&db, insert_query, params).await?;
@@ -136,7 +197,7 @@ pub async fn get_categories(
 
     let base_query = r#"
         SELECT id, user_id, name, description, color, icon, parent_category_id,
-               is_income, is_active, created_at, updated_at
+               is_income, tax_deductible, is_active, created_at, updated_at
         FROM categories
     "#;
 
@@ -171,7 +232,7 @@ pub async fn get_category_by_id(
 
     let query = r#"
         SELECT id, user_id, name, description, color, icon, parent_category_id,
-               is_income, is_active, created_at, updated_at
+               is_income, tax_deductible, is_active, created_at, updated_at
         FROM categories 
         WHERE id = ?1
     "#;
@@ -255,19 +316,14 @@ pub async fn update_category(
             Validator::validate_uuid(parent_id, "parent_category_id")?;
             DatabaseUtils::validate_category_ownership(&db, parent_id, &user_id).await?;
 
-            // Prevent circular reference
-            if parent_id == &category_id {
-                return Err(FiscusError::InvalidInput(
-                    "Category cannot be its own parent".to_string(),
-                ));
-            }
-
-            // Check if this would create a circular reference through the hierarchy
-            if is_circular_reference(&db, &category_id, parent_id).await? {
-                return Err(FiscusError::InvalidInput(
-                    "This would create a circular reference".to_string(),
-                ));
-            }
+            // Prevent circular references and overly deep hierarchies
+            let parents = load_category_parents(&db, &user_id).await?;
+            validate_parent_assignment(
+                Some(&category_id),
+                parent_id,
+                &parents,
+                max_category_depth(),
+            )?;
         }
 
         update_fields.push(format!("parent_category_id = ?{param_index}"));
@@ -285,6 +341,12 @@ pub async fn update_category(
         param_index += 1;
     }
 
+    if let Some(tax_deductible) = request.tax_deductible {
+        update_fields.push(format!("tax_deductible = ?{param_index}"));
+        params.push(Value::Bool(tax_deductible));
+        param_index += 1;
+    }
+
     if update_fields.is_empty() {
         return Err(FiscusError::InvalidInput("No fields to update".to_string()));
     }
@@ -393,7 +455,7 @@ pub async fn get_category_hierarchy(
 
     let mut base_query = r#"
         SELECT id, user_id, name, description, color, icon, parent_category_id,
-               is_income, is_active, created_at, updated_at
+               is_income, tax_deductible, is_active, created_at, updated_at
         FROM categories
         WHERE user_id = ?1 AND is_active = 1
     "#
@@ -410,38 +472,765 @@ pub async fn get_category_hierarchy(
 
     let categories: Vec<Category> = DatabaseUtils::execute_query(&db, &base_query, params).await?;
 
-    Ok(categories)
+    Ok(order_category_hierarchy(categories))
+}
+
+/// Merge a category into another category owned by the same user
+///
+/// Transactions, budgets, categorization rules and account default
+/// categories move from the source to the target, child categories are
+/// re-parented to the target and the source is deleted, all within one
+/// database transaction. A budget period
+/// holds one budget per category, so where the target already has a budget
+/// in a period the source budget's allocation and spending are added to it.
+#[tauri::command]
+pub async fn merge_categories(
+    user_id: String,
+    source_category_id: String,
+    target_category_id: String,
+    db: State<'_, Database>,
+) -> Result<CategoryMergeResponse, FiscusError> {
+    authorize_command("merge_categories").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_uuid(&source_category_id, "source_category_id")?;
+    Validator::validate_uuid(&target_category_id, "target_category_id")?;
+
+    // Validate ownership
+    DatabaseUtils::validate_category_ownership(&db, &source_category_id, &user_id).await?;
+    DatabaseUtils::validate_category_ownership(&db, &target_category_id, &user_id).await?;
+
+    let source = load_user_category(&db, &source_category_id, &user_id).await?;
+    let target = load_user_category(&db, &target_category_id, &user_id).await?;
+    validate_category_merge(&source, &target, &user_id)?;
+
+    let parents = load_category_parents(&db, &user_id).await?;
+    validate_reparented_children(&source.id, &target.id, &parents, max_category_depth())?;
+
+    let budget_query = r#"
+        SELECT id, user_id, budget_period_id, category_id, allocated_amount, spent_amount,
+               notes, created_at, updated_at
+        FROM budgets
+        WHERE user_id = ?1 AND category_id IN (?2, ?3)
+    "#;
+    let budgets: Vec<Budget> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        budget_query,
+        vec![
+            Value::String(user_id.clone()),
+            Value::String(source.id.clone()),
+            Value::String(target.id.clone()),
+        ],
+        &user_id,
+        "budgets",
+    )
+    .await?;
+    let (combined_budgets, absorbed_budget_ids) =
+        combine_overlapping_budgets(&budgets, &source.id, &target.id);
+
+    let count_query = r#"
+        SELECT category_id, COUNT(*) as count
+        FROM transactions
+        WHERE user_id = ?1 AND category_id IN (?2, ?3)
+        GROUP BY category_id
+    "#;
+    let count_rows: Vec<HashMap<String, Value>> = DatabaseUtils::execute_query(
+        &db,
+        count_query,
+        vec![
+            Value::String(user_id.clone()),
+            Value::String(source.id.clone()),
+            Value::String(target.id.clone()),
+        ],
+    )
+    .await?;
+    let counts = transaction_counts_by_category(&count_rows);
+    let target_count_before = counts.get(&target.id).copied().unwrap_or(0);
+
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let response = with_transaction!(&*db, async {
+        let reassign_transactions = r#"
+            UPDATE transactions SET category_id = ?1, updated_at = ?2
+            WHERE user_id = ?3 AND category_id = ?4
+        "#;
+        let transactions_reassigned = DatabaseUtils::execute_non_query(
+            &db,
+            reassign_transactions,
+            vec![
+                Value::String(target.id.clone()),
+                Value::String(now.clone()),
+                Value::String(user_id.clone()),
+                Value::String(source.id.clone()),
+            ],
+        )
+        .await?;
+
+        // Fold source budgets into the target's budget for the same period
+        for budget in &combined_budgets {
+            let params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+                vec![
+                    (
+                        "allocated_amount".to_string(),
+                        Value::String(budget.allocated_amount.to_string()),
+                    ),
+                    (
+                        "spent_amount".to_string(),
+                        Value::String(budget.spent_amount.to_string()),
+                    ),
+                    ("updated_at".to_string(), Value::String(now.clone())),
+                    ("id".to_string(), Value::String(budget.id.clone())),
+                ],
+                &user_id,
+                "budgets",
+            )
+            .await?;
+            DatabaseUtils::execute_non_query(
+                &db,
+                "UPDATE budgets SET allocated_amount = ?1, spent_amount = ?2, updated_at = ?3 WHERE id = ?4",
+                params,
+            )
+            .await?;
+        }
+
+        for budget_id in &absorbed_budget_ids {
+            DatabaseUtils::execute_non_query(
+                &db,
+                "DELETE FROM budgets WHERE id = ?1",
+                vec![Value::String(budget_id.clone())],
+            )
+            .await?;
+        }
+
+        let reassign_budgets = r#"
+            UPDATE budgets SET category_id = ?1, updated_at = ?2
+            WHERE user_id = ?3 AND category_id = ?4
+        "#;
+        let budgets_reassigned = DatabaseUtils::execute_non_query(
+            &db,
+            reassign_budgets,
+            vec![
+                Value::String(target.id.clone()),
+                Value::String(now.clone()),
+                Value::String(user_id.clone()),
+                Value::String(source.id.clone()),
+            ],
+        )
+        .await?;
+
+        let mut references_reassigned = 0;
+        for (table, column) in MERGED_CATEGORY_REFERENCES {
+            references_reassigned += DatabaseUtils::execute_non_query(
+                &db,
+                &reassign_category_reference_query(table, column),
+                vec![
+                    Value::String(target.id.clone()),
+                    Value::String(now.clone()),
+                    Value::String(user_id.clone()),
+                    Value::String(source.id.clone()),
+                ],
+            )
+            .await?;
+        }
+
+        let reparent_children = r#"
+            UPDATE categories SET parent_category_id = ?1, updated_at = ?2
+            WHERE user_id = ?3 AND parent_category_id = ?4
+        "#;
+        let categories_reparented = DatabaseUtils::execute_non_query(
+            &db,
+            reparent_children,
+            vec![
+                Value::String(target.id.clone()),
+                Value::String(now.clone()),
+                Value::String(user_id.clone()),
+                Value::String(source.id.clone()),
+            ],
+        )
+        .await?;
+
+        let deleted = DatabaseUtils::execute_non_query(
+            &db,
+            "DELETE FROM categories WHERE id = ?1 AND user_id = ?2",
+            vec![
+                Value::String(source.id.clone()),
+                Value::String(user_id.clone()),
+            ],
+        )
+        .await?;
+
+        if deleted == 0 {
+            return Err(FiscusError::NotFound("Category not found".to_string()));
+        }
+
+        Ok::<CategoryMergeResponse, FiscusError>(CategoryMergeResponse {
+            target_category_id: target.id.clone(),
+            transactions_reassigned,
+            target_transaction_count: target_count_before + transactions_reassigned,
+            budgets_reassigned,
+            budgets_combined: absorbed_budget_ids.len() as u64,
+            references_reassigned,
+            categories_reparented,
+        })
+    })?;
+
+    Ok(response)
 }
 
-/// Helper function to check for circular references in category hierarchy
-async fn is_circular_reference(
+/// Load a category, treating one owned by another user as missing
+async fn load_user_category(
     db: &Database,
     category_id: &str,
+    user_id: &str,
+) -> FiscusResult<Category> {
+    let query = r#"
+        SELECT id, user_id, name, description, color, icon, parent_category_id,
+               is_income, tax_deductible, is_active, created_at, updated_at
+        FROM categories
+        WHERE id = ?1 AND user_id = ?2
+    "#;
+
+    let category: Option<Category> = DatabaseUtils::execute_query_single(
+        db,
+        query,
+        vec![
+            Value::String(category_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+    )
+    .await?;
+
+    category.ok_or_else(|| FiscusError::NotFound("Category not found".to_string()))
+}
+
+/// Check that `source` can be merged into `target` on behalf of `user_id`
+fn validate_category_merge(
+    source: &Category,
+    target: &Category,
+    user_id: &str,
+) -> FiscusResult<()> {
+    if source.id == target.id {
+        return Err(FiscusError::InvalidInput(
+            "Cannot merge a category into itself".to_string(),
+        ));
+    }
+
+    if source.user_id != user_id || target.user_id != user_id {
+        return Err(FiscusError::NotFound("Category not found".to_string()));
+    }
+
+    if !target.is_active {
+        return Err(FiscusError::Validation(
+            "Cannot merge into an inactive category".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that moving the source's children under the target keeps the
+/// hierarchy acyclic and within `max_depth` levels
+///
+/// Rejects merging a category into one of its own descendants.
+fn validate_reparented_children(
+    source_id: &str,
+    target_id: &str,
+    parents: &HashMap<String, Option<String>>,
+    max_depth: usize,
+) -> FiscusResult<()> {
+    let merged = merged_category_parents(source_id, target_id, parents);
+
+    for (child_id, parent_id) in parents {
+        if parent_id.as_deref() == Some(source_id) {
+            validate_parent_assignment(Some(child_id), target_id, &merged, max_depth)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Category parents after `source_id` is merged into `target_id`
+fn merged_category_parents(
+    source_id: &str,
+    target_id: &str,
+    parents: &HashMap<String, Option<String>>,
+) -> HashMap<String, Option<String>> {
+    parents
+        .iter()
+        .filter(|(id, _)| id.as_str() != source_id)
+        .map(|(id, parent)| {
+            let parent = match parent.as_deref() {
+                Some(parent_id) if parent_id == source_id => Some(target_id.to_string()),
+                _ => parent.clone(),
+            };
+            (id.clone(), parent)
+        })
+        .collect()
+}
+
+/// Pair source budgets with target budgets in the same period
+///
+/// Returns the target budgets with the source amounts added, and the IDs of
+/// the source budgets they absorbed. Source budgets in periods the target has
+/// no budget for are left out; they are simply moved to the target.
+fn combine_overlapping_budgets(
+    budgets: &[Budget],
+    source_id: &str,
+    target_id: &str,
+) -> (Vec<Budget>, Vec<String>) {
+    let source_by_period: HashMap<&str, &Budget> = budgets
+        .iter()
+        .filter(|budget| budget.category_id == source_id)
+        .map(|budget| (budget.budget_period_id.as_str(), budget))
+        .collect();
+
+    let mut combined = Vec::new();
+    let mut absorbed = Vec::new();
+    for target_budget in budgets.iter().filter(|b| b.category_id == target_id) {
+        if let Some(source_budget) = source_by_period.get(target_budget.budget_period_id.as_str()) {
+            let mut merged = target_budget.clone();
+            merged.allocated_amount += source_budget.allocated_amount;
+            merged.spent_amount += source_budget.spent_amount;
+            combined.push(merged);
+            absorbed.push(source_budget.id.clone());
+        }
+    }
+
+    (combined, absorbed)
+}
+
+/// Transaction counts keyed by category ID from `category_id, count` rows
+fn transaction_counts_by_category(rows: &[HashMap<String, Value>]) -> HashMap<String, u64> {
+    rows.iter()
+        .filter_map(|row| {
+            let category_id = row.get("category_id")?.as_str()?.to_string();
+            let count = row.get("count")?.as_u64()?;
+            Some((category_id, count))
+        })
+        .collect()
+}
+
+/// Load the parent of every category a user owns, keyed by category ID
+async fn load_category_parents(
+    db: &Database,
+    user_id: &str,
+) -> FiscusResult<HashMap<String, Option<String>>> {
+    let query = "SELECT id, parent_category_id FROM categories WHERE user_id = ?1";
+    let rows: Vec<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query(db, query, vec![Value::String(user_id.to_string())]).await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let id = row.get("id")?.as_str()?.to_string();
+            let parent_id = row
+                .get("parent_category_id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            Some((id, parent_id))
+        })
+        .collect())
+}
+
+/// Check that making `proposed_parent_id` the parent of `category_id` keeps the
+/// hierarchy acyclic and within `max_depth` levels
+///
+/// `category_id` is `None` when validating a category that does not exist yet.
+fn validate_parent_assignment(
+    category_id: Option<&str>,
     proposed_parent_id: &str,
-) -> FiscusResult<bool> {
-    let mut current_parent = Some(proposed_parent_id.to_string());
-    let mut visited = std::collections::HashSet::new();
+    parents: &HashMap<String, Option<String>>,
+    max_depth: usize,
+) -> FiscusResult<()> {
+    // Walk up from the proposed parent, counting its depth
+    let mut parent_depth = 0;
+    let mut visited = HashSet::new();
+    let mut current = Some(proposed_parent_id.to_string());
+
+    while let Some(ancestor_id) = current {
+        if Some(ancestor_id.as_str()) == category_id {
+            return Err(FiscusError::Conflict(
+                "This would create a circular reference".to_string(),
+            ));
+        }
+
+        if !visited.insert(ancestor_id.clone()) {
+            return Err(FiscusError::Conflict(
+                "Category hierarchy already contains a circular reference".to_string(),
+            ));
+        }
+
+        parent_depth += 1;
+        current = parents.get(&ancestor_id).cloned().flatten();
+    }
+
+    // A moved category brings its whole subtree along
+    let subtree_height = match category_id {
+        Some(id) => category_subtree_height(id, parents),
+        None => 1,
+    };
+
+    if parent_depth + subtree_height > max_depth {
+        return Err(FiscusError::Validation(format!(
+            "Category hierarchy cannot be deeper than {max_depth} levels"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Number of levels in the subtree rooted at `category_id` (1 for a leaf)
+fn category_subtree_height(category_id: &str, parents: &HashMap<String, Option<String>>) -> usize {
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, parent_id) in parents {
+        if let Some(parent_id) = parent_id {
+            children
+                .entry(parent_id.as_str())
+                .or_default()
+                .push(id.as_str());
+        }
+    }
+
+    let mut height = 0;
+    let mut visited = HashSet::new();
+    let mut stack = vec![(category_id, 1)];
+
+    while let Some((id, depth)) = stack.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        height = height.max(depth);
+        if let Some(child_ids) = children.get(id) {
+            stack.extend(child_ids.iter().map(|child_id| (*child_id, depth + 1)));
+        }
+    }
+
+    height
+}
 
-    while let Some(parent_id) = current_parent {
-        if parent_id == category_id {
-            return Ok(true); // Circular reference detected
+/// Order categories depth-first so every parent precedes its children
+///
+/// Traversal tracks visited categories and is bounded by the number of
+/// categories, so corrupt data containing a cycle cannot loop forever.
+/// Categories only reachable through a cycle are left out.
+fn order_category_hierarchy(categories: Vec<Category>) -> Vec<Category> {
+    let max_depth = categories.len();
+    let ids: HashSet<String> = categories.iter().map(|c| c.id.clone()).collect();
+
+    let mut roots = Vec::new();
+    let mut children: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, category) in categories.iter().enumerate() {
+        match &category.parent_category_id {
+            // Parents filtered out of the result are treated as missing
+            Some(parent_id) if ids.contains(parent_id) => {
+                children.entry(parent_id.clone()).or_default().push(index)
+            }
+            _ => roots.push(index),
         }
+    }
 
-        if visited.contains(&parent_id) {
-            return Ok(true); // Circular reference detected
+    let mut slots: Vec<Option<Category>> = categories.into_iter().map(Some).collect();
+    let mut ordered = Vec::with_capacity(slots.len());
+    let mut visited = HashSet::new();
+    let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|i| (i, 1)).collect();
+
+    while let Some((index, depth)) = stack.pop() {
+        if depth > max_depth || !visited.insert(index) {
+            continue;
         }
+        let Some(category) = slots[index].take() else {
+            continue;
+        };
+
+        if let Some(child_indices) = children.get(&category.id) {
+            stack.extend(child_indices.iter().rev().map(|child| (*child, depth + 1)));
+        }
+        ordered.push(category);
+    }
+
+    let skipped = slots.iter().filter(|slot| slot.is_some()).count();
+    if skipped > 0 {
+        warn!(
+            skipped_categories = skipped,
+            "Omitted categories that form a circular parent reference"
+        );
+    }
+
+    ordered
+}
 
-        visited.insert(parent_id.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_database::{sign_in, TestDatabase},
+        test_utils::TestUtils,
+    };
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use tauri::Manager;
+
+    fn parents(pairs: &[(&str, Option<&str>)]) -> HashMap<String, Option<String>> {
+        pairs
+            .iter()
+            .map(|(id, parent)| (id.to_string(), parent.map(|p| p.to_string())))
+            .collect()
+    }
+
+    fn category(id: &str, parent: Option<&str>) -> Category {
+        Category {
+            id: id.to_string(),
+            user_id: "user".to_string(),
+            name: id.to_string(),
+            description: None,
+            color: None,
+            icon: None,
+            parent_category_id: parent.map(|p| p.to_string()),
+            is_income: false,
+            tax_deductible: false,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
 
-        // Get the parent of the current parent
-        let query = "SELECT parent_category_id FROM categories WHERE id = ?1";
-        let result: Option<HashMap<String, serde_json::Value>> =
-            DatabaseUtils::execute_query_single(db, query, vec![Value::String(parent_id)]).await?;
+    #[test]
+    fn test_direct_cycle_is_rejected() {
+        let parents = parents(&[("a", None)]);
 
-        current_parent = result
-            .and_then(|row| row.get("parent_category_id").cloned())
-            .and_then(|v| v.as_str().map(|s| s.to_string()));
+        let result = validate_parent_assignment(Some("a"), "a", &parents, 5);
+        assert!(matches!(result, Err(FiscusError::Conflict(_))));
     }
 
-    Ok(false)
+    #[test]
+    fn test_indirect_cycle_is_rejected() {
+        // b is a child of a, so a cannot become a child of b
+        let parents = parents(&[("a", None), ("b", Some("a"))]);
+
+        let result = validate_parent_assignment(Some("a"), "b", &parents, 5);
+        assert!(matches!(result, Err(FiscusError::Conflict(_))));
+    }
+
+    #[test]
+    fn test_exceeding_max_depth_is_rejected() {
+        let parents = parents(&[
+            ("l1", None),
+            ("l2", Some("l1")),
+            ("l3", Some("l2")),
+            ("l4", Some("l3")),
+            ("l5", Some("l4")),
+        ]);
+
+        // A new child of l4 is the fifth level, a child of l5 would be the sixth
+        assert!(validate_parent_assignment(None, "l4", &parents, 5).is_ok());
+        let result = validate_parent_assignment(None, "l5", &parents, 5);
+        assert!(matches!(result, Err(FiscusError::Validation(_))));
+    }
+
+    #[test]
+    fn test_configured_depth_limits_nesting() {
+        assert_eq!(
+            CategoryConfig::default().max_depth,
+            DEFAULT_MAX_CATEGORY_DEPTH
+        );
+
+        let parents = parents(&[("l1", None), ("l2", Some("l1"))]);
+        let config = CategoryConfig { max_depth: 2 };
+        let result = validate_parent_assignment(None, "l2", &parents, config.max_depth);
+        assert!(matches!(result, Err(FiscusError::Validation(_))));
+    }
+
+    #[test]
+    fn test_moving_subtree_counts_its_height() {
+        let parents = parents(&[
+            ("root", None),
+            ("mid", Some("root")),
+            ("other", None),
+            ("child", Some("other")),
+            ("grandchild", Some("child")),
+        ]);
+
+        // other (3 levels) under mid (depth 2) reaches 5 levels
+        assert!(validate_parent_assignment(Some("other"), "mid", &parents, 5).is_ok());
+        let result = validate_parent_assignment(Some("other"), "mid", &parents, 4);
+        assert!(matches!(result, Err(FiscusError::Validation(_))));
+    }
+
+    #[test]
+    fn test_hierarchy_orders_parents_before_children() {
+        let categories = vec![
+            category("food", None),
+            category("travel", None),
+            category("groceries", Some("food")),
+            category("flights", Some("travel")),
+        ];
+
+        let ordered: Vec<String> = order_category_hierarchy(categories)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+
+        assert_eq!(ordered, vec!["food", "groceries", "travel", "flights"]);
+    }
+
+    #[test]
+    fn test_merge_rejects_same_category_and_foreign_categories() {
+        let source = category("source", None);
+        let target = category("target", None);
+
+        let result = validate_category_merge(&source, &source, "user");
+        assert!(matches!(result, Err(FiscusError::InvalidInput(_))));
+
+        let mut foreign = category("foreign", None);
+        foreign.user_id = "someone-else".to_string();
+        let result = validate_category_merge(&source, &foreign, "user");
+        assert!(matches!(result, Err(FiscusError::NotFound(_))));
+
+        assert!(validate_category_merge(&source, &target, "user").is_ok());
+    }
+
+    #[test]
+    fn test_merge_reparents_children_to_target() {
+        let parents = parents(&[
+            ("source", None),
+            ("target", None),
+            ("child_a", Some("source")),
+            ("child_b", Some("source")),
+            ("grandchild", Some("child_a")),
+        ]);
+
+        assert!(validate_reparented_children("source", "target", &parents, 5).is_ok());
+
+        let merged = merged_category_parents("source", "target", &parents);
+        assert!(!merged.contains_key("source"));
+        assert_eq!(merged["child_a"].as_deref(), Some("target"));
+        assert_eq!(merged["child_b"].as_deref(), Some("target"));
+        assert_eq!(merged["grandchild"].as_deref(), Some("child_a"));
+    }
+
+    #[test]
+    fn test_merge_into_descendant_is_rejected() {
+        let parents = parents(&[
+            ("source", None),
+            ("child", Some("source")),
+            ("grandchild", Some("child")),
+        ]);
+
+        let result = validate_reparented_children("source", "grandchild", &parents, 5);
+        assert!(matches!(result, Err(FiscusError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_transactions_and_children_to_target() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+
+        let user = test_db.seed_user("merger").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let source = test_db
+            .seed_category(&user.id, "Dining", None)
+            .await
+            .unwrap();
+        let target = test_db.seed_category(&user.id, "Food", None).await.unwrap();
+        let child = test_db
+            .seed_category(&user.id, "Takeaway", Some(&source.id))
+            .await
+            .unwrap();
+        for category_id in [&source.id, &source.id, &source.id, &target.id, &target.id] {
+            test_db
+                .seed_transaction(
+                    &user.id,
+                    &account.id,
+                    Some(category_id),
+                    Decimal::new(1000, 2),
+                )
+                .await
+                .unwrap();
+        }
+        let _session = sign_in(&user.id).await;
+
+        let response = merge_categories(
+            user.id.clone(),
+            source.id.clone(),
+            target.id.clone(),
+            app.state(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.transactions_reassigned, 3);
+        assert_eq!(response.target_transaction_count, 5);
+        assert_eq!(response.categories_reparented, 1);
+
+        let count_in = |category_id: &str| {
+            test_db.fetch_text(
+                "SELECT CAST(COUNT(*) AS TEXT) FROM transactions WHERE category_id = ?1",
+                vec![Value::String(category_id.to_string())],
+            )
+        };
+        assert_eq!(count_in(&target.id).await.unwrap(), "5");
+        assert_eq!(count_in(&source.id).await.unwrap(), "0");
+
+        let child_parent = test_db
+            .fetch_text(
+                "SELECT parent_category_id FROM categories WHERE id = ?1",
+                vec![Value::String(child.id.clone())],
+            )
+            .await
+            .unwrap();
+        assert_eq!(child_parent, target.id);
+
+        let source_rows = test_db
+            .fetch_text(
+                "SELECT CAST(COUNT(*) AS TEXT) FROM categories WHERE id = ?1",
+                vec![Value::String(source.id.clone())],
+            )
+            .await
+            .unwrap();
+        assert_eq!(source_rows, "0");
+    }
+
+    #[test]
+    fn test_merge_combines_budgets_in_shared_periods() {
+        let source_shared =
+            TestUtils::create_test_budget("user", "period-1", "source", Decimal::new(100, 0));
+        let source_only =
+            TestUtils::create_test_budget("user", "period-2", "source", Decimal::new(40, 0));
+        let mut target_shared =
+            TestUtils::create_test_budget("user", "period-1", "target", Decimal::new(250, 0));
+        target_shared.spent_amount = Decimal::new(75, 0);
+        let target_only =
+            TestUtils::create_test_budget("user", "period-3", "target", Decimal::new(60, 0));
+
+        let budgets = vec![
+            source_shared.clone(),
+            source_only,
+            target_shared.clone(),
+            target_only,
+        ];
+        let (combined, absorbed) = combine_overlapping_budgets(&budgets, "source", "target");
+
+        assert_eq!(absorbed, vec![source_shared.id]);
+        assert_eq!(combined.len(), 1);
+        assert_eq!(combined[0].id, target_shared.id);
+        assert_eq!(combined[0].allocated_amount, Decimal::new(350, 0));
+        assert_eq!(combined[0].spent_amount, Decimal::new(75, 0));
+    }
+
+    #[test]
+    fn test_hierarchy_builder_terminates_on_cycle() {
+        let categories = vec![
+            category("root", None),
+            category("a", Some("b")),
+            category("b", Some("a")),
+        ];
+
+        let ordered = order_category_hierarchy(categories);
+        assert_eq!(ordered.len(), 1);
+        assert_eq!(ordered[0].id, "root");
+    }
 }
@@ -0,0 +1,334 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::str::FromStr;
+use tauri::State;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
+    error::{FiscusError, FiscusResult, Validator},
+    models::{CategorizationRule, RuleMatchField, Transaction},
+    security::authorize_command,
+    services::events::{self, TransactionEvent},
+    with_transaction,
+};
+
+/// Define a rule that `apply_categorization_rules` uses to assign a category
+///
+/// Payee and description rules match a case-insensitive substring; amount
+/// rules take an inclusive `min..max` range of amounts, e.g. `10..50`, `..5`
+/// or `1000..`. Rules with a higher `priority` are tried first.
+#[tauri::command]
+pub async fn create_categorization_rule(
+    user_id: String,
+    match_field: RuleMatchField,
+    pattern: String,
+    category_id: String,
+    priority: Option<i32>,
+    db: State<'_, Database>,
+) -> Result<CategorizationRule, FiscusError> {
+    authorize_command("create_categorization_rule").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_uuid(&category_id, "category_id")?;
+    let pattern = pattern.trim().to_string();
+    Validator::validate_string(&pattern, "pattern", 1, 200)?;
+    if match_field == RuleMatchField::Amount {
+        parse_amount_range(&pattern)?;
+    }
+
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+    DatabaseUtils::validate_category_ownership(&db, &category_id, &user_id).await?;
+
+    let now = Utc::now();
+    let rule = CategorizationRule {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        match_field,
+        pattern,
+        category_id,
+        priority: priority.unwrap_or(0),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let params_with_mapping = vec![
+        ("id".to_string(), Value::String(rule.id.clone())),
+        ("user_id".to_string(), Value::String(rule.user_id.clone())),
+        (
+            "match_field".to_string(),
+            Value::String(rule.match_field.to_string()),
+        ),
+        ("pattern".to_string(), Value::String(rule.pattern.clone())),
+        (
+            "category_id".to_string(),
+            Value::String(rule.category_id.clone()),
+        ),
+        ("priority".to_string(), Value::from(rule.priority)),
+        ("created_at".to_string(), Value::String(now.to_rfc3339())),
+        ("updated_at".to_string(), Value::String(now.to_rfc3339())),
+    ];
+
+    let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+        params_with_mapping,
+        &rule.user_id,
+        "categorization_rules",
+    )
+    .await?;
+
+    DatabaseUtils::execute_non_query(
+        &db,
+        r#"
+        INSERT INTO categorization_rules (
+            id, user_id, match_field, pattern, category_id, priority, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+        encrypted_params,
+    )
+    .await?;
+
+    Ok(rule)
+}
+
+/// Categorize transactions using the user's categorization rules
+///
+/// Only uncategorized transactions are considered unless `include_categorized`
+/// is set, in which case matching transactions are recategorized too.
+/// Transfers are never touched. Each transaction takes the category of the
+/// highest-priority rule it matches, and all updates are written in one
+/// database transaction. Returns how many transactions changed category.
+#[tauri::command]
+pub async fn apply_categorization_rules(
+    user_id: String,
+    include_categorized: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<usize, FiscusError> {
+    authorize_command("apply_categorization_rules").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let rules: Vec<CategorizationRule> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        r#"
+        SELECT id, user_id, match_field, pattern, category_id, priority, created_at, updated_at
+        FROM categorization_rules
+        WHERE user_id = ?1
+        ORDER BY priority DESC, created_at, id
+        "#,
+        vec![Value::String(user_id.clone())],
+        &user_id,
+        "categorization_rules",
+    )
+    .await?;
+    if rules.is_empty() {
+        return Ok(0);
+    }
+
+    let uncategorized_only = if include_categorized.unwrap_or(false) {
+        ""
+    } else {
+        "AND category_id IS NULL"
+    };
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        &format!(
+            r#"
+            SELECT id, user_id, account_id, category_id, amount, description, notes,
+                   transaction_date, transaction_type, status, reference_number, payee, tags,
+                   original_amount, original_currency, refunds_transaction_id,
+                   created_at, updated_at
+            FROM transactions
+            WHERE user_id = ?1 AND transaction_type != 'transfer' {uncategorized_only}
+            "#
+        ),
+        vec![Value::String(user_id.clone())],
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    let assignments = match_categorizations(&rules, &transactions);
+    if assignments.is_empty() {
+        return Ok(0);
+    }
+
+    let now = Utc::now();
+    with_transaction!(&*db, async {
+        for (transaction_id, category_id) in &assignments {
+            DatabaseUtils::execute_non_query(
+                &db,
+                "UPDATE transactions SET category_id = ?1, updated_at = ?2 WHERE id = ?3 AND user_id = ?4",
+                vec![
+                    Value::String(category_id.clone()),
+                    Value::String(now.to_rfc3339()),
+                    Value::String(transaction_id.clone()),
+                    Value::String(user_id.clone()),
+                ],
+            )
+            .await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    events::publish(assignments.iter().map(|(transaction_id, _)| {
+        TransactionEvent::TransactionUpdated {
+            user_id: user_id.clone(),
+            transaction_id: transaction_id.clone(),
+        }
+    }));
+
+    info!(
+        user_id = %user_id,
+        rule_count = rules.len(),
+        categorized_count = assignments.len(),
+        "Applied categorization rules"
+    );
+
+    Ok(assignments.len())
+}
+
+/// Category each transaction should move to, as `(transaction_id, category_id)`
+///
+/// `rules` must already be in priority order; the first matching rule wins.
+/// Transactions without a match, or already in the matched category, are left out.
+pub(crate) fn match_categorizations(
+    rules: &[CategorizationRule],
+    transactions: &[Transaction],
+) -> Vec<(String, String)> {
+    transactions
+        .iter()
+        .filter_map(|transaction| {
+            let rule = rules.iter().find(|rule| rule_matches(rule, transaction))?;
+            (transaction.category_id.as_deref() != Some(rule.category_id.as_str()))
+                .then(|| (transaction.id.clone(), rule.category_id.clone()))
+        })
+        .collect()
+}
+
+/// Whether `rule` matches `transaction`
+///
+/// A malformed amount range never matches, so one bad rule cannot block the rest.
+fn rule_matches(rule: &CategorizationRule, transaction: &Transaction) -> bool {
+    let contains = |text: Option<&str>| {
+        text.is_some_and(|text| text.to_lowercase().contains(&rule.pattern.to_lowercase()))
+    };
+
+    match rule.match_field {
+        RuleMatchField::Payee => contains(transaction.payee.as_deref()),
+        RuleMatchField::Description => contains(Some(&transaction.description)),
+        RuleMatchField::Amount => match parse_amount_range(&rule.pattern) {
+            Ok((min, max)) => {
+                let amount = transaction.amount.abs();
+                min.is_none_or(|min| amount >= min) && max.is_none_or(|max| amount <= max)
+            }
+            Err(_) => false,
+        },
+    }
+}
+
+/// Parse an inclusive `min..max` amount range; either bound may be omitted
+pub(crate) fn parse_amount_range(
+    pattern: &str,
+) -> FiscusResult<(Option<Decimal>, Option<Decimal>)> {
+    let invalid =
+        |message: &str| FiscusError::field_validation("pattern", "invalid_format", message);
+
+    let (min, max) = pattern
+        .split_once("..")
+        .ok_or_else(|| invalid("Amount rules need a range such as 10..50, ..5 or 1000.."))?;
+    let bound = |value: &str| -> FiscusResult<Option<Decimal>> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        let amount =
+            Decimal::from_str(value).map_err(|_| invalid("Range bounds must be amounts"))?;
+        if amount.is_sign_negative() {
+            return Err(invalid("Range bounds must not be negative"));
+        }
+        Ok(Some(amount))
+    };
+
+    match (bound(min)?, bound(max)?) {
+        (None, None) => Err(invalid("Amount range needs at least one bound")),
+        (Some(min), Some(max)) if min > max => {
+            Err(invalid("Range minimum must not exceed its maximum"))
+        }
+        range => Ok(range),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::TransactionType, test_utils::TestUtils};
+
+    fn rule(match_field: RuleMatchField, pattern: &str, category_id: &str) -> CategorizationRule {
+        let now = Utc::now();
+        CategorizationRule {
+            id: Uuid::new_v4().to_string(),
+            user_id: "user".to_string(),
+            match_field,
+            pattern: pattern.to_string(),
+            category_id: category_id.to_string(),
+            priority: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn expense(amount: Decimal, payee: Option<&str>) -> Transaction {
+        let mut transaction =
+            TestUtils::create_test_transaction("user", "account", amount, TransactionType::Expense);
+        transaction.payee = payee.map(str::to_string);
+        transaction
+    }
+
+    #[test]
+    fn test_payee_rule_categorizes_only_matching_transactions() {
+        let rules = vec![rule(RuleMatchField::Payee, "coffee", "cafes")];
+        let matching = expense(Decimal::new(450, 2), Some("Corner Coffee Co"));
+        let other_payee = expense(Decimal::new(450, 2), Some("Grocer"));
+        let no_payee = expense(Decimal::new(450, 2), None);
+
+        let assignments = match_categorizations(&rules, &[matching.clone(), other_payee, no_payee]);
+
+        assert_eq!(assignments, vec![(matching.id, "cafes".to_string())]);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins_and_unchanged_rows_are_skipped() {
+        let rules = vec![
+            rule(RuleMatchField::Amount, "1000..", "large"),
+            rule(RuleMatchField::Description, "rent", "housing"),
+        ];
+        let mut rent = expense(Decimal::new(1200, 0), None);
+        rent.description = "Monthly RENT".to_string();
+        let mut already_large = expense(Decimal::new(5000, 0), None);
+        already_large.category_id = Some("large".to_string());
+
+        let assignments = match_categorizations(&rules, &[rent.clone(), already_large]);
+
+        assert_eq!(assignments, vec![(rent.id, "large".to_string())]);
+    }
+
+    #[test]
+    fn test_amount_ranges_are_validated() {
+        assert_eq!(
+            parse_amount_range("10..50").unwrap(),
+            (Some(Decimal::new(10, 0)), Some(Decimal::new(50, 0)))
+        );
+        assert_eq!(
+            parse_amount_range("..5.50").unwrap(),
+            (None, Some(Decimal::new(550, 2)))
+        );
+        assert!(parse_amount_range("..").is_err());
+        assert!(parse_amount_range("50..10").is_err());
+        assert!(parse_amount_range("-5..10").is_err());
+        assert!(parse_amount_range("ten").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::State;
+use tracing::info;
+
+use crate::{
+    database::{Database, DatabaseUtils},
+    error::{
+        custom_currency_minor_units, register_custom_currency_code, FiscusError, FiscusResult,
+        ValidatedCurrency, Validator,
+    },
+    models::CustomCurrency,
+    security::authorize_command,
+    utils::parse_decimal_from_json,
+};
+
+/// ISO 4217 currencies without a minor unit
+const ZERO_DECIMAL_CURRENCIES: &[&str] = &["JPY", "KRW", "VND", "CLP", "IDR"];
+
+/// ISO 4217 currencies with three decimal places
+const THREE_DECIMAL_CURRENCIES: &[&str] = &["KWD", "BHD", "OMR", "JOD", "TND"];
+
+/// Register a custom currency (e.g. a cryptocurrency or local scrip)
+///
+/// Once registered the code is accepted anywhere a currency is validated.
+/// Registering an existing code again with the same minor units is a no-op.
+#[tauri::command]
+pub async fn register_custom_currency(
+    code: String,
+    minor_units: u32,
+    db: State<'_, Database>,
+) -> Result<CustomCurrency, FiscusError> {
+    authorize_command("register_custom_currency").await?;
+
+    let code = Validator::validate_custom_currency(&code, minor_units)?;
+
+    let existing: Option<CustomCurrency> = DatabaseUtils::execute_query_single(
+        &db,
+        "SELECT code, minor_units, created_at FROM custom_currencies WHERE code = ?1",
+        vec![Value::String(code.clone())],
+    )
+    .await?;
+
+    let currency = match existing {
+        Some(existing) if existing.minor_units != minor_units => {
+            return Err(FiscusError::Conflict(format!(
+                "Custom currency {code} is already registered with {} minor units",
+                existing.minor_units
+            )));
+        }
+        Some(existing) => existing,
+        None => {
+            let currency = CustomCurrency {
+                code,
+                minor_units,
+                created_at: chrono::Utc::now(),
+            };
+
+            DatabaseUtils::execute_non_query(
+                &db,
+                "INSERT INTO custom_currencies (code, minor_units, created_at) VALUES (?1, ?2, ?3)",
+                vec![
+                    Value::String(currency.code.clone()),
+                    Value::from(currency.minor_units),
+                    Value::String(currency.created_at.to_rfc3339()),
+                ],
+            )
+            .await?;
+
+            info!(code = %currency.code, minor_units, "Registered custom currency");
+            currency
+        }
+    };
+
+    register_custom_currency_code(&currency.code, currency.minor_units)?;
+    Ok(currency)
+}
+
+/// List registered custom currencies, loading them into the runtime allowlist
+///
+/// The frontend calls this at startup so stored custom codes validate before
+/// any account or transaction that uses them is read.
+#[tauri::command]
+pub async fn get_custom_currencies(
+    db: State<'_, Database>,
+) -> Result<Vec<CustomCurrency>, FiscusError> {
+    load_custom_currencies(&db).await
+}
+
+/// Read every stored custom currency and add it to the runtime allowlist
+pub(crate) async fn load_custom_currencies(db: &Database) -> FiscusResult<Vec<CustomCurrency>> {
+    let currencies: Vec<CustomCurrency> = DatabaseUtils::execute_query(
+        db,
+        "SELECT code, minor_units, created_at FROM custom_currencies ORDER BY code",
+        Vec::new(),
+    )
+    .await?;
+
+    for currency in &currencies {
+        register_custom_currency_code(&currency.code, currency.minor_units)?;
+    }
+
+    Ok(currencies)
+}
+
+/// Store the rate at which one unit of `base_currency` converts to `quote_currency`
+///
+/// A rate applies from `rate_date` until a later one is stored; setting a
+/// rate for an existing date replaces it.
+#[tauri::command]
+pub async fn set_exchange_rate(
+    base_currency: ValidatedCurrency,
+    quote_currency: ValidatedCurrency,
+    rate: Decimal,
+    rate_date: String,
+    db: State<'_, Database>,
+) -> Result<(), FiscusError> {
+    authorize_command("set_exchange_rate").await?;
+
+    let rate_date = Validator::validate_date(&rate_date, "rate_date")?;
+    if base_currency == quote_currency {
+        return Err(FiscusError::field_validation(
+            "quote_currency",
+            "invalid_value",
+            "quote_currency must differ from base_currency",
+        ));
+    }
+    if rate <= Decimal::ZERO {
+        return Err(FiscusError::field_validation(
+            "rate",
+            "out_of_range",
+            "Exchange rate must be positive",
+        ));
+    }
+
+    DatabaseUtils::execute_non_query(
+        &db,
+        r#"
+        INSERT OR REPLACE INTO exchange_rates (base_currency, quote_currency, rate, rate_date)
+        VALUES (?1, ?2, ?3, ?4)
+        "#,
+        vec![
+            Value::String(base_currency.to_string()),
+            Value::String(quote_currency.to_string()),
+            Value::String(rate.to_string()),
+            Value::String(rate_date.to_string()),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Rate converting one unit of `from` into `to`, using the latest rate on or before `on`
+///
+/// A stored rate in the opposite direction is inverted.
+pub(crate) async fn exchange_rate(
+    db: &Database,
+    from: &str,
+    to: &str,
+    on: NaiveDate,
+) -> FiscusResult<Decimal> {
+    if from == to {
+        return Ok(Decimal::ONE);
+    }
+
+    let row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        r#"
+        SELECT base_currency, rate FROM exchange_rates
+        WHERE ((base_currency = ?1 AND quote_currency = ?2)
+               OR (base_currency = ?2 AND quote_currency = ?1))
+          AND rate_date <= ?3
+        ORDER BY rate_date DESC, base_currency = ?1 DESC
+        LIMIT 1
+        "#,
+        vec![
+            Value::String(from.to_string()),
+            Value::String(to.to_string()),
+            Value::String(on.to_string()),
+        ],
+    )
+    .await?;
+
+    let row = row.ok_or_else(|| {
+        FiscusError::NotFound(format!(
+            "No exchange rate from {from} to {to} on or before {on}"
+        ))
+    })?;
+
+    rate_from_row(&row, from)
+}
+
+/// Rate from `from` given a stored rate row, inverting it when stored the other way round
+fn rate_from_row(row: &HashMap<String, Value>, from: &str) -> FiscusResult<Decimal> {
+    let rate = parse_decimal_from_json(row, "rate");
+    if rate <= Decimal::ZERO {
+        return Err(FiscusError::Internal(
+            "Stored exchange rate is not positive".to_string(),
+        ));
+    }
+
+    match row.get("base_currency").and_then(|v| v.as_str()) {
+        Some(base) if base == from => Ok(rate),
+        _ => Ok(Decimal::ONE / rate),
+    }
+}
+
+/// Number of decimal places amounts in `currency` are kept to
+pub(crate) fn currency_minor_units(currency: &str) -> u32 {
+    if let Some(minor_units) = custom_currency_minor_units(currency) {
+        minor_units
+    } else if ZERO_DECIMAL_CURRENCIES.contains(&currency) {
+        0
+    } else if THREE_DECIMAL_CURRENCIES.contains(&currency) {
+        3
+    } else {
+        2
+    }
+}
+
+/// Convert `amount` at `rate`, rounded to the minor units of `to_currency`
+pub(crate) fn convert_amount(amount: Decimal, rate: Decimal, to_currency: &str) -> Decimal {
+    (amount * rate).round_dp(currency_minor_units(to_currency))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rate_row(base: &str, rate: &str) -> HashMap<String, Value> {
+        HashMap::from([
+            ("base_currency".to_string(), json!(base)),
+            ("rate".to_string(), json!(rate)),
+        ])
+    }
+
+    #[test]
+    fn test_rate_is_inverted_when_stored_the_other_way() {
+        let row = rate_row("EUR", "1.25");
+
+        assert_eq!(rate_from_row(&row, "EUR").unwrap(), Decimal::new(125, 2));
+        assert_eq!(rate_from_row(&row, "USD").unwrap(), Decimal::new(8, 1));
+    }
+
+    #[test]
+    fn test_conversion_rounds_to_target_minor_units() {
+        let rate = Decimal::new(10853, 4); // 1 EUR = 1.0853 USD
+
+        assert_eq!(
+            convert_amount(Decimal::new(1999, 2), rate, "USD"),
+            Decimal::new(2170, 2)
+        );
+        assert_eq!(
+            convert_amount(Decimal::new(1999, 2), Decimal::new(16312, 2), "JPY"),
+            Decimal::new(3261, 0)
+        );
+        assert_eq!(currency_minor_units("KWD"), 3);
+    }
+
+    #[tokio::test]
+    async fn test_same_currency_needs_no_rate() {
+        let db = Database::new(
+            "sqlite:exchange-rates.db".to_string(),
+            crate::database::DatabaseType::SQLite,
+        );
+        let on = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        assert_eq!(
+            exchange_rate(&db, "USD", "USD", on).await.unwrap(),
+            Decimal::ONE
+        );
+        assert!(matches!(
+            exchange_rate(&db, "EUR", "USD", on).await,
+            Err(FiscusError::NotFound(_))
+        ));
+    }
+}
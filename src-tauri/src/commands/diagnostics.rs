@@ -0,0 +1,293 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::State;
+use tracing::{info, warn};
+
+use crate::{
+    commands::accounts::load_balance_audit,
+    database::{Database, DatabaseUtils},
+    dto::{BalanceAuditResponse, BrokenTransfer, DataInconsistencyReport, OrphanedTransaction},
+    error::{FiscusError, FiscusResult, Validator},
+    security::authorize_command,
+};
+
+/// Report referential and balance problems in a user's data
+///
+/// Finds transactions pointing at missing accounts or categories, transfers
+/// whose legs are gone, and accounts whose stored balance has drifted from
+/// their transactions. Nothing is fixed; see `repair_account_balance` for the
+/// balance case.
+#[tauri::command]
+pub async fn find_data_inconsistencies(
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<DataInconsistencyReport, FiscusError> {
+    authorize_command("find_data_inconsistencies").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let orphaned = orphaned_transactions(&load_transaction_references(&db, &user_id).await?);
+    let broken = broken_transfers(&load_transfer_references(&db, &user_id).await?);
+
+    let account_query = "SELECT id FROM accounts WHERE user_id = ?1";
+    let accounts: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(&db, account_query, vec![Value::String(user_id.clone())])
+            .await?;
+
+    let mut audits = Vec::with_capacity(accounts.len());
+    for account_id in accounts.iter().filter_map(|row| text(row, "id")) {
+        audits.push(load_balance_audit(&db, account_id, &user_id).await?);
+    }
+
+    let report = build_report(user_id, orphaned, broken, audits);
+    if report.is_consistent {
+        info!(user_id = %report.user_id, "No data inconsistencies found");
+    } else {
+        warn!(
+            user_id = %report.user_id,
+            orphaned_transactions = report.orphaned_transactions.len(),
+            broken_transfers = report.broken_transfers.len(),
+            balance_mismatches = report.balance_mismatches.len(),
+            "Data inconsistencies found"
+        );
+    }
+
+    Ok(report)
+}
+
+/// Each transaction's account and category ids, with the matching row ids
+/// (NULL when the referenced row is missing)
+async fn load_transaction_references(
+    db: &Database,
+    user_id: &str,
+) -> FiscusResult<Vec<HashMap<String, Value>>> {
+    let query = r#"
+        SELECT t.id, t.account_id, t.category_id,
+               a.id AS found_account_id, c.id AS found_category_id
+        FROM transactions t
+        LEFT JOIN accounts a ON a.id = t.account_id
+        LEFT JOIN categories c ON c.id = t.category_id
+        WHERE t.user_id = ?1
+    "#;
+
+    DatabaseUtils::execute_query(db, query, vec![Value::String(user_id.to_string())]).await
+}
+
+/// Each transfer's leg ids, with the matching transaction ids (NULL when the
+/// leg is missing)
+///
+/// Cross-user transfers are included from either side; their credit leg
+/// belongs to the receiving user, so the join is not restricted by owner.
+async fn load_transfer_references(
+    db: &Database,
+    user_id: &str,
+) -> FiscusResult<Vec<HashMap<String, Value>>> {
+    let query = r#"
+        SELECT tr.id, tr.from_transaction_id, tr.to_transaction_id,
+               ft.id AS found_from_transaction_id, tt.id AS found_to_transaction_id
+        FROM transfers tr
+        LEFT JOIN transactions ft ON ft.id = tr.from_transaction_id
+        LEFT JOIN transactions tt ON tt.id = tr.to_transaction_id
+        WHERE tr.user_id = ?1 OR tr.to_user_id = ?1
+    "#;
+
+    DatabaseUtils::execute_query(db, query, vec![Value::String(user_id.to_string())]).await
+}
+
+/// Non-null string column of `row`
+fn text<'a>(row: &'a HashMap<String, Value>, key: &str) -> Option<&'a str> {
+    row.get(key).and_then(|v| v.as_str())
+}
+
+/// `reference` when it is set but the joined `found` column is not
+fn missing_reference(row: &HashMap<String, Value>, reference: &str, found: &str) -> Option<String> {
+    match (text(row, reference), text(row, found)) {
+        (Some(id), None) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn orphaned_transactions(rows: &[HashMap<String, Value>]) -> Vec<OrphanedTransaction> {
+    rows.iter()
+        .filter_map(|row| {
+            let missing_account_id = missing_reference(row, "account_id", "found_account_id");
+            let missing_category_id = missing_reference(row, "category_id", "found_category_id");
+            if missing_account_id.is_none() && missing_category_id.is_none() {
+                return None;
+            }
+
+            Some(OrphanedTransaction {
+                transaction_id: text(row, "id").unwrap_or_default().to_string(),
+                missing_account_id,
+                missing_category_id,
+            })
+        })
+        .collect()
+}
+
+fn broken_transfers(rows: &[HashMap<String, Value>]) -> Vec<BrokenTransfer> {
+    rows.iter()
+        .filter_map(|row| {
+            let missing_from_transaction_id =
+                missing_reference(row, "from_transaction_id", "found_from_transaction_id");
+            let missing_to_transaction_id =
+                missing_reference(row, "to_transaction_id", "found_to_transaction_id");
+            if missing_from_transaction_id.is_none() && missing_to_transaction_id.is_none() {
+                return None;
+            }
+
+            Some(BrokenTransfer {
+                transfer_id: text(row, "id").unwrap_or_default().to_string(),
+                missing_from_transaction_id,
+                missing_to_transaction_id,
+            })
+        })
+        .collect()
+}
+
+/// Assemble the report, keeping only the audits that show drift
+fn build_report(
+    user_id: String,
+    orphaned_transactions: Vec<OrphanedTransaction>,
+    broken_transfers: Vec<BrokenTransfer>,
+    audits: Vec<BalanceAuditResponse>,
+) -> DataInconsistencyReport {
+    let balance_mismatches: Vec<BalanceAuditResponse> = audits
+        .into_iter()
+        .filter(|audit| !audit.drift.is_zero())
+        .collect();
+    let is_consistent = orphaned_transactions.is_empty()
+        && broken_transfers.is_empty()
+        && balance_mismatches.is_empty();
+
+    DataInconsistencyReport {
+        user_id,
+        orphaned_transactions,
+        broken_transfers,
+        balance_mismatches,
+        is_consistent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use serde_json::json;
+
+    fn row(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn audit(account_id: &str, stored: i64, expected: i64) -> BalanceAuditResponse {
+        BalanceAuditResponse {
+            account_id: account_id.to_string(),
+            stored_balance: Decimal::new(stored, 0),
+            expected_balance: Decimal::new(expected, 0),
+            drift: Decimal::new(stored - expected, 0),
+            repaired: false,
+        }
+    }
+
+    #[test]
+    fn test_orphaned_transaction_is_detected() {
+        let rows = vec![
+            row(json!({
+                "id": "tx-ok",
+                "account_id": "acc-1",
+                "category_id": "cat-1",
+                "found_account_id": "acc-1",
+                "found_category_id": "cat-1",
+            })),
+            row(json!({
+                "id": "tx-orphan",
+                "account_id": "acc-deleted",
+                "category_id": "cat-deleted",
+                "found_account_id": null,
+                "found_category_id": null,
+            })),
+            row(json!({
+                "id": "tx-uncategorized",
+                "account_id": "acc-1",
+                "category_id": null,
+                "found_account_id": "acc-1",
+                "found_category_id": null,
+            })),
+        ];
+
+        assert_eq!(
+            orphaned_transactions(&rows),
+            vec![OrphanedTransaction {
+                transaction_id: "tx-orphan".to_string(),
+                missing_account_id: Some("acc-deleted".to_string()),
+                missing_category_id: Some("cat-deleted".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_transfer_with_missing_leg_is_detected() {
+        let rows = vec![row(json!({
+            "id": "transfer-1",
+            "from_transaction_id": "tx-from",
+            "to_transaction_id": "tx-to",
+            "found_from_transaction_id": "tx-from",
+            "found_to_transaction_id": null,
+        }))];
+
+        assert_eq!(
+            broken_transfers(&rows),
+            vec![BrokenTransfer {
+                transfer_id: "transfer-1".to_string(),
+                missing_from_transaction_id: None,
+                missing_to_transaction_id: Some("tx-to".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_clean_data_reports_no_issues() {
+        let transactions = vec![row(json!({
+            "id": "tx-1",
+            "account_id": "acc-1",
+            "category_id": "cat-1",
+            "found_account_id": "acc-1",
+            "found_category_id": "cat-1",
+        }))];
+        let transfers = vec![row(json!({
+            "id": "transfer-1",
+            "from_transaction_id": "tx-from",
+            "to_transaction_id": "tx-to",
+            "found_from_transaction_id": "tx-from",
+            "found_to_transaction_id": "tx-to",
+        }))];
+
+        let report = build_report(
+            "user-1".to_string(),
+            orphaned_transactions(&transactions),
+            broken_transfers(&transfers),
+            vec![audit("acc-1", 100, 100)],
+        );
+
+        assert!(report.is_consistent);
+        assert!(report.orphaned_transactions.is_empty());
+        assert!(report.broken_transfers.is_empty());
+        assert!(report.balance_mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_balance_drift_is_reported() {
+        let report = build_report(
+            "user-1".to_string(),
+            Vec::new(),
+            Vec::new(),
+            vec![audit("acc-1", 100, 100), audit("acc-2", 150, 120)],
+        );
+
+        assert!(!report.is_consistent);
+        assert_eq!(report.balance_mismatches.len(), 1);
+        assert_eq!(report.balance_mismatches[0].account_id, "acc-2");
+        assert_eq!(report.balance_mismatches[0].drift, Decimal::new(30, 0));
+    }
+}
@@ -4,56 +4,159 @@ use base64::Engine;
 /// This module provides the Tauri command interface for the encryption service,
 /// allowing the frontend to perform secure encryption and decryption operations
 /// on financial data.
-use std::sync::{Arc, OnceLock};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::State;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::{
+    clock::SystemClock,
+    commands::auth::verify_password,
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
     dto::{
-        DecryptDataRequest, DecryptDataResponse, DeriveKeyRequest, DeriveKeyResponse,
-        EncryptDataRequest, EncryptDataResponse, EncryptionStatsResponse, GenerateKeyRequest,
-        GenerateKeyResponse, RotateKeysRequest,
+        AlgorithmMigrationResponse, DataIntegrityResponse, DecryptDataRequest, DecryptDataResponse,
+        DeriveKeyRequest, DeriveKeyResponse, EncryptDataRequest, EncryptDataResponse,
+        EncryptionOperationTiming, EncryptionPerformanceResponse, EncryptionSelfTestReport,
+        EncryptionStatsResponse, EncryptionStatus, GenerateKeyRequest, GenerateKeyResponse,
+        IntegrityFailure, KeyInfoResponse, KeyLineageResponse, ListUserKeysRequest,
+        RecoveredFieldsResponse, RekeyRequest, RekeyResponse, RevokeKeyRequest, RotateKeysRequest,
+        SelfTestCheck, SupportedAlgorithmsResponse,
+    },
+    encryption::{
+        utils::SecureRandom, EncryptionAlgorithm, EncryptionService, KeyDerivationAlgorithm,
+        SelfTestOutcome, WrappedKey,
     },
-    encryption::{EncryptionAlgorithm, EncryptionService},
     error::{FiscusError, FiscusResult, SecurityValidator, Validator},
+    logging::performance::{get_performance_monitor, EncryptionOperation, PerformanceMonitor},
+    security::{
+        active_context, authorize_command, authorize_user, require_recent_auth, RECENT_AUTH_MAX_AGE,
+    },
+    with_transaction,
 };
 
 #[cfg(test)]
 use crate::security::data_protection::SensitiveData;
 
-/// Global encryption service instance
-static ENCRYPTION_SERVICE: OnceLock<Arc<EncryptionService>> = OnceLock::new();
+/// Error returned by encrypted operations while the service is unavailable
+const SERVICE_UNAVAILABLE: &str = "Encryption service unavailable";
 
-/// Initialize the encryption service (called once at startup)
-pub fn initialize_encryption_service() -> FiscusResult<()> {
-    match EncryptionService::new() {
-        Ok(service) => {
-            let arc_service = Arc::new(service);
-            match ENCRYPTION_SERVICE.set(arc_service) {
-                Ok(()) => {
-                    info!("Encryption service initialized successfully");
-                    Ok(())
-                }
-                Err(_) => {
+/// Slot holding the encryption service once it has initialized
+///
+/// Initialization may fail and be retried; the service is only set once it
+/// succeeds, so readers never observe a half-initialized service.
+struct EncryptionServiceSlot {
+    service: OnceLock<Arc<EncryptionService>>,
+}
+
+impl EncryptionServiceSlot {
+    const fn new() -> Self {
+        Self {
+            service: OnceLock::new(),
+        }
+    }
+
+    fn initialize(
+        &self,
+        create: impl FnOnce() -> FiscusResult<EncryptionService>,
+    ) -> FiscusResult<()> {
+        if self.service.get().is_some() {
+            warn!("Encryption service was already initialized");
+            return Ok(());
+        }
+
+        match create() {
+            Ok(service) => {
+                if self.service.set(Arc::new(service)).is_err() {
                     warn!("Encryption service was already initialized");
-                    Ok(())
                 }
+                info!("Encryption service initialized successfully");
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to initialize encryption service: {}", e);
+                Err(FiscusError::Encryption(e.to_string()))
             }
         }
-        Err(e) => {
-            error!("Failed to initialize encryption service: {}", e);
-            Err(FiscusError::Internal(
-                "Failed to initialize encryption service".to_string(),
-            ))
-        }
+    }
+
+    fn get(&self) -> FiscusResult<Arc<EncryptionService>> {
+        self.service
+            .get()
+            .cloned()
+            .ok_or_else(|| FiscusError::Encryption(SERVICE_UNAVAILABLE.to_string()))
     }
 }
 
+/// Global encryption service instance
+static ENCRYPTION_SERVICE: EncryptionServiceSlot = EncryptionServiceSlot::new();
+
+/// Initialize the encryption service (called at startup and on retry)
+///
+/// On failure the app keeps running in degraded mode; see `EncryptionState`.
+pub fn initialize_encryption_service() -> FiscusResult<()> {
+    ENCRYPTION_SERVICE.initialize(EncryptionService::new)
+}
+
 /// Get the encryption service instance
+///
+/// Fails with `FiscusError::Encryption` while the service is unavailable.
 pub fn get_encryption_service() -> FiscusResult<Arc<EncryptionService>> {
-    ENCRYPTION_SERVICE
-        .get()
-        .cloned()
-        .ok_or_else(|| FiscusError::Internal("Encryption service not initialized".to_string()))
+    ENCRYPTION_SERVICE.get()
+}
+
+/// Whether the encryption service is running, kept in Tauri app state
+///
+/// Set from the outcome of initialization at startup and of each retry.
+pub struct EncryptionState {
+    status: Mutex<EncryptionStatus>,
+}
+
+impl EncryptionState {
+    /// Initialize the encryption service and record the outcome
+    pub fn initialize() -> Self {
+        Self {
+            status: Mutex::new(status_after(initialize_encryption_service())),
+        }
+    }
+
+    /// Current encryption status
+    pub fn status(&self) -> EncryptionStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn record(&self, result: FiscusResult<()>) -> EncryptionStatus {
+        let status = status_after(result);
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = status.clone();
+        status
+    }
+}
+
+/// Encryption status after an initialization attempt
+fn status_after(result: FiscusResult<()>) -> EncryptionStatus {
+    match result {
+        Ok(()) => EncryptionStatus::Available,
+        Err(FiscusError::Encryption(reason)) => EncryptionStatus::Unavailable(reason),
+        Err(e) => EncryptionStatus::Unavailable(e.to_string()),
+    }
+}
+
+/// Retry initializing the encryption service after a failure at startup
+///
+/// Does nothing if the service is already available.
+#[tauri::command]
+pub async fn retry_encryption_initialization(
+    state: State<'_, EncryptionState>,
+) -> FiscusResult<EncryptionStatus> {
+    let status = state.record(initialize_encryption_service());
+    if let EncryptionStatus::Unavailable(reason) = &status {
+        warn!(reason = %reason, "Encryption service is still unavailable");
+    }
+
+    Ok(status)
 }
 
 /// Encrypt sensitive financial data
@@ -102,6 +205,8 @@ pub async fn encrypt_financial_data(
         algorithm: encrypted_data.metadata.algorithm,
         key_id: encrypted_data.metadata.key_id,
         encrypted_at: encrypted_data.metadata.encrypted_at,
+        compression: encrypted_data.metadata.compression,
+        compressed: encrypted_data.metadata.compressed,
     };
 
     info!(
@@ -143,19 +248,18 @@ pub async fn decrypt_financial_data(
         .map_err(|e| FiscusError::InvalidInput(format!("Invalid base64 nonce: {e}")))?;
 
     // Reconstruct encrypted data
-    let encrypted_data = crate::encryption::types::EncryptedData::new(
-        ciphertext,
-        nonce,
-        None,
-        crate::encryption::types::EncryptionMetadata::new(
-            request.algorithm,
-            request.key_id.clone(),
-        ),
+    let mut metadata = crate::encryption::types::EncryptionMetadata::new(
+        request.algorithm,
+        request.key_id.clone(),
     );
+    metadata.compression = request.compression;
+    metadata.compressed = request.compressed;
+    let encrypted_data =
+        crate::encryption::types::EncryptedData::new(ciphertext, nonce, None, metadata);
 
-    // Decrypt the data
+    // Decrypt the data; the plaintext buffer is wiped once it has been encoded
     let decrypted_bytes = service
-        .decrypt_financial_data(
+        .decrypt_financial_data_zeroizing(
             &encrypted_data,
             &request.user_id.as_str(),
             &request.data_type,
@@ -164,7 +268,7 @@ pub async fn decrypt_financial_data(
 
     // Convert decrypted data to base64 for transport
     let response = DecryptDataResponse {
-        data: base64::engine::general_purpose::STANDARD.encode(&decrypted_bytes),
+        data: base64::engine::general_purpose::STANDARD.encode(decrypted_bytes.as_slice()),
         decrypted_at: chrono::Utc::now(),
     };
 
@@ -232,11 +336,18 @@ pub async fn generate_encryption_key(
 }
 
 /// Rotate encryption keys for a user
+///
+/// Requires the session to have authenticated recently.
 #[tauri::command]
 #[instrument(skip(request), fields(user_id = %request.user_id))]
 pub async fn rotate_user_keys(request: RotateKeysRequest) -> FiscusResult<bool> {
     // Validate input
     Validator::validate_uuid(&request.user_id.as_str(), "user_id")?;
+    require_recent_auth(
+        active_context().await.as_ref(),
+        RECENT_AUTH_MAX_AGE,
+        &SystemClock,
+    )?;
 
     let service = get_encryption_service()?;
 
@@ -249,6 +360,644 @@ pub async fn rotate_user_keys(request: RotateKeysRequest) -> FiscusResult<bool>
     Ok(true)
 }
 
+/// List metadata for all of a user's encryption keys
+#[tauri::command]
+#[instrument(skip(request), fields(user_id = %request.user_id))]
+pub async fn list_user_keys(request: ListUserKeysRequest) -> FiscusResult<Vec<KeyInfoResponse>> {
+    authorize_command("list_user_keys").await?;
+
+    // Validate input
+    Validator::validate_uuid(&request.user_id.as_str(), "user_id")?;
+    authorize_user(&request.user_id.as_str()).await?;
+
+    let service = get_encryption_service()?;
+
+    let keys = service.list_user_keys(&request.user_id.as_str()).await?;
+
+    Ok(keys.into_iter().map(KeyInfoResponse::from).collect())
+}
+
+/// Show the active and retained rotated keys of one of a user's data types
+///
+/// Keys are ordered oldest first, so rotations can be read off their
+/// creation times.
+#[tauri::command]
+#[instrument]
+pub async fn get_key_lineage(
+    user_id: String,
+    data_type: String,
+) -> FiscusResult<KeyLineageResponse> {
+    authorize_command("get_key_lineage").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_string(&data_type, "data_type", 1, 100)?;
+    authorize_user(&user_id).await?;
+
+    let service = get_encryption_service()?;
+    let keys: Vec<KeyInfoResponse> = service
+        .key_lineage(&user_id, &data_type)
+        .await?
+        .into_iter()
+        .map(KeyInfoResponse::from)
+        .collect();
+
+    Ok(KeyLineageResponse {
+        user_id,
+        data_type,
+        generations: keys.len(),
+        active_key_id: keys
+            .iter()
+            .find(|key| key.is_active)
+            .map(|key| key.key_id.clone()),
+        keys,
+    })
+}
+
+/// Revoke a specific encryption key so it can no longer decrypt data
+///
+/// Requires the session to have authenticated recently.
+#[tauri::command]
+#[instrument(skip(request), fields(user_id = %request.user_id, key_id = %request.key_id))]
+pub async fn revoke_key(request: RevokeKeyRequest) -> FiscusResult<bool> {
+    authorize_command("revoke_key").await?;
+    require_recent_auth(
+        active_context().await.as_ref(),
+        RECENT_AUTH_MAX_AGE,
+        &SystemClock,
+    )?;
+
+    // Validate input
+    Validator::validate_uuid(&request.user_id.as_str(), "user_id")?;
+    Validator::validate_uuid(&request.key_id, "key_id")?;
+    authorize_user(&request.user_id.as_str()).await?;
+
+    let service = get_encryption_service()?;
+
+    info!(user_id = %request.user_id, key_id = %request.key_id, "Revoking encryption key");
+
+    service
+        .revoke_key(&request.user_id.as_str(), &request.key_id)
+        .await?;
+
+    Ok(true)
+}
+
+/// Re-wrap a user's stored encryption keys after their password has changed
+///
+/// `change_password` re-wraps the keys itself; this command repairs a
+/// wrapping left under an earlier password. The new password must already
+/// be the account password, and the old one is proven by unwrapping the
+/// stored keys with it. See `prepare_rekey` for how the keys are re-wrapped;
+/// the rows and the salt are replaced in one transaction, so any failure
+/// leaves the previous wrapping in place.
+#[tauri::command]
+#[instrument(skip(request, db), fields(user_id = %request.user_id))]
+pub async fn rekey_after_password_change(
+    request: RekeyRequest,
+    db: State<'_, Database>,
+) -> FiscusResult<RekeyResponse> {
+    authorize_command("rekey_after_password_change").await?;
+
+    // Validate input
+    let user_id = request.user_id.as_str();
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_string(request.old_password.expose(), "old_password", 1, 128)?;
+    Validator::validate_string(request.new_password.expose(), "new_password", 8, 128)?;
+
+    let user_row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        &db,
+        "SELECT password_hash FROM users WHERE id = ?1",
+        vec![Value::String(user_id.clone())],
+    )
+    .await?;
+    let user_row = user_row.ok_or_else(|| FiscusError::NotFound("User not found".to_string()))?;
+    let stored_hash = user_row
+        .get("password_hash")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| FiscusError::Database("Invalid user data".to_string()))?;
+
+    if !verify_password(request.new_password.expose(), stored_hash)? {
+        return Err(FiscusError::Authentication(
+            "New password is not the current account password".to_string(),
+        ));
+    }
+
+    let rekey = prepare_rekey(
+        &db,
+        &user_id,
+        request.old_password.expose(),
+        request.new_password.expose(),
+    )
+    .await?;
+
+    with_transaction!(&*db, async {
+        store_rekey(&db, &rekey).await?;
+        Ok::<(), FiscusError>(())
+    })?;
+
+    info!(
+        user_id = %user_id,
+        keys_rewrapped = rekey.response.keys_rewrapped,
+        keys_wrapped = rekey.response.keys_wrapped,
+        "Re-keyed stored encryption keys after password change"
+    );
+
+    Ok(rekey.response)
+}
+
+/// Key wrapping re-wrapped under a new password, ready to be stored
+pub(crate) struct PreparedRekey {
+    salt_params: Vec<Value>,
+    key_rows: Vec<Vec<Value>>,
+    pub(crate) response: RekeyResponse,
+}
+
+/// Re-wrap a user's keys from `old_password` to `new_password` without writing anything
+///
+/// Every stored key is re-wrapped under a master key derived from the new
+/// password with a fresh salt and test-decrypted first; keys that were never
+/// wrapped are wrapped under the new master key at the same time. Fails with
+/// `Authentication` if `old_password` does not unwrap the stored keys.
+pub(crate) async fn prepare_rekey(
+    db: &Database,
+    user_id: &str,
+    old_password: &str,
+    new_password: &str,
+) -> FiscusResult<PreparedRekey> {
+    let service = get_encryption_service()?;
+    let new_salt = SecureRandom::new()?.generate_salt()?;
+    let new_kdf = service.password_kdf();
+    let new_master = service
+        .derive_master_key(new_password, &new_salt, new_kdf)
+        .await?;
+
+    let rewrapped = match load_key_wrapping(db, user_id).await? {
+        Some(stored) => {
+            let old_master = service
+                .derive_master_key(old_password, &stored.salt, stored.kdf)
+                .await?;
+            service
+                .rewrap_keys(&stored.keys, &old_master, &new_master)
+                .await?
+        }
+        None => Vec::new(),
+    };
+
+    let already_wrapped: HashSet<&str> = rewrapped.iter().map(|k| k.key_id.as_str()).collect();
+    let newly_wrapped: Vec<WrappedKey> = service
+        .wrap_user_keys(user_id, &new_master)
+        .await?
+        .into_iter()
+        .filter(|k| !already_wrapped.contains(k.key_id.as_str()))
+        .collect();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let key_rows = rewrapped
+        .iter()
+        .chain(&newly_wrapped)
+        .map(|wrapped| wrapped_key_params(wrapped, user_id, &now))
+        .collect::<FiscusResult<Vec<_>>>()?;
+    let salt_params = vec![
+        Value::String(user_id.to_string()),
+        Value::String(base64::engine::general_purpose::STANDARD.encode(&new_salt)),
+        serde_json::to_value(new_kdf)?,
+        Value::String(now),
+    ];
+
+    Ok(PreparedRekey {
+        salt_params,
+        key_rows,
+        response: RekeyResponse {
+            user_id: user_id.to_string(),
+            keys_rewrapped: rewrapped.len(),
+            keys_wrapped: newly_wrapped.len(),
+        },
+    })
+}
+
+/// Write a prepared re-wrapping; run inside the caller's database transaction
+pub(crate) async fn store_rekey(db: &Database, rekey: &PreparedRekey) -> FiscusResult<()> {
+    DatabaseUtils::execute_non_query(
+        db,
+        "INSERT OR REPLACE INTO user_key_wrapping (user_id, kdf_salt, kdf_algorithm, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        rekey.salt_params.clone(),
+    )
+    .await?;
+
+    for params in &rekey.key_rows {
+        DatabaseUtils::execute_non_query(
+            db,
+            r#"
+            INSERT OR REPLACE INTO wrapped_user_keys
+                (key_id, user_id, key_identifier, wrapped_key, algorithm, is_active, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params.clone(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Load a user's wrapped keys into the key manager with the master key from `password`
+///
+/// Called at login once the password has been verified. Users without a
+/// stored wrapping have nothing to restore.
+pub(crate) async fn unlock_user_keys(
+    db: &Database,
+    user_id: &str,
+    password: &str,
+) -> FiscusResult<usize> {
+    let Some(stored) = load_key_wrapping(db, user_id).await? else {
+        return Ok(0);
+    };
+
+    let service = get_encryption_service()?;
+    let master = service
+        .derive_master_key(password, &stored.salt, stored.kdf)
+        .await?;
+    let restored = service
+        .restore_wrapped_keys(user_id, &stored.keys, &master)
+        .await?;
+
+    debug!(user_id = %user_id, keys = restored, "Unlocked stored encryption keys");
+    Ok(restored)
+}
+
+/// A user's stored key wrapping
+struct StoredKeyWrapping {
+    /// Salt the master key is derived with
+    salt: Vec<u8>,
+    /// Password KDF the master key is derived with
+    kdf: KeyDerivationAlgorithm,
+    /// Keys wrapped under the master key
+    keys: Vec<WrappedKey>,
+}
+
+/// Load a user's master key salt, KDF and wrapped keys, if any have been stored
+async fn load_key_wrapping(
+    db: &Database,
+    user_id: &str,
+) -> FiscusResult<Option<StoredKeyWrapping>> {
+    let salt_row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        "SELECT kdf_salt, kdf_algorithm FROM user_key_wrapping WHERE user_id = ?1",
+        vec![Value::String(user_id.to_string())],
+    )
+    .await?;
+
+    let Some(salt_row) = salt_row else {
+        return Ok(None);
+    };
+    let salt = salt_row
+        .get("kdf_salt")
+        .and_then(|v| v.as_str())
+        .and_then(|s| base64::engine::general_purpose::STANDARD.decode(s).ok())
+        .ok_or_else(|| FiscusError::Database("Invalid key wrapping salt".to_string()))?;
+    let kdf = salt_row
+        .get("kdf_algorithm")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .ok_or_else(|| FiscusError::Database("Invalid key wrapping KDF".to_string()))?;
+
+    let rows: Vec<HashMap<String, Value>> = DatabaseUtils::execute_query(
+        db,
+        "SELECT key_id, key_identifier, wrapped_key, algorithm, is_active FROM wrapped_user_keys WHERE user_id = ?1",
+        vec![Value::String(user_id.to_string())],
+    )
+    .await?;
+    let wrapped = rows
+        .iter()
+        .map(wrapped_key_from_row)
+        .collect::<FiscusResult<Vec<_>>>()?;
+
+    Ok(Some(StoredKeyWrapping {
+        salt,
+        kdf,
+        keys: wrapped,
+    }))
+}
+
+/// Parameters for storing a wrapped key row
+fn wrapped_key_params(wrapped: &WrappedKey, user_id: &str, now: &str) -> FiscusResult<Vec<Value>> {
+    let wrapped_key = serde_json::to_string(&wrapped.wrapped_key)?;
+    Ok(vec![
+        Value::String(wrapped.key_id.clone()),
+        Value::String(user_id.to_string()),
+        Value::String(wrapped.key_identifier.clone()),
+        Value::String(wrapped_key),
+        serde_json::to_value(wrapped.algorithm)?,
+        Value::Bool(wrapped.is_active),
+        Value::String(now.to_string()),
+    ])
+}
+
+/// Read a wrapped key back from its stored row
+fn wrapped_key_from_row(row: &HashMap<String, Value>) -> FiscusResult<WrappedKey> {
+    let field = |name: &str| {
+        row.get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FiscusError::Database(format!("Wrapped key row is missing {name}")))
+    };
+    // SQLite hands booleans back as integers
+    let is_active = match row.get("is_active") {
+        Some(Value::Bool(active)) => *active,
+        Some(Value::Number(n)) => n.as_i64() != Some(0),
+        _ => {
+            return Err(FiscusError::Database(
+                "Wrapped key row is missing is_active".to_string(),
+            ))
+        }
+    };
+
+    Ok(WrappedKey {
+        key_id: field("key_id")?.to_string(),
+        key_identifier: field("key_identifier")?.to_string(),
+        wrapped_key: serde_json::from_str(field("wrapped_key")?)?,
+        algorithm: serde_json::from_value(Value::String(field("algorithm")?.to_string()))?,
+        is_active,
+    })
+}
+
+/// Move a data type to a different encryption algorithm, re-encrypting stored values
+///
+/// A fresh key for the target algorithm becomes the active key for the data
+/// type, then every stored value of that field is re-encrypted under it. Each
+/// row is only updated if it still holds the value that was read, and values
+/// already under the active key are skipped, so an interrupted migration can
+/// simply be run again.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn migrate_data_type_algorithm(
+    user_id: String,
+    data_type: String,
+    target_algorithm: EncryptionAlgorithm,
+    db: State<'_, Database>,
+) -> FiscusResult<AlgorithmMigrationResponse> {
+    authorize_command("migrate_data_type_algorithm").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    if !matches!(
+        target_algorithm,
+        EncryptionAlgorithm::Aes256Gcm | EncryptionAlgorithm::ChaCha20Poly1305
+    ) {
+        return Err(FiscusError::InvalidInput(format!(
+            "Algorithm {target_algorithm:?} cannot be used for field encryption"
+        )));
+    }
+
+    let tables = EncryptedDatabaseUtils::tables_with_encrypted_field(&data_type);
+    if tables.is_empty() {
+        return Err(FiscusError::InvalidInput(format!(
+            "{data_type} is not an encrypted field"
+        )));
+    }
+
+    let service = get_encryption_service()?;
+    let key_id = service
+        .migrate_data_type_key(&user_id, &data_type, target_algorithm)
+        .await?;
+
+    info!(
+        user_id = %user_id,
+        data_type = %data_type,
+        key_id = %key_id,
+        "Migrating encrypted field to new algorithm"
+    );
+
+    let mut rows_migrated = 0;
+    let mut rows_skipped = 0;
+
+    for table in tables {
+        // `table` and `data_type` come from ENCRYPTED_FIELDS, never from raw input
+        let select_query = format!("SELECT id, {data_type} FROM {table} WHERE user_id = ?1");
+        let update_query =
+            format!("UPDATE {table} SET {data_type} = ?1 WHERE id = ?2 AND {data_type} = ?3");
+
+        let rows: Vec<HashMap<String, Value>> =
+            DatabaseUtils::execute_query(&db, &select_query, vec![Value::String(user_id.clone())])
+                .await?;
+
+        for row in rows {
+            let (Some(row_id), Some(stored)) = (
+                row.get("id").and_then(|v| v.as_str()),
+                row.get(&data_type).and_then(|v| v.as_str()),
+            ) else {
+                rows_skipped += 1;
+                continue;
+            };
+
+            if !stored.starts_with("enc:") {
+                rows_skipped += 1;
+                continue;
+            }
+
+            let Some(reencrypted) =
+                EncryptedDatabaseUtils::reencrypt_field_value(stored, &user_id, &data_type).await?
+            else {
+                rows_skipped += 1;
+                continue;
+            };
+
+            let params = vec![
+                Value::String(reencrypted),
+                Value::String(row_id.to_string()),
+                Value::String(stored.to_string()),
+            ];
+
+            let updated = with_transaction!(&*db, async {
+                let affected = DatabaseUtils::execute_non_query(&db, &update_query, params).await?;
+                Ok::<u64, FiscusError>(affected)
+            })?;
+
+            if updated > 0 {
+                rows_migrated += 1;
+            } else {
+                rows_skipped += 1;
+            }
+        }
+    }
+
+    info!(
+        user_id = %user_id,
+        data_type = %data_type,
+        rows_migrated = rows_migrated,
+        rows_skipped = rows_skipped,
+        "Encrypted field algorithm migration completed"
+    );
+
+    Ok(AlgorithmMigrationResponse {
+        user_id,
+        data_type,
+        algorithm: target_algorithm,
+        key_id,
+        rows_migrated,
+        rows_skipped,
+    })
+}
+
+/// Encrypted tables checked by the integrity verification, with their raw row queries
+const INTEGRITY_CHECK_QUERIES: &[(&str, &str)] = &[
+    (
+        "transactions",
+        "SELECT id, amount, description, notes, payee, original_amount FROM transactions WHERE user_id = ?1",
+    ),
+    (
+        "transfers",
+        "SELECT id, amount, description FROM transfers WHERE user_id = ?1",
+    ),
+];
+
+/// Check every row of a table for fields that can no longer be decrypted
+async fn verify_rows_decryptable(
+    table: &str,
+    rows: &[HashMap<String, Value>],
+    user_id: &str,
+) -> Vec<IntegrityFailure> {
+    let mut failures = Vec::new();
+
+    for row in rows {
+        let fields = EncryptedDatabaseUtils::find_undecryptable_fields(row, user_id, table).await;
+
+        if !fields.is_empty() {
+            let row_id = row
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            failures.push(IntegrityFailure {
+                table: table.to_string(),
+                row_id,
+                fields,
+            });
+        }
+    }
+
+    failures
+}
+
+/// Verify that all of a user's encrypted rows are still decryptable (read-only)
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn verify_user_data_integrity(
+    user_id: String,
+    db: State<'_, Database>,
+) -> FiscusResult<DataIntegrityResponse> {
+    authorize_command("verify_user_data_integrity").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    authorize_user(&user_id).await?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    info!(user_id = %user_id, "Starting encrypted data integrity verification");
+
+    let mut checked_rows = 0;
+    let mut failures = Vec::new();
+
+    for (table, query) in INTEGRITY_CHECK_QUERIES {
+        // Read raw rows so failures are not masked by the decrypting query path
+        let rows: Vec<HashMap<String, Value>> =
+            DatabaseUtils::execute_query(&db, query, vec![Value::String(user_id.clone())]).await?;
+
+        checked_rows += rows.len();
+        failures.extend(verify_rows_decryptable(table, &rows, &user_id).await);
+    }
+
+    let failing_row_ids: Vec<String> = failures.iter().map(|f| f.row_id.clone()).collect();
+
+    if failures.is_empty() {
+        info!(
+            user_id = %user_id,
+            checked_rows = checked_rows,
+            "Encrypted data integrity verification passed"
+        );
+    } else {
+        warn!(
+            user_id = %user_id,
+            checked_rows = checked_rows,
+            failing_rows = failures.len(),
+            "Encrypted data integrity verification found undecryptable rows"
+        );
+    }
+
+    Ok(DataIntegrityResponse {
+        user_id,
+        checked_rows,
+        decryptable_rows: checked_rows - failures.len(),
+        failing_rows: failures.len(),
+        failing_row_ids,
+        failures,
+        checked_at: chrono::Utc::now(),
+    })
+}
+
+/// Decrypt what can still be read of one transaction
+///
+/// Each sensitive field is decrypted on its own, so a corrupted field or a
+/// missing key leaves the rest readable instead of failing the whole row.
+/// Unreadable fields come back null and are listed by name.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn recover_readable_fields(
+    transaction_id: String,
+    user_id: String,
+    db: State<'_, Database>,
+) -> FiscusResult<RecoveredFieldsResponse> {
+    authorize_command("recover_readable_fields").await?;
+
+    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    // Read the raw row; the decrypting query path would fail on the bad field
+    let row: HashMap<String, Value> = DatabaseUtils::execute_query_single(
+        &db,
+        r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               original_amount, original_currency, refunds_transaction_id,
+               created_at, updated_at
+        FROM transactions
+        WHERE id = ?1 AND user_id = ?2
+        "#,
+        vec![
+            Value::String(transaction_id.clone()),
+            Value::String(user_id.clone()),
+        ],
+    )
+    .await?
+    .ok_or_else(|| FiscusError::NotFound("Transaction not found".to_string()))?;
+
+    let (fields, unreadable_fields) =
+        EncryptedDatabaseUtils::decrypt_readable_fields(row, &user_id, "transactions").await;
+    let mut readable_fields: Vec<String> = fields
+        .keys()
+        .filter(|field| EncryptedDatabaseUtils::is_field_sensitive("transactions", field))
+        .filter(|field| !unreadable_fields.contains(field))
+        .cloned()
+        .collect();
+    readable_fields.sort();
+
+    if !unreadable_fields.is_empty() {
+        warn!(
+            transaction_id = %transaction_id,
+            unreadable_fields = ?unreadable_fields,
+            "Transaction has unreadable encrypted fields"
+        );
+    }
+
+    Ok(RecoveredFieldsResponse {
+        transaction_id,
+        fields,
+        readable_fields,
+        unreadable_fields,
+    })
+}
+
 /// Get encryption service statistics
 #[tauri::command]
 pub async fn get_encryption_stats() -> FiscusResult<EncryptionStatsResponse> {
@@ -272,6 +1021,87 @@ pub async fn get_encryption_stats() -> FiscusResult<EncryptionStatsResponse> {
     Ok(response)
 }
 
+/// Counts and durations of encrypt, decrypt and key derivation calls since startup
+#[tauri::command]
+pub async fn get_encryption_performance() -> FiscusResult<EncryptionPerformanceResponse> {
+    Ok(encryption_performance_response(get_performance_monitor()))
+}
+
+fn encryption_performance_response(monitor: &PerformanceMonitor) -> EncryptionPerformanceResponse {
+    let as_ms = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+    EncryptionPerformanceResponse {
+        operations: monitor
+            .get_summary()
+            .encryption
+            .into_iter()
+            .map(|op| EncryptionOperationTiming {
+                operation: op.operation.as_str().to_string(),
+                count: op.count,
+                total_duration_ms: as_ms(op.total_duration),
+                average_duration_ms: as_ms(op.average_duration),
+            })
+            .collect(),
+    }
+}
+
+/// Encryption algorithms this build supports and what each can be used for
+///
+/// Lets the UI offer only valid choices, e.g. never Ed25519 for encryption.
+#[tauri::command]
+pub async fn get_supported_algorithms() -> FiscusResult<SupportedAlgorithmsResponse> {
+    let service = get_encryption_service()?;
+
+    Ok(supported_algorithms_response(&service))
+}
+
+fn supported_algorithms_response(service: &EncryptionService) -> SupportedAlgorithmsResponse {
+    let (symmetric, asymmetric) = service.supported_algorithms();
+    SupportedAlgorithmsResponse {
+        symmetric,
+        asymmetric,
+    }
+}
+
+/// Verify the encryption primitives work on this build
+///
+/// Meant to run at startup; uses throwaway keys and never touches user data.
+#[tauri::command]
+pub async fn run_encryption_self_test() -> FiscusResult<EncryptionSelfTestReport> {
+    let service = get_encryption_service()?;
+
+    let report = self_test_report(service.run_self_test().await);
+    if report.passed {
+        info!("Encryption self-test passed");
+    } else {
+        let failed: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.as_str())
+            .collect();
+        error!(?failed, "Encryption self-test failed");
+    }
+
+    Ok(report)
+}
+
+fn self_test_report(outcomes: Vec<SelfTestOutcome>) -> EncryptionSelfTestReport {
+    let checks: Vec<SelfTestCheck> = outcomes
+        .into_iter()
+        .map(|(name, result)| SelfTestCheck {
+            name: name.to_string(),
+            passed: result.is_ok(),
+            detail: result.err().map(|e| e.to_string()),
+        })
+        .collect();
+
+    EncryptionSelfTestReport {
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+        ran_at: chrono::Utc::now(),
+    }
+}
+
 /// Derive a key from password
 #[tauri::command]
 #[instrument(skip(request))]
@@ -279,7 +1109,7 @@ pub async fn derive_key_from_password(
     request: DeriveKeyRequest,
 ) -> FiscusResult<DeriveKeyResponse> {
     use crate::encryption::key_derivation::{Argon2Kdf, KeyDerivation, Pbkdf2Kdf, ScryptKdf};
-    use crate::encryption::types::{KeyDerivationAlgorithm, KeyDerivationParams};
+    use crate::encryption::types::KeyDerivationParams;
     use crate::encryption::utils::SecureRandom;
     use chrono::Utc;
 
@@ -320,6 +1150,7 @@ pub async fn derive_key_from_password(
     };
 
     // Create the appropriate key derivation instance and derive the key
+    let started = std::time::Instant::now();
     let derived_key = match request.algorithm {
         KeyDerivationAlgorithm::Argon2id => {
             let kdf = Argon2Kdf::new()?;
@@ -344,6 +1175,12 @@ pub async fn derive_key_from_password(
         }
     };
 
+    get_performance_monitor().record_encryption_operations(
+        EncryptionOperation::KeyDerivation,
+        1,
+        started.elapsed(),
+    );
+
     let response = DeriveKeyResponse {
         key_id: derived_key.key_id.clone(),
         algorithm: request.algorithm,
@@ -362,7 +1199,233 @@ pub async fn derive_key_from_password(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::encryption::types::KeyDerivationAlgorithm;
+    use crate::encryption::types::{EncryptedData, KeyDerivationAlgorithm};
+
+    #[tokio::test]
+    async fn test_key_commands_refuse_another_users_keys() {
+        let _session = crate::test_database::sign_in("660e8400-e29b-41d4-a716-446655440001").await;
+        let owner =
+            crate::error::ValidatedUserId::new("550e8400-e29b-41d4-a716-446655440000").unwrap();
+
+        let listed = list_user_keys(ListUserKeysRequest {
+            user_id: owner.clone(),
+        })
+        .await;
+        assert!(matches!(listed, Err(FiscusError::Authorization(_))));
+
+        let revoked = revoke_key(RevokeKeyRequest {
+            user_id: owner,
+            key_id: "770e8400-e29b-41d4-a716-446655440002".to_string(),
+        })
+        .await;
+        assert!(matches!(revoked, Err(FiscusError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_integrity_check_of_another_user_is_refused() {
+        let test_db = crate::test_database::TestDatabase::in_memory()
+            .await
+            .unwrap();
+        let app = test_db.app();
+        let _session = crate::test_database::sign_in("660e8400-e29b-41d4-a716-446655440001").await;
+
+        let result = verify_user_data_integrity(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            tauri::Manager::state(&app),
+        )
+        .await;
+        assert!(matches!(result, Err(FiscusError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_key_lineage_of_another_user_is_refused() {
+        let _session = crate::test_database::sign_in("660e8400-e29b-41d4-a716-446655440001").await;
+
+        let result = get_key_lineage(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "transaction".to_string(),
+        )
+        .await;
+        assert!(matches!(result, Err(FiscusError::Authorization(_))));
+    }
+
+    #[test]
+    fn test_integrity_queries_select_every_encrypted_field() {
+        for (table, query) in INTEGRITY_CHECK_QUERIES {
+            let columns = query
+                .trim_start_matches("SELECT ")
+                .split(" FROM ")
+                .next()
+                .unwrap_or_default();
+            let selected: Vec<&str> = columns.split(", ").collect();
+
+            for field in EncryptedDatabaseUtils::get_encrypted_fields(table) {
+                assert!(
+                    selected.contains(&field.as_str()),
+                    "{table} integrity check does not select {field}"
+                );
+            }
+        }
+    }
+
+    fn failing_service() -> FiscusResult<EncryptionService> {
+        Err(FiscusError::Internal("no entropy source".to_string()))
+    }
+
+    #[test]
+    fn test_failed_initialization_enters_degraded_mode() {
+        let slot = EncryptionServiceSlot::new();
+
+        let result = slot.initialize(failing_service);
+        assert!(result.is_err());
+
+        assert_eq!(
+            status_after(result),
+            EncryptionStatus::Unavailable("Internal server error: no entropy source".to_string())
+        );
+        match slot.get() {
+            Err(FiscusError::Encryption(message)) => assert_eq!(message, SERVICE_UNAVAILABLE),
+            other => panic!("expected Encryption error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_read_fails_cleanly_while_service_unavailable() {
+        let slot = EncryptionServiceSlot::new();
+        assert!(slot.initialize(failing_service).is_err());
+
+        let result = EncryptedDatabaseUtils::decrypt_query_results_with(
+            vec![encrypted_row("txn-1", "enc:AAAA")],
+            "550e8400-e29b-41d4-a716-446655440000",
+            "transactions",
+            slot.get(),
+        )
+        .await;
+        match result {
+            Err(FiscusError::Encryption(message)) => assert_eq!(message, SERVICE_UNAVAILABLE),
+            other => panic!("expected Encryption error, got {other:?}"),
+        }
+
+        // Rows without ciphertext, like unencrypted tables, are still readable
+        let rows = EncryptedDatabaseUtils::decrypt_query_results_with(
+            vec![encrypted_row("period-1", "12.00")],
+            "550e8400-e29b-41d4-a716-446655440000",
+            "budget_periods",
+            slot.get(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_supported_algorithms_report_real_capabilities() {
+        let service = EncryptionService::new().unwrap();
+        let response = supported_algorithms_response(&service);
+
+        let aes = response
+            .symmetric
+            .iter()
+            .find(|c| c.algorithm == EncryptionAlgorithm::Aes256Gcm)
+            .unwrap();
+        assert!(aes.encrypt);
+        assert!(!aes.sign);
+
+        let ed25519 = response
+            .asymmetric
+            .iter()
+            .find(|c| c.algorithm == EncryptionAlgorithm::Ed25519)
+            .unwrap();
+        assert!(ed25519.sign);
+        assert!(!ed25519.encrypt);
+        assert!(!ed25519.transmission_safe);
+
+        assert!(response
+            .asymmetric
+            .iter()
+            .any(|c| c.algorithm == EncryptionAlgorithm::Rsa4096 && c.transmission_safe));
+    }
+
+    #[test]
+    fn test_retry_recovers_from_failed_initialization() {
+        let slot = EncryptionServiceSlot::new();
+        assert!(slot.initialize(failing_service).is_err());
+
+        let result = slot.initialize(EncryptionService::new);
+
+        assert_eq!(status_after(result), EncryptionStatus::Available);
+        assert!(slot.get().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unencrypted_tables_work_without_service() {
+        // Categories carry no encrypted fields, so writes never reach the service
+        let params = vec![
+            ("id".to_string(), Value::String("category-1".to_string())),
+            ("name".to_string(), Value::String("Groceries".to_string())),
+        ];
+        let encrypted = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            params,
+            "550e8400-e29b-41d4-a716-446655440000",
+            "categories",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(encrypted[1], Value::String("Groceries".to_string()));
+    }
+
+    fn encrypted_row(id: &str, amount: &str) -> HashMap<String, Value> {
+        let mut row = HashMap::new();
+        row.insert("id".to_string(), Value::String(id.to_string()));
+        row.insert("amount".to_string(), Value::String(amount.to_string()));
+        row
+    }
+
+    /// Rewrite an encrypted field so its metadata references a key that does not exist
+    fn with_missing_key_id(encrypted: &str) -> String {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let decoded = engine
+            .decode(encrypted.strip_prefix("enc:").unwrap())
+            .unwrap();
+        let mut data: EncryptedData = serde_json::from_slice(&decoded).unwrap();
+        data.metadata.key_id = uuid::Uuid::new_v4().to_string();
+
+        let reencoded = engine.encode(serde_json::to_string(&data).unwrap());
+        format!("enc:{reencoded}")
+    }
+
+    #[tokio::test]
+    async fn test_verify_rows_reports_missing_key() {
+        let _ = initialize_encryption_service();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "integrity-test-user";
+
+        let good_amount = EncryptedDatabaseUtils::encrypt_field_value("42.00", user_id, "amount")
+            .await
+            .unwrap();
+        let other_amount = EncryptedDatabaseUtils::encrypt_field_value("13.37", user_id, "amount")
+            .await
+            .unwrap();
+        let bad_amount = with_missing_key_id(&good_amount);
+
+        let rows = vec![
+            encrypted_row("good-row", &good_amount),
+            encrypted_row("bad-row", &bad_amount),
+            encrypted_row("other-good-row", &other_amount),
+            encrypted_row("plaintext-row", "10.00"),
+        ];
+
+        let failures = verify_rows_decryptable("transactions", &rows, user_id).await;
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].row_id, "bad-row");
+        assert_eq!(failures[0].table, "transactions");
+        assert_eq!(failures[0].fields, vec!["amount".to_string()]);
+
+        // Verification must not mutate the rows it inspects
+        assert_eq!(rows[1]["amount"], Value::String(bad_amount));
+    }
 
     #[tokio::test]
     async fn test_encryption_service_initialization() {
@@ -466,7 +1529,10 @@ mod tests {
 
         let result = derive_key_from_password(request).await;
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), FiscusError::Validation(_)));
+        assert!(matches!(
+            result.unwrap_err(),
+            FiscusError::FieldValidation { .. }
+        ));
     }
 
     #[tokio::test]
@@ -484,4 +1550,164 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), FiscusError::InvalidInput(_)));
     }
+
+    #[tokio::test]
+    async fn test_wrapped_key_survives_storage_roundtrip() {
+        use crate::encryption::{AesGcmEncryption, KeyManager, SymmetricEncryption};
+
+        let key_manager = KeyManager::new().unwrap();
+        let master = AesGcmEncryption::new()
+            .unwrap()
+            .generate_key()
+            .await
+            .unwrap();
+        let key = key_manager
+            .get_or_create_key("test-user", "transaction_amount")
+            .await
+            .unwrap();
+
+        let wrapped = key_manager
+            .wrap_user_keys("test-user", &master)
+            .await
+            .unwrap()
+            .remove(0);
+        let params = wrapped_key_params(&wrapped, "test-user", "2024-01-01T00:00:00Z").unwrap();
+        let row = HashMap::from([
+            ("key_id".to_string(), params[0].clone()),
+            ("key_identifier".to_string(), params[2].clone()),
+            ("wrapped_key".to_string(), params[3].clone()),
+            ("algorithm".to_string(), params[4].clone()),
+            ("is_active".to_string(), Value::from(1)),
+        ]);
+
+        let restored = wrapped_key_from_row(&row).unwrap();
+        assert_eq!(restored.key_id, key.key_id);
+        assert_eq!(restored.key_identifier, "test-user:transaction_amount");
+        assert_eq!(restored.algorithm, EncryptionAlgorithm::Aes256Gcm);
+        assert!(restored.is_active);
+
+        let key_data = key_manager.unwrap_key(&restored, &master).await.unwrap();
+        assert_eq!(key_data.as_slice(), key.key_data.as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_rekeyed_keys_restore_into_fresh_key_manager() {
+        use crate::encryption::{AesGcmEncryption, KeyManager, KeyMetadata, SymmetricEncryption};
+
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+        let cipher = AesGcmEncryption::new().unwrap();
+        let key_manager = KeyManager::new().unwrap();
+
+        let original = key_manager
+            .get_or_create_key(user_id, "transaction_amount")
+            .await
+            .unwrap();
+        let encrypted = cipher.encrypt(b"1234.56", &original).await.unwrap();
+        key_manager.rotate_user_keys(user_id).await.unwrap();
+        let index_key = key_manager.get_or_create_index_key(user_id).await.unwrap();
+
+        let old_master = cipher.generate_key().await.unwrap();
+        let new_master = cipher.generate_key().await.unwrap();
+        let wrapped = key_manager
+            .wrap_user_keys(user_id, &old_master)
+            .await
+            .unwrap();
+        let rewrapped = key_manager
+            .rewrap_keys(&wrapped, &old_master, &new_master)
+            .await
+            .unwrap();
+
+        // Round-trip through the stored row format, as the next login reads it
+        let stored: Vec<WrappedKey> = rewrapped
+            .iter()
+            .map(|wrapped| {
+                let params = wrapped_key_params(wrapped, user_id, "2024-01-01T00:00:00Z").unwrap();
+                let row = HashMap::from([
+                    ("key_id".to_string(), params[0].clone()),
+                    ("key_identifier".to_string(), params[2].clone()),
+                    ("wrapped_key".to_string(), params[3].clone()),
+                    ("algorithm".to_string(), params[4].clone()),
+                    ("is_active".to_string(), params[5].clone()),
+                ]);
+                wrapped_key_from_row(&row).unwrap()
+            })
+            .collect();
+
+        let fresh = KeyManager::new().unwrap();
+        assert!(matches!(
+            fresh
+                .restore_wrapped_keys(user_id, &stored, &old_master)
+                .await,
+            Err(FiscusError::Authentication(_))
+        ));
+        assert_eq!(
+            fresh
+                .restore_wrapped_keys(user_id, &stored, &new_master)
+                .await
+                .unwrap(),
+            3
+        );
+
+        let restored = fresh.get_key_by_id(&original.key_id).await.unwrap();
+        assert!(!restored.is_active);
+        let decrypted = cipher.decrypt(&encrypted, &restored).await.unwrap();
+        assert_eq!(decrypted, b"1234.56");
+
+        let active_keys = |keys: Vec<KeyMetadata>| -> Vec<String> {
+            keys.into_iter()
+                .filter(|key| key.is_active)
+                .map(|key| key.key_id)
+                .collect()
+        };
+        assert_eq!(
+            active_keys(fresh.list_user_keys(user_id).await.unwrap()),
+            active_keys(key_manager.list_user_keys(user_id).await.unwrap())
+        );
+        let restored_index = fresh.get_or_create_index_key(user_id).await.unwrap();
+        assert_eq!(
+            restored_index.key_data.as_slice(),
+            index_key.key_data.as_slice()
+        );
+    }
+
+    #[test]
+    fn test_self_test_report_shape() {
+        let report = self_test_report(vec![
+            ("aes256_gcm_roundtrip", Ok(())),
+            (
+                "aes256_gcm_tamper_detected",
+                Err(FiscusError::Encryption(
+                    "tampered data decrypted".to_string(),
+                )),
+            ),
+        ]);
+
+        assert!(!report.passed);
+        assert_eq!(
+            report.checks,
+            vec![
+                SelfTestCheck {
+                    name: "aes256_gcm_roundtrip".to_string(),
+                    passed: true,
+                    detail: None,
+                },
+                SelfTestCheck {
+                    name: "aes256_gcm_tamper_detected".to_string(),
+                    passed: false,
+                    detail: Some("Encryption error: tampered data decrypted".to_string()),
+                },
+            ]
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["checks", "passed", "ran_at"]);
+    }
 }
@@ -0,0 +1,891 @@
+use base64::Engine;
+use chrono::Utc;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufWriter, Write};
+use tauri::State;
+use tracing::info;
+
+use crate::{
+    clock::SystemClock,
+    commands::{
+        encryption::get_encryption_service,
+        transactions::{next_page_cursor, query_transactions, TransactionCsvWriter},
+    },
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
+    dto::{
+        CsvOptions, ExportFormat, SignedArchiveResponse, TransactionExportSummary,
+        TransactionFilters, UserDataArchive,
+    },
+    encryption::{EncryptionAlgorithm, EncryptionService},
+    error::{FiscusError, FiscusResult, ValidatedUserId, Validator},
+    models::Transaction,
+    security::{
+        active_context, authorize_command, authorize_user, require_recent_auth, RECENT_AUTH_MAX_AGE,
+    },
+};
+
+/// Version of the archive document layout; bump when sections change shape
+pub const USER_ARCHIVE_VERSION: u32 = 1;
+
+/// Number of transactions decrypted per page while building an archive
+const TRANSACTION_EXPORT_PAGE_SIZE: i64 = 500;
+
+/// Number of transactions fetched per cursor page while streaming an export
+const STREAMING_EXPORT_PAGE_SIZE: i32 = 500;
+
+type Row = HashMap<String, Value>;
+
+/// Export every record owned by a user as a single versioned JSON document.
+///
+/// Goal contributions are not stored as separate rows; each goal's
+/// `current_amount` already reflects them. Requires the session to have
+/// authenticated recently.
+#[tauri::command]
+pub async fn export_user_archive(
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<UserDataArchive, FiscusError> {
+    authorize_command("export_user_archive").await?;
+    require_recent_auth(
+        active_context().await.as_ref(),
+        RECENT_AUTH_MAX_AGE,
+        &SystemClock,
+    )?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    authorize_user(&user_id).await?;
+
+    build_user_archive(&db, &user_id).await
+}
+
+/// Collect every record owned by `user_id` into an archive
+async fn build_user_archive(db: &Database, user_id: &str) -> FiscusResult<UserDataArchive> {
+    DatabaseUtils::validate_user_exists(db, user_id).await?;
+
+    let accounts: Vec<Row> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        r#"
+        SELECT id, user_id, account_type_id, name, balance, opening_balance,
+               opening_balance_date, currency, account_number, is_active,
+               status, closing_date, default_category_id, default_payee,
+               created_at, updated_at
+        FROM accounts
+        WHERE user_id = ?1
+        ORDER BY created_at
+        "#,
+        vec![Value::String(user_id.to_string())],
+        user_id,
+        "accounts",
+    )
+    .await?;
+
+    let transactions = fetch_transactions_paged(db, user_id).await?;
+
+    let transfers = fetch_user_rows(
+        db,
+        user_id,
+        r#"
+        SELECT id, user_id, to_user_id, from_account_id, to_account_id, from_transaction_id,
+               to_transaction_id, amount, description, transfer_date, created_at
+        FROM transfers
+        WHERE user_id = ?1
+        ORDER BY transfer_date
+        "#,
+        "transfers",
+    )
+    .await?;
+
+    let categories = fetch_user_rows(
+        db,
+        user_id,
+        r#"
+        SELECT id, user_id, name, description, color, icon, parent_category_id,
+               is_income, tax_deductible, is_active, created_at, updated_at
+        FROM categories
+        WHERE user_id = ?1
+        ORDER BY name
+        "#,
+        "categories",
+    )
+    .await?;
+
+    let budget_periods = fetch_user_rows(
+        db,
+        user_id,
+        r#"
+        SELECT id, user_id, name, start_date, end_date, is_active, created_at, updated_at
+        FROM budget_periods
+        WHERE user_id = ?1
+        ORDER BY start_date
+        "#,
+        "budget_periods",
+    )
+    .await?;
+
+    let budgets = fetch_user_rows(
+        db,
+        user_id,
+        r#"
+        SELECT id, user_id, budget_period_id, category_id, allocated_amount,
+               spent_amount, notes, rollover, created_at, updated_at
+        FROM budgets
+        WHERE user_id = ?1
+        ORDER BY created_at
+        "#,
+        "budgets",
+    )
+    .await?;
+
+    let goals = fetch_user_rows(
+        db,
+        user_id,
+        r#"
+        SELECT id, user_id, name, description, target_amount, current_amount,
+               target_date, priority, status, category, created_at, updated_at
+        FROM goals
+        WHERE user_id = ?1
+        ORDER BY created_at
+        "#,
+        "goals",
+    )
+    .await?;
+
+    Ok(assemble_user_archive(
+        user_id,
+        ArchiveSections {
+            accounts,
+            transactions,
+            transfers,
+            categories,
+            budget_periods,
+            budgets,
+            goals,
+        },
+    ))
+}
+
+/// Export a user's archive together with a detached signature for tamper evidence
+///
+/// The archive is serialized canonically (object keys sorted) and signed with
+/// the user's Ed25519 key; check it later with `verify_archive_signature`.
+/// Requires the session to have authenticated recently.
+#[tauri::command]
+pub async fn export_signed_archive(
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<SignedArchiveResponse, FiscusError> {
+    authorize_command("export_signed_archive").await?;
+    require_recent_auth(
+        active_context().await.as_ref(),
+        RECENT_AUTH_MAX_AGE,
+        &SystemClock,
+    )?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    authorize_user(&user_id).await?;
+
+    let archive = build_user_archive(&db, &user_id).await?;
+    let service = get_encryption_service()?;
+
+    let signed = sign_archive(&service, &archive).await?;
+    info!(user_id = %archive.user_id, "Signed user archive");
+
+    Ok(signed)
+}
+
+/// Check a signature returned by `export_signed_archive`
+///
+/// `archive` must be exactly the string that was exported; any change to it,
+/// including reformatting, makes verification fail.
+#[tauri::command]
+pub async fn verify_archive_signature(
+    archive: String,
+    signature: String,
+    public_key: String,
+) -> Result<bool, FiscusError> {
+    let service = get_encryption_service()?;
+
+    verify_archive(&service, &archive, &signature, &public_key).await
+}
+
+async fn sign_archive(
+    service: &EncryptionService,
+    archive: &UserDataArchive,
+) -> FiscusResult<SignedArchiveResponse> {
+    let canonical = canonical_archive_json(archive)?;
+    let (signature, public_key) = service
+        .sign_for_user(canonical.as_bytes(), &archive.user_id)
+        .await?;
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    Ok(SignedArchiveResponse {
+        archive: canonical,
+        signature: engine.encode(signature),
+        public_key: engine.encode(public_key),
+        algorithm: EncryptionAlgorithm::Ed25519,
+        signed_at: Utc::now(),
+    })
+}
+
+async fn verify_archive(
+    service: &EncryptionService,
+    archive: &str,
+    signature: &str,
+    public_key: &str,
+) -> FiscusResult<bool> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let signature = engine
+        .decode(signature)
+        .map_err(|e| FiscusError::InvalidInput(format!("Invalid base64 signature: {e}")))?;
+    let public_key = engine
+        .decode(public_key)
+        .map_err(|e| FiscusError::InvalidInput(format!("Invalid base64 public key: {e}")))?;
+
+    service
+        .verify_signature(archive.as_bytes(), &signature, &public_key)
+        .await
+}
+
+/// Serialize `archive` with object keys in sorted order, so the same archive
+/// always produces the same bytes
+fn canonical_archive_json(archive: &UserDataArchive) -> FiscusResult<String> {
+    let value = serde_json::to_value(archive)
+        .map_err(|e| FiscusError::Internal(format!("Failed to serialize archive: {e}")))?;
+
+    serde_json::to_string(&sort_keys(value))
+        .map_err(|e| FiscusError::Internal(format!("Failed to serialize archive: {e}")))
+}
+
+/// Rebuild every object in `value` with its keys inserted in sorted order
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Run a `WHERE user_id = ?1` query and decrypt the table's sensitive fields
+async fn fetch_user_rows(
+    db: &Database,
+    user_id: &str,
+    query: &str,
+    table_name: &str,
+) -> Result<Vec<Row>, FiscusError> {
+    EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        query,
+        vec![Value::String(user_id.to_string())],
+        user_id,
+        table_name,
+    )
+    .await
+}
+
+/// Fetch and decrypt a user's transactions one page at a time so a single
+/// decryption batch never holds the whole history
+async fn fetch_transactions_paged(db: &Database, user_id: &str) -> Result<Vec<Row>, FiscusError> {
+    let query = r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee,
+               tags, original_amount, original_currency, refunds_transaction_id,
+               created_at, updated_at
+        FROM transactions
+        WHERE user_id = ?1
+        ORDER BY transaction_date, id
+        LIMIT ?2 OFFSET ?3
+    "#;
+
+    let mut transactions = Vec::new();
+    let mut offset: i64 = 0;
+
+    loop {
+        let page: Vec<Row> = EncryptedDatabaseUtils::execute_encrypted_query(
+            db,
+            query,
+            vec![
+                Value::String(user_id.to_string()),
+                Value::Number(TRANSACTION_EXPORT_PAGE_SIZE.into()),
+                Value::Number(offset.into()),
+            ],
+            user_id,
+            "transactions",
+        )
+        .await?;
+
+        let page_len = page.len() as i64;
+        transactions.extend(page);
+
+        if page_len < TRANSACTION_EXPORT_PAGE_SIZE {
+            break;
+        }
+        offset += TRANSACTION_EXPORT_PAGE_SIZE;
+    }
+
+    Ok(transactions)
+}
+
+/// Export all of a user's transactions to `file_path` as JSON or CSV
+///
+/// Transactions are fetched a cursor page at a time and written as they
+/// arrive, so memory use stays flat however long the history is. The output
+/// is written next to the target and only moved into place once complete.
+/// CSV output uses `csv_options`, or comma-separated RFC 4180 without a BOM.
+#[tauri::command]
+pub async fn export_transactions_streaming(
+    user_id: String,
+    format: ExportFormat,
+    file_path: String,
+    csv_options: Option<CsvOptions>,
+    db: State<'_, Database>,
+) -> Result<TransactionExportSummary, FiscusError> {
+    authorize_command("export_transactions").await?;
+
+    let user_id = ValidatedUserId::new(&user_id)?;
+    Validator::validate_string(&file_path, "file_path", 1, 4096)?;
+
+    let csv_options = csv_options.unwrap_or_default();
+
+    let partial_path = format!("{file_path}.partial");
+    let file = File::create(&partial_path)
+        .map_err(|e| FiscusError::Internal(format!("Failed to create export file: {e}")))?;
+
+    let mut writer = match TransactionExportWriter::new(BufWriter::new(file), format, &csv_options)
+    {
+        Ok(writer) => writer,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+    let streamed = stream_transactions(&mut writer, |cursor| {
+        query_transactions(export_page_filters(&user_id, cursor), &db, true)
+    })
+    .await;
+
+    let transaction_count = match streamed.and_then(|()| writer.finish()) {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+
+    std::fs::rename(&partial_path, &file_path)
+        .map_err(|e| FiscusError::Internal(format!("Failed to finalize export file: {e}")))?;
+
+    info!(transaction_count, "Streamed transaction export");
+
+    Ok(TransactionExportSummary {
+        file_path,
+        transaction_count,
+    })
+}
+
+/// Filters selecting one keyset page of a user's transactions
+fn export_page_filters(user_id: &ValidatedUserId, cursor: Option<String>) -> TransactionFilters {
+    TransactionFilters {
+        user_id: user_id.clone(),
+        account_id: None,
+        category_id: None,
+        transaction_type: None,
+        status: None,
+        start_date: None,
+        end_date: None,
+        min_amount: None,
+        max_amount: None,
+        search: None,
+        payee: None,
+        tag_filter: None,
+        cursor,
+        sort_by: None,
+        sort_direction: None,
+        limit: Some(STREAMING_EXPORT_PAGE_SIZE),
+        offset: None,
+        fields: None,
+    }
+}
+
+/// Write every page returned by `fetch_page`, following cursors until the last page
+async fn stream_transactions<W, F, Fut>(
+    writer: &mut TransactionExportWriter<W>,
+    mut fetch_page: F,
+) -> FiscusResult<()>
+where
+    W: Write,
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = FiscusResult<Vec<Transaction>>>,
+{
+    let mut cursor = None;
+
+    loop {
+        let page = fetch_page(cursor.take()).await?;
+        for transaction in &page {
+            writer.write(transaction)?;
+        }
+
+        match next_page_cursor(&page, STREAMING_EXPORT_PAGE_SIZE) {
+            Some(next) => cursor = Some(next),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Encodes transactions one at a time, keeping JSON array framing valid across pages
+struct TransactionExportWriter<W: Write> {
+    encoder: ExportEncoder<W>,
+    written: usize,
+}
+
+enum ExportEncoder<W: Write> {
+    Json(W),
+    Csv(Box<TransactionCsvWriter<W>>),
+}
+
+impl<W: Write> TransactionExportWriter<W> {
+    fn new(mut out: W, format: ExportFormat, csv_options: &CsvOptions) -> FiscusResult<Self> {
+        let encoder = match format {
+            ExportFormat::Json => {
+                out.write_all(b"[").map_err(export_io_error)?;
+                ExportEncoder::Json(out)
+            }
+            ExportFormat::Csv => {
+                ExportEncoder::Csv(Box::new(TransactionCsvWriter::new(out, csv_options)?))
+            }
+        };
+
+        Ok(Self {
+            encoder,
+            written: 0,
+        })
+    }
+
+    fn write(&mut self, transaction: &Transaction) -> FiscusResult<()> {
+        match &mut self.encoder {
+            ExportEncoder::Json(out) => {
+                if self.written > 0 {
+                    out.write_all(b",").map_err(export_io_error)?;
+                }
+                serde_json::to_writer(out, transaction).map_err(|e| {
+                    FiscusError::Internal(format!("JSON serialization failed: {e}"))
+                })?;
+            }
+            ExportEncoder::Csv(writer) => writer.write(transaction)?,
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close the JSON array, flush, and return the number of transactions written
+    fn finish(&mut self) -> FiscusResult<usize> {
+        match &mut self.encoder {
+            ExportEncoder::Json(out) => {
+                out.write_all(b"]").map_err(export_io_error)?;
+                out.flush().map_err(export_io_error)?;
+            }
+            ExportEncoder::Csv(writer) => writer.flush()?,
+        }
+        Ok(self.written)
+    }
+}
+
+fn export_io_error(e: std::io::Error) -> FiscusError {
+    FiscusError::Internal(format!("Failed to write export: {e}"))
+}
+
+/// Raw rows gathered for each archive section
+struct ArchiveSections {
+    accounts: Vec<Row>,
+    transactions: Vec<Row>,
+    transfers: Vec<Row>,
+    categories: Vec<Row>,
+    budget_periods: Vec<Row>,
+    budgets: Vec<Row>,
+    goals: Vec<Row>,
+}
+
+/// Build the archive document, keeping only rows owned by `user_id`
+fn assemble_user_archive(user_id: &str, sections: ArchiveSections) -> UserDataArchive {
+    let owned = |rows: Vec<Row>| -> Vec<Row> {
+        rows.into_iter()
+            .filter(|row| row.get("user_id").and_then(|v| v.as_str()) == Some(user_id))
+            .collect()
+    };
+
+    UserDataArchive {
+        version: USER_ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        user_id: user_id.to_string(),
+        accounts: owned(sections.accounts),
+        transactions: owned(sections.transactions),
+        transfers: owned(sections.transfers),
+        categories: owned(sections.categories),
+        budget_periods: owned(sections.budget_periods),
+        budgets: owned(sections.budgets),
+        goals: owned(sections.goals),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_database::{sign_in, TestDatabase};
+    use serde_json::json;
+    use tauri::Manager;
+
+    const OWNER: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const OTHER: &str = "660e8400-e29b-41d4-a716-446655440001";
+
+    fn row(id: &str, user_id: &str) -> Row {
+        let mut row = HashMap::new();
+        row.insert("id".to_string(), json!(id));
+        row.insert("user_id".to_string(), json!(user_id));
+        row
+    }
+
+    fn rows(prefix: &str, count: usize) -> Vec<Row> {
+        (0..count)
+            .map(|i| row(&format!("{prefix}-{i}"), OWNER))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_archive_of_another_user_is_refused() {
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let _session = sign_in(OTHER).await;
+
+        let result = export_user_archive(OWNER.to_string(), app.state()).await;
+        assert!(matches!(result, Err(FiscusError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_signed_archive_of_another_user_is_refused() {
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let _session = sign_in(OTHER).await;
+
+        let result = export_signed_archive(OWNER.to_string(), app.state()).await;
+        assert!(matches!(result, Err(FiscusError::Authorization(_))));
+    }
+
+    #[test]
+    fn test_archive_contains_every_entity_type() {
+        let mut transactions = rows("tx", 5);
+        transactions.push(row("tx-foreign", OTHER));
+
+        let archive = assemble_user_archive(
+            OWNER,
+            ArchiveSections {
+                accounts: rows("acct", 2),
+                transactions,
+                transfers: rows("xfer", 1),
+                categories: rows("cat", 3),
+                budget_periods: rows("period", 1),
+                budgets: rows("budget", 2),
+                goals: rows("goal", 1),
+            },
+        );
+
+        assert_eq!(archive.version, USER_ARCHIVE_VERSION);
+        assert_eq!(archive.user_id, OWNER);
+        assert_eq!(archive.accounts.len(), 2);
+        assert_eq!(archive.transactions.len(), 5);
+        assert_eq!(archive.transfers.len(), 1);
+        assert_eq!(archive.categories.len(), 3);
+        assert_eq!(archive.budget_periods.len(), 1);
+        assert_eq!(archive.budgets.len(), 2);
+        assert_eq!(archive.goals.len(), 1);
+
+        let serialized = serde_json::to_value(&archive).unwrap();
+        for section in [
+            "accounts",
+            "transactions",
+            "transfers",
+            "categories",
+            "budget_periods",
+            "budgets",
+            "goals",
+        ] {
+            assert!(serialized[section].is_array(), "missing section {section}");
+        }
+    }
+
+    #[test]
+    fn test_archive_excludes_other_users_rows() {
+        let archive = assemble_user_archive(
+            OWNER,
+            ArchiveSections {
+                accounts: vec![row("acct-1", OWNER), row("acct-2", OTHER)],
+                transactions: vec![row("tx-1", OTHER)],
+                transfers: Vec::new(),
+                categories: vec![row("cat-1", OTHER)],
+                budget_periods: Vec::new(),
+                budgets: Vec::new(),
+                goals: Vec::new(),
+            },
+        );
+
+        assert_eq!(archive.accounts.len(), 1);
+        assert_eq!(archive.accounts[0]["id"], json!("acct-1"));
+        assert!(archive.transactions.is_empty());
+        assert!(archive.categories.is_empty());
+    }
+
+    /// Store `fields` for `table` the way the write paths do, then read the
+    /// row back through the same decryption `fetch_user_rows` applies
+    async fn stored_and_read_back(table: &str, fields: &[(&str, &str)]) -> Vec<Row> {
+        let mut record = row(&format!("{table}-1"), OWNER);
+        for (field, value) in fields {
+            record.insert(field.to_string(), json!(value));
+        }
+        EncryptedDatabaseUtils::encrypt_record(&mut record, OWNER, table)
+            .await
+            .unwrap();
+
+        EncryptedDatabaseUtils::decrypt_query_results(vec![record], OWNER, table)
+            .await
+            .unwrap()
+    }
+
+    fn collect_strings<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+        match value {
+            Value::String(s) => out.push(s),
+            Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+            Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+            _ => {}
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_contains_no_ciphertext() {
+        crate::commands::encryption::initialize_encryption_service()
+            .expect("Failed to initialize encryption service for test");
+
+        let transfers =
+            stored_and_read_back("transfers", &[("amount", "40.00"), ("description", "Rent")])
+                .await;
+        let budgets = stored_and_read_back(
+            "budgets",
+            &[("allocated_amount", "300.00"), ("spent_amount", "120.50")],
+        )
+        .await;
+        let goals = stored_and_read_back(
+            "goals",
+            &[
+                ("target_amount", "5000.00"),
+                ("current_amount", "750.00"),
+                ("description", "Emergency fund"),
+            ],
+        )
+        .await;
+
+        let archive = assemble_user_archive(
+            OWNER,
+            ArchiveSections {
+                accounts: Vec::new(),
+                transactions: Vec::new(),
+                transfers,
+                categories: Vec::new(),
+                budget_periods: Vec::new(),
+                budgets,
+                goals,
+            },
+        );
+        assert_eq!(archive.transfers[0]["amount"], json!("40.00"));
+        assert_eq!(archive.budgets[0]["spent_amount"], json!("120.50"));
+        assert_eq!(archive.goals[0]["description"], json!("Emergency fund"));
+
+        let serialized = serde_json::to_value(&archive).unwrap();
+        let mut values = Vec::new();
+        collect_strings(&serialized, &mut values);
+        assert!(
+            values.iter().all(|value| !value.starts_with("enc:")),
+            "archive leaked ciphertext"
+        );
+    }
+
+    fn signable_archive() -> UserDataArchive {
+        let mut account = row("acct-1", OWNER);
+        account.insert("name".to_string(), json!("Everyday checking"));
+        account.insert("balance".to_string(), json!("1250.00"));
+
+        assemble_user_archive(
+            OWNER,
+            ArchiveSections {
+                accounts: vec![account],
+                transactions: rows("tx", 3),
+                transfers: Vec::new(),
+                categories: rows("cat", 2),
+                budget_periods: Vec::new(),
+                budgets: Vec::new(),
+                goals: Vec::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_canonical_archive_json_sorts_keys() {
+        let archive = signable_archive();
+
+        let canonical = canonical_archive_json(&archive).unwrap();
+        assert_eq!(canonical, canonical_archive_json(&archive).unwrap());
+
+        let account = &canonical[canonical.find("\"accounts\"").unwrap()..];
+        let balance = account.find("\"balance\"").unwrap();
+        let id = account.find("\"id\"").unwrap();
+        let name = account.find("\"name\"").unwrap();
+        assert!(balance < id && id < name);
+    }
+
+    #[tokio::test]
+    async fn test_signed_archive_verifies() {
+        let service = EncryptionService::new().unwrap();
+
+        let signed = sign_archive(&service, &signable_archive()).await.unwrap();
+
+        assert_eq!(signed.algorithm, EncryptionAlgorithm::Ed25519);
+        assert!(verify_archive(
+            &service,
+            &signed.archive,
+            &signed.signature,
+            &signed.public_key
+        )
+        .await
+        .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_flipped_byte_fails_archive_verification() {
+        let service = EncryptionService::new().unwrap();
+        let signed = sign_archive(&service, &signable_archive()).await.unwrap();
+
+        let mut tampered = signed.archive.clone().into_bytes();
+        let position = signed.archive.find("1250.00").unwrap();
+        tampered[position] ^= 0x01;
+        let tampered = String::from_utf8(tampered).unwrap();
+
+        assert!(
+            !verify_archive(&service, &tampered, &signed.signature, &signed.public_key)
+                .await
+                .unwrap()
+        );
+    }
+
+    mod streaming {
+        use super::*;
+        use crate::{
+            commands::transactions::TRANSACTION_CSV_HEADER, models::TransactionType,
+            test_utils::TestUtils,
+        };
+        use rust_decimal::Decimal;
+        use std::cell::Cell;
+
+        const ACCOUNT_ID: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+
+        /// Transactions newest first with distinct timestamps, as the keyset query returns them
+        fn seeded_transactions(count: usize) -> Vec<Transaction> {
+            let newest = Utc::now();
+            (0..count)
+                .map(|i| {
+                    let mut transaction = TestUtils::create_test_transaction(
+                        OWNER,
+                        ACCOUNT_ID,
+                        Decimal::new(i as i64 + 1, 2),
+                        TransactionType::Expense,
+                    );
+                    transaction.transaction_date = newest - chrono::Duration::seconds(i as i64);
+                    transaction
+                })
+                .collect()
+        }
+
+        /// Stream `seeded` through the writer, serving it a page at a time
+        async fn export(seeded: &[Transaction], format: ExportFormat) -> (Vec<u8>, usize, usize) {
+            let served = Cell::new(0);
+            let fetches = Cell::new(0);
+
+            let mut writer =
+                TransactionExportWriter::new(Vec::new(), format, &CsvOptions::default()).unwrap();
+            stream_transactions(&mut writer, |cursor| {
+                let start = served.get();
+                assert_eq!(cursor.is_some(), start > 0);
+
+                let page: Vec<Transaction> = seeded[start..]
+                    .iter()
+                    .take(STREAMING_EXPORT_PAGE_SIZE as usize)
+                    .cloned()
+                    .collect();
+                served.set(start + page.len());
+                fetches.set(fetches.get() + 1);
+                async move { Ok(page) }
+            })
+            .await
+            .unwrap();
+
+            let count = writer.finish().unwrap();
+            let output = match writer.encoder {
+                ExportEncoder::Json(out) => out,
+                ExportEncoder::Csv(csv) => csv.into_inner().unwrap(),
+            };
+            (output, count, fetches.get())
+        }
+
+        #[tokio::test]
+        async fn test_json_export_of_large_history_parses() {
+            let seeded = seeded_transactions(10_000);
+
+            let (output, count, fetches) = export(&seeded, ExportFormat::Json).await;
+
+            assert_eq!(count, 10_000);
+            // 20 full pages, then an empty page ends the cursor walk
+            assert_eq!(fetches, 21);
+
+            let parsed: Vec<Transaction> = serde_json::from_slice(&output).unwrap();
+            assert_eq!(parsed.len(), seeded.len());
+            assert!(parsed
+                .iter()
+                .zip(&seeded)
+                .all(|(exported, original)| exported.id == original.id));
+        }
+
+        #[tokio::test]
+        async fn test_csv_export_writes_header_and_every_row() {
+            let seeded = seeded_transactions(1_234);
+
+            let (output, count, fetches) = export(&seeded, ExportFormat::Csv).await;
+
+            assert_eq!(count, 1_234);
+            assert_eq!(fetches, 3);
+
+            let text = String::from_utf8(output).unwrap();
+            let mut lines = text.lines();
+            assert_eq!(
+                Some(TRANSACTION_CSV_HEADER.join(",").as_str()),
+                lines.next()
+            );
+            assert_eq!(lines.count(), 1_234);
+        }
+
+        #[tokio::test]
+        async fn test_empty_json_export_is_an_empty_array() {
+            let (output, count, _) = export(&[], ExportFormat::Json).await;
+
+            assert_eq!(count, 0);
+            assert_eq!(output, b"[]");
+        }
+    }
+}
@@ -5,9 +5,14 @@ use uuid::Uuid;
 
 use crate::{
     database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
-    dto::{CreateGoalRequest, GoalFilters, UpdateGoalRequest},
+    dto::{
+        CreateGoalRequest, GoalFilters, GoalProgressResponse, GoalProjectionResponse,
+        UpdateGoalRequest,
+    },
+    env_config,
     error::{FiscusError, Validator},
     models::{Goal, GoalStatus},
+    services::events::{self, TransactionEvent},
     utils::parse_decimal_from_json,
 };
 use rust_decimal::prelude::ToPrimitive;
@@ -27,7 +32,7 @@ pub async fn create_goal(
     }
 
     let target_date = if let Some(ref date_str) = request.target_date {
-        Some(Validator::validate_date(date_str)?)
+        Some(Validator::validate_date(date_str, "target_date")?)
     } else {
         None
     };
@@ -284,7 +289,7 @@ pub async fn update_goal(
     }
 
     if let Some(target_date) = &request.target_date {
-        let parsed_date = Validator::validate_date(target_date)?;
+        let parsed_date = Validator::validate_date(target_date, "target_date")?;
         update_fields.push(format!("\"target_date\" = ?{param_index}"));
         params_with_mapping.push((
             "target_date".to_string(),
@@ -391,7 +396,7 @@ pub async fn update_goal_progress(
     user_id: String,
     amount: rust_decimal::Decimal,
     db: State<'_, Database>,
-) -> Result<Goal, FiscusError> {
+) -> Result<GoalProgressResponse, FiscusError> {
     // Validate input
     Validator::validate_uuid(&goal_id, "goal_id")?;
     Validator::validate_uuid(&user_id, "user_id")?;
@@ -412,8 +417,26 @@ pub async fn update_goal_progress(
         new_status = GoalStatus::Completed;
     }
 
-    let update_query =
-        "UPDATE goals SET current_amount = ?1, status = ?2, updated_at = ?3 WHERE id = ?4";
+    // Milestones are stored unencrypted; only the highest one reported so far is kept
+    let milestone_row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        &db,
+        "SELECT last_milestone FROM goals WHERE id = ?1",
+        vec![Value::String(goal_id.clone())],
+    )
+    .await?;
+    let last_milestone = milestone_row
+        .and_then(|row| row.get("last_milestone").and_then(|v| v.as_u64()))
+        .unwrap_or(0) as u32;
+
+    let milestones_reached = newly_reached_milestones(
+        &GoalMilestoneConfig::current().milestones,
+        last_milestone,
+        new_current_amount,
+        current_goal.target_amount,
+    );
+    let new_last_milestone = milestones_reached.last().copied().unwrap_or(last_milestone);
+
+    let update_query = "UPDATE goals SET current_amount = ?1, status = ?2, last_milestone = ?3, updated_at = ?4 WHERE id = ?5";
 
     // Use encrypted parameter mapping for sensitive fields
     let params_with_mapping = vec![
@@ -422,6 +445,10 @@ pub async fn update_goal_progress(
             Value::String(new_current_amount.to_string()),
         ),
         ("status".to_string(), Value::String(new_status.to_string())),
+        (
+            "last_milestone".to_string(),
+            Value::Number(new_last_milestone.into()),
+        ),
         (
             "updated_at".to_string(),
             Value::String(chrono::Utc::now().to_rfc3339()),
@@ -440,8 +467,21 @@ pub async fn update_goal_progress(
         return Err(FiscusError::NotFound("Goal not found".to_string()));
     }
 
+    events::publish(milestones_reached.iter().map(|milestone| {
+        TransactionEvent::GoalMilestoneReached {
+            user_id: user_id.clone(),
+            goal_id: goal_id.clone(),
+            milestone: *milestone,
+        }
+    }));
+
     // Return updated goal
-    get_goal_by_id(goal_id, db).await
+    let goal = get_goal_by_id(goal_id, db).await?;
+
+    Ok(GoalProgressResponse {
+        goal,
+        milestones_reached,
+    })
 }
 
 /// Get goal progress summary for a user
@@ -549,3 +589,300 @@ pub async fn get_goal_progress_summary(
 
     Ok(summary)
 }
+
+/// Environment variable overriding the goal milestone percentages
+const GOAL_MILESTONES_ENV: &str = "FISCUS_GOAL_MILESTONES";
+
+/// Default goal milestones: every quarter of the target
+const DEFAULT_GOAL_MILESTONES: [u32; 4] = [25, 50, 75, 100];
+
+/// Progress percentages at which a goal reports a milestone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GoalMilestoneConfig {
+    /// Ascending and without duplicates
+    pub milestones: Vec<u32>,
+}
+
+impl Default for GoalMilestoneConfig {
+    fn default() -> Self {
+        Self {
+            milestones: DEFAULT_GOAL_MILESTONES.to_vec(),
+        }
+    }
+}
+
+impl GoalMilestoneConfig {
+    /// Configuration read from `FISCUS_GOAL_MILESTONES`
+    ///
+    /// Takes a comma-separated list of percentages between 1 and 100; an
+    /// unset or invalid value falls back to the quartiles.
+    pub fn from_env() -> Self {
+        std::env::var(GOAL_MILESTONES_ENV)
+            .ok()
+            .and_then(|value| parse_goal_milestones(&value))
+            .map(|milestones| Self { milestones })
+            .unwrap_or_default()
+    }
+}
+
+env_config!(GoalMilestoneConfig);
+
+/// Parse a comma-separated milestone list, `None` if any entry is invalid
+fn parse_goal_milestones(value: &str) -> Option<Vec<u32>> {
+    let mut milestones = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+
+    if milestones.is_empty() || milestones.iter().any(|m| !(1..=100).contains(m)) {
+        return None;
+    }
+
+    milestones.sort_unstable();
+    milestones.dedup();
+    Some(milestones)
+}
+
+/// Milestones crossed by a goal that has not yet reported any above `already_reached`
+///
+/// A single contribution may cross several milestones; all of them are
+/// returned in ascending order.
+fn newly_reached_milestones(
+    milestones: &[u32],
+    already_reached: u32,
+    current_amount: rust_decimal::Decimal,
+    target_amount: rust_decimal::Decimal,
+) -> Vec<u32> {
+    if target_amount <= rust_decimal::Decimal::ZERO {
+        return Vec::new();
+    }
+
+    let progress = current_amount * rust_decimal::Decimal::from(100) / target_amount;
+
+    milestones
+        .iter()
+        .copied()
+        .filter(|milestone| {
+            *milestone > already_reached && rust_decimal::Decimal::from(*milestone) <= progress
+        })
+        .collect()
+}
+
+/// Upper bound on simulated months (100 years) to guarantee termination
+const MAX_PROJECTION_MONTHS: u32 = 1200;
+
+/// Simulate month-by-month compounding plus a fixed contribution.
+///
+/// Interest accrues at `annual_rate / 12` on the running balance before the
+/// contribution is added. Returns the number of months until `target_amount`
+/// is reached, or `None` if it is not reached within `MAX_PROJECTION_MONTHS`.
+fn simulate_goal_completion(
+    current_amount: rust_decimal::Decimal,
+    target_amount: rust_decimal::Decimal,
+    monthly_contribution: rust_decimal::Decimal,
+    annual_rate: rust_decimal::Decimal,
+) -> Option<u32> {
+    if current_amount >= target_amount {
+        return Some(0);
+    }
+
+    let monthly_rate = annual_rate / rust_decimal::Decimal::from(12);
+
+    // Without contributions the balance can only grow through interest on a positive balance
+    if monthly_contribution.is_zero()
+        && (monthly_rate.is_zero() || current_amount <= rust_decimal::Decimal::ZERO)
+    {
+        return None;
+    }
+
+    let mut balance = current_amount;
+    for month in 1..=MAX_PROJECTION_MONTHS {
+        balance = (balance + balance * monthly_rate + monthly_contribution).round_dp(2);
+        if balance >= target_amount {
+            return Some(month);
+        }
+    }
+
+    None
+}
+
+/// Project when a goal will be completed given a monthly contribution and annual interest rate
+///
+/// `annual_rate` is a fraction (e.g. `0.05` for 5%).
+#[tauri::command]
+pub async fn project_goal_completion(
+    goal_id: String,
+    monthly_contribution: rust_decimal::Decimal,
+    annual_rate: rust_decimal::Decimal,
+    db: State<'_, Database>,
+) -> Result<GoalProjectionResponse, FiscusError> {
+    // Validate input
+    Validator::validate_uuid(&goal_id, "goal_id")?;
+
+    Validator::validate_amount(monthly_contribution, false)?;
+
+    if annual_rate < rust_decimal::Decimal::ZERO || annual_rate > rust_decimal::Decimal::ONE {
+        return Err(FiscusError::field_validation(
+            "annual_rate",
+            "out_of_range",
+            "Annual rate must be between 0 and 1",
+        ));
+    }
+
+    let goal = get_goal_by_id(goal_id.clone(), db).await?;
+
+    let months_to_target = simulate_goal_completion(
+        goal.current_amount,
+        goal.target_amount,
+        monthly_contribution,
+        annual_rate,
+    );
+
+    let projected_completion_date = months_to_target.and_then(|months| {
+        chrono::Utc::now()
+            .date_naive()
+            .checked_add_months(chrono::Months::new(months))
+    });
+
+    Ok(GoalProjectionResponse {
+        goal_id,
+        current_amount: goal.current_amount,
+        target_amount: goal.target_amount,
+        monthly_contribution,
+        annual_rate,
+        months_to_target,
+        projected_completion_date,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_large_contribution_reports_every_crossed_milestone() {
+        let target = Decimal::new(1000, 0);
+
+        // 0 -> 600 crosses both 25% and 50% in one contribution
+        let reached =
+            newly_reached_milestones(&DEFAULT_GOAL_MILESTONES, 0, Decimal::new(600, 0), target);
+        assert_eq!(reached, vec![25, 50]);
+
+        // A later 600 -> 650 contribution crosses nothing new
+        let last = *reached.last().unwrap();
+        let reached =
+            newly_reached_milestones(&DEFAULT_GOAL_MILESTONES, last, Decimal::new(650, 0), target);
+        assert!(reached.is_empty());
+
+        // Reaching 75% reports only that milestone
+        let reached =
+            newly_reached_milestones(&DEFAULT_GOAL_MILESTONES, last, Decimal::new(750, 0), target);
+        assert_eq!(reached, vec![75]);
+    }
+
+    #[test]
+    fn test_milestones_ignore_zero_target() {
+        let reached = newly_reached_milestones(
+            &DEFAULT_GOAL_MILESTONES,
+            0,
+            Decimal::new(10, 0),
+            Decimal::ZERO,
+        );
+        assert!(reached.is_empty());
+    }
+
+    #[test]
+    fn test_parse_goal_milestones() {
+        assert_eq!(
+            parse_goal_milestones("50, 10,50,100"),
+            Some(vec![10, 50, 100])
+        );
+        assert_eq!(parse_goal_milestones("0,50"), None);
+        assert_eq!(parse_goal_milestones("25,abc"), None);
+        assert_eq!(parse_goal_milestones("101"), None);
+    }
+
+    #[test]
+    fn test_zero_rate_projection_is_linear() {
+        // 1000 remaining at 100/month takes exactly 10 months
+        let months = simulate_goal_completion(
+            Decimal::new(500, 0),
+            Decimal::new(1500, 0),
+            Decimal::new(100, 0),
+            Decimal::ZERO,
+        );
+        assert_eq!(months, Some(10));
+    }
+
+    #[test]
+    fn test_positive_rate_projection_is_faster() {
+        let linear = simulate_goal_completion(
+            Decimal::new(10000, 0),
+            Decimal::new(20000, 0),
+            Decimal::new(200, 0),
+            Decimal::ZERO,
+        )
+        .unwrap();
+        let compounded = simulate_goal_completion(
+            Decimal::new(10000, 0),
+            Decimal::new(20000, 0),
+            Decimal::new(200, 0),
+            Decimal::new(6, 2), // 6% annually
+        )
+        .unwrap();
+
+        assert_eq!(linear, 50);
+        assert!(compounded < linear);
+        assert_eq!(compounded, 37);
+    }
+
+    #[test]
+    fn test_unreachable_without_contributions() {
+        assert_eq!(
+            simulate_goal_completion(
+                Decimal::new(100, 0),
+                Decimal::new(1000, 0),
+                Decimal::ZERO,
+                Decimal::ZERO,
+            ),
+            None
+        );
+        assert_eq!(
+            simulate_goal_completion(
+                Decimal::ZERO,
+                Decimal::new(1000, 0),
+                Decimal::ZERO,
+                Decimal::new(5, 2),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_already_reached_goal() {
+        assert_eq!(
+            simulate_goal_completion(
+                Decimal::new(1000, 0),
+                Decimal::new(1000, 0),
+                Decimal::ZERO,
+                Decimal::ZERO,
+            ),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_projection_is_capped() {
+        // A tiny contribution toward a huge target must stop at the iteration cap
+        assert_eq!(
+            simulate_goal_completion(
+                Decimal::ZERO,
+                Decimal::new(1_000_000_000, 0),
+                Decimal::new(1, 0),
+                Decimal::ZERO,
+            ),
+            None
+        );
+    }
+}
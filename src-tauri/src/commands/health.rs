@@ -0,0 +1,190 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tauri::State;
+use tracing::{instrument, warn};
+
+use crate::{
+    commands::{encryption::get_encryption_service, secure_storage::get_connection_stats},
+    database::{Database, DatabaseUtils, PoolStats},
+    dto::{SubsystemStatus, SystemHealthResponse},
+    error::{FiscusError, FiscusResult},
+};
+
+/// Longest time any single subsystem check may take
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Owner and data type of the throwaway value used for the encryption self-test
+const SELF_TEST_USER_ID: &str = "fiscus-health-check";
+const SELF_TEST_DATA_TYPE: &str = "health_check";
+
+/// Report the status of the database, encryption service and connection pool
+///
+/// Every check is bounded by a short timeout and failures are reported in the
+/// response rather than returned as errors.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_system_health(
+    db: State<'_, Database>,
+) -> Result<SystemHealthResponse, FiscusError> {
+    Ok(check_system_health(&db).await)
+}
+
+async fn check_system_health(db: &Database) -> SystemHealthResponse {
+    let database = status_of(with_timeout("Database ping", ping_database(db)).await);
+    let encryption = status_of(with_timeout("Encryption self-test", encryption_self_test()).await);
+    let pool = get_connection_stats().await;
+    let pending_migrations = with_timeout("Migration check", pending_migrations(db))
+        .await
+        .ok()
+        .flatten();
+
+    build_health_response(database, encryption, pool, pending_migrations)
+}
+
+fn build_health_response(
+    database: SubsystemStatus,
+    encryption: SubsystemStatus,
+    pool: FiscusResult<PoolStats>,
+    pending_migrations: Option<bool>,
+) -> SystemHealthResponse {
+    let pool_error = pool.as_ref().err().map(|e| format!("Pool stats: {e}"));
+    let last_error = [&database, &encryption]
+        .into_iter()
+        .find_map(|status| match status {
+            SubsystemStatus::Err(error) => Some(error.clone()),
+            SubsystemStatus::Ok => None,
+        })
+        .or(pool_error);
+
+    if let Some(error) = &last_error {
+        warn!(error = %error, "System health check found a failing subsystem");
+    }
+
+    SystemHealthResponse {
+        database,
+        encryption,
+        pool: pool.ok(),
+        pending_migrations,
+        last_error,
+        checked_at: chrono::Utc::now(),
+    }
+}
+
+fn status_of(result: FiscusResult<()>) -> SubsystemStatus {
+    match result {
+        Ok(()) => SubsystemStatus::Ok,
+        Err(e) => SubsystemStatus::Err(e.to_string()),
+    }
+}
+
+/// Run a check, failing it if it does not finish within `HEALTH_CHECK_TIMEOUT`
+async fn with_timeout<T>(
+    check: &str,
+    future: impl Future<Output = FiscusResult<T>>,
+) -> FiscusResult<T> {
+    tokio::time::timeout(HEALTH_CHECK_TIMEOUT, future)
+        .await
+        .unwrap_or_else(|_| {
+            Err(FiscusError::Database(format!(
+                "{check} timed out after {}ms",
+                HEALTH_CHECK_TIMEOUT.as_millis()
+            )))
+        })
+}
+
+async fn ping_database(db: &Database) -> FiscusResult<()> {
+    let _: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(db, "SELECT 1 AS ok", Vec::new()).await?;
+    Ok(())
+}
+
+/// Encrypt and decrypt a fixed value to confirm the service can do both
+async fn encryption_self_test() -> FiscusResult<()> {
+    let service = get_encryption_service()?;
+    let probe = b"fiscus health check";
+
+    let encrypted = service
+        .encrypt_financial_data(probe, SELF_TEST_USER_ID, SELF_TEST_DATA_TYPE)
+        .await?;
+    let decrypted = service
+        .decrypt_financial_data(&encrypted, SELF_TEST_USER_ID, SELF_TEST_DATA_TYPE)
+        .await?;
+
+    if decrypted != probe {
+        return Err(FiscusError::Encryption(
+            "Encryption self-test returned different plaintext".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether bundled migrations are newer than the latest applied one
+async fn pending_migrations(db: &Database) -> FiscusResult<Option<bool>> {
+    let latest = crate::migrations()
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0);
+
+    let applied: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        "SELECT MAX(version) AS version FROM _sqlx_migrations WHERE success = 1",
+        Vec::new(),
+    )
+    .await?;
+
+    Ok(applied
+        .and_then(|row| row.get("version").and_then(|v| v.as_i64()))
+        .map(|applied| applied < latest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseType;
+
+    #[tokio::test]
+    async fn test_working_setup_reports_healthy() {
+        crate::commands::encryption::initialize_encryption_service()
+            .expect("Failed to initialize encryption service for test");
+        let db = Database::new("sqlite:health-check.db".to_string(), DatabaseType::SQLite);
+
+        let health = check_system_health(&db).await;
+
+        assert_eq!(health.database, SubsystemStatus::Ok);
+        assert_eq!(health.encryption, SubsystemStatus::Ok);
+        assert!(health.pool.is_some());
+        assert_eq!(health.last_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_database_failure_is_reported() {
+        crate::commands::encryption::initialize_encryption_service()
+            .expect("Failed to initialize encryption service for test");
+        // Only SQLite is supported, so every query on this connection fails
+        let db = Database::new(
+            "postgres://unreachable/fiscus".to_string(),
+            DatabaseType::PostgreSQL,
+        );
+
+        let health = check_system_health(&db).await;
+
+        assert!(matches!(health.database, SubsystemStatus::Err(_)));
+        assert_eq!(health.encryption, SubsystemStatus::Ok);
+        assert!(health.last_error.is_some());
+        assert_eq!(health.pending_migrations, None);
+    }
+
+    #[tokio::test]
+    async fn test_slow_check_times_out() {
+        let result = with_timeout("Slow check", async {
+            tokio::time::sleep(HEALTH_CHECK_TIMEOUT * 2).await;
+            Ok(())
+        })
+        .await;
+
+        assert!(matches!(result, Err(FiscusError::Database(_))));
+    }
+}
@@ -2,22 +2,38 @@
 /// This module provides a clean separation of concerns for different
 /// areas of the personal finance application
 pub mod accounts;
+pub mod accruals;
 pub mod auth;
 pub mod budgets;
 pub mod categories;
+pub mod categorization_rules;
+pub mod currencies;
+pub mod diagnostics;
 pub mod encryption;
+pub mod export;
 pub mod goals;
+pub mod health;
 pub mod reports;
+pub mod schema;
 pub mod secure_storage;
+pub mod spending_limits;
 pub mod transactions;
 
 // Re-export all command functions for easy registration
 pub use accounts::*;
+pub use accruals::*;
 pub use auth::*;
 pub use budgets::*;
 pub use categories::*;
+pub use categorization_rules::*;
+pub use currencies::*;
+pub use diagnostics::*;
 pub use encryption::*;
+pub use export::*;
 pub use goals::*;
+pub use health::*;
 pub use reports::*;
+pub use schema::*;
 pub use secure_storage::*;
+pub use spending_limits::*;
 pub use transactions::*;
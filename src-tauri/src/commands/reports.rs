@@ -1,13 +1,33 @@
+use rust_decimal::Decimal;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tauri::State;
+use uuid::Uuid;
 
 use crate::{
-    database::{Database, DatabaseUtils},
-    error::{FiscusError, Validator},
-    utils::parse_decimal_from_json,
+    commands::{
+        accounts::get_account_summary,
+        accruals::{accrual_deltas, due_dates},
+        currencies::currency_minor_units,
+        transactions::{get_transaction_summary, query_transactions},
+    },
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
+    dto::{
+        CashFlowProjectionResponse, DeductibleCategoryTotal, DeductibleSummaryResponse,
+        DigestCategory, DigestResponse, PayeeSpending, ProjectedBalance, SavingsRatePoint,
+        TransactionFilters, TransactionSummaryResponse, TrendGranularity,
+    },
+    error::{FiscusError, FiscusResult, ValidatedCurrency, ValidatedUserId, Validator},
+    models::{AccountAccrual, NetWorthSnapshot, Transaction, TransactionStatus, TransactionType},
+    utils::{format_currency, parse_decimal_from_json},
 };
 
+/// Number of categories listed in a spending digest
+const DIGEST_TOP_CATEGORY_COUNT: usize = 3;
+
+/// Longest horizon a cash-flow projection covers
+const MAX_PROJECTION_DAYS: u32 = 366;
+
 /// Get financial overview report for a user
 #[tauri::command]
 pub async fn get_financial_overview(
@@ -25,14 +45,14 @@ pub async fn get_financial_overview(
     let mut param_index = 2;
 
     if let Some(start) = &start_date {
-        Validator::validate_date(start)?;
+        Validator::validate_date(start, "start_date")?;
         date_conditions.push(format!("DATE(t.transaction_date) >= ?{param_index}"));
         params.push(Value::String(start.clone()));
         param_index += 1;
     }
 
     if let Some(end) = &end_date {
-        Validator::validate_date(end)?;
+        Validator::validate_date(end, "end_date")?;
         date_conditions.push(format!("DATE(t.transaction_date) <= ?{param_index}"));
         params.push(Value::String(end.clone()));
     }
@@ -109,7 +129,14 @@ pub async fn get_financial_overview(
     Ok(result)
 }
 
+/// Category bucket for expenses recorded without one
+const UNCATEGORIZED: (&str, &str) = ("Uncategorized", "#808080");
+
 /// Get spending by category report
+///
+/// Refunds linked with `refunds_transaction_id` are netted against the
+/// category of the expense they refund. Amounts are encrypted, so the window's
+/// expenses and refunds are decrypted and grouped in memory.
 #[tauri::command]
 pub async fn get_spending_by_category(
     user_id: String,
@@ -122,62 +149,409 @@ pub async fn get_spending_by_category(
     Validator::validate_uuid(&user_id, "user_id")?;
     DatabaseUtils::validate_user_exists(&db, &user_id).await?;
 
-    let mut date_conditions = vec![
+    let mut conditions = vec![
         "t.user_id = ?1".to_string(),
-        "t.transaction_type = 'expense'".to_string(),
+        "(t.transaction_type = 'expense' OR t.refunds_transaction_id IS NOT NULL)".to_string(),
+        "t.status NOT IN ('cancelled', 'voided')".to_string(),
     ];
-    let mut params = vec![Value::String(user_id)];
+    let mut params = vec![Value::String(user_id.clone())];
     let mut param_index = 2;
 
     if let Some(start) = &start_date {
-        Validator::validate_date(start)?;
-        date_conditions.push(format!("DATE(t.transaction_date) >= ?{param_index}"));
+        Validator::validate_date(start, "start_date")?;
+        conditions.push(format!("DATE(t.transaction_date) >= ?{param_index}"));
         params.push(Value::String(start.clone()));
         param_index += 1;
     }
 
     if let Some(end) = &end_date {
-        Validator::validate_date(end)?;
-        date_conditions.push(format!("DATE(t.transaction_date) <= ?{param_index}"));
+        Validator::validate_date(end, "end_date")?;
+        conditions.push(format!("DATE(t.transaction_date) <= ?{param_index}"));
         params.push(Value::String(end.clone()));
     }
 
-    let limit_clause = if let Some(l) = limit {
-        format!("LIMIT {}", l.clamp(1, 100))
-    } else {
-        "LIMIT 20".to_string()
-    };
-
-    let category_query = format!(
+    // A refund reports under the category of the expense it refunds
+    let query = format!(
         r#"
-        SELECT 
-            COALESCE(c.name, 'Uncategorized') as category_name,
-            COALESCE(c.color, '#808080') as category_color,
-            COALESCE(SUM(t.amount), 0) as total_amount,
-            COUNT(t.id) as transaction_count,
-            COALESCE(AVG(t.amount), 0) as average_amount
+        SELECT t.id, t.user_id, t.account_id,
+               COALESCE(o.category_id, t.category_id) AS category_id,
+               t.amount, t.description, t.notes, t.transaction_date, t.transaction_type,
+               t.status, t.reference_number, t.payee, t.tags, t.refunds_transaction_id,
+               t.created_at, t.updated_at
         FROM transactions t
-        LEFT JOIN categories c ON t.category_id = c.id
+        LEFT JOIN transactions o ON o.id = t.refunds_transaction_id
         WHERE {}
-        GROUP BY c.id, c.name, c.color
-        ORDER BY total_amount DESC
-        {}
+        ORDER BY t.transaction_date ASC
     "#,
-        date_conditions.join(" AND "),
-        limit_clause
+        conditions.join(" AND ")
     );
 
-    let categories: Vec<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query(&db, &category_query, params).await?;
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        &query,
+        params,
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    let category_query = "SELECT id, name, color FROM categories WHERE user_id = ?1";
+    let category_rows: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(&db, category_query, vec![Value::String(user_id)]).await?;
+    let categories: HashMap<String, (String, String)> = category_rows
+        .iter()
+        .filter_map(|row| {
+            let id = row.get("id")?.as_str()?;
+            let name = row.get("name")?.as_str()?;
+            let color = row
+                .get("color")
+                .and_then(|v| v.as_str())
+                .unwrap_or(UNCATEGORIZED.1);
+            Some((id.to_string(), (name.to_string(), color.to_string())))
+        })
+        .collect();
 
-    Ok(categories)
+    let limit = limit.unwrap_or(20).clamp(1, 100) as usize;
+    Ok(spending_by_category(&transactions, &categories, limit))
 }
 
-/// Get monthly spending trend
+/// Net expenses per category, less linked refunds, and keep the `limit` largest totals
+///
+/// `categories` maps category ids to their name and color. Only expenses
+/// count towards `transaction_count`; refunds just reduce the total.
+fn spending_by_category(
+    transactions: &[Transaction],
+    categories: &HashMap<String, (String, String)>,
+    limit: usize,
+) -> Vec<HashMap<String, Value>> {
+    let mut totals: HashMap<Option<&str>, (Decimal, i64)> = HashMap::new();
+
+    for transaction in transactions {
+        let signed_amount = match transaction.transaction_type {
+            TransactionType::Expense => transaction.amount.abs(),
+            TransactionType::Income if transaction.refunds_transaction_id.is_some() => {
+                -transaction.amount.abs()
+            }
+            _ => continue,
+        };
+
+        // Categories that no longer exist are reported as uncategorized
+        let category_id = transaction
+            .category_id
+            .as_deref()
+            .filter(|id| categories.contains_key(*id));
+        let entry = totals.entry(category_id).or_insert((Decimal::ZERO, 0));
+        entry.0 += signed_amount;
+        if transaction.transaction_type == TransactionType::Expense {
+            entry.1 += 1;
+        }
+    }
+
+    let mut rows: Vec<(&str, &str, Decimal, i64)> = totals
+        .into_iter()
+        .map(|(category_id, (total, count))| {
+            let (name, color) = category_id
+                .and_then(|id| categories.get(id))
+                .map(|(name, color)| (name.as_str(), color.as_str()))
+                .unwrap_or(UNCATEGORIZED);
+            (name, color, total, count)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+    rows.truncate(limit);
+
+    rows.into_iter()
+        .map(|(name, color, total, count)| {
+            let average = if count > 0 {
+                total / Decimal::from(count)
+            } else {
+                Decimal::ZERO
+            };
+            HashMap::from([
+                ("category_name".to_string(), Value::String(name.to_string())),
+                (
+                    "category_color".to_string(),
+                    Value::String(color.to_string()),
+                ),
+                ("total_amount".to_string(), Value::String(total.to_string())),
+                ("transaction_count".to_string(), Value::from(count)),
+                (
+                    "average_amount".to_string(),
+                    Value::String(average.to_string()),
+                ),
+            ])
+        })
+        .collect()
+}
+
+/// Payee bucket for transactions recorded without one
+const UNKNOWN_PAYEE: &str = "Unknown";
+
+/// Get the payees with the most expense spending, largest first
+///
+/// Transaction amounts are encrypted, so every matching expense in the window
+/// is loaded and decrypted before grouping in memory. The cost grows with the
+/// number of transactions in the window rather than the number of payees
+/// returned; keep the date range narrow for large ledgers.
+#[tauri::command]
+pub async fn get_spending_by_payee(
+    user_id: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    limit: Option<i32>,
+    db: State<'_, Database>,
+) -> Result<Vec<PayeeSpending>, FiscusError> {
+    // Validate user
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let mut conditions = vec![
+        "user_id = ?1".to_string(),
+        "transaction_type = 'expense'".to_string(),
+        "status NOT IN ('cancelled', 'voided')".to_string(),
+    ];
+    let mut params = vec![Value::String(user_id.clone())];
+    let mut param_index = 2;
+
+    if let Some(start) = &start_date {
+        Validator::validate_date(start, "start_date")?;
+        conditions.push(format!("DATE(transaction_date) >= ?{param_index}"));
+        params.push(Value::String(start.clone()));
+        param_index += 1;
+    }
+
+    if let Some(end) = &end_date {
+        Validator::validate_date(end, "end_date")?;
+        conditions.push(format!("DATE(transaction_date) <= ?{param_index}"));
+        params.push(Value::String(end.clone()));
+    }
+
+    let query = format!(
+        r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               created_at, updated_at
+        FROM transactions
+        WHERE {}
+        ORDER BY transaction_date ASC
+    "#,
+        conditions.join(" AND ")
+    );
+
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        &query,
+        params,
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    let limit = limit.unwrap_or(20).clamp(1, 100) as usize;
+    Ok(spending_by_payee(&transactions, limit))
+}
+
+/// Sum expenses per payee and keep the `limit` largest totals
+///
+/// Payees are compared ignoring case and runs of whitespace, and each group is
+/// labelled with the first spelling encountered. Blank and missing payees fall
+/// into the "Unknown" bucket.
+fn spending_by_payee(transactions: &[Transaction], limit: usize) -> Vec<PayeeSpending> {
+    // Keyed by normalized payee, with `None` collecting transactions without one
+    let mut groups: HashMap<Option<String>, PayeeSpending> = HashMap::new();
+
+    for transaction in transactions
+        .iter()
+        .filter(|t| t.transaction_type == TransactionType::Expense)
+    {
+        let payee = transaction
+            .payee
+            .as_deref()
+            .map(|payee| payee.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|payee| !payee.is_empty());
+        let key = payee.as_deref().map(str::to_lowercase);
+        let label = payee.unwrap_or_else(|| UNKNOWN_PAYEE.to_string());
+
+        let entry = groups.entry(key).or_insert_with(|| PayeeSpending {
+            payee: label,
+            total_amount: Decimal::ZERO,
+            transaction_count: 0,
+        });
+        entry.total_amount += transaction.amount.abs();
+        entry.transaction_count += 1;
+    }
+
+    let mut payees: Vec<PayeeSpending> = groups.into_values().collect();
+    payees.sort_by(|a, b| {
+        b.total_amount
+            .cmp(&a.total_amount)
+            .then_with(|| a.payee.cmp(&b.payee))
+    });
+    payees.truncate(limit);
+    payees
+}
+
+/// Accepted UTC offsets for tax-year bucketing, in minutes (UTC-12:00 to UTC+14:00)
+const MIN_UTC_OFFSET_MINUTES: i32 = -12 * 60;
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Get yearly expense totals for tax-deductible categories
+///
+/// Transactions are assigned to a calendar year by their local date at
+/// `utc_offset_minutes` (UTC when omitted), so a late-December purchase in a
+/// western timezone stays in that year even though it is January in UTC.
+#[tauri::command]
+pub async fn get_deductible_summary(
+    user_id: String,
+    tax_year: i32,
+    utc_offset_minutes: Option<i32>,
+    db: State<'_, Database>,
+) -> Result<DeductibleSummaryResponse, FiscusError> {
+    // Validate user
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let offset = parse_utc_offset(utc_offset_minutes.unwrap_or(0))?;
+    let (year_start, year_end) = chrono::NaiveDate::from_ymd_opt(tax_year, 1, 1)
+        .zip(chrono::NaiveDate::from_ymd_opt(tax_year, 12, 31))
+        .ok_or_else(|| FiscusError::InvalidInput(format!("Invalid tax year: {tax_year}")))?;
+
+    let category_query = r#"
+        SELECT id, name FROM categories
+        WHERE user_id = ?1 AND tax_deductible = 1
+    "#;
+    let category_rows: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(&db, category_query, vec![Value::String(user_id.clone())])
+            .await?;
+    let categories: HashMap<String, String> = category_rows
+        .iter()
+        .filter_map(|row| {
+            let id = row.get("id")?.as_str()?;
+            let name = row.get("name")?.as_str()?;
+            Some((id.to_string(), name.to_string()))
+        })
+        .collect();
+
+    if categories.is_empty() {
+        return Ok(deductible_summary(&[], &categories, tax_year, offset));
+    }
+
+    // Stored dates are UTC; a day either side of the year covers any offset
+    let query = r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               created_at, updated_at
+        FROM transactions
+        WHERE user_id = ?1
+          AND transaction_type = 'expense'
+          AND status NOT IN ('cancelled', 'voided')
+          AND category_id IN (
+              SELECT id FROM categories WHERE user_id = ?1 AND tax_deductible = 1
+          )
+          AND DATE(transaction_date) >= ?2
+          AND DATE(transaction_date) <= ?3
+        ORDER BY transaction_date ASC
+    "#;
+    let params = vec![
+        Value::String(user_id.clone()),
+        Value::String((year_start - chrono::Duration::days(1)).to_string()),
+        Value::String((year_end + chrono::Duration::days(1)).to_string()),
+    ];
+
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        query,
+        params,
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    Ok(deductible_summary(
+        &transactions,
+        &categories,
+        tax_year,
+        offset,
+    ))
+}
+
+fn parse_utc_offset(minutes: i32) -> Result<chrono::FixedOffset, FiscusError> {
+    if !(MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&minutes) {
+        return Err(FiscusError::InvalidInput(format!(
+            "utc_offset_minutes must be between {MIN_UTC_OFFSET_MINUTES} and {MAX_UTC_OFFSET_MINUTES}"
+        )));
+    }
+
+    chrono::FixedOffset::east_opt(minutes * 60)
+        .ok_or_else(|| FiscusError::InvalidInput(format!("Invalid UTC offset: {minutes}")))
+}
+
+/// Sum expenses in `categories` (id to name) whose local date falls in `tax_year`
+///
+/// Categories are ordered by total, largest first; deductible categories
+/// without spending in the year are left out.
+fn deductible_summary(
+    transactions: &[Transaction],
+    categories: &HashMap<String, String>,
+    tax_year: i32,
+    offset: chrono::FixedOffset,
+) -> DeductibleSummaryResponse {
+    use chrono::Datelike;
+
+    let mut totals: HashMap<&str, DeductibleCategoryTotal> = HashMap::new();
+
+    for transaction in transactions.iter().filter(|t| {
+        t.transaction_type == TransactionType::Expense
+            && !matches!(
+                t.status,
+                TransactionStatus::Cancelled | TransactionStatus::Voided
+            )
+            && t.transaction_date.with_timezone(&offset).year() == tax_year
+    }) {
+        let Some((category_id, category_name)) = transaction
+            .category_id
+            .as_deref()
+            .and_then(|id| categories.get_key_value(id))
+        else {
+            continue;
+        };
+
+        let entry = totals
+            .entry(category_id.as_str())
+            .or_insert_with(|| DeductibleCategoryTotal {
+                category_id: category_id.clone(),
+                category_name: category_name.clone(),
+                total_amount: Decimal::ZERO,
+                transaction_count: 0,
+            });
+        entry.total_amount += transaction.amount.abs();
+        entry.transaction_count += 1;
+    }
+
+    let mut categories: Vec<DeductibleCategoryTotal> = totals.into_values().collect();
+    categories.sort_by(|a, b| {
+        b.total_amount
+            .cmp(&a.total_amount)
+            .then_with(|| a.category_name.cmp(&b.category_name))
+    });
+
+    DeductibleSummaryResponse {
+        tax_year,
+        utc_offset_minutes: offset.local_minus_utc() / 60,
+        total_amount: categories.iter().map(|c| c.total_amount).sum(),
+        categories,
+    }
+}
+
+/// Get spending trend, bucketed by `granularity` (monthly by default)
+///
+/// `months` is the look-back window. Every bucket in the window is returned,
+/// with zero totals for periods without transactions, newest first.
 #[tauri::command]
 pub async fn get_monthly_spending_trend(
     user_id: String,
     months: Option<i32>,
+    granularity: Option<TrendGranularity>,
     db: State<'_, Database>,
 ) -> Result<Vec<HashMap<String, serde_json::Value>>, FiscusError> {
     // Validate user
@@ -185,30 +559,231 @@ pub async fn get_monthly_spending_trend(
     DatabaseUtils::validate_user_exists(&db, &user_id).await?;
 
     let months_back = months.unwrap_or(12).clamp(1, 24);
+    let granularity = granularity.unwrap_or_default();
 
-    let trend_query = r#"
+    let end_date = chrono::Utc::now().date_naive();
+    let start_date = end_date
+        .checked_sub_months(chrono::Months::new(months_back as u32))
+        .unwrap_or(end_date);
+
+    let bucket = trend_bucket_expression(granularity, "transaction_date");
+    let trend_query = format!(
+        r#"
         SELECT 
-            strftime('%Y-%m', transaction_date) as month,
+            {bucket} as period,
             COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END), 0) as income,
             COALESCE(SUM(CASE WHEN transaction_type = 'expense' THEN amount ELSE 0 END), 0) as expenses,
             COUNT(CASE WHEN transaction_type != 'transfer' THEN 1 END) as transaction_count
         FROM transactions
         WHERE user_id = ?1 
         AND transaction_type != 'transfer'
-        AND transaction_date >= date('now', '-' || ?2 || ' months')
-        GROUP BY strftime('%Y-%m', transaction_date)
-        ORDER BY month DESC
-    "#;
+        AND DATE(transaction_date) >= ?2
+        GROUP BY {bucket}
+        ORDER BY period DESC
+    "#
+    );
 
     let params = vec![
         Value::String(user_id),
-        Value::Number(serde_json::Number::from(months_back as i64)),
+        Value::String(start_date.format("%Y-%m-%d").to_string()),
     ];
 
     let trend: Vec<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query(&db, trend_query, params).await?;
+        DatabaseUtils::execute_query(&db, &trend_query, params).await?;
 
-    Ok(trend)
+    Ok(fill_trend_buckets(trend, start_date, end_date, granularity))
+}
+
+/// SQLite expression grouping `column` into buckets of the given granularity
+///
+/// Must produce the same keys as `trend_bucket_key`.
+fn trend_bucket_expression(granularity: TrendGranularity, column: &str) -> String {
+    match granularity {
+        TrendGranularity::Daily => format!("strftime('%Y-%m-%d', {column})"),
+        TrendGranularity::Weekly => format!("strftime('%Y-W%W', {column})"),
+        TrendGranularity::Monthly => format!("strftime('%Y-%m', {column})"),
+        TrendGranularity::Quarterly => format!(
+            "(strftime('%Y', {column}) || '-Q' || ((CAST(strftime('%m', {column}) AS INTEGER) + 2) / 3))"
+        ),
+        TrendGranularity::Yearly => format!("strftime('%Y', {column})"),
+    }
+}
+
+/// Bucket key for a date, matching `trend_bucket_expression`
+fn trend_bucket_key(granularity: TrendGranularity, date: chrono::NaiveDate) -> String {
+    use chrono::Datelike;
+
+    match granularity {
+        TrendGranularity::Daily => date.format("%Y-%m-%d").to_string(),
+        TrendGranularity::Weekly => date.format("%Y-W%W").to_string(),
+        TrendGranularity::Monthly => date.format("%Y-%m").to_string(),
+        TrendGranularity::Quarterly => format!("{}-Q{}", date.year(), date.month0() / 3 + 1),
+        TrendGranularity::Yearly => date.format("%Y").to_string(),
+    }
+}
+
+/// Fill in zero rows for every bucket between `start_date` and `end_date`
+///
+/// Rows are returned newest first. Monthly rows also carry the legacy
+/// `month` key so existing consumers keep working.
+fn fill_trend_buckets(
+    rows: Vec<HashMap<String, Value>>,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    granularity: TrendGranularity,
+) -> Vec<HashMap<String, Value>> {
+    let mut by_period: HashMap<String, HashMap<String, Value>> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let period = row.get("period")?.as_str()?.to_string();
+            Some((period, row))
+        })
+        .collect();
+
+    trend_periods(start_date, end_date, granularity)
+        .into_iter()
+        .rev()
+        .map(|period| {
+            let mut row = by_period.remove(&period).unwrap_or_else(|| {
+                HashMap::from([
+                    ("income".to_string(), Value::from(0)),
+                    ("expenses".to_string(), Value::from(0)),
+                    ("transaction_count".to_string(), Value::from(0)),
+                ])
+            });
+            if granularity == TrendGranularity::Monthly {
+                row.insert("month".to_string(), Value::String(period.clone()));
+            }
+            row.insert("period".to_string(), Value::String(period));
+            row
+        })
+        .collect()
+}
+
+/// Every bucket key between `start_date` and `end_date` (inclusive), oldest first
+fn trend_periods(
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    granularity: TrendGranularity,
+) -> Vec<String> {
+    // Walk day by day so every granularity yields each of its buckets exactly once
+    let mut periods: Vec<String> = Vec::new();
+    let mut date = start_date;
+    while date <= end_date {
+        let key = trend_bucket_key(granularity, date);
+        if periods.last() != Some(&key) {
+            periods.push(key);
+        }
+        match date.succ_opt() {
+            Some(next) => date = next,
+            None => break,
+        }
+    }
+    periods
+}
+
+/// Get income, expenses and savings rate per bucket between two dates (inclusive)
+///
+/// Transfers are excluded and linked refunds reduce expenses rather than
+/// counting as income. Amounts are encrypted, so the window's transactions are
+/// decrypted and bucketed in memory. Every bucket in the window is returned,
+/// oldest first, with zero totals for periods without transactions.
+#[tauri::command]
+pub async fn get_savings_rate_trend(
+    user_id: String,
+    start_date: String,
+    end_date: String,
+    granularity: Option<TrendGranularity>,
+    db: State<'_, Database>,
+) -> Result<Vec<SavingsRatePoint>, FiscusError> {
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    Validator::validate_date(&start_date, "start_date")?;
+    Validator::validate_date(&end_date, "end_date")?;
+    let start = parse_report_date(&start_date, "start_date")?;
+    let end = parse_report_date(&end_date, "end_date")?;
+    if start > end {
+        return Err(FiscusError::InvalidInput(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+
+    let query = r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               refunds_transaction_id, created_at, updated_at
+        FROM transactions
+        WHERE user_id = ?1
+        AND transaction_type IN ('income', 'expense')
+        AND status NOT IN ('cancelled', 'voided')
+        AND DATE(transaction_date) >= ?2
+        AND DATE(transaction_date) <= ?3
+    "#;
+    let params = vec![
+        Value::String(user_id.clone()),
+        Value::String(start_date),
+        Value::String(end_date),
+    ];
+
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        query,
+        params,
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    Ok(savings_rate_trend(
+        &transactions,
+        start,
+        end,
+        granularity.unwrap_or_default(),
+    ))
+}
+
+/// Bucket income and expenses and compute each bucket's savings rate
+fn savings_rate_trend(
+    transactions: &[Transaction],
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    granularity: TrendGranularity,
+) -> Vec<SavingsRatePoint> {
+    let mut totals: HashMap<String, (Decimal, Decimal)> = HashMap::new();
+    for transaction in transactions {
+        let period = trend_bucket_key(granularity, transaction.transaction_date.date_naive());
+        let (income, expenses) = totals.entry(period).or_default();
+        match transaction.transaction_type {
+            TransactionType::Income if transaction.refunds_transaction_id.is_some() => {
+                *expenses -= transaction.amount.abs()
+            }
+            TransactionType::Income => *income += transaction.amount.abs(),
+            TransactionType::Expense => *expenses += transaction.amount.abs(),
+            _ => {}
+        }
+    }
+
+    trend_periods(start_date, end_date, granularity)
+        .into_iter()
+        .map(|period| {
+            let (income, expenses) = totals.remove(&period).unwrap_or_default();
+            SavingsRatePoint {
+                period,
+                income,
+                expenses,
+                savings_rate: savings_rate(income, expenses),
+            }
+        })
+        .collect()
+}
+
+/// `1 - expenses / income` as a percentage to two places, or `None` without income
+fn savings_rate(income: Decimal, expenses: Decimal) -> Option<Decimal> {
+    if income <= Decimal::ZERO {
+        return None;
+    }
+    Some(((income - expenses) / income * Decimal::ONE_HUNDRED).round_dp(2))
 }
 
 /// Get account balance history
@@ -265,6 +840,188 @@ pub async fn get_account_balance_history(
     Ok(history)
 }
 
+/// Project an account's daily balance over the next `horizon_days`
+///
+/// Starts from today's balance and applies future-dated transactions and the
+/// account's pending accruals on the days they fall due. Nothing is posted;
+/// days on which the projected balance is below zero are flagged.
+#[tauri::command]
+pub async fn project_cash_flow(
+    user_id: String,
+    account_id: String,
+    horizon_days: u32,
+    db: State<'_, Database>,
+) -> Result<CashFlowProjectionResponse, FiscusError> {
+    Validator::validate_uuid(&user_id, "user_id")?;
+    Validator::validate_uuid(&account_id, "account_id")?;
+    if horizon_days == 0 || horizon_days > MAX_PROJECTION_DAYS {
+        return Err(FiscusError::field_validation(
+            "horizon_days",
+            "out_of_range",
+            format!("horizon_days must be between 1 and {MAX_PROJECTION_DAYS}"),
+        ));
+    }
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    let today = chrono::Utc::now().date_naive();
+
+    let future_rows: Vec<HashMap<String, Value>> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        r#"
+        SELECT amount, transaction_type, transaction_date
+        FROM transactions
+        WHERE account_id = ?1 AND user_id = ?2 AND date(transaction_date) > ?3
+          AND status NOT IN ('cancelled', 'voided')
+        "#,
+        vec![
+            Value::String(account_id.clone()),
+            Value::String(user_id.clone()),
+            Value::String(today.to_string()),
+        ],
+        &user_id,
+        "transactions",
+    )
+    .await?;
+    let scheduled: Vec<(chrono::NaiveDate, Decimal)> =
+        future_rows.iter().filter_map(scheduled_item).collect();
+
+    // Future-dated transactions were posted to the stored balance when created
+    let current_balance = DatabaseUtils::get_account_balance(&db, &account_id).await?;
+    let starting_balance =
+        current_balance - scheduled.iter().map(|(_, delta)| *delta).sum::<Decimal>();
+
+    let accruals: Vec<AccountAccrual> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        r#"
+        SELECT id, user_id, account_id, name, kind, amount, annual_rate, frequency,
+               category_id, start_date, last_applied, is_active, created_at, updated_at
+        FROM account_accruals
+        WHERE account_id = ?1 AND user_id = ?2 AND is_active = 1
+        ORDER BY start_date, id
+        "#,
+        vec![
+            Value::String(account_id.clone()),
+            Value::String(user_id.clone()),
+        ],
+        &user_id,
+        "account_accruals",
+    )
+    .await?;
+
+    let currency_row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        &db,
+        "SELECT currency FROM accounts WHERE id = ?1",
+        vec![Value::String(account_id.clone())],
+    )
+    .await?;
+    let currency = currency_row
+        .as_ref()
+        .and_then(|row| row.get("currency"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("USD");
+
+    let days = project_daily_balances(
+        starting_balance,
+        today,
+        horizon_days,
+        &scheduled,
+        &accruals,
+        currency_minor_units(currency),
+    );
+    let first_negative_date = days.iter().find(|day| day.is_negative).map(|day| day.date);
+    let lowest_balance = days
+        .iter()
+        .map(|day| day.balance)
+        .fold(starting_balance, Decimal::min);
+
+    Ok(CashFlowProjectionResponse {
+        account_id,
+        starting_balance,
+        horizon_days,
+        days,
+        first_negative_date,
+        lowest_balance,
+    })
+}
+
+/// Date and signed balance effect of a future-dated transaction row
+fn scheduled_item(row: &HashMap<String, Value>) -> Option<(chrono::NaiveDate, Decimal)> {
+    let date = row
+        .get("transaction_date")
+        .and_then(|v| v.as_str())
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())?
+        .with_timezone(&chrono::Utc)
+        .date_naive();
+    let amount = parse_decimal_from_json(row, "amount");
+
+    let delta = match row.get("transaction_type").and_then(|v| v.as_str()) {
+        Some("income") => amount,
+        Some("expense") => -amount,
+        // Transfer legs carry their direction in the sign of the amount
+        Some("transfer") => amount,
+        _ => return None,
+    };
+
+    Some((date, delta))
+}
+
+/// Daily balances for the `horizon_days` after `today`
+///
+/// Each day applies its scheduled transactions, then any accrual falling due
+/// that day, with interest worked out on the balance at that point. Accrual
+/// periods already due but not yet applied land on the first projected day.
+fn project_daily_balances(
+    starting_balance: Decimal,
+    today: chrono::NaiveDate,
+    horizon_days: u32,
+    scheduled: &[(chrono::NaiveDate, Decimal)],
+    accruals: &[AccountAccrual],
+    minor_units: u32,
+) -> Vec<ProjectedBalance> {
+    let first_day = today + chrono::Days::new(1);
+    let last_day = today + chrono::Days::new(u64::from(horizon_days));
+
+    let mut changes_by_day: BTreeMap<chrono::NaiveDate, Decimal> = BTreeMap::new();
+    for (date, delta) in scheduled {
+        *changes_by_day.entry(*date).or_default() += *delta;
+    }
+
+    let mut accruals_by_day: BTreeMap<chrono::NaiveDate, Vec<&AccountAccrual>> = BTreeMap::new();
+    for accrual in accruals {
+        for date in due_dates(accrual, last_day) {
+            accruals_by_day
+                .entry(date.max(first_day))
+                .or_default()
+                .push(accrual);
+        }
+    }
+
+    let mut balance = starting_balance;
+    let mut days = Vec::with_capacity(horizon_days as usize);
+    for date in first_day.iter_days().take(horizon_days as usize) {
+        let mut change = changes_by_day.get(&date).copied().unwrap_or_default();
+        balance += change;
+
+        for accrual in accruals_by_day.get(&date).into_iter().flatten() {
+            let delta = accrual_deltas(accrual, balance, 1, minor_units)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            balance += delta;
+            change += delta;
+        }
+
+        days.push(ProjectedBalance {
+            date,
+            balance,
+            change,
+            is_negative: balance < Decimal::ZERO,
+        });
+    }
+
+    days
+}
+
 /// Get budget performance report
 #[tauri::command]
 pub async fn get_budget_performance(
@@ -333,8 +1090,7 @@ pub async fn get_net_worth_progression(
 
     let months_back = months.unwrap_or(12).clamp(1, 24);
 
-    // This is a simplified version - in a real application, you'd want to track
-    // historical balance snapshots for more accurate net worth progression
+    // Recompute monthly net change from transactions to fill months without snapshots
     let progression_query = r#"
         SELECT 
             strftime('%Y-%m', transaction_date) as month,
@@ -351,12 +1107,1059 @@ pub async fn get_net_worth_progression(
     "#;
 
     let params = vec![
-        Value::String(user_id),
+        Value::String(user_id.clone()),
         Value::Number(serde_json::Number::from(months_back as i64)),
     ];
 
-    let progression: Vec<HashMap<String, serde_json::Value>> =
+    let computed: Vec<HashMap<String, serde_json::Value>> =
         DatabaseUtils::execute_query(&db, progression_query, params).await?;
 
-    Ok(progression)
+    // Prefer captured snapshots for the same window
+    let snapshots_query = r#"
+        SELECT id, user_id, snapshot_date, total_assets, total_liabilities, net_worth,
+               account_count, created_at, updated_at
+        FROM net_worth_snapshots
+        WHERE user_id = ?1
+        AND snapshot_date >= date('now', '-' || ?2 || ' months')
+        ORDER BY snapshot_date ASC
+    "#;
+
+    let snapshot_params = vec![
+        Value::String(user_id),
+        Value::Number(serde_json::Number::from(months_back as i64)),
+    ];
+
+    let snapshots: Vec<NetWorthSnapshot> =
+        DatabaseUtils::execute_query(&db, snapshots_query, snapshot_params).await?;
+
+    Ok(merge_net_worth_progression(snapshots, computed))
+}
+
+/// Merge monthly snapshots with recomputed progression, preferring snapshots.
+/// The latest snapshot in a month wins; months without one keep the computed row.
+fn merge_net_worth_progression(
+    mut snapshots: Vec<NetWorthSnapshot>,
+    computed: Vec<HashMap<String, serde_json::Value>>,
+) -> Vec<HashMap<String, serde_json::Value>> {
+    let mut by_month: BTreeMap<String, HashMap<String, serde_json::Value>> = BTreeMap::new();
+
+    for mut row in computed {
+        let Some(month) = row
+            .get("month")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        row.insert("source".to_string(), Value::String("computed".to_string()));
+        by_month.insert(month, row);
+    }
+
+    snapshots.sort_by_key(|snapshot| snapshot.snapshot_date);
+
+    for snapshot in snapshots {
+        let month = snapshot.snapshot_date.format("%Y-%m").to_string();
+        let row = by_month.entry(month.clone()).or_default();
+
+        row.insert("month".to_string(), Value::String(month));
+        row.insert(
+            "snapshot_date".to_string(),
+            Value::String(snapshot.snapshot_date.format("%Y-%m-%d").to_string()),
+        );
+        row.insert(
+            "total_assets".to_string(),
+            serde_json::to_value(snapshot.total_assets).unwrap_or(Value::Null),
+        );
+        row.insert(
+            "total_liabilities".to_string(),
+            serde_json::to_value(snapshot.total_liabilities).unwrap_or(Value::Null),
+        );
+        row.insert(
+            "net_worth".to_string(),
+            serde_json::to_value(snapshot.net_worth).unwrap_or(Value::Null),
+        );
+        row.insert("source".to_string(), Value::String("snapshot".to_string()));
+    }
+
+    by_month.into_values().collect()
+}
+
+/// Date a snapshot is captured for: `as_of` when given, otherwise `today`
+///
+/// Totals come from the current account balances, so only today's date is
+/// accepted; a past `as_of` would store today's totals under an earlier date.
+fn snapshot_date(as_of: Option<&str>, today: chrono::NaiveDate) -> FiscusResult<chrono::NaiveDate> {
+    let snapshot_date = match as_of {
+        Some(date) => Validator::validate_date(date, "as_of")?,
+        None => today,
+    };
+
+    if snapshot_date > today {
+        return Err(FiscusError::field_validation(
+            "as_of",
+            "out_of_range",
+            "Snapshot date cannot be in the future",
+        ));
+    }
+    if snapshot_date < today {
+        return Err(FiscusError::field_validation(
+            "as_of",
+            "out_of_range",
+            "Snapshots can only be captured for today",
+        ));
+    }
+
+    Ok(snapshot_date)
+}
+
+/// Capture today's net worth snapshot for a user
+///
+/// `as_of` may be omitted or set to today's date; past and future dates are rejected.
+#[tauri::command]
+pub async fn capture_net_worth_snapshot(
+    user_id: String,
+    as_of: Option<String>,
+    db: State<'_, Database>,
+) -> Result<NetWorthSnapshot, FiscusError> {
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    let snapshot_date = snapshot_date(as_of.as_deref(), chrono::Utc::now().date_naive())?;
+
+    // Totals come from the current (decrypted) account balances
+    let summary = get_account_summary(user_id.clone(), None, db.clone()).await?;
+
+    let now = chrono::Utc::now();
+    let snapshot = NetWorthSnapshot {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        snapshot_date,
+        total_assets: summary.total_assets,
+        total_liabilities: summary.total_liabilities,
+        net_worth: summary.net_worth,
+        account_count: summary.account_count,
+        created_at: now,
+        updated_at: now,
+    };
+
+    // Recapturing the same day replaces the earlier snapshot
+    let upsert_query = r#"
+        INSERT INTO net_worth_snapshots (
+            id, user_id, snapshot_date, total_assets, total_liabilities, net_worth,
+            account_count, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT(user_id, snapshot_date) DO UPDATE SET
+            total_assets = excluded.total_assets,
+            total_liabilities = excluded.total_liabilities,
+            net_worth = excluded.net_worth,
+            account_count = excluded.account_count,
+            updated_at = excluded.updated_at
+    "#;
+
+    let params = vec![
+        Value::String(snapshot.id.clone()),
+        Value::String(snapshot.user_id.clone()),
+        Value::String(snapshot.snapshot_date.format("%Y-%m-%d").to_string()),
+        Value::String(snapshot.total_assets.to_string()),
+        Value::String(snapshot.total_liabilities.to_string()),
+        Value::String(snapshot.net_worth.to_string()),
+        Value::Number(serde_json::Number::from(snapshot.account_count)),
+        Value::String(now.to_rfc3339()),
+        Value::String(now.to_rfc3339()),
+    ];
+
+    DatabaseUtils::execute_non_query(&db, upsert_query, params).await?;
+
+    Ok(snapshot)
+}
+
+/// Get captured net worth snapshots for a user, ordered by date
+#[tauri::command]
+pub async fn get_net_worth_history(
+    user_id: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    db: State<'_, Database>,
+) -> Result<Vec<NetWorthSnapshot>, FiscusError> {
+    // Validate user
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let mut query = r#"
+        SELECT id, user_id, snapshot_date, total_assets, total_liabilities, net_worth,
+               account_count, created_at, updated_at
+        FROM net_worth_snapshots
+        WHERE user_id = ?1
+    "#
+    .to_string();
+    let mut params = vec![Value::String(user_id)];
+
+    if let Some(ref start) = start_date {
+        Validator::validate_date(start, "start_date")?;
+        params.push(Value::String(start.clone()));
+        query.push_str(&format!(" AND snapshot_date >= ?{}", params.len()));
+    }
+
+    if let Some(ref end) = end_date {
+        Validator::validate_date(end, "end_date")?;
+        params.push(Value::String(end.clone()));
+        query.push_str(&format!(" AND snapshot_date <= ?{}", params.len()));
+    }
+
+    query.push_str(" ORDER BY snapshot_date ASC");
+
+    let mut snapshots: Vec<NetWorthSnapshot> =
+        DatabaseUtils::execute_query(&db, &query, params).await?;
+    snapshots.sort_by_key(|snapshot| snapshot.snapshot_date);
+
+    Ok(snapshots)
+}
+
+/// Format an amount for display using the currency's conventions
+#[tauri::command]
+pub async fn format_amount(amount: Decimal, currency: String) -> Result<String, FiscusError> {
+    let currency = ValidatedCurrency::new(&currency).map_err(|e| e.for_field("currency"))?;
+
+    Ok(format_currency(amount, currency.as_str()))
+}
+
+/// Generate a spending digest for a date window (inclusive)
+///
+/// Built from the existing transaction summary, category spending and budget
+/// performance reports. A window without activity yields zero totals and
+/// empty lists.
+#[tauri::command]
+pub async fn generate_spending_digest(
+    user_id: String,
+    period_start: String,
+    period_end: String,
+    db: State<'_, Database>,
+) -> Result<DigestResponse, FiscusError> {
+    let validated_user_id = ValidatedUserId::new(&user_id)?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    Validator::validate_date(&period_start, "period_start")?;
+    Validator::validate_date(&period_end, "period_end")?;
+    let start = parse_report_date(&period_start, "period_start")?;
+    let end = parse_report_date(&period_end, "period_end")?;
+    if start > end {
+        return Err(FiscusError::InvalidInput(
+            "period_start must not be after period_end".to_string(),
+        ));
+    }
+
+    // The digest reads the same transactions several times over, so share
+    // decrypted values between the underlying queries
+    let (summary, categories, transactions, budgets) =
+        EncryptedDatabaseUtils::with_decryption_cache(&user_id, async {
+            let summary = get_transaction_summary(
+                user_id.clone(),
+                Some(period_start.clone()),
+                Some(period_end.clone()),
+                db.clone(),
+            )
+            .await?;
+
+            let categories = get_spending_by_category(
+                user_id.clone(),
+                Some(period_start.clone()),
+                Some(period_end.clone()),
+                None,
+                db.clone(),
+            )
+            .await?;
+
+            let transactions = query_transactions(
+                TransactionFilters {
+                    user_id: validated_user_id,
+                    account_id: None,
+                    category_id: None,
+                    transaction_type: None,
+                    status: None,
+                    start_date: Some(period_start),
+                    end_date: Some(period_end),
+                    min_amount: None,
+                    max_amount: None,
+                    search: None,
+                    payee: None,
+                    tag_filter: None,
+                    cursor: None,
+                    sort_by: None,
+                    sort_direction: None,
+                    limit: None,
+                    offset: None,
+                    fields: None,
+                },
+                &db,
+                false,
+            )
+            .await?;
+
+            let budgets = get_budget_performance(user_id.clone(), None, db).await?;
+
+            Ok::<_, FiscusError>((summary, categories, transactions, budgets))
+        })
+        .await?;
+
+    Ok(build_spending_digest(
+        user_id,
+        start,
+        end,
+        summary,
+        categories,
+        &transactions,
+        &budgets,
+    ))
+}
+
+fn parse_report_date(value: &str, field: &str) -> Result<chrono::NaiveDate, FiscusError> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| FiscusError::InvalidInput(format!("{field} must be a YYYY-MM-DD date")))
+}
+
+/// Combine the report results gathered for a digest window
+fn build_spending_digest(
+    user_id: String,
+    period_start: chrono::NaiveDate,
+    period_end: chrono::NaiveDate,
+    summary: TransactionSummaryResponse,
+    category_rows: Vec<HashMap<String, Value>>,
+    transactions: &[Transaction],
+    budget_rows: &[HashMap<String, Value>],
+) -> DigestResponse {
+    DigestResponse {
+        user_id,
+        period_start,
+        period_end,
+        total_income: summary.total_income,
+        total_expenses: summary.total_expenses,
+        net_change: summary.net_income,
+        top_categories: top_spending_categories(category_rows, DIGEST_TOP_CATEGORY_COUNT),
+        largest_transaction: largest_transaction(transactions).cloned(),
+        budgets_over: count_budgets_over(budget_rows, period_start, period_end),
+    }
+}
+
+/// Highest-spending categories, largest first (ties broken by name)
+fn top_spending_categories(rows: Vec<HashMap<String, Value>>, count: usize) -> Vec<DigestCategory> {
+    let mut categories: Vec<DigestCategory> = rows
+        .iter()
+        .map(|row| DigestCategory {
+            category_name: row
+                .get("category_name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Uncategorized")
+                .to_string(),
+            total_amount: parse_decimal_from_json(row, "total_amount"),
+            transaction_count: row
+                .get("transaction_count")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0),
+        })
+        .filter(|category| category.total_amount > Decimal::ZERO)
+        .collect();
+
+    categories.sort_by(|a, b| {
+        b.total_amount
+            .cmp(&a.total_amount)
+            .then_with(|| a.category_name.cmp(&b.category_name))
+    });
+    categories.truncate(count);
+    categories
+}
+
+/// Largest income or expense by amount, ignoring transfers and cancelled entries
+fn largest_transaction(transactions: &[Transaction]) -> Option<&Transaction> {
+    transactions
+        .iter()
+        .filter(|t| t.transaction_type != TransactionType::Transfer)
+        .filter(|t| {
+            !matches!(
+                t.status,
+                TransactionStatus::Cancelled | TransactionStatus::Voided
+            )
+        })
+        .max_by_key(|t| t.amount.abs())
+}
+
+/// Over-budget rows whose budget period overlaps the window
+fn count_budgets_over(
+    rows: &[HashMap<String, Value>],
+    period_start: chrono::NaiveDate,
+    period_end: chrono::NaiveDate,
+) -> i32 {
+    let row_date = |row: &HashMap<String, Value>, key: &str| {
+        row.get(key)
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.get(..10))
+            .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+    };
+
+    rows.iter()
+        .filter(|row| {
+            row.get("is_over_budget")
+                .and_then(|v| v.as_i64())
+                .is_some_and(|flag| flag == 1)
+        })
+        .filter(
+            |row| match (row_date(row, "start_date"), row_date(row, "end_date")) {
+                (Some(start), Some(end)) => start <= period_end && end >= period_start,
+                _ => false,
+            },
+        )
+        .count() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn snapshot(date: &str, net_worth: i64) -> NetWorthSnapshot {
+        let now = chrono::Utc::now();
+        NetWorthSnapshot {
+            id: Uuid::new_v4().to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            snapshot_date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            total_assets: Decimal::new(net_worth + 500, 0),
+            total_liabilities: Decimal::new(500, 0),
+            net_worth: Decimal::new(net_worth, 0),
+            account_count: 2,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn computed_row(month: &str, net_change: f64) -> HashMap<String, Value> {
+        let mut row = HashMap::new();
+        row.insert("month".to_string(), Value::String(month.to_string()));
+        row.insert("net_change".to_string(), serde_json::json!(net_change));
+        row
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_verbatim() {
+        let captured = snapshot("2024-03-31", 12_345);
+        let serialized = serde_json::to_string(&captured).unwrap();
+        let restored: NetWorthSnapshot = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(restored, captured);
+    }
+
+    #[test]
+    fn test_merge_prefers_snapshots_and_orders_by_month() {
+        let snapshots = vec![
+            snapshot("2024-03-31", 3_000),
+            snapshot("2024-01-31", 1_000),
+            snapshot("2024-03-15", 2_500),
+        ];
+        let computed = vec![
+            computed_row("2024-02", 100.0),
+            computed_row("2024-01", 50.0),
+        ];
+
+        let merged = merge_net_worth_progression(snapshots, computed);
+        let months: Vec<&str> = merged
+            .iter()
+            .map(|row| row["month"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(months, vec!["2024-01", "2024-02", "2024-03"]);
+        assert_eq!(merged[0]["source"], "snapshot");
+        assert_eq!(merged[1]["source"], "computed");
+        // Latest snapshot within a month wins
+        assert_eq!(merged[2]["snapshot_date"], "2024-03-31");
+        assert_eq!(merged[2]["net_worth"], serde_json::json!(3000.0));
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn trend_row(period: &str, expenses: i64, count: i64) -> HashMap<String, Value> {
+        HashMap::from([
+            ("period".to_string(), Value::String(period.to_string())),
+            ("income".to_string(), Value::from(0)),
+            ("expenses".to_string(), Value::from(expenses)),
+            ("transaction_count".to_string(), Value::from(count)),
+        ])
+    }
+
+    fn periods(rows: &[HashMap<String, Value>]) -> Vec<&str> {
+        rows.iter()
+            .map(|row| row["period"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_trend_bucket_keys() {
+        let day = date("2024-05-15");
+
+        assert_eq!(trend_bucket_key(TrendGranularity::Daily, day), "2024-05-15");
+        assert_eq!(trend_bucket_key(TrendGranularity::Weekly, day), "2024-W20");
+        assert_eq!(trend_bucket_key(TrendGranularity::Monthly, day), "2024-05");
+        assert_eq!(
+            trend_bucket_key(TrendGranularity::Quarterly, day),
+            "2024-Q2"
+        );
+        assert_eq!(trend_bucket_key(TrendGranularity::Yearly, day), "2024");
+        assert_eq!(
+            trend_bucket_key(TrendGranularity::Quarterly, date("2024-12-31")),
+            "2024-Q4"
+        );
+    }
+
+    #[test]
+    fn test_weekly_and_quarterly_groupings_of_same_dataset() {
+        // Expenses of 10 and 20 in the first week of January, 30 in mid
+        // February and 40 in early April
+        let (start, end) = (date("2024-01-01"), date("2024-06-30"));
+
+        let weekly = fill_trend_buckets(
+            vec![
+                trend_row("2024-W01", 30, 2),
+                trend_row("2024-W07", 30, 1),
+                trend_row("2024-W14", 40, 1),
+            ],
+            start,
+            end,
+            TrendGranularity::Weekly,
+        );
+        let quarterly = fill_trend_buckets(
+            vec![trend_row("2024-Q1", 60, 3), trend_row("2024-Q2", 40, 1)],
+            start,
+            end,
+            TrendGranularity::Quarterly,
+        );
+
+        // 2024-01-01 is a Monday, so the half year spans weeks 01 through 26
+        assert_eq!(weekly.len(), 26);
+        assert_eq!(periods(&quarterly), vec!["2024-Q2", "2024-Q1"]);
+
+        let total = |rows: &[HashMap<String, Value>]| -> i64 {
+            rows.iter()
+                .map(|row| row["expenses"].as_i64().unwrap())
+                .sum()
+        };
+        assert_eq!(total(&weekly), 100);
+        assert_eq!(total(&quarterly), 100);
+
+        assert_eq!(quarterly[1]["expenses"], Value::from(60));
+        assert_eq!(quarterly[1]["transaction_count"], Value::from(3));
+        assert!(!quarterly[0].contains_key("month"));
+    }
+
+    #[test]
+    fn test_trend_fills_gap_periods_with_zero() {
+        let monthly = fill_trend_buckets(
+            vec![trend_row("2024-01", 50, 1), trend_row("2024-04", 70, 1)],
+            date("2024-01-01"),
+            date("2024-04-30"),
+            TrendGranularity::Monthly,
+        );
+
+        assert_eq!(
+            periods(&monthly),
+            vec!["2024-04", "2024-03", "2024-02", "2024-01"]
+        );
+        for gap in &monthly[1..3] {
+            assert_eq!(gap["expenses"], Value::from(0));
+            assert_eq!(gap["transaction_count"], Value::from(0));
+        }
+        // Monthly rows keep the legacy key
+        assert_eq!(monthly[0]["month"], Value::String("2024-04".to_string()));
+    }
+
+    #[test]
+    fn test_savings_rate_math_and_zero_income_guard() {
+        assert_eq!(
+            savings_rate(Decimal::new(4_000, 0), Decimal::new(3_000, 0)),
+            Some(Decimal::new(2_500, 2))
+        );
+        assert_eq!(
+            savings_rate(Decimal::new(1_000, 0), Decimal::ZERO),
+            Some(Decimal::ONE_HUNDRED)
+        );
+        // Spending more than was earned is a negative rate
+        assert_eq!(
+            savings_rate(Decimal::new(1_000, 0), Decimal::new(1_500, 0)),
+            Some(Decimal::new(-5_000, 2))
+        );
+        assert_eq!(savings_rate(Decimal::ZERO, Decimal::new(200, 0)), None);
+        assert_eq!(savings_rate(Decimal::ZERO, Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn test_savings_rate_trend_excludes_transfers_and_fills_gaps() {
+        let mut transactions = seeded_transactions();
+        let mut refund = crate::test_utils::TestUtils::create_test_transaction(
+            DIGEST_USER,
+            DIGEST_ACCOUNT,
+            Decimal::new(200, 0),
+            TransactionType::Income,
+        );
+        refund.refunds_transaction_id = Some(transactions[1].id.clone());
+        refund.transaction_date = date("2024-03-25").and_hms_opt(12, 0, 0).unwrap().and_utc();
+        transactions.push(refund);
+
+        let trend = savings_rate_trend(
+            &transactions,
+            date("2024-02-01"),
+            date("2024-04-30"),
+            TrendGranularity::Monthly,
+        );
+
+        let periods: Vec<&str> = trend.iter().map(|point| point.period.as_str()).collect();
+        assert_eq!(periods, vec!["2024-02", "2024-03", "2024-04"]);
+
+        // The 5,000 transfer is ignored and the refund offsets 200 of expenses
+        let march = &trend[1];
+        assert_eq!(march.income, Decimal::new(4_000, 0));
+        assert_eq!(march.expenses, Decimal::new(2_000, 0));
+        assert_eq!(march.savings_rate, Some(Decimal::new(5_000, 2)));
+
+        for gap in [&trend[0], &trend[2]] {
+            assert_eq!(gap.income, Decimal::ZERO);
+            assert_eq!(gap.expenses, Decimal::ZERO);
+            assert_eq!(gap.savings_rate, None);
+        }
+    }
+
+    const DIGEST_USER: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const DIGEST_ACCOUNT: &str = "660e8400-e29b-41d4-a716-446655440001";
+
+    /// A month of activity: (day, category, amount, type)
+    const SEEDED_MONTH: &[(&str, &str, i64, TransactionType)] = &[
+        ("2024-03-01", "Salary", 4_000, TransactionType::Income),
+        ("2024-03-02", "Rent", 1_500, TransactionType::Expense),
+        ("2024-03-05", "Groceries", 120, TransactionType::Expense),
+        ("2024-03-12", "Groceries", 180, TransactionType::Expense),
+        ("2024-03-14", "Dining", 90, TransactionType::Expense),
+        ("2024-03-20", "Transport", 60, TransactionType::Expense),
+        ("2024-03-22", "Dining", 250, TransactionType::Expense),
+        ("2024-03-28", "Savings", 5_000, TransactionType::Transfer),
+    ];
+
+    fn seeded_transactions() -> Vec<Transaction> {
+        SEEDED_MONTH
+            .iter()
+            .map(|(day, category, amount, transaction_type)| {
+                let mut transaction = crate::test_utils::TestUtils::create_test_transaction(
+                    DIGEST_USER,
+                    DIGEST_ACCOUNT,
+                    Decimal::new(*amount, 0),
+                    transaction_type.clone(),
+                );
+                transaction.category_id = Some(category.to_string());
+                transaction.transaction_date = date(day).and_hms_opt(12, 0, 0).unwrap().and_utc();
+                transaction
+            })
+            .collect()
+    }
+
+    fn summary(income: i64, expenses: i64, count: i32) -> TransactionSummaryResponse {
+        TransactionSummaryResponse {
+            total_income: Decimal::new(income, 0),
+            total_expenses: Decimal::new(expenses, 0),
+            net_income: Decimal::new(income - expenses, 0),
+            transaction_count: count,
+            average_transaction: Decimal::ZERO,
+        }
+    }
+
+    fn category_row(name: &str, total: &str, count: i64) -> HashMap<String, Value> {
+        HashMap::from([
+            ("category_name".to_string(), Value::String(name.to_string())),
+            ("total_amount".to_string(), Value::String(total.to_string())),
+            ("transaction_count".to_string(), Value::from(count)),
+        ])
+    }
+
+    fn budget_row(start: &str, end: &str, over: bool) -> HashMap<String, Value> {
+        HashMap::from([
+            ("start_date".to_string(), Value::String(start.to_string())),
+            ("end_date".to_string(), Value::String(end.to_string())),
+            ("is_over_budget".to_string(), Value::from(i64::from(over))),
+        ])
+    }
+
+    #[test]
+    fn test_digest_ranks_top_categories_and_net_change() {
+        let transactions = seeded_transactions();
+        let budgets = vec![
+            budget_row("2024-03-01", "2024-03-31", true),
+            budget_row("2024-03-01", "2024-03-31", false),
+            budget_row("2024-02-01", "2024-02-29", true),
+        ];
+
+        let digest = build_spending_digest(
+            DIGEST_USER.to_string(),
+            date("2024-03-01"),
+            date("2024-03-31"),
+            summary(4_000, 2_200, 8),
+            vec![
+                category_row("Groceries", "300", 2),
+                category_row("Transport", "60", 1),
+                category_row("Rent", "1500", 1),
+                category_row("Dining", "340", 2),
+            ],
+            &transactions,
+            &budgets,
+        );
+
+        let ranking: Vec<(&str, Decimal)> = digest
+            .top_categories
+            .iter()
+            .map(|c| (c.category_name.as_str(), c.total_amount))
+            .collect();
+        assert_eq!(
+            ranking,
+            vec![
+                ("Rent", Decimal::new(1_500, 0)),
+                ("Dining", Decimal::new(340, 0)),
+                ("Groceries", Decimal::new(300, 0)),
+            ]
+        );
+        assert_eq!(digest.top_categories[1].transaction_count, 2);
+
+        assert_eq!(digest.total_income, Decimal::new(4_000, 0));
+        assert_eq!(digest.total_expenses, Decimal::new(2_200, 0));
+        assert_eq!(digest.net_change, Decimal::new(1_800, 0));
+
+        // The transfer is larger than any expense but is not spending
+        let largest = digest.largest_transaction.unwrap();
+        assert_eq!(largest.amount, Decimal::new(4_000, 0));
+        assert_eq!(largest.transaction_type, TransactionType::Income);
+
+        // February's over-budget row falls outside the window
+        assert_eq!(digest.budgets_over, 1);
+    }
+
+    #[test]
+    fn test_digest_for_empty_period_is_all_zero() {
+        let digest = build_spending_digest(
+            DIGEST_USER.to_string(),
+            date("2024-04-01"),
+            date("2024-04-07"),
+            summary(0, 0, 0),
+            Vec::new(),
+            &[],
+            &[],
+        );
+
+        assert_eq!(digest.total_income, Decimal::ZERO);
+        assert_eq!(digest.total_expenses, Decimal::ZERO);
+        assert_eq!(digest.net_change, Decimal::ZERO);
+        assert!(digest.top_categories.is_empty());
+        assert!(digest.largest_transaction.is_none());
+        assert_eq!(digest.budgets_over, 0);
+    }
+
+    fn expense(payee: Option<&str>, amount: i64) -> Transaction {
+        let mut transaction = crate::test_utils::TestUtils::create_test_transaction(
+            "user-id",
+            "account-id",
+            Decimal::new(amount, 2),
+            TransactionType::Expense,
+        );
+        transaction.payee = payee.map(str::to_string);
+        transaction
+    }
+
+    #[test]
+    fn test_spending_by_payee_merges_case_and_whitespace() {
+        let transactions = vec![
+            expense(Some("WALMART"), 4_000),
+            expense(Some("Walmart "), 2_550),
+            expense(Some("  walmart"), 1_000),
+            expense(Some("Corner  Cafe"), 900),
+            expense(Some("corner cafe"), 600),
+            expense(Some("Bookshop"), 10_000),
+        ];
+
+        let payees = spending_by_payee(&transactions, 10);
+
+        assert_eq!(payees.len(), 3);
+        assert_eq!(payees[0].payee, "Bookshop");
+        assert_eq!(payees[1].payee, "WALMART");
+        assert_eq!(payees[1].total_amount, Decimal::new(7_550, 2));
+        assert_eq!(payees[1].transaction_count, 3);
+        assert_eq!(payees[2].payee, "Corner Cafe");
+        assert_eq!(payees[2].total_amount, Decimal::new(1_500, 2));
+        assert_eq!(payees[2].transaction_count, 2);
+
+        let top = spending_by_payee(&transactions, 1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].payee, "Bookshop");
+    }
+
+    #[test]
+    fn test_spending_by_payee_buckets_missing_payees_as_unknown() {
+        let mut income = expense(Some("Employer"), 500_000);
+        income.transaction_type = TransactionType::Income;
+        let transactions = vec![
+            expense(None, 1_200),
+            expense(Some("   "), 800),
+            expense(Some("Grocer"), 1_000),
+            income,
+        ];
+
+        let payees = spending_by_payee(&transactions, 10);
+
+        assert_eq!(payees.len(), 2);
+        assert_eq!(payees[0].payee, UNKNOWN_PAYEE);
+        assert_eq!(payees[0].total_amount, Decimal::new(2_000, 2));
+        assert_eq!(payees[0].transaction_count, 2);
+        assert_eq!(payees[1].payee, "Grocer");
+    }
+
+    #[test]
+    fn test_partial_refund_reduces_net_category_spend() {
+        let categories = HashMap::from([
+            (
+                "cat-electronics".to_string(),
+                ("Electronics".to_string(), "#0000ff".to_string()),
+            ),
+            (
+                "cat-groceries".to_string(),
+                ("Groceries".to_string(), "#00ff00".to_string()),
+            ),
+        ]);
+        let mut headphones = expense(None, 20_000);
+        headphones.category_id = Some("cat-electronics".to_string());
+        let mut groceries = expense(None, 5_000);
+        groceries.category_id = Some("cat-groceries".to_string());
+        // The query reports a refund under its original's category
+        let mut refund = expense(None, 7_500);
+        refund.transaction_type = TransactionType::Income;
+        refund.category_id = Some("cat-electronics".to_string());
+        refund.refunds_transaction_id = Some(headphones.id.clone());
+        let mut salary = expense(None, 300_000);
+        salary.transaction_type = TransactionType::Income;
+
+        let rows = spending_by_category(&[headphones, groceries, refund, salary], &categories, 10);
+
+        let totals: Vec<(&str, Decimal, i64)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row["category_name"].as_str().unwrap(),
+                    parse_decimal_from_json(row, "total_amount"),
+                    row["transaction_count"].as_i64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            totals,
+            vec![
+                ("Electronics", Decimal::new(12_500, 2), 1),
+                ("Groceries", Decimal::new(5_000, 2), 1),
+            ]
+        );
+    }
+
+    fn expense_in(category_id: &str, amount: i64, date: &str) -> Transaction {
+        let mut transaction = crate::test_utils::TestUtils::create_test_transaction(
+            "550e8400-e29b-41d4-a716-446655440000",
+            "account",
+            Decimal::new(amount, 2),
+            TransactionType::Expense,
+        );
+        transaction.category_id = Some(category_id.to_string());
+        transaction.transaction_date = chrono::DateTime::parse_from_rfc3339(date)
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        transaction
+    }
+
+    fn deductible_categories() -> HashMap<String, String> {
+        HashMap::from([
+            ("charity".to_string(), "Charity".to_string()),
+            ("medical".to_string(), "Medical".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_deductible_summary_assigns_year_by_local_date() {
+        // 2025-01-01 03:00 UTC is still New Year's Eve 2024 at UTC-05:00
+        let transactions = vec![
+            expense_in("charity", 10000, "2025-01-01T03:00:00Z"),
+            expense_in("charity", 2500, "2024-01-01T02:00:00Z"),
+            expense_in("medical", 5000, "2024-06-15T12:00:00Z"),
+        ];
+        let eastern = parse_utc_offset(-5 * 60).unwrap();
+
+        let summary = deductible_summary(&transactions, &deductible_categories(), 2024, eastern);
+
+        assert_eq!(summary.utc_offset_minutes, -300);
+        assert_eq!(summary.total_amount, Decimal::new(15000, 2));
+        assert_eq!(summary.categories[0].category_name, "Charity");
+        assert_eq!(summary.categories[0].total_amount, Decimal::new(10000, 2));
+        assert_eq!(summary.categories[0].transaction_count, 1);
+
+        // In UTC the same purchases fall the other way around the boundary
+        let utc = deductible_summary(
+            &transactions,
+            &deductible_categories(),
+            2024,
+            parse_utc_offset(0).unwrap(),
+        );
+        assert_eq!(utc.total_amount, Decimal::new(7500, 2));
+    }
+
+    #[test]
+    fn test_deductible_summary_excludes_other_categories() {
+        let transactions = vec![
+            expense_in("medical", 4000, "2024-03-01T12:00:00Z"),
+            expense_in("groceries", 9000, "2024-03-02T12:00:00Z"),
+        ];
+
+        let summary = deductible_summary(
+            &transactions,
+            &deductible_categories(),
+            2024,
+            parse_utc_offset(0).unwrap(),
+        );
+
+        assert_eq!(summary.categories.len(), 1);
+        assert_eq!(summary.categories[0].category_id, "medical");
+        assert_eq!(summary.total_amount, Decimal::new(4000, 2));
+    }
+
+    #[test]
+    fn test_utc_offset_out_of_range_is_rejected() {
+        assert!(parse_utc_offset(14 * 60).is_ok());
+        assert!(parse_utc_offset(15 * 60).is_err());
+        assert!(parse_utc_offset(-13 * 60).is_err());
+    }
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_future_expense_dips_projection_below_zero() {
+        let today = day("2024-05-10");
+        let scheduled = vec![
+            (day("2024-05-13"), Decimal::new(-30000, 2)),
+            (day("2024-05-15"), Decimal::new(50000, 2)),
+        ];
+
+        let days = project_daily_balances(Decimal::new(25000, 2), today, 7, &scheduled, &[], 2);
+
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[0].date, day("2024-05-11"));
+        assert!(!days[1].is_negative);
+
+        let dip = &days[2];
+        assert_eq!(dip.date, day("2024-05-13"));
+        assert_eq!(dip.change, Decimal::new(-30000, 2));
+        assert_eq!(dip.balance, Decimal::new(-5000, 2));
+        assert!(dip.is_negative);
+
+        assert!(days[3].is_negative);
+        assert_eq!(days[4].balance, Decimal::new(45000, 2));
+        assert!(!days[4].is_negative);
+        assert_eq!(
+            days.iter().find(|d| d.is_negative).map(|d| d.date),
+            Some(day("2024-05-13"))
+        );
+    }
+
+    #[test]
+    fn test_projection_applies_accruals_without_posting() {
+        let now = chrono::Utc::now();
+        let fee = AccountAccrual {
+            id: Uuid::new_v4().to_string(),
+            user_id: "user".to_string(),
+            account_id: "account".to_string(),
+            name: "Maintenance fee".to_string(),
+            kind: crate::models::AccrualKind::Fee,
+            amount: Some(Decimal::new(1500, 2)),
+            annual_rate: None,
+            frequency: crate::models::AccrualFrequency::Monthly,
+            category_id: None,
+            start_date: day("2024-04-01"),
+            last_applied: Some(day("2024-04-01")),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let days = project_daily_balances(
+            Decimal::new(1000, 2),
+            day("2024-05-10"),
+            30,
+            &[],
+            std::slice::from_ref(&fee),
+            2,
+        );
+
+        // May's fee is overdue and lands on the first projected day, June's on the 1st
+        assert_eq!(days[0].change, Decimal::new(-1500, 2));
+        assert_eq!(days[0].balance, Decimal::new(-500, 2));
+        assert!(days[0].is_negative);
+        let june = days.iter().find(|d| d.date == day("2024-06-01")).unwrap();
+        assert_eq!(june.balance, Decimal::new(-2000, 2));
+        assert_eq!(fee.last_applied, Some(day("2024-04-01")));
+    }
+
+    #[test]
+    fn test_scheduled_item_signs_amounts_by_type() {
+        let row = |kind: &str, amount: &str| {
+            HashMap::from([
+                ("transaction_type".to_string(), Value::from(kind)),
+                ("amount".to_string(), Value::from(amount)),
+                (
+                    "transaction_date".to_string(),
+                    Value::from("2024-05-13T09:30:00+00:00"),
+                ),
+            ])
+        };
+
+        assert_eq!(
+            scheduled_item(&row("expense", "12.50")),
+            Some((day("2024-05-13"), Decimal::new(-1250, 2)))
+        );
+        assert_eq!(
+            scheduled_item(&row("transfer", "-40.00")),
+            Some((day("2024-05-13"), Decimal::new(-4000, 2)))
+        );
+    }
+
+    #[test]
+    fn test_snapshot_rejects_past_and_future_dates() {
+        let today = day("2024-05-13");
+
+        assert_eq!(snapshot_date(None, today).unwrap(), today);
+        assert_eq!(snapshot_date(Some("2024-05-13"), today).unwrap(), today);
+        for as_of in ["2024-05-12", "2024-05-14"] {
+            assert!(matches!(
+                snapshot_date(Some(as_of), today),
+                Err(FiscusError::FieldValidation { ref field, .. }) if field == "as_of"
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_format_amount_accepts_registered_custom_currency() {
+        crate::error::register_custom_currency_code("SATS", 0).unwrap();
+
+        assert_eq!(
+            format_amount(Decimal::new(1_234_567, 0), "sats".to_string())
+                .await
+                .unwrap(),
+            "1,234,567 SATS"
+        );
+        assert_eq!(
+            format_amount(Decimal::new(1000, 0), "USD".to_string())
+                .await
+                .unwrap(),
+            "$1,000.00"
+        );
+        assert!(matches!(
+            format_amount(Decimal::ONE, "NOTACODE".to_string()).await,
+            Err(FiscusError::FieldValidation { ref field, .. }) if field == "currency"
+        ));
+    }
 }
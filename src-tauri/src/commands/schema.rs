@@ -0,0 +1,167 @@
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+use std::collections::BTreeMap;
+
+use crate::{
+    dto::*,
+    error::FiscusError,
+    models::{CategorizationRule, CustomCurrency, Transaction},
+};
+
+/// Build a map of schema name to JSON Schema for each listed DTO
+macro_rules! dto_schemas {
+    ($($dto:ty),* $(,)?) => {{
+        let mut schemas = BTreeMap::new();
+        $(
+            schemas.insert(<$dto as JsonSchema>::schema_name(), schema_for!($dto));
+        )*
+        schemas
+    }};
+}
+
+/// JSON Schemas for every request and response DTO, keyed by DTO name
+///
+/// Lets the frontend and integrators validate payloads and generate types
+/// from the same definitions the backend deserializes with.
+#[tauri::command]
+pub async fn get_api_schemas() -> Result<BTreeMap<String, RootSchema>, FiscusError> {
+    Ok(api_schemas())
+}
+
+fn api_schemas() -> BTreeMap<String, RootSchema> {
+    dto_schemas![
+        // Requests
+        CreateUserRequest,
+        CreateAccountRequest,
+        CreateAccountGroupRequest,
+        CreateAccountAccrualRequest,
+        CreateCategoryRequest,
+        CreateTransactionRequest,
+        CreateBudgetPeriodRequest,
+        CreateBudgetRequest,
+        CreateBudgetTemplateRequest,
+        CreateGoalRequest,
+        CreateTransferRequest,
+        UpdateUserRequest,
+        UpdateAccountRequest,
+        UpdateCategoryRequest,
+        UpdateTransactionRequest,
+        UpdateBudgetRequest,
+        UpdateGoalRequest,
+        AccountFilters,
+        TransactionFilters,
+        CategoryFilters,
+        BudgetFilters,
+        GoalFilters,
+        LoginRequest,
+        ChangePasswordRequest,
+        ReauthenticateRequest,
+        BulkTransactionRequest,
+        TrendGranularity,
+        // Responses
+        LoginResponse,
+        UserResponse,
+        PaginatedResponse<Transaction>,
+        CursorPaginatedResponse<Transaction>,
+        TransactionImportResponse,
+        TransactionJsonImportResponse,
+        AccountSummaryResponse,
+        BalanceAuditResponse,
+        PayoffProjectionResponse,
+        BudgetSummaryResponse,
+        BudgetVsActualLine,
+        CurrentBudgetPeriodResponse,
+        CategoryMergeResponse,
+        TransactionSummaryResponse,
+        DigestResponse,
+        CashFlowProjectionResponse,
+        ProjectedBalance,
+        PayeeSpending,
+        SavingsRatePoint,
+        DeductibleSummaryResponse,
+        CustomCurrency,
+        CategorizationRule,
+        TransactionStatsResponse,
+        TagUsage,
+        DuplicateTransactionCluster,
+        GoalProjectionResponse,
+        GoalProgressResponse,
+        UserDataArchive,
+        SignedArchiveResponse,
+        TransactionExportSummary,
+        DataInconsistencyReport,
+        OrphanedTransaction,
+        BrokenTransfer,
+        // Encryption
+        EncryptDataRequest,
+        EncryptDataResponse,
+        DecryptDataRequest,
+        DecryptDataResponse,
+        GenerateKeyRequest,
+        GenerateKeyResponse,
+        RotateKeysRequest,
+        ListUserKeysRequest,
+        RevokeKeyRequest,
+        KeyInfoResponse,
+        KeyLineageResponse,
+        EncryptionStatsResponse,
+        EncryptionOperationTiming,
+        EncryptionPerformanceResponse,
+        DataIntegrityResponse,
+        RecoveredFieldsResponse,
+        SelfTestCheck,
+        EncryptionSelfTestReport,
+        SupportedAlgorithmsResponse,
+        AlgorithmMigrationResponse,
+        RekeyRequest,
+        RekeyResponse,
+        DeriveKeyRequest,
+        DeriveKeyResponse,
+        SignDataRequest,
+        SignDataResponse,
+        VerifySignatureRequest,
+        VerifySignatureResponse,
+        // Secure storage
+        SecureStoreRequest,
+        SecureStoreResponse,
+        SecureRetrieveRequest,
+        SecureRetrieveResponse,
+        SecureDeleteRequest,
+        SecureDeleteResponse,
+        // Health
+        SystemHealthResponse,
+        EncryptionStatus,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_transaction_request_requires_amount_and_description() {
+        let schemas = api_schemas();
+        let schema = &schemas["CreateTransactionRequest"].schema;
+        let required = &schema.object.as_ref().unwrap().required;
+
+        assert!(required.contains("amount"));
+        assert!(required.contains("description"));
+        assert!(!required.contains("notes"));
+        assert!(!required.contains("idempotency_key"));
+    }
+
+    #[test]
+    fn test_custom_types_use_string_schemas() {
+        let schemas = api_schemas();
+        let value = serde_json::to_value(&schemas["CreateAccountRequest"]).unwrap();
+        let properties = &value["properties"];
+
+        assert_eq!(properties["user_id"]["type"], "string");
+        assert_eq!(properties["user_id"]["format"], "uuid");
+        assert_eq!(properties["currency"]["pattern"], "^[A-Za-z0-9]{3,8}$");
+
+        let value = serde_json::to_value(&schemas["LoginRequest"]).unwrap();
+        assert_eq!(value["properties"]["password"]["type"], "string");
+        assert_eq!(value["properties"]["password"]["writeOnly"], true);
+    }
+}
@@ -4,15 +4,17 @@ use tracing::{info, instrument};
 
 use crate::{
     database::{
-        secure_storage_repository::SecureStorageRepository, ConnectionManager, DatabaseConnection,
-        PoolStats, SQLiteManager, SQLiteStats,
+        secure_storage_repository::SecureStorageRepository, ConnectionManager, PoolStats,
+        PooledConnection, SQLiteManager, SQLiteStats,
     },
     dto::{
         SecureDeleteRequest, SecureDeleteResponse, SecureRetrieveRequest, SecureRetrieveResponse,
         SecureStoreRequest, SecureStoreResponse,
     },
     error::{FiscusError, FiscusResult},
-    services::get_secure_storage_service,
+    services::{
+        get_secure_storage_service, secure_storage_service::initialize_secure_storage_service,
+    },
 };
 
 /// Global connection manager instance
@@ -29,9 +31,17 @@ static CONNECTION_MANAGER: Lazy<ConnectionManager> = Lazy::new(|| {
 });
 
 /// Get database connection for secure storage operations
-/// Uses proper connection pooling and configuration management
-fn get_database() -> FiscusResult<DatabaseConnection> {
-    CONNECTION_MANAGER.get_connection()
+///
+/// The connection returns to the pool when the guard is dropped, so keep it
+/// alive for as long as the connection is in use.
+async fn get_database() -> FiscusResult<PooledConnection> {
+    CONNECTION_MANAGER.get_connection().await
+}
+
+/// Start the secure storage service, which purges expired entries in the background
+pub(crate) async fn start_secure_storage_service() -> FiscusResult<()> {
+    let db = get_database().await?;
+    initialize_secure_storage_service(db.clone(), None).await
 }
 
 /// Store encrypted data securely
@@ -39,8 +49,8 @@ fn get_database() -> FiscusResult<DatabaseConnection> {
 #[instrument(skip(request), fields(user_id = %request.user_id, data_type = %request.data_type))]
 pub async fn secure_store(request: SecureStoreRequest) -> FiscusResult<SecureStoreResponse> {
     // Get database connection from pool
-    let db = get_database()?;
-    let repository = SecureStorageRepository::new(db);
+    let db = get_database().await?;
+    let repository = SecureStorageRepository::new(db.clone());
 
     // Store the data using the repository
     let record = repository
@@ -69,8 +79,8 @@ pub async fn secure_retrieve(
     request: SecureRetrieveRequest,
 ) -> FiscusResult<SecureRetrieveResponse> {
     // Get database connection from pool
-    let db = get_database()?;
-    let repository = SecureStorageRepository::new(db);
+    let db = get_database().await?;
+    let repository = SecureStorageRepository::new(db.clone());
 
     // Retrieve the data using the repository
     match repository
@@ -96,8 +106,8 @@ pub async fn secure_retrieve(
 #[instrument(skip(request), fields(user_id = %request.user_id, data_type = %request.data_type))]
 pub async fn secure_delete(request: SecureDeleteRequest) -> FiscusResult<SecureDeleteResponse> {
     // Get database connection from pool
-    let db = get_database()?;
-    let repository = SecureStorageRepository::new(db);
+    let db = get_database().await?;
+    let repository = SecureStorageRepository::new(db.clone());
 
     // Delete the data using the repository
     let was_deleted = repository
@@ -124,8 +134,8 @@ pub async fn secure_cleanup_expired() -> FiscusResult<u64> {
         }
         Err(_) => {
             // Fallback to direct repository access
-            let db = get_database()?;
-            let repository = SecureStorageRepository::new(db);
+            let db = get_database().await?;
+            let repository = SecureStorageRepository::new(db.clone());
             let deleted_count = repository.cleanup_expired().await?;
 
             info!(
@@ -152,8 +162,8 @@ pub async fn secure_get_statistics(
         }
         Err(_) => {
             // Fallback to direct repository access
-            let db = get_database()?;
-            let repository = SecureStorageRepository::new(db);
+            let db = get_database().await?;
+            let repository = SecureStorageRepository::new(db.clone());
             repository.get_storage_stats(user_id.as_deref()).await
         }
     }
@@ -188,7 +198,7 @@ pub async fn cleanup_idle_connections() -> FiscusResult<usize> {
 #[tauri::command]
 #[instrument]
 pub async fn get_sqlite_stats() -> FiscusResult<SQLiteStats> {
-    let db = get_database()?;
+    let db = get_database().await?;
     let sqlite_manager = SQLiteManager::new(CONNECTION_MANAGER.config().clone())?;
     sqlite_manager.get_sqlite_stats(&db).await
 }
@@ -198,7 +208,7 @@ pub async fn get_sqlite_stats() -> FiscusResult<SQLiteStats> {
 #[tauri::command]
 #[instrument]
 pub async fn optimize_sqlite_database() -> FiscusResult<()> {
-    let db = get_database()?;
+    let db = get_database().await?;
     let sqlite_manager = SQLiteManager::new(CONNECTION_MANAGER.config().clone())?;
     sqlite_manager.optimize_database(&db).await
 }
@@ -208,7 +218,7 @@ pub async fn optimize_sqlite_database() -> FiscusResult<()> {
 #[tauri::command]
 #[instrument]
 pub async fn configure_sqlite_performance() -> FiscusResult<()> {
-    let db = get_database()?;
+    let db = get_database().await?;
     let sqlite_manager = SQLiteManager::new(CONNECTION_MANAGER.config().clone())?;
     sqlite_manager.configure_sqlite_performance(&db).await
 }
@@ -218,7 +228,7 @@ pub async fn configure_sqlite_performance() -> FiscusResult<()> {
 #[tauri::command]
 #[instrument]
 pub async fn check_sqlite_integrity() -> FiscusResult<bool> {
-    let db = get_database()?;
+    let db = get_database().await?;
     let sqlite_manager = SQLiteManager::new(CONNECTION_MANAGER.config().clone())?;
     sqlite_manager.check_integrity(&db).await
 }
@@ -0,0 +1,344 @@
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::State;
+use tracing::{info, warn};
+
+use crate::{
+    database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
+    error::{FiscusError, FiscusResult, Validator},
+    security::authorize_command,
+};
+
+/// Set or clear the monthly spending limit of an account
+///
+/// Expenses that would take the account's outflow for a calendar month past
+/// the limit are rejected unless the transaction overrides it.
+#[tauri::command]
+pub async fn set_account_spending_limit(
+    account_id: String,
+    user_id: String,
+    spending_limit: Option<Decimal>,
+    db: State<'_, Database>,
+) -> Result<(), FiscusError> {
+    authorize_command("set_account_spending_limit").await?;
+
+    Validator::validate_uuid(&account_id, "account_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_account_ownership(&db, &account_id, &user_id).await?;
+
+    store_spending_limit(
+        &db,
+        LimitScope::Account,
+        &account_id,
+        &user_id,
+        spending_limit,
+    )
+    .await
+}
+
+/// Set or clear the monthly spending limit of a category
+///
+/// Expenses that would take the category's spend for a calendar month past
+/// the limit are rejected unless the transaction overrides it.
+#[tauri::command]
+pub async fn set_category_spending_limit(
+    category_id: String,
+    user_id: String,
+    spending_limit: Option<Decimal>,
+    db: State<'_, Database>,
+) -> Result<(), FiscusError> {
+    authorize_command("set_category_spending_limit").await?;
+
+    Validator::validate_uuid(&category_id, "category_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_category_ownership(&db, &category_id, &user_id).await?;
+
+    store_spending_limit(
+        &db,
+        LimitScope::Category,
+        &category_id,
+        &user_id,
+        spending_limit,
+    )
+    .await
+}
+
+/// What a spending limit is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LimitScope {
+    Account,
+    Category,
+}
+
+impl LimitScope {
+    fn table(self) -> &'static str {
+        match self {
+            LimitScope::Account => "accounts",
+            LimitScope::Category => "categories",
+        }
+    }
+
+    /// Column on `transactions` referencing the limited entity
+    fn transaction_column(self) -> &'static str {
+        match self {
+            LimitScope::Account => "account_id",
+            LimitScope::Category => "category_id",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LimitScope::Account => "Account",
+            LimitScope::Category => "Category",
+        }
+    }
+}
+
+async fn store_spending_limit(
+    db: &Database,
+    scope: LimitScope,
+    id: &str,
+    user_id: &str,
+    spending_limit: Option<Decimal>,
+) -> FiscusResult<()> {
+    if let Some(limit) = spending_limit {
+        Validator::validate_amount(limit, false).map_err(|e| e.for_field("spending_limit"))?;
+    }
+
+    // `table` comes from LimitScope, never from raw input
+    let query = format!(
+        "UPDATE {} SET spending_limit = ?1, updated_at = ?2 WHERE id = ?3 AND user_id = ?4",
+        scope.table()
+    );
+    let params_with_mapping = vec![
+        (
+            "spending_limit".to_string(),
+            spending_limit
+                .map(|limit| Value::String(limit.to_string()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "updated_at".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        ),
+        ("id".to_string(), Value::String(id.to_string())),
+        ("user_id".to_string(), Value::String(user_id.to_string())),
+    ];
+    let params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+        params_with_mapping,
+        user_id,
+        scope.table(),
+    )
+    .await?;
+
+    DatabaseUtils::execute_non_query(db, &query, params).await?;
+
+    info!(
+        scope = scope.table(),
+        id = id,
+        limited = spending_limit.is_some(),
+        "Updated spending limit"
+    );
+    Ok(())
+}
+
+/// Reject an expense that would push its account's or category's spending for
+/// the month of `transaction_date` past a configured limit
+///
+/// With `override_limit` the expense is allowed and the breach only logged.
+pub(crate) async fn enforce_spending_limits(
+    db: &Database,
+    user_id: &str,
+    account_id: &str,
+    category_id: Option<&str>,
+    amount: Decimal,
+    transaction_date: NaiveDate,
+    override_limit: bool,
+) -> FiscusResult<()> {
+    let scopes = std::iter::once((LimitScope::Account, account_id))
+        .chain(category_id.map(|id| (LimitScope::Category, id)));
+
+    for (scope, id) in scopes {
+        let Some(limit) = fetch_spending_limit(db, scope, id, user_id).await? else {
+            continue;
+        };
+        let spent = month_expense_total(db, scope, id, user_id, transaction_date).await?;
+
+        check_spending_limit(scope, id, limit, spent, amount, override_limit)?;
+    }
+
+    Ok(())
+}
+
+async fn fetch_spending_limit(
+    db: &Database,
+    scope: LimitScope,
+    id: &str,
+    user_id: &str,
+) -> FiscusResult<Option<Decimal>> {
+    let query = format!(
+        "SELECT spending_limit FROM {} WHERE id = ?1 AND user_id = ?2",
+        scope.table()
+    );
+    let rows: Vec<HashMap<String, Value>> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        &query,
+        vec![
+            Value::String(id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+        user_id,
+        scope.table(),
+    )
+    .await?;
+
+    Ok(rows
+        .first()
+        .and_then(|row| row.get("spending_limit"))
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<Decimal>().ok()))
+}
+
+/// Total of the expenses already booked against `id` in the month containing `date`
+async fn month_expense_total(
+    db: &Database,
+    scope: LimitScope,
+    id: &str,
+    user_id: &str,
+    date: NaiveDate,
+) -> FiscusResult<Decimal> {
+    let (month_start, next_month) = month_bounds(date);
+    let query = format!(
+        r#"
+        SELECT amount FROM transactions
+        WHERE {} = ?1 AND user_id = ?2 AND transaction_type = 'expense'
+          AND status NOT IN ('cancelled', 'voided')
+          AND date(transaction_date) >= ?3 AND date(transaction_date) < ?4
+        "#,
+        scope.transaction_column()
+    );
+    let rows: Vec<HashMap<String, Value>> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        &query,
+        vec![
+            Value::String(id.to_string()),
+            Value::String(user_id.to_string()),
+            Value::String(month_start.to_string()),
+            Value::String(next_month.to_string()),
+        ],
+        user_id,
+        "transactions",
+    )
+    .await?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| row.get("amount").and_then(|v| v.as_str()))
+        .filter_map(|s| s.parse::<Decimal>().ok())
+        .sum())
+}
+
+/// First day of the month containing `date` and of the month after it
+fn month_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let start = date.with_day(1).unwrap_or(date);
+    (start, start + Months::new(1))
+}
+
+/// Fail with a `Conflict` naming the limit when `amount` on top of `spent` exceeds it
+fn check_spending_limit(
+    scope: LimitScope,
+    id: &str,
+    limit: Decimal,
+    spent: Decimal,
+    amount: Decimal,
+    override_limit: bool,
+) -> FiscusResult<()> {
+    let total = spent + amount;
+    if total <= limit {
+        return Ok(());
+    }
+
+    if override_limit {
+        warn!(
+            scope = scope.table(),
+            id = id,
+            %limit,
+            %total,
+            "Spending limit overridden"
+        );
+        return Ok(());
+    }
+
+    Err(FiscusError::Conflict(format!(
+        "{} {id} spending limit of {limit} would be exceeded: {spent} already spent this month, \
+         {} remaining, transaction amount {amount}",
+        scope.label(),
+        (limit - spent).max(Decimal::ZERO)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expense_over_category_limit_is_blocked() {
+        let result = check_spending_limit(
+            LimitScope::Category,
+            "groceries",
+            Decimal::new(500, 0),
+            Decimal::new(45000, 2),
+            Decimal::new(7550, 2),
+            false,
+        );
+
+        match result {
+            Err(FiscusError::Conflict(message)) => {
+                assert!(message.contains("Category groceries spending limit of 500"));
+                assert!(message.contains("50.00 remaining"));
+            }
+            other => panic!("expected Conflict, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_override_permits_expense_over_limit() {
+        assert!(check_spending_limit(
+            LimitScope::Category,
+            "groceries",
+            Decimal::new(500, 0),
+            Decimal::new(45000, 2),
+            Decimal::new(7550, 2),
+            true,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_expense_reaching_limit_exactly_is_allowed() {
+        assert!(check_spending_limit(
+            LimitScope::Account,
+            "checking",
+            Decimal::new(500, 0),
+            Decimal::new(450, 0),
+            Decimal::new(50, 0),
+            false,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_month_bounds() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 17).unwrap();
+
+        assert_eq!(
+            month_bounds(date),
+            (
+                NaiveDate::from_ymd_opt(2024, 12, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+            )
+        );
+    }
+}
@@ -1,1439 +1,6047 @@
+use base64::Engine;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::io::Write;
 use tauri::State;
 use uuid::Uuid;
 
 use crate::{
+    commands::{
+        accounts::{ensure_account_open, load_account_defaults, AccountDefaults},
+        accruals::insert_generated_transaction,
+        currencies::{convert_amount, exchange_rate},
+        spending_limits::enforce_spending_limits,
+    },
     database::{encrypted::EncryptedDatabaseUtils, Database, DatabaseUtils},
     dto::{
-        BulkTransactionAction, BulkTransactionRequest, CreateTransactionRequest,
-        CreateTransferRequest, ExportFormat, PaginatedResponse, TransactionFilters,
-        TransactionStatsResponse, TransactionSummaryResponse, UpdateTransactionRequest,
+        AmountConvention, BulkTransactionAction, BulkTransactionRequest, CreateTransactionRequest,
+        CreateTransferRequest, CsvOptions, CursorPaginatedResponse, DuplicateTransactionCluster,
+        ExportFormat, PaginatedResponse, Patch, TagUsage, TransactionFilters,
+        TransactionImportResponse, TransactionJsonImportResponse, TransactionStatsResponse,
+        TransactionSummaryResponse, UpdateTransactionRequest,
     },
-    error::{FiscusError, SecurityValidator, Validator},
+    env_config,
+    error::{FiscusError, FiscusResult, SecurityValidator, ValidatedUserId, Validator},
     models::{Transaction, TransactionStatus, TransactionType, Transfer},
-    utils::parse_decimal_from_json,
+    security::{authorize_command, authorize_user},
+    services::events::{self, TransactionEvent},
+    utils::{
+        decimal_to_minor_units, ensure_not_stale, env_duration, env_flag, env_usize,
+        no_rows_updated_error, parse_decimal_from_json, stale_write_guard,
+        transaction_content_hash,
+    },
     with_transaction,
 };
 
-/// Create a new transaction
-#[tauri::command]
-pub async fn create_transaction(
-    request: CreateTransactionRequest,
-    db: State<'_, Database>,
-) -> Result<Transaction, FiscusError> {
-    // Validate input (user_id already validated by ValidatedUserId)
-    Validator::validate_uuid(&request.account_id, "account_id")?;
-    Validator::validate_string(&request.description, "description", 1, 255)?;
-    Validator::validate_amount(request.amount, true)?; // Allow negative for refunds/corrections
+/// Environment variable overriding how long idempotency keys are honoured
+const IDEMPOTENCY_KEY_TTL_ENV: &str = "FISCUS_IDEMPOTENCY_KEY_TTL_SECS";
 
-    // Format the DateTime to RFC3339 string for validation
-    let transaction_date = Validator::validate_datetime(&request.transaction_date.to_rfc3339())?;
+/// Default idempotency key window (24 hours)
+const DEFAULT_IDEMPOTENCY_KEY_TTL_SECS: i64 = 86_400;
 
-    if let Some(ref category_id) = request.category_id {
-        Validator::validate_uuid(category_id, "category_id")?;
+/// How long a repeated idempotency key returns the original transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IdempotencyConfig {
+    pub ttl: chrono::Duration,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl: chrono::Duration::seconds(DEFAULT_IDEMPOTENCY_KEY_TTL_SECS),
+        }
     }
+}
 
-    // Validate ownership
-    DatabaseUtils::validate_account_ownership(&db, &request.account_id, &request.user_id.as_str())
-        .await?;
+impl IdempotencyConfig {
+    /// Configuration read from `FISCUS_IDEMPOTENCY_KEY_TTL_SECS`
+    pub fn from_env() -> Self {
+        Self {
+            ttl: env_duration(
+                IDEMPOTENCY_KEY_TTL_ENV,
+                chrono::Duration::seconds(DEFAULT_IDEMPOTENCY_KEY_TTL_SECS),
+            ),
+        }
+    }
 
-    if let Some(ref category_id) = request.category_id {
-        DatabaseUtils::validate_category_ownership(&db, category_id, &request.user_id.as_str())
-            .await?;
+    /// When a key recorded at `recorded_at` stops being honoured
+    pub fn expires_at(
+        &self,
+        recorded_at: chrono::DateTime<chrono::Utc>,
+    ) -> chrono::DateTime<chrono::Utc> {
+        recorded_at + self.ttl
     }
+}
 
-    let transaction_id = Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+env_config!(IdempotencyConfig);
 
-    // Use transaction for atomicity
-    with_transaction!(&*db, async {
-        // Insert transaction
-        let insert_query = r#"
-            INSERT INTO transactions (
-                id, user_id, account_id, category_id, amount, description, notes,
-                transaction_date, transaction_type, status, reference_number, payee, tags,
-                created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
-        "#;
+/// Transaction created under an unexpired idempotency key, from its lookup row
+fn replayed_transaction_id(existing: Option<&HashMap<String, Value>>) -> Option<String> {
+    existing
+        .and_then(|row| row.get("transaction_id"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
 
-        let tags_json = request
-            .tags
-            .as_ref()
-            .map(|tags| serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string()));
+/// Environment variable enabling the integer `amount_minor` column
+const STORE_AMOUNT_MINOR_UNITS_ENV: &str = "FISCUS_STORE_AMOUNT_MINOR_UNITS";
+
+/// Which extra representations of a transaction amount are stored
+///
+/// `store_minor_units` is off by default: `amount_minor` is stored
+/// unencrypted, so enabling it trades amount confidentiality at rest for
+/// numerically correct range filters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AmountStorageConfig {
+    pub store_minor_units: bool,
+}
 
-        // Use encrypted parameter mapping for sensitive fields
-        let params_with_mapping = vec![
-            ("id".to_string(), Value::String(transaction_id.clone())),
-            (
-                "user_id".to_string(),
-                Value::String(request.user_id.to_string()),
-            ),
-            (
-                "account_id".to_string(),
-                Value::String(request.account_id.clone()),
-            ),
-            (
-                "category_id".to_string(),
-                request
-                    .category_id
-                    .as_ref()
-                    .map(|id| Value::String(id.clone()))
-                    .unwrap_or(Value::Null),
-            ),
-            (
-                "amount".to_string(),
-                Value::String(request.amount.to_string()),
-            ),
-            (
-                "description".to_string(),
-                Value::String(request.description.clone()),
-            ),
-            (
-                "notes".to_string(),
-                request
-                    .notes
-                    .as_ref()
-                    .map(|n| Value::String(n.clone()))
-                    .unwrap_or(Value::Null),
-            ),
-            (
-                "transaction_date".to_string(),
-                Value::String(transaction_date.to_rfc3339()),
-            ),
-            (
-                "transaction_type".to_string(),
-                Value::String(request.transaction_type.to_string()),
-            ),
-            (
-                "status".to_string(),
-                Value::String(TransactionStatus::Completed.to_string()),
-            ),
-            (
-                "reference_number".to_string(),
-                request
-                    .reference_number
-                    .as_ref()
-                    .map(|r| Value::String(r.clone()))
-                    .unwrap_or(Value::Null),
-            ),
-            (
-                "payee".to_string(),
-                request
-                    .payee
-                    .as_ref()
-                    .map(|p| Value::String(p.clone()))
-                    .unwrap_or(Value::Null),
-            ),
-            (
-                "tags".to_string(),
-                tags_json
-                    .as_ref()
-                    .map(|t| Value::String(t.clone()))
-                    .unwrap_or(Value::Null),
-            ),
-            ("created_at".to_string(), Value::String(now.clone())),
-            ("updated_at".to_string(), Value::String(now)),
-        ];
+impl AmountStorageConfig {
+    /// Configuration read from `FISCUS_STORE_AMOUNT_MINOR_UNITS`
+    pub fn from_env() -> Self {
+        Self {
+            store_minor_units: env_flag(STORE_AMOUNT_MINOR_UNITS_ENV),
+        }
+    }
+}
 
-        let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
-            params_with_mapping,
-            &request.user_id.as_str(),
-            "transactions",
-        )
-        .await?;
+env_config!(AmountStorageConfig);
 
-        DatabaseUtils::execute_non_query(&db, insert_query, encrypted_params).await?;
+/// Whether amounts are also stored as integer minor units
+pub(crate) fn store_amount_minor_units() -> bool {
+    AmountStorageConfig::current().store_minor_units
+}
 
-        // Update account balance based on transaction type
-        let current_balance = DatabaseUtils::get_account_balance(&db, &request.account_id).await?;
-        let new_balance = match request.transaction_type {
-            TransactionType::Income => current_balance + request.amount,
-            TransactionType::Expense => current_balance - request.amount,
-            TransactionType::Transfer => current_balance, // Transfers are handled separately
-        };
+/// Value for the `amount_minor` column, NULL unless minor-unit storage is enabled
+pub(crate) fn amount_minor_value(amount: Decimal, store_minor_units: bool) -> FiscusResult<Value> {
+    if store_minor_units {
+        Ok(Value::from(decimal_to_minor_units(amount)?))
+    } else {
+        Ok(Value::Null)
+    }
+}
 
-        if request.transaction_type != TransactionType::Transfer {
-            DatabaseUtils::update_account_balance(&db, &request.account_id, new_balance).await?;
-        }
+/// Environment variable overriding the most rows an unpaginated transaction query may load
+const QUERY_SOFT_CAP_ENV: &str = "FISCUS_QUERY_SOFT_CAP";
 
-        Ok::<(), FiscusError>(())
-    })?;
+/// Environment variable choosing what happens past the soft cap: `reject` or `truncate`
+const QUERY_SOFT_CAP_MODE_ENV: &str = "FISCUS_QUERY_SOFT_CAP_MODE";
 
-    // Return the created transaction
-    get_transaction_by_id(transaction_id, db).await
-}
+/// Default most rows an unpaginated transaction query may load
+const DEFAULT_QUERY_SOFT_CAP: usize = 5000;
 
-/// Get transactions with filtering and pagination
-#[tauri::command]
-pub async fn get_transactions(
-    filters: TransactionFilters,
-    db: State<'_, Database>,
-) -> Result<Vec<Transaction>, FiscusError> {
-    // Validate user (already validated by ValidatedUserId)
-    DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
+/// What a transaction query does when it would load more rows than the soft cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SoftCapMode {
+    /// Fail with `InvalidInput`, asking for narrower filters or pagination
+    Reject,
+    /// Return only the first rows, flagged as truncated where the response allows it
+    Truncate,
+}
 
-    // Build filter map
-    let mut filter_map = HashMap::new();
-    filter_map.insert("user_id".to_string(), filters.user_id.as_str().to_string());
+/// Limit on how many transactions one query loads and decrypts
+///
+/// Checked with a `COUNT(*)` before anything is decrypted, and only for
+/// queries whose page size could exceed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct QuerySoftCap {
+    pub max_rows: usize,
+    pub mode: SoftCapMode,
+}
 
-    if let Some(account_id) = filters.account_id {
-        Validator::validate_uuid(&account_id, "account_id")?;
-        filter_map.insert("account_id".to_string(), account_id);
+impl Default for QuerySoftCap {
+    fn default() -> Self {
+        Self {
+            max_rows: DEFAULT_QUERY_SOFT_CAP,
+            mode: SoftCapMode::Reject,
+        }
     }
+}
 
-    if let Some(category_id) = filters.category_id {
-        Validator::validate_uuid(&category_id, "category_id")?;
-        filter_map.insert("category_id".to_string(), category_id);
-    }
+impl QuerySoftCap {
+    /// Configuration read from `FISCUS_QUERY_SOFT_CAP` and `FISCUS_QUERY_SOFT_CAP_MODE`
+    pub fn from_env() -> Self {
+        let max_rows = env_usize(QUERY_SOFT_CAP_ENV, DEFAULT_QUERY_SOFT_CAP);
+        let mode = match std::env::var(QUERY_SOFT_CAP_MODE_ENV) {
+            Ok(value) if value.trim().eq_ignore_ascii_case("truncate") => SoftCapMode::Truncate,
+            _ => SoftCapMode::Reject,
+        };
 
-    if let Some(transaction_type) = filters.transaction_type {
-        filter_map.insert("transaction_type".to_string(), transaction_type.to_string());
+        Self { max_rows, mode }
     }
 
-    if let Some(status) = filters.status {
-        filter_map.insert("status".to_string(), status.to_string());
+    /// Whether a query returning at most `page_bound` rows (`None` for no
+    /// bound) needs its matching rows counted
+    pub fn applies_to(&self, page_bound: Option<usize>) -> bool {
+        page_bound.is_none_or(|bound| bound > self.max_rows)
     }
 
-    if let Some(start_date) = filters.start_date {
-        Validator::validate_date(&start_date)?;
-        filter_map.insert("start_date".to_string(), start_date);
-    }
+    /// Row limit to impose on a query matching `matching` rows
+    ///
+    /// `None` leaves the query as it is; past the cap, `Reject` fails and
+    /// `Truncate` returns the cap.
+    pub fn check(&self, matching: usize) -> FiscusResult<Option<usize>> {
+        if matching <= self.max_rows {
+            return Ok(None);
+        }
 
-    if let Some(end_date) = filters.end_date {
-        Validator::validate_date(&end_date)?;
-        filter_map.insert("end_date".to_string(), end_date);
+        match self.mode {
+            SoftCapMode::Reject => Err(FiscusError::InvalidInput(format!(
+                "This query matches {matching} transactions, more than the {} that can be loaded at once; \
+                 narrow it with filters such as a date range or account, or paginate with limit and offset",
+                self.max_rows
+            ))),
+            SoftCapMode::Truncate => Ok(Some(self.max_rows)),
+        }
     }
+}
 
-    if let Some(min_amount) = filters.min_amount {
-        filter_map.insert("min_amount".to_string(), min_amount.to_string());
-    }
+env_config!(QuerySoftCap);
 
-    if let Some(max_amount) = filters.max_amount {
-        filter_map.insert("max_amount".to_string(), max_amount.to_string());
+/// Most rows a query with `limit` and `offset` can return, `None` when unbounded
+///
+/// Mirrors the clamping in `DatabaseUtils::build_limit_clause`.
+fn page_bound(limit: Option<i32>, offset: Option<i32>) -> Option<usize> {
+    match (limit, offset) {
+        (Some(limit), _) => Some(limit.clamp(1, 1000) as usize),
+        (None, Some(_)) => Some(100),
+        (None, None) => None,
     }
+}
 
-    // Validate filter fields
-    SecurityValidator::validate_transaction_filter_fields(&filter_map)?;
+/// Environment variable overriding the maximum number of items in one bulk operation
+const MAX_BULK_ITEMS_ENV: &str = "FISCUS_MAX_BULK_ITEMS";
 
-    let base_query = r#"
-        SELECT id, user_id, account_id, category_id, amount, description, notes,
-               transaction_date, transaction_type, status, reference_number, payee, tags,
-               created_at, updated_at
-        FROM transactions
-    "#
-    .to_string();
+/// Default maximum number of items in one bulk operation
+const DEFAULT_MAX_BULK_ITEMS: usize = 100;
 
-    // Add search functionality
-    let mut search_conditions = Vec::new();
-    if let Some(ref search) = filters.search {
-        if !search.trim().is_empty() {
-            search_conditions
-                .push("(description LIKE ? OR payee LIKE ? OR notes LIKE ?)".to_string());
+/// Limits applied to bulk transaction operations and imports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BulkConfig {
+    pub max_items: usize,
+}
+
+impl Default for BulkConfig {
+    fn default() -> Self {
+        Self {
+            max_items: DEFAULT_MAX_BULK_ITEMS,
         }
     }
+}
 
-    let (where_clause, mut where_params) = DatabaseUtils::build_where_clause(
-        &filter_map,
-        &[
-            "user_id",
-            "account_id",
-            "category_id",
-            "transaction_type",
-            "status",
-            "start_date",
-            "end_date",
-            "min_amount",
-            "max_amount",
-        ],
-        search_conditions,
-    )?;
+impl BulkConfig {
+    /// Configuration read from the environment, `FISCUS_MAX_BULK_ITEMS` overriding the default
+    pub fn from_env() -> Self {
+        Self {
+            max_items: env_usize(MAX_BULK_ITEMS_ENV, DEFAULT_MAX_BULK_ITEMS),
+        }
+    }
 
-    // Add search parameters
-    if let Some(search) = filters.search {
-        if !search.trim().is_empty() {
-            let search_pattern = format!("%{}%", search.trim());
-            where_params.push(Value::String(search_pattern.clone()));
-            where_params.push(Value::String(search_pattern.clone()));
-            where_params.push(Value::String(search_pattern));
+    /// Reject an empty batch or one larger than `max_items`
+    pub fn validate_batch_size(&self, item_count: usize) -> FiscusResult<()> {
+        if item_count == 0 {
+            return Err(FiscusError::InvalidInput(
+                "No transaction IDs provided".to_string(),
+            ));
+        }
+
+        if item_count > self.max_items {
+            return Err(FiscusError::InvalidInput(format!(
+                "Cannot process {item_count} transactions at once; the configured maximum is {}",
+                self.max_items
+            )));
         }
+
+        Ok(())
     }
+}
 
-    let order_clause = DatabaseUtils::build_order_clause(
-        filters.sort_by.as_deref(),
-        filters.sort_direction.as_deref(),
-        SecurityValidator::TRANSACTION_SORT_FIELDS,
-        "transaction_date",
-    )?;
+env_config!(BulkConfig);
 
-    let limit_clause = DatabaseUtils::build_limit_clause(filters.limit, filters.offset);
+/// Environment variable overriding the maximum number of tags on one transaction
+const MAX_TAGS_ENV: &str = "FISCUS_MAX_TAGS";
 
-    let final_query = format!("{base_query} {where_clause} {order_clause} {limit_clause}");
+/// Environment variable overriding the maximum length of one tag, in characters
+const MAX_TAG_LENGTH_ENV: &str = "FISCUS_MAX_TAG_LENGTH";
 
-    // Use encrypted query to properly decrypt sensitive fields
-    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
-        &db,
-        &final_query,
-        where_params,
-        &filters.user_id.as_str(),
-        "transactions",
-    )
-    .await?;
+/// Environment variable that, when `true`, stores tags in lowercase
+const LOWERCASE_TAGS_ENV: &str = "FISCUS_LOWERCASE_TAGS";
 
-    Ok(transactions)
+/// Default maximum number of tags on one transaction
+const DEFAULT_MAX_TAGS: usize = 20;
+
+/// Default maximum length of one tag, in characters
+const DEFAULT_MAX_TAG_LENGTH: usize = 50;
+
+/// Limits and normalization applied to transaction tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TagConfig {
+    pub max_tags: usize,
+    pub max_tag_length: usize,
+    pub lowercase: bool,
 }
 
-/// Get transactions with pagination support
-#[tauri::command]
-pub async fn get_transactions_paginated(
-    filters: TransactionFilters,
-    db: State<'_, Database>,
-) -> Result<PaginatedResponse<Transaction>, FiscusError> {
-    // Validate user (already validated by ValidatedUserId)
-    DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
+impl Default for TagConfig {
+    fn default() -> Self {
+        Self {
+            max_tags: DEFAULT_MAX_TAGS,
+            max_tag_length: DEFAULT_MAX_TAG_LENGTH,
+            lowercase: false,
+        }
+    }
+}
 
-    // Get total count first
-    let count_query = r#"
-        SELECT COUNT(*) as total
-        FROM transactions
-        WHERE user_id = ?1
-    "#;
+impl TagConfig {
+    /// Configuration read from `FISCUS_MAX_TAGS`, `FISCUS_MAX_TAG_LENGTH` and `FISCUS_LOWERCASE_TAGS`
+    pub fn from_env() -> Self {
+        Self {
+            max_tags: env_usize(MAX_TAGS_ENV, DEFAULT_MAX_TAGS),
+            max_tag_length: env_usize(MAX_TAG_LENGTH_ENV, DEFAULT_MAX_TAG_LENGTH),
+            lowercase: env_flag(LOWERCASE_TAGS_ENV),
+        }
+    }
 
-    let count_result: Option<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query_single(
-            &db,
-            count_query,
-            vec![Value::String(filters.user_id.as_str().to_string())],
-        )
-        .await?;
+    /// Trim, optionally lowercase and deduplicate `tags`, then check them against the limits
+    ///
+    /// Tags may contain letters, digits, spaces, hyphens and underscores.
+    pub fn normalize_tags(&self, tags: &[String]) -> FiscusResult<Vec<String>> {
+        let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = tag.trim();
+            let tag = if self.lowercase {
+                tag.to_lowercase()
+            } else {
+                tag.to_string()
+            };
 
-    let total = count_result
-        .and_then(|row| row.get("total").and_then(|v| v.as_i64()))
-        .unwrap_or(0) as i32;
+            if tag.is_empty() {
+                return Err(FiscusError::field_validation(
+                    "tags",
+                    "empty",
+                    "Tags must not be empty",
+                ));
+            }
+            if tag.chars().count() > self.max_tag_length {
+                return Err(FiscusError::field_validation(
+                    "tags",
+                    "too_long",
+                    format!(
+                        "Tag '{tag}' is longer than {} characters",
+                        self.max_tag_length
+                    ),
+                ));
+            }
+            if !tag
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+            {
+                return Err(FiscusError::field_validation(
+                    "tags",
+                    "invalid_format",
+                    format!(
+                        "Tag '{tag}' may only contain letters, digits, spaces, hyphens and underscores"
+                    ),
+                ));
+            }
 
-    // Get transactions with filters
-    let transactions = get_transactions(filters.clone(), db).await?;
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
 
-    let page = filters.offset.unwrap_or(0) / filters.limit.unwrap_or(50) + 1;
-    let per_page = filters.limit.unwrap_or(50);
+        if normalized.len() > self.max_tags {
+            return Err(FiscusError::field_validation(
+                "tags",
+                "too_many",
+                format!(
+                    "A transaction can have at most {} tags, got {}",
+                    self.max_tags,
+                    normalized.len()
+                ),
+            ));
+        }
 
-    Ok(PaginatedResponse::new(transactions, total, page, per_page))
+        Ok(normalized)
+    }
 }
 
-/// Get transaction statistics
-#[tauri::command]
-pub async fn get_transaction_stats(
-    filters: TransactionFilters,
-    db: State<'_, Database>,
-) -> Result<TransactionStatsResponse, FiscusError> {
-    // Validate user (already validated by ValidatedUserId)
-    DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
+env_config!(TagConfig);
 
-    let stats_query = r#"
-        SELECT
-            COUNT(*) as total_transactions,
-            COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END), 0) as total_income,
-            COALESCE(SUM(CASE WHEN transaction_type = 'expense' THEN amount ELSE 0 END), 0) as total_expenses,
-            COALESCE(AVG(amount), 0) as average_amount,
-            MAX(CASE WHEN transaction_type = 'expense' THEN amount END) as largest_expense,
-            MAX(CASE WHEN transaction_type = 'income' THEN amount END) as largest_income
-        FROM transactions
-        WHERE user_id = ?1
-    "#;
+/// Events announcing a committed transaction and, unless it is a transfer, its balance change
+fn transaction_created_events(
+    user_id: &str,
+    transaction_id: &str,
+    account_id: &str,
+    transaction_type: &TransactionType,
+) -> Vec<TransactionEvent> {
+    let mut events = vec![TransactionEvent::TransactionCreated {
+        user_id: user_id.to_string(),
+        transaction_id: transaction_id.to_string(),
+        account_id: account_id.to_string(),
+    }];
+
+    if *transaction_type != TransactionType::Transfer {
+        events.push(TransactionEvent::BalanceChanged {
+            user_id: user_id.to_string(),
+            account_id: account_id.to_string(),
+        });
+    }
 
-    let stats_result: Option<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query_single(
-            &db,
-            stats_query,
-            vec![Value::String(filters.user_id.as_str().to_string())],
-        )
-        .await?;
+    events
+}
 
-    let stats = stats_result
-        .ok_or_else(|| FiscusError::NotFound("No transaction statistics found".to_string()))?;
+/// Validate the fields of a new transaction, returning its parsed date
+///
+/// User ID is already validated by `ValidatedUserId`.
+fn validate_create_transaction_request(
+    request: &CreateTransactionRequest,
+) -> FiscusResult<chrono::DateTime<chrono::Utc>> {
+    Validator::validate_uuid(&request.account_id, "account_id")?;
+    Validator::validate_string(&request.description, "description", 1, 255)?;
+    Validator::validate_amount(request.amount, true)?; // Sign is handled by `normalize_amount`
 
-    let total_income = parse_decimal_from_json(&stats, "total_income");
-    let total_expenses = parse_decimal_from_json(&stats, "total_expenses");
+    // Format the DateTime to RFC3339 string for validation
+    let transaction_date =
+        Validator::validate_datetime(&request.transaction_date.to_rfc3339(), "transaction_date")?;
 
-    // Get transactions by type
-    let type_query = r#"
-        SELECT transaction_type, COUNT(*) as count
-        FROM transactions
-        WHERE user_id = ?1
-        GROUP BY transaction_type
-    "#;
+    if let Some(ref category_id) = request.category_id {
+        Validator::validate_uuid(category_id, "category_id")?;
+    }
 
-    let type_results: Vec<HashMap<String, serde_json::Value>> = DatabaseUtils::execute_query(
-        &db,
-        type_query,
-        vec![Value::String(filters.user_id.as_str().to_string())],
-    )
-    .await?;
+    if let Some(ref idempotency_key) = request.idempotency_key {
+        validate_idempotency_key(idempotency_key)?;
+    }
 
-    let mut transactions_by_type = HashMap::new();
-    for row in type_results {
-        if let (Some(tx_type), Some(count)) = (
-            row.get("transaction_type").and_then(|v| v.as_str()),
-            row.get("count").and_then(|v| v.as_i64()),
-        ) {
-            transactions_by_type.insert(tx_type.to_string(), count as i32);
+    if let Some(ref original_transaction_id) = request.refunds_transaction_id {
+        Validator::validate_uuid(original_transaction_id, "refunds_transaction_id")?;
+        if request.transaction_type() != TransactionType::Income {
+            return Err(FiscusError::field_validation(
+                "transaction_type",
+                "invalid_refund",
+                "A refund must be an income transaction",
+            ));
+        }
+        if request.amount <= Decimal::ZERO {
+            return Err(FiscusError::field_validation(
+                "amount",
+                "invalid_refund",
+                "A refund amount must be positive",
+            ));
         }
     }
 
-    // Get transactions by status
-    let status_query = r#"
-        SELECT status, COUNT(*) as count
-        FROM transactions
-        WHERE user_id = ?1
-        GROUP BY status
-    "#;
+    match (request.original_amount, &request.original_currency) {
+        (Some(original_amount), Some(_)) => Validator::validate_amount(original_amount, true)?,
+        (None, None) => {}
+        (Some(_), None) => {
+            return Err(FiscusError::field_validation(
+                "original_currency",
+                "required",
+                "original_currency is required with original_amount",
+            ))
+        }
+        (None, Some(_)) => {
+            return Err(FiscusError::field_validation(
+                "original_amount",
+                "required",
+                "original_amount is required with original_currency",
+            ))
+        }
+    }
 
-    let status_results: Vec<HashMap<String, serde_json::Value>> = DatabaseUtils::execute_query(
-        &db,
-        status_query,
-        vec![Value::String(filters.user_id.as_str().to_string())],
+    Ok(transaction_date)
+}
+
+/// Amount to post to the account: the request amount, or the foreign-currency
+/// original converted to the account currency at the transaction date's rate
+async fn posted_amount(
+    db: &Database,
+    request: &CreateTransactionRequest,
+    transaction_date: chrono::DateTime<chrono::Utc>,
+) -> FiscusResult<Decimal> {
+    let (Some(original_amount), Some(original_currency)) =
+        (request.original_amount, &request.original_currency)
+    else {
+        return Ok(request.amount);
+    };
+
+    let row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        "SELECT currency FROM accounts WHERE id = ?1",
+        vec![Value::String(request.account_id.clone())],
     )
     .await?;
+    let account_currency = row
+        .as_ref()
+        .and_then(|row| row.get("currency"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| FiscusError::NotFound("Account not found".to_string()))?;
 
-    let mut transactions_by_status = HashMap::new();
-    for row in status_results {
-        if let (Some(status), Some(count)) = (
-            row.get("status").and_then(|v| v.as_str()),
-            row.get("count").and_then(|v| v.as_i64()),
-        ) {
-            transactions_by_status.insert(status.to_string(), count as i32);
-        }
+    let rate = exchange_rate(
+        db,
+        original_currency.as_str(),
+        account_currency,
+        transaction_date.date_naive(),
+    )
+    .await?;
+
+    Ok(convert_amount(original_amount, rate, account_currency))
+}
+
+/// Change to the account balance from posting `amount`; transfers are handled separately
+pub(crate) fn balance_delta(amount: Decimal, transaction_type: &TransactionType) -> Decimal {
+    match transaction_type {
+        TransactionType::Income => amount,
+        TransactionType::Expense => -amount,
+        TransactionType::Transfer => Decimal::ZERO,
     }
+}
 
-    Ok(TransactionStatsResponse {
-        total_transactions: stats
-            .get("total_transactions")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0) as i32,
-        total_income,
-        total_expenses,
-        net_income: total_income - total_expenses,
-        average_transaction_amount: parse_decimal_from_json(&stats, "average_amount"),
-        largest_expense: {
-            let value = parse_decimal_from_json(&stats, "largest_expense");
-            if value == rust_decimal::Decimal::ZERO {
-                None
-            } else {
-                Some(value)
-            }
-        },
-        largest_income: {
-            let value = parse_decimal_from_json(&stats, "largest_income");
-            if value == rust_decimal::Decimal::ZERO {
-                None
-            } else {
-                Some(value)
-            }
-        },
-        most_frequent_category: None, // TODO: Implement category analysis
-        transactions_by_type,
-        transactions_by_status,
-    })
+/// Blind index stored alongside an encrypted payee; blank payees get none
+async fn payee_index_value(payee: Option<&str>, user_id: &str) -> FiscusResult<Value> {
+    match payee.map(str::trim).filter(|payee| !payee.is_empty()) {
+        Some(payee) => Ok(Value::String(
+            EncryptedDatabaseUtils::blind_index_value(payee, user_id, "transactions", "payee")
+                .await?,
+        )),
+        None => Ok(Value::Null),
+    }
 }
 
-/// Get a single transaction by ID (internal helper with user_id for encryption)
-async fn get_transaction_by_id_encrypted(
-    transaction_id: String,
-    user_id: &str,
-    db: &Database,
-) -> Result<Transaction, FiscusError> {
-    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+/// Index (and encrypt) payees written before payee blind indexes existed
+///
+/// Rows older than migration 014 keep a plaintext payee and no index, so
+/// exact-match payee filters miss them. Idempotent: only unindexed rows
+/// are touched. Returns the number of rows updated.
+#[tauri::command]
+pub async fn backfill_payee_indexes(
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<u64, FiscusError> {
+    authorize_command("backfill_payee_indexes").await?;
 
-    let query = r#"
-        SELECT id, user_id, account_id, category_id, amount, description, notes,
-               transaction_date, transaction_type, status, reference_number, payee, tags,
-               created_at, updated_at
-        FROM transactions
-        WHERE id = ?1
-    "#;
+    let user_id = ValidatedUserId::new(&user_id)?;
+    authorize_user(&user_id.as_str()).await?;
 
-    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+    index_unindexed_payees(&db, &user_id.as_str()).await
+}
+
+/// Write a blind index for every payee of `user_id` that lacks one
+pub(crate) async fn index_unindexed_payees(db: &Database, user_id: &str) -> FiscusResult<u64> {
+    let rows: Vec<HashMap<String, Value>> = EncryptedDatabaseUtils::execute_encrypted_query(
         db,
-        query,
-        vec![Value::String(transaction_id.clone())],
+        "SELECT id, payee FROM transactions WHERE user_id = ?1 AND payee IS NOT NULL AND payee_index IS NULL",
+        vec![Value::String(user_id.to_string())],
         user_id,
         "transactions",
     )
     .await?;
 
-    transactions
-        .into_iter()
-        .next()
-        .ok_or_else(|| FiscusError::NotFound("Transaction not found".to_string()))
-}
+    let mut updated = 0;
+    for row in rows {
+        let (Some(id), Some(payee)) = (
+            row.get("id").and_then(|v| v.as_str()),
+            row.get("payee").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
 
-/// Get a single transaction by ID
-#[tauri::command]
-pub async fn get_transaction_by_id(
-    transaction_id: String,
-    db: State<'_, Database>,
-) -> Result<Transaction, FiscusError> {
-    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+        let params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            vec![
+                ("payee".to_string(), Value::String(payee.to_string())),
+                (
+                    "payee_index".to_string(),
+                    payee_index_value(Some(payee), user_id).await?,
+                ),
+                ("id".to_string(), Value::String(id.to_string())),
+                ("user_id".to_string(), Value::String(user_id.to_string())),
+            ],
+            user_id,
+            "transactions",
+        )
+        .await?;
 
-    // First, get the user_id for this transaction (this field is not encrypted)
-    let user_query = "SELECT user_id FROM transactions WHERE id = ?1";
-    let user_result: Option<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query_single(
-            &db,
-            user_query,
-            vec![Value::String(transaction_id.clone())],
+        updated += DatabaseUtils::execute_non_query(
+            db,
+            "UPDATE transactions SET payee = ?1, payee_index = ?2 WHERE id = ?3 AND user_id = ?4 AND payee_index IS NULL",
+            params,
         )
         .await?;
+    }
 
-    let user_id = user_result
-        .and_then(|row| {
-            row.get("user_id")
-                .and_then(|v| v.as_str().map(|s| s.to_string()))
-        })
-        .ok_or_else(|| FiscusError::NotFound("Transaction not found".to_string()))?;
+    Ok(updated)
+}
 
-    // Now get the full transaction with decryption
-    get_transaction_by_id_encrypted(transaction_id, &user_id, &db).await
+/// Validate a client-supplied idempotency key
+fn validate_idempotency_key(key: &str) -> Result<(), FiscusError> {
+    Validator::validate_string(key, "idempotency_key", 1, 255)?;
+
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+    {
+        return Err(FiscusError::field_validation(
+            "idempotency_key",
+            "invalid_format",
+            "idempotency_key contains invalid characters",
+        ));
+    }
+
+    Ok(())
 }
 
-/// Update a transaction
+/// Fill the category and payee the request leaves out from its account's defaults
+///
+/// Values given in the request always win. Transfers keep neither.
+fn apply_account_defaults(request: &mut CreateTransactionRequest, defaults: AccountDefaults) {
+    if request.transaction_type() == TransactionType::Transfer {
+        return;
+    }
+    if request.category_id.is_none() {
+        request.category_id = defaults.category_id;
+    }
+    if request.payee.is_none() {
+        request.payee = defaults.payee;
+    }
+}
+
+/// Create a new transaction
+///
+/// Expenses that would break an account or category spending limit are
+/// rejected with a `Conflict` unless `override_limit` is set. A missing
+/// category or payee falls back to the account's default.
 #[tauri::command]
-pub async fn update_transaction(
-    transaction_id: String,
-    user_id: String,
-    request: UpdateTransactionRequest,
+pub async fn create_transaction(
+    mut request: CreateTransactionRequest,
     db: State<'_, Database>,
 ) -> Result<Transaction, FiscusError> {
-    // Validate input
-    Validator::validate_uuid(&transaction_id, "transaction_id")?;
-    Validator::validate_uuid(&user_id, "user_id")?;
-
-    // Get current transaction to validate ownership and calculate balance changes
-    let current_transaction = get_transaction_by_id(transaction_id.clone(), db.clone()).await?;
+    authorize_command("create_transaction").await?;
 
-    if current_transaction.user_id != user_id {
-        return Err(FiscusError::Authorization(
-            "Transaction access denied".to_string(),
-        ));
-    }
+    request.normalize_amount(*AmountConvention::current())?;
+    request.tags = request
+        .tags
+        .as_deref()
+        .map(|tags| TagConfig::current().normalize_tags(tags))
+        .transpose()?;
+    let transaction_date = validate_create_transaction_request(&request)?;
 
-    // Build update query dynamically with encrypted parameter mapping
-    let mut update_fields = Vec::new();
-    let mut params_with_mapping = Vec::new();
-    let mut param_index = 1;
+    // Validate ownership
+    DatabaseUtils::validate_account_ownership(&db, &request.account_id, &request.user_id.as_str())
+        .await?;
+    ensure_account_open(
+        &db,
+        &request.account_id,
+        &request.user_id.as_str(),
+        transaction_date.date_naive(),
+    )
+    .await?;
+    let defaults =
+        load_account_defaults(&db, &request.account_id, &request.user_id.as_str()).await?;
+    apply_account_defaults(&mut request, defaults);
 
-    if let Some(category_id) = &request.category_id {
-        Validator::validate_uuid(category_id, "category_id")?;
-        DatabaseUtils::validate_category_ownership(&db, category_id, &user_id).await?;
-        update_fields.push(format!("`category_id` = ?{param_index}"));
-        params_with_mapping.push((
-            "category_id".to_string(),
-            Value::String(category_id.clone()),
-        ));
-        param_index += 1;
+    if let Some(ref category_id) = request.category_id {
+        DatabaseUtils::validate_category_ownership(&db, category_id, &request.user_id.as_str())
+            .await?;
     }
 
-    let mut amount_changed = false;
-    let mut new_amount = current_transaction.amount;
-    if let Some(amount) = request.amount {
-        Validator::validate_amount(amount, true)?;
-        update_fields.push(format!("`amount` = ?{param_index}"));
-        params_with_mapping.push(("amount".to_string(), Value::String(amount.to_string())));
-        param_index += 1;
-        amount_changed = true;
-        new_amount = amount;
-    }
+    let amount = posted_amount(&db, &request, transaction_date).await?;
+    let content_hash = stored_content_hash(&request, amount, transaction_date).await?;
 
-    // Track transaction type changes for balance calculation
-    let mut transaction_type_changed = false;
-    let mut new_transaction_type = current_transaction.transaction_type.clone();
-    if let Some(transaction_type) = &request.transaction_type {
-        if *transaction_type != current_transaction.transaction_type {
-            transaction_type_changed = true;
-            new_transaction_type = transaction_type.clone();
+    let new_transaction_id = Uuid::new_v4().to_string();
+    let now_utc = chrono::Utc::now();
+    let now = now_utc.to_rfc3339();
+
+    // Use transaction for atomicity
+    let transaction_id = with_transaction!(&*db, async {
+        if let Some(ref idempotency_key) = request.idempotency_key {
+            if let Some(existing_id) =
+                find_idempotent_transaction(&db, &request.user_id.as_str(), idempotency_key, &now)
+                    .await?
+            {
+                return Ok::<String, FiscusError>(existing_id);
+            }
         }
-    }
 
-    // Prevent changing transaction type to or from Transfer
-    // Transfers involve two linked transactions and require special handling
-    if transaction_type_changed {
-        if current_transaction.transaction_type == TransactionType::Transfer {
-            return Err(FiscusError::InvalidInput(
-                "Cannot change transaction type from Transfer. Transfers involve linked transactions and must be handled separately.".to_string(),
-            ));
+        if let Some(ref original_transaction_id) = request.refunds_transaction_id {
+            validate_refund(
+                &db,
+                &request.user_id.as_str(),
+                original_transaction_id,
+                amount,
+            )
+            .await?;
         }
-        if new_transaction_type == TransactionType::Transfer {
-            return Err(FiscusError::InvalidInput(
-                "Cannot change transaction type to Transfer. Use the create_transfer function to create transfers between accounts.".to_string(),
-            ));
+
+        if request.transaction_type() == TransactionType::Expense {
+            enforce_spending_limits(
+                &db,
+                &request.user_id.as_str(),
+                &request.account_id,
+                request.category_id.as_deref(),
+                amount,
+                transaction_date.date_naive(),
+                request.override_limit,
+            )
+            .await?;
         }
-    }
 
-    if let Some(description) = &request.description {
-        Validator::validate_string(description, "description", 1, 255)?;
-        update_fields.push(format!("`description` = ?{param_index}"));
-        params_with_mapping.push((
-            "description".to_string(),
-            Value::String(description.clone()),
-        ));
-        param_index += 1;
-    }
-
-    if let Some(notes) = &request.notes {
-        update_fields.push(format!("`notes` = ?{param_index}"));
-        params_with_mapping.push(("notes".to_string(), Value::String(notes.clone())));
-        param_index += 1;
-    }
-
-    if let Some(transaction_date) = &request.transaction_date {
-        let parsed_date = Validator::validate_datetime(transaction_date)?;
-        update_fields.push(format!("`transaction_date` = ?{param_index}"));
-        params_with_mapping.push((
-            "transaction_date".to_string(),
-            Value::String(parsed_date.to_rfc3339()),
-        ));
-        param_index += 1;
-    }
-
-    if let Some(transaction_type) = &request.transaction_type {
-        update_fields.push(format!("`transaction_type` = ?{param_index}"));
-        params_with_mapping.push((
-            "transaction_type".to_string(),
-            Value::String(transaction_type.to_string()),
-        ));
-        param_index += 1;
-    }
-
-    if let Some(status) = &request.status {
-        update_fields.push(format!("`status` = ?{param_index}"));
-        params_with_mapping.push(("status".to_string(), Value::String(status.to_string())));
-        param_index += 1;
-    }
-
-    if let Some(reference_number) = &request.reference_number {
-        update_fields.push(format!("`reference_number` = ?{param_index}"));
-        params_with_mapping.push((
-            "reference_number".to_string(),
-            Value::String(reference_number.clone()),
-        ));
-        param_index += 1;
-    }
-
-    if let Some(payee) = &request.payee {
-        update_fields.push(format!("`payee` = ?{param_index}"));
-        params_with_mapping.push(("payee".to_string(), Value::String(payee.clone())));
-        param_index += 1;
-    }
-
-    if let Some(tags) = &request.tags {
-        let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
-        update_fields.push(format!("`tags` = ?{param_index}"));
-        params_with_mapping.push(("tags".to_string(), Value::String(tags_json)));
-        param_index += 1;
-    }
-
-    if update_fields.is_empty() {
-        return Err(FiscusError::InvalidInput("No fields to update".to_string()));
-    }
-
-    // Use transaction for atomicity
-    with_transaction!(&*db, async {
-        // Update transaction
-        update_fields.push(format!("`updated_at` = ?{param_index}"));
-        params_with_mapping.push((
-            "updated_at".to_string(),
-            Value::String(chrono::Utc::now().to_rfc3339()),
-        ));
-        param_index += 1;
-
-        params_with_mapping.push(("id".to_string(), Value::String(transaction_id.clone())));
-
-        let update_query = format!(
-            "UPDATE transactions SET {} WHERE id = ?{}",
-            update_fields.join(", "),
-            param_index
-        );
-
-        // Encrypt sensitive parameters before update
-        let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
-            params_with_mapping,
-            &user_id,
-            "transactions",
+        let transaction_id = new_transaction_id.clone();
+        insert_transaction_row(
+            &db,
+            &request,
+            &transaction_id,
+            amount,
+            transaction_date,
+            &content_hash,
+            &now,
         )
         .await?;
 
-        let affected_rows =
-            DatabaseUtils::execute_non_query(&db, &update_query, encrypted_params).await?;
-
-        if affected_rows == 0 {
-            return Err(FiscusError::NotFound("Transaction not found".to_string()));
+        // Update account balance based on transaction type
+        if request.transaction_type() != TransactionType::Transfer {
+            DatabaseUtils::adjust_account_balance(
+                &db,
+                &request.account_id,
+                balance_delta(amount, &request.transaction_type()),
+            )
+            .await?;
         }
 
-        // Update account balance if amount or transaction type changed
-        // Note: Transfer type changes are prevented above, so we only need to check for non-Transfer transactions
-        if (amount_changed || transaction_type_changed)
-            && current_transaction.transaction_type != TransactionType::Transfer
-        {
-            let current_balance =
-                DatabaseUtils::get_account_balance(&db, &current_transaction.account_id).await?;
-
-            // Reverse the old transaction effect using the original transaction type and amount
-            let balance_after_reversal = match current_transaction.transaction_type {
-                TransactionType::Income => current_balance - current_transaction.amount,
-                TransactionType::Expense => current_balance + current_transaction.amount,
-                TransactionType::Transfer => current_balance,
-            };
-
-            // Apply the new transaction effect using the new transaction type and amount
-            let new_balance = match new_transaction_type {
-                TransactionType::Income => balance_after_reversal + new_amount,
-                TransactionType::Expense => balance_after_reversal - new_amount,
-                TransactionType::Transfer => balance_after_reversal,
-            };
-
-            DatabaseUtils::update_account_balance(
+        // Record the idempotency key alongside the new transaction
+        if let Some(ref idempotency_key) = request.idempotency_key {
+            record_idempotency_key(
                 &db,
-                &current_transaction.account_id,
-                new_balance,
+                &request.user_id.as_str(),
+                idempotency_key,
+                &transaction_id,
+                now_utc,
             )
             .await?;
         }
 
-        Ok::<(), FiscusError>(())
+        Ok::<String, FiscusError>(transaction_id)
     })?;
 
-    // Return updated transaction
+    // A replayed idempotency key returns the earlier transaction without changes
+    if transaction_id == new_transaction_id {
+        events::publish(transaction_created_events(
+            &request.user_id.as_str(),
+            &transaction_id,
+            &request.account_id,
+            &request.transaction_type(),
+        ));
+    }
+
+    // Return the created (or previously created) transaction
     get_transaction_by_id(transaction_id, db).await
 }
 
-#[cfg(test)]
-mod update_transaction_tests {
-    use crate::models::TransactionType;
-
-    #[test]
-    fn test_transaction_type_change_validation_logic() {
-        // Test the validation logic for transaction type changes
-        // This tests the core logic without requiring a full database setup
-
-        // Test case 1: Changing FROM Transfer to Income should be detected as invalid
-        let current_type = TransactionType::Transfer;
-        let new_type = TransactionType::Income;
-        let transaction_type_changed = new_type != current_type;
-
-        assert!(transaction_type_changed);
-        assert_eq!(current_type, TransactionType::Transfer);
-        assert_eq!(new_type, TransactionType::Income);
+/// Transaction already created under an unexpired `idempotency_key`, if any
+///
+/// Drops the user's expired keys first so they can be reused.
+async fn find_idempotent_transaction(
+    db: &Database,
+    user_id: &str,
+    idempotency_key: &str,
+    now: &str,
+) -> FiscusResult<Option<String>> {
+    let cleanup_query = "DELETE FROM idempotency_keys WHERE user_id = ?1 AND expires_at <= ?2";
+    DatabaseUtils::execute_non_query(
+        db,
+        cleanup_query,
+        vec![
+            Value::String(user_id.to_string()),
+            Value::String(now.to_string()),
+        ],
+    )
+    .await?;
 
-        // In the actual function, this would trigger the validation error
-        if transaction_type_changed {
-            if current_type == TransactionType::Transfer {
-                // This would return an error in the actual function
-                // Note: Transfer type changes should be prevented
-            }
-            if new_type == TransactionType::Transfer {
-                // This would return an error in the actual function
-                // Note: Transfer type changes should be prevented
-            }
-        }
+    let lookup_query = r#"
+        SELECT transaction_id FROM idempotency_keys
+        WHERE user_id = ?1 AND idempotency_key = ?2 AND expires_at > ?3
+    "#;
+    let lookup_params = vec![
+        Value::String(user_id.to_string()),
+        Value::String(idempotency_key.to_string()),
+        Value::String(now.to_string()),
+    ];
 
-        // Test case 2: Changing TO Transfer from Expense should be detected as invalid
-        let current_type_2 = TransactionType::Expense;
-        let new_type_2 = TransactionType::Transfer;
-        let transaction_type_changed_2 = new_type_2 != current_type_2;
+    let existing: Option<HashMap<String, Value>> =
+        DatabaseUtils::execute_query_single(db, lookup_query, lookup_params).await?;
 
-        assert!(transaction_type_changed_2);
-        assert_eq!(current_type_2, TransactionType::Expense);
-        assert_eq!(new_type_2, TransactionType::Transfer);
+    Ok(replayed_transaction_id(existing.as_ref()))
+}
 
-        // Test case 3: Changing from Income to Expense should be allowed
-        let current_type_3 = TransactionType::Income;
-        let new_type_3 = TransactionType::Expense;
-        let transaction_type_changed_3 = new_type_3 != current_type_3;
+/// Record `idempotency_key` for a new transaction until the configured TTL passes
+async fn record_idempotency_key(
+    db: &Database,
+    user_id: &str,
+    idempotency_key: &str,
+    transaction_id: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> FiscusResult<()> {
+    let record_query = r#"
+        INSERT INTO idempotency_keys (
+            id, user_id, idempotency_key, transaction_id, created_at, expires_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+    "#;
+    let record_params = vec![
+        Value::String(Uuid::new_v4().to_string()),
+        Value::String(user_id.to_string()),
+        Value::String(idempotency_key.to_string()),
+        Value::String(transaction_id.to_string()),
+        Value::String(now.to_rfc3339()),
+        Value::String(IdempotencyConfig::current().expires_at(now).to_rfc3339()),
+    ];
 
-        assert!(transaction_type_changed_3);
-        assert_ne!(current_type_3, TransactionType::Transfer);
-        assert_ne!(new_type_3, TransactionType::Transfer);
-        // This change should be allowed
-    }
+    DatabaseUtils::execute_non_query(db, record_query, record_params).await?;
+    Ok(())
 }
 
-/// Delete a transaction
+/// Refund part or all of an expense
+///
+/// Books an income transaction on the expense's account and category, linked
+/// to it through `refunds_transaction_id` so spending reports net the two.
+/// The refunds of one expense may not add up to more than its amount.
 #[tauri::command]
-pub async fn delete_transaction(
-    transaction_id: String,
+pub async fn create_refund(
+    original_transaction_id: String,
     user_id: String,
+    amount: Decimal,
+    date: String,
     db: State<'_, Database>,
-) -> Result<bool, FiscusError> {
-    // Validate input
-    Validator::validate_uuid(&transaction_id, "transaction_id")?;
-    Validator::validate_uuid(&user_id, "user_id")?;
+) -> Result<Transaction, FiscusError> {
+    authorize_command("create_refund").await?;
 
-    // Get current transaction to validate ownership and update balance
-    let current_transaction = get_transaction_by_id(transaction_id.clone(), db.clone()).await?;
+    Validator::validate_uuid(&original_transaction_id, "original_transaction_id")?;
+    let user_id = ValidatedUserId::new(&user_id)?;
+    let refund_date = Validator::validate_date(&date, "date")?;
 
-    if current_transaction.user_id != user_id {
-        return Err(FiscusError::Authorization(
-            "Transaction access denied".to_string(),
+    let original = load_refund_original(&db, &original_transaction_id, &user_id.as_str()).await?;
+    let request = refund_request(
+        &original,
+        user_id,
+        amount,
+        refund_date.and_time(chrono::NaiveTime::MIN).and_utc(),
+    );
+
+    // The amount is checked against earlier refunds inside the insert's transaction
+    create_transaction(request, db).await
+}
+
+/// Longest description prefix, in bytes, kept when labelling a refund; with
+/// the "Refund: " label it stays within the 255-byte description limit
+const REFUND_DESCRIPTION_MAX_LEN: usize = 247;
+
+/// Income request refunding `amount` of `original`
+fn refund_request(
+    original: &Transaction,
+    user_id: ValidatedUserId,
+    amount: Decimal,
+    transaction_date: chrono::DateTime<chrono::Utc>,
+) -> CreateTransactionRequest {
+    let mut end = original.description.len().min(REFUND_DESCRIPTION_MAX_LEN);
+    while !original.description.is_char_boundary(end) {
+        end -= 1;
+    }
+    let description = &original.description[..end];
+
+    CreateTransactionRequest {
+        user_id,
+        account_id: original.account_id.clone(),
+        category_id: original.category_id.clone(),
+        amount,
+        description: format!("Refund: {description}"),
+        notes: None,
+        transaction_date,
+        transaction_type: Some(TransactionType::Income),
+        reference_number: None,
+        payee: original.payee.clone(),
+        tags: None,
+        idempotency_key: None,
+        original_amount: None,
+        original_currency: None,
+        override_limit: false,
+        refunds_transaction_id: Some(original.id.clone()),
+    }
+}
+
+/// Load the expense a refund points at, which must belong to `user_id`
+async fn load_refund_original(
+    db: &Database,
+    original_transaction_id: &str,
+    user_id: &str,
+) -> FiscusResult<Transaction> {
+    let owner_query = "SELECT id FROM transactions WHERE id = ?1 AND user_id = ?2";
+    let owned: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+        db,
+        owner_query,
+        vec![
+            Value::String(original_transaction_id.to_string()),
+            Value::String(user_id.to_string()),
+        ],
+    )
+    .await?;
+    if owned.is_none() {
+        return Err(FiscusError::NotFound(
+            "Refunded transaction not found".to_string(),
         ));
     }
 
-    // Use transaction for atomicity
-    with_transaction!(&*db, async {
-        // Delete the transaction
-        let delete_query = "DELETE FROM transactions WHERE id = ?1";
-        let params = vec![Value::String(transaction_id)];
+    get_transaction_by_id_encrypted(original_transaction_id.to_string(), user_id, db).await
+}
 
-        let affected_rows = DatabaseUtils::execute_non_query(&db, delete_query, params).await?;
+/// Check that refunding `amount` keeps the refunds of an expense within its amount
+async fn validate_refund(
+    db: &Database,
+    user_id: &str,
+    original_transaction_id: &str,
+    amount: Decimal,
+) -> FiscusResult<()> {
+    let original = load_refund_original(db, original_transaction_id, user_id).await?;
 
-        if affected_rows == 0 {
-            return Err(FiscusError::NotFound("Transaction not found".to_string()));
-        }
+    let refunds_query = r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               original_amount, original_currency, refunds_transaction_id,
+               created_at, updated_at
+        FROM transactions
+        WHERE user_id = ?1 AND refunds_transaction_id = ?2
+    "#;
+    let refunds: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        refunds_query,
+        vec![
+            Value::String(user_id.to_string()),
+            Value::String(original_transaction_id.to_string()),
+        ],
+        user_id,
+        "transactions",
+    )
+    .await?;
 
-        // Update account balance by reversing the transaction effect
-        if current_transaction.transaction_type != TransactionType::Transfer {
-            let current_balance =
-                DatabaseUtils::get_account_balance(&db, &current_transaction.account_id).await?;
+    check_refund_amount(&original, &refunds, amount)
+}
 
-            let new_balance = match current_transaction.transaction_type {
-                TransactionType::Income => current_balance - current_transaction.amount,
-                TransactionType::Expense => current_balance + current_transaction.amount,
-                TransactionType::Transfer => current_balance,
-            };
+/// Reject a refund of `amount` that is not against a live expense or that,
+/// with the existing `refunds`, would exceed the expense's amount
+fn check_refund_amount(
+    original: &Transaction,
+    refunds: &[Transaction],
+    amount: Decimal,
+) -> FiscusResult<()> {
+    if original.transaction_type != TransactionType::Expense {
+        return Err(FiscusError::field_validation(
+            "refunds_transaction_id",
+            "not_refundable",
+            "Only expenses can be refunded",
+        ));
+    }
+    if matches!(
+        original.status,
+        TransactionStatus::Cancelled | TransactionStatus::Voided
+    ) {
+        return Err(FiscusError::field_validation(
+            "refunds_transaction_id",
+            "not_refundable",
+            "A cancelled or voided transaction cannot be refunded",
+        ));
+    }
 
-            DatabaseUtils::update_account_balance(
-                &db,
-                &current_transaction.account_id,
-                new_balance,
+    let refunded: Decimal = refunds
+        .iter()
+        .filter(|refund| {
+            !matches!(
+                refund.status,
+                TransactionStatus::Cancelled | TransactionStatus::Voided
             )
-            .await?;
-        }
-
-        Ok::<(), FiscusError>(())
-    })?;
+        })
+        .map(|refund| refund.amount.abs())
+        .sum();
+    let remaining = original.amount.abs() - refunded;
+    if amount > remaining {
+        return Err(FiscusError::field_validation(
+            "amount",
+            "exceeds_original",
+            format!("Refund of {amount} exceeds the {remaining} left to refund on the original transaction"),
+        ));
+    }
 
-    Ok(true)
+    Ok(())
 }
 
-/// Create a transfer between accounts
+/// Create several transactions for one user in a single database transaction
+///
+/// Every entry is validated before anything is written; the first invalid
+/// entry fails the whole batch with an error naming its index. Each account's
+/// balance is adjusted once, by the net of its entries. Idempotency keys are
+/// not supported in batches.
 #[tauri::command]
-pub async fn create_transfer(
-    request: CreateTransferRequest,
+pub async fn create_transactions_batch(
+    user_id: String,
+    mut transactions: Vec<CreateTransactionRequest>,
     db: State<'_, Database>,
-) -> Result<Transfer, FiscusError> {
-    // Validate input (user_id already validated by ValidatedUserId)
-    Validator::validate_uuid(&request.from_account_id, "from_account_id")?;
-    Validator::validate_uuid(&request.to_account_id, "to_account_id")?;
-    Validator::validate_amount(request.amount, false)?; // Transfers must be positive
-    Validator::validate_string(&request.description, "description", 1, 255)?;
+) -> Result<Vec<Transaction>, FiscusError> {
+    authorize_command("create_transactions_batch").await?;
 
-    let transfer_date = Validator::validate_datetime(&request.transfer_date)?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+    BulkConfig::current().validate_batch_size(transactions.len())?;
+    normalize_batch_amounts(&mut transactions, *AmountConvention::current())?;
+    normalize_batch_tags(&mut transactions, *TagConfig::current())?;
+    let transaction_dates = validate_transaction_batch(&user_id, &transactions)?;
 
-    if request.from_account_id == request.to_account_id {
-        return Err(FiscusError::InvalidInput(
-            "Cannot transfer to the same account".to_string(),
-        ));
-    }
+    let prepared = prepare_batch(&db, &user_id, &transactions, transaction_dates).await?;
 
-    // Validate account ownership
-    DatabaseUtils::validate_account_ownership(
-        &db,
-        &request.from_account_id,
-        &request.user_id.as_str(),
+    insert_batch(&db, &user_id, &prepared).await
+}
+
+/// Import transactions, skipping rows that were already imported
+///
+/// Works like `create_transactions_batch`, except that rows matching an
+/// existing transaction of the user by content hash (account, date, amount and
+/// description, ignoring case and spacing) are skipped, so overlapping bank
+/// files can be imported repeatedly. Identical rows within one import are all
+/// kept, less as many as already exist.
+#[tauri::command]
+pub async fn import_transactions(
+    user_id: String,
+    mut transactions: Vec<CreateTransactionRequest>,
+    db: State<'_, Database>,
+) -> Result<TransactionImportResponse, FiscusError> {
+    authorize_command("import_transactions").await?;
+
+    Validator::validate_uuid(&user_id, "user_id")?;
+    BulkConfig::current().validate_batch_size(transactions.len())?;
+    normalize_batch_amounts(&mut transactions, *AmountConvention::current())?;
+    normalize_batch_tags(&mut transactions, *TagConfig::current())?;
+    let transaction_dates = validate_transaction_batch(&user_id, &transactions)?;
+
+    let prepared = prepare_batch(&db, &user_id, &transactions, transaction_dates).await?;
+    let existing = existing_content_hashes(
+        &db,
+        &user_id,
+        prepared.iter().map(|entry| entry.content_hash.as_str()),
     )
     .await?;
-    DatabaseUtils::validate_account_ownership(
-        &db,
-        &request.to_account_id,
+
+    let (new_entries, skipped): (Vec<_>, Vec<_>) = prepared
+        .into_iter()
+        .partition(already_imported_filter(existing));
+    let skipped_duplicates: Vec<usize> = skipped.iter().map(|entry| entry.index).collect();
+
+    let imported = if new_entries.is_empty() {
+        Vec::new()
+    } else {
+        insert_batch(&db, &user_id, &new_entries).await?
+    };
+
+    Ok(TransactionImportResponse {
+        imported,
+        skipped_duplicates,
+    })
+}
+
+/// Import transactions from a JSON export, such as a backup from another install
+///
+/// `json` is the array of transactions a JSON export produces. IDs differ
+/// between installs, so every account and category an exported transaction
+/// references must be mapped to one of the user's own through
+/// `account_id_map` and `category_id_map`. Transfer legs and cancelled or
+/// voided transactions cannot be rebuilt from an export. Such rows, and rows
+/// with unmapped references, fail the import unless `skip_unmappable` is
+/// set, in which case their positions are reported as skipped. Amounts are
+/// imported as posted, without spending limit checks, and refund links are
+/// dropped. The rest are inserted in one database transaction like
+/// `create_transactions_batch`, which also updates the account balances.
+#[tauri::command]
+pub async fn import_transactions_json(
+    user_id: String,
+    account_id_map: HashMap<String, String>,
+    category_id_map: Option<HashMap<String, String>>,
+    json: String,
+    skip_unmappable: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<TransactionJsonImportResponse, FiscusError> {
+    authorize_command("import_transactions_json").await?;
+
+    let validated_user_id = ValidatedUserId::new(&user_id)?;
+    let exported: Vec<Transaction> = serde_json::from_str(&json)?;
+    BulkConfig::current().validate_batch_size(exported.len())?;
+
+    let category_id_map = category_id_map.unwrap_or_default();
+    let mut transactions = Vec::with_capacity(exported.len());
+    let mut skipped = Vec::new();
+    for (index, transaction) in exported.into_iter().enumerate() {
+        match exported_transaction_request(
+            transaction,
+            &validated_user_id,
+            &account_id_map,
+            &category_id_map,
+        ) {
+            Ok(request) => transactions.push(request),
+            Err(_) if skip_unmappable.unwrap_or(false) => skipped.push(index),
+            Err(e) => return Err(e.for_item("transactions", index)),
+        }
+    }
+
+    if transactions.is_empty() {
+        return Ok(TransactionJsonImportResponse {
+            imported: Vec::new(),
+            skipped,
+        });
+    }
+
+    normalize_batch_tags(&mut transactions, *TagConfig::current())?;
+    let transaction_dates = validate_transaction_batch(&user_id, &transactions)?;
+    let prepared = prepare_batch(&db, &user_id, &transactions, transaction_dates).await?;
+    let imported = insert_batch(&db, &user_id, &prepared).await?;
+
+    Ok(TransactionJsonImportResponse { imported, skipped })
+}
+
+/// Rebuild an exported transaction as a new transaction of `user_id`
+///
+/// Fails for a transaction that cannot be imported: a transfer leg, a
+/// cancelled or voided transaction, or one referencing an unmapped account or category.
+fn exported_transaction_request(
+    transaction: Transaction,
+    user_id: &ValidatedUserId,
+    account_id_map: &HashMap<String, String>,
+    category_id_map: &HashMap<String, String>,
+) -> FiscusResult<CreateTransactionRequest> {
+    if transaction.transaction_type == TransactionType::Transfer {
+        return Err(FiscusError::field_validation(
+            "transaction_type",
+            "unsupported",
+            "Transfers cannot be imported; recreate them with create_transfer",
+        ));
+    }
+    if matches!(
+        transaction.status,
+        TransactionStatus::Cancelled | TransactionStatus::Voided
+    ) {
+        return Err(FiscusError::field_validation(
+            "status",
+            "unsupported",
+            "Cancelled and voided transactions cannot be imported",
+        ));
+    }
+    Validator::validate_amount(transaction.amount, false)?;
+
+    let account_id = account_id_map
+        .get(&transaction.account_id)
+        .cloned()
+        .ok_or_else(|| {
+            FiscusError::field_validation(
+                "account_id",
+                "unmapped",
+                format!("No account is mapped to {}", transaction.account_id),
+            )
+        })?;
+    let category_id = transaction
+        .category_id
+        .map(|category_id| {
+            category_id_map.get(&category_id).cloned().ok_or_else(|| {
+                FiscusError::field_validation(
+                    "category_id",
+                    "unmapped",
+                    format!("No category is mapped to {category_id}"),
+                )
+            })
+        })
+        .transpose()?;
+
+    Ok(CreateTransactionRequest {
+        user_id: user_id.clone(),
+        account_id,
+        category_id,
+        amount: transaction.amount,
+        description: transaction.description,
+        notes: transaction.notes,
+        transaction_date: transaction.transaction_date,
+        transaction_type: Some(transaction.transaction_type),
+        reference_number: transaction.reference_number,
+        payee: transaction.payee,
+        tags: transaction.tags,
+        idempotency_key: None,
+        original_amount: None,
+        original_currency: None,
+        override_limit: true,
+        refunds_transaction_id: None,
+    })
+}
+
+/// Predicate for `partition` that is true for entries not yet imported
+///
+/// Each existing row with a hash absorbs one incoming entry with that hash.
+fn already_imported_filter(
+    mut existing: HashMap<String, usize>,
+) -> impl FnMut(&BatchEntry<'_>) -> bool {
+    move |entry| match existing.get_mut(&entry.content_hash) {
+        Some(count) if *count > 0 => {
+            *count -= 1;
+            false
+        }
+        _ => true,
+    }
+}
+
+/// Number of the user's stored transactions carrying each of `content_hashes`
+async fn existing_content_hashes<'a>(
+    db: &Database,
+    user_id: &str,
+    content_hashes: impl Iterator<Item = &'a str>,
+) -> FiscusResult<HashMap<String, usize>> {
+    let mut params = vec![Value::String(user_id.to_string())];
+    let mut seen = std::collections::HashSet::new();
+    for content_hash in content_hashes {
+        if seen.insert(content_hash) {
+            params.push(Value::String(content_hash.to_string()));
+        }
+    }
+
+    let placeholders: Vec<String> = (2..=params.len()).map(|i| format!("?{i}")).collect();
+    let query = format!(
+        "SELECT content_hash FROM transactions WHERE user_id = ?1 AND content_hash IN ({})",
+        placeholders.join(", ")
+    );
+    let rows: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(db, &query, params).await?;
+
+    let mut counts = HashMap::new();
+    for content_hash in rows
+        .iter()
+        .filter_map(|row| row.get("content_hash").and_then(|v| v.as_str()))
+    {
+        *counts.entry(content_hash.to_string()).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Check ownership and compute the posted amount and content hash of each validated entry
+async fn prepare_batch<'a>(
+    db: &Database,
+    user_id: &str,
+    transactions: &'a [CreateTransactionRequest],
+    transaction_dates: Vec<chrono::DateTime<chrono::Utc>>,
+) -> FiscusResult<Vec<BatchEntry<'a>>> {
+    let mut prepared = Vec::with_capacity(transactions.len());
+    for (index, (request, transaction_date)) in
+        transactions.iter().zip(transaction_dates).enumerate()
+    {
+        let amount = batch_entry_amount(db, user_id, request, transaction_date)
+            .await
+            .map_err(|e| e.for_item("transactions", index))?;
+        let content_hash = stored_content_hash(request, amount, transaction_date).await?;
+
+        prepared.push(BatchEntry {
+            index,
+            transaction_id: Uuid::new_v4().to_string(),
+            request,
+            transaction_date,
+            amount,
+            content_hash,
+        });
+    }
+
+    Ok(prepared)
+}
+
+/// Insert prepared entries in one database transaction and return the created rows
+async fn insert_batch(
+    db: &State<'_, Database>,
+    user_id: &str,
+    prepared: &[BatchEntry<'_>],
+) -> FiscusResult<Vec<Transaction>> {
+    let now = chrono::Utc::now().to_rfc3339();
+    with_transaction!(&**db, async {
+        for entry in prepared {
+            let request = entry.request;
+            if request.transaction_type() == TransactionType::Expense {
+                enforce_spending_limits(
+                    db,
+                    user_id,
+                    &request.account_id,
+                    request.category_id.as_deref(),
+                    entry.amount,
+                    entry.transaction_date.date_naive(),
+                    request.override_limit,
+                )
+                .await
+                .map_err(|e| e.for_item("transactions", entry.index))?;
+            }
+
+            insert_transaction_row(
+                db,
+                request,
+                &entry.transaction_id,
+                entry.amount,
+                entry.transaction_date,
+                &entry.content_hash,
+                &now,
+            )
+            .await?;
+        }
+
+        for (account_id, delta) in net_balance_changes(prepared) {
+            DatabaseUtils::adjust_account_balance(db, &account_id, delta).await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    for entry in prepared {
+        events::publish(transaction_created_events(
+            user_id,
+            &entry.transaction_id,
+            &entry.request.account_id,
+            &entry.request.transaction_type(),
+        ));
+    }
+
+    let mut created = Vec::with_capacity(prepared.len());
+    for entry in prepared {
+        created.push(get_transaction_by_id(entry.transaction_id.clone(), db.clone()).await?);
+    }
+    Ok(created)
+}
+
+/// Stored content hash of a new transaction; see `transaction_content_hash`
+async fn stored_content_hash(
+    request: &CreateTransactionRequest,
+    amount: Decimal,
+    transaction_date: chrono::DateTime<chrono::Utc>,
+) -> FiscusResult<String> {
+    let content_hash = transaction_content_hash(
+        &request.account_id,
+        transaction_date.date_naive(),
+        amount,
+        &request.description,
+    );
+    EncryptedDatabaseUtils::keyed_content_hash(&content_hash, &request.user_id.as_str()).await
+}
+
+/// A validated batch entry with its position, assigned ID, posted amount and content hash
+struct BatchEntry<'a> {
+    index: usize,
+    transaction_id: String,
+    request: &'a CreateTransactionRequest,
+    transaction_date: chrono::DateTime<chrono::Utc>,
+    amount: Decimal,
+    content_hash: String,
+}
+
+/// Canonicalize the amount of every batch entry; see `CreateTransactionRequest::normalize_amount`
+fn normalize_batch_amounts(
+    transactions: &mut [CreateTransactionRequest],
+    convention: AmountConvention,
+) -> FiscusResult<()> {
+    for (index, request) in transactions.iter_mut().enumerate() {
+        request
+            .normalize_amount(convention)
+            .map_err(|e| e.for_item("transactions", index))?;
+    }
+    Ok(())
+}
+
+/// Normalize the tags of every batch entry; see `TagConfig::normalize_tags`
+fn normalize_batch_tags(
+    transactions: &mut [CreateTransactionRequest],
+    config: TagConfig,
+) -> FiscusResult<()> {
+    for (index, request) in transactions.iter_mut().enumerate() {
+        if let Some(tags) = &request.tags {
+            let tags = config
+                .normalize_tags(tags)
+                .map_err(|e| e.for_item("transactions", index))?;
+            request.tags = Some(tags);
+        }
+    }
+    Ok(())
+}
+
+/// Validate every entry of a batch for `user_id`, returning their parsed dates
+///
+/// Errors name the index of the first invalid entry.
+fn validate_transaction_batch(
+    user_id: &str,
+    transactions: &[CreateTransactionRequest],
+) -> FiscusResult<Vec<chrono::DateTime<chrono::Utc>>> {
+    transactions
+        .iter()
+        .enumerate()
+        .map(|(index, request)| {
+            validate_batch_entry(user_id, request).map_err(|e| e.for_item("transactions", index))
+        })
+        .collect()
+}
+
+fn validate_batch_entry(
+    user_id: &str,
+    request: &CreateTransactionRequest,
+) -> FiscusResult<chrono::DateTime<chrono::Utc>> {
+    if request.user_id.as_str() != user_id {
+        return Err(FiscusError::field_validation(
+            "user_id",
+            "mismatch",
+            "Every transaction in a batch must belong to the batch user",
+        ));
+    }
+
+    if request.idempotency_key.is_some() {
+        return Err(FiscusError::field_validation(
+            "idempotency_key",
+            "unsupported",
+            "Idempotency keys are not supported in batches",
+        ));
+    }
+
+    if request.refunds_transaction_id.is_some() {
+        return Err(FiscusError::field_validation(
+            "refunds_transaction_id",
+            "unsupported",
+            "Refunds are not supported in batches; use create_refund",
+        ));
+    }
+
+    validate_create_transaction_request(request)
+}
+
+/// Check ownership of a batch entry's account and category and return its posted amount
+async fn batch_entry_amount(
+    db: &Database,
+    user_id: &str,
+    request: &CreateTransactionRequest,
+    transaction_date: chrono::DateTime<chrono::Utc>,
+) -> FiscusResult<Decimal> {
+    DatabaseUtils::validate_account_ownership(db, &request.account_id, user_id).await?;
+    ensure_account_open(
+        db,
+        &request.account_id,
+        user_id,
+        transaction_date.date_naive(),
+    )
+    .await?;
+    if let Some(ref category_id) = request.category_id {
+        DatabaseUtils::validate_category_ownership(db, category_id, user_id).await?;
+    }
+
+    posted_amount(db, request, transaction_date).await
+}
+
+/// Net balance change per account for a batch, skipping accounts that net to zero
+fn net_balance_changes(entries: &[BatchEntry<'_>]) -> Vec<(String, Decimal)> {
+    let mut net: std::collections::BTreeMap<String, Decimal> = std::collections::BTreeMap::new();
+    for entry in entries {
+        *net.entry(entry.request.account_id.clone()).or_default() +=
+            balance_delta(entry.amount, &entry.request.transaction_type());
+    }
+
+    net.into_iter()
+        .filter(|(_, delta)| !delta.is_zero())
+        .collect()
+}
+
+/// Insert the row for a new transaction, encrypting its sensitive fields
+///
+/// The caller adjusts the account balance.
+async fn insert_transaction_row(
+    db: &Database,
+    request: &CreateTransactionRequest,
+    transaction_id: &str,
+    amount: Decimal,
+    transaction_date: chrono::DateTime<chrono::Utc>,
+    content_hash: &str,
+    now: &str,
+) -> FiscusResult<()> {
+    let insert_query = r#"
+        INSERT INTO transactions (
+            id, user_id, account_id, category_id, amount, description, notes,
+            transaction_date, transaction_type, status, reference_number, payee, tags,
+            created_at, updated_at, amount_minor, original_amount, original_currency,
+            payee_index, refunds_transaction_id, content_hash
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
+    "#;
+
+    let tags_json = request
+        .tags
+        .as_ref()
+        .map(|tags| serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string()));
+
+    // Use encrypted parameter mapping for sensitive fields
+    let params_with_mapping = vec![
+        ("id".to_string(), Value::String(transaction_id.to_string())),
+        (
+            "user_id".to_string(),
+            Value::String(request.user_id.to_string()),
+        ),
+        (
+            "account_id".to_string(),
+            Value::String(request.account_id.clone()),
+        ),
+        (
+            "category_id".to_string(),
+            request
+                .category_id
+                .as_ref()
+                .map(|id| Value::String(id.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        ("amount".to_string(), Value::String(amount.to_string())),
+        (
+            "description".to_string(),
+            Value::String(request.description.clone()),
+        ),
+        (
+            "notes".to_string(),
+            request
+                .notes
+                .as_ref()
+                .map(|n| Value::String(n.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "transaction_date".to_string(),
+            Value::String(transaction_date.to_rfc3339()),
+        ),
+        (
+            "transaction_type".to_string(),
+            Value::String(request.transaction_type().to_string()),
+        ),
+        (
+            "status".to_string(),
+            Value::String(TransactionStatus::Completed.to_string()),
+        ),
+        (
+            "reference_number".to_string(),
+            request
+                .reference_number
+                .as_ref()
+                .map(|r| Value::String(r.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "payee".to_string(),
+            request
+                .payee
+                .as_ref()
+                .map(|p| Value::String(p.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "tags".to_string(),
+            tags_json
+                .as_ref()
+                .map(|t| Value::String(t.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        ("created_at".to_string(), Value::String(now.to_string())),
+        ("updated_at".to_string(), Value::String(now.to_string())),
+        (
+            "amount_minor".to_string(),
+            amount_minor_value(amount, store_amount_minor_units())?,
+        ),
+        (
+            "original_amount".to_string(),
+            request
+                .original_amount
+                .map(|a| Value::String(a.to_string()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "original_currency".to_string(),
+            request
+                .original_currency
+                .as_ref()
+                .map(|c| Value::String(c.to_string()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "payee_index".to_string(),
+            payee_index_value(request.payee.as_deref(), &request.user_id.as_str()).await?,
+        ),
+        (
+            "refunds_transaction_id".to_string(),
+            request
+                .refunds_transaction_id
+                .as_ref()
+                .map(|id| Value::String(id.clone()))
+                .unwrap_or(Value::Null),
+        ),
+        (
+            "content_hash".to_string(),
+            Value::String(content_hash.to_string()),
+        ),
+    ];
+
+    let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+        params_with_mapping,
         &request.user_id.as_str(),
+        "transactions",
     )
     .await?;
 
-    let transfer_id = Uuid::new_v4().to_string();
-    let from_transaction_id = Uuid::new_v4().to_string();
-    let to_transaction_id = Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+    DatabaseUtils::execute_non_query(db, insert_query, encrypted_params).await?;
 
-    // Use transaction for atomicity
-    with_transaction!(&*db, async {
-        // Create the transfer record
-        let transfer_query = r#"
-            INSERT INTO transfers (
-                id, user_id, from_account_id, to_account_id, amount, description,
-                transfer_date, status, from_transaction_id, to_transaction_id,
-                created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
-        "#;
+    Ok(())
+}
 
-        // Use encrypted parameter mapping for transfer record
-        let transfer_params_with_mapping = vec![
-            ("id".to_string(), Value::String(transfer_id.clone())),
-            (
-                "user_id".to_string(),
-                Value::String(request.user_id.to_string()),
-            ),
-            (
-                "from_account_id".to_string(),
-                Value::String(request.from_account_id.clone()),
-            ),
-            (
-                "to_account_id".to_string(),
-                Value::String(request.to_account_id.clone()),
-            ),
-            (
-                "amount".to_string(),
-                Value::String(request.amount.to_string()),
-            ),
-            (
-                "description".to_string(),
-                Value::String(request.description.clone()),
-            ),
-            (
-                "transfer_date".to_string(),
-                Value::String(transfer_date.to_rfc3339()),
-            ),
-            (
-                "status".to_string(),
-                Value::String(TransactionStatus::Completed.to_string()),
-            ),
-            (
-                "from_transaction_id".to_string(),
-                Value::String(from_transaction_id.clone()),
-            ),
-            (
-                "to_transaction_id".to_string(),
-                Value::String(to_transaction_id.clone()),
-            ),
-            ("created_at".to_string(), Value::String(now.clone())),
-            ("updated_at".to_string(), Value::String(now.clone())),
-        ];
+/// Get transactions with filtering and pagination
+///
+/// Pages by `limit`/`offset`, or by keyset when `cursor` is set (see
+/// `get_transactions_by_cursor`). Queries that could load more rows than the
+/// soft cap (`FISCUS_QUERY_SOFT_CAP`) are rejected or truncated before
+/// anything is decrypted.
+#[tauri::command]
+pub async fn get_transactions(
+    filters: TransactionFilters,
+    db: State<'_, Database>,
+) -> Result<Vec<HashMap<String, Value>>, FiscusError> {
+    authorize_command("get_transactions").await?;
+
+    if let Some(ref fields) = filters.fields {
+        SecurityValidator::validate_select_fields(
+            fields,
+            SecurityValidator::TRANSACTION_SELECT_FIELDS,
+        )?;
+    }
 
-        let encrypted_transfer_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
-            transfer_params_with_mapping,
-            &request.user_id.as_str(),
-            "transfers",
-        )
-        .await?;
+    let fields = filters.fields.clone();
+    let keyset = filters.cursor.is_some();
+    // A plain list has nowhere to carry the truncation flag; the paginated
+    // command reports it
+    let (transactions, _) =
+        run_transaction_query(filters, &db, keyset, Some(QuerySoftCap::current())).await?;
 
-        DatabaseUtils::execute_non_query(&db, transfer_query, encrypted_transfer_params).await?;
+    project_transactions(transactions, fields.as_deref())
+}
 
-        // Create outgoing transaction (expense)
-        let from_transaction_query = r#"
-            INSERT INTO transactions (
-                id, user_id, account_id, amount, description, transaction_date,
-                transaction_type, status, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-        "#;
+/// Transaction fields that are stored encrypted, with the placeholder selected
+/// in their place when a query leaves them out
+const ENCRYPTED_TRANSACTION_COLUMNS: &[(&str, &str)] = &[
+    ("amount", "'0'"),
+    ("description", "''"),
+    ("notes", "NULL"),
+    ("payee", "NULL"),
+    ("original_amount", "NULL"),
+];
+
+/// Column list for a transaction query restricted to `fields`
+///
+/// Unencrypted columns are always selected since they cost nothing to read
+/// and keep rows deserializable. Encrypted columns outside `fields` are
+/// replaced by placeholders, so they are never decrypted.
+fn transaction_select_columns(fields: Option<&[String]>) -> String {
+    let columns = [
+        "id",
+        "user_id",
+        "account_id",
+        "category_id",
+        "amount",
+        "description",
+        "notes",
+        "transaction_date",
+        "transaction_type",
+        "status",
+        "reference_number",
+        "payee",
+        "tags",
+        "original_amount",
+        "original_currency",
+        "refunds_transaction_id",
+        "created_at",
+        "updated_at",
+    ];
 
-        // Use encrypted parameter mapping for outgoing transaction
-        let from_params_with_mapping = vec![
-            ("id".to_string(), Value::String(from_transaction_id)),
-            (
-                "user_id".to_string(),
-                Value::String(request.user_id.to_string()),
-            ),
-            (
-                "account_id".to_string(),
-                Value::String(request.from_account_id.clone()),
-            ),
-            (
-                "amount".to_string(),
-                Value::String((-request.amount).to_string()),
-            ), // Negative for outgoing
-            (
-                "description".to_string(),
-                Value::String(format!("Transfer to account: {}", request.description)),
-            ),
-            (
-                "transaction_date".to_string(),
-                Value::String(transfer_date.to_rfc3339()),
-            ),
-            (
-                "transaction_type".to_string(),
-                Value::String(TransactionType::Transfer.to_string()),
-            ),
-            (
-                "status".to_string(),
-                Value::String(TransactionStatus::Completed.to_string()),
-            ),
-            ("created_at".to_string(), Value::String(now.clone())),
-            ("updated_at".to_string(), Value::String(now.clone())),
-        ];
+    columns
+        .iter()
+        .map(|column| {
+            let placeholder = ENCRYPTED_TRANSACTION_COLUMNS
+                .iter()
+                .find(|(name, _)| name == column)
+                .map(|(_, placeholder)| *placeholder);
+
+            match (fields, placeholder) {
+                (Some(fields), Some(placeholder)) if !fields.iter().any(|f| f == column) => {
+                    format!("{placeholder} AS {column}")
+                }
+                _ => column.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-        let encrypted_from_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
-            from_params_with_mapping,
-            &request.user_id.as_str(),
-            "transactions",
+/// Serialize transactions, keeping only `fields` and `id` when fields are given
+fn project_transactions(
+    transactions: Vec<Transaction>,
+    fields: Option<&[String]>,
+) -> FiscusResult<Vec<HashMap<String, Value>>> {
+    transactions
+        .into_iter()
+        .map(|transaction| {
+            let mut row: HashMap<String, Value> = serde_json::from_value(
+                serde_json::to_value(transaction)
+                    .map_err(|e| FiscusError::Internal(format!("Serialization failed: {e}")))?,
+            )
+            .map_err(|e| FiscusError::Internal(format!("Serialization failed: {e}")))?;
+
+            if let Some(fields) = fields {
+                row.retain(|key, _| key == "id" || fields.iter().any(|field| field == key));
+            }
+            Ok(row)
+        })
+        .collect()
+}
+
+/// Get one page of transactions, newest first, using keyset pagination
+///
+/// Pass the returned `next_cursor` back as `filters.cursor` to fetch the
+/// following page. Unlike offset paging, rows inserted between calls never
+/// shift later pages, so nothing is skipped or repeated.
+#[tauri::command]
+pub async fn get_transactions_by_cursor(
+    mut filters: TransactionFilters,
+    db: State<'_, Database>,
+) -> Result<CursorPaginatedResponse<Transaction>, FiscusError> {
+    authorize_command("get_transactions_by_cursor").await?;
+
+    if filters.fields.is_some() {
+        return Err(FiscusError::InvalidInput(
+            "fields is only supported by get_transactions".to_string(),
+        ));
+    }
+
+    let limit = filters
+        .limit
+        .unwrap_or(DEFAULT_CURSOR_PAGE_SIZE)
+        .clamp(1, 1000);
+    filters.limit = Some(limit);
+
+    let transactions = query_transactions(filters, &db, true).await?;
+    let next_cursor = next_page_cursor(&transactions, limit);
+
+    Ok(CursorPaginatedResponse {
+        data: transactions,
+        next_cursor,
+    })
+}
+
+/// Load transactions matching `filters`, ordered newest first by keyset when `keyset` is set
+pub(crate) async fn query_transactions(
+    filters: TransactionFilters,
+    db: &Database,
+    keyset: bool,
+) -> Result<Vec<Transaction>, FiscusError> {
+    run_transaction_query(filters, db, keyset, None)
+        .await
+        .map(|(transactions, _)| transactions)
+}
+
+/// `query_transactions`, held to `soft_cap` when one is given
+///
+/// Also returns whether the result was truncated at the cap.
+async fn run_transaction_query(
+    filters: TransactionFilters,
+    db: &Database,
+    keyset: bool,
+    soft_cap: Option<&QuerySoftCap>,
+) -> Result<(Vec<Transaction>, bool), FiscusError> {
+    // Validate user (already validated by ValidatedUserId)
+    DatabaseUtils::validate_user_exists(db, &filters.user_id.as_str()).await?;
+
+    let cursor = if keyset {
+        validate_keyset_filters(&filters)?;
+        filters
+            .cursor
+            .as_deref()
+            .map(TransactionCursor::decode)
+            .transpose()?
+    } else {
+        None
+    };
+
+    // Build filter map
+    let mut filter_map = HashMap::new();
+    filter_map.insert("user_id".to_string(), filters.user_id.as_str().to_string());
+
+    if let Some(account_id) = filters.account_id {
+        Validator::validate_uuid(&account_id, "account_id")?;
+        filter_map.insert("account_id".to_string(), account_id);
+    }
+
+    if let Some(category_id) = filters.category_id {
+        Validator::validate_uuid(&category_id, "category_id")?;
+        filter_map.insert("category_id".to_string(), category_id);
+    }
+
+    if let Some(transaction_type) = filters.transaction_type {
+        filter_map.insert("transaction_type".to_string(), transaction_type.to_string());
+    }
+
+    if let Some(status) = filters.status {
+        filter_map.insert("status".to_string(), status.to_string());
+    }
+
+    if let Some(start_date) = filters.start_date {
+        Validator::validate_date(&start_date, "start_date")?;
+        filter_map.insert("start_date".to_string(), start_date);
+    }
+
+    if let Some(end_date) = filters.end_date {
+        Validator::validate_date(&end_date, "end_date")?;
+        filter_map.insert("end_date".to_string(), end_date);
+    }
+
+    // Decimal strings compare as text, so with minor units stored the bounds
+    // are applied to the integer column instead
+    let store_minor_units = store_amount_minor_units();
+
+    if let Some(min_amount) = filters.min_amount {
+        if store_minor_units {
+            let units = decimal_to_minor_units(min_amount)?;
+            filter_map.insert("min_amount_minor".to_string(), units.to_string());
+        } else {
+            filter_map.insert("min_amount".to_string(), min_amount.to_string());
+        }
+    }
+
+    if let Some(max_amount) = filters.max_amount {
+        if store_minor_units {
+            let units = decimal_to_minor_units(max_amount)?;
+            filter_map.insert("max_amount_minor".to_string(), units.to_string());
+        } else {
+            filter_map.insert("max_amount".to_string(), max_amount.to_string());
+        }
+    }
+
+    // Payees are encrypted, so exact matches go through their blind index
+    if let Some(payee) = filters.payee.as_deref().map(str::trim) {
+        if !payee.is_empty() {
+            let index = EncryptedDatabaseUtils::blind_index_value(
+                payee,
+                &filters.user_id.as_str(),
+                "transactions",
+                "payee",
+            )
+            .await?;
+            filter_map.insert("payee_index".to_string(), index);
+        }
+    }
+
+    // Validate filter fields
+    SecurityValidator::validate_transaction_filter_fields(&filter_map)?;
+
+    // A placeholder would shadow the column in ORDER BY, so the sort field is always read
+    let mut selected_fields = filters.fields.clone();
+    if let (Some(selected), Some(sort_by)) = (selected_fields.as_mut(), filters.sort_by.as_ref()) {
+        selected.push(sort_by.clone());
+    }
+    let base_query = format!(
+        "SELECT {} FROM transactions",
+        transaction_select_columns(selected_fields.as_deref())
+    );
+
+    // Add search functionality
+    let mut search_conditions = Vec::new();
+    if let Some(ref search) = filters.search {
+        if !search.trim().is_empty() {
+            search_conditions
+                .push("(description LIKE ? OR payee LIKE ? OR notes LIKE ?)".to_string());
+        }
+    }
+
+    let (where_clause, mut where_params) = DatabaseUtils::build_where_clause(
+        &filter_map,
+        &[
+            "user_id",
+            "account_id",
+            "category_id",
+            "transaction_type",
+            "status",
+            "start_date",
+            "end_date",
+            "min_amount",
+            "max_amount",
+            "min_amount_minor",
+            "max_amount_minor",
+            "payee_index",
+        ],
+        search_conditions,
+    )?;
+
+    // Add search parameters
+    if let Some(search) = filters.search {
+        if !search.trim().is_empty() {
+            let search_pattern = format!("%{}%", search.trim());
+            where_params.push(Value::String(search_pattern.clone()));
+            where_params.push(Value::String(search_pattern.clone()));
+            where_params.push(Value::String(search_pattern));
+        }
+    }
+
+    let order_clause = if keyset {
+        "ORDER BY transaction_date DESC, id DESC".to_string()
+    } else {
+        DatabaseUtils::build_order_clause(
+            filters.sort_by.as_deref(),
+            filters.sort_direction.as_deref(),
+            SecurityValidator::TRANSACTION_SORT_FIELDS,
+            "transaction_date",
+        )?
+    };
+
+    // Tag matching happens after decryption, so with a tag filter the whole
+    // result set is loaded and paginated in memory. This trades memory and
+    // decryption work for correct pages; prefer narrowing by date or account
+    // alongside a tag filter for large histories.
+    let tag_filter = match filters.tag_filter.as_deref().map(str::trim) {
+        Some(tag) if !tag.is_empty() => {
+            Validator::validate_string(tag, "tag_filter", 1, 50)?;
+            Some(tag.to_string())
+        }
+        _ => None,
+    };
+
+    let limit_clause = if tag_filter.is_some() {
+        String::new()
+    } else {
+        DatabaseUtils::build_limit_clause(filters.limit, filters.offset)
+    };
+
+    // With a tag filter the cursor is applied in memory after tag matching
+    let mut where_clause = where_clause;
+    if let (Some(cursor), None) = (&cursor, &tag_filter) {
+        let date_index = where_params.len() + 1;
+        where_clause.push_str(&format!(
+            " AND (transaction_date, id) < (?{date_index}, ?{})",
+            date_index + 1
+        ));
+        where_params.push(Value::String(cursor.transaction_date.clone()));
+        where_params.push(Value::String(cursor.id.clone()));
+    }
+
+    // Count before loading anything, so an oversized result is never decrypted
+    let mut limit_clause = limit_clause;
+    let mut truncated = false;
+    let bound = if tag_filter.is_some() {
+        None
+    } else {
+        page_bound(filters.limit, filters.offset)
+    };
+    if let Some(soft_cap) = soft_cap.filter(|cap| cap.applies_to(bound)) {
+        let count_row: Option<HashMap<String, Value>> = DatabaseUtils::execute_query_single(
+            db,
+            &format!("SELECT COUNT(*) AS total FROM transactions {where_clause}"),
+            where_params.clone(),
         )
         .await?;
+        let matching = count_row
+            .and_then(|row| row.get("total").and_then(|v| v.as_u64()))
+            .unwrap_or(0) as usize;
+
+        if let Some(max_rows) = soft_cap.check(matching)? {
+            limit_clause = match filters.offset {
+                Some(offset) if tag_filter.is_none() => {
+                    format!("LIMIT {max_rows} OFFSET {}", offset.max(0))
+                }
+                _ => format!("LIMIT {max_rows}"),
+            };
+            truncated = true;
+        }
+    }
+
+    let final_query = format!("{base_query} {where_clause} {order_clause} {limit_clause}");
+
+    // Use encrypted query to properly decrypt sensitive fields
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        &final_query,
+        where_params,
+        &filters.user_id.as_str(),
+        "transactions",
+    )
+    .await?;
+
+    let transactions = match tag_filter {
+        Some(tag) => {
+            let mut tagged = filter_transactions_by_tag(transactions, &tag);
+            if let Some(cursor) = &cursor {
+                tagged.retain(|transaction| cursor.precedes(transaction));
+            }
+            paginate_in_memory(tagged, filters.limit, filters.offset)
+        }
+        None => transactions,
+    };
+
+    Ok((transactions, truncated))
+}
+
+/// Default page size for cursor pagination
+const DEFAULT_CURSOR_PAGE_SIZE: i32 = 50;
+
+/// Reject filter combinations that cannot be expressed with keyset paging
+fn validate_keyset_filters(filters: &TransactionFilters) -> Result<(), FiscusError> {
+    if filters.offset.is_some() {
+        return Err(FiscusError::InvalidInput(
+            "offset cannot be combined with cursor pagination".to_string(),
+        ));
+    }
+
+    let other_field =
+        matches!(filters.sort_by.as_deref(), Some(field) if field != "transaction_date");
+    let ascending = matches!(
+        filters.sort_direction.as_deref(),
+        Some(direction) if !direction.eq_ignore_ascii_case("desc")
+    );
+
+    if other_field || ascending {
+        return Err(FiscusError::InvalidInput(
+            "cursor pagination only supports transaction_date descending".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Position after the last row of a cursor page: `(transaction_date, id)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TransactionCursor {
+    transaction_date: String,
+    id: String,
+}
+
+impl TransactionCursor {
+    /// Cursor pointing just past `transaction`
+    fn after(transaction: &Transaction) -> Self {
+        Self {
+            transaction_date: transaction.transaction_date.to_rfc3339(),
+            id: transaction.id.clone(),
+        }
+    }
+
+    /// Opaque URL-safe form handed to the client
+    fn encode(&self) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(format!("{}|{}", self.transaction_date, self.id))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, FiscusError> {
+        let invalid = || FiscusError::InvalidInput("Invalid pagination cursor".to_string());
+
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| invalid())?;
+        let decoded = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (transaction_date, id) = decoded.split_once('|').ok_or_else(invalid)?;
+
+        Validator::validate_datetime(transaction_date, "transaction_date")
+            .map_err(|_| invalid())?;
+        Validator::validate_uuid(id, "cursor").map_err(|_| invalid())?;
+
+        Ok(Self {
+            transaction_date: transaction_date.to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    /// Whether `transaction` sorts after the cursor, i.e. `(date, id) < cursor`
+    fn precedes(&self, transaction: &Transaction) -> bool {
+        (
+            transaction.transaction_date.to_rfc3339().as_str(),
+            transaction.id.as_str(),
+        ) < (self.transaction_date.as_str(), self.id.as_str())
+    }
+}
+
+/// Cursor for the page after `page`, or `None` when `page` was the last one
+pub(crate) fn next_page_cursor(page: &[Transaction], limit: i32) -> Option<String> {
+    if page.len() < limit as usize {
+        return None;
+    }
+    page.last()
+        .map(|transaction| TransactionCursor::after(transaction).encode())
+}
+
+/// Keep only transactions carrying `tag` (case-insensitive)
+fn filter_transactions_by_tag(transactions: Vec<Transaction>, tag: &str) -> Vec<Transaction> {
+    transactions
+        .into_iter()
+        .filter(|transaction| {
+            transaction
+                .tags
+                .as_ref()
+                .is_some_and(|tags| tags.iter().any(|t| t.trim().eq_ignore_ascii_case(tag)))
+        })
+        .collect()
+}
+
+/// Apply limit/offset in memory with the same bounds as `build_limit_clause`
+fn paginate_in_memory<T>(items: Vec<T>, limit: Option<i32>, offset: Option<i32>) -> Vec<T> {
+    let (limit, offset) = match (limit, offset) {
+        (Some(l), Some(o)) => (l.clamp(1, 1000), o.max(0)),
+        (Some(l), None) => (l.clamp(1, 1000), 0),
+        (None, Some(o)) => (100, o.max(0)),
+        (None, None) => return items,
+    };
+
+    items
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect()
+}
+
+/// Count how often each tag is used from raw `tags` JSON column values
+///
+/// Tags are trimmed and grouped case-insensitively, keeping the first spelling
+/// seen. Results are ordered by usage count, then alphabetically.
+fn count_tag_usage(rows: &[HashMap<String, Value>]) -> Vec<TagUsage> {
+    let mut usage: HashMap<String, TagUsage> = HashMap::new();
+
+    for row in rows {
+        let tags: Vec<String> = match row.get("tags") {
+            Some(Value::String(json)) => serde_json::from_str(json).unwrap_or_default(),
+            Some(Value::Array(values)) => values
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect(),
+            _ => continue,
+        };
+
+        // Count each tag once per transaction
+        let mut seen = std::collections::HashSet::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if tag.is_empty() || !seen.insert(tag.to_lowercase()) {
+                continue;
+            }
+            usage
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| TagUsage {
+                    tag: tag.to_string(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+
+    let mut tags: Vec<TagUsage> = usage.into_values().collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    tags
+}
+
+/// Get all distinct tags a user has applied, with usage counts
+#[tauri::command]
+pub async fn get_user_tags(
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<Vec<TagUsage>, FiscusError> {
+    authorize_command("get_user_tags").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let query = "SELECT tags FROM transactions WHERE user_id = ?1 AND tags IS NOT NULL";
+    let rows: Vec<HashMap<String, Value>> =
+        DatabaseUtils::execute_query(&db, query, vec![Value::String(user_id)]).await?;
+
+    Ok(count_tag_usage(&rows))
+}
+
+/// Largest look-back window accepted by `find_duplicate_transactions`
+const MAX_DUPLICATE_WINDOW_DAYS: i64 = 365;
+
+/// Lowercase a description and collapse runs of whitespace
+fn normalize_description(description: &str) -> String {
+    description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Cluster transactions that share an account, absolute amount, and
+/// normalized description, and fall within `window_days` of the previous
+/// transaction in the cluster
+fn find_duplicate_clusters(
+    transactions: &[Transaction],
+    window_days: i64,
+) -> Vec<DuplicateTransactionCluster> {
+    let mut groups: HashMap<(String, Decimal, String), Vec<&Transaction>> = HashMap::new();
+    for transaction in transactions {
+        if matches!(
+            transaction.status,
+            TransactionStatus::Voided | TransactionStatus::Cancelled
+        ) {
+            continue;
+        }
+
+        groups
+            .entry((
+                transaction.account_id.clone(),
+                transaction.amount.abs().normalize(),
+                normalize_description(&transaction.description),
+            ))
+            .or_default()
+            .push(transaction);
+    }
+
+    let window = chrono::Duration::days(window_days);
+    let mut clusters = Vec::new();
+
+    for ((account_id, amount, description), mut group) in groups {
+        if group.len() < 2 {
+            continue;
+        }
+        group.sort_by_key(|t| t.transaction_date);
+
+        let mut current: Vec<&Transaction> = Vec::new();
+        for transaction in group {
+            if let Some(previous) = current.last() {
+                if transaction.transaction_date - previous.transaction_date > window {
+                    if current.len() > 1 {
+                        clusters.push(DuplicateTransactionCluster {
+                            account_id: account_id.clone(),
+                            amount,
+                            description: description.clone(),
+                            transaction_ids: current.iter().map(|t| t.id.clone()).collect(),
+                        });
+                    }
+                    current.clear();
+                }
+            }
+            current.push(transaction);
+        }
+
+        if current.len() > 1 {
+            clusters.push(DuplicateTransactionCluster {
+                account_id,
+                amount,
+                description,
+                transaction_ids: current.iter().map(|t| t.id.clone()).collect(),
+            });
+        }
+    }
+
+    clusters.sort_by(|a, b| {
+        a.account_id
+            .cmp(&b.account_id)
+            .then_with(|| a.transaction_ids.cmp(&b.transaction_ids))
+    });
+    clusters
+}
+
+/// Find clusters of likely duplicate transactions without modifying any data
+#[tauri::command]
+pub async fn find_duplicate_transactions(
+    user_id: String,
+    window_days: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<DuplicateTransactionCluster>, FiscusError> {
+    authorize_command("find_duplicate_transactions").await?;
+
+    // Validate input
+    Validator::validate_uuid(&user_id, "user_id")?;
+    if !(0..=MAX_DUPLICATE_WINDOW_DAYS).contains(&window_days) {
+        return Err(FiscusError::field_validation(
+            "window_days",
+            "out_of_range",
+            format!("window_days must be between 0 and {MAX_DUPLICATE_WINDOW_DAYS}"),
+        ));
+    }
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let query = r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               original_amount, original_currency, refunds_transaction_id,
+               created_at, updated_at
+        FROM transactions
+        WHERE user_id = ?1
+    "#;
+
+    // Amounts and descriptions are encrypted, so grouping happens after decryption
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        query,
+        vec![Value::String(user_id.clone())],
+        &user_id,
+        "transactions",
+    )
+    .await?;
+
+    Ok(find_duplicate_clusters(&transactions, window_days))
+}
+
+/// Get transactions with pagination support
+#[tauri::command]
+pub async fn get_transactions_paginated(
+    filters: TransactionFilters,
+    db: State<'_, Database>,
+) -> Result<PaginatedResponse<Transaction>, FiscusError> {
+    authorize_command("get_transactions_paginated").await?;
+
+    if filters.fields.is_some() {
+        return Err(FiscusError::InvalidInput(
+            "fields is only supported by get_transactions".to_string(),
+        ));
+    }
+
+    // Validate user (already validated by ValidatedUserId)
+    DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
+
+    // Get total count first
+    let count_query = r#"
+        SELECT COUNT(*) as total
+        FROM transactions
+        WHERE user_id = ?1
+    "#;
+
+    let count_result: Option<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query_single(
+            &db,
+            count_query,
+            vec![Value::String(filters.user_id.as_str().to_string())],
+        )
+        .await?;
+
+    let total = count_result
+        .and_then(|row| row.get("total").and_then(|v| v.as_i64()))
+        .unwrap_or(0) as i32;
+
+    // Get transactions with filters
+    let page = filters.offset.unwrap_or(0) / filters.limit.unwrap_or(50) + 1;
+    let per_page = filters.limit.unwrap_or(50);
+    let (transactions, truncated) =
+        run_transaction_query(filters, &db, false, Some(QuerySoftCap::current())).await?;
+
+    let mut response = PaginatedResponse::new(transactions, total, page, per_page);
+    response.truncated = truncated;
+    Ok(response)
+}
+
+/// Get transaction statistics
+#[tauri::command]
+pub async fn get_transaction_stats(
+    filters: TransactionFilters,
+    db: State<'_, Database>,
+) -> Result<TransactionStatsResponse, FiscusError> {
+    authorize_command("get_transaction_stats").await?;
+
+    // Validate user (already validated by ValidatedUserId)
+    DatabaseUtils::validate_user_exists(&db, &filters.user_id.as_str()).await?;
+
+    let stats_query = r#"
+        SELECT
+            COUNT(*) as total_transactions,
+            COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END), 0) as total_income,
+            COALESCE(SUM(CASE WHEN transaction_type = 'expense' THEN amount ELSE 0 END), 0) as total_expenses,
+            COALESCE(AVG(amount), 0) as average_amount,
+            MAX(CASE WHEN transaction_type = 'expense' THEN amount END) as largest_expense,
+            MAX(CASE WHEN transaction_type = 'income' THEN amount END) as largest_income
+        FROM transactions
+        WHERE user_id = ?1
+    "#;
+
+    let stats_result: Option<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query_single(
+            &db,
+            stats_query,
+            vec![Value::String(filters.user_id.as_str().to_string())],
+        )
+        .await?;
+
+    let stats = stats_result
+        .ok_or_else(|| FiscusError::NotFound("No transaction statistics found".to_string()))?;
+
+    let total_income = parse_decimal_from_json(&stats, "total_income");
+    let total_expenses = parse_decimal_from_json(&stats, "total_expenses");
+
+    // Get transactions by type
+    let type_query = r#"
+        SELECT transaction_type, COUNT(*) as count
+        FROM transactions
+        WHERE user_id = ?1
+        GROUP BY transaction_type
+    "#;
+
+    let type_results: Vec<HashMap<String, serde_json::Value>> = DatabaseUtils::execute_query(
+        &db,
+        type_query,
+        vec![Value::String(filters.user_id.as_str().to_string())],
+    )
+    .await?;
+
+    let mut transactions_by_type = HashMap::new();
+    for row in type_results {
+        if let (Some(tx_type), Some(count)) = (
+            row.get("transaction_type").and_then(|v| v.as_str()),
+            row.get("count").and_then(|v| v.as_i64()),
+        ) {
+            transactions_by_type.insert(tx_type.to_string(), count as i32);
+        }
+    }
+
+    // Get transactions by status
+    let status_query = r#"
+        SELECT status, COUNT(*) as count
+        FROM transactions
+        WHERE user_id = ?1
+        GROUP BY status
+    "#;
+
+    let status_results: Vec<HashMap<String, serde_json::Value>> = DatabaseUtils::execute_query(
+        &db,
+        status_query,
+        vec![Value::String(filters.user_id.as_str().to_string())],
+    )
+    .await?;
+
+    let mut transactions_by_status = HashMap::new();
+    for row in status_results {
+        if let (Some(status), Some(count)) = (
+            row.get("status").and_then(|v| v.as_str()),
+            row.get("count").and_then(|v| v.as_i64()),
+        ) {
+            transactions_by_status.insert(status.to_string(), count as i32);
+        }
+    }
+
+    // Amounts are encrypted, so percentiles are computed after decryption
+    // rather than with SQL ordering
+    let expense_rows: Vec<HashMap<String, Value>> =
+        EncryptedDatabaseUtils::execute_encrypted_query(
+            &db,
+            "SELECT amount FROM transactions WHERE user_id = ?1 AND transaction_type = 'expense'",
+            vec![Value::String(filters.user_id.as_str().to_string())],
+            &filters.user_id.as_str(),
+            "transactions",
+        )
+        .await?;
+
+    let mut expense_amounts: Vec<Decimal> = expense_rows
+        .iter()
+        .map(|row| parse_decimal_from_json(row, "amount"))
+        .collect();
+    expense_amounts.sort();
+
+    Ok(TransactionStatsResponse {
+        total_transactions: stats
+            .get("total_transactions")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0) as i32,
+        total_income,
+        total_expenses,
+        net_income: total_income - total_expenses,
+        average_transaction_amount: parse_decimal_from_json(&stats, "average_amount"),
+        largest_expense: {
+            let value = parse_decimal_from_json(&stats, "largest_expense");
+            if value == rust_decimal::Decimal::ZERO {
+                None
+            } else {
+                Some(value)
+            }
+        },
+        largest_income: {
+            let value = parse_decimal_from_json(&stats, "largest_income");
+            if value == rust_decimal::Decimal::ZERO {
+                None
+            } else {
+                Some(value)
+            }
+        },
+        most_frequent_category: None, // TODO: Implement category analysis
+        median_expense: percentile(&expense_amounts, 50),
+        p90_expense: percentile(&expense_amounts, 90),
+        p95_expense: percentile(&expense_amounts, 95),
+        transactions_by_type,
+        transactions_by_status,
+    })
+}
+
+/// Percentile of sorted amounts, interpolating linearly between neighbouring ranks
+///
+/// With few amounts this is the best available estimate: a single amount is
+/// every percentile, and an empty set has none.
+fn percentile(sorted: &[Decimal], percentile: u32) -> Option<Decimal> {
+    let last = sorted.len().checked_sub(1)?;
+
+    let rank = Decimal::from(percentile.min(100)) / Decimal::ONE_HUNDRED * Decimal::from(last);
+    let lower = rank.floor().to_usize().unwrap_or(0).min(last);
+    let upper = (lower + 1).min(last);
+    let fraction = rank - rank.floor();
+
+    Some((sorted[lower] + (sorted[upper] - sorted[lower]) * fraction).round_dp(2))
+}
+
+/// Get a single transaction by ID (internal helper with user_id for encryption)
+async fn get_transaction_by_id_encrypted(
+    transaction_id: String,
+    user_id: &str,
+    db: &Database,
+) -> Result<Transaction, FiscusError> {
+    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+
+    let query = r#"
+        SELECT id, user_id, account_id, category_id, amount, description, notes,
+               transaction_date, transaction_type, status, reference_number, payee, tags,
+               original_amount, original_currency, refunds_transaction_id,
+               created_at, updated_at
+        FROM transactions
+        WHERE id = ?1
+    "#;
+
+    let transactions: Vec<Transaction> = EncryptedDatabaseUtils::execute_encrypted_query(
+        db,
+        query,
+        vec![Value::String(transaction_id.clone())],
+        user_id,
+        "transactions",
+    )
+    .await?;
+
+    transactions
+        .into_iter()
+        .next()
+        .ok_or_else(|| FiscusError::NotFound("Transaction not found".to_string()))
+}
+
+/// Get a single transaction by ID
+#[tauri::command]
+pub async fn get_transaction_by_id(
+    transaction_id: String,
+    db: State<'_, Database>,
+) -> Result<Transaction, FiscusError> {
+    authorize_command("get_transaction_by_id").await?;
+
+    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+
+    // First, get the user_id for this transaction (this field is not encrypted)
+    let user_query = "SELECT user_id FROM transactions WHERE id = ?1";
+    let user_result: Option<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query_single(
+            &db,
+            user_query,
+            vec![Value::String(transaction_id.clone())],
+        )
+        .await?;
+
+    let user_id = user_result
+        .and_then(|row| {
+            row.get("user_id")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+        })
+        .ok_or_else(|| FiscusError::NotFound("Transaction not found".to_string()))?;
+
+    // Now get the full transaction with decryption
+    get_transaction_by_id_encrypted(transaction_id, &user_id, &db).await
+}
+
+/// Update a transaction
+#[tauri::command]
+pub async fn update_transaction(
+    transaction_id: String,
+    user_id: String,
+    request: UpdateTransactionRequest,
+    db: State<'_, Database>,
+) -> Result<Transaction, FiscusError> {
+    authorize_command("update_transaction").await?;
+
+    // Validate input
+    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    // Get current transaction to validate ownership and calculate balance changes
+    let current_transaction = get_transaction_by_id(transaction_id.clone(), db.clone()).await?;
+
+    if current_transaction.user_id != user_id {
+        return Err(FiscusError::Authorization(
+            "Transaction access denied".to_string(),
+        ));
+    }
+
+    // Fail fast if the client edited an outdated copy
+    ensure_not_stale(current_transaction.updated_at, request.expected_updated_at)?;
+
+    // Build update query dynamically with encrypted parameter mapping
+    let mut update_fields = Vec::new();
+    let mut params_with_mapping = Vec::new();
+    let mut param_index = 1;
+
+    match &request.category_id {
+        Patch::Unset => {}
+        Patch::Null => update_fields.push("`category_id` = NULL".to_string()),
+        Patch::Set(category_id) => {
+            Validator::validate_uuid(category_id, "category_id")?;
+            DatabaseUtils::validate_category_ownership(&db, category_id, &user_id).await?;
+            update_fields.push(format!("`category_id` = ?{param_index}"));
+            params_with_mapping.push((
+                "category_id".to_string(),
+                Value::String(category_id.clone()),
+            ));
+            param_index += 1;
+        }
+    }
+
+    let mut amount_changed = false;
+    let mut new_amount = current_transaction.amount;
+    if let Some(amount) = request.amount {
+        Validator::validate_amount(amount, true)?;
+        update_fields.push(format!("`amount` = ?{param_index}"));
+        params_with_mapping.push(("amount".to_string(), Value::String(amount.to_string())));
+        param_index += 1;
+        update_fields.push(format!("`amount_minor` = ?{param_index}"));
+        params_with_mapping.push((
+            "amount_minor".to_string(),
+            amount_minor_value(amount, store_amount_minor_units())?,
+        ));
+        param_index += 1;
+        amount_changed = true;
+        new_amount = amount;
+    }
+
+    // Track transaction type changes for balance calculation
+    let mut transaction_type_changed = false;
+    let mut new_transaction_type = current_transaction.transaction_type.clone();
+    if let Some(transaction_type) = &request.transaction_type {
+        if *transaction_type != current_transaction.transaction_type {
+            transaction_type_changed = true;
+            new_transaction_type = transaction_type.clone();
+        }
+    }
+
+    // Prevent changing transaction type to or from Transfer
+    // Transfers involve two linked transactions and require special handling
+    if transaction_type_changed {
+        if current_transaction.transaction_type == TransactionType::Transfer {
+            return Err(FiscusError::InvalidInput(
+                "Cannot change transaction type from Transfer. Transfers involve linked transactions and must be handled separately.".to_string(),
+            ));
+        }
+        if new_transaction_type == TransactionType::Transfer {
+            return Err(FiscusError::InvalidInput(
+                "Cannot change transaction type to Transfer. Use the create_transfer function to create transfers between accounts.".to_string(),
+            ));
+        }
+    }
+
+    if let Some(description) = &request.description {
+        Validator::validate_string(description, "description", 1, 255)?;
+        update_fields.push(format!("`description` = ?{param_index}"));
+        params_with_mapping.push((
+            "description".to_string(),
+            Value::String(description.clone()),
+        ));
+        param_index += 1;
+    }
+
+    match &request.notes {
+        Patch::Unset => {}
+        Patch::Null => update_fields.push("`notes` = NULL".to_string()),
+        Patch::Set(notes) => {
+            update_fields.push(format!("`notes` = ?{param_index}"));
+            params_with_mapping.push(("notes".to_string(), Value::String(notes.clone())));
+            param_index += 1;
+        }
+    }
+
+    if let Some(transaction_date) = &request.transaction_date {
+        let parsed_date = Validator::validate_datetime(transaction_date, "transaction_date")?;
+        update_fields.push(format!("`transaction_date` = ?{param_index}"));
+        params_with_mapping.push((
+            "transaction_date".to_string(),
+            Value::String(parsed_date.to_rfc3339()),
+        ));
+        param_index += 1;
+    }
+
+    if let Some(transaction_type) = &request.transaction_type {
+        update_fields.push(format!("`transaction_type` = ?{param_index}"));
+        params_with_mapping.push((
+            "transaction_type".to_string(),
+            Value::String(transaction_type.to_string()),
+        ));
+        param_index += 1;
+    }
+
+    if let Some(status) = &request.status {
+        update_fields.push(format!("`status` = ?{param_index}"));
+        params_with_mapping.push(("status".to_string(), Value::String(status.to_string())));
+        param_index += 1;
+    }
+
+    if let Some(reference_number) = &request.reference_number {
+        update_fields.push(format!("`reference_number` = ?{param_index}"));
+        params_with_mapping.push((
+            "reference_number".to_string(),
+            Value::String(reference_number.clone()),
+        ));
+        param_index += 1;
+    }
+
+    if let Some(payee) = &request.payee {
+        update_fields.push(format!("`payee` = ?{param_index}"));
+        params_with_mapping.push(("payee".to_string(), Value::String(payee.clone())));
+        param_index += 1;
+        update_fields.push(format!("`payee_index` = ?{param_index}"));
+        params_with_mapping.push((
+            "payee_index".to_string(),
+            payee_index_value(Some(payee), &user_id).await?,
+        ));
+        param_index += 1;
+    }
+
+    if let Some(tags) = &request.tags {
+        let tags = TagConfig::current().normalize_tags(tags)?;
+        let tags_json = serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string());
+        update_fields.push(format!("`tags` = ?{param_index}"));
+        params_with_mapping.push(("tags".to_string(), Value::String(tags_json)));
+        param_index += 1;
+    }
+
+    if update_fields.is_empty() {
+        return Err(FiscusError::InvalidInput("No fields to update".to_string()));
+    }
+
+    // Use transaction for atomicity
+    with_transaction!(&*db, async {
+        // Update transaction
+        update_fields.push(format!("`updated_at` = ?{param_index}"));
+        params_with_mapping.push((
+            "updated_at".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        ));
+        param_index += 1;
+
+        params_with_mapping.push(("id".to_string(), Value::String(transaction_id.clone())));
+
+        // Guard against concurrent edits between the read above and this write
+        let mut where_clause = format!("id = ?{param_index}");
+        if let Some((condition, param)) =
+            stale_write_guard(request.expected_updated_at, param_index + 1)
+        {
+            where_clause.push_str(&condition);
+            params_with_mapping.push(("expected_updated_at".to_string(), param));
+        }
+
+        let update_query = format!(
+            "UPDATE transactions SET {} WHERE {}",
+            update_fields.join(", "),
+            where_clause
+        );
+
+        // Encrypt sensitive parameters before update
+        let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            params_with_mapping,
+            &user_id,
+            "transactions",
+        )
+        .await?;
+
+        let affected_rows =
+            DatabaseUtils::execute_non_query(&db, &update_query, encrypted_params).await?;
+
+        if affected_rows == 0 {
+            // The transaction may have been deleted since it was loaded above
+            let row_exists = request.expected_updated_at.is_some()
+                && DatabaseUtils::row_exists(&db, "transactions", &transaction_id, &user_id)
+                    .await?;
+            return Err(no_rows_updated_error(
+                request.expected_updated_at,
+                row_exists,
+                "Transaction not found",
+            ));
+        }
+
+        // Update account balance if amount or transaction type changed
+        // Note: Transfer type changes are prevented above, so we only need to check for non-Transfer transactions
+        if (amount_changed || transaction_type_changed)
+            && current_transaction.transaction_type != TransactionType::Transfer
+        {
+            // Reverse the old transaction effect and apply the new one in a single delta
+            let delta = balance_delta(new_amount, &new_transaction_type)
+                - balance_delta(
+                    current_transaction.amount,
+                    &current_transaction.transaction_type,
+                );
+
+            DatabaseUtils::adjust_account_balance(&db, &current_transaction.account_id, delta)
+                .await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    let mut change_events = vec![TransactionEvent::TransactionUpdated {
+        user_id: user_id.clone(),
+        transaction_id: transaction_id.clone(),
+    }];
+    if (amount_changed || transaction_type_changed)
+        && current_transaction.transaction_type != TransactionType::Transfer
+    {
+        change_events.push(TransactionEvent::BalanceChanged {
+            user_id: user_id.clone(),
+            account_id: current_transaction.account_id.clone(),
+        });
+    }
+    events::publish(change_events);
+
+    // Return updated transaction
+    get_transaction_by_id(transaction_id, db).await
+}
+
+#[cfg(test)]
+mod update_transaction_tests {
+    use super::balance_delta;
+    use crate::models::TransactionType;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_update_delta_reverses_old_effect_and_applies_new() {
+        // An expense of 50.00 edited into income of 30.00 raises the balance by 80.00
+        let delta = balance_delta(Decimal::new(3000, 2), &TransactionType::Income)
+            - balance_delta(Decimal::new(5000, 2), &TransactionType::Expense);
+        assert_eq!(delta, Decimal::new(8000, 2));
+
+        // Deleting an income of 30.00 lowers the balance by 30.00
+        assert_eq!(
+            -balance_delta(Decimal::new(3000, 2), &TransactionType::Income),
+            Decimal::new(-3000, 2)
+        );
+    }
+
+    #[test]
+    fn test_transaction_type_change_validation_logic() {
+        // Test the validation logic for transaction type changes
+        // This tests the core logic without requiring a full database setup
+
+        // Test case 1: Changing FROM Transfer to Income should be detected as invalid
+        let current_type = TransactionType::Transfer;
+        let new_type = TransactionType::Income;
+        let transaction_type_changed = new_type != current_type;
+
+        assert!(transaction_type_changed);
+        assert_eq!(current_type, TransactionType::Transfer);
+        assert_eq!(new_type, TransactionType::Income);
+
+        // In the actual function, this would trigger the validation error
+        if transaction_type_changed {
+            if current_type == TransactionType::Transfer {
+                // This would return an error in the actual function
+                // Note: Transfer type changes should be prevented
+            }
+            if new_type == TransactionType::Transfer {
+                // This would return an error in the actual function
+                // Note: Transfer type changes should be prevented
+            }
+        }
+
+        // Test case 2: Changing TO Transfer from Expense should be detected as invalid
+        let current_type_2 = TransactionType::Expense;
+        let new_type_2 = TransactionType::Transfer;
+        let transaction_type_changed_2 = new_type_2 != current_type_2;
+
+        assert!(transaction_type_changed_2);
+        assert_eq!(current_type_2, TransactionType::Expense);
+        assert_eq!(new_type_2, TransactionType::Transfer);
+
+        // Test case 3: Changing from Income to Expense should be allowed
+        let current_type_3 = TransactionType::Income;
+        let new_type_3 = TransactionType::Expense;
+        let transaction_type_changed_3 = new_type_3 != current_type_3;
+
+        assert!(transaction_type_changed_3);
+        assert_ne!(current_type_3, TransactionType::Transfer);
+        assert_ne!(new_type_3, TransactionType::Transfer);
+        // This change should be allowed
+    }
+}
+
+/// Delete a transaction
+#[tauri::command]
+pub async fn delete_transaction(
+    transaction_id: String,
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<bool, FiscusError> {
+    authorize_command("delete_transaction").await?;
+
+    // Validate input
+    Validator::validate_uuid(&transaction_id, "transaction_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    // Get current transaction to validate ownership and update balance
+    let current_transaction = get_transaction_by_id(transaction_id.clone(), db.clone()).await?;
+
+    if current_transaction.user_id != user_id {
+        return Err(FiscusError::Authorization(
+            "Transaction access denied".to_string(),
+        ));
+    }
+
+    // Use transaction for atomicity
+    with_transaction!(&*db, async {
+        // Delete the transaction
+        let delete_query = "DELETE FROM transactions WHERE id = ?1";
+        let params = vec![Value::String(transaction_id)];
+
+        let affected_rows = DatabaseUtils::execute_non_query(&db, delete_query, params).await?;
+
+        if affected_rows == 0 {
+            return Err(FiscusError::NotFound("Transaction not found".to_string()));
+        }
+
+        // Update account balance by reversing the transaction effect
+        if current_transaction.transaction_type != TransactionType::Transfer {
+            DatabaseUtils::adjust_account_balance(
+                &db,
+                &current_transaction.account_id,
+                -balance_delta(
+                    current_transaction.amount,
+                    &current_transaction.transaction_type,
+                ),
+            )
+            .await?;
+        }
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    if current_transaction.transaction_type != TransactionType::Transfer {
+        events::publish([TransactionEvent::BalanceChanged {
+            user_id,
+            account_id: current_transaction.account_id,
+        }]);
+    }
+
+    Ok(true)
+}
+
+/// Create a transfer between accounts
+///
+/// A `fee_amount` is posted as a separate expense on the source account,
+/// categorized by `fee_category_id`, so the source drops by the amount plus
+/// the fee while the destination receives the full amount. Voiding the
+/// transfer leaves the fee in place.
+#[tauri::command]
+pub async fn create_transfer(
+    request: CreateTransferRequest,
+    db: State<'_, Database>,
+) -> Result<Transfer, FiscusError> {
+    authorize_command("create_transfer").await?;
+
+    // Validate input (user_id already validated by ValidatedUserId)
+    Validator::validate_uuid(&request.from_account_id, "from_account_id")?;
+    Validator::validate_uuid(&request.to_account_id, "to_account_id")?;
+    Validator::validate_amount(request.amount, false)?; // Transfers must be positive
+    Validator::validate_string(&request.description, "description", 1, 255)?;
+
+    let transfer_date = Validator::validate_datetime(&request.transfer_date, "transfer_date")?;
+
+    if request.from_account_id == request.to_account_id {
+        return Err(FiscusError::InvalidInput(
+            "Cannot transfer to the same account".to_string(),
+        ));
+    }
+
+    if let Some(fee_amount) = request.fee_amount {
+        Validator::validate_amount(fee_amount, false).map_err(|e| e.for_field("fee_amount"))?;
+        if fee_amount.is_zero() {
+            return Err(FiscusError::field_validation(
+                "fee_amount",
+                "invalid_amount",
+                "A transfer fee must be positive",
+            ));
+        }
+    }
+    if let Some(ref fee_category_id) = request.fee_category_id {
+        if request.fee_amount.is_none() {
+            return Err(FiscusError::field_validation(
+                "fee_amount",
+                "required",
+                "fee_amount is required with fee_category_id",
+            ));
+        }
+        Validator::validate_uuid(fee_category_id, "fee_category_id")?;
+        DatabaseUtils::validate_category_ownership(&db, fee_category_id, &request.user_id.as_str())
+            .await?;
+    }
+
+    // Validate account ownership
+    DatabaseUtils::validate_account_ownership(
+        &db,
+        &request.from_account_id,
+        &request.user_id.as_str(),
+    )
+    .await?;
+    DatabaseUtils::validate_account_ownership(
+        &db,
+        &request.to_account_id,
+        &request.user_id.as_str(),
+    )
+    .await?;
+    for account_id in [&request.from_account_id, &request.to_account_id] {
+        ensure_account_open(
+            &db,
+            account_id,
+            &request.user_id.as_str(),
+            transfer_date.date_naive(),
+        )
+        .await?;
+    }
+
+    let transfer_id = Uuid::new_v4().to_string();
+    let from_transaction_id = Uuid::new_v4().to_string();
+    let to_transaction_id = Uuid::new_v4().to_string();
+    let fee_transaction = transfer_fee_transaction(&request, &transfer_id, transfer_date);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    // Use transaction for atomicity
+    with_transaction!(&*db, async {
+        // Create the transfer record
+        let transfer_query = r#"
+            INSERT INTO transfers (
+                id, user_id, from_account_id, to_account_id, amount, description,
+                transfer_date, status, from_transaction_id, to_transaction_id,
+                created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        "#;
+
+        // Use encrypted parameter mapping for transfer record
+        let transfer_params_with_mapping = vec![
+            ("id".to_string(), Value::String(transfer_id.clone())),
+            (
+                "user_id".to_string(),
+                Value::String(request.user_id.to_string()),
+            ),
+            (
+                "from_account_id".to_string(),
+                Value::String(request.from_account_id.clone()),
+            ),
+            (
+                "to_account_id".to_string(),
+                Value::String(request.to_account_id.clone()),
+            ),
+            (
+                "amount".to_string(),
+                Value::String(request.amount.to_string()),
+            ),
+            (
+                "description".to_string(),
+                Value::String(request.description.clone()),
+            ),
+            (
+                "transfer_date".to_string(),
+                Value::String(transfer_date.to_rfc3339()),
+            ),
+            (
+                "status".to_string(),
+                Value::String(TransactionStatus::Completed.to_string()),
+            ),
+            (
+                "from_transaction_id".to_string(),
+                Value::String(from_transaction_id.clone()),
+            ),
+            (
+                "to_transaction_id".to_string(),
+                Value::String(to_transaction_id.clone()),
+            ),
+            ("created_at".to_string(), Value::String(now.clone())),
+            ("updated_at".to_string(), Value::String(now.clone())),
+        ];
+
+        let encrypted_transfer_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            transfer_params_with_mapping,
+            &request.user_id.as_str(),
+            "transfers",
+        )
+        .await?;
+
+        DatabaseUtils::execute_non_query(&db, transfer_query, encrypted_transfer_params).await?;
+
+        // Create outgoing transaction (expense)
+        let from_transaction_query = r#"
+            INSERT INTO transactions (
+                id, user_id, account_id, amount, description, transaction_date,
+                transaction_type, status, created_at, updated_at, amount_minor
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        "#;
+
+        // Use encrypted parameter mapping for outgoing transaction
+        let from_params_with_mapping = vec![
+            ("id".to_string(), Value::String(from_transaction_id.clone())),
+            (
+                "user_id".to_string(),
+                Value::String(request.user_id.to_string()),
+            ),
+            (
+                "account_id".to_string(),
+                Value::String(request.from_account_id.clone()),
+            ),
+            (
+                "amount".to_string(),
+                Value::String((-request.amount).to_string()),
+            ), // Negative for outgoing
+            (
+                "description".to_string(),
+                Value::String(format!("Transfer to account: {}", request.description)),
+            ),
+            (
+                "transaction_date".to_string(),
+                Value::String(transfer_date.to_rfc3339()),
+            ),
+            (
+                "transaction_type".to_string(),
+                Value::String(TransactionType::Transfer.to_string()),
+            ),
+            (
+                "status".to_string(),
+                Value::String(TransactionStatus::Completed.to_string()),
+            ),
+            ("created_at".to_string(), Value::String(now.clone())),
+            ("updated_at".to_string(), Value::String(now.clone())),
+            (
+                "amount_minor".to_string(),
+                amount_minor_value(-request.amount, store_amount_minor_units())?,
+            ),
+        ];
+
+        let encrypted_from_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            from_params_with_mapping,
+            &request.user_id.as_str(),
+            "transactions",
+        )
+        .await?;
+
+        DatabaseUtils::execute_non_query(&db, from_transaction_query, encrypted_from_params)
+            .await?;
+
+        // Create incoming transaction (income)
+        let to_transaction_query = r#"
+            INSERT INTO transactions (
+                id, user_id, account_id, amount, description, transaction_date,
+                transaction_type, status, created_at, updated_at, amount_minor
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        "#;
+
+        // Use encrypted parameter mapping for incoming transaction
+        let to_params_with_mapping = vec![
+            ("id".to_string(), Value::String(to_transaction_id.clone())),
+            (
+                "user_id".to_string(),
+                Value::String(request.user_id.to_string()),
+            ),
+            (
+                "account_id".to_string(),
+                Value::String(request.to_account_id.clone()),
+            ),
+            (
+                "amount".to_string(),
+                Value::String(request.amount.to_string()),
+            ), // Positive for incoming
+            (
+                "description".to_string(),
+                Value::String(format!("Transfer from account: {}", request.description)),
+            ),
+            (
+                "transaction_date".to_string(),
+                Value::String(transfer_date.to_rfc3339()),
+            ),
+            (
+                "transaction_type".to_string(),
+                Value::String(TransactionType::Transfer.to_string()),
+            ),
+            (
+                "status".to_string(),
+                Value::String(TransactionStatus::Completed.to_string()),
+            ),
+            ("created_at".to_string(), Value::String(now.clone())),
+            ("updated_at".to_string(), Value::String(now)),
+            (
+                "amount_minor".to_string(),
+                amount_minor_value(request.amount, store_amount_minor_units())?,
+            ),
+        ];
+
+        let encrypted_to_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            to_params_with_mapping,
+            &request.user_id.as_str(),
+            "transactions",
+        )
+        .await?;
+
+        DatabaseUtils::execute_non_query(&db, to_transaction_query, encrypted_to_params).await?;
+
+        if let Some(ref fee) = fee_transaction {
+            insert_generated_transaction(&db, fee).await?;
+        }
+
+        // Update account balances
+        let (from_delta, to_delta) =
+            transfer_balance_deltas_with_fee(request.amount, fee_transaction.as_ref());
+        DatabaseUtils::adjust_account_balance(&db, &request.from_account_id, from_delta).await?;
+        DatabaseUtils::adjust_account_balance(&db, &request.to_account_id, to_delta).await?;
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    let user_id = request.user_id.as_str();
+    let mut created_events = transfer_created_events(
+        (&user_id, &from_transaction_id, &request.from_account_id),
+        (&user_id, &to_transaction_id, &request.to_account_id),
+    );
+    if let Some(ref fee) = fee_transaction {
+        created_events.extend(transaction_created_events(
+            &user_id,
+            &fee.id,
+            &fee.account_id,
+            &fee.transaction_type,
+        ));
+    }
+    events::publish(created_events);
+
+    // Return the created transfer
+    get_transfer_by_id(transfer_id, db).await
+}
+
+/// Create a transfer from one user's account into another user's account
+///
+/// Each account is checked against its own owner and the session must act for
+/// the source owner. The outgoing transaction belongs to, and is encrypted for,
+/// the sender; the incoming transaction belongs to the recipient. The transfer
+/// record itself is owned by the sender.
+#[tauri::command]
+pub async fn create_cross_user_transfer(
+    from_user_id: String,
+    from_account_id: String,
+    to_user_id: String,
+    to_account_id: String,
+    amount: Decimal,
+    description: String,
+    db: State<'_, Database>,
+) -> Result<Transfer, FiscusError> {
+    authorize_command("create_cross_user_transfer").await?;
+
+    // Validate input
+    Validator::validate_uuid(&from_user_id, "from_user_id")?;
+    Validator::validate_uuid(&to_user_id, "to_user_id")?;
+    Validator::validate_uuid(&from_account_id, "from_account_id")?;
+    Validator::validate_uuid(&to_account_id, "to_account_id")?;
+    Validator::validate_amount(amount, false)?; // Transfers must be positive
+    Validator::validate_string(&description, "description", 1, 255)?;
+
+    if from_user_id == to_user_id {
+        return Err(FiscusError::InvalidInput(
+            "Use create_transfer for transfers between a user's own accounts".to_string(),
+        ));
+    }
+    if from_account_id == to_account_id {
+        return Err(FiscusError::InvalidInput(
+            "Cannot transfer to the same account".to_string(),
+        ));
+    }
+
+    // Only the owner of the source account may send money from it
+    authorize_user(&from_user_id).await?;
+
+    DatabaseUtils::validate_user_exists(&db, &from_user_id).await?;
+    DatabaseUtils::validate_user_exists(&db, &to_user_id).await?;
+    DatabaseUtils::validate_account_ownership(&db, &from_account_id, &from_user_id).await?;
+    DatabaseUtils::validate_account_ownership(&db, &to_account_id, &to_user_id).await?;
+    let today = chrono::Utc::now().date_naive();
+    ensure_account_open(&db, &from_account_id, &from_user_id, today).await?;
+    ensure_account_open(&db, &to_account_id, &to_user_id, today).await?;
+
+    let transfer_id = Uuid::new_v4().to_string();
+    let from_transaction_id = Uuid::new_v4().to_string();
+    let to_transaction_id = Uuid::new_v4().to_string();
+    let transfer_date = chrono::Utc::now();
+    let now = transfer_date.to_rfc3339();
+
+    // Use transaction for atomicity
+    with_transaction!(&*db, async {
+        let transfer_query = r#"
+            INSERT INTO transfers (
+                id, user_id, to_user_id, from_account_id, to_account_id, amount, description,
+                transfer_date, status, from_transaction_id, to_transaction_id,
+                created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        "#;
+
+        let transfer_params_with_mapping = vec![
+            ("id".to_string(), Value::String(transfer_id.clone())),
+            ("user_id".to_string(), Value::String(from_user_id.clone())),
+            ("to_user_id".to_string(), Value::String(to_user_id.clone())),
+            (
+                "from_account_id".to_string(),
+                Value::String(from_account_id.clone()),
+            ),
+            (
+                "to_account_id".to_string(),
+                Value::String(to_account_id.clone()),
+            ),
+            ("amount".to_string(), Value::String(amount.to_string())),
+            (
+                "description".to_string(),
+                Value::String(description.clone()),
+            ),
+            ("transfer_date".to_string(), Value::String(now.clone())),
+            (
+                "status".to_string(),
+                Value::String(TransactionStatus::Completed.to_string()),
+            ),
+            (
+                "from_transaction_id".to_string(),
+                Value::String(from_transaction_id.clone()),
+            ),
+            (
+                "to_transaction_id".to_string(),
+                Value::String(to_transaction_id.clone()),
+            ),
+            ("created_at".to_string(), Value::String(now.clone())),
+            ("updated_at".to_string(), Value::String(now.clone())),
+        ];
+
+        let encrypted_transfer_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+            transfer_params_with_mapping,
+            &from_user_id,
+            "transfers",
+        )
+        .await?;
+
+        DatabaseUtils::execute_non_query(&db, transfer_query, encrypted_transfer_params).await?;
+
+        let transaction_query = r#"
+            INSERT INTO transactions (
+                id, user_id, account_id, amount, description, transaction_date,
+                transaction_type, status, created_at, updated_at, amount_minor
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        "#;
+
+        // Each side is written and encrypted for the user who owns that account
+        let legs = [
+            transfer_leg_params(
+                &from_transaction_id,
+                &from_user_id,
+                &from_account_id,
+                -amount,
+                format!("Transfer to another user: {description}"),
+                &now,
+            )?,
+            transfer_leg_params(
+                &to_transaction_id,
+                &to_user_id,
+                &to_account_id,
+                amount,
+                format!("Transfer from another user: {description}"),
+                &now,
+            )?,
+        ];
+
+        for (leg_user_id, leg_params) in [&from_user_id, &to_user_id].into_iter().zip(legs) {
+            let encrypted_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
+                leg_params,
+                leg_user_id,
+                "transactions",
+            )
+            .await?;
+
+            DatabaseUtils::execute_non_query(&db, transaction_query, encrypted_params).await?;
+        }
+
+        // Update account balances
+        let (from_delta, to_delta) = transfer_balance_deltas(amount);
+        DatabaseUtils::adjust_account_balance(&db, &from_account_id, from_delta).await?;
+        DatabaseUtils::adjust_account_balance(&db, &to_account_id, to_delta).await?;
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    events::publish(transfer_created_events(
+        (&from_user_id, &from_transaction_id, &from_account_id),
+        (&to_user_id, &to_transaction_id, &to_account_id),
+    ));
+
+    get_transfer_by_id(transfer_id, db).await
+}
+
+/// Events for both legs of a committed transfer, each as `(user_id, transaction_id, account_id)`
+fn transfer_created_events(
+    from_leg: (&str, &str, &str),
+    to_leg: (&str, &str, &str),
+) -> Vec<TransactionEvent> {
+    [from_leg, to_leg]
+        .into_iter()
+        .flat_map(|(user_id, transaction_id, account_id)| {
+            [
+                TransactionEvent::TransactionCreated {
+                    user_id: user_id.to_string(),
+                    transaction_id: transaction_id.to_string(),
+                    account_id: account_id.to_string(),
+                },
+                TransactionEvent::BalanceChanged {
+                    user_id: user_id.to_string(),
+                    account_id: account_id.to_string(),
+                },
+            ]
+        })
+        .collect()
+}
+
+/// Column mapping for one side of a cross-user transfer
+fn transfer_leg_params(
+    transaction_id: &str,
+    user_id: &str,
+    account_id: &str,
+    amount: Decimal,
+    description: String,
+    transaction_date: &str,
+) -> FiscusResult<Vec<(String, Value)>> {
+    Ok(vec![
+        ("id".to_string(), Value::String(transaction_id.to_string())),
+        ("user_id".to_string(), Value::String(user_id.to_string())),
+        (
+            "account_id".to_string(),
+            Value::String(account_id.to_string()),
+        ),
+        ("amount".to_string(), Value::String(amount.to_string())),
+        ("description".to_string(), Value::String(description)),
+        (
+            "transaction_date".to_string(),
+            Value::String(transaction_date.to_string()),
+        ),
+        (
+            "transaction_type".to_string(),
+            Value::String(TransactionType::Transfer.to_string()),
+        ),
+        (
+            "status".to_string(),
+            Value::String(TransactionStatus::Completed.to_string()),
+        ),
+        (
+            "created_at".to_string(),
+            Value::String(transaction_date.to_string()),
+        ),
+        (
+            "updated_at".to_string(),
+            Value::String(transaction_date.to_string()),
+        ),
+        (
+            "amount_minor".to_string(),
+            amount_minor_value(amount, store_amount_minor_units())?,
+        ),
+    ])
+}
+
+/// Balance changes for the source and destination of a transfer of `amount`.
+/// A negative amount reverses a previously applied transfer.
+fn transfer_balance_deltas(amount: Decimal) -> (Decimal, Decimal) {
+    (-amount, amount)
+}
+
+/// Like `transfer_balance_deltas`, with the source also paying `fee`
+fn transfer_balance_deltas_with_fee(
+    amount: Decimal,
+    fee: Option<&Transaction>,
+) -> (Decimal, Decimal) {
+    let (from_delta, to_delta) = transfer_balance_deltas(amount);
+    let fee_delta = fee.map_or(Decimal::ZERO, |fee| {
+        balance_delta(fee.amount, &fee.transaction_type)
+    });
+    (from_delta + fee_delta, to_delta)
+}
+
+/// Expense charging the fee of a transfer to its source account, if it has one
+///
+/// The reference number links the fee back to `transfer_id`.
+fn transfer_fee_transaction(
+    request: &CreateTransferRequest,
+    transfer_id: &str,
+    transfer_date: chrono::DateTime<chrono::Utc>,
+) -> Option<Transaction> {
+    let fee_amount = request.fee_amount?;
+    let now = chrono::Utc::now();
+
+    Some(Transaction {
+        id: Uuid::new_v4().to_string(),
+        user_id: request.user_id.to_string(),
+        account_id: request.from_account_id.clone(),
+        category_id: request.fee_category_id.clone(),
+        amount: fee_amount,
+        description: format!("Transfer fee: {}", request.description),
+        notes: None,
+        transaction_date: transfer_date,
+        transaction_type: TransactionType::Expense,
+        status: TransactionStatus::Completed,
+        reference_number: Some(format!("transfer-fee:{transfer_id}")),
+        payee: None,
+        tags: None,
+        original_amount: None,
+        original_currency: None,
+        refunds_transaction_id: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Ensure a transfer can be voided by the given user
+fn ensure_transfer_voidable(transfer: &Transfer, user_id: &str) -> Result<(), FiscusError> {
+    if transfer.user_id != user_id {
+        return Err(FiscusError::Authorization(
+            "Transfer access denied".to_string(),
+        ));
+    }
+
+    if transfer.status == TransactionStatus::Voided {
+        return Err(FiscusError::Conflict(
+            "Transfer has already been voided".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Void a transfer, reversing both account balances and keeping the records for history
+#[tauri::command]
+pub async fn void_transfer(
+    transfer_id: String,
+    user_id: String,
+    db: State<'_, Database>,
+) -> Result<Transfer, FiscusError> {
+    authorize_command("void_transfer").await?;
+
+    // Validate input
+    Validator::validate_uuid(&transfer_id, "transfer_id")?;
+    Validator::validate_uuid(&user_id, "user_id")?;
+
+    let transfer = get_transfer_by_id(transfer_id.clone(), db.clone()).await?;
+    ensure_transfer_voidable(&transfer, &user_id)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let voided = TransactionStatus::Voided.to_string();
+
+    // Use transaction for atomicity
+    with_transaction!(&*db, async {
+        // Guard on the current status so a concurrent void cannot apply twice
+        let void_transfer_query = r#"
+            UPDATE transfers SET status = ?1, updated_at = ?2
+            WHERE id = ?3 AND user_id = ?4 AND status != ?1
+        "#;
+        let affected_rows = DatabaseUtils::execute_non_query(
+            &db,
+            void_transfer_query,
+            vec![
+                Value::String(voided.clone()),
+                Value::String(now.clone()),
+                Value::String(transfer_id.clone()),
+                Value::String(user_id.clone()),
+            ],
+        )
+        .await?;
+
+        if affected_rows == 0 {
+            return Err(FiscusError::Conflict(
+                "Transfer has already been voided".to_string(),
+            ));
+        }
+
+        // The incoming side belongs to the recipient for cross-user transfers
+        let void_transactions_query = r#"
+            UPDATE transactions SET status = ?1, updated_at = ?2
+            WHERE (id = ?3 AND user_id = ?5) OR (id = ?4 AND user_id = ?6)
+        "#;
+        DatabaseUtils::execute_non_query(
+            &db,
+            void_transactions_query,
+            vec![
+                Value::String(voided.clone()),
+                Value::String(now.clone()),
+                Value::String(transfer.from_transaction_id.clone()),
+                Value::String(transfer.to_transaction_id.clone()),
+                Value::String(user_id.clone()),
+                Value::String(transfer.recipient_user_id().to_string()),
+            ],
+        )
+        .await?;
+
+        // Reverse the balance movement
+        let (from_delta, to_delta) = transfer_balance_deltas(-transfer.amount);
+        DatabaseUtils::adjust_account_balance(&db, &transfer.from_account_id, from_delta).await?;
+        DatabaseUtils::adjust_account_balance(&db, &transfer.to_account_id, to_delta).await?;
+
+        Ok::<(), FiscusError>(())
+    })?;
+
+    let recipient_user_id = transfer.recipient_user_id();
+    events::publish(
+        [
+            (
+                user_id.as_str(),
+                &transfer.from_transaction_id,
+                &transfer.from_account_id,
+            ),
+            (
+                recipient_user_id,
+                &transfer.to_transaction_id,
+                &transfer.to_account_id,
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(leg_user_id, transaction_id, account_id)| {
+            [
+                TransactionEvent::TransactionUpdated {
+                    user_id: leg_user_id.to_string(),
+                    transaction_id: transaction_id.clone(),
+                },
+                TransactionEvent::BalanceChanged {
+                    user_id: leg_user_id.to_string(),
+                    account_id: account_id.clone(),
+                },
+            ]
+        }),
+    );
+
+    get_transfer_by_id(transfer_id, db).await
+}
+
+/// Get a transfer by ID
+#[tauri::command]
+pub async fn get_transfer_by_id(
+    transfer_id: String,
+    db: State<'_, Database>,
+) -> Result<Transfer, FiscusError> {
+    authorize_command("get_transfer_by_id").await?;
+
+    Validator::validate_uuid(&transfer_id, "transfer_id")?;
+
+    // First, get the user_id for this transfer (this field is not encrypted)
+    let user_query = "SELECT user_id FROM transfers WHERE id = ?1";
+    let user_result: Option<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query_single(
+            &db,
+            user_query,
+            vec![Value::String(transfer_id.clone())],
+        )
+        .await?;
+
+    let user_id = user_result
+        .and_then(|row| {
+            row.get("user_id")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+        })
+        .ok_or_else(|| FiscusError::NotFound("Transfer not found".to_string()))?;
+
+    let query = r#"
+        SELECT id, user_id, to_user_id, from_account_id, to_account_id, amount, description,
+               transfer_date, status, from_transaction_id, to_transaction_id,
+               created_at, updated_at
+        FROM transfers
+        WHERE id = ?1
+    "#;
+
+    // Use encrypted query to properly decrypt sensitive fields
+    let transfers: Vec<Transfer> = EncryptedDatabaseUtils::execute_encrypted_query(
+        &db,
+        query,
+        vec![Value::String(transfer_id.clone())],
+        &user_id,
+        "transfers",
+    )
+    .await?;
+
+    transfers
+        .into_iter()
+        .next()
+        .ok_or_else(|| FiscusError::NotFound("Transfer not found".to_string()))
+}
+
+/// Bulk operations on transactions
+#[tauri::command]
+pub async fn bulk_transaction_operations(
+    request: BulkTransactionRequest,
+    db: State<'_, Database>,
+) -> Result<String, FiscusError> {
+    // Exporting only reads, so observers may still run it
+    let operation = match request.action {
+        BulkTransactionAction::Export { .. } => "export_transactions",
+        _ => "bulk_transaction_operations",
+    };
+    authorize_command(operation).await?;
+
+    // Validate user (already validated by ValidatedUserId)
+    DatabaseUtils::validate_user_exists(&db, &request.user_id.as_str()).await?;
+
+    // Validate transaction IDs
+    for transaction_id in &request.transaction_ids {
+        Validator::validate_uuid(transaction_id, "transaction_id")?;
+    }
+
+    BulkConfig::current().validate_batch_size(request.transaction_ids.len())?;
+
+    match request.action {
+        BulkTransactionAction::Delete => {
+            bulk_delete_transactions(request.transaction_ids, &request.user_id.as_str(), &db).await
+        }
+        BulkTransactionAction::UpdateCategory { category_id } => {
+            bulk_update_category(
+                request.transaction_ids,
+                category_id,
+                &request.user_id.as_str(),
+                &db,
+            )
+            .await
+        }
+        BulkTransactionAction::UpdateStatus { status } => {
+            bulk_update_status(
+                request.transaction_ids,
+                status,
+                &request.user_id.as_str(),
+                &db,
+            )
+            .await
+        }
+        BulkTransactionAction::Export {
+            format,
+            csv_options,
+        } => {
+            bulk_export_transactions(
+                request.transaction_ids,
+                format,
+                &csv_options,
+                &request.user_id.as_str(),
+                &db,
+            )
+            .await
+        }
+    }
+}
+
+/// Bulk delete transactions
+async fn bulk_delete_transactions(
+    transaction_ids: Vec<String>,
+    user_id: &str,
+    db: &Database,
+) -> Result<String, FiscusError> {
+    with_transaction!(db, async {
+        let mut balance_changes: std::collections::BTreeMap<String, Decimal> =
+            std::collections::BTreeMap::new();
+
+        for transaction_id in &transaction_ids {
+            // Verify ownership before deletion
+            let transaction =
+                get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+
+            if transaction.user_id != user_id {
+                return Err(FiscusError::Authorization(
+                    "Transaction access denied".to_string(),
+                ));
+            }
+
+            // Delete the transaction
+            let delete_query = "DELETE FROM transactions WHERE id = ?1 AND user_id = ?2";
+            DatabaseUtils::execute_non_query(
+                db,
+                delete_query,
+                vec![
+                    Value::String(transaction_id.clone()),
+                    Value::String(user_id.to_string()),
+                ],
+            )
+            .await?;
+
+            *balance_changes.entry(transaction.account_id).or_default() -=
+                balance_delta(transaction.amount, &transaction.transaction_type);
+        }
+
+        // Reverse the deleted transactions' effect, once per account
+        for (account_id, delta) in balance_changes {
+            if !delta.is_zero() {
+                DatabaseUtils::adjust_account_balance(db, &account_id, delta).await?;
+            }
+        }
+
+        Ok(format!(
+            "Successfully deleted {} transactions",
+            transaction_ids.len()
+        ))
+    })
+}
+
+/// Bulk update transaction categories
+async fn bulk_update_category(
+    transaction_ids: Vec<String>,
+    category_id: Option<String>,
+    user_id: &str,
+    db: &Database,
+) -> Result<String, FiscusError> {
+    // Validate category if provided
+    if let Some(ref cat_id) = category_id {
+        Validator::validate_uuid(cat_id, "category_id")?;
+    }
+
+    with_transaction!(db, async {
+        let update_query = r#"
+            UPDATE transactions
+            SET category_id = ?1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?2 AND user_id = ?3
+        "#;
+
+        for transaction_id in &transaction_ids {
+            // Verify ownership
+            let transaction =
+                get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+
+            if transaction.user_id != user_id {
+                return Err(FiscusError::Authorization(
+                    "Transaction access denied".to_string(),
+                ));
+            }
+
+            DatabaseUtils::execute_non_query(
+                db,
+                update_query,
+                vec![
+                    category_id
+                        .as_ref()
+                        .map(|id| Value::String(id.clone()))
+                        .unwrap_or(Value::Null),
+                    Value::String(transaction_id.clone()),
+                    Value::String(user_id.to_string()),
+                ],
+            )
+            .await?;
+        }
+
+        Ok(format!(
+            "Successfully updated category for {} transactions",
+            transaction_ids.len()
+        ))
+    })
+}
+
+/// Bulk update transaction status
+async fn bulk_update_status(
+    transaction_ids: Vec<String>,
+    status: TransactionStatus,
+    user_id: &str,
+    db: &Database,
+) -> Result<String, FiscusError> {
+    with_transaction!(db, async {
+        let update_query = r#"
+            UPDATE transactions
+            SET status = ?1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?2 AND user_id = ?3
+        "#;
+
+        for transaction_id in &transaction_ids {
+            // Verify ownership
+            let transaction =
+                get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+
+            if transaction.user_id != user_id {
+                return Err(FiscusError::Authorization(
+                    "Transaction access denied".to_string(),
+                ));
+            }
+
+            DatabaseUtils::execute_non_query(
+                db,
+                update_query,
+                vec![
+                    Value::String(status.to_string()),
+                    Value::String(transaction_id.clone()),
+                    Value::String(user_id.to_string()),
+                ],
+            )
+            .await?;
+        }
+
+        Ok(format!(
+            "Successfully updated status for {} transactions",
+            transaction_ids.len()
+        ))
+    })
+}
+
+/// Bulk export transactions
+async fn bulk_export_transactions(
+    transaction_ids: Vec<String>,
+    format: ExportFormat,
+    csv_options: &CsvOptions,
+    user_id: &str,
+    db: &Database,
+) -> Result<String, FiscusError> {
+    let mut transactions = Vec::new();
+
+    for transaction_id in &transaction_ids {
+        let transaction =
+            get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+
+        if transaction.user_id != user_id {
+            return Err(FiscusError::Authorization(
+                "Transaction access denied".to_string(),
+            ));
+        }
+
+        transactions.push(transaction);
+    }
+
+    match format {
+        ExportFormat::Json => {
+            let json_data = serde_json::to_string_pretty(&transactions)
+                .map_err(|e| FiscusError::Internal(format!("JSON serialization failed: {e}")))?;
+            Ok(json_data)
+        }
+        ExportFormat::Csv => {
+            let mut writer = TransactionCsvWriter::new(Vec::new(), csv_options)?;
+            for transaction in &transactions {
+                writer.write(transaction)?;
+            }
+
+            String::from_utf8(writer.into_inner()?)
+                .map_err(|e| FiscusError::Internal(format!("CSV export is not UTF-8: {e}")))
+        }
+    }
+}
+
+/// Column names of a CSV transaction export
+pub(crate) const TRANSACTION_CSV_HEADER: [&str; 10] = [
+    "id",
+    "account_id",
+    "category_id",
+    "amount",
+    "description",
+    "transaction_date",
+    "transaction_type",
+    "status",
+    "payee",
+    "notes",
+];
+
+/// Byte order mark that makes Excel read a CSV file as UTF-8
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Writes transactions as RFC 4180 CSV in the dialect given by `CsvOptions`
+///
+/// Records end in CRLF and fields are quoted only when they contain the
+/// delimiter, the quote character or a line break.
+pub(crate) struct TransactionCsvWriter<W: Write> {
+    writer: csv::Writer<W>,
+    date_format: String,
+}
+
+impl<W: Write> TransactionCsvWriter<W> {
+    /// Start a CSV export on `out`, writing the optional BOM and the header row
+    pub(crate) fn new(mut out: W, options: &CsvOptions) -> FiscusResult<Self> {
+        validate_csv_options(options)?;
+
+        if options.include_bom {
+            out.write_all(UTF8_BOM).map_err(csv_io_error)?;
+        }
+
+        // Both characters were checked to be ASCII above
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(options.delimiter as u8)
+            .quote(options.quote as u8)
+            .quote_style(csv::QuoteStyle::Necessary)
+            .terminator(csv::Terminator::CRLF)
+            .from_writer(out);
+        writer
+            .write_record(TRANSACTION_CSV_HEADER)
+            .map_err(csv_write_error)?;
+
+        Ok(Self {
+            writer,
+            date_format: options.date_format.clone(),
+        })
+    }
+
+    pub(crate) fn write(&mut self, transaction: &Transaction) -> FiscusResult<()> {
+        let amount = transaction.amount.to_string();
+        let transaction_date = transaction
+            .transaction_date
+            .format(&self.date_format)
+            .to_string();
+        let transaction_type = transaction.transaction_type.to_string();
+        let status = transaction.status.to_string();
+
+        self.writer
+            .write_record([
+                transaction.id.as_str(),
+                transaction.account_id.as_str(),
+                transaction.category_id.as_deref().unwrap_or_default(),
+                amount.as_str(),
+                transaction.description.as_str(),
+                transaction_date.as_str(),
+                transaction_type.as_str(),
+                status.as_str(),
+                transaction.payee.as_deref().unwrap_or_default(),
+                transaction.notes.as_deref().unwrap_or_default(),
+            ])
+            .map_err(csv_write_error)
+    }
+
+    pub(crate) fn flush(&mut self) -> FiscusResult<()> {
+        self.writer.flush().map_err(csv_io_error)
+    }
+
+    /// Flush and return the underlying writer
+    pub(crate) fn into_inner(self) -> FiscusResult<W> {
+        self.writer
+            .into_inner()
+            .map_err(|e| csv_io_error(e.into_error()))
+    }
+}
+
+/// Check that a CSV dialect can be written unambiguously
+fn validate_csv_options(options: &CsvOptions) -> FiscusResult<()> {
+    for (field, value) in [("delimiter", options.delimiter), ("quote", options.quote)] {
+        if !value.is_ascii() || matches!(value, '\r' | '\n') {
+            return Err(FiscusError::field_validation(
+                field,
+                "invalid_value",
+                format!("{field} must be a single ASCII character other than a line break"),
+            ));
+        }
+    }
+
+    if options.delimiter == options.quote {
+        return Err(FiscusError::field_validation(
+            "quote",
+            "invalid_value",
+            "quote must differ from delimiter",
+        ));
+    }
+
+    let invalid_date_format = options.date_format.trim().is_empty()
+        || chrono::format::StrftimeItems::new(&options.date_format)
+            .any(|item| matches!(item, chrono::format::Item::Error));
+    if invalid_date_format {
+        return Err(FiscusError::field_validation(
+            "date_format",
+            "invalid_format",
+            "date_format is not a valid chrono format string",
+        ));
+    }
+
+    Ok(())
+}
+
+fn csv_write_error(e: csv::Error) -> FiscusError {
+    FiscusError::Internal(format!("Failed to write CSV export: {e}"))
+}
+
+fn csv_io_error(e: std::io::Error) -> FiscusError {
+    FiscusError::Internal(format!("Failed to write CSV export: {e}"))
+}
+
+/// Get transaction summary for a user
+#[tauri::command]
+pub async fn get_transaction_summary(
+    user_id: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    db: State<'_, Database>,
+) -> Result<TransactionSummaryResponse, FiscusError> {
+    authorize_command("get_transaction_summary").await?;
+
+    // Validate user
+    Validator::validate_uuid(&user_id, "user_id")?;
+    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+
+    let mut where_conditions = vec![
+        "user_id = ?1".to_string(),
+        "transaction_type != 'transfer'".to_string(),
+    ];
+    let mut params = vec![Value::String(user_id)];
+    let mut param_index = 2;
+
+    if let Some(start) = start_date {
+        Validator::validate_date(&start, "start_date")?;
+        where_conditions.push(format!("DATE(transaction_date) >= ?{param_index}"));
+        params.push(Value::String(start));
+        param_index += 1;
+    }
+
+    if let Some(end) = end_date {
+        Validator::validate_date(&end, "end_date")?;
+        where_conditions.push(format!("DATE(transaction_date) <= ?{param_index}"));
+        params.push(Value::String(end));
+    }
+
+    let summary_query = format!(
+        r#"
+        SELECT
+            COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END), 0) as total_income,
+            COALESCE(SUM(CASE WHEN transaction_type = 'expense' THEN amount ELSE 0 END), 0) as total_expenses,
+            COUNT(*) as transaction_count,
+            COALESCE(AVG(ABS(amount)), 0) as average_transaction
+        FROM transactions
+        WHERE {}
+    "#,
+        where_conditions.join(" AND ")
+    );
+
+    let summary: Option<HashMap<String, serde_json::Value>> =
+        DatabaseUtils::execute_query_single(&db, &summary_query, params).await?;
+
+    let summary_data = summary.unwrap_or_default();
+
+    let total_income = parse_decimal_from_json(&summary_data, "total_income");
+    let total_expenses = parse_decimal_from_json(&summary_data, "total_expenses");
+
+    let transaction_count = summary_data
+        .get("transaction_count")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0) as i32;
+
+    let average_transaction = parse_decimal_from_json(&summary_data, "average_transaction");
+
+    let net_income = total_income - total_expenses;
+
+    Ok(TransactionSummaryResponse {
+        total_income,
+        total_expenses,
+        net_income,
+        transaction_count,
+        average_transaction,
+    })
+}
+
+#[cfg(test)]
+mod idempotency_tests {
+    use super::*;
+    use crate::{
+        test_database::{sign_in, TestDatabase},
+        test_utils::TestUtils,
+    };
+    use tauri::Manager;
+
+    #[test]
+    fn test_validate_idempotency_key() {
+        assert!(validate_idempotency_key("retry-7f3c2a").is_ok());
+        assert!(validate_idempotency_key("client:2024-01-15.001_a").is_ok());
+        assert!(validate_idempotency_key("").is_err());
+        assert!(validate_idempotency_key("has spaces").is_err());
+        assert!(validate_idempotency_key("quote'injection").is_err());
+        assert!(validate_idempotency_key(&"a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_idempotency_key_names_field() {
+        match validate_idempotency_key("has spaces") {
+            Err(FiscusError::FieldValidation { field, code, .. }) => {
+                assert_eq!(field, "idempotency_key");
+                assert_eq!(code, "invalid_format");
+            }
+            other => panic!("expected FieldValidation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_idempotency_key_ttl_default() {
+        if std::env::var(IDEMPOTENCY_KEY_TTL_ENV).is_err() {
+            assert_eq!(IdempotencyConfig::from_env(), IdempotencyConfig::default());
+        }
+        assert_eq!(
+            IdempotencyConfig::default().ttl,
+            chrono::Duration::seconds(DEFAULT_IDEMPOTENCY_KEY_TTL_SECS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recorded_key_is_found_until_it_expires() {
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let db = test_db.database();
+        let user = test_db.seed_user("retrier").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let now = chrono::Utc::now();
+
+        let fresh = test_db
+            .seed_transaction(&user.id, &account.id, None, Decimal::new(1000, 2))
+            .await
+            .unwrap();
+        record_idempotency_key(&db, &user.id, "retry-1", &fresh, now)
+            .await
+            .unwrap();
+
+        let stale = test_db
+            .seed_transaction(&user.id, &account.id, None, Decimal::new(1000, 2))
+            .await
+            .unwrap();
+        let recorded_at = now - IdempotencyConfig::current().ttl - chrono::Duration::seconds(1);
+        record_idempotency_key(&db, &user.id, "retry-2", &stale, recorded_at)
+            .await
+            .unwrap();
+
+        let found = find_idempotent_transaction(&db, &user.id, "retry-1", &now.to_rfc3339())
+            .await
+            .unwrap();
+        assert_eq!(found, Some(fresh));
+
+        let expired = find_idempotent_transaction(&db, &user.id, "retry-2", &now.to_rfc3339())
+            .await
+            .unwrap();
+        assert_eq!(expired, None);
+        let remaining = test_db
+            .fetch_text(
+                "SELECT CAST(COUNT(*) AS TEXT) FROM idempotency_keys WHERE idempotency_key = ?1",
+                vec![Value::String("retry-2".to_string())],
+            )
+            .await
+            .unwrap();
+        assert_eq!(remaining, "0");
+    }
+
+    #[tokio::test]
+    async fn test_repeated_key_creates_one_row_and_one_balance_change() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let user = test_db.seed_user("double-submitter").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let _session = sign_in(&user.id).await;
+
+        let keyed_request = || {
+            let mut request = TestUtils::create_transaction_request(
+                &user.id,
+                &account.id,
+                Decimal::new(2550, 2),
+                "Groceries",
+            );
+            request.idempotency_key = Some("retry-1".to_string());
+            request
+        };
+        let first = create_transaction(keyed_request(), app.state())
+            .await
+            .unwrap();
+        let second = create_transaction(keyed_request(), app.state())
+            .await
+            .unwrap();
+
+        assert_eq!(first.id, second.id);
+        let rows = test_db
+            .fetch_text(
+                "SELECT CAST(COUNT(*) AS TEXT) FROM transactions WHERE account_id = ?1",
+                vec![Value::String(account.id.clone())],
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows, "1");
+        assert_eq!(
+            DatabaseUtils::get_account_balance(&test_db.database(), &account.id)
+                .await
+                .unwrap(),
+            Decimal::new(97450, 2)
+        );
+    }
+}
+
+#[cfg(test)]
+mod bulk_limit_tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_limit_enforced_at_configured_boundary() {
+        let config = BulkConfig::default();
+        assert!(config.validate_batch_size(DEFAULT_MAX_BULK_ITEMS).is_ok());
+
+        match config.validate_batch_size(DEFAULT_MAX_BULK_ITEMS + 1) {
+            Err(FiscusError::InvalidInput(message)) => {
+                assert!(message.contains(&DEFAULT_MAX_BULK_ITEMS.to_string()))
+            }
+            other => panic!("expected InvalidInput, got {other:?}"),
+        }
+
+        assert!(matches!(
+            config.validate_batch_size(0),
+            Err(FiscusError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_bulk_limit_follows_configuration() {
+        let raised = BulkConfig { max_items: 500 };
+        assert!(raised.validate_batch_size(500).is_ok());
+        assert!(raised.validate_batch_size(501).is_err());
+
+        let lowered = BulkConfig { max_items: 10 };
+        assert!(lowered.validate_batch_size(10).is_ok());
+        assert!(lowered.validate_batch_size(11).is_err());
+        assert!(lowered.validate_batch_size(100).is_err());
+    }
+}
+
+#[cfg(test)]
+mod void_transfer_tests {
+    use super::*;
+
+    fn transfer_with_status(status: TransactionStatus) -> Transfer {
+        let now = chrono::Utc::now();
+        Transfer {
+            id: Uuid::new_v4().to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            to_user_id: None,
+            from_account_id: Uuid::new_v4().to_string(),
+            to_account_id: Uuid::new_v4().to_string(),
+            amount: Decimal::new(25000, 2),
+            description: "Savings".to_string(),
+            transfer_date: now,
+            status,
+            from_transaction_id: Uuid::new_v4().to_string(),
+            to_transaction_id: Uuid::new_v4().to_string(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_void_restores_pre_transfer_balances() {
+        let from_before = Decimal::new(100000, 2);
+        let to_before = Decimal::new(5000, 2);
+        let amount = Decimal::new(25000, 2);
+
+        let (from_delta, to_delta) = transfer_balance_deltas(amount);
+        let (from_after, to_after) = (from_before + from_delta, to_before + to_delta);
+        assert_eq!(from_after, Decimal::new(75000, 2));
+        assert_eq!(to_after, Decimal::new(30000, 2));
+
+        let (from_delta, to_delta) = transfer_balance_deltas(-amount);
+        assert_eq!(from_after + from_delta, from_before);
+        assert_eq!(to_after + to_delta, to_before);
+    }
+
+    fn fee_transfer_request(fee_amount: Option<Decimal>) -> CreateTransferRequest {
+        CreateTransferRequest {
+            user_id: ValidatedUserId::new("550e8400-e29b-41d4-a716-446655440000").unwrap(),
+            from_account_id: Uuid::new_v4().to_string(),
+            to_account_id: Uuid::new_v4().to_string(),
+            amount: Decimal::new(50000, 2),
+            description: "Wire to savings".to_string(),
+            transfer_date: chrono::Utc::now().to_rfc3339(),
+            fee_amount,
+            fee_category_id: fee_amount.map(|_| "bank-fees".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_transfer_fee_is_charged_to_source_only() {
+        let request = fee_transfer_request(Some(Decimal::new(1500, 2)));
+        let fee = transfer_fee_transaction(&request, "transfer", chrono::Utc::now());
+
+        let (from_delta, to_delta) = transfer_balance_deltas_with_fee(request.amount, fee.as_ref());
+        assert_eq!(Decimal::new(100000, 2) + from_delta, Decimal::new(48500, 2));
+        assert_eq!(Decimal::ZERO + to_delta, request.amount);
+    }
+
+    #[test]
+    fn test_transfer_fee_is_a_categorized_source_expense() {
+        let request = fee_transfer_request(Some(Decimal::new(1500, 2)));
+        let fee = transfer_fee_transaction(&request, "transfer", chrono::Utc::now())
+            .expect("fee transaction");
+
+        assert_eq!(fee.account_id, request.from_account_id);
+        assert_eq!(fee.category_id.as_deref(), Some("bank-fees"));
+        assert_eq!(fee.transaction_type, TransactionType::Expense);
+        assert_eq!(fee.amount, Decimal::new(1500, 2));
+        assert_eq!(
+            fee.reference_number.as_deref(),
+            Some("transfer-fee:transfer")
+        );
+
+        let no_fee = fee_transfer_request(None);
+        assert!(transfer_fee_transaction(&no_fee, "transfer", chrono::Utc::now()).is_none());
+        assert_eq!(
+            transfer_balance_deltas_with_fee(no_fee.amount, None),
+            transfer_balance_deltas(no_fee.amount)
+        );
+    }
+
+    #[test]
+    fn test_double_void_is_rejected() {
+        let user_id = "550e8400-e29b-41d4-a716-446655440000";
+
+        let active = transfer_with_status(TransactionStatus::Completed);
+        assert!(ensure_transfer_voidable(&active, user_id).is_ok());
+
+        let voided = transfer_with_status(TransactionStatus::Voided);
+        assert!(matches!(
+            ensure_transfer_voidable(&voided, user_id),
+            Err(FiscusError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn test_void_requires_owner() {
+        let transfer = transfer_with_status(TransactionStatus::Completed);
+        assert!(matches!(
+            ensure_transfer_voidable(&transfer, "660e8400-e29b-41d4-a716-446655440000"),
+            Err(FiscusError::Authorization(_))
+        ));
+    }
+
+    fn leg_value<'a>(params: &'a [(String, Value)], column: &str) -> &'a str {
+        params
+            .iter()
+            .find(|(name, _)| name == column)
+            .and_then(|(_, value)| value.as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cross_user_transfer_legs_belong_to_each_owner() {
+        let amount = Decimal::new(4000, 2);
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let outgoing = transfer_leg_params(
+            "from-transaction",
+            "sender",
+            "sender-account",
+            -amount,
+            "Transfer to another user: Rent share".to_string(),
+            &now,
+        )
+        .unwrap();
+        let incoming = transfer_leg_params(
+            "to-transaction",
+            "recipient",
+            "recipient-account",
+            amount,
+            "Transfer from another user: Rent share".to_string(),
+            &now,
+        )
+        .unwrap();
+
+        assert_eq!(leg_value(&outgoing, "user_id"), "sender");
+        assert_eq!(leg_value(&outgoing, "account_id"), "sender-account");
+        assert_eq!(leg_value(&outgoing, "amount"), "-40.00");
+        assert_eq!(leg_value(&incoming, "user_id"), "recipient");
+        assert_eq!(leg_value(&incoming, "account_id"), "recipient-account");
+        assert_eq!(leg_value(&incoming, "amount"), "40.00");
+        assert_eq!(leg_value(&incoming, "transaction_type"), "transfer");
+
+        let (sender_delta, recipient_delta) = transfer_balance_deltas(amount);
+        assert_eq!(Decimal::new(10000, 2) + sender_delta, Decimal::new(6000, 2));
+        assert_eq!(Decimal::ZERO + recipient_delta, amount);
+    }
+
+    #[test]
+    fn test_cross_user_transfer_sender_must_be_the_session_user() {
+        let alice = "550e8400-e29b-41d4-a716-446655440000";
+        let bob_session = crate::security::SecurityContext::owner(
+            "660e8400-e29b-41d4-a716-446655440000".to_string(),
+        );
+
+        // Bob cannot send from Alice's account by naming her as the sender
+        assert!(matches!(
+            crate::security::ensure_context_user(Some(&bob_session), alice),
+            Err(FiscusError::Authorization(_))
+        ));
+        assert!(matches!(
+            crate::security::ensure_context_user(None, alice),
+            Err(FiscusError::Authorization(_))
+        ));
+
+        let alice_session = crate::security::SecurityContext::owner(alice.to_string());
+        assert!(crate::security::ensure_context_user(Some(&alice_session), alice).is_ok());
+    }
+
+    #[test]
+    fn test_recipient_defaults_to_transfer_owner() {
+        let mut transfer = transfer_with_status(TransactionStatus::Completed);
+        assert_eq!(transfer.recipient_user_id(), transfer.user_id);
+
+        transfer.to_user_id = Some("660e8400-e29b-41d4-a716-446655440000".to_string());
+        assert_eq!(
+            transfer.recipient_user_id(),
+            "660e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tag_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    fn tags_row(tags: &[&str]) -> HashMap<String, Value> {
+        HashMap::from([(
+            "tags".to_string(),
+            Value::String(serde_json::to_string(tags).unwrap()),
+        )])
+    }
+
+    fn tagged_transaction(tags: Option<Vec<&str>>) -> Transaction {
+        let mut transaction = TestUtils::create_test_transaction(
+            "user-1",
+            "account-1",
+            Decimal::new(1000, 2),
+            TransactionType::Expense,
+        );
+        transaction.tags = tags.map(|tags| tags.into_iter().map(String::from).collect());
+        transaction
+    }
+
+    #[test]
+    fn test_tag_usage_counts() {
+        let rows = vec![
+            tags_row(&["groceries", "weekly"]),
+            tags_row(&["Groceries"]),
+            tags_row(&["travel", "travel"]),
+            tags_row(&[]),
+            HashMap::from([("tags".to_string(), Value::String("not json".to_string()))]),
+        ];
+
+        let usage = count_tag_usage(&rows);
+
+        assert_eq!(
+            usage,
+            vec![
+                TagUsage {
+                    tag: "groceries".to_string(),
+                    count: 2
+                },
+                TagUsage {
+                    tag: "travel".to_string(),
+                    count: 1
+                },
+                TagUsage {
+                    tag: "weekly".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_user_tags_are_read_for_the_signed_in_user() {
+        let test_db = crate::test_database::TestDatabase::in_memory()
+            .await
+            .unwrap();
+        let app = test_db.app();
+        let user = test_db.seed_user("tagger").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        for tags in [r#"["groceries","weekly"]"#, r#"["Groceries"]"#] {
+            let id = test_db
+                .seed_transaction(&user.id, &account.id, None, Decimal::new(1000, 2))
+                .await
+                .unwrap();
+            test_db
+                .execute(
+                    "UPDATE transactions SET tags = ?1 WHERE id = ?2",
+                    vec![Value::String(tags.to_string()), Value::String(id)],
+                )
+                .await
+                .unwrap();
+        }
+        let _session = crate::test_database::sign_in(&user.id).await;
+
+        let usage = get_user_tags(user.id.clone(), tauri::Manager::state(&app))
+            .await
+            .unwrap();
+        assert_eq!(
+            usage,
+            vec![
+                TagUsage {
+                    tag: "groceries".to_string(),
+                    count: 2
+                },
+                TagUsage {
+                    tag: "weekly".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_transactions_by_tag() {
+        let transactions = vec![
+            tagged_transaction(Some(vec!["groceries", "weekly"])),
+            tagged_transaction(Some(vec!["travel"])),
+            tagged_transaction(None),
+            tagged_transaction(Some(vec!["Groceries"])),
+        ];
+
+        let filtered = filter_transactions_by_tag(transactions, "groceries");
+
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered.iter().all(|t| t
+            .tags
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|tag| tag.eq_ignore_ascii_case("groceries"))));
+    }
+
+    #[test]
+    fn test_paginate_in_memory_matches_limit_clause() {
+        let items: Vec<i32> = (0..10).collect();
+
+        assert_eq!(paginate_in_memory(items.clone(), None, None).len(), 10);
+        assert_eq!(
+            paginate_in_memory(items.clone(), Some(3), Some(8)),
+            vec![8, 9]
+        );
+        assert_eq!(paginate_in_memory(items, Some(0), None), vec![0]);
+    }
+
+    fn tag_list(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn test_too_many_tags_rejected() {
+        let config = TagConfig {
+            max_tags: 2,
+            ..TagConfig::default()
+        };
+        assert!(config.normalize_tags(&tag_list(&["a", "b"])).is_ok());
+
+        match config.normalize_tags(&tag_list(&["a", "b", "c"])) {
+            Err(FiscusError::FieldValidation { field, code, .. }) => {
+                assert_eq!(field, "tags");
+                assert_eq!(code, "too_many");
+            }
+            other => panic!("expected FieldValidation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_over_long_and_invalid_tags_rejected() {
+        let config = TagConfig::default();
+        let long_tag = "x".repeat(DEFAULT_MAX_TAG_LENGTH + 1);
+
+        match config.normalize_tags(&[long_tag]) {
+            Err(FiscusError::FieldValidation { field, code, .. }) => {
+                assert_eq!(field, "tags");
+                assert_eq!(code, "too_long");
+            }
+            other => panic!("expected FieldValidation, got {other:?}"),
+        }
+        assert!(config
+            .normalize_tags(&["x".repeat(DEFAULT_MAX_TAG_LENGTH)])
+            .is_ok());
+        assert!(config.normalize_tags(&tag_list(&["<script>"])).is_err());
+        assert!(config.normalize_tags(&tag_list(&["   "])).is_err());
+    }
+
+    #[test]
+    fn test_mixed_case_tags_normalized() {
+        let tags = tag_list(&["  Groceries ", "groceries", "Weekly-Shop"]);
+
+        let preserved = TagConfig::default().normalize_tags(&tags).unwrap();
+        assert_eq!(
+            preserved,
+            tag_list(&["Groceries", "groceries", "Weekly-Shop"])
+        );
+
+        let lowercased = TagConfig {
+            lowercase: true,
+            ..TagConfig::default()
+        }
+        .normalize_tags(&tags)
+        .unwrap();
+        assert_eq!(lowercased, tag_list(&["groceries", "weekly-shop"]));
+    }
+}
+
+#[cfg(test)]
+mod duplicate_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn transaction_on(day: u32, month: u32, description: &str) -> Transaction {
+        let mut transaction = TestUtils::create_test_transaction(
+            "user-1",
+            "account-1",
+            Decimal::new(4250, 2),
+            TransactionType::Expense,
+        );
+        transaction.description = description.to_string();
+        transaction.transaction_date = Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+        transaction
+    }
+
+    #[test]
+    fn test_identical_same_day_transactions_are_flagged() {
+        let first = transaction_on(10, 3, "Coffee Shop");
+        let second = transaction_on(10, 3, "  coffee   shop ");
+
+        let clusters = find_duplicate_clusters(&[first.clone(), second.clone()], 7);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].account_id, "account-1");
+        assert_eq!(clusters[0].amount, Decimal::new(4250, 2));
+
+        let mut ids = clusters[0].transaction_ids.clone();
+        ids.sort();
+        let mut expected = vec![first.id, second.id];
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_same_amount_a_month_apart_is_not_flagged() {
+        let first = transaction_on(10, 3, "Coffee Shop");
+        let second = transaction_on(10, 4, "Coffee Shop");
+
+        assert!(find_duplicate_clusters(&[first, second], 7).is_empty());
+    }
+
+    #[test]
+    fn test_different_accounts_or_descriptions_are_not_flagged() {
+        let first = transaction_on(10, 3, "Coffee Shop");
+        let mut other_account = transaction_on(10, 3, "Coffee Shop");
+        other_account.account_id = "account-2".to_string();
+        let other_description = transaction_on(10, 3, "Bakery");
+
+        assert!(find_duplicate_clusters(&[first, other_account, other_description], 7).is_empty());
+    }
+
+    #[test]
+    fn test_refund_sign_and_voided_status() {
+        let first = transaction_on(10, 3, "Coffee Shop");
+        let mut refund = transaction_on(11, 3, "Coffee Shop");
+        refund.amount = -refund.amount;
+        let mut voided = transaction_on(10, 3, "Coffee Shop");
+        voided.status = TransactionStatus::Voided;
+        voided.transaction_date += Duration::hours(1);
+
+        let clusters = find_duplicate_clusters(&[first, refund, voided.clone()], 7);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].transaction_ids.len(), 2);
+        assert!(!clusters[0].transaction_ids.contains(&voided.id));
+    }
+}
+
+#[cfg(test)]
+mod cursor_tests {
+    use super::*;
+    use crate::{
+        test_database::{sign_in, TestDatabase},
+        test_utils::TestUtils,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::HashSet;
+    use tauri::{test::MockRuntime, App, Manager};
+
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+    fn transaction_at(minutes: i64) -> Transaction {
+        let mut transaction = TestUtils::create_test_transaction(
+            USER_ID,
+            "account-1",
+            Decimal::new(1000, 2),
+            TransactionType::Expense,
+        );
+        transaction.transaction_date =
+            Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap() + Duration::minutes(minutes);
+        transaction
+    }
+
+    /// Insert an expense dated `minutes` after the first test timestamp
+    async fn insert_at(
+        test_db: &TestDatabase,
+        user_id: &str,
+        account_id: &str,
+        minutes: i64,
+    ) -> String {
+        let id = test_db
+            .seed_transaction(user_id, account_id, None, Decimal::new(1000, 2))
+            .await
+            .unwrap();
+        test_db
+            .execute(
+                "UPDATE transactions SET transaction_date = ?1 WHERE id = ?2",
+                vec![
+                    Value::String(transaction_at(minutes).transaction_date.to_rfc3339()),
+                    Value::String(id.clone()),
+                ],
+            )
+            .await
+            .unwrap();
+        id
+    }
+
+    /// Ids on one page of `get_transactions_by_cursor` and the cursor for the next
+    async fn fetch_page(
+        app: &App<MockRuntime>,
+        user_id: &str,
+        cursor: Option<&str>,
+        limit: i32,
+    ) -> (Vec<String>, Option<String>) {
+        let mut filters = TestUtils::default_transaction_filters(user_id);
+        filters.cursor = cursor.map(str::to_string);
+        filters.limit = Some(limit);
+
+        let page = get_transactions_by_cursor(filters, app.state())
+            .await
+            .unwrap();
+        (
+            page.data.into_iter().map(|t| t.id).collect(),
+            page.next_cursor,
+        )
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let transaction = transaction_at(5);
+        let cursor = TransactionCursor::after(&transaction);
+
+        assert_eq!(TransactionCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(TransactionCursor::decode("not a cursor").is_err());
+
+        let forged = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode("2024-03-01T09:00:00+00:00|1 OR 1=1");
+        assert!(TransactionCursor::decode(&forged).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pages_have_no_duplicates_or_gaps_across_inserts() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let user = test_db.seed_user("pager").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let _session = sign_in(&user.id).await;
+
+        // Several rows share a timestamp so the id tie-breaker matters
+        let mut original_ids = HashSet::new();
+        for i in 0..10 {
+            original_ids.insert(insert_at(&test_db, &user.id, &account.id, i / 2).await);
+        }
+
+        let mut seen = Vec::new();
+        let (page, mut cursor) = fetch_page(&app, &user.id, None, 3).await;
+        seen.extend(page);
+
+        // A new transaction arrives between pages
+        insert_at(&test_db, &user.id, &account.id, 60).await;
+
+        while let Some(current) = cursor {
+            let (page, next) = fetch_page(&app, &user.id, Some(&current), 3).await;
+            seen.extend(page);
+            cursor = next;
+        }
+
+        let unique: HashSet<String> = seen.iter().cloned().collect();
+        assert_eq!(unique.len(), seen.len(), "a row was returned twice");
+        assert_eq!(unique, original_ids, "a row was skipped");
+    }
+
+    #[tokio::test]
+    async fn test_last_page_has_no_next_cursor() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let user = test_db.seed_user("last-pager").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let _session = sign_in(&user.id).await;
+        for minutes in 0..4 {
+            insert_at(&test_db, &user.id, &account.id, minutes).await;
+        }
+
+        let (first, cursor) = fetch_page(&app, &user.id, None, 2).await;
+        assert_eq!(first.len(), 2);
+        let (second, cursor) = fetch_page(&app, &user.id, cursor.as_deref(), 2).await;
+        assert_eq!(second.len(), 2);
+        let (last, cursor) = fetch_page(&app, &user.id, cursor.as_deref(), 2).await;
+        assert!(last.is_empty());
+        assert!(cursor.is_none());
+
+        let (short, cursor) = fetch_page(&app, &user.id, None, 10).await;
+        assert_eq!(short.len(), 4);
+        assert!(cursor.is_none());
+    }
+
+    #[test]
+    fn test_keyset_rejects_offset_and_other_orderings() {
+        let mut filters = TestUtils::default_transaction_filters(USER_ID);
+        assert!(validate_keyset_filters(&filters).is_ok());
+
+        filters.sort_direction = Some("DESC".to_string());
+        assert!(validate_keyset_filters(&filters).is_ok());
+
+        filters.offset = Some(20);
+        assert!(validate_keyset_filters(&filters).is_err());
+
+        filters.offset = None;
+        filters.sort_by = Some("amount".to_string());
+        assert!(validate_keyset_filters(&filters).is_err());
+
+        filters.sort_by = None;
+        filters.sort_direction = Some("asc".to_string());
+        assert!(validate_keyset_filters(&filters).is_err());
+    }
+}
+
+#[cfg(test)]
+mod amount_minor_tests {
+    use super::*;
+
+    #[test]
+    fn test_minor_unit_storage_is_off_by_default() {
+        assert!(!AmountStorageConfig::default().store_minor_units);
+        if std::env::var(STORE_AMOUNT_MINOR_UNITS_ENV).is_err() {
+            assert_eq!(
+                AmountStorageConfig::from_env(),
+                AmountStorageConfig::default()
+            );
+        }
+    }
+
+    #[test]
+    fn test_amount_minor_value_is_null_when_disabled() {
+        let value = amount_minor_value(Decimal::new(12345, 2), false).unwrap();
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn test_amount_minor_value_orders_numerically() {
+        let small = amount_minor_value(Decimal::new(900, 2), true).unwrap();
+        let large = amount_minor_value(Decimal::new(10000, 2), true).unwrap();
+
+        assert_eq!(small, Value::from(900_i64));
+        assert_eq!(large, Value::from(10000_i64));
+        // As decimal strings "9.00" sorts after "100.00"; as minor units it does not
+        assert!(small.as_i64() < large.as_i64());
+    }
+
+    #[test]
+    fn test_amount_minor_value_keeps_sign() {
+        let value = amount_minor_value(Decimal::new(-2550, 2), true).unwrap();
+        assert_eq!(value, Value::from(-2550_i64));
+    }
+}
+
+#[cfg(test)]
+mod event_tests {
+    use super::*;
+    use crate::{
+        test_database::{sign_in, TestDatabase},
+        test_utils::TestUtils,
+    };
+    use tauri::Manager;
+    use tokio::sync::broadcast::Receiver;
+
+    /// Events published so far for one user, ignoring other tests on the shared bus
+    fn drain_for_user(
+        receiver: &mut Receiver<TransactionEvent>,
+        user_id: &str,
+    ) -> Vec<TransactionEvent> {
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter(|event| event.user_id() == user_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_committed_create_fires_one_created_event() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let user = test_db.seed_user("event-creator").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let _session = sign_in(&user.id).await;
+        let mut receiver = events::subscribe();
+
+        let request = TestUtils::create_transaction_request(
+            &user.id,
+            &account.id,
+            Decimal::new(2550, 2),
+            "Groceries",
+        );
+        let transaction = create_transaction(request, app.state()).await.unwrap();
+
+        let received = drain_for_user(&mut receiver, &user.id);
+        let created: Vec<_> = received
+            .iter()
+            .filter(|event| matches!(event, TransactionEvent::TransactionCreated { .. }))
+            .collect();
+        assert_eq!(created.len(), 1);
+        assert_eq!(
+            created[0],
+            &TransactionEvent::TransactionCreated {
+                user_id: user.id.clone(),
+                transaction_id: transaction.id,
+                account_id: account.id.clone(),
+            }
+        );
+        assert!(received
+            .iter()
+            .any(|event| matches!(event, TransactionEvent::BalanceChanged { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_rolled_back_create_fires_nothing() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+        let user = test_db.seed_user("event-rollback").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let _session = sign_in(&user.id).await;
+        let mut receiver = events::subscribe();
+
+        // The refunded expense does not exist, so the insert's transaction rolls back
+        let mut request = TestUtils::create_transaction_request(
+            &user.id,
+            &account.id,
+            Decimal::new(2550, 2),
+            "Refund",
+        );
+        request.transaction_type = Some(TransactionType::Income);
+        request.refunds_transaction_id = Some(Uuid::new_v4().to_string());
+        let result = create_transaction(request, app.state()).await;
+
+        assert!(matches!(result, Err(FiscusError::NotFound(_))));
+        assert!(drain_for_user(&mut receiver, &user.id).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers_is_noop() {
+        let published = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            tokio::task::spawn_blocking(|| {
+                events::publish(transaction_created_events(
+                    "nobody",
+                    "transaction-1",
+                    "account-1",
+                    &TransactionType::Income,
+                ))
+            }),
+        )
+        .await;
+
+        assert!(matches!(published, Ok(Ok(()))));
+    }
+
+    #[test]
+    fn test_transfer_events_cover_both_legs() {
+        let events = transfer_created_events(
+            ("sender", "tx-out", "account-a"),
+            ("recipient", "tx-in", "account-b"),
+        );
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].user_id(), "sender");
+        assert_eq!(events[2].user_id(), "recipient");
+        assert_eq!(events[3].name(), "balance-changed");
+    }
+}
+
+#[cfg(test)]
+mod request_validation_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const ACCOUNT_ID: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+
+    #[test]
+    fn test_valid_request_passes() {
+        let request = TestUtils::create_transaction_request(
+            USER_ID,
+            ACCOUNT_ID,
+            Decimal::new(1250, 2),
+            "Groceries",
+        );
+
+        assert!(validate_create_transaction_request(&request).is_ok());
+    }
+
+    #[test]
+    fn test_empty_description_names_field() {
+        let request =
+            TestUtils::create_transaction_request(USER_ID, ACCOUNT_ID, Decimal::new(1250, 2), "");
+
+        match validate_create_transaction_request(&request) {
+            Err(FiscusError::FieldValidation {
+                field,
+                code,
+                message,
+            }) => {
+                assert_eq!(field, "description");
+                assert_eq!(code, "required");
+                assert!(message.contains("description"));
+            }
+            other => panic!("expected FieldValidation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_account_id_names_field() {
+        let request = TestUtils::create_transaction_request(
+            USER_ID,
+            "not-a-uuid",
+            Decimal::new(1250, 2),
+            "Groceries",
+        );
+
+        assert!(matches!(
+            validate_create_transaction_request(&request),
+            Err(FiscusError::FieldValidation { ref field, .. }) if field == "account_id"
+        ));
+    }
+
+    #[test]
+    fn test_original_amount_requires_currency() {
+        let mut request = TestUtils::create_transaction_request(
+            USER_ID,
+            ACCOUNT_ID,
+            Decimal::new(1250, 2),
+            "Groceries",
+        );
+        request.original_amount = Some(Decimal::new(1000, 2));
+
+        assert!(matches!(
+            validate_create_transaction_request(&request),
+            Err(FiscusError::FieldValidation { ref field, .. }) if field == "original_currency"
+        ));
+    }
+
+    #[test]
+    fn test_foreign_original_posts_converted_amount() {
+        let mut request = TestUtils::create_transaction_request(
+            USER_ID,
+            ACCOUNT_ID,
+            Decimal::new(10000, 2),
+            "Hotel in Paris",
+        );
+        request.original_amount = Some(Decimal::new(10000, 2));
+        request.original_currency = Some(crate::error::ValidatedCurrency::new("EUR").unwrap());
+        assert!(validate_create_transaction_request(&request).is_ok());
+
+        // 1 EUR = 1.0853 USD
+        let posted = convert_amount(Decimal::new(10000, 2), Decimal::new(10853, 4), "USD");
+        assert_eq!(posted, Decimal::new(10853, 2));
+
+        let balance = Decimal::new(100000, 2) + balance_delta(posted, &TransactionType::Expense);
+        assert_eq!(balance, Decimal::new(89147, 2));
+
+        // The stored record keeps the original next to the converted amount
+        let row = serde_json::json!({
+            "id": Uuid::new_v4().to_string(),
+            "user_id": USER_ID,
+            "account_id": ACCOUNT_ID,
+            "category_id": null,
+            "amount": posted.to_string(),
+            "description": "Hotel in Paris",
+            "notes": null,
+            "transaction_date": "2024-03-01T12:00:00Z",
+            "transaction_type": "expense",
+            "status": "completed",
+            "reference_number": null,
+            "payee": null,
+            "tags": null,
+            "original_amount": "100.00",
+            "original_currency": "EUR",
+            "created_at": "2024-03-01T12:00:00Z",
+            "updated_at": "2024-03-01T12:00:00Z",
+        });
+        let transaction: Transaction = serde_json::from_value(row).unwrap();
+
+        assert_eq!(transaction.amount, Decimal::new(10853, 2));
+        assert_eq!(transaction.original_amount, Some(Decimal::new(10000, 2)));
+        assert_eq!(transaction.original_currency.as_deref(), Some("EUR"));
+    }
+}
+
+#[cfg(test)]
+mod in_memory_database_tests {
+    use super::*;
+    use crate::{
+        test_database::{sign_in, TestDatabase},
+        test_utils::TestUtils,
+    };
+    use tauri::Manager;
+
+    #[tokio::test]
+    async fn test_expense_reduces_seeded_account_balance() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let app = test_db.app();
+
+        let user = test_db.seed_user("alice").await.unwrap();
+        let account = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let _session = sign_in(&user.id).await;
+
+        let request = TestUtils::create_transaction_request(
+            &user.id,
+            &account.id,
+            Decimal::new(2550, 2),
+            "Groceries",
+        );
+        let transaction = create_transaction(request, app.state()).await.unwrap();
+        assert_eq!(transaction.amount, Decimal::new(2550, 2));
+
+        assert_eq!(
+            DatabaseUtils::get_account_balance(&test_db.database(), &account.id)
+                .await
+                .unwrap(),
+            Decimal::new(97450, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_restores_each_account_balance() {
+        crate::commands::encryption::initialize_encryption_service().unwrap();
+        let test_db = TestDatabase::in_memory().await.unwrap();
+        let db = test_db.database();
+
+        let user = test_db.seed_user("bulk-deleter").await.unwrap();
+        let checking = test_db
+            .seed_account(&user.id, Decimal::new(100000, 2))
+            .await
+            .unwrap();
+        let savings = test_db
+            .seed_account(&user.id, Decimal::new(50000, 2))
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for (account_id, amount) in [
+            (&checking.id, Decimal::new(1000, 2)),
+            (&checking.id, Decimal::new(2550, 2)),
+            (&savings.id, Decimal::new(500, 2)),
+        ] {
+            ids.push(
+                test_db
+                    .seed_transaction(&user.id, account_id, None, amount)
+                    .await
+                    .unwrap(),
+            );
+        }
 
-        DatabaseUtils::execute_non_query(&db, from_transaction_query, encrypted_from_params)
-            .await?;
+        bulk_delete_transactions(ids, &user.id, &db).await.unwrap();
 
-        // Create incoming transaction (income)
-        let to_transaction_query = r#"
-            INSERT INTO transactions (
-                id, user_id, account_id, amount, description, transaction_date,
-                transaction_type, status, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-        "#;
+        assert_eq!(
+            DatabaseUtils::get_account_balance(&db, &checking.id)
+                .await
+                .unwrap(),
+            Decimal::new(103550, 2)
+        );
+        assert_eq!(
+            DatabaseUtils::get_account_balance(&db, &savings.id)
+                .await
+                .unwrap(),
+            Decimal::new(50500, 2)
+        );
+    }
+}
 
-        // Use encrypted parameter mapping for incoming transaction
-        let to_params_with_mapping = vec![
-            ("id".to_string(), Value::String(to_transaction_id)),
-            (
-                "user_id".to_string(),
-                Value::String(request.user_id.to_string()),
-            ),
-            (
-                "account_id".to_string(),
-                Value::String(request.to_account_id.clone()),
-            ),
-            (
-                "amount".to_string(),
-                Value::String(request.amount.to_string()),
-            ), // Positive for incoming
-            (
-                "description".to_string(),
-                Value::String(format!("Transfer from account: {}", request.description)),
-            ),
-            (
-                "transaction_date".to_string(),
-                Value::String(transfer_date.to_rfc3339()),
-            ),
-            (
-                "transaction_type".to_string(),
-                Value::String(TransactionType::Transfer.to_string()),
-            ),
-            (
-                "status".to_string(),
-                Value::String(TransactionStatus::Completed.to_string()),
-            ),
-            ("created_at".to_string(), Value::String(now.clone())),
-            ("updated_at".to_string(), Value::String(now)),
-        ];
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
 
-        let encrypted_to_params = EncryptedDatabaseUtils::encrypt_params_with_mapping(
-            to_params_with_mapping,
-            &request.user_id.as_str(),
-            "transactions",
-        )
-        .await?;
+    fn dollars(amounts: &[i64]) -> Vec<Decimal> {
+        let mut amounts: Vec<Decimal> = amounts.iter().map(|a| Decimal::new(*a, 0)).collect();
+        amounts.sort();
+        amounts
+    }
 
-        DatabaseUtils::execute_non_query(&db, to_transaction_query, encrypted_to_params).await?;
+    #[test]
+    fn test_percentiles_of_known_distribution() {
+        let amounts = dollars(&[7, 1, 10, 4, 2, 9, 3, 6, 8, 5]);
 
-        // Update account balances
-        let from_balance =
-            DatabaseUtils::get_account_balance(&db, &request.from_account_id).await?;
-        let to_balance = DatabaseUtils::get_account_balance(&db, &request.to_account_id).await?;
+        assert_eq!(percentile(&amounts, 50), Some(Decimal::new(550, 2)));
+        assert_eq!(percentile(&amounts, 90), Some(Decimal::new(910, 2)));
+        assert_eq!(percentile(&amounts, 95), Some(Decimal::new(955, 2)));
+        assert_eq!(percentile(&amounts, 100), Some(Decimal::new(10, 0)));
+    }
 
-        DatabaseUtils::update_account_balance(
-            &db,
-            &request.from_account_id,
-            from_balance - request.amount,
-        )
-        .await?;
-        DatabaseUtils::update_account_balance(
-            &db,
-            &request.to_account_id,
-            to_balance + request.amount,
-        )
-        .await?;
+    #[test]
+    fn test_odd_count_median_is_middle_amount() {
+        let amounts = dollars(&[120, 15, 40, 300, 60]);
 
-        Ok::<(), FiscusError>(())
-    })?;
+        assert_eq!(percentile(&amounts, 50), Some(Decimal::new(60, 0)));
+    }
 
-    // Return the created transfer
-    get_transfer_by_id(transfer_id, db).await
+    #[test]
+    fn test_small_datasets_do_not_panic() {
+        assert_eq!(percentile(&[], 50), None);
+
+        let single = dollars(&[42]);
+        assert_eq!(percentile(&single, 50), Some(Decimal::new(42, 0)));
+        assert_eq!(percentile(&single, 95), Some(Decimal::new(42, 0)));
+
+        let pair = dollars(&[10, 20]);
+        assert_eq!(percentile(&pair, 50), Some(Decimal::new(15, 0)));
+        assert_eq!(percentile(&pair, 90), Some(Decimal::new(19, 0)));
+    }
 }
 
-/// Get a transfer by ID
-#[tauri::command]
-pub async fn get_transfer_by_id(
-    transfer_id: String,
-    db: State<'_, Database>,
-) -> Result<Transfer, FiscusError> {
-    Validator::validate_uuid(&transfer_id, "transfer_id")?;
+#[cfg(test)]
+mod payee_search_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_exact_payee_filter_matches_only_that_payee() {
+        crate::commands::encryption::initialize_encryption_service()
+            .expect("Failed to initialize encryption service for test");
+        let user_id = "payee-search-user";
+
+        let mut rows = Vec::new();
+        for payee in [
+            Some("Corner Grocery"),
+            Some("Gas Station"),
+            Some("corner  grocery "),
+            Some("Corner Grocery Outlet"),
+            None,
+        ] {
+            rows.push((payee, payee_index_value(payee, user_id).await.unwrap()));
+        }
 
-    // First, get the user_id for this transfer (this field is not encrypted)
-    let user_query = "SELECT user_id FROM transfers WHERE id = ?1";
-    let user_result: Option<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query_single(
-            &db,
-            user_query,
-            vec![Value::String(transfer_id.clone())],
+        let index = EncryptedDatabaseUtils::blind_index_value(
+            "Corner Grocery",
+            user_id,
+            "transactions",
+            "payee",
         )
-        .await?;
-
-    let user_id = user_result
-        .and_then(|row| {
-            row.get("user_id")
-                .and_then(|v| v.as_str().map(|s| s.to_string()))
-        })
-        .ok_or_else(|| FiscusError::NotFound("Transfer not found".to_string()))?;
+        .await
+        .unwrap();
+        let matching: Vec<Option<&str>> = rows
+            .iter()
+            .filter(|(_, stored)| stored.as_str() == Some(index.as_str()))
+            .map(|(payee, _)| *payee)
+            .collect();
+
+        assert_eq!(
+            matching,
+            vec![Some("Corner Grocery"), Some("corner  grocery ")]
+        );
+        assert_eq!(rows[4].1, Value::Null);
+    }
 
-    let query = r#"
-        SELECT id, user_id, from_account_id, to_account_id, amount, description,
-               transfer_date, status, from_transaction_id, to_transaction_id,
-               created_at, updated_at
-        FROM transfers
-        WHERE id = ?1
-    "#;
+    #[test]
+    fn test_payee_index_filter_is_an_equality_condition() {
+        let filter_map = HashMap::from([("payee_index".to_string(), "bidx:abc".to_string())]);
+        SecurityValidator::validate_transaction_filter_fields(&filter_map).unwrap();
 
-    // Use encrypted query to properly decrypt sensitive fields
-    let transfers: Vec<Transfer> = EncryptedDatabaseUtils::execute_encrypted_query(
-        &db,
-        query,
-        vec![Value::String(transfer_id.clone())],
-        &user_id,
-        "transfers",
-    )
-    .await?;
+        let (where_clause, params) =
+            DatabaseUtils::build_where_clause(&filter_map, &["payee_index"], vec![]).unwrap();
 
-    transfers
-        .into_iter()
-        .next()
-        .ok_or_else(|| FiscusError::NotFound("Transfer not found".to_string()))
+        assert!(where_clause.contains("`payee_index` = ?1"));
+        assert_eq!(params, vec![Value::String("bidx:abc".to_string())]);
+    }
 }
 
-/// Bulk operations on transactions
-#[tauri::command]
-pub async fn bulk_transaction_operations(
-    request: BulkTransactionRequest,
-    db: State<'_, Database>,
-) -> Result<String, FiscusError> {
-    // Validate user (already validated by ValidatedUserId)
-    DatabaseUtils::validate_user_exists(&db, &request.user_id.as_str()).await?;
+#[cfg(test)]
+mod csv_export_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    fn awkward_transaction() -> Transaction {
+        let mut transaction = TestUtils::create_test_transaction(
+            "550e8400-e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-446655440001",
+            Decimal::new(123456, 2),
+            TransactionType::Expense,
+        );
+        transaction.description = "Dinner, drinks; tip".to_string();
+        transaction.payee = Some("The \"Corner\" Bistro".to_string());
+        transaction.notes = Some("Split with Sam\r\nPaid back, mostly".to_string());
+        transaction
+    }
 
-    // Validate transaction IDs
-    for transaction_id in &request.transaction_ids {
-        Validator::validate_uuid(transaction_id, "transaction_id")?;
+    fn export(transactions: &[Transaction], options: &CsvOptions) -> Vec<u8> {
+        let mut writer = TransactionCsvWriter::new(Vec::new(), options).unwrap();
+        for transaction in transactions {
+            writer.write(transaction).unwrap();
+        }
+        writer.into_inner().unwrap()
     }
 
-    if request.transaction_ids.is_empty() {
-        return Err(FiscusError::InvalidInput(
-            "No transaction IDs provided".to_string(),
-        ));
+    fn parse(output: &[u8], options: &CsvOptions) -> Vec<csv::StringRecord> {
+        csv::ReaderBuilder::new()
+            .delimiter(options.delimiter as u8)
+            .quote(options.quote as u8)
+            .from_reader(output)
+            .records()
+            .collect::<Result<_, _>>()
+            .unwrap()
     }
 
-    if request.transaction_ids.len() > 100 {
-        return Err(FiscusError::InvalidInput(
-            "Cannot process more than 100 transactions at once".to_string(),
-        ));
+    #[test]
+    fn test_free_text_round_trips_through_csv() {
+        let transaction = awkward_transaction();
+        let options = CsvOptions::default();
+
+        let output = export(std::slice::from_ref(&transaction), &options);
+        let records = parse(&output, &options);
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(&record[0], transaction.id);
+        assert_eq!(&record[3], "1234.56");
+        assert_eq!(&record[4], "Dinner, drinks; tip");
+        assert_eq!(&record[8], "The \"Corner\" Bistro");
+        assert_eq!(&record[9], "Split with Sam\r\nPaid back, mostly");
+        assert!(output.ends_with(b"\r\n"));
     }
 
-    match request.action {
-        BulkTransactionAction::Delete => {
-            bulk_delete_transactions(request.transaction_ids, &request.user_id.as_str(), &db).await
+    #[test]
+    fn test_semicolon_dialect_with_bom_and_custom_date() {
+        let transaction = awkward_transaction();
+        let options = CsvOptions {
+            delimiter: ';',
+            quote: '\'',
+            include_bom: true,
+            date_format: "%d.%m.%Y".to_string(),
+        };
+
+        let output = export(std::slice::from_ref(&transaction), &options);
+        assert!(output.starts_with(UTF8_BOM));
+
+        let records = parse(&output[UTF8_BOM.len()..], &options);
+        let record = &records[0];
+        assert_eq!(&record[4], "Dinner, drinks; tip");
+        assert_eq!(
+            &record[5],
+            transaction.transaction_date.format("%d.%m.%Y").to_string()
+        );
+        assert_eq!(&record[8], "The \"Corner\" Bistro");
+    }
+
+    #[test]
+    fn test_ambiguous_dialects_are_rejected() {
+        let same_quote = CsvOptions {
+            delimiter: '"',
+            ..CsvOptions::default()
+        };
+        let multibyte = CsvOptions {
+            delimiter: '¦',
+            ..CsvOptions::default()
+        };
+        let bad_date = CsvOptions {
+            date_format: "%Y-%Q".to_string(),
+            ..CsvOptions::default()
+        };
+
+        for options in [same_quote, multibyte, bad_date] {
+            assert!(matches!(
+                TransactionCsvWriter::new(Vec::new(), &options),
+                Err(FiscusError::FieldValidation { .. })
+            ));
         }
-        BulkTransactionAction::UpdateCategory { category_id } => {
-            bulk_update_category(
-                request.transaction_ids,
-                category_id,
-                &request.user_id.as_str(),
-                &db,
-            )
-            .await
+    }
+}
+
+#[cfg(test)]
+mod batch_creation_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const CHECKING: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+    const SAVINGS: &str = "6ba7b811-9dad-11d1-80b4-00c04fd430c8";
+
+    fn request(
+        account_id: &str,
+        amount: i64,
+        transaction_type: TransactionType,
+    ) -> CreateTransactionRequest {
+        let mut request = TestUtils::create_transaction_request(
+            USER_ID,
+            account_id,
+            Decimal::new(amount, 2),
+            "Line item",
+        );
+        request.transaction_type = Some(transaction_type);
+        request
+    }
+
+    fn entries(requests: &[CreateTransactionRequest]) -> Vec<BatchEntry<'_>> {
+        let dates = validate_transaction_batch(USER_ID, requests).unwrap();
+        requests
+            .iter()
+            .zip(dates)
+            .enumerate()
+            .map(|(index, (request, transaction_date))| BatchEntry {
+                index,
+                transaction_id: Uuid::new_v4().to_string(),
+                request,
+                transaction_date,
+                amount: request.amount,
+                content_hash: transaction_content_hash(
+                    &request.account_id,
+                    transaction_date.date_naive(),
+                    request.amount,
+                    &request.description,
+                ),
+            })
+            .collect()
+    }
+
+    /// Hash counts as stored after importing `entries`
+    fn stored_hashes(entries: &[BatchEntry<'_>]) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for entry in entries {
+            *counts.entry(entry.content_hash.clone()).or_insert(0) += 1;
         }
-        BulkTransactionAction::UpdateStatus { status } => {
-            bulk_update_status(
-                request.transaction_ids,
-                status,
-                &request.user_id.as_str(),
-                &db,
-            )
+        counts
+    }
+
+    /// Positions of the entries an import would insert given `existing` hashes
+    fn new_rows(entries: Vec<BatchEntry<'_>>, existing: HashMap<String, usize>) -> Vec<usize> {
+        let (new_entries, _): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(already_imported_filter(existing));
+        new_entries.iter().map(|entry| entry.index).collect()
+    }
+
+    #[test]
+    fn test_reimporting_same_file_inserts_nothing() {
+        let file = || {
+            vec![
+                request(CHECKING, 4_500, TransactionType::Expense),
+                request(CHECKING, 12_000, TransactionType::Income),
+                request(SAVINGS, 1_250, TransactionType::Expense),
+            ]
+        };
+        let imported = file();
+        let existing = stored_hashes(&entries(&imported));
+
+        let mut reformatted = file();
+        reformatted[0].description = "  LINE   item ".to_string();
+
+        assert!(new_rows(entries(&file()), existing.clone()).is_empty());
+        assert!(new_rows(entries(&reformatted), existing).is_empty());
+    }
+
+    #[test]
+    fn test_reimport_inserts_row_with_modified_amount() {
+        let file = || {
+            vec![
+                request(CHECKING, 4_500, TransactionType::Expense),
+                request(CHECKING, 12_000, TransactionType::Income),
+            ]
+        };
+        let imported = file();
+        let existing = stored_hashes(&entries(&imported));
+
+        let mut corrected = file();
+        corrected[1].amount = Decimal::new(12_001, 2);
+
+        assert_eq!(new_rows(entries(&corrected), existing), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_reimport_after_key_rotation_inserts_nothing() {
+        crate::commands::encryption::initialize_encryption_service()
+            .expect("Failed to initialize encryption service for test");
+
+        let file = vec![
+            request(CHECKING, 4_500, TransactionType::Expense),
+            request(SAVINGS, 1_250, TransactionType::Expense),
+        ];
+        let keyed_entries = || async {
+            let mut keyed = entries(&file);
+            for entry in &mut keyed {
+                entry.content_hash =
+                    stored_content_hash(entry.request, entry.amount, entry.transaction_date)
+                        .await
+                        .unwrap();
+            }
+            keyed
+        };
+
+        let existing = stored_hashes(&keyed_entries().await);
+
+        // Give the user a transactions key, then rotate it
+        EncryptedDatabaseUtils::encrypt_column_value("4500", USER_ID, "transactions", "amount")
             .await
-        }
-        BulkTransactionAction::Export { format } => {
-            bulk_export_transactions(
-                request.transaction_ids,
-                format,
-                &request.user_id.as_str(),
-                &db,
-            )
+            .unwrap();
+        crate::commands::encryption::get_encryption_service()
+            .unwrap()
+            .rotate_user_keys(USER_ID)
             .await
-        }
+            .unwrap();
+
+        assert!(new_rows(keyed_entries().await, existing).is_empty());
     }
-}
 
-/// Bulk delete transactions
-async fn bulk_delete_transactions(
-    transaction_ids: Vec<String>,
-    user_id: &str,
-    db: &Database,
-) -> Result<String, FiscusError> {
-    with_transaction!(db, async {
-        for transaction_id in &transaction_ids {
-            // Verify ownership before deletion
-            let transaction =
-                get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+    #[test]
+    fn test_repeated_rows_are_kept_beyond_existing_count() {
+        // Two identical coffees on one day are both real purchases
+        let file = vec![
+            request(CHECKING, 350, TransactionType::Expense),
+            request(CHECKING, 350, TransactionType::Expense),
+        ];
+        let existing = stored_hashes(&entries(&file[..1]));
 
-            if transaction.user_id != user_id {
-                return Err(FiscusError::Authorization(
-                    "Transaction access denied".to_string(),
-                ));
-            }
+        assert_eq!(new_rows(entries(&file), existing), vec![1]);
+    }
 
-            // Delete the transaction
-            let delete_query = "DELETE FROM transactions WHERE id = ?1 AND user_id = ?2";
-            DatabaseUtils::execute_non_query(
-                db,
-                delete_query,
-                vec![
-                    Value::String(transaction_id.clone()),
-                    Value::String(user_id.to_string()),
-                ],
-            )
-            .await?;
+    #[test]
+    fn test_three_item_batch_applies_net_balance_change_once() {
+        let requests = vec![
+            request(CHECKING, 4_500, TransactionType::Expense),
+            request(CHECKING, 12_000, TransactionType::Income),
+            request(CHECKING, 1_250, TransactionType::Expense),
+        ];
 
-            // Update account balance
-            let balance_change = match transaction.transaction_type {
-                TransactionType::Income => -transaction.amount,
-                TransactionType::Expense => transaction.amount,
-                TransactionType::Transfer => rust_decimal::Decimal::ZERO,
-            };
+        assert_eq!(
+            net_balance_changes(&entries(&requests)),
+            vec![(CHECKING.to_string(), Decimal::new(6_250, 2))]
+        );
+    }
+
+    #[test]
+    fn test_net_balance_change_per_account() {
+        let requests = vec![
+            request(CHECKING, 2_000, TransactionType::Expense),
+            request(SAVINGS, 2_000, TransactionType::Income),
+            request(SAVINGS, 2_000, TransactionType::Expense),
+        ];
+
+        // Savings nets to zero and is left alone
+        assert_eq!(
+            net_balance_changes(&entries(&requests)),
+            vec![(CHECKING.to_string(), Decimal::new(-2_000, 2))]
+        );
+    }
 
-            if balance_change != rust_decimal::Decimal::ZERO {
-                let update_balance_query = r#"
-                    UPDATE accounts
-                    SET current_balance = current_balance + ?1, updated_at = CURRENT_TIMESTAMP
-                    WHERE id = ?2 AND user_id = ?3
-                "#;
+    #[test]
+    fn test_invalid_item_aborts_the_whole_batch() {
+        let mut invalid = request(CHECKING, 1_000, TransactionType::Expense);
+        invalid.description = String::new();
+        let requests = vec![
+            request(CHECKING, 4_500, TransactionType::Expense),
+            invalid,
+            request(CHECKING, 1_250, TransactionType::Expense),
+        ];
 
-                DatabaseUtils::execute_non_query(
-                    db,
-                    update_balance_query,
-                    vec![
-                        Value::String(balance_change.to_string()),
-                        Value::String(transaction.account_id),
-                        Value::String(user_id.to_string()),
-                    ],
-                )
-                .await?;
+        match validate_transaction_batch(USER_ID, &requests) {
+            Err(FiscusError::FieldValidation { field, .. }) => {
+                assert_eq!(field, "transactions[1].description");
             }
+            other => panic!("expected the batch to fail at index 1, got {other:?}"),
         }
+    }
 
-        Ok(format!(
-            "Successfully deleted {} transactions",
-            transaction_ids.len()
-        ))
-    })
+    #[test]
+    fn test_batch_rejects_other_users_and_idempotency_keys() {
+        let mut foreign = request(CHECKING, 1_000, TransactionType::Expense);
+        foreign.user_id =
+            crate::error::ValidatedUserId::new("660e8400-e29b-41d4-a716-446655440001").unwrap();
+        let mut keyed = request(CHECKING, 1_000, TransactionType::Expense);
+        keyed.idempotency_key = Some("retry-1".to_string());
+
+        for (requests, field) in [
+            (vec![foreign], "transactions[0].user_id"),
+            (
+                vec![request(CHECKING, 1, TransactionType::Expense), keyed],
+                "transactions[1].idempotency_key",
+            ),
+        ] {
+            match validate_transaction_batch(USER_ID, &requests) {
+                Err(FiscusError::FieldValidation { field: actual, .. }) => {
+                    assert_eq!(actual, field)
+                }
+                other => panic!("expected FieldValidation for {field}, got {other:?}"),
+            }
+        }
+    }
 }
 
-/// Bulk update transaction categories
-async fn bulk_update_category(
-    transaction_ids: Vec<String>,
-    category_id: Option<String>,
-    user_id: &str,
-    db: &Database,
-) -> Result<String, FiscusError> {
-    // Validate category if provided
-    if let Some(ref cat_id) = category_id {
-        Validator::validate_uuid(cat_id, "category_id")?;
+#[cfg(test)]
+mod refund_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const ACCOUNT_ID: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+
+    fn purchase(amount: i64) -> Transaction {
+        TestUtils::create_test_transaction(
+            USER_ID,
+            ACCOUNT_ID,
+            Decimal::new(amount, 2),
+            TransactionType::Expense,
+        )
     }
 
-    with_transaction!(db, async {
-        let update_query = r#"
-            UPDATE transactions
-            SET category_id = ?1, updated_at = CURRENT_TIMESTAMP
-            WHERE id = ?2 AND user_id = ?3
-        "#;
+    fn refund_of(original: &Transaction, amount: i64) -> Transaction {
+        let mut refund = TestUtils::create_test_transaction(
+            USER_ID,
+            ACCOUNT_ID,
+            Decimal::new(amount, 2),
+            TransactionType::Income,
+        );
+        refund.refunds_transaction_id = Some(original.id.clone());
+        refund
+    }
 
-        for transaction_id in &transaction_ids {
-            // Verify ownership
-            let transaction =
-                get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+    #[test]
+    fn test_partial_refunds_up_to_original_amount_are_accepted() {
+        let original = purchase(10_000);
+        let earlier = vec![refund_of(&original, 4_000)];
 
-            if transaction.user_id != user_id {
-                return Err(FiscusError::Authorization(
-                    "Transaction access denied".to_string(),
-                ));
-            }
+        assert!(check_refund_amount(&original, &earlier, Decimal::new(6_000, 2)).is_ok());
+    }
 
-            DatabaseUtils::execute_non_query(
-                db,
-                update_query,
-                vec![
-                    category_id
-                        .as_ref()
-                        .map(|id| Value::String(id.clone()))
-                        .unwrap_or(Value::Null),
-                    Value::String(transaction_id.clone()),
-                    Value::String(user_id.to_string()),
-                ],
-            )
-            .await?;
-        }
+    #[test]
+    fn test_over_refunding_is_rejected() {
+        let original = purchase(10_000);
+        let mut cancelled = refund_of(&original, 5_000);
+        cancelled.status = TransactionStatus::Cancelled;
+        let earlier = vec![refund_of(&original, 4_000), cancelled];
+
+        // The cancelled refund does not count, so 60.00 remains
+        let result = check_refund_amount(&original, &earlier, Decimal::new(6_001, 2));
+
+        assert!(matches!(
+            result,
+            Err(FiscusError::FieldValidation { ref field, ref code, .. })
+                if field == "amount" && code == "exceeds_original"
+        ));
+    }
 
-        Ok(format!(
-            "Successfully updated category for {} transactions",
-            transaction_ids.len()
-        ))
-    })
+    #[test]
+    fn test_refund_request_links_original() {
+        let mut original = purchase(10_000);
+        original.category_id = Some("6ba7b811-9dad-11d1-80b4-00c04fd430c8".to_string());
+        original.description = "é".repeat(200);
+
+        let request = refund_request(
+            &original,
+            ValidatedUserId::new(USER_ID).unwrap(),
+            Decimal::new(2_500, 2),
+            chrono::Utc::now(),
+        );
+
+        assert_eq!(request.transaction_type, Some(TransactionType::Income));
+        assert_eq!(request.category_id, original.category_id);
+        assert_eq!(
+            request.refunds_transaction_id.as_deref(),
+            Some(original.id.as_str())
+        );
+        assert!(validate_create_transaction_request(&request).is_ok());
+    }
+
+    #[test]
+    fn test_refund_must_be_positive_income() {
+        let original = purchase(10_000);
+        let mut request = refund_request(
+            &original,
+            ValidatedUserId::new(USER_ID).unwrap(),
+            Decimal::new(-100, 2),
+            chrono::Utc::now(),
+        );
+        assert!(validate_create_transaction_request(&request).is_err());
+
+        request.amount = Decimal::new(100, 2);
+        request.transaction_type = Some(TransactionType::Expense);
+        assert!(validate_create_transaction_request(&request).is_err());
+    }
 }
 
-/// Bulk update transaction status
-async fn bulk_update_status(
-    transaction_ids: Vec<String>,
-    status: TransactionStatus,
-    user_id: &str,
-    db: &Database,
-) -> Result<String, FiscusError> {
-    with_transaction!(db, async {
-        let update_query = r#"
-            UPDATE transactions
-            SET status = ?1, updated_at = CURRENT_TIMESTAMP
-            WHERE id = ?2 AND user_id = ?3
-        "#;
+#[cfg(test)]
+mod field_selection_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
 
-        for transaction_id in &transaction_ids {
-            // Verify ownership
-            let transaction =
-                get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const ACCOUNT_ID: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
 
-            if transaction.user_id != user_id {
-                return Err(FiscusError::Authorization(
-                    "Transaction access denied".to_string(),
-                ));
-            }
+    fn chart_fields() -> Vec<String> {
+        vec!["amount".to_string(), "transaction_date".to_string()]
+    }
 
-            DatabaseUtils::execute_non_query(
-                db,
-                update_query,
-                vec![
-                    Value::String(status.to_string()),
-                    Value::String(transaction_id.clone()),
-                    Value::String(user_id.to_string()),
-                ],
-            )
-            .await?;
+    #[test]
+    fn test_minimal_select_skips_unrequested_encrypted_columns() {
+        let full = transaction_select_columns(None);
+        let minimal = transaction_select_columns(Some(&chart_fields()));
+
+        assert!(!full.contains(" AS "));
+        assert!(minimal.contains("NULL AS payee"));
+        assert!(minimal.contains("NULL AS notes"));
+        assert!(minimal.contains("'' AS description"));
+        assert!(!minimal.contains("AS amount"));
+        assert!(minimal.contains("transaction_date"));
+    }
+
+    #[test]
+    fn test_minimal_projection_matches_full_query() {
+        let mut transaction = TestUtils::create_test_transaction(
+            USER_ID,
+            ACCOUNT_ID,
+            Decimal::new(4_250, 2),
+            TransactionType::Expense,
+        );
+        transaction.payee = Some("Corner Cafe".to_string());
+        transaction.notes = Some("Team breakfast".to_string());
+
+        let full = project_transactions(vec![transaction.clone()], None).unwrap();
+        let minimal = project_transactions(vec![transaction], Some(&chart_fields())).unwrap();
+
+        assert!(full[0].contains_key("payee"));
+        assert!(full[0].contains_key("notes"));
+
+        let mut keys: Vec<&str> = minimal[0].keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["amount", "id", "transaction_date"]);
+        for key in keys {
+            assert_eq!(minimal[0][key], full[0][key]);
         }
+    }
 
-        Ok(format!(
-            "Successfully updated status for {} transactions",
-            transaction_ids.len()
-        ))
-    })
+    #[test]
+    fn test_unknown_select_field_is_rejected() {
+        let fields = vec!["amount".to_string(), "password_hash".to_string()];
+
+        assert!(SecurityValidator::validate_select_fields(
+            &fields,
+            SecurityValidator::TRANSACTION_SELECT_FIELDS
+        )
+        .is_err());
+        assert!(SecurityValidator::validate_select_fields(
+            &[],
+            SecurityValidator::TRANSACTION_SELECT_FIELDS
+        )
+        .is_err());
+    }
 }
 
-/// Bulk export transactions
-async fn bulk_export_transactions(
-    transaction_ids: Vec<String>,
-    format: ExportFormat,
-    user_id: &str,
-    db: &Database,
-) -> Result<String, FiscusError> {
-    let mut transactions = Vec::new();
+#[cfg(test)]
+mod soft_cap_tests {
+    use super::*;
 
-    for transaction_id in &transaction_ids {
-        let transaction =
-            get_transaction_by_id_encrypted(transaction_id.clone(), user_id, db).await?;
+    #[test]
+    fn test_query_over_the_cap_is_rejected() {
+        let cap = QuerySoftCap {
+            max_rows: 100,
+            mode: SoftCapMode::Reject,
+        };
 
-        if transaction.user_id != user_id {
-            return Err(FiscusError::Authorization(
-                "Transaction access denied".to_string(),
-            ));
-        }
+        assert!(cap.applies_to(page_bound(None, None)));
+        assert!(matches!(cap.check(101), Err(FiscusError::InvalidInput(_))));
+        assert_eq!(cap.check(100).unwrap(), None);
+    }
 
-        transactions.push(transaction);
+    #[test]
+    fn test_query_over_the_cap_is_truncated_when_configured() {
+        let cap = QuerySoftCap {
+            max_rows: 100,
+            mode: SoftCapMode::Truncate,
+        };
+
+        assert_eq!(cap.check(25_000).unwrap(), Some(100));
     }
 
-    match format {
-        ExportFormat::Json => {
-            let json_data = serde_json::to_string_pretty(&transactions)
-                .map_err(|e| FiscusError::Internal(format!("JSON serialization failed: {e}")))?;
-            Ok(json_data)
-        }
-        ExportFormat::Csv => {
-            let mut csv_data = String::from("id,account_id,category_id,amount,description,transaction_date,transaction_type,status,payee,notes\n");
-
-            for transaction in transactions {
-                csv_data.push_str(&format!(
-                    "{},{},{},{},{},{},{},{},{},{}\n",
-                    transaction.id,
-                    transaction.account_id,
-                    transaction.category_id.unwrap_or_default(),
-                    transaction.amount,
-                    transaction.description.replace(',', ";"),
-                    transaction.transaction_date.format("%Y-%m-%d %H:%M:%S"),
-                    transaction.transaction_type,
-                    transaction.status,
-                    transaction.payee.unwrap_or_default().replace(',', ";"),
-                    transaction.notes.unwrap_or_default().replace(',', ";")
-                ));
-            }
+    #[test]
+    fn test_pages_under_the_cap_are_not_counted() {
+        let cap = QuerySoftCap {
+            max_rows: 100,
+            mode: SoftCapMode::Reject,
+        };
 
-            Ok(csv_data)
-        }
+        assert!(!cap.applies_to(page_bound(Some(50), Some(200))));
+        assert!(!cap.applies_to(page_bound(None, Some(10))));
+        assert!(cap.applies_to(page_bound(Some(500), None)));
+        assert!(!QuerySoftCap::default().applies_to(page_bound(Some(5000), None)));
     }
 }
 
-/// Get transaction summary for a user
-#[tauri::command]
-pub async fn get_transaction_summary(
-    user_id: String,
-    start_date: Option<String>,
-    end_date: Option<String>,
-    db: State<'_, Database>,
-) -> Result<TransactionSummaryResponse, FiscusError> {
-    // Validate user
-    Validator::validate_uuid(&user_id, "user_id")?;
-    DatabaseUtils::validate_user_exists(&db, &user_id).await?;
+#[cfg(test)]
+mod json_import_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const OLD_ACCOUNT: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+    const NEW_ACCOUNT: &str = "6ba7b811-9dad-11d1-80b4-00c04fd430c8";
+    const OLD_CATEGORY: &str = "6ba7b812-9dad-11d1-80b4-00c04fd430c8";
+    const NEW_CATEGORY: &str = "6ba7b813-9dad-11d1-80b4-00c04fd430c8";
+
+    fn import_maps() -> (HashMap<String, String>, HashMap<String, String>) {
+        (
+            HashMap::from([(OLD_ACCOUNT.to_string(), NEW_ACCOUNT.to_string())]),
+            HashMap::from([(OLD_CATEGORY.to_string(), NEW_CATEGORY.to_string())]),
+        )
+    }
 
-    let mut where_conditions = vec![
-        "user_id = ?1".to_string(),
-        "transaction_type != 'transfer'".to_string(),
-    ];
-    let mut params = vec![Value::String(user_id)];
-    let mut param_index = 2;
+    fn convert(transaction: Transaction) -> FiscusResult<CreateTransactionRequest> {
+        let (accounts, categories) = import_maps();
+        exported_transaction_request(
+            transaction,
+            &ValidatedUserId::new(USER_ID).unwrap(),
+            &accounts,
+            &categories,
+        )
+    }
 
-    if let Some(start) = start_date {
-        Validator::validate_date(&start)?;
-        where_conditions.push(format!("DATE(transaction_date) >= ?{param_index}"));
-        params.push(Value::String(start));
-        param_index += 1;
+    #[test]
+    fn test_exported_transactions_round_trip_into_new_account() {
+        let mut groceries = TestUtils::create_test_transaction(
+            USER_ID,
+            OLD_ACCOUNT,
+            Decimal::new(4250, 2),
+            TransactionType::Expense,
+        );
+        groceries.category_id = Some(OLD_CATEGORY.to_string());
+        groceries.payee = Some("Corner Grocer".to_string());
+        groceries.notes = Some("Weekly shop".to_string());
+        groceries.tags = Some(vec!["food".to_string()]);
+        let salary = TestUtils::create_test_transaction(
+            USER_ID,
+            OLD_ACCOUNT,
+            Decimal::new(300000, 2),
+            TransactionType::Income,
+        );
+        let exported = vec![groceries, salary];
+
+        // The same serialization as a JSON export
+        let json = serde_json::to_string_pretty(&exported).unwrap();
+        let parsed: Vec<Transaction> = serde_json::from_str(&json).unwrap();
+        let requests: Vec<CreateTransactionRequest> =
+            parsed.into_iter().map(|t| convert(t).unwrap()).collect();
+
+        for (original, request) in exported.iter().zip(&requests) {
+            assert_eq!(request.account_id, NEW_ACCOUNT);
+            assert_eq!(request.amount, original.amount);
+            assert_eq!(request.description, original.description);
+            assert_eq!(request.notes, original.notes);
+            assert_eq!(request.transaction_date, original.transaction_date);
+            assert_eq!(request.transaction_type(), original.transaction_type);
+            assert_eq!(request.payee, original.payee);
+            assert_eq!(request.tags, original.tags);
+            assert!(validate_batch_entry(USER_ID, request).is_ok());
+        }
+        assert_eq!(requests[0].category_id.as_deref(), Some(NEW_CATEGORY));
+        assert_eq!(requests[1].category_id, None);
+
+        let balance = |deltas: Vec<Decimal>| deltas.into_iter().sum::<Decimal>();
+        assert_eq!(
+            balance(
+                requests
+                    .iter()
+                    .map(|r| balance_delta(r.amount, &r.transaction_type()))
+                    .collect()
+            ),
+            balance(
+                exported
+                    .iter()
+                    .map(|t| balance_delta(t.amount, &t.transaction_type))
+                    .collect()
+            )
+        );
     }
 
-    if let Some(end) = end_date {
-        Validator::validate_date(&end)?;
-        where_conditions.push(format!("DATE(transaction_date) <= ?{param_index}"));
-        params.push(Value::String(end));
+    #[test]
+    fn test_unmappable_exported_transactions_are_rejected() {
+        let unmapped_account = TestUtils::create_test_transaction(
+            USER_ID,
+            "6ba7b814-9dad-11d1-80b4-00c04fd430c8",
+            Decimal::new(1000, 2),
+            TransactionType::Expense,
+        );
+        let mut unmapped_category = TestUtils::create_test_transaction(
+            USER_ID,
+            OLD_ACCOUNT,
+            Decimal::new(1000, 2),
+            TransactionType::Expense,
+        );
+        unmapped_category.category_id = Some("6ba7b815-9dad-11d1-80b4-00c04fd430c8".to_string());
+        let transfer = TestUtils::create_test_transaction(
+            USER_ID,
+            OLD_ACCOUNT,
+            Decimal::new(1000, 2),
+            TransactionType::Transfer,
+        );
+
+        for (transaction, expected_field) in [
+            (unmapped_account, "account_id"),
+            (unmapped_category, "category_id"),
+            (transfer, "transaction_type"),
+        ] {
+            match convert(transaction) {
+                Err(FiscusError::FieldValidation { field, .. }) => {
+                    assert_eq!(field, expected_field)
+                }
+                other => panic!("expected FieldValidation, got {other:?}"),
+            }
+        }
     }
+}
 
-    let summary_query = format!(
-        r#"
-        SELECT
-            COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END), 0) as total_income,
-            COALESCE(SUM(CASE WHEN transaction_type = 'expense' THEN amount ELSE 0 END), 0) as total_expenses,
-            COUNT(*) as transaction_count,
-            COALESCE(AVG(ABS(amount)), 0) as average_transaction
-        FROM transactions
-        WHERE {}
-    "#,
-        where_conditions.join(" AND ")
-    );
+#[cfg(test)]
+mod account_defaults_tests {
+    use super::*;
+    use crate::test_utils::TestUtils;
+
+    const USER_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const ACCOUNT_ID: &str = "550e8400-e29b-41d4-a716-446655440001";
+    const DEFAULT_CATEGORY_ID: &str = "550e8400-e29b-41d4-a716-446655440002";
+    const CHOSEN_CATEGORY_ID: &str = "550e8400-e29b-41d4-a716-446655440003";
+
+    fn defaults() -> AccountDefaults {
+        AccountDefaults {
+            category_id: Some(DEFAULT_CATEGORY_ID.to_string()),
+            payee: Some("Landlord".to_string()),
+        }
+    }
 
-    let summary: Option<HashMap<String, serde_json::Value>> =
-        DatabaseUtils::execute_query_single(&db, &summary_query, params).await?;
+    fn request() -> CreateTransactionRequest {
+        TestUtils::create_transaction_request(USER_ID, ACCOUNT_ID, Decimal::new(120000, 2), "Rent")
+    }
 
-    let summary_data = summary.unwrap_or_default();
+    #[test]
+    fn test_unspecified_category_and_payee_inherit_account_defaults() {
+        let mut request = request();
 
-    let total_income = parse_decimal_from_json(&summary_data, "total_income");
-    let total_expenses = parse_decimal_from_json(&summary_data, "total_expenses");
+        apply_account_defaults(&mut request, defaults());
 
-    let transaction_count = summary_data
-        .get("transaction_count")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0) as i32;
+        assert_eq!(request.category_id.as_deref(), Some(DEFAULT_CATEGORY_ID));
+        assert_eq!(request.payee.as_deref(), Some("Landlord"));
+    }
 
-    let average_transaction = parse_decimal_from_json(&summary_data, "average_transaction");
+    #[test]
+    fn test_request_values_override_account_defaults() {
+        let mut request = request();
+        request.category_id = Some(CHOSEN_CATEGORY_ID.to_string());
+        request.payee = Some("Property Manager".to_string());
 
-    let net_income = total_income - total_expenses;
+        apply_account_defaults(&mut request, defaults());
 
-    Ok(TransactionSummaryResponse {
-        total_income,
-        total_expenses,
-        net_income,
-        transaction_count,
-        average_transaction,
-    })
+        assert_eq!(request.category_id.as_deref(), Some(CHOSEN_CATEGORY_ID));
+        assert_eq!(request.payee.as_deref(), Some("Property Manager"));
+    }
+
+    #[test]
+    fn test_account_without_defaults_leaves_request_unchanged() {
+        let mut request = request();
+
+        apply_account_defaults(&mut request, AccountDefaults::default());
+
+        assert_eq!(request.category_id, None);
+        assert_eq!(request.payee, None);
+    }
 }
@@ -4,11 +4,16 @@
 /// including connection pooling settings, database URLs, and connection timeouts.
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 use tracing::{debug, info};
 
 use crate::error::{FiscusError, FiscusResult};
 
+/// Longest a single query may run before it is abandoned
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Database configuration for the Fiscus application
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
@@ -22,6 +27,9 @@ pub struct DatabaseConfig {
     pub min_connections: u32,
     /// Connection timeout in seconds
     pub connection_timeout: Duration,
+    /// Maximum time to wait for a pooled connection when the pool is exhausted
+    #[serde(default = "default_acquire_timeout")]
+    pub acquire_timeout: Duration,
     /// Query timeout in seconds
     pub query_timeout: Duration,
     /// Enable connection pooling
@@ -32,6 +40,9 @@ pub struct DatabaseConfig {
     pub enable_slow_query_detection: bool,
     /// Slow query threshold in milliseconds
     pub slow_query_threshold_ms: u64,
+    /// Pragmas applied to each SQLite connection when it is opened
+    #[serde(default)]
+    pub pragmas: SqlitePragmas,
 }
 
 /// Supported database types
@@ -42,6 +53,131 @@ pub enum DatabaseType {
     PostgreSQL,
 }
 
+/// SQLite `journal_mode` pragma values
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    /// Write-ahead logging; readers no longer block on a writer
+    Wal,
+    Off,
+}
+
+impl fmt::Display for JournalMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalMode::Delete => write!(f, "DELETE"),
+            JournalMode::Truncate => write!(f, "TRUNCATE"),
+            JournalMode::Persist => write!(f, "PERSIST"),
+            JournalMode::Memory => write!(f, "MEMORY"),
+            JournalMode::Wal => write!(f, "WAL"),
+            JournalMode::Off => write!(f, "OFF"),
+        }
+    }
+}
+
+impl FromStr for JournalMode {
+    type Err = FiscusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "DELETE" => Ok(JournalMode::Delete),
+            "TRUNCATE" => Ok(JournalMode::Truncate),
+            "PERSIST" => Ok(JournalMode::Persist),
+            "MEMORY" => Ok(JournalMode::Memory),
+            "WAL" => Ok(JournalMode::Wal),
+            "OFF" => Ok(JournalMode::Off),
+            _ => Err(FiscusError::InvalidInput(format!(
+                "Invalid journal mode: {s}"
+            ))),
+        }
+    }
+}
+
+/// SQLite `synchronous` pragma values
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SynchronousMode {
+    Off,
+    /// Safe with WAL; a power loss can only roll back the latest commits
+    Normal,
+    Full,
+    Extra,
+}
+
+impl fmt::Display for SynchronousMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SynchronousMode::Off => write!(f, "OFF"),
+            SynchronousMode::Normal => write!(f, "NORMAL"),
+            SynchronousMode::Full => write!(f, "FULL"),
+            SynchronousMode::Extra => write!(f, "EXTRA"),
+        }
+    }
+}
+
+impl FromStr for SynchronousMode {
+    type Err = FiscusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "OFF" => Ok(SynchronousMode::Off),
+            "NORMAL" => Ok(SynchronousMode::Normal),
+            "FULL" => Ok(SynchronousMode::Full),
+            "EXTRA" => Ok(SynchronousMode::Extra),
+            _ => Err(FiscusError::InvalidInput(format!(
+                "Invalid synchronous mode: {s}"
+            ))),
+        }
+    }
+}
+
+/// Pragmas set on every SQLite connection when it is opened
+///
+/// WAL lets reads proceed while a write is in progress, and the busy timeout
+/// makes a connection wait for a lock instead of failing straight away with
+/// "database is locked".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SqlitePragmas {
+    pub journal_mode: JournalMode,
+    pub synchronous: SynchronousMode,
+    /// How long to wait for a lock held by another connection
+    pub busy_timeout: Duration,
+}
+
+impl Default for SqlitePragmas {
+    fn default() -> Self {
+        Self {
+            journal_mode: JournalMode::Wal,
+            synchronous: SynchronousMode::Normal,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl SqlitePragmas {
+    /// PRAGMA statements to run on a new connection, in order
+    ///
+    /// The busy timeout comes first so switching the journal mode, which needs
+    /// an exclusive lock, waits for other connections rather than failing.
+    pub fn statements(&self) -> Vec<String> {
+        vec![
+            format!("PRAGMA busy_timeout = {}", self.busy_timeout.as_millis()),
+            format!("PRAGMA journal_mode = {}", self.journal_mode),
+            format!("PRAGMA synchronous = {}", self.synchronous),
+        ]
+    }
+}
+
+/// Default time to wait for a pooled connection
+fn default_acquire_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
@@ -50,11 +186,13 @@ impl Default for DatabaseConfig {
             max_connections: 5, // Lower for local SQLite
             min_connections: 1,
             connection_timeout: Duration::from_secs(10), // Faster for local
-            query_timeout: Duration::from_secs(30),      // Faster for local
+            acquire_timeout: default_acquire_timeout(),
+            query_timeout: DEFAULT_QUERY_TIMEOUT, // Faster for local
             enable_pooling: true,
             enable_query_logging: true,
             enable_slow_query_detection: true,
             slow_query_threshold_ms: 50, // Lower threshold for local
+            pragmas: SqlitePragmas::default(),
         }
     }
 }
@@ -91,6 +229,13 @@ impl DatabaseConfig {
             config.connection_timeout = Duration::from_secs(timeout_secs);
         }
 
+        if let Ok(acquire_timeout) = env::var("FISCUS_DB_ACQUIRE_TIMEOUT_MS") {
+            let timeout_ms: u64 = acquire_timeout
+                .parse()
+                .map_err(|e| FiscusError::InvalidInput(format!("Invalid acquire timeout: {e}")))?;
+            config.acquire_timeout = Duration::from_millis(timeout_ms);
+        }
+
         if let Ok(query_timeout) = env::var("FISCUS_DB_QUERY_TIMEOUT") {
             let timeout_secs: u64 = query_timeout
                 .parse()
@@ -123,6 +268,22 @@ impl DatabaseConfig {
             })?;
         }
 
+        // SQLite pragmas
+        if let Ok(journal_mode) = env::var("FISCUS_DB_JOURNAL_MODE") {
+            config.pragmas.journal_mode = journal_mode.parse()?;
+        }
+
+        if let Ok(synchronous) = env::var("FISCUS_DB_SYNCHRONOUS") {
+            config.pragmas.synchronous = synchronous.parse()?;
+        }
+
+        if let Ok(busy_timeout) = env::var("FISCUS_DB_BUSY_TIMEOUT_MS") {
+            let timeout_ms: u64 = busy_timeout
+                .parse()
+                .map_err(|e| FiscusError::InvalidInput(format!("Invalid busy timeout: {e}")))?;
+            config.pragmas.busy_timeout = Duration::from_millis(timeout_ms);
+        }
+
         config.validate()?;
 
         debug!("Loaded database configuration from environment");
@@ -184,12 +345,31 @@ impl DatabaseConfig {
             ));
         }
 
+        if self.acquire_timeout.is_zero() {
+            return Err(FiscusError::InvalidInput(
+                "Acquire timeout must be greater than 0".to_string(),
+            ));
+        }
+
         if self.query_timeout.is_zero() {
             return Err(FiscusError::InvalidInput(
                 "Query timeout must be greater than 0".to_string(),
             ));
         }
 
+        // A zero busy timeout fails immediately on any lock contention
+        if self.pragmas.busy_timeout.is_zero() {
+            return Err(FiscusError::InvalidInput(
+                "Busy timeout must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.pragmas.busy_timeout > self.query_timeout {
+            return Err(FiscusError::InvalidInput(
+                "Busy timeout cannot be longer than the query timeout".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -203,6 +383,11 @@ impl DatabaseConfig {
         &self.database_type
     }
 
+    /// Get the maximum time to wait for a pooled connection
+    pub fn acquire_timeout(&self) -> Duration {
+        self.acquire_timeout
+    }
+
     /// Check if pooling is enabled
     pub fn is_pooling_enabled(&self) -> bool {
         self.enable_pooling
@@ -271,6 +456,43 @@ mod tests {
         config.min_connections = 20;
         config.max_connections = 10;
         assert!(config.validate().is_err());
+
+        config = DatabaseConfig::default();
+        config.acquire_timeout = Duration::ZERO;
+        assert!(config.validate().is_err());
+
+        config = DatabaseConfig::default();
+        config.pragmas.busy_timeout = Duration::ZERO;
+        assert!(config.validate().is_err());
+
+        config = DatabaseConfig::default();
+        config.pragmas.busy_timeout = config.query_timeout + Duration::from_secs(1);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_default_pragmas_enable_wal_with_busy_timeout() {
+        let statements = DatabaseConfig::default().pragmas.statements();
+
+        assert_eq!(
+            statements,
+            vec![
+                "PRAGMA busy_timeout = 5000",
+                "PRAGMA journal_mode = WAL",
+                "PRAGMA synchronous = NORMAL",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pragma_values_are_validated() {
+        assert_eq!("wal".parse::<JournalMode>().unwrap(), JournalMode::Wal);
+        assert_eq!(
+            "Full".parse::<SynchronousMode>().unwrap(),
+            SynchronousMode::Full
+        );
+        assert!("WAL2".parse::<JournalMode>().is_err());
+        assert!("SOMETIMES".parse::<SynchronousMode>().is_err());
     }
 
     #[test]
@@ -2,12 +2,16 @@
 ///
 /// This module provides a connection manager that handles database connections
 /// with proper pooling, configuration, and error handling for the Tauri SQL plugin.
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
 use std::sync::{Arc, RwLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, error, info, warn};
 
-use super::config::{DatabaseConfig, DatabaseType};
+use super::config::{DatabaseConfig, DatabaseType, SqlitePragmas, DEFAULT_QUERY_TIMEOUT};
 use crate::error::{FiscusError, FiscusResult};
 use crate::logging::DatabaseLogger;
 
@@ -24,6 +28,13 @@ pub struct DatabaseConnection {
     pub last_used: Instant,
     /// Connection ID for tracking
     pub connection_id: String,
+    /// Longest a query on this connection may run
+    pub query_timeout: Duration,
+    /// PRAGMA statements run when the connection was opened; empty until then
+    pub applied_pragmas: Vec<String>,
+    /// Pool that queries run against in tests; see `database::test_backend`
+    #[cfg(test)]
+    pub(crate) test_pool: Option<sqlx::SqlitePool>,
 }
 
 impl DatabaseConnection {
@@ -38,9 +49,48 @@ impl DatabaseConnection {
             created_at: now,
             last_used: now,
             connection_id,
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
+            applied_pragmas: Vec::new(),
+            #[cfg(test)]
+            test_pool: None,
         }
     }
 
+    /// Run this handle's queries against `pool`
+    #[cfg(test)]
+    pub(crate) fn with_test_pool(mut self, pool: sqlx::SqlitePool) -> Self {
+        self.test_pool = Some(pool);
+        self
+    }
+
+    /// Run the configured pragmas on this connection
+    ///
+    /// Pragmas are applied once per connection; a pooled connection being
+    /// reused keeps its settings. Returns whether anything was run.
+    pub fn apply_pragmas(&mut self, pragmas: &SqlitePragmas) -> bool {
+        if !self.applied_pragmas.is_empty() {
+            return false;
+        }
+
+        // Note: In a real implementation, these would be executed through the Tauri SQL plugin
+        let statements = pragmas.statements();
+        for statement in &statements {
+            debug!(
+                connection_id = %self.connection_id,
+                pragma = %statement,
+                "Applying SQLite pragma"
+            );
+        }
+        self.applied_pragmas = statements;
+        true
+    }
+
+    /// Abandon queries on this connection that run longer than `timeout`
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = timeout;
+        self
+    }
+
     /// Update the last used timestamp
     pub fn touch(&mut self) {
         self.last_used = Instant::now();
@@ -58,13 +108,71 @@ impl DatabaseConnection {
 }
 
 /// Connection pool statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct PoolStats {
     pub total_connections: usize,
     pub active_connections: usize,
     pub idle_connections: usize,
     pub total_requests: u64,
     pub failed_requests: u64,
+    /// Configured maximum pool size
+    pub max_connections: u32,
+    /// Configured minimum (pre-warmed) pool size
+    pub min_connections: u32,
+    /// Configured acquire timeout in milliseconds
+    pub acquire_timeout_ms: u64,
+}
+
+/// A connection checked out of a `ConnectionManager`
+///
+/// Dropping it returns the connection to the idle pool and frees its slot,
+/// so a connection can never leak out of the pool.
+#[derive(Debug)]
+pub struct PooledConnection {
+    connection: Option<DatabaseConnection>,
+    /// Idle pool to return to; `None` when pooling is disabled
+    pool: Option<Arc<RwLock<Vec<DatabaseConnection>>>>,
+    max_connections: usize,
+    /// Slot held until the connection is returned
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Deref for PooledConnection {
+    type Target = DatabaseConnection;
+
+    fn deref(&self) -> &DatabaseConnection {
+        self.connection
+            .as_ref()
+            .expect("connection is present until dropped")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut DatabaseConnection {
+        self.connection
+            .as_mut()
+            .expect("connection is present until dropped")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        let (Some(mut connection), Some(pool)) = (self.connection.take(), &self.pool) else {
+            return;
+        };
+        connection.touch();
+
+        match pool.write() {
+            // Idle connections never outnumber the pool size, whatever the checkouts
+            Ok(mut pool) if pool.len() < self.max_connections => {
+                pool.push(connection);
+                debug!("Connection returned to pool");
+            }
+            Ok(_) => debug!("Pool full, discarding connection"),
+            Err(e) => warn!(error = %e, "Failed to return connection to pool"),
+        }
+        // The permit is released after this, once the connection is back in the pool
+    }
 }
 
 /// Database connection manager
@@ -72,8 +180,10 @@ pub struct PoolStats {
 pub struct ConnectionManager {
     /// Database configuration
     config: DatabaseConfig,
-    /// Connection pool
+    /// Connection pool (idle connections)
     pool: Arc<RwLock<Vec<DatabaseConnection>>>,
+    /// One permit per connection that may be checked out at once
+    permits: Arc<Semaphore>,
     /// Connection usage tracking
     #[allow(dead_code)]
     usage_stats: Arc<RwLock<HashMap<String, u64>>>,
@@ -89,17 +199,32 @@ impl ConnectionManager {
     pub fn new(config: DatabaseConfig) -> FiscusResult<Self> {
         config.validate()?;
 
+        // Pre-warm the pool up to the configured minimum
+        let mut initial_pool = Vec::new();
+        if config.is_pooling_enabled() && config.database_type() == &DatabaseType::SQLite {
+            for _ in 0..config.min_connections {
+                initial_pool.push(Self::open_connection(&config));
+            }
+        }
+
+        let stats = PoolStats {
+            total_connections: initial_pool.len(),
+            active_connections: 0,
+            idle_connections: initial_pool.len(),
+            total_requests: 0,
+            failed_requests: 0,
+            max_connections: config.max_connections,
+            min_connections: config.min_connections,
+            acquire_timeout_ms: config.acquire_timeout().as_millis() as u64,
+        };
+
+        let permits = Arc::new(Semaphore::new(config.max_connections as usize));
         let manager = Self {
             config,
-            pool: Arc::new(RwLock::new(Vec::new())),
+            pool: Arc::new(RwLock::new(initial_pool)),
+            permits,
             usage_stats: Arc::new(RwLock::new(HashMap::new())),
-            stats: Arc::new(RwLock::new(PoolStats {
-                total_connections: 0,
-                active_connections: 0,
-                idle_connections: 0,
-                total_requests: 0,
-                failed_requests: 0,
-            })),
+            stats: Arc::new(RwLock::new(stats)),
             logger: DatabaseLogger::new(),
         };
 
@@ -107,6 +232,8 @@ impl ConnectionManager {
             database_url = %manager.config.database_url(),
             database_type = ?manager.config.database_type(),
             max_connections = manager.config.max_connections,
+            min_connections = manager.config.min_connections,
+            acquire_timeout_ms = manager.config.acquire_timeout().as_millis(),
             pooling_enabled = manager.config.is_pooling_enabled(),
             "Database connection manager initialized"
         );
@@ -127,7 +254,9 @@ impl ConnectionManager {
     }
 
     /// Get a database connection
-    pub fn get_connection(&self) -> FiscusResult<DatabaseConnection> {
+    ///
+    /// The connection goes back to the pool when the returned guard is dropped.
+    pub async fn get_connection(&self) -> FiscusResult<PooledConnection> {
         let start_time = Instant::now();
 
         // Update request statistics
@@ -140,9 +269,15 @@ impl ConnectionManager {
         }
 
         let result = if self.config.is_pooling_enabled() {
-            self.get_pooled_connection()
+            self.get_pooled_connection().await
         } else {
             self.create_new_connection()
+                .map(|connection| PooledConnection {
+                    connection: Some(connection),
+                    pool: None,
+                    max_connections: 0,
+                    _permit: None,
+                })
         };
 
         match &result {
@@ -171,46 +306,52 @@ impl ConnectionManager {
         result
     }
 
-    /// Get a pooled connection
-    fn get_pooled_connection(&self) -> FiscusResult<DatabaseConnection> {
-        // Try to get an existing connection from the pool
-        {
-            let mut pool = self
-                .pool
-                .write()
-                .map_err(|e| FiscusError::Internal(format!("Failed to acquire pool lock: {e}")))?;
+    /// Get a pooled connection, waiting up to the acquire timeout when the pool is exhausted
+    async fn get_pooled_connection(&self) -> FiscusResult<PooledConnection> {
+        let max_connections = self.config.max_connections as usize;
+        let permit = tokio::time::timeout(
+            self.config.acquire_timeout(),
+            self.permits.clone().acquire_owned(),
+        )
+        .await
+        .map_err(|_| {
+            FiscusError::Database(format!(
+                "Timed out after {}ms waiting for a database connection (max_connections = {})",
+                self.config.acquire_timeout().as_millis(),
+                max_connections
+            ))
+        })?
+        .map_err(|e| FiscusError::Internal(format!("Connection pool is closed: {e}")))?;
+
+        let idle = self
+            .pool
+            .write()
+            .map_err(|e| FiscusError::Internal(format!("Failed to acquire pool lock: {e}")))?
+            .pop();
 
-            if let Some(mut conn) = pool.pop() {
+        let connection = match idle {
+            Some(mut conn) => {
                 conn.touch();
                 debug!(
                     connection_id = %conn.connection_id,
                     age_ms = conn.age().as_millis(),
                     "Reusing pooled connection"
                 );
-                return Ok(conn);
+                conn
             }
-        }
+            None => self.create_new_connection()?,
+        };
 
-        // No available connections, create a new one
-        self.create_new_connection()
+        Ok(PooledConnection {
+            connection: Some(connection),
+            pool: Some(Arc::clone(&self.pool)),
+            max_connections,
+            _permit: Some(permit),
+        })
     }
 
     /// Create a new database connection
     fn create_new_connection(&self) -> FiscusResult<DatabaseConnection> {
-        let current_count = {
-            let pool = self
-                .pool
-                .read()
-                .map_err(|e| FiscusError::Internal(format!("Failed to acquire pool lock: {e}")))?;
-            pool.len()
-        };
-
-        if current_count >= self.config.max_connections as usize {
-            return Err(FiscusError::Internal(
-                "Maximum number of database connections reached".to_string(),
-            ));
-        }
-
         // Ensure we're only working with SQLite for local operations
         if self.config.database_type() != &DatabaseType::SQLite {
             return Err(FiscusError::InvalidInput(
@@ -218,10 +359,7 @@ impl ConnectionManager {
             ));
         }
 
-        let connection = DatabaseConnection::new(
-            self.config.database_url().to_string(),
-            self.config.database_type().clone(),
-        );
+        let connection = Self::open_connection(&self.config);
 
         debug!(
             connection_id = %connection.connection_id,
@@ -233,31 +371,15 @@ impl ConnectionManager {
         Ok(connection)
     }
 
-    /// Return a connection to the pool
-    pub fn return_connection(&self, mut connection: DatabaseConnection) -> FiscusResult<()> {
-        if !self.config.is_pooling_enabled() {
-            debug!(
-                connection_id = %connection.connection_id,
-                "Connection pooling disabled, discarding connection"
-            );
-            return Ok(());
-        }
-
-        connection.touch();
-
-        let mut pool = self
-            .pool
-            .write()
-            .map_err(|e| FiscusError::Internal(format!("Failed to acquire pool lock: {e}")))?;
-
-        if pool.len() < self.config.max_connections as usize {
-            pool.push(connection);
-            debug!("Connection returned to pool");
-        } else {
-            debug!("Pool full, discarding connection");
-        }
-
-        Ok(())
+    /// Open a SQLite connection and apply the configured pragmas to it
+    fn open_connection(config: &DatabaseConfig) -> DatabaseConnection {
+        let mut connection = DatabaseConnection::new(
+            config.database_url().to_string(),
+            config.database_type().clone(),
+        )
+        .with_query_timeout(config.query_timeout);
+        connection.apply_pragmas(&config.pragmas);
+        connection
     }
 
     /// Get connection pool statistics
@@ -267,17 +389,26 @@ impl ConnectionManager {
             .read()
             .map_err(|e| FiscusError::Internal(format!("Failed to acquire stats lock: {e}")))?;
 
+        let active = if self.config.is_pooling_enabled() {
+            (self.config.max_connections as usize).saturating_sub(self.permits.available_permits())
+        } else {
+            0
+        };
+
         let pool = self
             .pool
             .read()
             .map_err(|e| FiscusError::Internal(format!("Failed to acquire pool lock: {e}")))?;
 
         Ok(PoolStats {
-            total_connections: pool.len(),
-            active_connections: pool.len(), // Simplified for now
+            total_connections: pool.len() + active,
+            active_connections: active,
             idle_connections: pool.len(),
             total_requests: stats.total_requests,
             failed_requests: stats.failed_requests,
+            max_connections: self.config.max_connections,
+            min_connections: self.config.min_connections,
+            acquire_timeout_ms: self.config.acquire_timeout().as_millis() as u64,
         })
     }
 
@@ -357,29 +488,115 @@ mod tests {
         assert!(manager.config.is_pooling_enabled());
     }
 
-    #[test]
-    fn test_get_connection() {
+    #[tokio::test]
+    async fn test_get_connection() {
         let config = DatabaseConfig::default();
         let manager = ConnectionManager::new(config).unwrap();
 
-        let conn = manager.get_connection().unwrap();
+        let conn = manager.get_connection().await.unwrap();
         assert_eq!(conn.url, "sqlite:fiscus.db");
         assert_eq!(conn.db_type, DatabaseType::SQLite);
     }
 
-    #[test]
-    fn test_connection_pooling() {
+    #[tokio::test]
+    async fn test_connection_pooling() {
         let config = DatabaseConfig::default();
         let manager = ConnectionManager::new(config).unwrap();
 
-        let conn1 = manager.get_connection().unwrap();
+        let conn1 = manager.get_connection().await.unwrap();
         let conn_id = conn1.connection_id.clone();
 
-        // Return connection to pool
-        manager.return_connection(conn1).unwrap();
+        // Dropping the guard returns the connection to the pool
+        drop(conn1);
 
         // Get another connection - should reuse the pooled one
-        let conn2 = manager.get_connection().unwrap();
+        let conn2 = manager.get_connection().await.unwrap();
         assert_eq!(conn2.connection_id, conn_id);
     }
+
+    #[tokio::test]
+    async fn test_configured_pragmas_are_applied_once_per_connection() {
+        let mut config = DatabaseConfig::default();
+        config.pragmas.busy_timeout = std::time::Duration::from_millis(2500);
+        let manager = ConnectionManager::new(config).unwrap();
+
+        let mut conn = manager.get_connection().await.unwrap();
+        assert!(conn
+            .applied_pragmas
+            .contains(&"PRAGMA journal_mode = WAL".to_string()));
+        assert!(conn
+            .applied_pragmas
+            .contains(&"PRAGMA busy_timeout = 2500".to_string()));
+        assert!(!conn.apply_pragmas(&manager.config().pragmas));
+
+        drop(conn);
+        let reused = manager.get_connection().await.unwrap();
+        assert_eq!(reused.applied_pragmas.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pool_stats_surface_configuration() {
+        let config = DatabaseConfig {
+            max_connections: 3,
+            min_connections: 2,
+            acquire_timeout: std::time::Duration::from_millis(250),
+            ..Default::default()
+        };
+        let manager = ConnectionManager::new(config).unwrap();
+
+        let stats = manager.get_stats().unwrap();
+        assert_eq!(stats.max_connections, 3);
+        assert_eq!(stats.min_connections, 2);
+        assert_eq!(stats.acquire_timeout_ms, 250);
+        assert_eq!(stats.idle_connections, 2);
+        assert_eq!(stats.active_connections, 0);
+
+        let _conn = manager.get_connection().await.unwrap();
+        let stats = manager.get_stats().unwrap();
+        assert_eq!(stats.active_connections, 1);
+        assert_eq!(stats.idle_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn test_over_acquisition_times_out() {
+        let config = DatabaseConfig {
+            max_connections: 1,
+            min_connections: 0,
+            acquire_timeout: std::time::Duration::from_millis(50),
+            ..Default::default()
+        };
+        let manager = ConnectionManager::new(config).unwrap();
+
+        let held = manager.get_connection().await.unwrap();
+
+        let start = Instant::now();
+        let result = manager.get_connection().await;
+        assert!(matches!(result, Err(FiscusError::Database(_))));
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+        // Returning the held connection makes the pool usable again
+        drop(held);
+        assert!(manager.get_connection().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_connections_free_their_slots() {
+        let config = DatabaseConfig {
+            max_connections: 2,
+            min_connections: 0,
+            acquire_timeout: std::time::Duration::from_millis(50),
+            ..Default::default()
+        };
+        let manager = ConnectionManager::new(config).unwrap();
+
+        // Far more checkouts than the pool holds, each released when it goes out of scope
+        for _ in 0..10 {
+            let conn = manager.get_connection().await.unwrap();
+            assert!(!conn.connection_id.is_empty());
+        }
+
+        let stats = manager.get_stats().unwrap();
+        assert_eq!(stats.active_connections, 0);
+        assert_eq!(stats.idle_connections, 1);
+    }
 }
@@ -317,16 +317,25 @@ impl SecureStorageRepository {
     /// Clean up expired data entries
     #[instrument(skip(self))]
     pub async fn cleanup_expired(&self) -> FiscusResult<u64> {
-        let query = r#"
-            DELETE FROM secure_storage
-            WHERE expires_at IS NOT NULL AND expires_at <= CURRENT_TIMESTAMP
-        "#;
-
         // Execute cleanup operation and get actual deleted count
-        let deleted_count = if cfg!(test) {
-            // In test mode, simulate cleanup
-            0u64
-        } else {
+        #[cfg(test)]
+        let deleted_count = {
+            // In test mode, remove expired records from test storage
+            let test_storage = self.get_test_storage();
+            let mut storage_map = test_storage.lock().unwrap();
+            let before = storage_map.len();
+            let now = Utc::now();
+            storage_map.retain(|_, record| !matches!(record.expires_at, Some(at) if at <= now));
+            (before - storage_map.len()) as u64
+        };
+
+        #[cfg(not(test))]
+        let deleted_count = {
+            let query = r#"
+                DELETE FROM secure_storage
+                WHERE expires_at IS NOT NULL AND expires_at <= CURRENT_TIMESTAMP
+            "#;
+
             // Execute delete and return actual count of affected rows
             DatabaseUtils::execute_non_query(&self.db, query, vec![]).await?
         };
@@ -125,8 +125,8 @@ impl SQLiteManager {
             page_size: 4096, // Default SQLite page size
             cache_size: self.config.max_connections as u64 * 1024, // Estimated
             auto_vacuum: false, // Would be queried from PRAGMA auto_vacuum
-            journal_mode: "WAL".to_string(), // Recommended for Tauri apps
-            synchronous: "NORMAL".to_string(), // Balanced performance/safety
+            journal_mode: self.config.pragmas.journal_mode.to_string(),
+            synchronous: self.config.pragmas.synchronous.to_string(),
         })
     }
 
@@ -141,16 +141,15 @@ impl SQLiteManager {
         );
 
         // Note: In a real implementation, these would be executed as PRAGMA statements
-        let optimizations = vec![
-            "PRAGMA journal_mode = WAL", // Write-Ahead Logging for better concurrency
-            "PRAGMA synchronous = NORMAL", // Balanced safety/performance
-            "PRAGMA cache_size = -64000", // 64MB cache
-            "PRAGMA temp_store = MEMORY", // Store temp tables in memory
-            "PRAGMA mmap_size = 268435456", // 256MB memory-mapped I/O
-        ];
-
-        for pragma in optimizations {
-            debug!(pragma = pragma, "Would execute SQLite optimization");
+        let mut optimizations = self.config.pragmas.statements();
+        optimizations.extend([
+            "PRAGMA cache_size = -64000".to_string(),   // 64MB cache
+            "PRAGMA temp_store = MEMORY".to_string(),   // Store temp tables in memory
+            "PRAGMA mmap_size = 268435456".to_string(), // 256MB memory-mapped I/O
+        ]);
+
+        for pragma in &optimizations {
+            debug!(pragma = %pragma, "Would execute SQLite optimization");
         }
 
         info!("SQLite performance configuration completed");
@@ -238,6 +237,7 @@ pub struct SQLiteStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::DatabaseType;
     use tempfile::tempdir;
 
     #[test]
@@ -301,4 +301,21 @@ mod tests {
         // Should fail for in-memory database
         assert!(manager.backup_database(&backup_path).await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_stats_report_configured_pragmas() {
+        let mut config = DatabaseConfig {
+            database_url: "sqlite::memory:".to_string(),
+            ..Default::default()
+        };
+        config.pragmas.synchronous = crate::database::config::SynchronousMode::Full;
+
+        let manager = SQLiteManager::new(config).unwrap();
+        let connection =
+            DatabaseConnection::new("sqlite::memory:".to_string(), DatabaseType::SQLite);
+        let stats = manager.get_sqlite_stats(&connection).await.unwrap();
+
+        assert_eq!(stats.journal_mode, "WAL");
+        assert_eq!(stats.synchronous, "FULL");
+    }
 }
@@ -0,0 +1,98 @@
+//! SQLite backend for `DatabaseUtils` in tests
+//!
+//! Production queries run through the Tauri SQL plugin. Under test a
+//! `Database` handle can carry a sqlx pool instead, so commands execute
+//! against a real, migrated schema. Rows come back as JSON objects the way
+//! the plugin returns them: integers and reals as numbers, `BOOLEAN`
+//! columns as booleans and everything else as text.
+use serde_json::Value;
+use sqlx::sqlite::{SqliteArguments, SqlitePool, SqliteRow};
+use sqlx::{query::Query, Column, Row, Sqlite, TypeInfo, ValueRef};
+use std::collections::HashMap;
+
+use crate::error::{FiscusError, FiscusResult};
+
+/// Map a sqlx failure onto the application error type
+fn sqlx_error(e: sqlx::Error) -> FiscusError {
+    FiscusError::Database(e.to_string())
+}
+
+/// Bind JSON parameters in order, the way `DatabaseUtils` receives them
+pub(crate) fn bind_params<'q>(
+    mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    params: Vec<Value>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    for param in params {
+        query = match param {
+            Value::Null => query.bind(None::<String>),
+            Value::Bool(flag) => query.bind(flag),
+            Value::Number(number) => match number.as_i64() {
+                Some(integer) => query.bind(integer),
+                None => query.bind(number.as_f64()),
+            },
+            Value::String(text) => query.bind(text),
+            other => query.bind(other.to_string()),
+        };
+    }
+    query
+}
+
+/// Column values of a row keyed by column name
+fn row_to_json(row: &SqliteRow) -> FiscusResult<HashMap<String, Value>> {
+    let mut object = HashMap::with_capacity(row.columns().len());
+
+    for column in row.columns() {
+        let index = column.ordinal();
+        let raw = row.try_get_raw(index).map_err(sqlx_error)?;
+        let storage = raw.type_info().name().to_string();
+
+        let value = if raw.is_null() {
+            Value::Null
+        } else if column.type_info().name() == "BOOLEAN" {
+            Value::Bool(row.try_get_unchecked::<i64, _>(index).map_err(sqlx_error)? != 0)
+        } else {
+            match storage.as_str() {
+                "INTEGER" => {
+                    Value::from(row.try_get_unchecked::<i64, _>(index).map_err(sqlx_error)?)
+                }
+                "REAL" => Value::from(row.try_get_unchecked::<f64, _>(index).map_err(sqlx_error)?),
+                _ => Value::String(
+                    row.try_get_unchecked::<String, _>(index)
+                        .map_err(sqlx_error)?,
+                ),
+            }
+        };
+
+        object.insert(column.name().to_string(), value);
+    }
+
+    Ok(object)
+}
+
+/// Rows returned by `query`
+pub(crate) async fn fetch_rows(
+    pool: &SqlitePool,
+    query: &str,
+    params: Vec<Value>,
+) -> FiscusResult<Vec<HashMap<String, Value>>> {
+    let rows = bind_params(sqlx::query(query), params)
+        .fetch_all(pool)
+        .await
+        .map_err(sqlx_error)?;
+
+    rows.iter().map(row_to_json).collect()
+}
+
+/// Run a statement and return the number of affected rows
+pub(crate) async fn execute(
+    pool: &SqlitePool,
+    query: &str,
+    params: Vec<Value>,
+) -> FiscusResult<u64> {
+    let result = bind_params(sqlx::query(query), params)
+        .execute(pool)
+        .await
+        .map_err(sqlx_error)?;
+
+    Ok(result.rows_affected())
+}
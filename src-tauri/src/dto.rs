@@ -1,23 +1,93 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::encryption::types::{EncryptionAlgorithm, KeyDerivationAlgorithm, KeyType};
-use crate::error::{ValidatedCurrency, ValidatedUserId};
-use crate::models::{GoalStatus, TransactionStatus, TransactionType};
+use crate::database::PoolStats;
+use crate::encryption::types::{
+    AlgorithmCapabilities, CompressionAlgorithm, EncryptionAlgorithm, KeyDerivationAlgorithm,
+    KeyType,
+};
+use crate::encryption::KeyMetadata;
+use crate::error::{FiscusError, FiscusResult, ValidatedCurrency, ValidatedUserId};
+use crate::logging::{DataSanitizer, Sanitizable};
+use crate::models::{
+    Account, AccountGroup, AccrualFrequency, AccrualKind, Budget, BudgetPeriod,
+    BudgetTemplateAllocation, Goal, GoalStatus, Transaction, TransactionStatus, TransactionType,
+};
 use crate::security::data_protection::SensitiveData;
 
+/// Field of a partial update that distinguishes "leave unchanged" from "clear"
+///
+/// An absent JSON key deserializes to `Unset` (with `#[serde(default)]` on the
+/// field), an explicit `null` to `Null`, and any other value to `Set`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Patch<T> {
+    #[default]
+    Unset,
+    Null,
+    Set(T),
+}
+
+impl<T> Patch<T> {
+    /// Whether the field was left out of the request
+    pub fn is_unset(&self) -> bool {
+        matches!(self, Patch::Unset)
+    }
+
+    /// Borrow the contained value
+    pub fn as_ref(&self) -> Patch<&T> {
+        match self {
+            Patch::Unset => Patch::Unset,
+            Patch::Null => Patch::Null,
+            Patch::Set(value) => Patch::Set(value),
+        }
+    }
+}
+
+/// A patch is sent as the field's value or `null`, like an `Option`
+impl<T: JsonSchema> JsonSchema for Patch<T> {
+    fn schema_name() -> String {
+        Option::<T>::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        Option::<T>::json_schema(gen)
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Patch<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(|value| match value {
+            Some(value) => Patch::Set(value),
+            None => Patch::Null,
+        })
+    }
+}
+
 /// Request DTOs for creating entities
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct CreateUserRequest {
     pub username: String,
     pub email: Option<String>,
     pub password: SensitiveData<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateAccountRequest {
     pub user_id: ValidatedUserId,
     pub account_type_id: String,
@@ -27,7 +97,28 @@ pub struct CreateAccountRequest {
     pub account_number: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateAccountGroupRequest {
+    pub user_id: ValidatedUserId,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateAccountAccrualRequest {
+    pub user_id: ValidatedUserId,
+    pub account_id: String,
+    pub name: String,
+    pub kind: AccrualKind,
+    /// Flat amount per period; required for fees
+    pub amount: Option<Decimal>,
+    /// Annual rate as a fraction; required for interest
+    pub annual_rate: Option<Decimal>,
+    pub frequency: AccrualFrequency,
+    pub category_id: Option<String>,
+    pub start_date: String, // YYYY-MM-DD format
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateCategoryRequest {
     pub user_id: ValidatedUserId,
     pub name: String,
@@ -36,9 +127,44 @@ pub struct CreateCategoryRequest {
     pub icon: Option<String>,
     pub parent_category_id: Option<String>,
     pub is_income: bool,
+    #[serde(default)]
+    pub tax_deductible: bool,
 }
 
-#[derive(Debug, Deserialize)]
+/// Environment variable selecting the `AmountConvention`
+const AMOUNT_CONVENTION_ENV: &str = "FISCUS_AMOUNT_CONVENTION";
+
+/// How the sign of an incoming transaction amount is read
+///
+/// Whatever the convention, a new transaction is stored as a positive
+/// magnitude plus its type. Transfer legs are the exception: their direction
+/// is carried by the sign of the amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmountConvention {
+    /// The type decides the direction and the sign of the amount is ignored
+    #[default]
+    Typed,
+    /// A negative amount is an expense and a positive one income; the type
+    /// may be omitted but must agree with the sign when given
+    Signed,
+}
+
+impl AmountConvention {
+    /// Convention read from `FISCUS_AMOUNT_CONVENTION` (`typed` or `signed`), typed by default
+    pub fn from_env() -> Self {
+        match std::env::var(AMOUNT_CONVENTION_ENV)
+            .map(|value| value.trim().to_lowercase())
+            .as_deref()
+        {
+            Ok("signed") => AmountConvention::Signed,
+            _ => AmountConvention::Typed,
+        }
+    }
+}
+
+crate::env_config!(AmountConvention);
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateTransactionRequest {
     pub user_id: ValidatedUserId,
     pub account_id: String,
@@ -47,13 +173,93 @@ pub struct CreateTransactionRequest {
     pub description: String,
     pub notes: Option<String>,
     pub transaction_date: DateTime<Utc>,
-    pub transaction_type: TransactionType,
+    /// Required under `AmountConvention::Typed`; see `normalize_amount`
+    #[serde(default)]
+    pub transaction_type: Option<TransactionType>,
     pub reference_number: Option<String>,
     pub payee: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Client-generated key used to deduplicate retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Amount in a foreign currency; when set with `original_currency`, the
+    /// posted `amount` is converted from it at the stored exchange rate
+    #[serde(default)]
+    pub original_amount: Option<Decimal>,
+    #[serde(default)]
+    pub original_currency: Option<ValidatedCurrency>,
+    /// Book an expense even if it breaks an account or category spending limit
+    #[serde(default)]
+    pub override_limit: bool,
+    /// Expense this income refunds; see `create_refund`
+    #[serde(default)]
+    pub refunds_transaction_id: Option<String>,
+}
+
+impl CreateTransactionRequest {
+    /// Type of the transaction, inferred from the sign of `amount` when omitted
+    pub fn transaction_type(&self) -> TransactionType {
+        self.transaction_type
+            .clone()
+            .unwrap_or_else(|| self.type_implied_by_sign())
+    }
+
+    fn type_implied_by_sign(&self) -> TransactionType {
+        if self.amount < Decimal::ZERO {
+            TransactionType::Expense
+        } else {
+            TransactionType::Income
+        }
+    }
+
+    /// Canonicalize `amount` to a positive magnitude and fill in the type
+    ///
+    /// Refunds are left as given so that a negative refund is rejected by
+    /// validation rather than silently flipped.
+    pub fn normalize_amount(&mut self, convention: AmountConvention) -> FiscusResult<()> {
+        if self.refunds_transaction_id.is_some() {
+            return Ok(());
+        }
+
+        let transaction_type = match (convention, &self.transaction_type) {
+            (_, Some(TransactionType::Transfer)) => TransactionType::Transfer,
+            (AmountConvention::Typed, Some(transaction_type)) => transaction_type.clone(),
+            (AmountConvention::Typed, None) => {
+                return Err(FiscusError::field_validation(
+                    "transaction_type",
+                    "required",
+                    "transaction_type is required",
+                ))
+            }
+            (AmountConvention::Signed, declared) => {
+                if self.amount.is_zero() && declared.is_none() {
+                    return Err(FiscusError::field_validation(
+                        "transaction_type",
+                        "required",
+                        "transaction_type is required for a zero amount",
+                    ));
+                }
+
+                let implied = self.type_implied_by_sign();
+                if !self.amount.is_zero() && declared.as_ref().is_some_and(|d| *d != implied) {
+                    return Err(FiscusError::field_validation(
+                        "amount",
+                        "sign_mismatch",
+                        "Amount sign contradicts transaction_type; expenses are negative",
+                    ));
+                }
+                declared.clone().unwrap_or(implied)
+            }
+        };
+
+        self.amount = self.amount.abs();
+        self.original_amount = self.original_amount.map(|amount| amount.abs());
+        self.transaction_type = Some(transaction_type);
+        Ok(())
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateBudgetPeriodRequest {
     pub user_id: ValidatedUserId,
     pub name: String,
@@ -61,7 +267,7 @@ pub struct CreateBudgetPeriodRequest {
     pub end_date: String,   // YYYY-MM-DD format
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateBudgetRequest {
     pub user_id: ValidatedUserId,
     pub budget_period_id: String,
@@ -70,7 +276,14 @@ pub struct CreateBudgetRequest {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBudgetTemplateRequest {
+    pub user_id: ValidatedUserId,
+    pub name: String,
+    pub category_allocations: Vec<BudgetTemplateAllocation>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateGoalRequest {
     pub user_id: ValidatedUserId,
     pub name: String,
@@ -81,7 +294,7 @@ pub struct CreateGoalRequest {
     pub category: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateTransferRequest {
     pub user_id: ValidatedUserId,
     pub from_account_id: String,
@@ -89,25 +302,42 @@ pub struct CreateTransferRequest {
     pub amount: Decimal,
     pub description: String,
     pub transfer_date: String, // ISO 8601 format
+    /// Fee charged on top of `amount`, posted as an expense on the source account
+    #[serde(default)]
+    pub fee_amount: Option<Decimal>,
+    /// Category of the fee expense, e.g. bank fees; requires `fee_amount`
+    #[serde(default)]
+    pub fee_category_id: Option<String>,
 }
 
 /// Update DTOs for modifying entities
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateUserRequest {
     pub username: Option<String>,
     pub email: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateAccountRequest {
     pub name: Option<String>,
     pub balance: Option<Decimal>,
-    pub account_number: Option<String>,
+    /// `null` clears the stored account number
+    #[serde(default)]
+    pub account_number: Patch<String>,
     pub is_active: Option<bool>,
+    /// Category new transactions fall back to; `null` clears it
+    #[serde(default)]
+    pub default_category_id: Patch<String>,
+    /// Payee new transactions fall back to; `null` clears it
+    #[serde(default)]
+    pub default_payee: Patch<String>,
+    /// `updated_at` the client last saw; the update is rejected as stale if it changed
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateCategoryRequest {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -115,29 +345,37 @@ pub struct UpdateCategoryRequest {
     pub icon: Option<String>,
     pub parent_category_id: Option<String>,
     pub is_active: Option<bool>,
+    pub tax_deductible: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateTransactionRequest {
-    pub category_id: Option<String>,
+    /// `null` removes the transaction's category
+    #[serde(default)]
+    pub category_id: Patch<String>,
     pub amount: Option<Decimal>,
     pub description: Option<String>,
-    pub notes: Option<String>,
+    /// `null` clears the notes
+    #[serde(default)]
+    pub notes: Patch<String>,
     pub transaction_date: Option<String>,
     pub transaction_type: Option<TransactionType>,
     pub status: Option<TransactionStatus>,
     pub reference_number: Option<String>,
     pub payee: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// `updated_at` the client last saw; the update is rejected as stale if it changed
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateBudgetRequest {
     pub allocated_amount: Option<Decimal>,
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateGoalRequest {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -151,7 +389,7 @@ pub struct UpdateGoalRequest {
 
 /// Filter and query DTOs
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct AccountFilters {
     pub user_id: ValidatedUserId,
     pub account_type_id: Option<String>,
@@ -160,9 +398,13 @@ pub struct AccountFilters {
     pub sort_direction: Option<String>,
     pub limit: Option<i32>,
     pub offset: Option<i32>,
+    /// Return full account numbers; needs the reveal permission, otherwise
+    /// numbers stay masked
+    #[serde(default)]
+    pub reveal_account_number: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct TransactionFilters {
     pub user_id: ValidatedUserId,
     pub account_id: Option<String>,
@@ -174,13 +416,25 @@ pub struct TransactionFilters {
     pub min_amount: Option<Decimal>,
     pub max_amount: Option<Decimal>,
     pub search: Option<String>,
+    /// Only return transactions with exactly this payee (ignoring case and extra whitespace)
+    #[serde(default)]
+    pub payee: Option<String>,
+    /// Only return transactions carrying this tag (matched case-insensitively)
+    #[serde(default)]
+    pub tag_filter: Option<String>,
+    /// Opaque keyset cursor from a previous page's `next_cursor`
+    #[serde(default)]
+    pub cursor: Option<String>,
     pub sort_by: Option<String>,
     pub sort_direction: Option<String>,
     pub limit: Option<i32>,
     pub offset: Option<i32>,
+    /// Only return these fields (plus `id`); encrypted fields left out are not decrypted
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CategoryFilters {
     pub user_id: ValidatedUserId,
     pub parent_category_id: Option<String>,
@@ -190,7 +444,7 @@ pub struct CategoryFilters {
     pub sort_direction: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct BudgetFilters {
     pub user_id: ValidatedUserId,
     pub budget_period_id: Option<String>,
@@ -199,7 +453,7 @@ pub struct BudgetFilters {
     pub sort_direction: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct GoalFilters {
     pub user_id: ValidatedUserId,
     pub status: Option<GoalStatus>,
@@ -210,28 +464,34 @@ pub struct GoalFilters {
 
 /// Authentication DTOs
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct LoginRequest {
     pub username: String,
     pub password: SensitiveData<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct ChangePasswordRequest {
     pub user_id: ValidatedUserId,
     pub current_password: SensitiveData<String>,
     pub new_password: SensitiveData<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReauthenticateRequest {
+    pub user_id: ValidatedUserId,
+    pub password: SensitiveData<String>,
+}
+
 /// Response DTOs
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LoginResponse {
     pub user: UserResponse,
     pub session_token: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UserResponse {
     pub id: String,
     pub username: String,
@@ -240,16 +500,43 @@ pub struct UserResponse {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
     pub total: i32,
     pub page: i32,
     pub per_page: i32,
     pub total_pages: i32,
+    /// Set when `data` was cut short at the query soft cap
+    #[serde(default)]
+    pub truncated: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Result of `import_transactions`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TransactionImportResponse {
+    pub imported: Vec<Transaction>,
+    /// Positions in the request of rows skipped as already imported
+    pub skipped_duplicates: Vec<usize>,
+}
+
+/// Result of `import_transactions_json`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TransactionJsonImportResponse {
+    pub imported: Vec<Transaction>,
+    /// Positions in the export of rows skipped as not importable
+    pub skipped: Vec<usize>,
+}
+
+/// Page of results from keyset pagination
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CursorPaginatedResponse<T> {
+    pub data: Vec<T>,
+    /// Cursor for the next page; `None` once the last page is reached
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AccountSummaryResponse {
     pub total_assets: Decimal,
     pub total_liabilities: Decimal,
@@ -257,7 +544,32 @@ pub struct AccountSummaryResponse {
     pub account_count: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Stored account balance compared with the balance implied by its transactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BalanceAuditResponse {
+    pub account_id: String,
+    pub stored_balance: Decimal,
+    pub expected_balance: Decimal,
+    /// Stored minus expected; positive when the stored balance is too high
+    pub drift: Decimal,
+    /// Whether the stored balance was overwritten with the expected balance
+    pub repaired: bool,
+}
+
+/// Projected payoff of a liability account at a fixed monthly payment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PayoffProjectionResponse {
+    pub account_id: String,
+    /// Amount owed, as a positive magnitude
+    pub balance: Decimal,
+    pub monthly_payment: Decimal,
+    pub annual_apr: Decimal,
+    pub months_to_payoff: u32,
+    pub total_interest: Decimal,
+    pub payoff_date: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BudgetSummaryResponse {
     pub total_allocated: Decimal,
     pub total_spent: Decimal,
@@ -266,7 +578,51 @@ pub struct BudgetSummaryResponse {
     pub categories_under_budget: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Allocation and actual spending for one category within a budget period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetVsActualLine {
+    pub category_id: Option<String>,
+    pub category_name: String,
+    pub allocated_amount: Decimal,
+    pub spent_amount: Decimal,
+    pub remaining_amount: Decimal,
+    pub percent_used: Decimal,
+    /// Spending in a category that has no budget in the period
+    pub unbudgeted: bool,
+}
+
+/// Budget period containing a date, with the periods either side of it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CurrentBudgetPeriodResponse {
+    pub as_of: NaiveDate,
+    /// Period whose start and end dates include `as_of`
+    pub current: Option<BudgetPeriod>,
+    /// Latest period ending before `as_of`, or before `current` when covered
+    pub previous: Option<BudgetPeriod>,
+    /// Earliest period starting after `as_of`, or after `current` when covered
+    pub next: Option<BudgetPeriod>,
+    /// Whether any period covers `as_of`
+    pub covered: bool,
+    /// Days from `as_of` to the current period's end date; zero on its last day
+    pub days_remaining: Option<i64>,
+}
+
+/// Outcome of merging one category into another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CategoryMergeResponse {
+    pub target_category_id: String,
+    pub transactions_reassigned: u64,
+    /// Transactions in the target category once the merge completes
+    pub target_transaction_count: u64,
+    pub budgets_reassigned: u64,
+    /// Source budgets folded into a target budget for the same period
+    pub budgets_combined: u64,
+    /// Categorization rules and account default categories moved to the target
+    pub references_reassigned: u64,
+    pub categories_reparented: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TransactionSummaryResponse {
     pub total_income: Decimal,
     pub total_expenses: Decimal,
@@ -275,7 +631,90 @@ pub struct TransactionSummaryResponse {
     pub average_transaction: Decimal,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// One category's share of spending in a digest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DigestCategory {
+    pub category_name: String,
+    pub total_amount: Decimal,
+    pub transaction_count: i64,
+}
+
+/// Total expense spending at one payee
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PayeeSpending {
+    /// Payee as first recorded, or "Unknown" for transactions without one
+    pub payee: String,
+    pub total_amount: Decimal,
+    pub transaction_count: i64,
+}
+
+/// Income, expenses and savings rate for one trend bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SavingsRatePoint {
+    pub period: String,
+    pub income: Decimal,
+    pub expenses: Decimal,
+    /// `1 - expenses / income` as a percentage; null for buckets without income
+    pub savings_rate: Option<Decimal>,
+}
+
+/// Deductible expense total for one category in a tax year
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DeductibleCategoryTotal {
+    pub category_id: String,
+    pub category_name: String,
+    pub total_amount: Decimal,
+    pub transaction_count: i64,
+}
+
+/// Expenses in tax-deductible categories for one calendar year
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DeductibleSummaryResponse {
+    pub tax_year: i32,
+    /// Offset from UTC, in minutes, used to assign transactions to a year
+    pub utc_offset_minutes: i32,
+    pub categories: Vec<DeductibleCategoryTotal>,
+    pub total_amount: Decimal,
+}
+
+/// Spending digest over a date window, suitable for rendering as a notification
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DigestResponse {
+    pub user_id: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub total_income: Decimal,
+    pub total_expenses: Decimal,
+    pub net_change: Decimal,
+    pub top_categories: Vec<DigestCategory>,
+    pub largest_transaction: Option<Transaction>,
+    pub budgets_over: i32,
+}
+
+/// Projected end-of-day balance of an account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectedBalance {
+    pub date: NaiveDate,
+    pub balance: Decimal,
+    /// Net effect of the items scheduled for this day
+    pub change: Decimal,
+    pub is_negative: bool,
+}
+
+/// Forward-looking daily balance series for one account
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CashFlowProjectionResponse {
+    pub account_id: String,
+    /// Balance at the end of today, before any future-dated item
+    pub starting_balance: Decimal,
+    pub horizon_days: u32,
+    pub days: Vec<ProjectedBalance>,
+    /// First day the projected balance drops below zero
+    pub first_negative_date: Option<NaiveDate>,
+    pub lowest_balance: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TransactionStatsResponse {
     pub total_transactions: i32,
     pub total_income: Decimal,
@@ -285,33 +724,210 @@ pub struct TransactionStatsResponse {
     pub largest_expense: Option<Decimal>,
     pub largest_income: Option<Decimal>,
     pub most_frequent_category: Option<String>,
+    /// Median expense amount; `None` without expenses
+    pub median_expense: Option<Decimal>,
+    /// 90th percentile expense amount
+    pub p90_expense: Option<Decimal>,
+    /// 95th percentile expense amount
+    pub p95_expense: Option<Decimal>,
     pub transactions_by_type: HashMap<String, i32>,
     pub transactions_by_status: HashMap<String, i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TagUsage {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// Group of transactions that look like the same entry recorded more than once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DuplicateTransactionCluster {
+    pub account_id: String,
+    pub amount: Decimal,
+    pub description: String,
+    pub transaction_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoalProjectionResponse {
+    pub goal_id: String,
+    pub current_amount: Decimal,
+    pub target_amount: Decimal,
+    pub monthly_contribution: Decimal,
+    pub annual_rate: Decimal,
+    /// Months until the target is reached; `None` when it is unreachable
+    pub months_to_target: Option<u32>,
+    pub projected_completion_date: Option<NaiveDate>,
+}
+
+/// A goal after a progress update, with the milestones it newly crossed
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoalProgressResponse {
+    pub goal: Goal,
+    /// Milestone percentages reached by this update, in ascending order
+    pub milestones_reached: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct BulkTransactionRequest {
     pub user_id: ValidatedUserId,
     pub transaction_ids: Vec<String>,
     pub action: BulkTransactionAction,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum BulkTransactionAction {
     Delete,
-    UpdateCategory { category_id: Option<String> },
-    UpdateStatus { status: TransactionStatus },
-    Export { format: ExportFormat },
+    UpdateCategory {
+        category_id: Option<String>,
+    },
+    UpdateStatus {
+        status: TransactionStatus,
+    },
+    Export {
+        format: ExportFormat,
+        /// Dialect used when `format` is CSV
+        #[serde(default)]
+        csv_options: CsvOptions,
+    },
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExportFormat {
     Csv,
     Json,
 }
 
+/// CSV dialect for transaction exports
+///
+/// Fields are quoted as RFC 4180 requires whatever the delimiter, so free text
+/// containing delimiters, quotes or line breaks survives a round trip.
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CsvOptions {
+    /// Field separator, e.g. `;` for spreadsheet locales that use a decimal comma
+    pub delimiter: char,
+    pub quote: char,
+    /// Start the file with a UTF-8 byte order mark so Excel detects the encoding
+    pub include_bom: bool,
+    /// chrono format string for `transaction_date`
+    pub date_format: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            include_bom: false,
+            date_format: "%Y-%m-%d %H:%M:%S".to_string(),
+        }
+    }
+}
+
+/// A user's accounts nested under their groups, as returned by `get_accounts_grouped`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupedAccountsResponse {
+    pub groups: Vec<AccountGroupWithAccounts>,
+    /// Accounts not assigned to any group
+    pub ungrouped: Vec<Account>,
+}
+
+/// An account group and the accounts assigned to it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountGroupWithAccounts {
+    pub group: AccountGroup,
+    pub accounts: Vec<Account>,
+}
+
+/// Budget period and category budgets created from a template
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetTemplateInstance {
+    pub budget_period: BudgetPeriod,
+    pub budgets: Vec<Budget>,
+}
+
+/// Result of `export_transactions_streaming`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionExportSummary {
+    pub file_path: String,
+    pub transaction_count: usize,
+}
+
+/// Portable copy of everything a user owns, as returned by `export_user_archive`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UserDataArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub user_id: String,
+    pub accounts: Vec<HashMap<String, serde_json::Value>>,
+    pub transactions: Vec<HashMap<String, serde_json::Value>>,
+    pub transfers: Vec<HashMap<String, serde_json::Value>>,
+    pub categories: Vec<HashMap<String, serde_json::Value>>,
+    pub budget_periods: Vec<HashMap<String, serde_json::Value>>,
+    pub budgets: Vec<HashMap<String, serde_json::Value>>,
+    pub goals: Vec<HashMap<String, serde_json::Value>>,
+}
+
+/// User archive with a detached Ed25519 signature, as returned by `export_signed_archive`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SignedArchiveResponse {
+    /// Canonical JSON of the `UserDataArchive`; store it byte for byte, since
+    /// the signature covers exactly these bytes
+    pub archive: String,
+    pub signature: String,  // Base64 encoded signature
+    pub public_key: String, // Base64 encoded public key
+    pub algorithm: EncryptionAlgorithm,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// Transaction referencing an account or category that no longer exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OrphanedTransaction {
+    pub transaction_id: String,
+    /// Set when `account_id` points at a missing account
+    pub missing_account_id: Option<String>,
+    /// Set when `category_id` points at a missing category
+    pub missing_category_id: Option<String>,
+}
+
+/// Transfer whose debit or credit leg no longer exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BrokenTransfer {
+    pub transfer_id: String,
+    pub missing_from_transaction_id: Option<String>,
+    pub missing_to_transaction_id: Option<String>,
+}
+
+/// Referential and balance problems found in a user's data, as returned by
+/// `find_data_inconsistencies`
+///
+/// The report is read-only; nothing is repaired.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DataInconsistencyReport {
+    pub user_id: String,
+    pub orphaned_transactions: Vec<OrphanedTransaction>,
+    pub broken_transfers: Vec<BrokenTransfer>,
+    /// Accounts whose stored balance differs from the one implied by their transactions
+    pub balance_mismatches: Vec<BalanceAuditResponse>,
+    pub is_consistent: bool,
+}
+
+/// Bucket size for spending trend reports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendGranularity {
+    Daily,
+    Weekly,
+    #[default]
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
 /// Utility functions for DTOs
 impl<T> PaginatedResponse<T> {
     pub fn new(data: Vec<T>, total: i32, page: i32, per_page: i32) -> Self {
@@ -322,29 +938,34 @@ impl<T> PaginatedResponse<T> {
             page,
             per_page,
             total_pages,
+            truncated: false,
         }
     }
 }
 
 /// Encryption-related DTOs
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct EncryptDataRequest {
     pub user_id: ValidatedUserId,
     pub data_type: String,
     pub data: String, // Base64 encoded data
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct EncryptDataResponse {
     pub encrypted_data: String, // Base64 encoded
     pub nonce: String,          // Base64 encoded
     pub algorithm: EncryptionAlgorithm,
     pub key_id: String,
     pub encrypted_at: DateTime<Utc>,
+    /// Compression tried before encryption; pass back when decrypting
+    pub compression: Option<CompressionAlgorithm>,
+    /// Whether the plaintext was compressed; pass back when decrypting
+    pub compressed: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct DecryptDataRequest {
     pub user_id: ValidatedUserId,
     pub data_type: String,
@@ -352,21 +973,25 @@ pub struct DecryptDataRequest {
     pub nonce: String,          // Base64 encoded
     pub algorithm: EncryptionAlgorithm,
     pub key_id: String,
+    #[serde(default)]
+    pub compression: Option<CompressionAlgorithm>,
+    #[serde(default)]
+    pub compressed: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct DecryptDataResponse {
     pub data: String, // Base64 encoded decrypted data
     pub decrypted_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct GenerateKeyRequest {
     pub user_id: ValidatedUserId,
     pub algorithm: EncryptionAlgorithm,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct GenerateKeyResponse {
     pub key_id: String,
     pub algorithm: EncryptionAlgorithm,
@@ -374,12 +999,75 @@ pub struct GenerateKeyResponse {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct RotateKeysRequest {
     pub user_id: ValidatedUserId,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListUserKeysRequest {
+    pub user_id: ValidatedUserId,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RevokeKeyRequest {
+    pub user_id: ValidatedUserId,
+    pub key_id: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KeyInfoResponse {
+    pub key_id: String,
+    pub data_type: String,
+    pub algorithm: EncryptionAlgorithm,
+    pub is_active: bool,
+    pub is_revoked: bool,
+    pub created_at: DateTime<Utc>,
+    pub usage_count: u64,
+}
+
+impl From<KeyMetadata> for KeyInfoResponse {
+    fn from(key: KeyMetadata) -> Self {
+        Self {
+            key_id: key.key_id,
+            data_type: key.data_type,
+            algorithm: key.algorithm,
+            is_active: key.is_active,
+            is_revoked: key.is_revoked,
+            created_at: key.created_at,
+            usage_count: key.usage_count,
+        }
+    }
+}
+
+/// Key history for one data type, as returned by `get_key_lineage`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KeyLineageResponse {
+    pub user_id: String,
+    pub data_type: String,
+    /// Number of keys the data type has had, including the active one
+    pub generations: usize,
+    pub active_key_id: Option<String>,
+    /// Oldest first
+    pub keys: Vec<KeyInfoResponse>,
+}
+
+/// Timings of one kind of encryption operation since startup
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EncryptionOperationTiming {
+    /// `encrypt`, `decrypt` or `key_derivation`
+    pub operation: String,
+    pub count: u64,
+    pub total_duration_ms: f64,
+    pub average_duration_ms: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EncryptionPerformanceResponse {
+    pub operations: Vec<EncryptionOperationTiming>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct EncryptionStatsResponse {
     pub total_keys: usize,
     pub active_keys: usize,
@@ -390,21 +1078,99 @@ pub struct EncryptionStatsResponse {
     pub last_key_rotation: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct IntegrityFailure {
+    pub table: String,
+    pub row_id: String,
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DataIntegrityResponse {
+    pub user_id: String,
+    pub checked_rows: usize,
+    pub decryptable_rows: usize,
+    pub failing_rows: usize,
+    pub failing_row_ids: Vec<String>,
+    pub failures: Vec<IntegrityFailure>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Transaction fields that could still be decrypted, from `recover_readable_fields`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RecoveredFieldsResponse {
+    pub transaction_id: String,
+    /// The stored row with readable fields decrypted and unreadable ones null
+    pub fields: HashMap<String, serde_json::Value>,
+    /// Encrypted fields that decrypted successfully
+    pub readable_fields: Vec<String>,
+    /// Encrypted fields that could not be decrypted
+    pub unreadable_fields: Vec<String>,
+}
+
+/// One check run by `run_encryption_self_test`
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub passed: bool,
+    /// Failure reason; `None` when the check passed
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EncryptionSelfTestReport {
+    /// True only when every check passed
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+    pub ran_at: DateTime<Utc>,
+}
+
+/// Algorithms this build supports, with what each can be used for
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SupportedAlgorithmsResponse {
+    pub symmetric: Vec<AlgorithmCapabilities>,
+    pub asymmetric: Vec<AlgorithmCapabilities>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AlgorithmMigrationResponse {
+    pub user_id: String,
+    pub data_type: String,
+    pub algorithm: EncryptionAlgorithm,
+    pub key_id: String,
+    pub rows_migrated: usize,
+    pub rows_skipped: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RekeyRequest {
+    pub user_id: ValidatedUserId,
+    pub old_password: SensitiveData<String>,
+    pub new_password: SensitiveData<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RekeyResponse {
+    pub user_id: String,
+    pub keys_rewrapped: usize,
+    pub keys_wrapped: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct DeriveKeyRequest {
     pub password: SensitiveData<String>,
     pub algorithm: KeyDerivationAlgorithm,
     pub salt: Option<String>, // Base64 encoded salt
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct DeriveKeyResponse {
     pub key_id: String,
     pub algorithm: KeyDerivationAlgorithm,
     pub derived_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct SignDataRequest {
     pub user_id: ValidatedUserId,
     pub data: String, // Base64 encoded data to sign
@@ -412,14 +1178,14 @@ pub struct SignDataRequest {
     pub algorithm: EncryptionAlgorithm,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SignDataResponse {
     pub signature: String, // Base64 encoded signature
     pub algorithm: EncryptionAlgorithm,
     pub signed_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct VerifySignatureRequest {
     pub data: String,       // Base64 encoded original data
     pub signature: String,  // Base64 encoded signature
@@ -427,7 +1193,7 @@ pub struct VerifySignatureRequest {
     pub algorithm: EncryptionAlgorithm,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct VerifySignatureResponse {
     pub is_valid: bool,
     pub algorithm: EncryptionAlgorithm,
@@ -435,7 +1201,7 @@ pub struct VerifySignatureResponse {
 }
 
 /// Secure storage DTOs
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct SecureStoreRequest {
     pub user_id: ValidatedUserId,
     pub data_type: String,
@@ -445,20 +1211,20 @@ pub struct SecureStoreRequest {
     pub key_id: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SecureStoreResponse {
     pub stored: bool,
     pub storage_key: String,
     pub stored_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct SecureRetrieveRequest {
     pub user_id: ValidatedUserId,
     pub data_type: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SecureRetrieveResponse {
     pub encrypted_data: String, // Base64 encoded encrypted data
     pub nonce: String,          // Base64 encoded nonce
@@ -467,18 +1233,58 @@ pub struct SecureRetrieveResponse {
     pub stored_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct SecureDeleteRequest {
     pub user_id: ValidatedUserId,
     pub data_type: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SecureDeleteResponse {
     pub deleted: bool,
     pub deleted_at: DateTime<Utc>,
 }
 
+/// Outcome of checking one subsystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", content = "error", rename_all = "lowercase")]
+pub enum SubsystemStatus {
+    Ok,
+    Err(String),
+}
+
+impl SubsystemStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, SubsystemStatus::Ok)
+    }
+}
+
+/// Whether encrypted data can be read and written
+///
+/// `Unavailable` means the app is running in degraded mode: commands that
+/// touch encrypted data fail, while everything else keeps working.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", content = "reason", rename_all = "lowercase")]
+pub enum EncryptionStatus {
+    Available,
+    Unavailable(String),
+}
+
+/// Aggregated status of the backend subsystems
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SystemHealthResponse {
+    pub database: SubsystemStatus,
+    pub encryption: SubsystemStatus,
+    /// Connection pool statistics, when they could be read
+    pub pool: Option<PoolStats>,
+    /// Whether migrations bundled with the app have not been applied yet;
+    /// `None` when the applied version could not be determined
+    pub pending_migrations: Option<bool>,
+    /// First failure encountered while checking, if any
+    pub last_error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
 impl From<crate::models::User> for UserResponse {
     fn from(user: crate::models::User) -> Self {
         Self {
@@ -491,11 +1297,97 @@ impl From<crate::models::User> for UserResponse {
     }
 }
 
+// Log-safe representations of request DTOs
+
+/// Serialize `request` and redact `fields`, so fields added later are logged too
+fn sanitize_request<T: Serialize>(
+    request: &T,
+    sanitizer: &DataSanitizer,
+    fields: &[&str],
+) -> serde_json::Value {
+    match serde_json::to_value(request) {
+        Ok(value) => sanitizer.redact_fields(&value, fields),
+        Err(_) => serde_json::Value::String("[SERIALIZATION_ERROR]".to_string()),
+    }
+}
+
+impl Sanitizable for CreateAccountRequest {
+    fn sanitize(&self, sanitizer: &DataSanitizer) -> serde_json::Value {
+        sanitize_request(self, sanitizer, &["account_number", "balance"])
+    }
+}
+
+impl Sanitizable for CreateTransactionRequest {
+    fn sanitize(&self, sanitizer: &DataSanitizer) -> serde_json::Value {
+        sanitize_request(self, sanitizer, &["amount", "original_amount"])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rust_decimal::Decimal;
 
+    #[test]
+    fn test_create_transaction_request_idempotency_key() {
+        let json = r#"{
+            "user_id": "550e8400-e29b-41d4-a716-446655440000",
+            "account_id": "660e8400-e29b-41d4-a716-446655440000",
+            "amount": 10.00,
+            "description": "Coffee",
+            "transaction_date": "2024-01-15T10:30:00Z",
+            "transaction_type": "expense",
+            "idempotency_key": "retry-abc-123"
+        }"#;
+
+        let request: CreateTransactionRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.idempotency_key, Some("retry-abc-123".to_string()));
+    }
+
+    #[test]
+    fn test_create_transaction_request_sanitized_for_logging() {
+        let json = r#"{
+            "user_id": "550e8400-e29b-41d4-a716-446655440000",
+            "account_id": "660e8400-e29b-41d4-a716-446655440000",
+            "amount": 12345.67,
+            "description": "Rent",
+            "transaction_date": "2024-01-15T10:30:00Z",
+            "transaction_type": "expense"
+        }"#;
+
+        let request: CreateTransactionRequest = serde_json::from_str(json).unwrap();
+        let sanitized = request.sanitize(&DataSanitizer::new());
+
+        assert_eq!(sanitized["amount"], "[REDACTED]");
+        assert_eq!(
+            sanitized["account_id"],
+            "660e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(sanitized["description"], "Rent");
+        // Every field of the request is logged, not a hand-picked subset
+        assert_eq!(sanitized["override_limit"], false);
+        assert!(sanitized.get("idempotency_key").is_some());
+    }
+
+    #[test]
+    fn test_create_account_request_sanitized_for_logging() {
+        let json = r#"{
+            "user_id": "550e8400-e29b-41d4-a716-446655440000",
+            "account_type_id": "checking",
+            "name": "Main",
+            "balance": 100.00,
+            "currency": "USD",
+            "account_number": "1234567890123456"
+        }"#;
+
+        let request: CreateAccountRequest = serde_json::from_str(json).unwrap();
+        let sanitized = request.sanitize(&DataSanitizer::new());
+
+        assert_eq!(sanitized["account_number"], "[REDACTED]");
+        assert_eq!(sanitized["balance"], "[REDACTED]");
+        assert_eq!(sanitized["name"], "Main");
+    }
+
     #[test]
     fn test_create_user_request_deserialization() {
         let json = r#"{
@@ -592,7 +1484,7 @@ mod tests {
         );
         assert_eq!(
             request.transaction_type,
-            crate::models::TransactionType::Expense
+            Some(crate::models::TransactionType::Expense)
         );
         assert_eq!(request.reference_number, Some("REF123".to_string()));
         assert_eq!(request.payee, Some("Grocery Store".to_string()));
@@ -602,6 +1494,73 @@ mod tests {
         );
     }
 
+    fn amount_request(amount: &str, transaction_type: Option<&str>) -> CreateTransactionRequest {
+        let mut json = serde_json::json!({
+            "user_id": "550e8400-e29b-41d4-a716-446655440000",
+            "account_id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+            "amount": amount.parse::<f64>().unwrap(),
+            "description": "Coffee",
+            "transaction_date": "2024-03-01T08:00:00Z"
+        });
+        if let Some(transaction_type) = transaction_type {
+            json["transaction_type"] = serde_json::json!(transaction_type);
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_signed_negative_amount_without_type_is_expense() {
+        let mut request = amount_request("-50", None);
+        request.normalize_amount(AmountConvention::Signed).unwrap();
+
+        assert_eq!(request.amount, Decimal::new(50, 0));
+        assert_eq!(
+            request.transaction_type,
+            Some(crate::models::TransactionType::Expense)
+        );
+    }
+
+    #[test]
+    fn test_positive_income_stays_income() {
+        for convention in [AmountConvention::Typed, AmountConvention::Signed] {
+            let mut request = amount_request("50", Some("income"));
+            request.normalize_amount(convention).unwrap();
+
+            assert_eq!(request.amount, Decimal::new(50, 0));
+            assert_eq!(
+                request.transaction_type,
+                Some(crate::models::TransactionType::Income)
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_convention_ignores_sign() {
+        let mut request = amount_request("-50", Some("expense"));
+        request.normalize_amount(AmountConvention::Typed).unwrap();
+
+        assert_eq!(request.amount, Decimal::new(50, 0));
+        assert_eq!(
+            request.transaction_type,
+            Some(crate::models::TransactionType::Expense)
+        );
+    }
+
+    #[test]
+    fn test_amount_convention_rejections() {
+        let mut request = amount_request("50", None);
+        assert!(matches!(
+            request.normalize_amount(AmountConvention::Typed),
+            Err(FiscusError::FieldValidation { ref field, .. }) if field == "transaction_type"
+        ));
+
+        let mut request = amount_request("50", Some("expense"));
+        assert!(matches!(
+            request.normalize_amount(AmountConvention::Signed),
+            Err(FiscusError::FieldValidation { ref code, .. }) if code == "sign_mismatch"
+        ));
+    }
+
     #[test]
     fn test_create_category_request_deserialization() {
         let json = r#"{
@@ -847,7 +1806,7 @@ mod tests {
 
         let request: UpdateAccountRequest = serde_json::from_str(json).unwrap();
         assert_eq!(request.name, Some("Updated Account Name".to_string()));
-        assert_eq!(request.account_number, Some("987654321".to_string()));
+        assert_eq!(request.account_number, Patch::Set("987654321".to_string()));
 
         // Test UpdateTransactionRequest
         let json = r#"{
@@ -859,7 +1818,27 @@ mod tests {
         let request: UpdateTransactionRequest = serde_json::from_str(json).unwrap();
         assert_eq!(request.amount, Some(Decimal::new(7550, 2)));
         assert_eq!(request.description, Some("Updated description".to_string()));
-        assert_eq!(request.category_id, Some("new-category-id".to_string()));
+        assert_eq!(
+            request.category_id,
+            Patch::Set("new-category-id".to_string())
+        );
+        assert_eq!(request.notes, Patch::Unset);
+    }
+
+    #[test]
+    fn test_patch_distinguishes_absent_from_null() {
+        let request: UpdateAccountRequest =
+            serde_json::from_str(r#"{"name": "Checking"}"#).unwrap();
+        assert_eq!(request.account_number, Patch::Unset);
+
+        let request: UpdateAccountRequest =
+            serde_json::from_str(r#"{"account_number": null}"#).unwrap();
+        assert_eq!(request.account_number, Patch::Null);
+
+        let request: UpdateTransactionRequest =
+            serde_json::from_str(r#"{"notes": null, "category_id": null}"#).unwrap();
+        assert_eq!(request.notes, Patch::Null);
+        assert_eq!(request.category_id, Patch::Null);
     }
 
     #[test]
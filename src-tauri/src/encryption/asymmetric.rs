@@ -2,17 +2,24 @@
 ///
 /// This module provides asymmetric (public-key) encryption capabilities using
 /// RSA-4096 and Ed25519 algorithms for secure key exchange and digital signatures.
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
 use async_trait::async_trait;
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::RngCore;
 use rsa::{
     pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey},
-    Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
+    Oaep, Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
 };
+use sha2::Sha256;
 use tracing::{debug, error, instrument, warn};
+use zeroize::Zeroizing;
 
 use super::types::{
-    EncryptedData, EncryptionAlgorithm, EncryptionKey, EncryptionMetadata, EncryptionResult,
-    KeyType,
+    AlgorithmCapabilities, EncryptedData, EncryptionAlgorithm, EncryptionKey, EncryptionMetadata,
+    EncryptionResult, KeyType,
 };
 use crate::error::FiscusError;
 
@@ -53,8 +60,17 @@ pub trait AsymmetricEncryption {
 
     /// Get the algorithm identifier
     fn algorithm(&self) -> EncryptionAlgorithm;
+
+    /// What this implementation supports
+    fn capabilities(&self) -> AlgorithmCapabilities;
 }
 
+/// Length of the AES-256-GCM content key used for hybrid encryption
+const HYBRID_CONTENT_KEY_LEN: usize = 32;
+
+/// Length of the AES-256-GCM nonce used for hybrid encryption
+const HYBRID_NONCE_LEN: usize = 12;
+
 /// RSA-4096 asymmetric encryption implementation
 ///
 /// RSA with 4096-bit keys provides strong security for key exchange and
@@ -112,6 +128,110 @@ impl RsaEncryption {
             FiscusError::InvalidInput("Invalid RSA private key format".to_string())
         })
     }
+
+    /// Encrypt a payload of any size for the holder of `public_key`
+    ///
+    /// The payload is encrypted with a random single-use AES-256-GCM content
+    /// key, and the content key is encrypted with RSA-OAEP (SHA-256) and stored
+    /// in `metadata.wrapped_content_key`.
+    #[instrument(skip(data, public_key), fields(data_len = data.len()))]
+    pub fn encrypt_hybrid(data: &[u8], public_key: &[u8]) -> EncryptionResult<EncryptedData> {
+        let rsa_public_key = Self::public_key_from_pem(public_key)?;
+        let mut rng = rand::rngs::OsRng;
+
+        let mut content_key = Zeroizing::new([0u8; HYBRID_CONTENT_KEY_LEN]);
+        rng.fill_bytes(&mut content_key[..]);
+        // The content key is never reused, so a random nonce cannot repeat under it
+        let mut nonce_bytes = vec![0u8; HYBRID_NONCE_LEN];
+        rng.fill_bytes(&mut nonce_bytes);
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&content_key[..]));
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), data)
+            .map_err(|e| {
+                error!("Hybrid payload encryption failed: {}", e);
+                FiscusError::Internal("Encryption operation failed".to_string())
+            })?;
+
+        let wrapped_content_key = rsa_public_key
+            .encrypt(&mut rng, Oaep::new::<Sha256>(), &content_key[..])
+            .map_err(|e| {
+                error!("RSA-OAEP key wrapping failed: {}", e);
+                FiscusError::Internal("RSA encryption failed".to_string())
+            })?;
+
+        let mut metadata =
+            EncryptionMetadata::new(EncryptionAlgorithm::Rsa4096, "rsa-public".to_string());
+        metadata.wrapped_content_key = Some(wrapped_content_key);
+
+        debug!(
+            ciphertext_len = ciphertext.len(),
+            "Hybrid RSA-OAEP encryption completed successfully"
+        );
+
+        Ok(EncryptedData::new(ciphertext, nonce_bytes, None, metadata))
+    }
+
+    /// Decrypt a payload produced by [`RsaEncryption::encrypt_hybrid`]
+    #[instrument(skip(encrypted_data, private_key), fields(ciphertext_len = encrypted_data.ciphertext.len()))]
+    pub fn decrypt_hybrid(
+        encrypted_data: &EncryptedData,
+        private_key: &EncryptionKey,
+    ) -> EncryptionResult<Vec<u8>> {
+        if encrypted_data.metadata.algorithm != EncryptionAlgorithm::Rsa4096 {
+            return Err(FiscusError::InvalidInput(
+                "Algorithm mismatch for RSA decryption".to_string(),
+            ));
+        }
+
+        let wrapped_content_key = encrypted_data
+            .metadata
+            .wrapped_content_key
+            .as_deref()
+            .ok_or_else(|| FiscusError::InvalidInput("Missing wrapped content key".to_string()))?;
+
+        if encrypted_data.nonce.len() != HYBRID_NONCE_LEN {
+            return Err(FiscusError::InvalidInput(
+                "Invalid nonce length for AES-256-GCM (expected 12 bytes)".to_string(),
+            ));
+        }
+
+        let rsa_private_key = Self::private_key_from_pem(private_key.key_bytes())?;
+        let content_key = Zeroizing::new(
+            rsa_private_key
+                .decrypt(Oaep::new::<Sha256>(), wrapped_content_key)
+                .map_err(|e| {
+                    error!("RSA-OAEP key unwrapping failed: {}", e);
+                    FiscusError::Authentication("RSA decryption failed".to_string())
+                })?,
+        );
+
+        if content_key.len() != HYBRID_CONTENT_KEY_LEN {
+            return Err(FiscusError::Authentication(
+                "RSA decryption failed".to_string(),
+            ));
+        }
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&content_key[..]));
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&encrypted_data.nonce),
+                encrypted_data.ciphertext.as_slice(),
+            )
+            .map_err(|e| {
+                error!("Hybrid payload decryption failed: {}", e);
+                FiscusError::Authentication(
+                    "Decryption failed - invalid key or corrupted data".to_string(),
+                )
+            })?;
+
+        debug!(
+            plaintext_len = plaintext.len(),
+            "Hybrid RSA-OAEP decryption completed successfully"
+        );
+
+        Ok(plaintext)
+    }
 }
 
 #[async_trait]
@@ -254,6 +374,16 @@ impl AsymmetricEncryption for RsaEncryption {
     fn algorithm(&self) -> EncryptionAlgorithm {
         EncryptionAlgorithm::Rsa4096
     }
+
+    fn capabilities(&self) -> AlgorithmCapabilities {
+        // Signing and verification are not implemented for RSA yet
+        AlgorithmCapabilities {
+            algorithm: EncryptionAlgorithm::Rsa4096,
+            encrypt: true,
+            sign: false,
+            transmission_safe: true,
+        }
+    }
 }
 
 /// Ed25519 asymmetric encryption implementation
@@ -408,6 +538,15 @@ impl AsymmetricEncryption for Ed25519Encryption {
     fn algorithm(&self) -> EncryptionAlgorithm {
         EncryptionAlgorithm::Ed25519
     }
+
+    fn capabilities(&self) -> AlgorithmCapabilities {
+        AlgorithmCapabilities {
+            algorithm: EncryptionAlgorithm::Ed25519,
+            encrypt: false,
+            sign: true,
+            transmission_safe: false,
+        }
+    }
 }
 
 #[cfg(test)]
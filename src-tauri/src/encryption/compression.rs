@@ -0,0 +1,103 @@
+/// Compression of plaintext before encryption
+///
+/// Ciphertext is incompressible, so large, repetitive fields such as notes
+/// have to be compressed before they are encrypted to save any storage.
+/// Compression is only kept when it makes the payload smaller.
+use std::io::Read;
+
+use zeroize::Zeroizing;
+
+use super::types::{CompressionAlgorithm, EncryptedData, EncryptionResult};
+use crate::error::FiscusError;
+
+/// Largest plaintext a compressed payload may expand to
+///
+/// Guards decryption against decompression bombs.
+pub const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+/// Compress `data`, returning `None` when that would not reduce its size
+pub fn compress_if_smaller(
+    data: &[u8],
+    algorithm: CompressionAlgorithm,
+    level: i32,
+) -> EncryptionResult<Option<Vec<u8>>> {
+    let compressed = match algorithm {
+        CompressionAlgorithm::Zstd => zstd::bulk::compress(data, level)
+            .map_err(|e| FiscusError::Encryption(format!("Compression failed: {e}")))?,
+    };
+
+    Ok((compressed.len() < data.len()).then_some(compressed))
+}
+
+/// Reverse `compress_if_smaller`
+pub fn decompress(data: &[u8], algorithm: CompressionAlgorithm) -> EncryptionResult<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    match algorithm {
+        CompressionAlgorithm::Zstd => zstd::stream::read::Decoder::new(data)
+            .and_then(|decoder| {
+                decoder
+                    .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+                    .read_to_end(&mut decompressed)
+            })
+            .map_err(|e| FiscusError::Encryption(format!("Decompression failed: {e}")))?,
+    };
+
+    if decompressed.len() > MAX_DECOMPRESSED_SIZE {
+        return Err(FiscusError::Encryption(format!(
+            "Decompressed data exceeds {MAX_DECOMPRESSED_SIZE} bytes"
+        )));
+    }
+
+    Ok(decompressed)
+}
+
+/// Decompress decrypted `plaintext` if the metadata of `encrypted_data` says it was compressed
+pub fn restore_plaintext(
+    encrypted_data: &EncryptedData,
+    plaintext: Vec<u8>,
+) -> EncryptionResult<Vec<u8>> {
+    if !encrypted_data.metadata.compressed {
+        return Ok(plaintext);
+    }
+
+    // The compressed form is plaintext too, so it is wiped once expanded
+    let plaintext = Zeroizing::new(plaintext);
+    let algorithm = encrypted_data.metadata.compression.ok_or_else(|| {
+        FiscusError::Encryption("Compressed data is missing its compression algorithm".to_string())
+    })?;
+    decompress(&plaintext, algorithm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressible_payload_round_trips() {
+        let data = "Weekly groceries at the corner shop. ".repeat(200);
+
+        let compressed = compress_if_smaller(data.as_bytes(), CompressionAlgorithm::Zstd, 3)
+            .unwrap()
+            .expect("repetitive text should compress");
+
+        assert!(compressed.len() < data.len());
+        assert_eq!(
+            decompress(&compressed, CompressionAlgorithm::Zstd).unwrap(),
+            data.as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_incompressible_payload_is_left_alone() {
+        let data = b"x7Q";
+
+        assert!(compress_if_smaller(data, CompressionAlgorithm::Zstd, 3)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_garbage_fails_to_decompress() {
+        assert!(decompress(b"not zstd", CompressionAlgorithm::Zstd).is_err());
+    }
+}
@@ -7,20 +7,79 @@ use std::time::Duration;
 use tracing::{debug, info};
 
 use super::nonce_manager::{NonceConfig, NonceStrategy};
-use super::types::{EncryptionAlgorithm, EncryptionResult};
+use super::types::{
+    CompressionAlgorithm, EncryptionAlgorithm, EncryptionResult, KeyDerivationAlgorithm,
+};
 use crate::error::FiscusError;
 
+/// Days until a newly stored key is due for rotation, unless configured otherwise
+pub const DEFAULT_KEY_ROTATION_DAYS: u32 = 90;
+
+/// Accepted range for `EncryptionConfig::key_rotation_days`
+pub const MIN_KEY_ROTATION_DAYS: u32 = 1;
+pub const MAX_KEY_ROTATION_DAYS: u32 = 730;
+
 /// Global encryption service configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptionConfig {
     /// Nonce generation configuration
     pub nonce: NonceConfig,
     /// Key rotation policies
     pub rotation: RotationConfig,
+    /// Days after creation that a key is due for rotation
+    ///
+    /// Only applies to keys stored after the setting changes; existing keys
+    /// keep the due date they were given.
+    #[serde(default = "default_key_rotation_days")]
+    pub key_rotation_days: u32,
+    /// Key derivation function for the password-derived master key
+    #[serde(default)]
+    pub key_derivation: KeyDerivationAlgorithm,
     /// Security settings
     pub security: SecurityConfig,
     /// Performance settings
     pub performance: PerformanceConfig,
+    /// Compression applied to plaintext before encryption
+    #[serde(default)]
+    pub compression: CompressionConfig,
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            nonce: NonceConfig::default(),
+            rotation: RotationConfig::default(),
+            key_rotation_days: DEFAULT_KEY_ROTATION_DAYS,
+            key_derivation: KeyDerivationAlgorithm::default(),
+            security: SecurityConfig::default(),
+            performance: PerformanceConfig::default(),
+            compression: CompressionConfig::default(),
+        }
+    }
+}
+
+fn default_key_rotation_days() -> u32 {
+    DEFAULT_KEY_ROTATION_DAYS
+}
+
+/// Reject rotation intervals outside `MIN_KEY_ROTATION_DAYS..=MAX_KEY_ROTATION_DAYS`
+pub fn validate_key_rotation_days(days: u32) -> EncryptionResult<()> {
+    if !(MIN_KEY_ROTATION_DAYS..=MAX_KEY_ROTATION_DAYS).contains(&days) {
+        return Err(FiscusError::InvalidInput(format!(
+            "Key rotation interval must be between {MIN_KEY_ROTATION_DAYS} and {MAX_KEY_ROTATION_DAYS} days, got {days}"
+        )));
+    }
+    Ok(())
+}
+
+/// Reject key derivation functions that cannot derive keys from passwords
+pub fn validate_password_kdf(algorithm: KeyDerivationAlgorithm) -> EncryptionResult<()> {
+    if algorithm == KeyDerivationAlgorithm::HkdfSha256 {
+        return Err(FiscusError::InvalidInput(
+            "HKDF-SHA256 cannot derive keys from passwords".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 /// Key rotation configuration
@@ -152,6 +211,38 @@ impl Default for PerformanceConfig {
     }
 }
 
+/// Compression configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompressionConfig {
+    /// Data types whose plaintext is compressed before encryption
+    pub data_types: Vec<String>,
+    /// Algorithm used for those data types
+    pub algorithm: CompressionAlgorithm,
+    /// Algorithm-specific compression level
+    pub level: i32,
+}
+
+impl CompressionConfig {
+    /// Algorithm to try for `data_type`, if it is designated for compression
+    pub fn algorithm_for(&self, data_type: &str) -> Option<CompressionAlgorithm> {
+        self.data_types
+            .iter()
+            .any(|designated| designated == data_type)
+            .then_some(self.algorithm)
+    }
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            data_types: vec!["notes".to_string(), "attachments".to_string()],
+            algorithm: CompressionAlgorithm::Zstd,
+            level: 3,
+        }
+    }
+}
+
 /// Configuration manager for the encryption service
 #[derive(Debug)]
 pub struct ConfigManager {
@@ -202,12 +293,38 @@ impl ConfigManager {
             })?;
         }
 
+        if let Ok(auto_rotate) = std::env::var("FISCUS_NONCE_AUTO_ROTATE") {
+            config.nonce.auto_rotate_on_warning = auto_rotate.parse().map_err(|e| {
+                FiscusError::InvalidInput(format!("Invalid nonce auto rotate setting: {e}"))
+            })?;
+        }
+
         if let Ok(auto_rotation) = std::env::var("FISCUS_AUTO_ROTATION") {
             config.rotation.auto_rotation_enabled = auto_rotation.parse().map_err(|e| {
                 FiscusError::InvalidInput(format!("Invalid auto rotation setting: {e}"))
             })?;
         }
 
+        if let Ok(rotation_days) = std::env::var("FISCUS_KEY_ROTATION_DAYS") {
+            config.key_rotation_days = rotation_days.parse().map_err(|e| {
+                FiscusError::InvalidInput(format!("Invalid key rotation days: {e}"))
+            })?;
+            validate_key_rotation_days(config.key_rotation_days)?;
+        }
+
+        if let Ok(algorithm) = std::env::var("FISCUS_KDF_ALGORITHM") {
+            config.key_derivation = match algorithm.to_lowercase().as_str() {
+                "argon2id" => KeyDerivationAlgorithm::Argon2id,
+                "pbkdf2" | "pbkdf2_sha256" => KeyDerivationAlgorithm::Pbkdf2Sha256,
+                "scrypt" => KeyDerivationAlgorithm::Scrypt,
+                _ => {
+                    return Err(FiscusError::InvalidInput(format!(
+                        "Invalid key derivation algorithm: {algorithm}"
+                    )))
+                }
+            };
+        }
+
         debug!("Loaded encryption configuration from environment");
         Ok(Self { config })
     }
@@ -292,6 +409,9 @@ impl ConfigManager {
             }
         }
 
+        validate_key_rotation_days(self.config.key_rotation_days)?;
+        validate_password_kdf(self.config.key_derivation)?;
+
         // Validate security settings
         if self.config.security.min_key_strength.min_symmetric_key_bits < 128 {
             return Err(FiscusError::InvalidInput(
@@ -414,4 +534,54 @@ mod tests {
         let manager = ConfigManager { config };
         assert!(manager.validate().is_err());
     }
+
+    #[test]
+    fn test_key_rotation_days_range() {
+        for days in [MIN_KEY_ROTATION_DAYS, 30, 180, MAX_KEY_ROTATION_DAYS] {
+            let config = EncryptionConfig {
+                key_rotation_days: days,
+                ..EncryptionConfig::default()
+            };
+            assert!(ConfigManager { config }.validate().is_ok());
+        }
+
+        for days in [0, MAX_KEY_ROTATION_DAYS + 1] {
+            let config = EncryptionConfig {
+                key_rotation_days: days,
+                ..EncryptionConfig::default()
+            };
+            assert!(matches!(
+                ConfigManager { config }.validate(),
+                Err(FiscusError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_key_rotation_days_defaults_when_missing() {
+        let mut value = serde_json::to_value(EncryptionConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("key_rotation_days");
+
+        let config: EncryptionConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.key_rotation_days, DEFAULT_KEY_ROTATION_DAYS);
+    }
+
+    #[test]
+    fn test_key_derivation_defaults_to_argon2id() {
+        assert_eq!(
+            EncryptionConfig::default().key_derivation,
+            KeyDerivationAlgorithm::Argon2id
+        );
+
+        let mut value = serde_json::to_value(EncryptionConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("key_derivation");
+        let config: EncryptionConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.key_derivation, KeyDerivationAlgorithm::Argon2id);
+
+        let config = EncryptionConfig {
+            key_derivation: KeyDerivationAlgorithm::HkdfSha256,
+            ..EncryptionConfig::default()
+        };
+        assert!(ConfigManager { config }.validate().is_err());
+    }
 }
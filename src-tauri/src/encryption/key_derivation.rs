@@ -5,6 +5,7 @@
 /// algorithms including Argon2, PBKDF2, and Scrypt.
 use argon2::{Algorithm, Argon2, Params, Version};
 use async_trait::async_trait;
+use hkdf::Hkdf;
 use pbkdf2::pbkdf2_hmac;
 use scrypt::Params as ScryptParams;
 use sha2::Sha256;
@@ -375,6 +376,121 @@ impl KeyDerivation for ScryptKdf {
     }
 }
 
+/// Smallest PBKDF2 iteration count accepted for password-derived keys
+pub const MIN_PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// Accepted Scrypt cost exponents; `N = 2^log_n`, so 20 already needs 1 GiB at r = 8
+const SCRYPT_LOG_N_RANGE: std::ops::RangeInclusive<u32> = 10..=20;
+
+/// Key derivation implementation for a password-based `algorithm`
+pub fn kdf_for(
+    algorithm: KeyDerivationAlgorithm,
+) -> EncryptionResult<Box<dyn KeyDerivation + Send + Sync>> {
+    match algorithm {
+        KeyDerivationAlgorithm::Argon2id => Ok(Box::new(Argon2Kdf::new()?)),
+        KeyDerivationAlgorithm::Pbkdf2Sha256 => Ok(Box::new(Pbkdf2Kdf::new()?)),
+        KeyDerivationAlgorithm::Scrypt => Ok(Box::new(ScryptKdf::new()?)),
+        KeyDerivationAlgorithm::HkdfSha256 => Err(FiscusError::InvalidInput(
+            "HKDF-SHA256 cannot derive keys from passwords".to_string(),
+        )),
+    }
+}
+
+/// Check `params` against the limits of their algorithm before deriving a key
+///
+/// Unset cost parameters are checked at the defaults the implementations fall back to.
+pub fn validate_params(params: &KeyDerivationParams) -> EncryptionResult<()> {
+    let invalid = |message: String| Err(FiscusError::InvalidInput(message));
+
+    if !(16..=64).contains(&params.key_length) {
+        return invalid(format!(
+            "Derived key length must be between 16 and 64 bytes, got {}",
+            params.key_length
+        ));
+    }
+    if params.salt.len() < 16 {
+        return invalid("Key derivation salt must be at least 16 bytes".to_string());
+    }
+
+    match params.algorithm {
+        KeyDerivationAlgorithm::Argon2id => {
+            let memory_cost = params.memory_cost.unwrap_or(65536);
+            let time_cost = params.time_cost.unwrap_or(3);
+            let parallelism = params.parallelism.unwrap_or(1);
+            if memory_cost < 8 {
+                return invalid("Argon2 memory cost too low (minimum 8 KB)".to_string());
+            }
+            if time_cost < 1 {
+                return invalid("Argon2 time cost too low (minimum 1)".to_string());
+            }
+            if !(1..=16).contains(&parallelism) {
+                return invalid("Argon2 parallelism must be between 1 and 16".to_string());
+            }
+        }
+        KeyDerivationAlgorithm::Pbkdf2Sha256 => {
+            let iterations = params.iterations.unwrap_or(120_000);
+            if iterations < MIN_PBKDF2_ITERATIONS {
+                return invalid(format!(
+                    "PBKDF2 needs at least {MIN_PBKDF2_ITERATIONS} iterations, got {iterations}"
+                ));
+            }
+        }
+        KeyDerivationAlgorithm::Scrypt => {
+            let log_n = params.time_cost.unwrap_or(15);
+            if !SCRYPT_LOG_N_RANGE.contains(&log_n) {
+                return invalid(format!(
+                    "Scrypt cost exponent must be between {} and {}, got {log_n}",
+                    SCRYPT_LOG_N_RANGE.start(),
+                    SCRYPT_LOG_N_RANGE.end()
+                ));
+            }
+            if params.memory_cost.unwrap_or(8) < 1 || params.parallelism.unwrap_or(1) < 1 {
+                return invalid("Scrypt block size and parallelism must be at least 1".to_string());
+            }
+        }
+        KeyDerivationAlgorithm::HkdfSha256 => {
+            return invalid("HKDF-SHA256 cannot derive keys from passwords".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Domain separator for per-field subkeys; bump the version if the layout changes
+const FIELD_SUBKEY_INFO: &[u8] = b"fiscus:field-subkey:v1";
+
+/// Derive a per-field subkey from a data type key with HKDF-SHA256
+///
+/// The subkey keeps the parent's `key_id` and algorithm, so it is never
+/// stored: decryption re-derives it from the parent key and `field_label`.
+pub fn derive_field_subkey(
+    parent: &EncryptionKey,
+    field_label: &str,
+) -> EncryptionResult<EncryptionKey> {
+    if field_label.is_empty() {
+        return Err(FiscusError::InvalidInput(
+            "Field label for subkey derivation cannot be empty".to_string(),
+        ));
+    }
+
+    let mut info = Vec::with_capacity(FIELD_SUBKEY_INFO.len() + 4 + field_label.len());
+    info.extend_from_slice(FIELD_SUBKEY_INFO);
+    info.extend_from_slice(&(field_label.len() as u32).to_be_bytes());
+    info.extend_from_slice(field_label.as_bytes());
+
+    let mut subkey = vec![0u8; 32];
+    Hkdf::<Sha256>::new(None, parent.key_bytes())
+        .expand(&info, &mut subkey)
+        .map_err(|e| {
+            error!("HKDF subkey expansion failed: {}", e);
+            FiscusError::Internal("Subkey derivation failed".to_string())
+        })?;
+
+    let mut derived = parent.clone();
+    derived.key_data = subkey.into();
+    Ok(derived)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,4 +546,57 @@ mod tests {
         let is_valid = kdf.verify_password(password, &key, &params).await.unwrap();
         assert!(is_valid);
     }
+
+    #[test]
+    fn test_params_are_validated_per_algorithm() {
+        let salt = vec![3u8; 32];
+
+        assert!(validate_params(&KeyDerivationParams::argon2id_default(salt.clone())).is_ok());
+        assert!(validate_params(&KeyDerivationParams::pbkdf2_default(salt.clone())).is_ok());
+        assert!(validate_params(&KeyDerivationParams::scrypt_default(salt.clone())).is_ok());
+
+        let mut pbkdf2 = KeyDerivationParams::pbkdf2_default(salt.clone());
+        pbkdf2.iterations = Some(MIN_PBKDF2_ITERATIONS - 1);
+        assert!(validate_params(&pbkdf2).is_err());
+
+        let mut scrypt = KeyDerivationParams::scrypt_default(salt.clone());
+        scrypt.time_cost = Some(30);
+        assert!(validate_params(&scrypt).is_err());
+
+        let mut argon2 = KeyDerivationParams::argon2id_default(salt.clone());
+        argon2.parallelism = Some(0);
+        assert!(validate_params(&argon2).is_err());
+
+        let short_salt = KeyDerivationParams::pbkdf2_default(vec![3u8; 8]);
+        assert!(validate_params(&short_salt).is_err());
+
+        assert!(kdf_for(KeyDerivationAlgorithm::HkdfSha256).is_err());
+        assert_eq!(
+            kdf_for(KeyDerivationAlgorithm::Scrypt).unwrap().algorithm(),
+            KeyDerivationAlgorithm::Scrypt
+        );
+    }
+
+    #[test]
+    fn test_field_subkeys_are_distinct_and_deterministic() {
+        let parent = EncryptionKey::new(
+            vec![7u8; 32],
+            KeyType::Symmetric,
+            EncryptionAlgorithm::Aes256Gcm,
+            "parent-key".to_string(),
+        );
+
+        let amount = derive_field_subkey(&parent, "transactions.amount").unwrap();
+        let payee = derive_field_subkey(&parent, "transactions.payee").unwrap();
+        let amount_again = derive_field_subkey(&parent, "transactions.amount").unwrap();
+
+        assert_eq!(amount.key_bytes().len(), 32);
+        assert_ne!(amount.key_bytes(), payee.key_bytes());
+        assert_ne!(amount.key_bytes(), parent.key_bytes());
+        assert_eq!(amount.key_bytes(), amount_again.key_bytes());
+        assert_eq!(amount.key_id, parent.key_id);
+        assert_eq!(amount.algorithm, parent.algorithm);
+
+        assert!(derive_field_subkey(&parent, "").is_err());
+    }
 }
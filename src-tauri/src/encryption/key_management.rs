@@ -5,15 +5,22 @@ use chrono::{DateTime, Duration, Utc};
 /// management for the encryption service. It handles both symmetric and
 /// asymmetric keys with proper security controls.
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 
-use super::key_derivation::{Argon2Kdf, KeyDerivation};
+use super::asymmetric::{AsymmetricEncryption, Ed25519Encryption};
+use super::config::{validate_key_rotation_days, validate_password_kdf, DEFAULT_KEY_ROTATION_DAYS};
+use super::key_derivation;
 use super::symmetric::{AesGcmEncryption, SymmetricEncryption};
-use super::types::{EncryptionKey, EncryptionResult, KeyDerivationParams};
-use super::utils::SecureRandom;
+use super::types::{
+    EncryptedData, EncryptionAlgorithm, EncryptionKey, EncryptionResult, KeyDerivationAlgorithm,
+    KeyDerivationParams, KeyType, SecureBytes,
+};
 use super::EncryptionStats;
+use crate::clock::{system_clock, SharedClock};
 use crate::error::FiscusError;
 
 /// Key storage entry with metadata
@@ -23,6 +30,138 @@ struct KeyEntry {
     usage_count: u64,
     last_used: DateTime<Utc>,
     rotation_due: Option<DateTime<Utc>>,
+    /// When the key was revoked; revoked keys can no longer decrypt data
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Key metadata exposed for administration (never includes key material)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct KeyMetadata {
+    pub key_id: String,
+    pub data_type: String,
+    pub algorithm: EncryptionAlgorithm,
+    pub is_active: bool,
+    pub is_revoked: bool,
+    pub created_at: DateTime<Utc>,
+    pub usage_count: u64,
+}
+
+/// A user key encrypted ("wrapped") under a password-derived master key
+///
+/// The wrapping binds the key ID as associated data, so a wrapped blob cannot
+/// be swapped onto another key's record.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WrappedKey {
+    pub key_id: String,
+    pub key_identifier: String,
+    pub wrapped_key: EncryptedData,
+    /// Algorithm the unwrapped key is used with
+    pub algorithm: EncryptionAlgorithm,
+    /// Whether the key was its data type's active key when wrapped
+    pub is_active: bool,
+}
+
+/// Data type segment of the key identifier under which a user's index key is wrapped
+///
+/// `#` never appears in a data type, so this cannot collide with a data key.
+pub const INDEX_KEY_DATA_TYPE: &str = "#index";
+
+/// Default time-to-live for cached keys
+pub const DEFAULT_KEY_CACHE_TTL: StdDuration = StdDuration::from_secs(30);
+
+/// How often batched usage statistics are written back to key storage
+const USAGE_FLUSH_INTERVAL_MS: i64 = 1_000;
+
+/// Cached copy of a key with usage not yet applied to its `KeyEntry`
+struct CachedKey {
+    key: EncryptionKey,
+    cached_at: Instant,
+    pending_uses: AtomicU64,
+}
+
+/// Read-through key cache served under a read lock
+///
+/// Usage is counted with atomics on the cached entry and folded into the
+/// backing `KeyEntry` by `KeyManager::flush_usage_stats`, so the hot path
+/// never needs the key storage write lock.
+struct KeyCache {
+    entries: RwLock<HashMap<String, CachedKey>>,
+    ttl: StdDuration,
+    /// Unix timestamp (ms) of the last usage flush
+    last_flush_ms: AtomicI64,
+    clock: SharedClock,
+}
+
+impl KeyCache {
+    fn new(ttl: StdDuration, clock: SharedClock) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            last_flush_ms: AtomicI64::new(clock.now().timestamp_millis()),
+            clock,
+        }
+    }
+
+    /// Return a fresh cached key and record one pending use
+    async fn get(&self, key_identifier: &str) -> Option<EncryptionKey> {
+        let entries = self.entries.read().await;
+        let cached = entries.get(key_identifier)?;
+        if self
+            .clock
+            .instant()
+            .saturating_duration_since(cached.cached_at)
+            > self.ttl
+        {
+            return None;
+        }
+        cached.pending_uses.fetch_add(1, Ordering::Relaxed);
+        Some(cached.key.clone())
+    }
+
+    /// Cache a key, returning any pending uses of the entry it replaces
+    async fn insert(&self, key_identifier: &str, key: EncryptionKey) -> u64 {
+        let mut entries = self.entries.write().await;
+        entries
+            .insert(
+                key_identifier.to_string(),
+                CachedKey {
+                    key,
+                    cached_at: self.clock.instant(),
+                    pending_uses: AtomicU64::new(0),
+                },
+            )
+            .map(|old| old.pending_uses.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Take all pending uses, resetting the counters
+    async fn drain_pending_uses(&self) -> Vec<(String, u64)> {
+        let entries = self.entries.read().await;
+        entries
+            .iter()
+            .filter_map(|(key_identifier, cached)| {
+                let uses = cached.pending_uses.swap(0, Ordering::Relaxed);
+                (uses > 0).then(|| (key_identifier.clone(), uses))
+            })
+            .collect()
+    }
+
+    /// Remove cached entries whose identifier starts with `prefix`
+    async fn invalidate_prefix(&self, prefix: &str) {
+        let mut entries = self.entries.write().await;
+        entries.retain(|key_identifier, _| !key_identifier.starts_with(prefix));
+    }
+
+    /// Claim the next periodic flush if the interval has elapsed
+    fn try_claim_flush(&self) -> bool {
+        let now = self.clock.now().timestamp_millis();
+        let last = self.last_flush_ms.load(Ordering::Relaxed);
+        now - last >= USAGE_FLUSH_INTERVAL_MS
+            && self
+                .last_flush_ms
+                .compare_exchange(last, now, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+    }
 }
 
 /// Key manager for secure key storage and lifecycle management
@@ -33,16 +172,29 @@ pub struct KeyManager {
     user_keys: Arc<RwLock<HashMap<String, HashMap<String, String>>>>,
     /// Secondary index for fast key lookup by ID (maps key_id to key_identifier)
     key_id_index: Arc<RwLock<HashMap<String, String>>>,
+    /// Per-user Ed25519 signing key pairs (private, public)
+    ///
+    /// Kept apart from the data keys so key rotation never replaces them.
+    signing_keys: Arc<RwLock<HashMap<String, (EncryptionKey, EncryptionKey)>>>,
+    /// Per-user keys for blind indexes and keyed hashes
+    ///
+    /// Stored indexes must stay comparable across rotations, so these keys
+    /// are never rotated with the data keys.
+    index_keys: Arc<RwLock<HashMap<String, EncryptionKey>>>,
     /// Symmetric encryption for key storage
     symmetric_encryption: Box<dyn SymmetricEncryption + Send + Sync>,
-    /// Key derivation for user passwords
-    key_derivation: Box<dyn KeyDerivation + Send + Sync>,
+    /// Key derivation function used by `initialize_with_password` and new key wrappings
+    password_kdf: KeyDerivationAlgorithm,
     /// Master key for encrypting stored keys
     master_key: Option<EncryptionKey>,
     /// Statistics tracking
     stats: Arc<RwLock<EncryptionStats>>,
-    /// Secure random generator
-    secure_random: SecureRandom,
+    /// Optional read-through key cache
+    key_cache: Option<KeyCache>,
+    /// Time source for usage timestamps and rotation due dates
+    clock: SharedClock,
+    /// How long after storage a key is due for rotation
+    rotation_interval: Duration,
 }
 
 impl KeyManager {
@@ -51,14 +203,15 @@ impl KeyManager {
         debug!("Initializing key manager");
 
         let symmetric_encryption = Box::new(AesGcmEncryption::new()?);
-        let key_derivation = Box::new(Argon2Kdf::new()?);
 
         Ok(Self {
             keys: Arc::new(RwLock::new(HashMap::new())),
             user_keys: Arc::new(RwLock::new(HashMap::new())),
             key_id_index: Arc::new(RwLock::new(HashMap::new())),
+            signing_keys: Arc::new(RwLock::new(HashMap::new())),
+            index_keys: Arc::new(RwLock::new(HashMap::new())),
             symmetric_encryption,
-            key_derivation,
+            password_kdf: KeyDerivationAlgorithm::default(),
             master_key: None,
             stats: Arc::new(RwLock::new(EncryptionStats {
                 total_keys: 0,
@@ -69,25 +222,81 @@ impl KeyManager {
                 key_derivation_operations: 0,
                 last_key_rotation: None,
             })),
-            secure_random: SecureRandom::new()?,
+            key_cache: None,
+            clock: system_clock(),
+            rotation_interval: Duration::days(i64::from(DEFAULT_KEY_ROTATION_DAYS)),
         })
     }
 
+    /// Make keys stored from now on due for rotation after `days`
+    ///
+    /// Keys already stored keep their existing due date.
+    pub fn with_key_rotation_days(mut self, days: u32) -> EncryptionResult<Self> {
+        validate_key_rotation_days(days)?;
+        self.rotation_interval = Duration::days(i64::from(days));
+        Ok(self)
+    }
+
+    /// Derive the master key in `initialize_with_password` with `algorithm`
+    ///
+    /// Argon2id is used unless configured otherwise; PBKDF2 is available for
+    /// environments that require FIPS-approved primitives.
+    pub fn with_password_kdf(
+        mut self,
+        algorithm: KeyDerivationAlgorithm,
+    ) -> EncryptionResult<Self> {
+        validate_password_kdf(algorithm)?;
+        self.password_kdf = algorithm;
+        Ok(self)
+    }
+
+    /// Due date for a key stored now
+    fn next_rotation_due(&self) -> DateTime<Utc> {
+        self.clock.now() + self.rotation_interval
+    }
+
+    /// Enable the read-through key cache with the given TTL
+    ///
+    /// Cached lookups are served under a read lock and their usage statistics
+    /// are applied in batches, so `usage_count` may lag by up to one flush interval.
+    pub fn with_key_cache(mut self, ttl: StdDuration) -> Self {
+        self.key_cache = Some(KeyCache::new(ttl, self.clock.clone()));
+        self
+    }
+
+    /// Read usage timestamps, rotation due dates and cache expiry from `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        if let Some(cache) = &mut self.key_cache {
+            cache.clock = clock.clone();
+        }
+        self.clock = clock;
+        self
+    }
+
     /// Initialize the key manager with a master key derived from password
-    #[instrument(skip(self, password))]
+    ///
+    /// Uses the configured password KDF with its recommended parameters and a fresh salt.
+    #[instrument(skip(self, password), fields(kdf = ?self.password_kdf))]
     pub async fn initialize_with_password(&mut self, password: &str) -> EncryptionResult<()> {
-        info!("Initializing key manager with password-derived master key");
-
-        // Generate salt for master key derivation
-        let salt = self.secure_random.generate_salt()?;
+        let params = key_derivation::kdf_for(self.password_kdf)?.generate_params(32)?;
+        self.initialize_with_params(password, &params).await
+    }
 
-        // Create key derivation parameters
-        let params = KeyDerivationParams::argon2id_default(salt);
+    /// Initialize the key manager with a master key derived with explicit `params`
+    ///
+    /// The KDF is chosen by `params.algorithm`, so the same password and
+    /// parameters always yield the same master key.
+    #[instrument(skip(self, password, params), fields(kdf = ?params.algorithm))]
+    pub async fn initialize_with_params(
+        &mut self,
+        password: &str,
+        params: &KeyDerivationParams,
+    ) -> EncryptionResult<()> {
+        info!("Initializing key manager with password-derived master key");
 
-        // Derive master key from password
-        let master_key = self
-            .key_derivation
-            .derive_key(password.as_bytes(), &params)
+        key_derivation::validate_params(params)?;
+        let master_key = key_derivation::kdf_for(params.algorithm)?
+            .derive_key(password.as_bytes(), params)
             .await?;
 
         self.master_key = Some(master_key);
@@ -100,6 +309,234 @@ impl KeyManager {
         Ok(())
     }
 
+    /// Configured key derivation function for passwords
+    pub fn password_kdf(&self) -> KeyDerivationAlgorithm {
+        self.password_kdf
+    }
+
+    /// Derive the master key that wraps a user's stored keys from their password
+    ///
+    /// `algorithm` is the KDF stored with `salt`; new wrappings use `password_kdf()`.
+    pub async fn derive_master_key(
+        &self,
+        password: &str,
+        salt: &[u8],
+        algorithm: KeyDerivationAlgorithm,
+    ) -> EncryptionResult<EncryptionKey> {
+        let kdf = key_derivation::kdf_for(algorithm)?;
+        let params = KeyDerivationParams {
+            salt: salt.to_vec(),
+            ..kdf.generate_params(32)?
+        };
+        let master_key = kdf.derive_key(password.as_bytes(), &params).await?;
+
+        let mut stats = self.stats.write().await;
+        stats.key_derivation_operations += 1;
+
+        Ok(master_key)
+    }
+
+    /// Wrap every key of a user, including rotated ones and the index key, under `master_key`
+    #[instrument(skip(self, master_key), fields(user_id = user_id))]
+    pub async fn wrap_user_keys(
+        &self,
+        user_id: &str,
+        master_key: &EncryptionKey,
+    ) -> EncryptionResult<Vec<WrappedKey>> {
+        let prefix = format!("{user_id}:");
+        let mut user_keys: Vec<(String, EncryptionKey)> = {
+            let keys = self.keys.read().await;
+            keys.iter()
+                .filter(|(key_identifier, _)| key_identifier.starts_with(&prefix))
+                .map(|(key_identifier, entry)| (key_identifier.clone(), entry.key.clone()))
+                .collect()
+        };
+        if let Some(index_key) = self.index_keys.read().await.get(user_id) {
+            user_keys.push((
+                format!("{user_id}:{INDEX_KEY_DATA_TYPE}"),
+                index_key.clone(),
+            ));
+        }
+
+        let mut wrapped = Vec::with_capacity(user_keys.len());
+        for (key_identifier, key) in user_keys {
+            let wrapped_key = self
+                .wrap_verified(&key.key_data, &key.key_id, master_key)
+                .await?;
+            wrapped.push(WrappedKey {
+                key_id: key.key_id.clone(),
+                key_identifier,
+                wrapped_key,
+                algorithm: key.algorithm,
+                is_active: key.is_active,
+            });
+        }
+
+        Ok(wrapped)
+    }
+
+    /// Load a user's wrapped keys back into key storage
+    ///
+    /// Called when the user unlocks with their password so data encrypted in
+    /// earlier sessions can be decrypted. Nothing is loaded unless every key
+    /// unwraps. Returns the number of keys restored.
+    #[instrument(skip(self, wrapped, master_key), fields(user_id = user_id, keys = wrapped.len()))]
+    pub async fn restore_wrapped_keys(
+        &self,
+        user_id: &str,
+        wrapped: &[WrappedKey],
+        master_key: &EncryptionKey,
+    ) -> EncryptionResult<usize> {
+        let prefix = format!("{user_id}:");
+        let mut restored = Vec::with_capacity(wrapped.len());
+
+        for entry in wrapped {
+            let data_type = entry
+                .key_identifier
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.split(':').next())
+                .filter(|data_type| !data_type.is_empty())
+                .ok_or_else(|| {
+                    FiscusError::Encryption(format!(
+                        "Wrapped key {} does not belong to this user",
+                        entry.key_id
+                    ))
+                })?;
+            let key_data = self.unwrap_key(entry, master_key).await.map_err(|_| {
+                FiscusError::Authentication(
+                    "Password does not unlock the stored encryption keys".to_string(),
+                )
+            })?;
+
+            let mut key = EncryptionKey::new(
+                key_data.into_vec(),
+                KeyType::Symmetric,
+                entry.algorithm,
+                entry.key_id.clone(),
+            );
+            key.is_active = entry.is_active;
+            restored.push((data_type.to_string(), entry.key_identifier.clone(), key));
+        }
+
+        let count = restored.len();
+        for (data_type, key_identifier, key) in restored {
+            if data_type == INDEX_KEY_DATA_TYPE {
+                self.index_keys
+                    .write()
+                    .await
+                    .insert(user_id.to_string(), key);
+                continue;
+            }
+
+            let is_active = key.is_active;
+            self.store_key(&key_identifier, key).await?;
+            if is_active {
+                self.user_keys
+                    .write()
+                    .await
+                    .entry(user_id.to_string())
+                    .or_insert_with(HashMap::new)
+                    .insert(data_type, key_identifier);
+            } else {
+                let mut stats = self.stats.write().await;
+                stats.active_keys = stats.active_keys.saturating_sub(1);
+            }
+        }
+
+        if let Some(cache) = &self.key_cache {
+            cache.invalidate_prefix(&prefix).await;
+        }
+
+        debug!(keys = count, "Restored wrapped keys");
+        Ok(count)
+    }
+
+    /// Re-wrap stored keys from `old_master` to `new_master`
+    ///
+    /// Every re-wrapped key is test-decrypted under the new master key before
+    /// anything is returned, so callers either get a complete, verified set or
+    /// an error and can keep the old wrapping untouched.
+    #[instrument(skip_all, fields(keys = wrapped.len()))]
+    pub async fn rewrap_keys(
+        &self,
+        wrapped: &[WrappedKey],
+        old_master: &EncryptionKey,
+        new_master: &EncryptionKey,
+    ) -> EncryptionResult<Vec<WrappedKey>> {
+        let mut rewrapped = Vec::with_capacity(wrapped.len());
+
+        for entry in wrapped {
+            let key_data = self.unwrap_key(entry, old_master).await.map_err(|_| {
+                FiscusError::Authentication(
+                    "Old password does not unlock the stored encryption keys".to_string(),
+                )
+            })?;
+            let wrapped_key = self
+                .wrap_verified(&key_data, &entry.key_id, new_master)
+                .await?;
+            rewrapped.push(WrappedKey {
+                key_id: entry.key_id.clone(),
+                key_identifier: entry.key_identifier.clone(),
+                wrapped_key,
+                algorithm: entry.algorithm,
+                is_active: entry.is_active,
+            });
+        }
+
+        debug!(
+            keys = rewrapped.len(),
+            "Re-wrapped keys under new master key"
+        );
+        Ok(rewrapped)
+    }
+
+    /// Recover the key material of a wrapped key
+    pub async fn unwrap_key(
+        &self,
+        wrapped: &WrappedKey,
+        master_key: &EncryptionKey,
+    ) -> EncryptionResult<SecureBytes> {
+        if wrapped.wrapped_key.metadata.aad.as_deref() != Some(wrapped.key_id.as_bytes()) {
+            return Err(FiscusError::Encryption(format!(
+                "Wrapped key {} is not bound to its key ID",
+                wrapped.key_id
+            )));
+        }
+
+        let key_data = self
+            .symmetric_encryption
+            .decrypt(&wrapped.wrapped_key, master_key)
+            .await?;
+        Ok(SecureBytes::new(key_data))
+    }
+
+    /// Wrap key material and confirm it unwraps to the same bytes
+    async fn wrap_verified(
+        &self,
+        key_data: &SecureBytes,
+        key_id: &str,
+        master_key: &EncryptionKey,
+    ) -> EncryptionResult<EncryptedData> {
+        let wrapped_key = self
+            .symmetric_encryption
+            .encrypt_with_aad(key_data.as_slice(), master_key, Some(key_id.as_bytes()))
+            .await?;
+
+        let unwrapped = SecureBytes::new(
+            self.symmetric_encryption
+                .decrypt(&wrapped_key, master_key)
+                .await?,
+        );
+        if unwrapped.as_slice() != key_data.as_slice() {
+            error!(key_id = key_id, "Wrapped key failed verification");
+            return Err(FiscusError::Encryption(format!(
+                "Wrapped key {key_id} did not verify under the new master key"
+            )));
+        }
+
+        Ok(wrapped_key)
+    }
+
     /// Get or create an encryption key for a user and data type
     #[instrument(skip(self), fields(user_id = user_id, data_type = data_type))]
     pub async fn get_or_create_key(
@@ -133,6 +570,41 @@ impl KeyManager {
         Ok(new_key)
     }
 
+    /// Get or create the Ed25519 signing key pair of a user
+    ///
+    /// Returns `(private_key, public_key)`.
+    #[instrument(skip(self), fields(user_id = user_id))]
+    pub async fn get_or_create_signing_keypair(
+        &self,
+        user_id: &str,
+    ) -> EncryptionResult<(EncryptionKey, EncryptionKey)> {
+        let mut signing_keys = self.signing_keys.write().await;
+        if let Some(keypair) = signing_keys.get(user_id) {
+            return Ok(keypair.clone());
+        }
+
+        let keypair = Ed25519Encryption::new()?.generate_keypair().await?;
+        signing_keys.insert(user_id.to_string(), keypair.clone());
+
+        debug!(key_id = %keypair.0.key_id, "New signing key pair created");
+        Ok(keypair)
+    }
+
+    /// Get or create the blind index key of a user
+    #[instrument(skip(self), fields(user_id = user_id))]
+    pub async fn get_or_create_index_key(&self, user_id: &str) -> EncryptionResult<EncryptionKey> {
+        let mut index_keys = self.index_keys.write().await;
+        if let Some(key) = index_keys.get(user_id) {
+            return Ok(key.clone());
+        }
+
+        let key = self.symmetric_encryption.generate_key().await?;
+        index_keys.insert(user_id.to_string(), key.clone());
+
+        debug!(key_id = %key.key_id, "New blind index key created");
+        Ok(key)
+    }
+
     /// Get an existing encryption key
     #[instrument(skip(self), fields(user_id = user_id, data_type = data_type))]
     pub async fn get_key(&self, user_id: &str, data_type: &str) -> EncryptionResult<EncryptionKey> {
@@ -160,6 +632,13 @@ impl KeyManager {
             FiscusError::NotFound(format!("Key entry not found for ID: {key_id}"))
         })?;
 
+        if entry.revoked_at.is_some() {
+            warn!(key_id = key_id, "Attempted to use a revoked key");
+            return Err(FiscusError::KeyManagement(format!(
+                "Key has been revoked: {key_id}"
+            )));
+        }
+
         debug!(key_id = key_id, "Found key by ID using secondary index");
         Ok(entry.key.clone())
     }
@@ -224,16 +703,29 @@ impl KeyManager {
         &self,
         key_identifier: &str,
     ) -> EncryptionResult<Option<EncryptionKey>> {
+        if let Some(cache) = &self.key_cache {
+            if let Some(key) = cache.get(key_identifier).await {
+                if cache.try_claim_flush() {
+                    self.flush_usage_stats().await;
+                }
+                return Ok(Some(key));
+            }
+        }
+
         let mut keys = self.keys.write().await;
 
         if let Some(entry) = keys.get_mut(key_identifier) {
             // Update usage statistics
             entry.usage_count += 1;
-            entry.last_used = Utc::now();
+            entry.last_used = self.clock.now();
+
+            if let Some(cache) = &self.key_cache {
+                entry.usage_count += cache.insert(key_identifier, entry.key.clone()).await;
+            }
 
             // Check if key rotation is due
             if let Some(rotation_due) = entry.rotation_due {
-                if Utc::now() > rotation_due {
+                if self.clock.now() > rotation_due {
                     warn!(key_id = %entry.key.key_id, "Key rotation is overdue");
                 }
             }
@@ -244,6 +736,59 @@ impl KeyManager {
         }
     }
 
+    /// Apply batched usage statistics from the key cache to key storage
+    pub async fn flush_usage_stats(&self) {
+        let Some(cache) = &self.key_cache else {
+            return;
+        };
+
+        let pending = cache.drain_pending_uses().await;
+        if pending.is_empty() {
+            return;
+        }
+
+        let mut keys = self.keys.write().await;
+        let now = self.clock.now();
+        for (key_identifier, uses) in pending {
+            if let Some(entry) = keys.get_mut(&key_identifier) {
+                entry.usage_count += uses;
+                entry.last_used = now;
+            }
+        }
+    }
+
+    /// Record additional uses of a key obtained once for a batch of operations
+    pub async fn record_key_uses(&self, user_id: &str, data_type: &str, uses: u64) {
+        if uses == 0 {
+            return;
+        }
+
+        let key_identifier = format!("{user_id}:{data_type}");
+        let mut keys = self.keys.write().await;
+        if let Some(entry) = keys.get_mut(&key_identifier) {
+            entry.usage_count += uses;
+            entry.last_used = self.clock.now();
+        }
+    }
+
+    /// Get the recorded usage count of a user's key for a data type
+    ///
+    /// With the key cache enabled this may lag behind until the next flush.
+    pub async fn get_key_usage_count(
+        &self,
+        user_id: &str,
+        data_type: &str,
+    ) -> EncryptionResult<u64> {
+        let key_identifier = format!("{user_id}:{data_type}");
+        let keys = self.keys.read().await;
+
+        keys.get(&key_identifier)
+            .map(|entry| entry.usage_count)
+            .ok_or_else(|| {
+                FiscusError::NotFound(format!("Key not found for {user_id}:{data_type}"))
+            })
+    }
+
     /// Store a key securely
     #[instrument(skip(self, key), fields(key_id = %key.key_id))]
     async fn store_key(&self, key_identifier: &str, key: EncryptionKey) -> EncryptionResult<()> {
@@ -251,8 +796,9 @@ impl KeyManager {
         let entry = KeyEntry {
             key,
             usage_count: 0,
-            last_used: Utc::now(),
-            rotation_due: Some(Utc::now() + Duration::days(90)), // 90-day rotation
+            last_used: self.clock.now(),
+            rotation_due: Some(self.next_rotation_due()),
+            revoked_at: None,
         };
 
         let mut keys = self.keys.write().await;
@@ -276,6 +822,12 @@ impl KeyManager {
     pub async fn rotate_user_keys(&self, user_id: &str) -> EncryptionResult<()> {
         info!(user_id = user_id, "Starting key rotation");
 
+        // Cached copies would still report the old keys as active
+        if let Some(cache) = &self.key_cache {
+            self.flush_usage_stats().await;
+            cache.invalidate_prefix(&format!("{user_id}:")).await;
+        }
+
         let user_keys = {
             let user_keys_guard = self.user_keys.read().await;
             user_keys_guard.get(user_id).cloned()
@@ -301,7 +853,7 @@ impl KeyManager {
                     let mut keys = self.keys.write().await;
                     if let Some(entry) = keys.get_mut(&old_key_identifier) {
                         entry.key.is_active = false;
-                        entry.rotation_due = Some(Utc::now() + Duration::days(90));
+                        entry.rotation_due = Some(self.next_rotation_due());
                         debug!(old_key_id = %entry.key.key_id, "Marked old key as inactive");
                     }
                 }
@@ -320,7 +872,7 @@ impl KeyManager {
         // Update statistics
         let mut stats = self.stats.write().await;
         stats.rotated_keys += 1;
-        stats.last_key_rotation = Some(Utc::now());
+        stats.last_key_rotation = Some(self.clock.now());
 
         info!(user_id = user_id, "Key rotation completed successfully");
         Ok(())
@@ -331,6 +883,11 @@ impl KeyManager {
     pub async fn cleanup_expired_keys(&self) -> EncryptionResult<usize> {
         debug!("Starting expired key cleanup");
 
+        if let Some(cache) = &self.key_cache {
+            self.flush_usage_stats().await;
+            cache.invalidate_prefix("").await;
+        }
+
         let mut keys = self.keys.write().await;
         let mut removed_count = 0;
 
@@ -383,9 +940,9 @@ impl KeyManager {
         stats.decryption_operations += 1;
     }
 
-    /// List all keys for a user (for administrative purposes)
+    /// List the data types a user has keys for
     #[instrument(skip(self), fields(user_id = user_id))]
-    pub async fn list_user_keys(&self, user_id: &str) -> EncryptionResult<Vec<String>> {
+    pub async fn list_user_data_types(&self, user_id: &str) -> EncryptionResult<Vec<String>> {
         let user_keys = self.user_keys.read().await;
 
         if let Some(user_key_map) = user_keys.get(user_id) {
@@ -395,6 +952,264 @@ impl KeyManager {
         }
     }
 
+    /// List metadata for all keys of a user, including rotated and revoked ones
+    /// (for administrative purposes)
+    #[instrument(skip(self), fields(user_id = user_id))]
+    pub async fn list_user_keys(&self, user_id: &str) -> EncryptionResult<Vec<KeyMetadata>> {
+        self.flush_usage_stats().await;
+
+        let prefix = format!("{user_id}:");
+        let keys = self.keys.read().await;
+
+        let mut metadata: Vec<KeyMetadata> = keys
+            .iter()
+            .filter_map(|(key_identifier, entry)| {
+                // Identifiers are "user:data_type" or "user:data_type:key_id"
+                let rest = key_identifier.strip_prefix(&prefix)?;
+                let data_type = rest.split(':').next().unwrap_or(rest);
+                Some(KeyMetadata {
+                    key_id: entry.key.key_id.clone(),
+                    data_type: data_type.to_string(),
+                    algorithm: entry.key.algorithm,
+                    is_active: entry.key.is_active,
+                    is_revoked: entry.revoked_at.is_some(),
+                    created_at: entry.key.created_at,
+                    usage_count: entry.usage_count,
+                })
+            })
+            .collect();
+
+        metadata.sort_by(|a, b| {
+            a.data_type
+                .cmp(&b.data_type)
+                .then(a.created_at.cmp(&b.created_at))
+        });
+
+        Ok(metadata)
+    }
+
+    /// History of a user's keys for one data type, oldest first
+    ///
+    /// Covers the active key and every retained rotated or revoked key, found
+    /// through the key ID index. Keys created at the same instant list the
+    /// active one last.
+    #[instrument(skip(self), fields(user_id = user_id, data_type = data_type))]
+    pub async fn key_lineage(
+        &self,
+        user_id: &str,
+        data_type: &str,
+    ) -> EncryptionResult<Vec<KeyMetadata>> {
+        self.flush_usage_stats().await;
+
+        // Identifiers are "user:data_type" or "user:data_type:key_id"
+        let base_identifier = format!("{user_id}:{data_type}");
+        let rotated_prefix = format!("{base_identifier}:");
+
+        let key_id_index = self.key_id_index.read().await;
+        let keys = self.keys.read().await;
+
+        let mut lineage: Vec<KeyMetadata> = key_id_index
+            .values()
+            .filter(|identifier| {
+                **identifier == base_identifier || identifier.starts_with(&rotated_prefix)
+            })
+            .filter_map(|identifier| keys.get(identifier))
+            .map(|entry| KeyMetadata {
+                key_id: entry.key.key_id.clone(),
+                data_type: data_type.to_string(),
+                algorithm: entry.key.algorithm,
+                is_active: entry.key.is_active,
+                is_revoked: entry.revoked_at.is_some(),
+                created_at: entry.key.created_at,
+                usage_count: entry.usage_count,
+            })
+            .collect();
+
+        lineage.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then(a.is_active.cmp(&b.is_active))
+        });
+
+        Ok(lineage)
+    }
+
+    /// Revoke a user's key so it can no longer be used for decryption
+    ///
+    /// Revoking the key currently used for a data type causes a fresh key to be
+    /// generated on the next encryption for that data type.
+    #[instrument(skip(self), fields(user_id = user_id, key_id = key_id))]
+    pub async fn revoke_key(&self, user_id: &str, key_id: &str) -> EncryptionResult<()> {
+        let prefix = format!("{user_id}:");
+
+        self.flush_usage_stats().await;
+
+        let mut keys = self.keys.write().await;
+        let mut key_id_index = self.key_id_index.write().await;
+
+        let key_identifier = key_id_index
+            .get(key_id)
+            .filter(|identifier| identifier.starts_with(&prefix))
+            .cloned()
+            .ok_or_else(|| FiscusError::NotFound(format!("Key not found with ID: {key_id}")))?;
+
+        let mut entry = keys.remove(&key_identifier).ok_or_else(|| {
+            FiscusError::NotFound(format!("Key entry not found for ID: {key_id}"))
+        })?;
+
+        if entry.revoked_at.is_some() {
+            keys.insert(key_identifier, entry);
+            return Err(FiscusError::Conflict(format!(
+                "Key has already been revoked: {key_id}"
+            )));
+        }
+
+        let was_active = entry.key.is_active;
+        entry.key.is_active = false;
+        entry.revoked_at = Some(self.clock.now());
+
+        // The base "user:data_type" identifier is what new encryptions look up, so a
+        // revoked key stored there is moved aside to let a fresh key take its place
+        let rest = &key_identifier[prefix.len()..];
+        let revoked_identifier = if rest.contains(':') {
+            key_identifier.clone()
+        } else {
+            format!("{key_identifier}:{key_id}")
+        };
+
+        keys.insert(revoked_identifier.clone(), entry);
+        key_id_index.insert(key_id.to_string(), revoked_identifier.clone());
+
+        // Drop cached copies while still holding the key storage lock so the
+        // revoked key cannot be re-cached by a concurrent lookup
+        if let Some(cache) = &self.key_cache {
+            cache.invalidate_prefix(&prefix).await;
+        }
+
+        {
+            let mut user_keys = self.user_keys.write().await;
+            if let Some(user_map) = user_keys.get_mut(user_id) {
+                for identifier in user_map.values_mut() {
+                    if *identifier == key_identifier {
+                        *identifier = revoked_identifier.clone();
+                    }
+                }
+            }
+        }
+
+        if was_active {
+            let mut stats = self.stats.write().await;
+            stats.active_keys = stats.active_keys.saturating_sub(1);
+        }
+
+        warn!(user_id = user_id, key_id = key_id, "Encryption key revoked");
+        Ok(())
+    }
+
+    /// Make `new_key` the active key for a user's data type
+    ///
+    /// The previous active key is moved to `user:data_type:key_id` and marked
+    /// inactive, so data encrypted with it can still be decrypted.
+    #[instrument(skip(self, new_key), fields(user_id = user_id, data_type = data_type, key_id = %new_key.key_id))]
+    pub async fn replace_active_key(
+        &self,
+        user_id: &str,
+        data_type: &str,
+        new_key: EncryptionKey,
+    ) -> EncryptionResult<()> {
+        let key_identifier = format!("{user_id}:{data_type}");
+
+        self.flush_usage_stats().await;
+
+        let mut keys = self.keys.write().await;
+        let mut key_id_index = self.key_id_index.write().await;
+        let mut deactivated = false;
+
+        if let Some(mut previous) = keys.remove(&key_identifier) {
+            let archived_identifier = format!("{key_identifier}:{}", previous.key.key_id);
+            deactivated = previous.key.is_active;
+            previous.key.is_active = false;
+            key_id_index.insert(previous.key.key_id.clone(), archived_identifier.clone());
+            keys.insert(archived_identifier, previous);
+        }
+
+        key_id_index.insert(new_key.key_id.clone(), key_identifier.clone());
+        keys.insert(
+            key_identifier.clone(),
+            KeyEntry {
+                key: new_key,
+                usage_count: 0,
+                last_used: self.clock.now(),
+                rotation_due: Some(self.next_rotation_due()),
+                revoked_at: None,
+            },
+        );
+
+        // Invalidate while holding the key storage lock so the old key
+        // cannot be re-cached by a concurrent lookup
+        if let Some(cache) = &self.key_cache {
+            cache.invalidate_prefix(&key_identifier).await;
+        }
+
+        {
+            let mut user_keys = self.user_keys.write().await;
+            user_keys
+                .entry(user_id.to_string())
+                .or_insert_with(HashMap::new)
+                .insert(data_type.to_string(), key_identifier);
+        }
+
+        let mut stats = self.stats.write().await;
+        stats.total_keys += 1;
+        if !deactivated {
+            stats.active_keys += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Replace `key_id` with `new_key` if it is still a user's active key
+    ///
+    /// Returns `false` when `key_id` has already been retired, so concurrent
+    /// rotation requests for the same key rotate it only once.
+    #[instrument(skip(self, new_key), fields(key_id = key_id))]
+    pub async fn rotate_active_key(
+        &self,
+        key_id: &str,
+        new_key: EncryptionKey,
+    ) -> EncryptionResult<bool> {
+        let owner = {
+            let key_id_index = self.key_id_index.read().await;
+            let user_keys = self.user_keys.read().await;
+            key_id_index.get(key_id).and_then(|key_identifier| {
+                user_keys.iter().find_map(|(user_id, data_types)| {
+                    data_types
+                        .iter()
+                        .find(|(_, active)| *active == key_identifier)
+                        .map(|(data_type, _)| (user_id.clone(), data_type.clone()))
+                })
+            })
+        };
+
+        let Some((user_id, data_type)) = owner else {
+            debug!(
+                key_id = key_id,
+                "Key is no longer active; skipping rotation"
+            );
+            return Ok(false);
+        };
+
+        self.replace_active_key(&user_id, &data_type, new_key)
+            .await?;
+
+        let mut stats = self.stats.write().await;
+        stats.rotated_keys += 1;
+        stats.last_key_rotation = Some(self.clock.now());
+
+        info!(user_id = %user_id, data_type = %data_type, old_key_id = key_id, "Active key rotated");
+        Ok(true)
+    }
+
     /// Check if a key needs rotation
     pub async fn needs_rotation(&self, user_id: &str, data_type: &str) -> EncryptionResult<bool> {
         let key_identifier = format!("{user_id}:{data_type}");
@@ -402,7 +1217,7 @@ impl KeyManager {
 
         if let Some(entry) = keys.get(&key_identifier) {
             if let Some(rotation_due) = entry.rotation_due {
-                Ok(Utc::now() > rotation_due)
+                Ok(self.clock.now() > rotation_due)
             } else {
                 Ok(false)
             }
@@ -509,7 +1324,7 @@ impl KeyRotationManager {
         debug!(user_id = %user_id, "Checking keys for rotation");
 
         // Get all data types for this user
-        let data_types = self.key_manager.list_user_keys(user_id).await?;
+        let data_types = self.key_manager.list_user_data_types(user_id).await?;
 
         if data_types.is_empty() {
             debug!(user_id = %user_id, "User has no keys to check");
@@ -586,6 +1401,8 @@ impl KeyRotationManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
+    use crate::encryption::utils::SecureRandom;
 
     #[tokio::test]
     async fn test_key_manager_creation() {
@@ -594,6 +1411,93 @@ mod tests {
         assert_eq!(stats.total_keys, 0);
     }
 
+    #[tokio::test]
+    async fn test_argon2id_is_the_default_password_kdf() {
+        let mut key_manager = KeyManager::new().unwrap();
+        assert_eq!(key_manager.password_kdf, KeyDerivationAlgorithm::Argon2id);
+
+        // deepcode ignore HardcodedPassword: <test>
+        key_manager
+            .initialize_with_password("correct horse battery staple")
+            .await
+            .unwrap();
+        assert!(key_manager.master_key.is_some());
+        assert!(KeyManager::new()
+            .unwrap()
+            .with_password_kdf(KeyDerivationAlgorithm::HkdfSha256)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wrapping_master_key_uses_the_given_kdf() {
+        // deepcode ignore HardcodedPassword: <test>
+        let password = "correct horse battery staple";
+        let salt = vec![7u8; 32];
+        let key_manager = KeyManager::new()
+            .unwrap()
+            .with_password_kdf(KeyDerivationAlgorithm::Pbkdf2Sha256)
+            .unwrap();
+
+        let master = key_manager
+            .derive_master_key(password, &salt, key_manager.password_kdf())
+            .await
+            .unwrap();
+        let expected = key_derivation::kdf_for(KeyDerivationAlgorithm::Pbkdf2Sha256)
+            .unwrap()
+            .derive_key(
+                password.as_bytes(),
+                &KeyDerivationParams::pbkdf2_default(salt.clone()),
+            )
+            .await
+            .unwrap();
+        let argon2 = key_manager
+            .derive_master_key(password, &salt, KeyDerivationAlgorithm::Argon2id)
+            .await
+            .unwrap();
+
+        assert_eq!(master.key_bytes(), expected.key_bytes());
+        assert_ne!(master.key_bytes(), argon2.key_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_pbkdf2_and_scrypt_master_keys_are_deterministic() {
+        // deepcode ignore HardcodedPassword: <test>
+        let password = "correct horse battery staple";
+        let salt = vec![5u8; 32];
+
+        let mut master_keys = Vec::new();
+        for params in [
+            KeyDerivationParams::pbkdf2_default(salt.clone()),
+            KeyDerivationParams::scrypt_default(salt.clone()),
+        ] {
+            let mut first = KeyManager::new()
+                .unwrap()
+                .with_password_kdf(params.algorithm)
+                .unwrap();
+            first.initialize_with_password(password).await.unwrap();
+
+            let mut second = KeyManager::new().unwrap();
+            second
+                .initialize_with_params(password, &params)
+                .await
+                .unwrap();
+            let mut third = KeyManager::new().unwrap();
+            third
+                .initialize_with_params(password, &params)
+                .await
+                .unwrap();
+
+            let key = second.master_key.unwrap();
+            assert_eq!(key.key_bytes().len(), 32);
+            assert_eq!(key.key_bytes(), third.master_key.unwrap().key_bytes());
+            // A fresh salt gives a different key
+            assert_ne!(key.key_bytes(), first.master_key.unwrap().key_bytes());
+            master_keys.push(key);
+        }
+
+        assert_ne!(master_keys[0].key_bytes(), master_keys[1].key_bytes());
+    }
+
     #[tokio::test]
     async fn test_key_creation_and_retrieval() {
         let key_manager = KeyManager::new().unwrap();
@@ -613,6 +1517,134 @@ mod tests {
         assert_eq!(key1.key_id, key2.key_id);
     }
 
+    #[tokio::test]
+    async fn test_key_lineage_after_two_rotations() {
+        let key_manager = KeyManager::new().unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+        let data_type = "lineage";
+
+        let original = key_manager
+            .get_or_create_key(user_id, data_type)
+            .await
+            .unwrap();
+        key_manager
+            .get_or_create_key(user_id, "lineage_other")
+            .await
+            .unwrap();
+        key_manager.rotate_user_keys(user_id).await.unwrap();
+        key_manager.rotate_user_keys(user_id).await.unwrap();
+
+        let lineage = key_manager.key_lineage(user_id, data_type).await.unwrap();
+
+        assert_eq!(lineage.len(), 3);
+        assert_eq!(lineage.iter().filter(|key| key.is_active).count(), 1);
+        assert!(lineage.last().unwrap().is_active);
+        assert!(lineage.iter().any(|key| key.key_id == original.key_id));
+        assert!(lineage.iter().all(|key| key.data_type == data_type));
+        assert!(key_manager
+            .key_lineage(user_id, "unknown")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rotation_due_follows_mock_clock() {
+        let clock = Arc::new(MockClock::new());
+        let key_manager = KeyManager::new().unwrap().with_clock(clock.clone());
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+        let data_type = "rotation_due";
+
+        key_manager
+            .get_or_create_key(user_id, data_type)
+            .await
+            .unwrap();
+        assert!(!key_manager
+            .needs_rotation(user_id, data_type)
+            .await
+            .unwrap());
+
+        clock.advance(StdDuration::from_secs(89 * 24 * 60 * 60));
+        assert!(!key_manager
+            .needs_rotation(user_id, data_type)
+            .await
+            .unwrap());
+
+        clock.advance(StdDuration::from_secs(2 * 24 * 60 * 60));
+        assert!(key_manager
+            .needs_rotation(user_id, data_type)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rotation_due_uses_configured_interval() {
+        let clock = Arc::new(MockClock::new());
+        let key_manager = KeyManager::new()
+            .unwrap()
+            .with_clock(clock.clone())
+            .with_key_rotation_days(30)
+            .unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+        let data_type = "rotation_interval";
+
+        key_manager
+            .get_or_create_key(user_id, data_type)
+            .await
+            .unwrap();
+
+        clock.advance(StdDuration::from_secs(29 * 24 * 60 * 60));
+        assert!(!key_manager
+            .needs_rotation(user_id, data_type)
+            .await
+            .unwrap());
+
+        clock.advance(StdDuration::from_secs(2 * 24 * 60 * 60));
+        assert!(key_manager
+            .needs_rotation(user_id, data_type)
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn test_out_of_range_rotation_interval_rejected() {
+        for days in [0, 731] {
+            assert!(matches!(
+                KeyManager::new().unwrap().with_key_rotation_days(days),
+                Err(FiscusError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_key_expires_on_mock_clock() {
+        let clock = Arc::new(MockClock::new());
+        let key_manager = KeyManager::new()
+            .unwrap()
+            .with_key_cache(StdDuration::from_secs(30))
+            .with_clock(clock.clone());
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+        let data_type = "cache_ttl";
+
+        key_manager
+            .get_or_create_key(user_id, data_type)
+            .await
+            .unwrap();
+        // The first lookup of a stored key populates the cache
+        key_manager.get_key(user_id, data_type).await.unwrap();
+        let cache = key_manager.key_cache.as_ref().unwrap();
+        let key_identifier = format!("{user_id}:{data_type}");
+
+        assert!(cache.get(&key_identifier).await.is_some());
+
+        clock.advance(StdDuration::from_secs(31));
+        assert!(cache.get(&key_identifier).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_key_rotation() {
         // deepcode ignore NoHardcodedCredentials: <test>
@@ -650,7 +1682,7 @@ mod tests {
             .await
             .unwrap();
 
-        let user_keys = key_manager.list_user_keys(user_id).await.unwrap();
+        let user_keys = key_manager.list_user_data_types(user_id).await.unwrap();
         assert_eq!(user_keys.len(), 2);
         assert!(user_keys.contains(&"data_type_1".to_string()));
         assert!(user_keys.contains(&"data_type_2".to_string()));
@@ -774,4 +1806,249 @@ mod tests {
             .unwrap();
         assert_eq!(rotated_count, 0);
     }
+
+    #[tokio::test]
+    async fn test_list_user_key_metadata() {
+        let key_manager = KeyManager::new().unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+
+        let key1 = key_manager
+            .get_or_create_key(user_id, "data_type_1")
+            .await
+            .unwrap();
+        let _key2 = key_manager
+            .get_or_create_key(user_id, "data_type_2")
+            .await
+            .unwrap();
+        let _other = key_manager
+            .get_or_create_key("other-user", "data_type_1")
+            .await
+            .unwrap();
+
+        // Second lookup counts as a use
+        key_manager
+            .get_or_create_key(user_id, "data_type_1")
+            .await
+            .unwrap();
+
+        let metadata = key_manager.list_user_keys(user_id).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+
+        let first = metadata
+            .iter()
+            .find(|m| m.key_id == key1.key_id)
+            .expect("key should be listed");
+        assert_eq!(first.data_type, "data_type_1");
+        assert_eq!(first.algorithm, key1.algorithm);
+        assert!(first.is_active);
+        assert!(!first.is_revoked);
+        assert_eq!(first.usage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_cannot_be_used() {
+        let key_manager = KeyManager::new().unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+
+        let key = key_manager
+            .get_or_create_key(user_id, "test_data")
+            .await
+            .unwrap();
+
+        key_manager.revoke_key(user_id, &key.key_id).await.unwrap();
+
+        let result = key_manager.get_key_by_id(&key.key_id).await;
+        assert!(matches!(result, Err(FiscusError::KeyManagement(_))));
+
+        let metadata = key_manager.list_user_keys(user_id).await.unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert!(metadata[0].is_revoked);
+        assert!(!metadata[0].is_active);
+
+        // Revoking twice is a conflict
+        let result = key_manager.revoke_key(user_id, &key.key_id).await;
+        assert!(matches!(result, Err(FiscusError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_key_of_other_user_is_rejected() {
+        let key_manager = KeyManager::new().unwrap();
+
+        let key = key_manager
+            .get_or_create_key("owner-user", "test_data")
+            .await
+            .unwrap();
+
+        let result = key_manager.revoke_key("other-user", &key.key_id).await;
+        assert!(matches!(result, Err(FiscusError::NotFound(_))));
+        assert!(key_manager.get_key_by_id(&key.key_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_revoking_active_key_regenerates_on_next_use() {
+        let key_manager = KeyManager::new()
+            .unwrap()
+            .with_key_cache(DEFAULT_KEY_CACHE_TTL);
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+
+        let original = key_manager
+            .get_or_create_key(user_id, "test_data")
+            .await
+            .unwrap();
+        // Populate the cache with the key about to be revoked
+        key_manager
+            .get_or_create_key(user_id, "test_data")
+            .await
+            .unwrap();
+
+        key_manager
+            .revoke_key(user_id, &original.key_id)
+            .await
+            .unwrap();
+
+        let replacement = key_manager
+            .get_or_create_key(user_id, "test_data")
+            .await
+            .unwrap();
+        assert_ne!(replacement.key_id, original.key_id);
+        assert!(replacement.is_active);
+
+        // The replacement is usable while the revoked key stays blocked
+        assert!(key_manager.get_key_by_id(&replacement.key_id).await.is_ok());
+        assert!(matches!(
+            key_manager.get_key_by_id(&original.key_id).await,
+            Err(FiscusError::KeyManagement(_))
+        ));
+
+        let metadata = key_manager.list_user_keys(user_id).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rewrapped_keys_still_decrypt_existing_data() {
+        let key_manager = KeyManager::new().unwrap();
+        let cipher = AesGcmEncryption::new().unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+
+        let key = key_manager
+            .get_or_create_key(user_id, "transaction_amount")
+            .await
+            .unwrap();
+        let encrypted = cipher.encrypt(b"1234.56", &key).await.unwrap();
+        // The rotated-out key must be carried over too
+        key_manager.rotate_user_keys(user_id).await.unwrap();
+
+        let mut rng = SecureRandom::new().unwrap();
+        let old_master = key_manager
+            .derive_master_key(
+                "old-password",
+                &rng.generate_salt().unwrap(),
+                KeyDerivationAlgorithm::Argon2id,
+            )
+            .await
+            .unwrap();
+        let new_master = key_manager
+            .derive_master_key(
+                "new-password",
+                &rng.generate_salt().unwrap(),
+                KeyDerivationAlgorithm::Argon2id,
+            )
+            .await
+            .unwrap();
+
+        let wrapped = key_manager
+            .wrap_user_keys(user_id, &old_master)
+            .await
+            .unwrap();
+        assert_eq!(wrapped.len(), 2);
+
+        let rewrapped = key_manager
+            .rewrap_keys(&wrapped, &old_master, &new_master)
+            .await
+            .unwrap();
+        let entry = rewrapped
+            .iter()
+            .find(|w| w.key_id == key.key_id)
+            .expect("original key should be re-wrapped");
+
+        assert!(key_manager.unwrap_key(entry, &old_master).await.is_err());
+
+        let mut recovered = key.clone();
+        recovered.key_data = key_manager.unwrap_key(entry, &new_master).await.unwrap();
+        let decrypted = cipher.decrypt(&encrypted, &recovered).await.unwrap();
+        assert_eq!(decrypted, b"1234.56");
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_fails_as_a_whole_with_wrong_old_master() {
+        let key_manager = KeyManager::new().unwrap();
+        let cipher = AesGcmEncryption::new().unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+
+        key_manager
+            .get_or_create_key(user_id, "transaction_amount")
+            .await
+            .unwrap();
+        key_manager
+            .get_or_create_key(user_id, "transaction_notes")
+            .await
+            .unwrap();
+
+        let old_master = cipher.generate_key().await.unwrap();
+        let new_master = cipher.generate_key().await.unwrap();
+        let wrong_master = cipher.generate_key().await.unwrap();
+
+        let wrapped = key_manager
+            .wrap_user_keys(user_id, &old_master)
+            .await
+            .unwrap();
+
+        let result = key_manager
+            .rewrap_keys(&wrapped, &wrong_master, &new_master)
+            .await;
+        assert!(matches!(result, Err(FiscusError::Authentication(_))));
+
+        // A blob moved onto another key's record is rejected
+        let mut swapped = wrapped.clone();
+        swapped[0].wrapped_key = wrapped[1].wrapped_key.clone();
+        let result = key_manager
+            .rewrap_keys(&swapped, &old_master, &new_master)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signing_keypair_is_stable_across_rotation() {
+        let key_manager = KeyManager::new().unwrap();
+        let user_id = "signing-user";
+        key_manager
+            .get_or_create_key(user_id, "transaction_amount")
+            .await
+            .unwrap();
+
+        let (private_key, public_key) = key_manager
+            .get_or_create_signing_keypair(user_id)
+            .await
+            .unwrap();
+        assert_eq!(private_key.algorithm, EncryptionAlgorithm::Ed25519);
+
+        key_manager.rotate_user_keys(user_id).await.unwrap();
+
+        let (_, public_after) = key_manager
+            .get_or_create_signing_keypair(user_id)
+            .await
+            .unwrap();
+        assert_eq!(public_after.key_bytes(), public_key.key_bytes());
+
+        let (_, other_public) = key_manager
+            .get_or_create_signing_keypair("other-signing-user")
+            .await
+            .unwrap();
+        assert_ne!(other_public.key_bytes(), public_key.key_bytes());
+    }
 }
@@ -11,6 +11,7 @@
 /// - Memory-safe operations with secure deletion
 /// - Comprehensive error handling and logging
 pub mod asymmetric;
+pub mod compression;
 pub mod config;
 pub mod key_derivation;
 pub mod key_management;
@@ -21,14 +22,62 @@ pub mod utils;
 
 // Re-export main types and functions for easier access
 pub use asymmetric::{AsymmetricEncryption, Ed25519Encryption, RsaEncryption};
-pub use config::{ConfigManager, EncryptionConfig};
-pub use key_management::KeyManager;
-pub use nonce_manager::{NonceManager, NonceStrategy};
-pub use symmetric::{AesGcmEncryption, SymmetricEncryption};
-pub use types::{EncryptedData, EncryptionAlgorithm, EncryptionResult};
+pub use config::{CompressionConfig, ConfigManager, EncryptionConfig};
+pub use key_management::{KeyManager, KeyMetadata, WrappedKey};
+pub use nonce_manager::{NonceConfig, NonceManager, NonceStrategy, NonceThresholdEvent};
+pub use symmetric::{AesGcmEncryption, ChaCha20Poly1305Encryption, SymmetricEncryption};
+pub use types::{
+    AlgorithmCapabilities, CompressionAlgorithm, EncryptedData, EncryptionAlgorithm,
+    EncryptionResult, KeyDerivationAlgorithm,
+};
 
 use crate::error::FiscusError;
-use tracing::{debug, info};
+use crate::logging::performance::{
+    get_performance_monitor, EncryptionOperation, PerformanceMonitor,
+};
+use key_derivation::derive_field_subkey;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use zeroize::Zeroizing;
+
+/// Canonical AAD binding financial data ciphertext to its owner and data type
+///
+/// Each component is length-prefixed so distinct `(user_id, data_type)` pairs
+/// can never produce the same encoding.
+fn financial_data_aad(user_id: &str, data_type: &str) -> Vec<u8> {
+    const AAD_DOMAIN: &[u8] = b"fiscus:financial-data:v1";
+
+    let mut aad = Vec::with_capacity(AAD_DOMAIN.len() + 8 + user_id.len() + data_type.len());
+    aad.extend_from_slice(AAD_DOMAIN);
+    for component in [user_id, data_type] {
+        aad.extend_from_slice(&(component.len() as u32).to_be_bytes());
+        aad.extend_from_slice(component.as_bytes());
+    }
+    aad
+}
+
+/// Key that actually encrypts data carrying `subkey_label`
+///
+/// Unlabelled data (written before per-field subkeys) uses the data type key directly.
+fn field_key(
+    key: types::EncryptionKey,
+    subkey_label: Option<&str>,
+) -> EncryptionResult<types::EncryptionKey> {
+    match subkey_label {
+        Some(label) => derive_field_subkey(&key, label),
+        None => Ok(key),
+    }
+}
+
+/// Known plaintext used by `EncryptionService::run_self_test`
+const SELF_TEST_VECTOR: &[u8] = b"fiscus encryption self-test vector";
+
+/// Outcome of one `EncryptionService::run_self_test` check
+pub type SelfTestOutcome = (&'static str, EncryptionResult<()>);
+
+/// Decrypted plaintext that is overwritten with zeros when dropped
+pub type DecryptedBytes = Zeroizing<Vec<u8>>;
 
 /// Main encryption service that coordinates all encryption operations
 ///
@@ -36,31 +85,109 @@ use tracing::{debug, info};
 /// while maintaining security best practices and proper error handling.
 pub struct EncryptionService {
     symmetric: Box<dyn SymmetricEncryption + Send + Sync>,
+    symmetric_chacha: Box<dyn SymmetricEncryption + Send + Sync>,
     asymmetric_rsa: Box<dyn AsymmetricEncryption + Send + Sync>,
     asymmetric_ed25519: Box<dyn AsymmetricEncryption + Send + Sync>,
     key_manager: KeyManager,
+    compression: CompressionConfig,
+    /// Keys whose nonce counters crossed the warning threshold
+    nonce_warnings: std::sync::Mutex<mpsc::UnboundedReceiver<NonceThresholdEvent>>,
+    /// Receives the timings of encrypt, decrypt and key derivation calls
+    performance: PerformanceMonitor,
 }
 
 impl EncryptionService {
     /// Create a new encryption service with default algorithms
     pub fn new() -> Result<Self, FiscusError> {
+        Self::with_nonce_config(NonceConfig::default())
+    }
+
+    /// Create a new encryption service using `nonce_config` for symmetric ciphers
+    ///
+    /// Keys crossing the nonce warning threshold are rotated on the next
+    /// encryption, before the hard rotation threshold is reached.
+    pub fn with_nonce_config(nonce_config: NonceConfig) -> Result<Self, FiscusError> {
+        Self::with_config(&EncryptionConfig {
+            nonce: nonce_config,
+            ..EncryptionConfig::default()
+        })
+    }
+
+    /// Create a new encryption service from `config`
+    ///
+    /// Uses the nonce settings for symmetric ciphers, the key rotation
+    /// interval for newly stored keys and the password key derivation function.
+    pub fn with_config(config: &EncryptionConfig) -> Result<Self, FiscusError> {
         info!("Initializing encryption service");
 
-        let symmetric = Box::new(AesGcmEncryption::new()?);
+        let nonce_config = config.nonce.clone();
+        let (warning_sender, warning_receiver) = mpsc::unbounded_channel();
+        let symmetric = Box::new(AesGcmEncryption::with_nonce_manager(
+            NonceManager::with_config(nonce_config.clone())?
+                .with_threshold_channel(warning_sender.clone()),
+        )?);
+        let symmetric_chacha = Box::new(ChaCha20Poly1305Encryption::with_nonce_manager(
+            NonceManager::with_config(nonce_config)?.with_threshold_channel(warning_sender),
+        )?);
         let asymmetric_rsa = Box::new(RsaEncryption::new()?);
         let asymmetric_ed25519 = Box::new(Ed25519Encryption::new()?);
-        let key_manager = KeyManager::new()?;
+        let key_manager = KeyManager::new()?
+            .with_key_cache(key_management::DEFAULT_KEY_CACHE_TTL)
+            .with_key_rotation_days(config.key_rotation_days)?
+            .with_password_kdf(config.key_derivation)?;
 
         debug!("Encryption service initialized successfully");
 
         Ok(Self {
             symmetric,
+            symmetric_chacha,
             asymmetric_rsa,
             asymmetric_ed25519,
             key_manager,
+            compression: config.compression.clone(),
+            nonce_warnings: std::sync::Mutex::new(warning_receiver),
+            performance: get_performance_monitor().clone(),
         })
     }
 
+    /// Rotate keys reported by the nonce managers, returning the retired key IDs
+    async fn rotate_keys_past_nonce_warning(&self) -> EncryptionResult<Vec<String>> {
+        let events: Vec<NonceThresholdEvent> = {
+            let mut receiver = self.nonce_warnings.lock().unwrap();
+            std::iter::from_fn(|| receiver.try_recv().ok()).collect()
+        };
+
+        let mut rotated = Vec::new();
+        for event in events {
+            let current = match self.key_manager.get_key_by_id(&event.key_id).await {
+                Ok(key) => key,
+                Err(e) => {
+                    warn!(key_id = %event.key_id, error = %e, "Skipping rotation for unknown key");
+                    continue;
+                }
+            };
+
+            let new_key = self
+                .symmetric_for(current.algorithm)?
+                .generate_key()
+                .await?;
+            if self
+                .key_manager
+                .rotate_active_key(&event.key_id, new_key)
+                .await?
+            {
+                info!(
+                    key_id = %event.key_id,
+                    encryption_count = event.encryption_count,
+                    "Rotated key after nonce warning threshold"
+                );
+                rotated.push(event.key_id);
+            }
+        }
+
+        Ok(rotated)
+    }
+
     /// Encrypt sensitive financial data using symmetric encryption
     ///
     /// This method is optimized for encrypting financial data like transaction amounts,
@@ -70,6 +197,48 @@ impl EncryptionService {
         data: &[u8],
         user_id: &str,
         data_type: &str,
+    ) -> EncryptionResult<EncryptedData> {
+        self.encrypt_with_subkey(data, user_id, data_type, None)
+            .await
+    }
+
+    /// Encrypt a single column value under a subkey derived for `field_label`
+    ///
+    /// Fields sharing a data type key still get distinct effective keys; the
+    /// label is recorded in the metadata so decryption can derive the same subkey.
+    pub async fn encrypt_field_data(
+        &self,
+        data: &[u8],
+        user_id: &str,
+        data_type: &str,
+        field_label: &str,
+    ) -> EncryptionResult<EncryptedData> {
+        self.encrypt_with_subkey(data, user_id, data_type, Some(field_label))
+            .await
+    }
+
+    /// Deterministic blind index of `value` for equality search on `field_label`
+    ///
+    /// The index is keyed by a subkey of the user's index key, which data key
+    /// rotation leaves alone, so stored indexes keep matching new lookups.
+    pub async fn blind_index(
+        &self,
+        value: &str,
+        user_id: &str,
+        field_label: &str,
+    ) -> EncryptionResult<String> {
+        let key = self.key_manager.get_or_create_index_key(user_id).await?;
+        let index_key = derive_field_subkey(&key, &format!("{field_label}#blind-index"))?;
+
+        symmetric::blind_index(&index_key, value)
+    }
+
+    async fn encrypt_with_subkey(
+        &self,
+        data: &[u8],
+        user_id: &str,
+        data_type: &str,
+        field_label: Option<&str>,
     ) -> EncryptionResult<EncryptedData> {
         debug!(
             user_id = user_id,
@@ -78,14 +247,26 @@ impl EncryptionService {
             "Encrypting financial data"
         );
 
-        // Get or derive encryption key for this user and data type
-        let key = self
-            .key_manager
-            .get_or_create_key(user_id, data_type)
-            .await?;
+        let started = Instant::now();
+        self.rotate_keys_past_nonce_warning().await?;
 
-        // Encrypt using AES-256-GCM
-        let encrypted = self.symmetric.encrypt(data, &key).await?;
+        // Get or derive encryption key for this user and data type
+        let key = field_key(
+            self.key_manager
+                .get_or_create_key(user_id, data_type)
+                .await?,
+            field_label,
+        )?;
+
+        // Encrypt using AES-256-GCM, bound to the owning user and data type
+        let aad = financial_data_aad(user_id, data_type);
+        let mut encrypted = self.encrypt_plaintext(data, &key, &aad, data_type).await?;
+        encrypted.metadata.subkey_label = field_label.map(str::to_string);
+        self.performance.record_encryption_operations(
+            EncryptionOperation::Encrypt,
+            1,
+            started.elapsed(),
+        );
 
         debug!(
             user_id = user_id,
@@ -97,6 +278,79 @@ impl EncryptionService {
         Ok(encrypted)
     }
 
+    /// Encrypt many values of the same data type under a single key lookup
+    ///
+    /// Each value still gets its own nonce; only the key resolution is shared.
+    /// With a `field_label` every value is encrypted under that field's subkey.
+    pub async fn encrypt_financial_data_batch(
+        &self,
+        values: &[&[u8]],
+        user_id: &str,
+        data_type: &str,
+        field_label: Option<&str>,
+    ) -> EncryptionResult<Vec<EncryptedData>> {
+        if values.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        debug!(
+            user_id = user_id,
+            data_type = data_type,
+            batch_size = values.len(),
+            "Encrypting financial data batch"
+        );
+
+        let started = Instant::now();
+        self.rotate_keys_past_nonce_warning().await?;
+
+        let mut key = field_key(
+            self.key_manager
+                .get_or_create_key(user_id, data_type)
+                .await?,
+            field_label,
+        )?;
+        let mut key_lookups = 1u64;
+        let aad = financial_data_aad(user_id, data_type);
+
+        let mut encrypted = Vec::with_capacity(values.len());
+        for value in values {
+            // A large batch can itself push the key past the warning threshold
+            if self
+                .rotate_keys_past_nonce_warning()
+                .await?
+                .contains(&key.key_id)
+            {
+                key = field_key(
+                    self.key_manager
+                        .get_or_create_key(user_id, data_type)
+                        .await?,
+                    field_label,
+                )?;
+                key_lookups += 1;
+            }
+
+            let mut value = self.encrypt_plaintext(value, &key, &aad, data_type).await?;
+            value.metadata.subkey_label = field_label.map(str::to_string);
+            encrypted.push(value);
+        }
+
+        // The key lookups above already counted their uses
+        self.key_manager
+            .record_key_uses(
+                user_id,
+                data_type,
+                (values.len() as u64).saturating_sub(key_lookups),
+            )
+            .await;
+        self.performance.record_encryption_operations(
+            EncryptionOperation::Encrypt,
+            values.len() as u64,
+            started.elapsed(),
+        );
+
+        Ok(encrypted)
+    }
+
     /// Decrypt sensitive financial data
     pub async fn decrypt_financial_data(
         &self,
@@ -112,6 +366,8 @@ impl EncryptionService {
             "Decrypting financial data"
         );
 
+        let started = Instant::now();
+
         // Validate that the user has access to this key
         // This ensures security even when using key_id directly and prevents
         // users from accessing data encrypted with keys they don't own
@@ -122,14 +378,24 @@ impl EncryptionService {
         // Get the encryption key using the key_id from the encrypted data's metadata
         // This ensures we use the correct key even after key rotation, as old keys
         // are kept available for decrypting existing data while new keys are used
-        // for new encryptions
-        let key = self
-            .key_manager
-            .get_key_by_id(&encrypted_data.metadata.key_id)
-            .await?;
-
-        // Decrypt using AES-256-GCM
-        let decrypted = self.symmetric.decrypt(encrypted_data, &key).await?;
+        // for new encryptions. Per-field data is encrypted under a subkey of it.
+        let key = field_key(
+            self.key_manager
+                .get_key_by_id(&encrypted_data.metadata.key_id)
+                .await?,
+            encrypted_data.metadata.subkey_label.as_deref(),
+        )?;
+
+        let decrypted = compression::restore_plaintext(
+            encrypted_data,
+            self.decrypt_bound(encrypted_data, &key, user_id, data_type)
+                .await?,
+        )?;
+        self.performance.record_encryption_operations(
+            EncryptionOperation::Decrypt,
+            1,
+            started.elapsed(),
+        );
 
         debug!(
             user_id = user_id,
@@ -142,7 +408,172 @@ impl EncryptionService {
         Ok(decrypted)
     }
 
+    /// Like `decrypt_financial_data`, wiping the plaintext when it is dropped
+    ///
+    /// Prefer this whenever the plaintext does not have to outlive the caller.
+    pub async fn decrypt_financial_data_zeroizing(
+        &self,
+        encrypted_data: &EncryptedData,
+        user_id: &str,
+        data_type: &str,
+    ) -> EncryptionResult<DecryptedBytes> {
+        self.decrypt_financial_data(encrypted_data, user_id, data_type)
+            .await
+            .map(Zeroizing::new)
+    }
+
+    /// Encrypt `data` under `key`, bound to `aad`
+    ///
+    /// Data types designated in the compression config are compressed first,
+    /// unless that would not make them smaller; the metadata records both the
+    /// algorithm tried and whether it was applied.
+    async fn encrypt_plaintext(
+        &self,
+        data: &[u8],
+        key: &types::EncryptionKey,
+        aad: &[u8],
+        data_type: &str,
+    ) -> EncryptionResult<EncryptedData> {
+        let algorithm = self.compression.algorithm_for(data_type);
+        let compressed = match algorithm {
+            Some(algorithm) => {
+                compression::compress_if_smaller(data, algorithm, self.compression.level)?
+            }
+            None => None,
+        };
+
+        let mut encrypted = self
+            .symmetric_for(key.algorithm)?
+            .encrypt_with_aad(compressed.as_deref().unwrap_or(data), key, Some(aad))
+            .await?;
+        encrypted.metadata.compression = algorithm;
+        encrypted.metadata.compressed = compressed.is_some();
+
+        Ok(encrypted)
+    }
+
+    /// Decrypt data, verifying it was encrypted for this user and data type
+    ///
+    /// The expected AAD is reconstructed from the caller's context rather than
+    /// trusted from the metadata, so ciphertext moved to another user or data
+    /// type fails authentication. When the metadata carries no AAD (data written
+    /// before AAD binding, or metadata rebuilt by the frontend) an unbound
+    /// decryption is attempted as a fallback; a bound ciphertext can never pass it.
+    async fn decrypt_bound(
+        &self,
+        encrypted_data: &EncryptedData,
+        key: &types::EncryptionKey,
+        user_id: &str,
+        data_type: &str,
+    ) -> EncryptionResult<Vec<u8>> {
+        let cipher = self.symmetric_for(encrypted_data.metadata.algorithm)?;
+        let mut bound = encrypted_data.clone();
+        bound.metadata.aad = Some(financial_data_aad(user_id, data_type));
+
+        match cipher.decrypt(&bound, key).await {
+            Err(FiscusError::Authentication(_)) if encrypted_data.metadata.aad.is_none() => {
+                debug!(
+                    key_id = %encrypted_data.metadata.key_id,
+                    "Decrypting legacy data without context binding"
+                );
+                cipher.decrypt(encrypted_data, key).await
+            }
+            result => result,
+        }
+    }
+
+    /// Symmetric cipher implementing `algorithm`
+    fn symmetric_for(
+        &self,
+        algorithm: EncryptionAlgorithm,
+    ) -> EncryptionResult<&(dyn SymmetricEncryption + Send + Sync)> {
+        match algorithm {
+            EncryptionAlgorithm::Aes256Gcm => Ok(self.symmetric.as_ref()),
+            EncryptionAlgorithm::ChaCha20Poly1305 => Ok(self.symmetric_chacha.as_ref()),
+            other => Err(FiscusError::InvalidInput(format!(
+                "{other} is not a symmetric encryption algorithm"
+            ))),
+        }
+    }
+
+    /// Switch a user's data type to a fresh key for `target_algorithm`
+    ///
+    /// New encryptions use the new key immediately; the previous key is kept
+    /// (inactive) so existing ciphertext can still be decrypted and migrated.
+    /// If the active key already uses `target_algorithm` it is reused, which
+    /// makes repeated or resumed migrations safe. Returns the active key ID.
+    pub async fn migrate_data_type_key(
+        &self,
+        user_id: &str,
+        data_type: &str,
+        target_algorithm: EncryptionAlgorithm,
+    ) -> EncryptionResult<String> {
+        let cipher = self.symmetric_for(target_algorithm)?;
+
+        if let Ok(current) = self.key_manager.get_key(user_id, data_type).await {
+            if current.algorithm == target_algorithm {
+                debug!(
+                    user_id = user_id,
+                    data_type = data_type,
+                    key_id = %current.key_id,
+                    "Data type already uses target algorithm"
+                );
+                return Ok(current.key_id.clone());
+            }
+        }
+
+        let new_key = cipher.generate_key().await?;
+        let key_id = new_key.key_id.clone();
+        self.key_manager
+            .replace_active_key(user_id, data_type, new_key)
+            .await?;
+
+        info!(
+            user_id = user_id,
+            data_type = data_type,
+            algorithm = %target_algorithm,
+            key_id = %key_id,
+            "Installed new key for algorithm migration"
+        );
+        Ok(key_id)
+    }
+
+    /// Re-encrypt data under the active key for its data type
+    ///
+    /// Returns `None` when the data is already encrypted with the active key.
+    /// A per-field subkey label is carried over to the new ciphertext.
+    pub async fn reencrypt_financial_data(
+        &self,
+        encrypted_data: &EncryptedData,
+        user_id: &str,
+        data_type: &str,
+    ) -> EncryptionResult<Option<EncryptedData>> {
+        self.rotate_keys_past_nonce_warning().await?;
+
+        let active_key = self.key_manager.get_key(user_id, data_type).await?;
+        if encrypted_data.metadata.key_id == active_key.key_id {
+            return Ok(None);
+        }
+
+        let plaintext = self
+            .decrypt_financial_data(encrypted_data, user_id, data_type)
+            .await?;
+        let subkey_label = encrypted_data.metadata.subkey_label.as_deref();
+        let active_key = field_key(active_key, subkey_label)?;
+        let aad = financial_data_aad(user_id, data_type);
+        let mut reencrypted = self
+            .encrypt_plaintext(&plaintext, &active_key, &aad, data_type)
+            .await?;
+        reencrypted.metadata.subkey_label = subkey_label.map(str::to_string);
+
+        Ok(Some(reencrypted))
+    }
+
     /// Encrypt data for transmission (using asymmetric encryption)
+    ///
+    /// RSA-4096 payloads use hybrid encryption so they are not limited by the
+    /// modulus size: the data is encrypted with a fresh AES-256-GCM content
+    /// key, which is itself encrypted with RSA-OAEP for the recipient.
     pub async fn encrypt_for_transmission(
         &self,
         data: &[u8],
@@ -157,9 +588,7 @@ impl EncryptionService {
 
         let encrypted = match algorithm {
             EncryptionAlgorithm::Rsa4096 => {
-                self.asymmetric_rsa
-                    .encrypt_with_public_key(data, recipient_public_key)
-                    .await?
+                RsaEncryption::encrypt_hybrid(data, recipient_public_key)?
             }
             EncryptionAlgorithm::Ed25519 => {
                 self.asymmetric_ed25519
@@ -182,6 +611,43 @@ impl EncryptionService {
         Ok(encrypted)
     }
 
+    /// Decrypt data received from `encrypt_for_transmission` with the recipient's private key
+    ///
+    /// RSA payloads without a wrapped content key are treated as plain RSA
+    /// ciphertexts, as produced before hybrid encryption was introduced.
+    pub async fn decrypt_for_transmission(
+        &self,
+        encrypted_data: &EncryptedData,
+        recipient_private_key: &types::EncryptionKey,
+    ) -> EncryptionResult<Vec<u8>> {
+        debug!(
+            ciphertext_size = encrypted_data.ciphertext.len(),
+            algorithm = ?encrypted_data.metadata.algorithm,
+            "Decrypting data received in transmission"
+        );
+
+        match encrypted_data.metadata.algorithm {
+            EncryptionAlgorithm::Rsa4096
+                if encrypted_data.metadata.wrapped_content_key.is_some() =>
+            {
+                RsaEncryption::decrypt_hybrid(encrypted_data, recipient_private_key)
+            }
+            EncryptionAlgorithm::Rsa4096 => {
+                self.asymmetric_rsa
+                    .decrypt_with_private_key(encrypted_data, recipient_private_key)
+                    .await
+            }
+            EncryptionAlgorithm::Ed25519 => {
+                self.asymmetric_ed25519
+                    .decrypt_with_private_key(encrypted_data, recipient_private_key)
+                    .await
+            }
+            _ => Err(FiscusError::InvalidInput(
+                "Invalid algorithm for transmission decryption".to_string(),
+            )),
+        }
+    }
+
     /// Rotate encryption keys for a user
     pub async fn rotate_user_keys(&self, user_id: &str) -> EncryptionResult<()> {
         info!(user_id = user_id, "Starting key rotation");
@@ -192,12 +658,252 @@ impl EncryptionService {
         Ok(())
     }
 
+    /// List metadata for all of a user's keys
+    pub async fn list_user_keys(&self, user_id: &str) -> EncryptionResult<Vec<KeyMetadata>> {
+        self.key_manager.list_user_keys(user_id).await
+    }
+
+    /// History of a user's keys for one data type, oldest first
+    pub async fn key_lineage(
+        &self,
+        user_id: &str,
+        data_type: &str,
+    ) -> EncryptionResult<Vec<KeyMetadata>> {
+        self.key_manager.key_lineage(user_id, data_type).await
+    }
+
+    /// Revoke a user's key, blocking any further decryption with it
+    pub async fn revoke_key(&self, user_id: &str, key_id: &str) -> EncryptionResult<()> {
+        self.key_manager.revoke_key(user_id, key_id).await
+    }
+
+    /// Configured key derivation function for passwords
+    pub fn password_kdf(&self) -> KeyDerivationAlgorithm {
+        self.key_manager.password_kdf()
+    }
+
+    /// Derive the master key wrapping a user's keys from their password, salt and KDF
+    pub async fn derive_master_key(
+        &self,
+        password: &str,
+        salt: &[u8],
+        algorithm: KeyDerivationAlgorithm,
+    ) -> EncryptionResult<types::EncryptionKey> {
+        let started = Instant::now();
+        let key = self
+            .key_manager
+            .derive_master_key(password, salt, algorithm)
+            .await?;
+        self.performance.record_encryption_operations(
+            EncryptionOperation::KeyDerivation,
+            1,
+            started.elapsed(),
+        );
+
+        Ok(key)
+    }
+
+    /// Wrap all of a user's keys under a master key
+    pub async fn wrap_user_keys(
+        &self,
+        user_id: &str,
+        master_key: &types::EncryptionKey,
+    ) -> EncryptionResult<Vec<WrappedKey>> {
+        self.key_manager.wrap_user_keys(user_id, master_key).await
+    }
+
+    /// Load a user's wrapped keys back into key storage
+    pub async fn restore_wrapped_keys(
+        &self,
+        user_id: &str,
+        wrapped: &[WrappedKey],
+        master_key: &types::EncryptionKey,
+    ) -> EncryptionResult<usize> {
+        self.key_manager
+            .restore_wrapped_keys(user_id, wrapped, master_key)
+            .await
+    }
+
+    /// Re-wrap stored keys under a new master key, verifying each one
+    pub async fn rewrap_keys(
+        &self,
+        wrapped: &[WrappedKey],
+        old_master: &types::EncryptionKey,
+        new_master: &types::EncryptionKey,
+    ) -> EncryptionResult<Vec<WrappedKey>> {
+        self.key_manager
+            .rewrap_keys(wrapped, old_master, new_master)
+            .await
+    }
+
+    /// Sign `data` with the user's Ed25519 signing key
+    ///
+    /// Returns the detached signature and the public key that verifies it.
+    pub async fn sign_for_user(
+        &self,
+        data: &[u8],
+        user_id: &str,
+    ) -> EncryptionResult<(Vec<u8>, Vec<u8>)> {
+        let (private_key, public_key) = self
+            .key_manager
+            .get_or_create_signing_keypair(user_id)
+            .await?;
+        let signature = self
+            .asymmetric_ed25519
+            .sign_data(data, &private_key)
+            .await?;
+
+        Ok((signature, public_key.key_bytes().to_vec()))
+    }
+
+    /// Check an Ed25519 signature produced by `sign_for_user`
+    pub async fn verify_signature(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> EncryptionResult<bool> {
+        self.asymmetric_ed25519
+            .verify_signature(data, signature, public_key)
+            .await
+    }
+
+    /// Exercise every supported primitive with throwaway keys
+    ///
+    /// Round-trips a known vector through each symmetric cipher, checks that a
+    /// tampered ciphertext fails authentication, and signs and verifies with
+    /// Ed25519. Keys are generated for the test and never stored, so no user
+    /// keys or data are touched. Checks are returned in a fixed order.
+    pub async fn run_self_test(&self) -> Vec<SelfTestOutcome> {
+        let mut outcomes = Vec::new();
+        for (cipher, roundtrip, tamper) in [
+            (
+                self.symmetric.as_ref(),
+                "aes256_gcm_roundtrip",
+                "aes256_gcm_tamper_detected",
+            ),
+            (
+                self.symmetric_chacha.as_ref(),
+                "chacha20_poly1305_roundtrip",
+                "chacha20_poly1305_tamper_detected",
+            ),
+        ] {
+            let (roundtrip_result, tamper_result) = self_test_symmetric(cipher).await;
+            outcomes.push((roundtrip, roundtrip_result));
+            outcomes.push((tamper, tamper_result));
+        }
+        outcomes.push((
+            "ed25519_sign_verify",
+            self_test_signature(self.asymmetric_ed25519.as_ref()).await,
+        ));
+
+        outcomes
+    }
+
+    /// Capabilities of the symmetric and asymmetric implementations this service uses
+    ///
+    /// Reported by the implementations themselves, so the list always matches
+    /// what the service can actually do.
+    pub fn supported_algorithms(&self) -> (Vec<AlgorithmCapabilities>, Vec<AlgorithmCapabilities>) {
+        let symmetric = [self.symmetric.as_ref(), self.symmetric_chacha.as_ref()]
+            .into_iter()
+            .map(|cipher| cipher.capabilities())
+            .collect();
+        let asymmetric = [
+            self.asymmetric_rsa.as_ref(),
+            self.asymmetric_ed25519.as_ref(),
+        ]
+        .into_iter()
+        .map(|cipher| cipher.capabilities())
+        .collect();
+
+        (symmetric, asymmetric)
+    }
+
     /// Get encryption statistics for monitoring
     pub async fn get_encryption_stats(&self) -> EncryptionResult<EncryptionStats> {
         self.key_manager.get_stats().await
     }
 }
 
+/// Round-trip and tamper checks for one symmetric cipher
+async fn self_test_symmetric(
+    cipher: &(dyn SymmetricEncryption + Send + Sync),
+) -> (EncryptionResult<()>, EncryptionResult<()>) {
+    let setup = async {
+        let key = cipher.generate_key().await?;
+        let encrypted = cipher
+            .encrypt_with_aad(SELF_TEST_VECTOR, &key, Some(b"fiscus:self-test".as_slice()))
+            .await?;
+        Ok::<_, FiscusError>((key, encrypted))
+    };
+    let (key, encrypted) = match setup.await {
+        Ok(setup) => setup,
+        Err(e) => {
+            // Neither check can run without a ciphertext
+            let message = format!("Self-test setup failed: {e}");
+            return (
+                Err(FiscusError::Encryption(message.clone())),
+                Err(FiscusError::Encryption(message)),
+            );
+        }
+    };
+
+    let roundtrip = match cipher.decrypt(&encrypted, &key).await {
+        Ok(plaintext) if plaintext == SELF_TEST_VECTOR => Ok(()),
+        Ok(_) => Err(FiscusError::Encryption(
+            "Decrypted data does not match the test vector".to_string(),
+        )),
+        Err(e) => Err(e),
+    };
+
+    let mut tampered = encrypted.clone();
+    if let Some(byte) = tampered.ciphertext.first_mut() {
+        *byte ^= 0x01;
+    }
+    let tamper = match cipher.decrypt(&tampered, &key).await {
+        Err(FiscusError::Authentication(_)) => Ok(()),
+        Ok(_) => Err(FiscusError::Encryption(
+            "Tampered ciphertext decrypted without an authentication error".to_string(),
+        )),
+        Err(e) => Err(FiscusError::Encryption(format!(
+            "Tampered ciphertext failed with an unexpected error: {e}"
+        ))),
+    };
+
+    (roundtrip, tamper)
+}
+
+/// Sign the test vector and check the signature, including against an altered message
+async fn self_test_signature(
+    signer: &(dyn AsymmetricEncryption + Send + Sync),
+) -> EncryptionResult<()> {
+    let (private_key, public_key) = signer.generate_keypair().await?;
+    let signature = signer.sign_data(SELF_TEST_VECTOR, &private_key).await?;
+
+    if !signer
+        .verify_signature(SELF_TEST_VECTOR, &signature, public_key.key_bytes())
+        .await?
+    {
+        return Err(FiscusError::Cryptographic(
+            "Valid signature was rejected".to_string(),
+        ));
+    }
+
+    let mut altered = SELF_TEST_VECTOR.to_vec();
+    altered[0] ^= 0x01;
+    if signer
+        .verify_signature(&altered, &signature, public_key.key_bytes())
+        .await?
+    {
+        return Err(FiscusError::Cryptographic(
+            "Signature verified for an altered message".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Statistics about encryption operations for monitoring and auditing
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EncryptionStats {
@@ -326,6 +1032,60 @@ mod tests {
         assert_eq!(test_data, decrypted.as_slice());
     }
 
+    #[tokio::test]
+    async fn test_nonce_warning_rotates_key_before_hard_limit() {
+        let service = EncryptionService::with_nonce_config(NonceConfig {
+            default_strategy: NonceStrategy::CounterBased,
+            rotation_threshold: 5,
+            warning_threshold: 3,
+            persist_counters: false,
+            auto_rotate_on_warning: true,
+        })
+        .unwrap();
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user-nonce-warning";
+        let data_type = "test_data";
+
+        let initial_rotated_keys = service.get_encryption_stats().await.unwrap().rotated_keys;
+
+        // Far more encryptions than a single key's hard limit allows
+        let mut encrypted = Vec::new();
+        for i in 0..20 {
+            let value = format!("value {i}");
+            let result = service
+                .encrypt_financial_data(value.as_bytes(), user_id, data_type)
+                .await;
+            assert!(result.is_ok(), "encryption {i} failed: {result:?}");
+            encrypted.push((value, result.unwrap()));
+        }
+
+        let stats = service.get_encryption_stats().await.unwrap();
+        assert!(stats.rotated_keys > initial_rotated_keys);
+        assert!(stats.last_key_rotation.is_some());
+
+        let key_ids: HashSet<&str> = encrypted
+            .iter()
+            .map(|(_, data)| data.metadata.key_id.as_str())
+            .collect();
+        assert!(key_ids.len() > 1, "key was never rotated");
+
+        // Data written under retired keys stays readable
+        for (value, data) in &encrypted {
+            let decrypted = service
+                .decrypt_financial_data(data, user_id, data_type)
+                .await
+                .unwrap();
+            assert_eq!(decrypted, value.as_bytes());
+        }
+
+        // A batch larger than the hard limit also rotates as it goes
+        let batch: Vec<&[u8]> = vec![b"batch value".as_slice(); 12];
+        let batch_result = service
+            .encrypt_financial_data_batch(&batch, user_id, data_type, None)
+            .await;
+        assert!(batch_result.is_ok(), "batch failed: {batch_result:?}");
+    }
+
     #[tokio::test]
     async fn test_manual_key_rotation_functionality() {
         let service = create_test_service().await;
@@ -506,8 +1266,65 @@ mod tests {
 
         assert_eq!(encrypted.metadata.algorithm, EncryptionAlgorithm::Rsa4096);
         assert!(!encrypted.ciphertext.is_empty());
-        // RSA doesn't use nonces, so nonce should be empty
-        assert!(encrypted.nonce.is_empty());
+        // The payload is sealed with AES-GCM under an RSA-OAEP wrapped content key
+        assert_eq!(encrypted.nonce.len(), 12);
+        assert!(encrypted.metadata.wrapped_content_key.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rsa_hybrid_transmission_of_large_payload() {
+        let service = create_test_service().await;
+        let (private_key, public_key) = service.asymmetric_rsa.generate_keypair().await.unwrap();
+        let payload: Vec<u8> = (0..8 * 1024).map(|i| (i % 251) as u8).collect();
+
+        // Plain RSA cannot encrypt anything this size
+        let plain = service
+            .asymmetric_rsa
+            .encrypt_with_public_key(&payload, public_key.key_bytes())
+            .await;
+        assert!(matches!(plain, Err(FiscusError::InvalidInput(_))));
+
+        let encrypted = service
+            .encrypt_for_transmission(
+                &payload,
+                public_key.key_bytes(),
+                EncryptionAlgorithm::Rsa4096,
+            )
+            .await
+            .unwrap();
+        assert_ne!(encrypted.ciphertext, payload);
+
+        let decrypted = service
+            .decrypt_for_transmission(&encrypted, &private_key)
+            .await
+            .unwrap();
+        assert_eq!(decrypted, payload);
+
+        // Another recipient's key cannot unwrap the content key
+        let (other_private, _) = service.asymmetric_rsa.generate_keypair().await.unwrap();
+        assert!(service
+            .decrypt_for_transmission(&encrypted, &other_private)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_for_transmission_accepts_plain_rsa_payloads() {
+        let service = create_test_service().await;
+        let (private_key, public_key) = service.asymmetric_rsa.generate_keypair().await.unwrap();
+        let test_data = b"legacy RSA payload";
+
+        let encrypted = service
+            .asymmetric_rsa
+            .encrypt_with_public_key(test_data, public_key.key_bytes())
+            .await
+            .unwrap();
+
+        let decrypted = service
+            .decrypt_for_transmission(&encrypted, &private_key)
+            .await
+            .unwrap();
+        assert_eq!(decrypted, test_data);
     }
 
     #[tokio::test]
@@ -1194,4 +2011,355 @@ mod tests {
             "Stress test should have performed some operations"
         );
     }
+
+    #[tokio::test]
+    async fn test_cached_key_concurrent_encryptions_track_usage() {
+        let service = Arc::new(create_test_service().await);
+        let user_id = "test-user-key-cache";
+        let data_type = "cache_test";
+        let num_operations = 200u64;
+
+        // Create the key up front so every task hits the same cached entry
+        let key = service
+            .key_manager
+            .get_or_create_key(user_id, data_type)
+            .await
+            .unwrap();
+
+        let start_time = std::time::Instant::now();
+        let mut join_set = JoinSet::new();
+
+        for op_index in 0..num_operations {
+            let service_clone = Arc::clone(&service);
+            join_set.spawn(async move {
+                let data = format!("cached operation {op_index}");
+                service_clone
+                    .encrypt_financial_data(data.as_bytes(), user_id, data_type)
+                    .await
+            });
+        }
+
+        while let Some(result) = join_set.join_next().await {
+            let encrypted = result
+                .expect("Encryption task should not panic")
+                .expect("Encryption should succeed");
+            assert_eq!(encrypted.metadata.key_id, key.key_id);
+        }
+
+        let total_duration = start_time.elapsed();
+        assert!(
+            total_duration < Duration::from_secs(30),
+            "Cached concurrent encryptions took too long: {total_duration:?}"
+        );
+
+        // Usage is batched, so it becomes exact once pending counts are flushed
+        service.key_manager.flush_usage_stats().await;
+        let usage_count = service
+            .key_manager
+            .get_key_usage_count(user_id, data_type)
+            .await
+            .unwrap();
+        assert_eq!(usage_count, num_operations);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_blocks_decryption() {
+        let service = create_test_service().await;
+        let user_id = "test-user-revocation";
+        let data_type = "revocation_test";
+
+        let encrypted = service
+            .encrypt_financial_data(b"revoke me", user_id, data_type)
+            .await
+            .unwrap();
+
+        service
+            .revoke_key(user_id, &encrypted.metadata.key_id)
+            .await
+            .unwrap();
+
+        let result = service
+            .decrypt_financial_data(&encrypted, user_id, data_type)
+            .await;
+        assert!(matches!(result, Err(FiscusError::KeyManagement(_))));
+
+        // New data is encrypted with a freshly generated key
+        let re_encrypted = service
+            .encrypt_financial_data(b"fresh data", user_id, data_type)
+            .await
+            .unwrap();
+        assert_ne!(re_encrypted.metadata.key_id, encrypted.metadata.key_id);
+
+        let decrypted = service
+            .decrypt_financial_data(&re_encrypted, user_id, data_type)
+            .await
+            .unwrap();
+        assert_eq!(decrypted, b"fresh data");
+    }
+
+    #[test]
+    fn test_financial_data_aad_is_unambiguous() {
+        assert_ne!(
+            financial_data_aad("user-a", "amount"),
+            financial_data_aad("user-a", "notes")
+        );
+        assert_ne!(
+            financial_data_aad("user-a", "amount"),
+            financial_data_aad("user-b", "amount")
+        );
+        // Shifting bytes between components must not collide
+        assert_ne!(financial_data_aad("ab", "c"), financial_data_aad("a", "bc"));
+    }
+
+    #[tokio::test]
+    async fn test_ciphertext_is_bound_to_user_and_data_type() {
+        let service = create_test_service().await;
+        let user_a = "test-user-aad-a";
+        let user_b = "test-user-aad-b";
+
+        let encrypted = service
+            .encrypt_financial_data(b"1234.56", user_a, "amount")
+            .await
+            .unwrap();
+        assert!(encrypted.metadata.aad.is_some());
+
+        // Make sure the other contexts have keys so failures aren't just missing keys
+        service
+            .encrypt_financial_data(b"note", user_a, "notes")
+            .await
+            .unwrap();
+        service
+            .encrypt_financial_data(b"1", user_b, "amount")
+            .await
+            .unwrap();
+
+        assert!(service
+            .decrypt_financial_data(&encrypted, user_a, "notes")
+            .await
+            .is_err());
+        assert!(service
+            .decrypt_financial_data(&encrypted, user_b, "amount")
+            .await
+            .is_err());
+
+        let decrypted = service
+            .decrypt_financial_data(&encrypted, user_a, "amount")
+            .await
+            .unwrap();
+        assert_eq!(decrypted, b"1234.56");
+    }
+
+    #[tokio::test]
+    async fn test_aad_mismatch_fails_even_with_correct_key() {
+        let service = create_test_service().await;
+        let key = service
+            .key_manager
+            .get_or_create_key("test-user-aad", "amount")
+            .await
+            .unwrap();
+
+        let encrypted = service
+            .encrypt_financial_data(b"42.00", "test-user-aad", "amount")
+            .await
+            .unwrap();
+
+        // Same key, different claimed context
+        let result = service
+            .decrypt_bound(&encrypted, &key, "test-user-aad", "notes")
+            .await;
+        assert!(matches!(result, Err(FiscusError::Authentication(_))));
+
+        let result = service
+            .decrypt_bound(&encrypted, &key, "other-user", "amount")
+            .await;
+        assert!(matches!(result, Err(FiscusError::Authentication(_))));
+
+        // The stored AAD is ignored in favour of the caller's context
+        let mut tampered = encrypted.clone();
+        tampered.metadata.aad = Some(financial_data_aad("test-user-aad", "notes"));
+        let decrypted = service
+            .decrypt_bound(&tampered, &key, "test-user-aad", "amount")
+            .await
+            .unwrap();
+        assert_eq!(decrypted, b"42.00");
+
+        // Stripping the AAD does not allow decryption in another context
+        let mut stripped = encrypted.clone();
+        stripped.metadata.aad = None;
+        assert!(service
+            .decrypt_bound(&stripped, &key, "test-user-aad", "notes")
+            .await
+            .is_err());
+        assert!(service
+            .decrypt_bound(&stripped, &key, "test-user-aad", "amount")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_unbound_data_still_decrypts() {
+        let service = create_test_service().await;
+        let key = service
+            .key_manager
+            .get_or_create_key("test-user-legacy", "amount")
+            .await
+            .unwrap();
+
+        let legacy = service.symmetric.encrypt(b"99.99", &key).await.unwrap();
+        assert!(legacy.metadata.aad.is_none());
+
+        let decrypted = service
+            .decrypt_bound(&legacy, &key, "test-user-legacy", "amount")
+            .await
+            .unwrap();
+        assert_eq!(decrypted, b"99.99");
+    }
+
+    #[tokio::test]
+    async fn test_designated_data_type_is_compressed_before_encryption() {
+        let service = create_test_service().await;
+        let user_id = "test-user-compression";
+        let note = "Split the rent and utilities with the flatmates. ".repeat(100);
+
+        let compressed = service
+            .encrypt_financial_data(note.as_bytes(), user_id, "notes")
+            .await
+            .unwrap();
+        assert_eq!(
+            compressed.metadata.compression,
+            Some(CompressionAlgorithm::Zstd)
+        );
+        assert!(compressed.metadata.compressed);
+
+        // Same payload under a data type that is not designated for compression
+        let uncompressed = service
+            .encrypt_financial_data(note.as_bytes(), user_id, "description")
+            .await
+            .unwrap();
+        assert!(!uncompressed.metadata.compressed);
+        assert!(compressed.ciphertext.len() < uncompressed.ciphertext.len());
+
+        let decrypted = service
+            .decrypt_financial_data(&compressed, user_id, "notes")
+            .await
+            .unwrap();
+        assert_eq!(decrypted, note.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_compression_is_skipped_when_it_does_not_help() {
+        let service = create_test_service().await;
+        let user_id = "test-user-compression-skip";
+
+        let encrypted = service
+            .encrypt_financial_data(b"ok", user_id, "notes")
+            .await
+            .unwrap();
+        assert_eq!(
+            encrypted.metadata.compression,
+            Some(CompressionAlgorithm::Zstd)
+        );
+        assert!(!encrypted.metadata.compressed);
+
+        let decrypted = service
+            .decrypt_financial_data(&encrypted, user_id, "notes")
+            .await
+            .unwrap();
+        assert_eq!(decrypted, b"ok");
+    }
+
+    #[tokio::test]
+    async fn test_zeroizing_decryption_yields_plaintext() {
+        let service = create_test_service().await;
+        let user_id = "test-user-zeroizing";
+
+        let encrypted = service
+            .encrypt_financial_data(b"1234.56", user_id, "amount")
+            .await
+            .unwrap();
+        let decrypted = service
+            .decrypt_financial_data_zeroizing(&encrypted, user_id, "amount")
+            .await
+            .unwrap();
+
+        assert_eq!(decrypted.as_slice(), b"1234.56");
+    }
+
+    #[test]
+    fn test_zeroizing_wrapper_wipes_on_drop() {
+        // Best effort: a fixed-size buffer stays in place after its destructor
+        // runs, so the wiped bytes can be observed without a use-after-free
+        let mut buffer = std::mem::ManuallyDrop::new(Zeroizing::new(*b"secret"));
+        assert_eq!(&**buffer, b"secret");
+
+        unsafe { std::mem::ManuallyDrop::drop(&mut buffer) };
+
+        assert_eq!(&**buffer, &[0u8; 6]);
+    }
+
+    #[tokio::test]
+    async fn test_self_test_passes_on_healthy_service() {
+        let service = create_test_service().await;
+
+        let outcomes = service.run_self_test().await;
+
+        let names: Vec<&str> = outcomes.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                "aes256_gcm_roundtrip",
+                "aes256_gcm_tamper_detected",
+                "chacha20_poly1305_roundtrip",
+                "chacha20_poly1305_tamper_detected",
+                "ed25519_sign_verify",
+            ]
+        );
+        for (name, result) in &outcomes {
+            assert!(result.is_ok(), "{name} failed: {result:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_self_test_does_not_create_user_keys() {
+        let service = create_test_service().await;
+
+        service.run_self_test().await;
+
+        let stats = service.get_encryption_stats().await.unwrap();
+        assert_eq!(stats.total_keys, 0);
+    }
+
+    #[tokio::test]
+    async fn test_encryption_timings_recorded() {
+        let mut service = create_test_service().await;
+        service.performance = PerformanceMonitor::new();
+        let user_id = "perf_user";
+
+        let mut encrypted = Vec::new();
+        for i in 0..5 {
+            encrypted.push(
+                service
+                    .encrypt_financial_data(format!("{i}.00").as_bytes(), user_id, "amount")
+                    .await
+                    .unwrap(),
+            );
+        }
+        service
+            .decrypt_financial_data(&encrypted[0], user_id, "amount")
+            .await
+            .unwrap();
+
+        let summary = service.performance.get_summary();
+        let timings = |operation| {
+            summary
+                .encryption
+                .iter()
+                .find(|op| op.operation == operation)
+                .expect("operation should be timed")
+        };
+        let encrypt = timings(EncryptionOperation::Encrypt);
+        assert_eq!(encrypt.count, 5);
+        assert!(encrypt.average_duration > Duration::ZERO);
+        assert_eq!(timings(EncryptionOperation::Decrypt).count, 1);
+    }
 }
@@ -4,10 +4,10 @@
 /// of nonce reuse, which is critical for maintaining security in high-volume
 /// encryption scenarios where the birthday paradox could lead to nonce collisions.
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, info, instrument, warn};
 
 use super::types::{EncryptionAlgorithm, EncryptionResult};
@@ -37,6 +37,13 @@ pub struct NonceConfig {
     pub warning_threshold: u64,
     /// Enable persistence of counter state
     pub persist_counters: bool,
+    /// Signal for key rotation when a key crosses `warning_threshold`
+    #[serde(default = "default_auto_rotate_on_warning")]
+    pub auto_rotate_on_warning: bool,
+}
+
+fn default_auto_rotate_on_warning() -> bool {
+    true
 }
 
 impl Default for NonceConfig {
@@ -46,10 +53,18 @@ impl Default for NonceConfig {
             rotation_threshold: 1u64 << 32, // 2^32 encryptions
             warning_threshold: 1u64 << 30,  // 2^30 encryptions (warning at 25%)
             persist_counters: true,
+            auto_rotate_on_warning: default_auto_rotate_on_warning(),
         }
     }
 }
 
+/// Sent when a key's nonce counter crosses the warning threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceThresholdEvent {
+    pub key_id: String,
+    pub encryption_count: u64,
+}
+
 /// Thread-safe counter for a specific key
 #[derive(Debug)]
 struct KeyCounter {
@@ -83,6 +98,10 @@ pub struct NonceManager {
     counters: Arc<RwLock<HashMap<String, Arc<KeyCounter>>>>,
     /// Secure random number generator for random components
     secure_random: std::sync::Mutex<SecureRandom>,
+    /// Receives threshold events; owned by whoever rotates keys
+    threshold_sender: Option<mpsc::UnboundedSender<NonceThresholdEvent>>,
+    /// Keys already reported, so each key is signalled once
+    signalled_keys: std::sync::Mutex<HashSet<String>>,
 }
 
 impl NonceManager {
@@ -102,9 +121,24 @@ impl NonceManager {
             config,
             counters: Arc::new(RwLock::new(HashMap::new())),
             secure_random: std::sync::Mutex::new(SecureRandom::new()?),
+            threshold_sender: None,
+            signalled_keys: std::sync::Mutex::new(HashSet::new()),
         })
     }
 
+    /// Report keys crossing the warning threshold on `sender`
+    ///
+    /// Events are only sent while `auto_rotate_on_warning` is enabled. The
+    /// receiver is expected to rotate the key before it reaches
+    /// `rotation_threshold`, where nonce generation fails.
+    pub fn with_threshold_channel(
+        mut self,
+        sender: mpsc::UnboundedSender<NonceThresholdEvent>,
+    ) -> Self {
+        self.threshold_sender = Some(sender);
+        self
+    }
+
     /// Generate a nonce for the given key and algorithm
     #[instrument(skip(self), fields(key_id = %key_id, algorithm = ?algorithm, strategy = ?strategy))]
     pub async fn generate_nonce(
@@ -197,6 +231,7 @@ impl NonceManager {
                     threshold = self.config.rotation_threshold,
                     "Key approaching rotation threshold"
                 );
+                self.signal_threshold(key_id, counter_value);
             }
         }
 
@@ -216,6 +251,35 @@ impl NonceManager {
         Ok(nonce)
     }
 
+    /// Send a threshold event for `key_id` unless one was already sent
+    fn signal_threshold(&self, key_id: &str, encryption_count: u64) {
+        if !self.config.auto_rotate_on_warning {
+            return;
+        }
+        let Some(sender) = &self.threshold_sender else {
+            return;
+        };
+
+        if !self
+            .signalled_keys
+            .lock()
+            .unwrap()
+            .insert(key_id.to_string())
+        {
+            return;
+        }
+
+        let event = NonceThresholdEvent {
+            key_id: key_id.to_string(),
+            encryption_count,
+        };
+        if sender.send(event).is_err() {
+            warn!(key_id = %key_id, "Nonce threshold receiver dropped; key will not auto-rotate");
+        } else {
+            info!(key_id = %key_id, count = encryption_count, "Requested key rotation");
+        }
+    }
+
     /// Get the current encryption count for a key
     pub async fn get_encryption_count(&self, key_id: &str) -> u64 {
         let counters = self.counters.read().await;
@@ -234,6 +298,7 @@ impl NonceManager {
     pub async fn reset_counter(&self, key_id: &str) -> EncryptionResult<()> {
         let mut counters = self.counters.write().await;
         counters.remove(key_id);
+        self.signalled_keys.lock().unwrap().remove(key_id);
         info!(key_id = %key_id, "Reset counter for key");
         Ok(())
     }
@@ -411,4 +476,60 @@ mod tests {
             .to_string()
             .contains("rotation threshold"));
     }
+
+    #[tokio::test]
+    async fn test_warning_threshold_signals_once() {
+        let config = NonceConfig {
+            rotation_threshold: 10,
+            warning_threshold: 3,
+            ..Default::default()
+        };
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let manager = NonceManager::with_config(config)
+            .unwrap()
+            .with_threshold_channel(sender);
+
+        for _ in 0..6 {
+            manager
+                .generate_nonce(
+                    "test-key",
+                    EncryptionAlgorithm::Aes256Gcm,
+                    Some(NonceStrategy::CounterBased),
+                )
+                .await
+                .unwrap();
+        }
+
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.key_id, "test-key");
+        assert_eq!(event.encryption_count, 3);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_signal_respects_config() {
+        let config = NonceConfig {
+            rotation_threshold: 10,
+            warning_threshold: 1,
+            auto_rotate_on_warning: false,
+            ..Default::default()
+        };
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let manager = NonceManager::with_config(config)
+            .unwrap()
+            .with_threshold_channel(sender);
+
+        for _ in 0..3 {
+            manager
+                .generate_nonce(
+                    "test-key",
+                    EncryptionAlgorithm::Aes256Gcm,
+                    Some(NonceStrategy::CounterBased),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert!(receiver.try_recv().is_err());
+    }
 }
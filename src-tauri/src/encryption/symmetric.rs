@@ -7,12 +7,16 @@ use aes_gcm::{
     Aes256Gcm, Key, Nonce,
 };
 use async_trait::async_trait;
+use base64::Engine;
 use chacha20poly1305::{ChaCha20Poly1305, Key as ChaChaKey, Nonce as ChaChaNonce};
+use hkdf::hmac::{Hmac, Mac};
+use sha2::Sha256;
 use tracing::{debug, error, instrument};
 
 use super::nonce_manager::NonceManager;
 use super::types::{
-    EncryptedData, EncryptionAlgorithm, EncryptionKey, EncryptionMetadata, EncryptionResult,
+    AlgorithmCapabilities, EncryptedData, EncryptionAlgorithm, EncryptionKey, EncryptionMetadata,
+    EncryptionResult,
 };
 
 #[cfg(test)]
@@ -26,6 +30,16 @@ pub trait SymmetricEncryption {
     /// Encrypt data using the provided key
     async fn encrypt(&self, data: &[u8], key: &EncryptionKey) -> EncryptionResult<EncryptedData>;
 
+    /// Encrypt data with additional authenticated data (AAD) bound to the ciphertext
+    ///
+    /// The AAD is recorded in the metadata and must be unchanged for decryption to succeed.
+    async fn encrypt_with_aad(
+        &self,
+        data: &[u8],
+        key: &EncryptionKey,
+        aad: Option<&[u8]>,
+    ) -> EncryptionResult<EncryptedData>;
+
     /// Decrypt data using the provided key
     async fn decrypt(
         &self,
@@ -38,6 +52,9 @@ pub trait SymmetricEncryption {
 
     /// Get the algorithm identifier
     fn algorithm(&self) -> EncryptionAlgorithm;
+
+    /// What this implementation supports
+    fn capabilities(&self) -> AlgorithmCapabilities;
 }
 
 /// AES-256-GCM symmetric encryption implementation
@@ -205,7 +222,16 @@ impl AesGcmEncryption {
 impl SymmetricEncryption for AesGcmEncryption {
     #[instrument(skip(self, data, key), fields(data_len = data.len()))]
     async fn encrypt(&self, data: &[u8], key: &EncryptionKey) -> EncryptionResult<EncryptedData> {
-        self.encrypt_with_aad(data, key, None).await
+        AesGcmEncryption::encrypt_with_aad(self, data, key, None).await
+    }
+
+    async fn encrypt_with_aad(
+        &self,
+        data: &[u8],
+        key: &EncryptionKey,
+        aad: Option<&[u8]>,
+    ) -> EncryptionResult<EncryptedData> {
+        AesGcmEncryption::encrypt_with_aad(self, data, key, aad).await
     }
 
     #[instrument(skip(self, encrypted_data, key), fields(ciphertext_len = encrypted_data.ciphertext.len()))]
@@ -237,6 +263,16 @@ impl SymmetricEncryption for AesGcmEncryption {
     fn algorithm(&self) -> EncryptionAlgorithm {
         EncryptionAlgorithm::Aes256Gcm
     }
+
+    fn capabilities(&self) -> AlgorithmCapabilities {
+        // A shared secret key cannot be used to encrypt for someone else
+        AlgorithmCapabilities {
+            algorithm: EncryptionAlgorithm::Aes256Gcm,
+            encrypt: true,
+            sign: false,
+            transmission_safe: false,
+        }
+    }
 }
 
 /// ChaCha20-Poly1305 symmetric encryption implementation
@@ -273,6 +309,16 @@ impl ChaCha20Poly1305Encryption {
 impl SymmetricEncryption for ChaCha20Poly1305Encryption {
     #[instrument(skip(self, data, key), fields(data_len = data.len()))]
     async fn encrypt(&self, data: &[u8], key: &EncryptionKey) -> EncryptionResult<EncryptedData> {
+        self.encrypt_with_aad(data, key, None).await
+    }
+
+    #[instrument(skip(self, data, key, aad), fields(data_len = data.len(), aad_len = aad.as_ref().map_or(0, |a| a.len())))]
+    async fn encrypt_with_aad(
+        &self,
+        data: &[u8],
+        key: &EncryptionKey,
+        aad: Option<&[u8]>,
+    ) -> EncryptionResult<EncryptedData> {
         // Validate key
         if key.algorithm != EncryptionAlgorithm::ChaCha20Poly1305 {
             return Err(FiscusError::InvalidInput(
@@ -298,14 +344,29 @@ impl SymmetricEncryption for ChaCha20Poly1305Encryption {
         let nonce = ChaChaNonce::from_slice(&nonce_bytes);
 
         // Perform encryption
-        let ciphertext = cipher.encrypt(nonce, data).map_err(|e| {
+        let ciphertext = if let Some(aad_data) = aad {
+            cipher.encrypt(
+                nonce,
+                chacha20poly1305::aead::Payload {
+                    msg: data,
+                    aad: aad_data,
+                },
+            )
+        } else {
+            cipher.encrypt(nonce, data)
+        }
+        .map_err(|e| {
             error!("ChaCha20-Poly1305 encryption failed: {}", e);
             FiscusError::Internal("Encryption operation failed".to_string())
         })?;
 
-        let metadata =
+        let mut metadata =
             EncryptionMetadata::new(EncryptionAlgorithm::ChaCha20Poly1305, key.key_id.clone());
 
+        if let Some(aad_data) = aad {
+            metadata = metadata.with_aad(aad_data.to_vec());
+        }
+
         debug!(
             ciphertext_len = ciphertext.len(),
             "ChaCha20-Poly1305 encryption completed successfully"
@@ -345,14 +406,23 @@ impl SymmetricEncryption for ChaCha20Poly1305Encryption {
         let nonce = ChaChaNonce::from_slice(&encrypted_data.nonce);
 
         // Perform decryption
-        let plaintext = cipher
-            .decrypt(nonce, encrypted_data.ciphertext.as_slice())
-            .map_err(|e| {
-                error!("ChaCha20-Poly1305 decryption failed: {}", e);
-                FiscusError::Authentication(
-                    "Decryption failed - invalid key or corrupted data".to_string(),
-                )
-            })?;
+        let plaintext = if let Some(ref aad) = encrypted_data.metadata.aad {
+            cipher.decrypt(
+                nonce,
+                chacha20poly1305::aead::Payload {
+                    msg: &encrypted_data.ciphertext,
+                    aad,
+                },
+            )
+        } else {
+            cipher.decrypt(nonce, encrypted_data.ciphertext.as_slice())
+        }
+        .map_err(|e| {
+            error!("ChaCha20-Poly1305 decryption failed: {}", e);
+            FiscusError::Authentication(
+                "Decryption failed - invalid key or corrupted data".to_string(),
+            )
+        })?;
 
         debug!(
             plaintext_len = plaintext.len(),
@@ -382,6 +452,49 @@ impl SymmetricEncryption for ChaCha20Poly1305Encryption {
     fn algorithm(&self) -> EncryptionAlgorithm {
         EncryptionAlgorithm::ChaCha20Poly1305
     }
+
+    fn capabilities(&self) -> AlgorithmCapabilities {
+        // A shared secret key cannot be used to encrypt for someone else
+        AlgorithmCapabilities {
+            algorithm: EncryptionAlgorithm::ChaCha20Poly1305,
+            encrypt: true,
+            sign: false,
+            transmission_safe: false,
+        }
+    }
+}
+
+/// Prefix marking a stored blind index value
+pub const BLIND_INDEX_PREFIX: &str = "bidx:";
+
+/// Deterministic keyed index of `value`, for exact-match search on an encrypted field
+///
+/// Computed as HMAC-SHA256 over the normalised value, so equal values (ignoring
+/// case and surrounding or repeated whitespace) always produce equal indexes.
+/// That is the point, and also the cost: anyone who can read the indexes can
+/// tell which rows share a value, and low-entropy values can be confirmed by
+/// guessing. Only use it for fields where that leak is acceptable.
+pub fn blind_index(key: &EncryptionKey, value: &str) -> EncryptionResult<String> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key.key_bytes()).map_err(|e| {
+        error!("Invalid blind index key: {}", e);
+        FiscusError::Encryption("Invalid blind index key".to_string())
+    })?;
+    mac.update(normalize_index_value(value).as_bytes());
+
+    let digest = mac.finalize().into_bytes();
+    Ok(format!(
+        "{BLIND_INDEX_PREFIX}{}",
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode(digest)
+    ))
+}
+
+/// Canonical form of a value before indexing: trimmed, single-spaced and lowercase
+fn normalize_index_value(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
 }
 
 #[cfg(test)]
@@ -439,6 +552,7 @@ mod tests {
             rotation_threshold: 1000,
             warning_threshold: 800,
             persist_counters: false,
+            auto_rotate_on_warning: false,
         };
         let nonce_manager = NonceManager::with_config(config).unwrap();
         let encryption = AesGcmEncryption::with_nonce_manager(nonce_manager).unwrap();
@@ -474,6 +588,7 @@ mod tests {
             rotation_threshold: 3,
             warning_threshold: 2,
             persist_counters: false,
+            auto_rotate_on_warning: false,
         };
         let nonce_manager = NonceManager::with_config(config).unwrap();
         let encryption = AesGcmEncryption::with_nonce_manager(nonce_manager).unwrap();
@@ -971,4 +1086,20 @@ mod tests {
         let result = aes_encryption.decrypt(&chacha_encrypted, &aes_key).await;
         assert!(result.is_err(), "Cross-algorithm decryption should fail");
     }
+
+    #[tokio::test]
+    async fn test_blind_index_is_deterministic_per_key() {
+        let encryption = AesGcmEncryption::new().unwrap();
+        let key = encryption.generate_key().await.unwrap();
+        let other_key = encryption.generate_key().await.unwrap();
+
+        let index = blind_index(&key, "Corner Grocery").unwrap();
+
+        assert!(index.starts_with(BLIND_INDEX_PREFIX));
+        assert_eq!(index, blind_index(&key, "Corner Grocery").unwrap());
+        assert_eq!(index, blind_index(&key, "  corner   GROCERY ").unwrap());
+        assert_ne!(index, blind_index(&key, "Corner Grocer").unwrap());
+        assert_ne!(index, blind_index(&other_key, "Corner Grocery").unwrap());
+        assert!(!index.contains("grocery"));
+    }
 }
@@ -13,6 +13,7 @@ use crate::error::FiscusError;
 /// in security-sensitive contexts (like EncryptionKey), they are marked with
 /// #[zeroize(skip)] to prevent automatic zeroization attempts.
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -20,7 +21,7 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 pub type EncryptionResult<T> = Result<T, FiscusError>;
 
 /// Supported encryption algorithms
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum EncryptionAlgorithm {
     /// AES-256 in Galois/Counter Mode (authenticated encryption)
@@ -48,7 +49,7 @@ impl std::fmt::Display for EncryptionAlgorithm {
 }
 
 /// Types of encryption keys
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum KeyType {
     /// Symmetric encryption key
@@ -63,11 +64,24 @@ pub enum KeyType {
     MasterKey,
 }
 
+/// What an algorithm implementation in this build can be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct AlgorithmCapabilities {
+    pub algorithm: EncryptionAlgorithm,
+    /// Can encrypt and decrypt data
+    pub encrypt: bool,
+    /// Can sign data and verify signatures
+    pub sign: bool,
+    /// Can encrypt data for another party's public key with `encrypt_for_transmission`
+    pub transmission_safe: bool,
+}
+
 /// Key derivation algorithms
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum KeyDerivationAlgorithm {
     /// Argon2id (recommended for password hashing)
+    #[default]
     Argon2id,
     /// PBKDF2 with SHA-256
     Pbkdf2Sha256,
@@ -90,6 +104,21 @@ pub struct EncryptedData {
     pub metadata: EncryptionMetadata,
 }
 
+/// Compression applied to plaintext before encryption
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithm {
+    Zstd,
+}
+
+impl std::fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompressionAlgorithm::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
 /// Metadata associated with encrypted data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptionMetadata {
@@ -105,6 +134,20 @@ pub struct EncryptionMetadata {
     pub aad: Option<Vec<u8>>,
     /// Salt used for key derivation (if applicable)
     pub salt: Option<Vec<u8>>,
+    /// Label of the per-field subkey derived from `key_id`, if one was used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subkey_label: Option<String>,
+    /// Content key wrapped for the recipient, for hybrid-encrypted payloads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapped_content_key: Option<Vec<u8>>,
+    /// Compression tried on the plaintext before encryption, if its data type
+    /// is designated for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<CompressionAlgorithm>,
+    /// Whether the plaintext was actually compressed; false when compression
+    /// would not have reduced its size
+    #[serde(default)]
+    pub compressed: bool,
 }
 
 /// Secure container for encryption keys
@@ -384,6 +427,10 @@ impl EncryptionMetadata {
             version: 1,
             aad: None,
             salt: None,
+            subkey_label: None,
+            wrapped_content_key: None,
+            compression: None,
+            compressed: false,
         }
     }
 
@@ -5,10 +5,22 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::error;
 
+use crate::logging::{config::Environment, middleware::current_request_id};
+
+/// Environment whose error verbosity applies to errors sent to the frontend
+///
+/// Set when logging is initialized; until then errors keep their full messages.
+static ERROR_ENVIRONMENT: std::sync::OnceLock<Environment> = std::sync::OnceLock::new();
+
+/// Choose how much internal detail serialized errors reveal; only the first call takes effect
+pub fn set_error_environment(environment: Environment) {
+    let _ = ERROR_ENVIRONMENT.set(environment);
+}
+
 /// Custom error types for the Fiscus application
-/// These errors can be serialized across the Tauri bridge
-#[derive(Error, Debug, Serialize, Deserialize)]
-#[serde(tag = "type", content = "message")]
+/// These errors can be serialized across the Tauri bridge as
+/// `{ "type": ..., "code": ..., "message": ... }`
+#[derive(Error, Debug)]
 pub enum FiscusError {
     #[error("Database error: {0}")]
     Database(String),
@@ -16,6 +28,15 @@ pub enum FiscusError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Validation failure tied to one input field, so the frontend can highlight it
+    #[error("Validation error: {message}")]
+    FieldValidation {
+        field: String,
+        message: String,
+        /// Machine-readable reason, e.g. `required` or `invalid_format`
+        code: String,
+    },
+
     #[error("Authentication error: {0}")]
     Authentication(String),
 
@@ -55,13 +76,19 @@ pub enum FiscusError {
 
 impl FiscusError {
     /// Log the error with appropriate level and context
+    ///
+    /// Includes the id of the command being executed, if any, so a reported
+    /// error can be traced through that command's logs.
     pub fn log_error(&self, context: Option<&str>) {
         let error_msg = self.to_string();
         let error_type = self.error_type();
+        let request_id = current_request_id();
+        let request_id = request_id.as_deref();
 
         match self {
             FiscusError::Database(_) => {
                 error!(
+                    request_id = request_id,
                     error_type = error_type,
                     error = %error_msg,
                     context = context,
@@ -70,6 +97,7 @@ impl FiscusError {
             }
             FiscusError::Security(_) => {
                 error!(
+                    request_id = request_id,
                     error_type = error_type,
                     error = %error_msg,
                     context = context,
@@ -81,6 +109,7 @@ impl FiscusError {
             | FiscusError::KeyManagement(_)
             | FiscusError::Cryptographic(_) => {
                 error!(
+                    request_id = request_id,
                     error_type = error_type,
                     error = %error_msg,
                     context = context,
@@ -89,6 +118,7 @@ impl FiscusError {
             }
             FiscusError::Authentication(_) | FiscusError::Authorization(_) => {
                 error!(
+                    request_id = request_id,
                     error_type = error_type,
                     error = %error_msg,
                     context = context,
@@ -97,6 +127,7 @@ impl FiscusError {
             }
             FiscusError::Internal(_) => {
                 error!(
+                    request_id = request_id,
                     error_type = error_type,
                     error = %error_msg,
                     context = context,
@@ -105,6 +136,7 @@ impl FiscusError {
             }
             _ => {
                 error!(
+                    request_id = request_id,
                     error_type = error_type,
                     error = %error_msg,
                     context = context,
@@ -119,6 +151,7 @@ impl FiscusError {
         match self {
             FiscusError::Database(_) => "database",
             FiscusError::Validation(_) => "validation",
+            FiscusError::FieldValidation { .. } => "field_validation",
             FiscusError::Authentication(_) => "authentication",
             FiscusError::Authorization(_) => "authorization",
             FiscusError::NotFound(_) => "not_found",
@@ -134,6 +167,150 @@ impl FiscusError {
         }
     }
 
+    /// Get the stable error code used by the frontend to look up localized messages
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            FiscusError::Database(_) => "ERR_DATABASE",
+            FiscusError::Validation(_) => "ERR_VALIDATION",
+            FiscusError::FieldValidation { .. } => "ERR_FIELD_VALIDATION",
+            FiscusError::Authentication(_) => "ERR_AUTHENTICATION",
+            FiscusError::Authorization(_) => "ERR_AUTHORIZATION",
+            FiscusError::NotFound(_) => "ERR_NOT_FOUND",
+            FiscusError::Conflict(_) => "ERR_CONFLICT",
+            FiscusError::InvalidInput(_) => "ERR_INVALID_INPUT",
+            FiscusError::Security(_) => "ERR_SECURITY",
+            FiscusError::Internal(_) => "ERR_INTERNAL",
+            FiscusError::External(_) => "ERR_EXTERNAL",
+            FiscusError::Encryption(_) => "ERR_ENCRYPTION",
+            FiscusError::KeyDerivation(_) => "ERR_KEY_DERIVATION",
+            FiscusError::KeyManagement(_) => "ERR_KEY_MANAGEMENT",
+            FiscusError::Cryptographic(_) => "ERR_CRYPTOGRAPHIC",
+        }
+    }
+
+    /// Get the variant name used as the serialized `type` tag
+    fn variant_name(&self) -> &'static str {
+        match self {
+            FiscusError::Database(_) => "Database",
+            FiscusError::Validation(_) => "Validation",
+            FiscusError::FieldValidation { .. } => "FieldValidation",
+            FiscusError::Authentication(_) => "Authentication",
+            FiscusError::Authorization(_) => "Authorization",
+            FiscusError::NotFound(_) => "NotFound",
+            FiscusError::Conflict(_) => "Conflict",
+            FiscusError::InvalidInput(_) => "InvalidInput",
+            FiscusError::Security(_) => "Security",
+            FiscusError::Internal(_) => "Internal",
+            FiscusError::External(_) => "External",
+            FiscusError::Encryption(_) => "Encryption",
+            FiscusError::KeyDerivation(_) => "KeyDerivation",
+            FiscusError::KeyManagement(_) => "KeyManagement",
+            FiscusError::Cryptographic(_) => "Cryptographic",
+        }
+    }
+
+    /// Get the raw message carried by the error
+    pub fn message(&self) -> &str {
+        match self {
+            FiscusError::Database(msg)
+            | FiscusError::Validation(msg)
+            | FiscusError::Authentication(msg)
+            | FiscusError::Authorization(msg)
+            | FiscusError::NotFound(msg)
+            | FiscusError::Conflict(msg)
+            | FiscusError::InvalidInput(msg)
+            | FiscusError::Security(msg)
+            | FiscusError::Internal(msg)
+            | FiscusError::External(msg)
+            | FiscusError::Encryption(msg)
+            | FiscusError::KeyDerivation(msg)
+            | FiscusError::KeyManagement(msg)
+            | FiscusError::Cryptographic(msg) => msg,
+            FiscusError::FieldValidation { message, .. } => message,
+        }
+    }
+
+    /// Generic replacement for messages that may carry internal detail, such as SQL
+    fn redacted_message(&self) -> Option<&'static str> {
+        match self {
+            FiscusError::Database(_) => Some("A database error occurred"),
+            FiscusError::Internal(_) => Some("An internal error occurred"),
+            FiscusError::Cryptographic(_) => Some("A cryptographic operation failed"),
+            _ => None,
+        }
+    }
+
+    /// Message to show users in `environment`
+    ///
+    /// Deployed environments get generic text in place of internal detail;
+    /// the error code still identifies the failure.
+    pub fn user_message(&self, environment: &Environment) -> &str {
+        match self.redacted_message() {
+            Some(redacted) if environment.is_deployed() => redacted,
+            _ => self.message(),
+        }
+    }
+
+    /// The error as serialized for the frontend of `environment`
+    pub fn for_environment<'a>(&'a self, environment: &'a Environment) -> EnvironmentError<'a> {
+        EnvironmentError {
+            error: self,
+            environment,
+        }
+    }
+
+    /// Create a validation error for `field` with a machine-readable `code`
+    pub fn field_validation(field: &str, code: &str, message: impl Into<String>) -> Self {
+        FiscusError::FieldValidation {
+            field: field.to_string(),
+            message: message.into(),
+            code: code.to_string(),
+        }
+    }
+
+    /// Attribute a field validation error to `field`, leaving other errors unchanged
+    pub fn for_field(self, field: &str) -> Self {
+        match self {
+            FiscusError::FieldValidation { message, code, .. } => FiscusError::FieldValidation {
+                field: field.to_string(),
+                message,
+                code,
+            },
+            other => other,
+        }
+    }
+
+    /// Attribute the error to item `index` of the request list `collection`
+    ///
+    /// Field errors get a `collection[index].field` path; other input errors
+    /// have the item prefixed to their message.
+    pub fn for_item(self, collection: &str, index: usize) -> Self {
+        let item = format!("{collection}[{index}]");
+        match self {
+            FiscusError::FieldValidation {
+                field,
+                message,
+                code,
+            } => FiscusError::FieldValidation {
+                field: format!("{item}.{field}"),
+                message,
+                code,
+            },
+            FiscusError::Validation(message) => {
+                FiscusError::Validation(format!("{item}: {message}"))
+            }
+            FiscusError::InvalidInput(message) => {
+                FiscusError::InvalidInput(format!("{item}: {message}"))
+            }
+            FiscusError::NotFound(message) => FiscusError::NotFound(format!("{item}: {message}")),
+            FiscusError::Conflict(message) => FiscusError::Conflict(format!("{item}: {message}")),
+            FiscusError::Authorization(message) => {
+                FiscusError::Authorization(format!("{item}: {message}"))
+            }
+            other => other,
+        }
+    }
+
     /// Check if the error is critical (requires immediate attention)
     pub fn is_critical(&self) -> bool {
         matches!(
@@ -154,6 +331,122 @@ impl FiscusError {
     }
 }
 
+/// A `FiscusError` serialized with the message verbosity of one environment
+pub struct EnvironmentError<'a> {
+    error: &'a FiscusError,
+    environment: &'a Environment,
+}
+
+impl Serialize for EnvironmentError<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let error = self.error;
+        let message = error.user_message(self.environment);
+        if message != error.message() {
+            // The detail no longer reaches the user, so keep it in the logs
+            error.log_error(Some("detail withheld from the frontend"));
+        }
+
+        let field_details = match error {
+            FiscusError::FieldValidation { field, code, .. } => Some((field, code)),
+            _ => None,
+        };
+
+        let field_count = if field_details.is_some() { 5 } else { 3 };
+        let mut state = serializer.serialize_struct("FiscusError", field_count)?;
+        state.serialize_field("type", error.variant_name())?;
+        state.serialize_field("code", error.error_code())?;
+        state.serialize_field("message", message)?;
+        if let Some((field, validation_code)) = field_details {
+            state.serialize_field("field", field)?;
+            state.serialize_field("validation_code", validation_code)?;
+        }
+        state.end()
+    }
+}
+
+impl Serialize for FiscusError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let environment = ERROR_ENVIRONMENT.get().unwrap_or(&Environment::Development);
+        self.for_environment(environment).serialize(serializer)
+    }
+}
+
+/// Wire representation of `FiscusError`
+///
+/// The variant comes from `type`, so the serialized `code` is ignored and
+/// payloads produced before error codes were introduced still deserialize.
+#[derive(Deserialize)]
+struct FiscusErrorRepr {
+    #[serde(rename = "type")]
+    error_type: String,
+    message: String,
+    /// Only present for `FieldValidation`
+    #[serde(default)]
+    field: Option<String>,
+    #[serde(default)]
+    validation_code: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for FiscusError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let repr = FiscusErrorRepr::deserialize(deserializer)?;
+        let message = repr.message;
+
+        match repr.error_type.as_str() {
+            "Database" => Ok(FiscusError::Database(message)),
+            "Validation" => Ok(FiscusError::Validation(message)),
+            "FieldValidation" => Ok(FiscusError::FieldValidation {
+                field: repr.field.unwrap_or_default(),
+                message,
+                code: repr.validation_code.unwrap_or_default(),
+            }),
+            "Authentication" => Ok(FiscusError::Authentication(message)),
+            "Authorization" => Ok(FiscusError::Authorization(message)),
+            "NotFound" => Ok(FiscusError::NotFound(message)),
+            "Conflict" => Ok(FiscusError::Conflict(message)),
+            "InvalidInput" => Ok(FiscusError::InvalidInput(message)),
+            "Security" => Ok(FiscusError::Security(message)),
+            "Internal" => Ok(FiscusError::Internal(message)),
+            "External" => Ok(FiscusError::External(message)),
+            "Encryption" => Ok(FiscusError::Encryption(message)),
+            "KeyDerivation" => Ok(FiscusError::KeyDerivation(message)),
+            "KeyManagement" => Ok(FiscusError::KeyManagement(message)),
+            "Cryptographic" => Ok(FiscusError::Cryptographic(message)),
+            other => Err(serde::de::Error::unknown_variant(
+                other,
+                &[
+                    "Database",
+                    "Validation",
+                    "FieldValidation",
+                    "Authentication",
+                    "Authorization",
+                    "NotFound",
+                    "Conflict",
+                    "InvalidInput",
+                    "Security",
+                    "Internal",
+                    "External",
+                    "Encryption",
+                    "KeyDerivation",
+                    "KeyManagement",
+                    "Cryptographic",
+                ],
+            )),
+        }
+    }
+}
+
 impl From<tauri_plugin_sql::Error> for FiscusError {
     fn from(err: tauri_plugin_sql::Error) -> Self {
         FiscusError::Database(err.to_string())
@@ -267,6 +560,161 @@ static VALID_CURRENCY_CODES: Lazy<std::collections::HashSet<&'static str>> = Laz
     .collect()
 });
 
+/// Format for user-defined currency codes, e.g. `BTC` or `LOCALX1`
+static CUSTOM_CURRENCY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[A-Z0-9]{3,8}$")
+        .expect("Failed to compile custom currency regex - this should never happen")
+});
+
+/// Most decimal places a custom currency may use (satoshi precision fits comfortably)
+pub const MAX_CUSTOM_CURRENCY_MINOR_UNITS: u32 = 18;
+
+/// Registered custom currency codes and their number of minor units
+///
+/// Loaded from the `custom_currencies` table; consulted after the ISO list.
+static CUSTOM_CURRENCY_CODES: Lazy<std::sync::RwLock<std::collections::HashMap<String, u32>>> =
+    Lazy::new(Default::default);
+
+/// Add a custom currency to the runtime allowlist, returning the normalized code
+///
+/// Persisting the currency is up to the caller; this only affects validation.
+pub fn register_custom_currency_code(code: &str, minor_units: u32) -> FiscusResult<String> {
+    let code = Validator::validate_custom_currency(code, minor_units)?;
+    CUSTOM_CURRENCY_CODES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(code.clone(), minor_units);
+    Ok(code)
+}
+
+/// Minor units of a registered custom currency, if any
+pub fn custom_currency_minor_units(code: &str) -> Option<u32> {
+    CUSTOM_CURRENCY_CODES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(code)
+        .copied()
+}
+
+/// Small list of passwords that are rejected regardless of complexity
+static COMMON_PASSWORDS: &[&str] = &[
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "123456",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "qwerty",
+    "qwerty123",
+    "abc123",
+    "letmein",
+    "welcome",
+    "welcome1",
+    "iloveyou",
+    "admin",
+    "admin123",
+    "monkey",
+    "dragon",
+    "football",
+    "baseball",
+    "sunshine",
+    "trustno1",
+    "changeme",
+];
+
+/// Environment variable selecting the password policy preset (`default` or `strict`)
+const PASSWORD_POLICY_ENV: &str = "FISCUS_PASSWORD_POLICY";
+
+/// Environment variable raising or lowering the preset's minimum password length
+const PASSWORD_MIN_LENGTH_ENV: &str = "FISCUS_PASSWORD_MIN_LENGTH";
+
+/// Password complexity requirements
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub reject_common: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+            reject_common: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Stricter policy for deployments that need stronger passwords
+    pub fn strict() -> Self {
+        Self {
+            min_length: 12,
+            require_symbol: true,
+            ..Self::default()
+        }
+    }
+
+    /// Preset policy by name, `None` for an unknown name
+    pub fn named(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "strict" => Some(Self::strict()),
+            _ => None,
+        }
+    }
+
+    /// Configuration read from `FISCUS_PASSWORD_POLICY` and `FISCUS_PASSWORD_MIN_LENGTH`
+    ///
+    /// An unknown preset falls back to the default policy; the minimum length
+    /// override is ignored unless it is between 1 and `max_length`.
+    pub fn from_env() -> Self {
+        let mut policy = std::env::var(PASSWORD_POLICY_ENV)
+            .ok()
+            .and_then(|name| Self::named(&name))
+            .unwrap_or_default();
+
+        if let Some(min_length) = std::env::var(PASSWORD_MIN_LENGTH_ENV)
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|min| (1..=policy.max_length).contains(min))
+        {
+            policy.min_length = min_length;
+        }
+
+        policy
+    }
+}
+
+crate::env_config!(PasswordPolicy);
+
+/// Date layouts `Validator::parse_flexible_date` tries, in order: ISO, US,
+/// day-first with slashes, and European with dots
+pub const FLEXIBLE_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%m/%d/%Y", "%d/%m/%Y", "%d.%m.%Y"];
+
+/// Most decimal places `Validator::validate_amount` accepts in an amount
+pub const DEFAULT_MAX_AMOUNT_SCALE: u32 = 4;
+
+/// How an amount with more decimal places than allowed is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExcessPrecision {
+    /// Fail validation with a `too_precise` field error
+    Reject,
+    /// Round to the allowed number of decimal places
+    Round,
+}
+
 /// Validation utilities
 pub struct Validator;
 
@@ -279,21 +727,27 @@ impl Validator {
         max_len: usize,
     ) -> FiscusResult<()> {
         if value.trim().is_empty() {
-            return Err(FiscusError::Validation(format!(
-                "{field_name} cannot be empty"
-            )));
+            return Err(FiscusError::field_validation(
+                field_name,
+                "required",
+                format!("{field_name} cannot be empty"),
+            ));
         }
 
         if value.len() < min_len {
-            return Err(FiscusError::Validation(format!(
-                "{field_name} must be at least {min_len} characters"
-            )));
+            return Err(FiscusError::field_validation(
+                field_name,
+                "too_short",
+                format!("{field_name} must be at least {min_len} characters"),
+            ));
         }
 
         if value.len() > max_len {
-            return Err(FiscusError::Validation(format!(
-                "{field_name} cannot exceed {max_len} characters"
-            )));
+            return Err(FiscusError::field_validation(
+                field_name,
+                "too_long",
+                format!("{field_name} cannot exceed {max_len} characters"),
+            ));
         }
 
         Ok(())
@@ -302,7 +756,11 @@ impl Validator {
     /// Validate email format
     pub fn validate_email(email: &str) -> FiscusResult<()> {
         if !EMAIL_REGEX.is_match(email) {
-            return Err(FiscusError::Validation("Invalid email format".to_string()));
+            return Err(FiscusError::field_validation(
+                "email",
+                "invalid_format",
+                "Invalid email format",
+            ));
         }
 
         Ok(())
@@ -310,91 +768,316 @@ impl Validator {
 
     /// Validate UUID format
     pub fn validate_uuid(id: &str, field_name: &str) -> FiscusResult<uuid::Uuid> {
-        uuid::Uuid::parse_str(id)
-            .map_err(|_| FiscusError::Validation(format!("Invalid {field_name} format")))
+        uuid::Uuid::parse_str(id).map_err(|_| {
+            FiscusError::field_validation(
+                field_name,
+                "invalid_format",
+                format!("Invalid {field_name} format"),
+            )
+        })
     }
 
     /// Validate amount (must be positive for most operations)
+    ///
+    /// Amounts with more than `DEFAULT_MAX_AMOUNT_SCALE` decimal places are rejected.
     pub fn validate_amount(
         amount: rust_decimal::Decimal,
         allow_negative: bool,
     ) -> FiscusResult<()> {
+        Self::validate_amount_with_precision(
+            amount,
+            allow_negative,
+            DEFAULT_MAX_AMOUNT_SCALE,
+            ExcessPrecision::Reject,
+        )
+        .map(|_| ())
+    }
+
+    /// Validate an amount allowing at most `max_scale` decimal places
+    ///
+    /// Trailing zeros don't count towards the scale. Returns the amount to
+    /// store, which is rounded when `excess` is `ExcessPrecision::Round`.
+    pub fn validate_amount_with_precision(
+        amount: rust_decimal::Decimal,
+        allow_negative: bool,
+        max_scale: u32,
+        excess: ExcessPrecision,
+    ) -> FiscusResult<rust_decimal::Decimal> {
         if !allow_negative && amount < rust_decimal::Decimal::ZERO {
-            return Err(FiscusError::Validation(
-                "Amount cannot be negative".to_string(),
+            return Err(FiscusError::field_validation(
+                "amount",
+                "negative",
+                "Amount cannot be negative",
             ));
         }
 
         // Check for reasonable limits (prevent overflow)
         let max_amount = rust_decimal::Decimal::from(999_999_999_999i64);
         if amount.abs() > max_amount {
-            return Err(FiscusError::Validation(
-                "Amount exceeds maximum allowed value".to_string(),
+            return Err(FiscusError::field_validation(
+                "amount",
+                "out_of_range",
+                "Amount exceeds maximum allowed value",
             ));
         }
 
-        Ok(())
+        if amount.normalize().scale() <= max_scale {
+            return Ok(amount);
+        }
+
+        match excess {
+            ExcessPrecision::Round => Ok(amount.round_dp(max_scale)),
+            ExcessPrecision::Reject => Err(FiscusError::field_validation(
+                "amount",
+                "too_precise",
+                format!("Amount cannot have more than {max_scale} decimal places"),
+            )),
+        }
     }
 
     /// Validate date string
-    pub fn validate_date(date_str: &str) -> FiscusResult<chrono::NaiveDate> {
+    pub fn validate_date(date_str: &str, field_name: &str) -> FiscusResult<chrono::NaiveDate> {
         chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|_| {
-            FiscusError::Validation("Invalid date format. Expected YYYY-MM-DD".to_string())
+            FiscusError::field_validation(
+                field_name,
+                "invalid_format",
+                "Invalid date format. Expected YYYY-MM-DD",
+            )
         })
     }
 
+    /// Validate a date string in the chrono `format`, e.g. `%d.%m.%Y`
+    pub fn validate_date_with_format(
+        date_str: &str,
+        format: &str,
+        field_name: &str,
+    ) -> FiscusResult<chrono::NaiveDate> {
+        chrono::NaiveDate::parse_from_str(date_str.trim(), format).map_err(|_| {
+            FiscusError::field_validation(
+                field_name,
+                "invalid_format",
+                format!("Invalid date format. Expected {format}"),
+            )
+        })
+    }
+
+    /// Parse a date from imported data
+    ///
+    /// With a `format_hint` only that format is accepted. Otherwise every
+    /// format in `FLEXIBLE_DATE_FORMATS` is tried, and a date that reads
+    /// differently under two of them (such as `03/04/2024`) is rejected as
+    /// ambiguous rather than guessed.
+    pub fn parse_flexible_date(
+        date_str: &str,
+        field_name: &str,
+        format_hint: Option<&str>,
+    ) -> FiscusResult<chrono::NaiveDate> {
+        if let Some(format) = format_hint {
+            return Self::validate_date_with_format(date_str, format, field_name);
+        }
+
+        let mut parsed = FLEXIBLE_DATE_FORMATS
+            .iter()
+            .filter_map(|format| chrono::NaiveDate::parse_from_str(date_str.trim(), format).ok());
+        let Some(date) = parsed.next() else {
+            return Err(FiscusError::field_validation(
+                field_name,
+                "invalid_format",
+                format!(
+                    "Invalid date format. Expected one of {}",
+                    FLEXIBLE_DATE_FORMATS.join(", ")
+                ),
+            ));
+        };
+
+        if parsed.any(|other| other != date) {
+            return Err(FiscusError::field_validation(
+                field_name,
+                "ambiguous",
+                format!("Date {date_str} is ambiguous; specify its format"),
+            ));
+        }
+
+        Ok(date)
+    }
+
     /// Validate datetime string
-    pub fn validate_datetime(datetime_str: &str) -> FiscusResult<chrono::DateTime<chrono::Utc>> {
+    pub fn validate_datetime(
+        datetime_str: &str,
+        field_name: &str,
+    ) -> FiscusResult<chrono::DateTime<chrono::Utc>> {
         chrono::DateTime::parse_from_rfc3339(datetime_str)
             .map(|dt| dt.with_timezone(&chrono::Utc))
             .map_err(|_| {
-                FiscusError::Validation("Invalid datetime format. Expected RFC3339".to_string())
+                FiscusError::field_validation(
+                    field_name,
+                    "invalid_format",
+                    "Invalid datetime format. Expected RFC3339",
+                )
             })
     }
 
     /// Validate currency code according to ISO 4217 standard
-    /// Ensures the currency code is a 3-letter uppercase code and is in the supported list
+    /// Ensures the currency code is a 3-letter uppercase code and is in the supported list,
+    /// or a registered custom currency
     pub fn validate_currency_code(currency: &str) -> FiscusResult<()> {
         // Check if empty or whitespace
         if currency.trim().is_empty() {
-            return Err(FiscusError::Validation(
-                "Currency code cannot be empty".to_string(),
+            return Err(FiscusError::field_validation(
+                "currency",
+                "required",
+                "Currency code cannot be empty",
             ));
         }
 
         // Normalize to uppercase for validation
         let currency_upper = currency.trim().to_uppercase();
 
+        if custom_currency_minor_units(&currency_upper).is_some() {
+            return Ok(());
+        }
+
         // Check format (3 uppercase letters)
         if !CURRENCY_REGEX.is_match(&currency_upper) {
-            return Err(FiscusError::Validation(
-                "Currency code must be exactly 3 uppercase letters (e.g., USD, EUR, GBP)"
-                    .to_string(),
+            return Err(FiscusError::field_validation(
+                "currency",
+                "invalid_format",
+                "Currency code must be exactly 3 uppercase letters (e.g., USD, EUR, GBP)",
             ));
         }
 
         // Check if currency is supported
         if !VALID_CURRENCY_CODES.contains(currency_upper.as_str()) {
-            return Err(FiscusError::Validation(format!(
-                "Unsupported currency code: {currency_upper}. Please use a valid ISO 4217 currency code"
-            )));
+            return Err(FiscusError::field_validation(
+                "currency",
+                "unsupported",
+                format!(
+                    "Unsupported currency code: {currency_upper}. Please use a valid ISO 4217 currency code"
+                ),
+            ));
         }
 
         Ok(())
     }
 
+    /// Validate a custom currency definition, returning the normalized code
+    /// Codes are 3-8 uppercase alphanumerics and may not shadow a built-in ISO code
+    pub fn validate_custom_currency(code: &str, minor_units: u32) -> FiscusResult<String> {
+        let code = code.trim().to_uppercase();
+
+        if !CUSTOM_CURRENCY_REGEX.is_match(&code) {
+            return Err(FiscusError::field_validation(
+                "code",
+                "invalid_format",
+                "Custom currency code must be 3 to 8 uppercase letters or digits",
+            ));
+        }
+
+        if VALID_CURRENCY_CODES.contains(code.as_str()) {
+            return Err(FiscusError::field_validation(
+                "code",
+                "conflict",
+                format!("{code} is already a supported ISO 4217 currency code"),
+            ));
+        }
+
+        if minor_units > MAX_CUSTOM_CURRENCY_MINOR_UNITS {
+            return Err(FiscusError::field_validation(
+                "minor_units",
+                "out_of_range",
+                format!("minor_units cannot exceed {MAX_CUSTOM_CURRENCY_MINOR_UNITS}"),
+            ));
+        }
+
+        Ok(code)
+    }
+
     /// Validate user ID format and content
     /// Ensures the user ID is a valid UUID format and not empty
     pub fn validate_user_id(user_id: &str) -> FiscusResult<uuid::Uuid> {
         // Check if empty or whitespace
         if user_id.trim().is_empty() {
-            return Err(FiscusError::Validation(
-                "User ID cannot be empty".to_string(),
+            return Err(FiscusError::field_validation(
+                "user_id",
+                "required",
+                "User ID cannot be empty",
             ));
         }
 
         // Validate UUID format and return the parsed UUID
-        Self::validate_uuid(user_id, "user ID")
+        Self::validate_uuid(user_id, "user ID").map_err(|e| e.for_field("user_id"))
+    }
+
+    /// Validate a password against the default password policy
+    pub fn validate_password_strength(password: &str) -> FiscusResult<()> {
+        Self::validate_password_with_policy(password, &PasswordPolicy::default())
+    }
+
+    /// Validate a password against a custom password policy
+    pub fn validate_password_with_policy(
+        password: &str,
+        policy: &PasswordPolicy,
+    ) -> FiscusResult<()> {
+        let length = password.chars().count();
+        if length < policy.min_length {
+            return Err(FiscusError::field_validation(
+                "password",
+                "too_short",
+                format!(
+                    "Password must be at least {} characters long",
+                    policy.min_length
+                ),
+            ));
+        }
+        if length > policy.max_length {
+            return Err(FiscusError::field_validation(
+                "password",
+                "too_long",
+                format!("Password cannot exceed {} characters", policy.max_length),
+            ));
+        }
+
+        if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            return Err(FiscusError::field_validation(
+                "password",
+                "missing_uppercase",
+                "Password must contain at least one uppercase letter",
+            ));
+        }
+        if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            return Err(FiscusError::field_validation(
+                "password",
+                "missing_lowercase",
+                "Password must contain at least one lowercase letter",
+            ));
+        }
+        if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err(FiscusError::field_validation(
+                "password",
+                "missing_digit",
+                "Password must contain at least one digit",
+            ));
+        }
+        if policy.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+            return Err(FiscusError::field_validation(
+                "password",
+                "missing_symbol",
+                "Password must contain at least one symbol",
+            ));
+        }
+
+        if policy.reject_common {
+            let normalized = password.to_lowercase();
+            if COMMON_PASSWORDS.contains(&normalized.as_str()) {
+                return Err(FiscusError::field_validation(
+                    "password",
+                    "too_common",
+                    "Password is too common",
+                ));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -459,8 +1142,28 @@ impl<'de> serde::Deserialize<'de> for ValidatedUserId {
     }
 }
 
+/// Schema for the string form user IDs are (de)serialized as
+impl schemars::JsonSchema for ValidatedUserId {
+    fn schema_name() -> String {
+        "ValidatedUserId".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            format: Some("uuid".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+}
+
 /// Validated wrapper type for currency codes
-/// Ensures currency codes follow ISO 4217 standard
+/// Ensures currency codes follow ISO 4217 standard or are registered custom currencies
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ValidatedCurrency(String);
 
@@ -511,6 +1214,32 @@ impl<'de> serde::Deserialize<'de> for ValidatedCurrency {
     }
 }
 
+/// Schema for currency codes; lowercase input is accepted and normalized on deserialization
+///
+/// Codes longer than three characters are only valid once registered as custom currencies.
+impl schemars::JsonSchema for ValidatedCurrency {
+    fn schema_name() -> String {
+        "ValidatedCurrency".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            string: Some(Box::new(schemars::schema::StringValidation {
+                min_length: Some(3),
+                max_length: Some(8),
+                pattern: Some("^[A-Za-z0-9]{3,8}$".to_string()),
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+}
+
 /// Security utilities for field whitelisting and SQL injection prevention
 pub struct SecurityValidator;
 
@@ -528,6 +1257,28 @@ impl SecurityValidator {
         "updated_at",
     ];
 
+    /// Transaction fields a query may be restricted to
+    pub const TRANSACTION_SELECT_FIELDS: &'static [&'static str] = &[
+        "id",
+        "user_id",
+        "account_id",
+        "category_id",
+        "amount",
+        "description",
+        "notes",
+        "transaction_date",
+        "transaction_type",
+        "status",
+        "reference_number",
+        "payee",
+        "tags",
+        "original_amount",
+        "original_currency",
+        "refunds_transaction_id",
+        "created_at",
+        "updated_at",
+    ];
+
     /// Allowed fields for category sorting
     pub const CATEGORY_SORT_FIELDS: &'static [&'static str] = &["name", "created_at", "updated_at"];
 
@@ -550,6 +1301,25 @@ impl SecurityValidator {
         }
     }
 
+    /// Validate requested select fields against a whitelist
+    pub fn validate_select_fields(fields: &[String], allowed_fields: &[&str]) -> FiscusResult<()> {
+        if fields.is_empty() {
+            return Err(FiscusError::Security(
+                "At least one field must be selected".to_string(),
+            ));
+        }
+
+        match fields
+            .iter()
+            .find(|field| !allowed_fields.contains(&field.as_str()))
+        {
+            Some(field) => Err(FiscusError::Security(format!(
+                "Invalid select field: {field}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
     /// Validate sort direction
     pub fn validate_sort_direction(direction: &str) -> FiscusResult<String> {
         match direction.to_uppercase().as_str() {
@@ -604,6 +1374,9 @@ impl SecurityValidator {
             "end_date",
             "min_amount",
             "max_amount",
+            "min_amount_minor",
+            "max_amount_minor",
+            "payee_index",
         ];
 
         for key in filters.keys() {
@@ -674,6 +1447,151 @@ mod tests {
         }
     }
 
+    fn all_error_variants() -> Vec<FiscusError> {
+        vec![
+            FiscusError::Database("db".to_string()),
+            FiscusError::Validation("validation".to_string()),
+            FiscusError::field_validation("amount", "negative", "field validation"),
+            FiscusError::Authentication("auth".to_string()),
+            FiscusError::Authorization("authz".to_string()),
+            FiscusError::NotFound("missing".to_string()),
+            FiscusError::Conflict("conflict".to_string()),
+            FiscusError::InvalidInput("input".to_string()),
+            FiscusError::Security("security".to_string()),
+            FiscusError::Internal("internal".to_string()),
+            FiscusError::External("external".to_string()),
+            FiscusError::Encryption("encryption".to_string()),
+            FiscusError::KeyDerivation("kdf".to_string()),
+            FiscusError::KeyManagement("keys".to_string()),
+            FiscusError::Cryptographic("crypto".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_error_codes_are_distinct_and_stable() {
+        let errors = all_error_variants();
+        let codes: std::collections::HashSet<&'static str> =
+            errors.iter().map(|e| e.error_code()).collect();
+        assert_eq!(codes.len(), errors.len());
+
+        for code in &codes {
+            assert!(code.starts_with("ERR_"));
+        }
+
+        assert_eq!(
+            FiscusError::NotFound("x".to_string()).error_code(),
+            "ERR_NOT_FOUND"
+        );
+        assert_eq!(
+            FiscusError::InvalidInput("x".to_string()).error_code(),
+            "ERR_INVALID_INPUT"
+        );
+    }
+
+    #[test]
+    fn test_fiscus_error_serialization_includes_code() {
+        let error = FiscusError::NotFound("Account not found".to_string());
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["type"], "NotFound");
+        assert_eq!(value["code"], "ERR_NOT_FOUND");
+        assert_eq!(value["message"], "Account not found");
+    }
+
+    #[test]
+    fn test_fiscus_error_serialization_round_trip() {
+        for error in all_error_variants() {
+            let serialized = serde_json::to_string(&error).unwrap();
+            let deserialized: FiscusError = serde_json::from_str(&serialized).unwrap();
+
+            assert_eq!(deserialized.error_code(), error.error_code());
+            assert_eq!(deserialized.message(), error.message());
+        }
+    }
+
+    #[test]
+    fn test_production_serialization_hides_internal_detail() {
+        let error = FiscusError::Database(
+            "no such column: amount_minor in SELECT * FROM transactions".into(),
+        );
+        let value = serde_json::to_value(error.for_environment(&Environment::Production)).unwrap();
+
+        assert_eq!(value["type"], "Database");
+        assert_eq!(value["code"], "ERR_DATABASE");
+        assert!(!value["message"].as_str().unwrap().contains("SELECT"));
+
+        // Messages meant for users are left alone
+        let not_found = FiscusError::NotFound("Account not found".to_string());
+        let value =
+            serde_json::to_value(not_found.for_environment(&Environment::Production)).unwrap();
+        assert_eq!(value["message"], "Account not found");
+    }
+
+    #[test]
+    fn test_development_serialization_keeps_internal_detail() {
+        let error = FiscusError::Database(
+            "no such column: amount_minor in SELECT * FROM transactions".into(),
+        );
+        let value = serde_json::to_value(error.for_environment(&Environment::Development)).unwrap();
+
+        assert_eq!(value["code"], "ERR_DATABASE");
+        assert_eq!(
+            value["message"],
+            "no such column: amount_minor in SELECT * FROM transactions"
+        );
+    }
+
+    #[test]
+    fn test_field_validation_serialization_keeps_field() {
+        let error =
+            FiscusError::field_validation("description", "required", "description cannot be empty");
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["type"], "FieldValidation");
+        assert_eq!(value["code"], "ERR_FIELD_VALIDATION");
+        assert_eq!(value["field"], "description");
+        assert_eq!(value["validation_code"], "required");
+
+        let deserialized: FiscusError = serde_json::from_value(value).unwrap();
+        match deserialized {
+            FiscusError::FieldValidation {
+                field,
+                message,
+                code,
+            } => {
+                assert_eq!(field, "description");
+                assert_eq!(code, "required");
+                assert_eq!(message, "description cannot be empty");
+            }
+            other => panic!("Expected FieldValidation error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_for_field_retags_only_field_errors() {
+        let retagged = Validator::validate_uuid("bad", "user ID")
+            .unwrap_err()
+            .for_field("user_id");
+        assert!(matches!(
+            retagged,
+            FiscusError::FieldValidation { ref field, .. } if field == "user_id"
+        ));
+
+        let other = FiscusError::NotFound("missing".to_string()).for_field("user_id");
+        assert!(matches!(other, FiscusError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_fiscus_error_deserialization_without_code() {
+        let legacy = r#"{"type":"Conflict","message":"Username already exists"}"#;
+        let error: FiscusError = serde_json::from_str(legacy).unwrap();
+
+        match error {
+            FiscusError::Conflict(msg) => assert_eq!(msg, "Username already exists"),
+            _ => panic!("Expected Conflict error"),
+        }
+    }
+
     #[test]
     fn test_error_conversions() {
         // Test serde_json::Error conversion
@@ -810,33 +1728,129 @@ mod tests {
             assert!(Validator::validate_amount(too_large, false).is_err());
         }
 
+        #[test]
+        fn test_validate_amount_precision_guard() {
+            let precise = Decimal::new(123_456_789, 8); // 1.23456789
+
+            let result = Validator::validate_amount_with_precision(
+                precise,
+                false,
+                2,
+                ExcessPrecision::Reject,
+            );
+            assert!(matches!(
+                result,
+                Err(FiscusError::FieldValidation { ref code, .. }) if code == "too_precise"
+            ));
+            assert_eq!(
+                Validator::validate_amount_with_precision(
+                    precise,
+                    false,
+                    2,
+                    ExcessPrecision::Round
+                )
+                .unwrap(),
+                Decimal::new(123, 2)
+            );
+            assert_eq!(
+                Validator::validate_amount_with_precision(
+                    Decimal::new(123, 2),
+                    false,
+                    2,
+                    ExcessPrecision::Reject
+                )
+                .unwrap(),
+                Decimal::new(123, 2)
+            );
+
+            // The default guard allows four places; trailing zeros don't count
+            assert!(Validator::validate_amount(precise, false).is_err());
+            assert!(Validator::validate_amount(Decimal::new(12345, 4), false).is_ok());
+            assert!(Validator::validate_amount(Decimal::new(1_500_000, 6), false).is_ok());
+
+            // Range checks still apply before rounding
+            assert!(Validator::validate_amount_with_precision(
+                -precise,
+                false,
+                2,
+                ExcessPrecision::Round
+            )
+            .is_err());
+        }
+
         #[test]
         fn test_validate_date() {
             // Valid dates
-            assert!(Validator::validate_date("2023-12-25").is_ok());
-            assert!(Validator::validate_date("2000-01-01").is_ok());
-            assert!(Validator::validate_date("2024-02-29").is_ok()); // Leap year
+            assert!(Validator::validate_date("2023-12-25", "date").is_ok());
+            assert!(Validator::validate_date("2000-01-01", "date").is_ok());
+            assert!(Validator::validate_date("2024-02-29", "date").is_ok()); // Leap year
 
             // Invalid dates
-            assert!(Validator::validate_date("2023-13-01").is_err()); // Invalid month
-            assert!(Validator::validate_date("2023-12-32").is_err()); // Invalid day
-            assert!(Validator::validate_date("2023/12/25").is_err()); // Wrong format
-            assert!(Validator::validate_date("invalid-date").is_err());
-            assert!(Validator::validate_date("").is_err());
+            assert!(Validator::validate_date("2023-13-01", "date").is_err()); // Invalid month
+            assert!(Validator::validate_date("2023-12-32", "date").is_err()); // Invalid day
+            assert!(Validator::validate_date("2023/12/25", "date").is_err()); // Wrong format
+            assert!(Validator::validate_date("invalid-date", "date").is_err());
+            assert!(Validator::validate_date("", "date").is_err());
+        }
+
+        #[test]
+        fn test_parse_flexible_date_formats() {
+            let expected = chrono::NaiveDate::from_ymd_opt(2024, 3, 25).unwrap();
+
+            for input in ["2024-03-25", "03/25/2024", "25/03/2024", "25.03.2024"] {
+                assert_eq!(
+                    Validator::parse_flexible_date(input, "date", None).unwrap(),
+                    expected,
+                    "{input}"
+                );
+            }
+
+            assert_eq!(
+                Validator::parse_flexible_date("03/04/2024", "date", Some("%m/%d/%Y")).unwrap(),
+                chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+            );
+            assert_eq!(
+                Validator::parse_flexible_date("03/04/2024", "date", Some("%d/%m/%Y")).unwrap(),
+                chrono::NaiveDate::from_ymd_opt(2024, 4, 3).unwrap()
+            );
+            assert!(
+                Validator::parse_flexible_date("2024-03-25", "date", Some("%d.%m.%Y")).is_err()
+            );
+        }
+
+        #[test]
+        fn test_parse_flexible_date_rejects_ambiguous_and_invalid() {
+            match Validator::parse_flexible_date("03/04/2024", "booked_on", None) {
+                Err(FiscusError::FieldValidation { field, code, .. }) => {
+                    assert_eq!(field, "booked_on");
+                    assert_eq!(code, "ambiguous");
+                }
+                other => panic!("expected an ambiguous date error, got {other:?}"),
+            }
+
+            // Same day and month reads the same either way
+            assert!(Validator::parse_flexible_date("04/04/2024", "date", None).is_ok());
+
+            for input in ["2024-13-01", "31.02.2024", "yesterday", ""] {
+                assert!(
+                    Validator::parse_flexible_date(input, "date", None).is_err(),
+                    "{input}"
+                );
+            }
         }
 
         #[test]
         fn test_validate_datetime() {
             // Valid datetimes
-            assert!(Validator::validate_datetime("2023-12-25T10:30:00Z").is_ok());
-            assert!(Validator::validate_datetime("2023-12-25T10:30:00+00:00").is_ok());
-            assert!(Validator::validate_datetime("2023-12-25T10:30:00-05:00").is_ok());
+            assert!(Validator::validate_datetime("2023-12-25T10:30:00Z", "datetime").is_ok());
+            assert!(Validator::validate_datetime("2023-12-25T10:30:00+00:00", "datetime").is_ok());
+            assert!(Validator::validate_datetime("2023-12-25T10:30:00-05:00", "datetime").is_ok());
 
             // Invalid datetimes
-            assert!(Validator::validate_datetime("2023-12-25 10:30:00").is_err()); // Wrong format
-            assert!(Validator::validate_datetime("2023-12-25T25:30:00Z").is_err()); // Invalid hour
-            assert!(Validator::validate_datetime("invalid-datetime").is_err());
-            assert!(Validator::validate_datetime("").is_err());
+            assert!(Validator::validate_datetime("2023-12-25 10:30:00", "datetime").is_err()); // Wrong format
+            assert!(Validator::validate_datetime("2023-12-25T25:30:00Z", "datetime").is_err()); // Invalid hour
+            assert!(Validator::validate_datetime("invalid-datetime", "datetime").is_err());
+            assert!(Validator::validate_datetime("", "datetime").is_err());
         }
     }
 
@@ -1122,6 +2136,117 @@ mod tests {
             assert_eq!(user_id_from_str.as_str(), valid_uuid);
         }
 
+        #[test]
+        fn test_validate_password_strength() {
+            // deepcode ignore NoHardcodedCredentials: <test>
+            assert!(Validator::validate_password_strength("Correct7Horse").is_ok());
+
+            // Too short
+            match Validator::validate_password_strength("Ab1") {
+                Err(FiscusError::FieldValidation { field, message, .. }) => {
+                    assert_eq!(field, "password");
+                    assert!(message.contains("at least 8"))
+                }
+                other => panic!("Expected validation error, got {other:?}"),
+            }
+
+            // Missing character classes
+            match Validator::validate_password_strength("lowercase123") {
+                Err(FiscusError::FieldValidation { field, message, .. }) => {
+                    assert_eq!(field, "password");
+                    assert!(message.contains("uppercase"))
+                }
+                other => panic!("Expected validation error, got {other:?}"),
+            }
+            match Validator::validate_password_strength("NoDigitsHere") {
+                Err(FiscusError::FieldValidation { field, message, .. }) => {
+                    assert_eq!(field, "password");
+                    assert!(message.contains("digit"))
+                }
+                other => panic!("Expected validation error, got {other:?}"),
+            }
+
+            // Common password, even with the required classes
+            match Validator::validate_password_strength("Password123") {
+                Err(FiscusError::FieldValidation { field, message, .. }) => {
+                    assert_eq!(field, "password");
+                    assert!(message.contains("too common"))
+                }
+                other => panic!("Expected validation error, got {other:?}"),
+            }
+        }
+
+        #[test]
+        fn test_validate_password_with_custom_policy() {
+            let strict = PasswordPolicy::strict();
+            // deepcode ignore NoHardcodedCredentials: <test>
+            assert!(Validator::validate_password_with_policy("Correct7Horse!", &strict).is_ok());
+            assert!(Validator::validate_password_with_policy("Correct7Horse", &strict).is_err());
+            assert!(Validator::validate_password_with_policy("Sh0rt!", &strict).is_err());
+
+            let relaxed = PasswordPolicy {
+                require_uppercase: false,
+                require_digit: false,
+                reject_common: false,
+                ..PasswordPolicy::default()
+            };
+            assert!(Validator::validate_password_with_policy("password", &relaxed).is_ok());
+        }
+
+        #[test]
+        fn test_strict_setting_rejects_password_the_default_accepts() {
+            // deepcode ignore NoHardcodedCredentials: <test>
+            let password = "Correct7Horse";
+            assert!(Validator::validate_password_with_policy(
+                password,
+                &PasswordPolicy::named("default").unwrap()
+            )
+            .is_ok());
+
+            let strict = PasswordPolicy::named("Strict").unwrap();
+            assert_eq!(strict, PasswordPolicy::strict());
+            assert!(Validator::validate_password_with_policy(password, &strict).is_err());
+
+            assert_eq!(PasswordPolicy::named("lenient"), None);
+        }
+
+        #[test]
+        fn test_registered_custom_currency_validates() {
+            assert!(ValidatedCurrency::new("BTC").is_err());
+
+            assert_eq!(register_custom_currency_code("btc", 8).unwrap(), "BTC");
+
+            assert_eq!(ValidatedCurrency::new("BTC").unwrap().as_str(), "BTC");
+            assert_eq!(ValidatedCurrency::new("btc").unwrap().as_str(), "BTC");
+            assert_eq!(custom_currency_minor_units("BTC"), Some(8));
+
+            // Registering one custom code does not open up others
+            assert!(ValidatedCurrency::new("ZZZ").is_err());
+        }
+
+        #[test]
+        fn test_custom_currency_definition_validation() {
+            assert!(Validator::validate_custom_currency("LOCALX1", 2).is_ok());
+            assert!(Validator::validate_custom_currency("SATS", 0).is_ok());
+
+            // Format: 3-8 uppercase alphanumerics
+            assert!(Validator::validate_custom_currency("AB", 2).is_err());
+            assert!(Validator::validate_custom_currency("TOOLONGXX", 2).is_err());
+            assert!(Validator::validate_custom_currency("BT-C", 2).is_err());
+
+            // Cannot shadow an ISO code
+            assert!(matches!(
+                Validator::validate_custom_currency("usd", 2),
+                Err(FiscusError::FieldValidation { ref code, .. }) if code == "conflict"
+            ));
+
+            assert!(Validator::validate_custom_currency(
+                "DUST",
+                MAX_CUSTOM_CURRENCY_MINOR_UNITS + 1
+            )
+            .is_err());
+        }
+
         #[test]
         fn test_validated_currency() {
             // Valid creation
@@ -1,6 +1,7 @@
 use tauri_plugin_sql::{Migration, MigrationKind};
 
 // Module declarations
+pub mod clock;
 mod commands;
 mod database;
 mod dto;
@@ -35,27 +36,9 @@ pub use logging::{
 pub use models::*;
 pub use utils::*;
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    // Initialize logging system first
-    if let Err(e) = logging::init() {
-        eprintln!("Failed to initialize logging: {e}");
-        // Continue without logging rather than crash
-    }
-
-    tracing::info!("Starting Fiscus application");
-
-    // Initialize encryption service
-    if let Err(e) = commands::encryption::initialize_encryption_service() {
-        tracing::error!("Failed to initialize encryption service: {e}");
-        // Encryption is critical for security - fail fast
-        panic!("Failed to initialize encryption service: {e}");
-    } else {
-        tracing::info!("Encryption service initialized successfully");
-    }
-
-    // Define database migrations for the personal finance application
-    let migrations = vec![
+/// Database migrations for the personal finance application, in version order
+pub(crate) fn migrations() -> Vec<Migration> {
+    vec![
         Migration {
             version: 1,
             description: "create_initial_tables",
@@ -68,7 +51,174 @@ pub fn run() {
             sql: include_str!("../migrations/002_secure_storage.sql"),
             kind: MigrationKind::Up,
         },
-    ];
+        Migration {
+            version: 3,
+            description: "create_idempotency_keys",
+            sql: include_str!("../migrations/003_idempotency_keys.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "create_net_worth_snapshots",
+            sql: include_str!("../migrations/004_net_worth_snapshots.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "allow_voided_transfers",
+            sql: include_str!("../migrations/005_transfer_voiding.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "add_account_opening_balance",
+            sql: include_str!("../migrations/006_account_opening_balance.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_cross_user_transfers",
+            sql: include_str!("../migrations/007_cross_user_transfers.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_transaction_amount_minor_units",
+            sql: include_str!("../migrations/008_amount_minor_units.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_category_tax_deductible",
+            sql: include_str!("../migrations/009_category_tax_deductible.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "create_custom_currencies",
+            sql: include_str!("../migrations/010_custom_currencies.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "create_budget_templates",
+            sql: include_str!("../migrations/011_budget_templates.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "add_transaction_original_currency",
+            sql: include_str!("../migrations/012_transaction_original_currency.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "create_account_groups",
+            sql: include_str!("../migrations/013_account_groups.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add_payee_blind_index",
+            sql: include_str!("../migrations/014_payee_blind_index.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "create_account_accruals",
+            sql: include_str!("../migrations/015_account_accruals.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "create_wrapped_user_keys",
+            sql: include_str!("../migrations/016_wrapped_user_keys.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_spending_limits",
+            sql: include_str!("../migrations/017_spending_limits.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add_transaction_refunds",
+            sql: include_str!("../migrations/018_transaction_refunds.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "add_transaction_content_hash",
+            sql: include_str!("../migrations/019_transaction_content_hash.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "add_goal_milestones",
+            sql: include_str!("../migrations/020_goal_milestones.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "create_categorization_rules",
+            sql: include_str!("../migrations/021_categorization_rules.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 22,
+            description: "add_account_closing",
+            sql: include_str!("../migrations/022_account_closing.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 23,
+            description: "add_account_defaults",
+            sql: include_str!("../migrations/023_account_defaults.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 24,
+            description: "add_wrapped_key_metadata",
+            sql: include_str!("../migrations/024_wrapped_key_metadata.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 25,
+            description: "add_key_wrapping_kdf",
+            sql: include_str!("../migrations/025_key_wrapping_kdf.sql"),
+            kind: MigrationKind::Up,
+        },
+    ]
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    // Initialize logging system first
+    if let Err(e) = logging::init() {
+        eprintln!("Failed to initialize logging: {e}");
+        // Continue without logging rather than crash
+    }
+
+    tracing::info!("Starting Fiscus application");
+
+    // Initialize encryption service; without it the app runs in degraded mode
+    // where only commands that never touch encrypted data succeed
+    let encryption_state = commands::encryption::EncryptionState::initialize();
+    match encryption_state.status() {
+        EncryptionStatus::Available => {
+            tracing::info!("Encryption service initialized successfully")
+        }
+        EncryptionStatus::Unavailable(reason) => {
+            tracing::error!("Encryption service unavailable, starting in degraded mode: {reason}")
+        }
+    }
+
+    // Read the field encryption policy before any command can write, so a
+    // malformed FISCUS_PLAINTEXT_FIELDS is reported at launch
+    encrypted::EncryptionPolicy::current();
+
+    let migrations = migrations();
 
     tracing::info!(
         "Configuring Tauri application with {} migrations",
@@ -76,34 +226,69 @@ pub fn run() {
     );
 
     tauri::Builder::default()
+        .manage(encryption_state)
         .plugin(tauri_plugin_opener::init())
         .plugin(
             tauri_plugin_sql::Builder::default()
                 .add_migrations("sqlite:fiscus.db", migrations)
                 .build(),
         )
+        .setup(|app| {
+            // Push transaction change events to the frontend instead of making it poll
+            services::events::forward_to_frontend(app.handle().clone());
+            // Purge expired secure storage entries on the configured interval
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = commands::secure_storage::start_secure_storage_service().await {
+                    tracing::error!("Failed to start secure storage service: {e}");
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Authentication commands
             commands::create_user,
             commands::login_user,
             commands::change_password,
+            commands::reauthenticate,
             commands::get_current_user,
             // Account commands
             commands::create_account,
+            commands::clone_account,
             commands::get_accounts,
             commands::get_account_by_id,
             commands::update_account,
             commands::delete_account,
             commands::get_account_summary,
+            commands::set_opening_balance,
+            commands::audit_account_balance,
+            commands::repair_account_balance,
+            commands::create_account_group,
+            commands::assign_account_to_group,
+            commands::get_accounts_grouped,
+            commands::create_account_accrual,
+            commands::apply_accruals,
+            commands::set_account_spending_limit,
+            commands::project_payoff,
+            commands::close_account,
             // Transaction commands
             commands::create_transaction,
+            commands::create_transactions_batch,
+            commands::create_refund,
+            commands::backfill_payee_indexes,
+            commands::import_transactions,
+            commands::import_transactions_json,
             commands::get_transactions,
+            commands::get_transactions_by_cursor,
             commands::get_transactions_paginated,
+            commands::get_user_tags,
+            commands::find_duplicate_transactions,
             commands::get_transaction_by_id,
             commands::update_transaction,
             commands::delete_transaction,
             commands::create_transfer,
+            commands::create_cross_user_transfer,
             commands::get_transfer_by_id,
+            commands::void_transfer,
             commands::get_transaction_summary,
             commands::get_transaction_stats,
             commands::bulk_transaction_operations,
@@ -114,16 +299,29 @@ pub fn run() {
             commands::update_category,
             commands::delete_category,
             commands::get_category_hierarchy,
+            commands::merge_categories,
+            commands::set_category_spending_limit,
+            commands::create_categorization_rule,
+            commands::apply_categorization_rules,
+            // Currency commands
+            commands::register_custom_currency,
+            commands::get_custom_currencies,
+            commands::set_exchange_rate,
             // Budget commands
             commands::create_budget_period,
             commands::get_budget_periods,
             commands::get_budget_period_by_id,
+            commands::get_current_budget_period,
             commands::create_budget,
             commands::get_budgets,
             commands::get_budget_by_id,
             commands::update_budget,
             commands::delete_budget,
+            commands::scale_budget_period,
             commands::get_budget_summary,
+            commands::get_budget_vs_actual,
+            commands::create_budget_template,
+            commands::instantiate_budget_from_template,
             // Goal commands
             commands::create_goal,
             commands::get_goals,
@@ -132,26 +330,57 @@ pub fn run() {
             commands::delete_goal,
             commands::update_goal_progress,
             commands::get_goal_progress_summary,
+            commands::project_goal_completion,
             // Report commands
             commands::get_financial_overview,
             commands::get_spending_by_category,
+            commands::get_spending_by_payee,
+            commands::get_deductible_summary,
             commands::get_monthly_spending_trend,
+            commands::get_savings_rate_trend,
             commands::get_account_balance_history,
+            commands::project_cash_flow,
             commands::get_budget_performance,
             commands::get_net_worth_progression,
+            commands::capture_net_worth_snapshot,
+            commands::get_net_worth_history,
+            commands::format_amount,
+            commands::generate_spending_digest,
+            // Export commands
+            commands::export_user_archive,
+            commands::export_signed_archive,
+            commands::verify_archive_signature,
+            commands::export_transactions_streaming,
             // Encryption commands
             commands::encrypt_financial_data,
             commands::decrypt_financial_data,
             commands::generate_encryption_key,
             commands::rotate_user_keys,
+            commands::list_user_keys,
+            commands::get_key_lineage,
+            commands::revoke_key,
+            commands::migrate_data_type_algorithm,
             commands::get_encryption_stats,
+            commands::get_encryption_performance,
+            commands::verify_user_data_integrity,
+            commands::recover_readable_fields,
             commands::derive_key_from_password,
+            commands::retry_encryption_initialization,
+            commands::rekey_after_password_change,
+            commands::run_encryption_self_test,
+            commands::get_supported_algorithms,
             // Secure storage commands
             commands::secure_store,
             commands::secure_retrieve,
             commands::secure_delete,
             commands::secure_cleanup_expired,
             commands::secure_get_statistics,
+            // API schema commands
+            commands::get_api_schemas,
+            // Health commands
+            commands::get_system_health,
+            // Diagnostics commands
+            commands::find_data_inconsistencies,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
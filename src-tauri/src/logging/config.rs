@@ -26,6 +26,8 @@ pub struct LoggingConfig {
     pub environment: Environment,
     /// Fields to sanitize in logs
     pub sensitive_fields: Vec<String>,
+    /// Whether request/response payloads pass through the sanitizer
+    pub sanitize_payloads: bool,
 }
 
 /// Log output format
@@ -43,10 +45,23 @@ pub enum LogFormat {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Environment {
     Development,
+    Staging,
     Production,
     Test,
 }
 
+impl Environment {
+    /// Whether this environment serves real user data
+    pub fn is_deployed(&self) -> bool {
+        matches!(self, Environment::Production | Environment::Staging)
+    }
+
+    /// Default for `LoggingConfig::sanitize_payloads`; only development logs raw payloads
+    pub fn default_sanitize_payloads(&self) -> bool {
+        !matches!(self, Environment::Development)
+    }
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
@@ -75,6 +90,7 @@ impl Default for LoggingConfig {
                 "session_token".to_string(),
                 "api_key".to_string(),
             ],
+            sanitize_payloads: Environment::Development.default_sanitize_payloads(),
         }
     }
 }
@@ -110,11 +126,18 @@ impl LoggingConfig {
         if let Ok(env_str) = env::var("FISCUS_ENV") {
             config.environment = match env_str.to_lowercase().as_str() {
                 "production" | "prod" => Environment::Production,
+                "staging" | "stage" => Environment::Staging,
                 "test" => Environment::Test,
                 _ => Environment::Development,
             };
         }
 
+        // Payload sanitization follows the environment unless explicitly overridden
+        config.sanitize_payloads = config.environment.default_sanitize_payloads();
+        if let Ok(sanitize_str) = env::var("FISCUS_LOG_SANITIZE") {
+            config.sanitize_payloads = sanitize_str.to_lowercase() == "true";
+        }
+
         // Enable/disable console logging
         if let Ok(console_str) = env::var("FISCUS_LOG_CONSOLE") {
             config.console_enabled = console_str.to_lowercase() == "true";
@@ -150,6 +173,14 @@ impl LoggingConfig {
         config
     }
 
+    /// Whether request/response payloads must be sanitized before logging.
+    ///
+    /// Deployed environments and release builds always sanitize, even if
+    /// `sanitize_payloads` was switched off.
+    pub fn payload_sanitization_enabled(&self) -> bool {
+        self.sanitize_payloads || self.environment.is_deployed() || !cfg!(debug_assertions)
+    }
+
     /// Get the environment filter string
     pub fn env_filter(&self) -> String {
         let base_level = match self.level {
@@ -177,6 +208,9 @@ pub fn init_logging() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 pub fn init_logging_with_config(
     config: LoggingConfig,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before anything can fail, so deployed builds never fall back to verbose errors
+    crate::error::set_error_environment(config.environment.clone());
+
     let env_filter =
         EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new(config.env_filter()))?;
 
@@ -245,6 +279,31 @@ mod tests {
         env::remove_var("FISCUS_ENV");
     }
 
+    #[test]
+    fn test_sanitize_payloads_defaults_per_environment() {
+        assert!(!Environment::Development.default_sanitize_payloads());
+        assert!(Environment::Staging.default_sanitize_payloads());
+        assert!(Environment::Production.default_sanitize_payloads());
+        assert!(Environment::Test.default_sanitize_payloads());
+    }
+
+    #[test]
+    fn test_production_cannot_disable_sanitization() {
+        let config = LoggingConfig {
+            environment: Environment::Production,
+            sanitize_payloads: false,
+            ..Default::default()
+        };
+        assert!(config.payload_sanitization_enabled());
+
+        let config = LoggingConfig {
+            environment: Environment::Staging,
+            sanitize_payloads: false,
+            ..Default::default()
+        };
+        assert!(config.payload_sanitization_enabled());
+    }
+
     #[test]
     fn test_env_filter() {
         let config = LoggingConfig {
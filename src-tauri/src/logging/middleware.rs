@@ -1,4 +1,5 @@
 use crate::error::FiscusError;
+use crate::logging::config::{get_config, LoggingConfig};
 use crate::logging::performance::get_performance_monitor;
 use crate::logging::sanitizer::DataSanitizer;
 use serde_json::Value;
@@ -7,6 +8,25 @@ use std::time::{Duration, Instant};
 use tracing::{error, info, warn, Instrument};
 use uuid::Uuid;
 
+tokio::task_local! {
+    /// Request id of the command whose future is being polled
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// Request id of the command currently executing, if any
+///
+/// Set for the whole of a command run through the logging wrappers, including
+/// the database and encryption work it awaits, so errors and queries logged
+/// deep in a call stack can be tied back to the command that caused them.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `future` with `request_id` as the current request id
+pub async fn scope_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    CURRENT_REQUEST_ID.scope(request_id, future).await
+}
+
 /// Request context for logging
 #[derive(Debug, Clone)]
 pub struct RequestContext {
@@ -31,11 +51,22 @@ impl RequestContext {
     pub fn elapsed(&self) -> Duration {
         self.start_time.elapsed()
     }
+
+    /// Span for the command; spans entered while it runs are nested under it
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "tauri_command",
+            command = %self.command_name,
+            request_id = %self.request_id,
+            user_id = self.user_id.as_deref()
+        )
+    }
 }
 
 /// Logging middleware for Tauri commands
 pub struct LoggingMiddleware {
     sanitizer: DataSanitizer,
+    sanitize_payloads: bool,
     performance_threshold_ms: u64,
 }
 
@@ -47,16 +78,40 @@ impl Default for LoggingMiddleware {
 
 impl LoggingMiddleware {
     pub fn new() -> Self {
+        Self::from_config(&get_config())
+    }
+
+    pub fn with_threshold(threshold_ms: u64) -> Self {
         Self {
-            sanitizer: DataSanitizer::new(),
-            performance_threshold_ms: 1000, // Default to 1000ms
+            performance_threshold_ms: threshold_ms,
+            ..Self::new()
         }
     }
 
-    pub fn with_threshold(threshold_ms: u64) -> Self {
+    /// Start a request for `command_name`, generating its request id
+    pub fn start_request(&self, command_name: &str, user_id: Option<String>) -> RequestContext {
+        RequestContext::new(command_name, user_id)
+    }
+
+    /// Create middleware honouring the configuration's payload sanitization setting
+    pub fn from_config(config: &LoggingConfig) -> Self {
         Self {
             sanitizer: DataSanitizer::new(),
-            performance_threshold_ms: threshold_ms,
+            sanitize_payloads: config.payload_sanitization_enabled(),
+            performance_threshold_ms: 1000, // Default to 1000ms
+        }
+    }
+
+    /// Convert a request or response payload into the value that gets logged
+    pub fn prepare_payload<T>(&self, payload: &T) -> Value
+    where
+        T: serde::Serialize,
+    {
+        if self.sanitize_payloads {
+            self.sanitizer.sanitize_serializable(payload)
+        } else {
+            serde_json::to_value(payload)
+                .unwrap_or_else(|_| Value::String("[SERIALIZATION_ERROR]".to_string()))
         }
     }
 
@@ -65,7 +120,7 @@ impl LoggingMiddleware {
     where
         T: serde::Serialize,
     {
-        let sanitized_params = self.sanitizer.sanitize_serializable(params);
+        let sanitized_params = self.prepare_payload(params);
 
         info!(
             request_id = %ctx.request_id,
@@ -82,7 +137,7 @@ impl LoggingMiddleware {
     where
         T: serde::Serialize,
     {
-        let sanitized_response = self.sanitizer.sanitize_serializable(response);
+        let sanitized_response = self.prepare_payload(response);
         let duration = ctx.elapsed();
 
         // Record performance metrics
@@ -139,20 +194,14 @@ impl LoggingMiddleware {
 #[macro_export]
 macro_rules! logged_command {
     ($command_name:expr, $user_id:expr, $params:expr, $handler:expr) => {{
-        use $crate::logging::middleware::{LoggingMiddleware, RequestContext};
+        use $crate::logging::middleware::LoggingMiddleware;
 
         let middleware = LoggingMiddleware::new();
-        let ctx = RequestContext::new($command_name, $user_id);
+        let ctx = middleware.start_request($command_name, $user_id);
+        let request_id = ctx.request_id.clone();
+        let span = ctx.span();
 
-        // Create a span for this command
-        let span = tracing::info_span!(
-            "tauri_command",
-            command = $command_name,
-            request_id = %ctx.request_id,
-            user_id = ctx.user_id.as_deref()
-        );
-
-        async move {
+        let command = async move {
             // Log the request
             middleware.log_request(&ctx, &$params);
 
@@ -172,7 +221,9 @@ macro_rules! logged_command {
 
             result
         }
-        .instrument(span)
+        .instrument(span);
+
+        $crate::logging::middleware::scope_request_id(request_id, command)
     }};
 }
 
@@ -195,16 +246,11 @@ where
 {
     let middleware = LoggingMiddleware::new();
     let user_id = params.extract_user_id();
-    let ctx = RequestContext::new(command_name, user_id);
+    let ctx = middleware.start_request(command_name, user_id);
+    let request_id = ctx.request_id.clone();
+    let span = ctx.span();
 
-    let span = tracing::info_span!(
-        "tauri_command",
-        command = command_name,
-        request_id = %ctx.request_id,
-        user_id = ctx.user_id.as_deref()
-    );
-
-    async move {
+    let command = async move {
         middleware.log_request(&ctx, &params);
 
         let result = handler(params).await;
@@ -221,8 +267,9 @@ where
 
         result
     }
-    .instrument(span)
-    .await
+    .instrument(span);
+
+    scope_request_id(request_id, command).await
 }
 
 /// Simple wrapper for commands without complex parameter extraction
@@ -237,16 +284,11 @@ where
     Fut: Future<Output = Result<R, FiscusError>>,
 {
     let middleware = LoggingMiddleware::new();
-    let ctx = RequestContext::new(command_name, user_id);
+    let ctx = middleware.start_request(command_name, user_id);
+    let request_id = ctx.request_id.clone();
+    let span = ctx.span();
 
-    let span = tracing::info_span!(
-        "tauri_command",
-        command = command_name,
-        request_id = %ctx.request_id,
-        user_id = ctx.user_id.as_deref()
-    );
-
-    async move {
+    let command = async move {
         info!(
             request_id = %ctx.request_id,
             command = %ctx.command_name,
@@ -268,8 +310,9 @@ where
 
         result
     }
-    .instrument(span)
-    .await
+    .instrument(span);
+
+    scope_request_id(request_id, command).await
 }
 
 /// Database operation logging helper
@@ -294,6 +337,7 @@ impl DatabaseLogger {
         monitor.record_database_query(duration, true, is_slow);
 
         info!(
+            request_id = current_request_id().as_deref(),
             query = query,
             params = ?sanitized_params,
             duration_ms = duration.as_millis(),
@@ -311,6 +355,7 @@ impl DatabaseLogger {
         monitor.record_database_query(Duration::ZERO, false, false);
 
         error!(
+            request_id = current_request_id().as_deref(),
             query = query,
             params = ?sanitized_params,
             error = %sanitized_error,
@@ -319,7 +364,10 @@ impl DatabaseLogger {
     }
 
     pub fn log_transaction_start(&self) {
-        info!("Database transaction started");
+        info!(
+            request_id = current_request_id().as_deref(),
+            "Database transaction started"
+        );
     }
 
     pub fn log_transaction_commit(&self, duration: Duration) {
@@ -328,6 +376,7 @@ impl DatabaseLogger {
         monitor.record_transaction(true);
 
         info!(
+            request_id = current_request_id().as_deref(),
             duration_ms = duration.as_millis(),
             "Database transaction committed"
         );
@@ -338,7 +387,11 @@ impl DatabaseLogger {
         let monitor = get_performance_monitor();
         monitor.record_transaction(false);
 
-        warn!(reason = reason, "Database transaction rolled back");
+        warn!(
+            request_id = current_request_id().as_deref(),
+            reason = reason,
+            "Database transaction rolled back"
+        );
     }
 }
 
@@ -352,6 +405,7 @@ impl Default for DatabaseLogger {
 mod tests {
     use super::*;
     use crate::error::FiscusError;
+    use crate::logging::config::Environment;
     use serde::{Deserialize, Serialize};
 
     #[derive(Serialize, Deserialize)]
@@ -409,6 +463,86 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Log output captured from a test subscriber
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLogs {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    impl CapturedLogs {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_commands_get_distinct_request_ids() {
+        let command = |name: &'static str| {
+            with_simple_logging(name, None, || async {
+                tokio::task::yield_now().await;
+                Ok(current_request_id())
+            })
+        };
+
+        let (first, second) = tokio::join!(command("first_command"), command("second_command"));
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert!(first.is_some());
+        assert!(second.is_some());
+        assert_ne!(first, second);
+        assert!(current_request_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_error_logs_originating_request_id() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let request_id = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let seen = request_id.clone();
+        let result: Result<(), FiscusError> =
+            with_simple_logging("failing_command", None, || async move {
+                *seen.lock().unwrap() = current_request_id();
+                let error = FiscusError::Database("connection lost".to_string());
+                error.log_error(Some("nested query"));
+                Err(error)
+            })
+            .await;
+
+        assert!(result.is_err());
+        let request_id = request_id.lock().unwrap().clone().unwrap();
+        let output = logs.contents();
+        let error_line = output
+            .lines()
+            .find(|line| line.contains("Database error occurred"))
+            .expect("log_error output");
+        assert!(error_line.contains(&format!("request_id=\"{request_id}\"")));
+        assert!(output
+            .lines()
+            .any(|line| line.contains("Command failed with error") && line.contains(&request_id)));
+    }
+
     #[test]
     fn test_configurable_threshold() {
         // Test default threshold
@@ -424,4 +558,46 @@ mod tests {
         middleware_default.log_performance_warning(&ctx);
         middleware_custom.log_performance_warning(&ctx);
     }
+
+    #[test]
+    fn test_production_config_masks_payload_password() {
+        let config = LoggingConfig {
+            environment: Environment::Production,
+            sanitize_payloads: Environment::Production.default_sanitize_payloads(),
+            ..Default::default()
+        };
+        let middleware = LoggingMiddleware::from_config(&config);
+        let params = TestParams {
+            user_id: "user123".to_string(),
+            password: "secret".to_string(),
+            amount: 100.0,
+        };
+
+        let logged = middleware.prepare_payload(&params);
+        assert_eq!(logged["password"], "[REDACTED]");
+        assert_eq!(logged["user_id"], "user123");
+    }
+
+    #[test]
+    fn test_development_config_leaves_payload_raw() {
+        let config = LoggingConfig {
+            environment: Environment::Development,
+            sanitize_payloads: Environment::Development.default_sanitize_payloads(),
+            ..Default::default()
+        };
+        let middleware = LoggingMiddleware::from_config(&config);
+        let params = TestParams {
+            user_id: "user123".to_string(),
+            password: "secret".to_string(),
+            amount: 100.0,
+        };
+
+        let logged = middleware.prepare_payload(&params);
+        // Raw payloads are only ever available in debug builds
+        if cfg!(debug_assertions) {
+            assert_eq!(logged["password"], "secret");
+        } else {
+            assert_eq!(logged["password"], "[REDACTED]");
+        }
+    }
 }
@@ -14,6 +14,7 @@ pub struct PerformanceMonitor {
 struct PerformanceMetrics {
     command_metrics: HashMap<String, CommandMetrics>,
     database_metrics: DatabaseMetrics,
+    encryption_metrics: HashMap<EncryptionOperation, EncryptionMetrics>,
     system_metrics: SystemMetrics,
 }
 
@@ -39,6 +40,31 @@ struct DatabaseMetrics {
     transaction_rollbacks: u64,
 }
 
+/// Kind of timed encryption service operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EncryptionOperation {
+    Encrypt,
+    Decrypt,
+    KeyDerivation,
+}
+
+impl EncryptionOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EncryptionOperation::Encrypt => "encrypt",
+            EncryptionOperation::Decrypt => "decrypt",
+            EncryptionOperation::KeyDerivation => "key_derivation",
+        }
+    }
+}
+
+/// Timings for one kind of encryption operation
+#[derive(Debug, Default)]
+struct EncryptionMetrics {
+    count: u64,
+    total_duration: Duration,
+}
+
 /// System-level metrics
 #[derive(Debug, Default)]
 struct SystemMetrics {
@@ -127,6 +153,20 @@ impl PerformanceMonitor {
         }
     }
 
+    /// Record `count` encryption operations of one kind taking `duration` in total
+    pub fn record_encryption_operations(
+        &self,
+        operation: EncryptionOperation,
+        count: u64,
+        duration: Duration,
+    ) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            let encryption_metrics = metrics.encryption_metrics.entry(operation).or_default();
+            encryption_metrics.count += count;
+            encryption_metrics.total_duration += duration;
+        }
+    }
+
     /// Record system-level metrics
     pub fn record_request_start(&self) {
         if let Ok(mut metrics) = self.metrics.lock() {
@@ -157,13 +197,9 @@ impl PerformanceMonitor {
             let mut command_summaries = Vec::new();
 
             for (name, cmd_metrics) in &metrics.command_metrics {
-                let avg_duration = if cmd_metrics.total_calls > 0 {
-                    Duration::from_nanos(
-                        cmd_metrics.total_duration.as_nanos() as u64 / cmd_metrics.total_calls,
-                    )
-                } else {
-                    Duration::ZERO
-                };
+                let avg_duration = (cmd_metrics.total_duration.as_nanos() as u64)
+                    .checked_div(cmd_metrics.total_calls)
+                    .map_or(Duration::ZERO, Duration::from_nanos);
 
                 command_summaries.push(CommandSummary {
                     name: name.clone(),
@@ -184,14 +220,23 @@ impl PerformanceMonitor {
                 });
             }
 
-            let db_avg_query_time = if metrics.database_metrics.total_queries > 0 {
-                Duration::from_nanos(
-                    metrics.database_metrics.total_query_time.as_nanos() as u64
-                        / metrics.database_metrics.total_queries,
-                )
-            } else {
-                Duration::ZERO
-            };
+            let db_avg_query_time = (metrics.database_metrics.total_query_time.as_nanos() as u64)
+                .checked_div(metrics.database_metrics.total_queries)
+                .map_or(Duration::ZERO, Duration::from_nanos);
+
+            let mut encryption_summaries: Vec<EncryptionOperationSummary> = metrics
+                .encryption_metrics
+                .iter()
+                .map(|(operation, op_metrics)| EncryptionOperationSummary {
+                    operation: *operation,
+                    count: op_metrics.count,
+                    total_duration: op_metrics.total_duration,
+                    average_duration: (op_metrics.total_duration.as_nanos() as u64)
+                        .checked_div(op_metrics.count)
+                        .map_or(Duration::ZERO, Duration::from_nanos),
+                })
+                .collect();
+            encryption_summaries.sort_by_key(|summary| summary.operation);
 
             PerformanceSummary {
                 commands: command_summaries,
@@ -221,6 +266,7 @@ impl PerformanceMonitor {
                         0.0
                     },
                 },
+                encryption: encryption_summaries,
                 system: SystemSummary {
                     uptime: metrics
                         .system_metrics
@@ -264,6 +310,15 @@ impl PerformanceMonitor {
                 "Command performance summary"
             );
         }
+
+        for op in &summary.encryption {
+            info!(
+                operation = op.operation.as_str(),
+                count = op.count,
+                avg_duration_us = op.average_duration.as_micros(),
+                "Encryption performance summary"
+            );
+        }
     }
 }
 
@@ -272,6 +327,8 @@ impl PerformanceMonitor {
 pub struct PerformanceSummary {
     pub commands: Vec<CommandSummary>,
     pub database: DatabaseSummary,
+    /// Ordered by operation
+    pub encryption: Vec<EncryptionOperationSummary>,
     pub system: SystemSummary,
 }
 
@@ -298,6 +355,15 @@ pub struct DatabaseSummary {
     pub rollback_rate: f64,
 }
 
+/// Encryption operation performance summary
+#[derive(Debug)]
+pub struct EncryptionOperationSummary {
+    pub operation: EncryptionOperation,
+    pub count: u64,
+    pub total_duration: Duration,
+    pub average_duration: Duration,
+}
+
 /// System performance summary
 #[derive(Debug, Default)]
 pub struct SystemSummary {
@@ -360,4 +426,38 @@ mod tests {
         assert!((summary.database.error_rate - 33.333333333333336).abs() < 0.001);
         // 1/3
     }
+
+    #[test]
+    fn test_encryption_metrics() {
+        let monitor = PerformanceMonitor::new();
+
+        monitor.record_encryption_operations(
+            EncryptionOperation::Decrypt,
+            1,
+            Duration::from_micros(30),
+        );
+        monitor.record_encryption_operations(
+            EncryptionOperation::Encrypt,
+            1,
+            Duration::from_micros(10),
+        );
+        monitor.record_encryption_operations(
+            EncryptionOperation::Encrypt,
+            3,
+            Duration::from_micros(50),
+        );
+
+        let summary = monitor.get_summary();
+        assert_eq!(summary.encryption.len(), 2);
+
+        let encrypt = &summary.encryption[0];
+        assert_eq!(encrypt.operation, EncryptionOperation::Encrypt);
+        assert_eq!(encrypt.count, 4);
+        assert_eq!(encrypt.total_duration, Duration::from_micros(60));
+        assert_eq!(encrypt.average_duration, Duration::from_micros(15));
+        assert_eq!(
+            summary.encryption[1].operation,
+            EncryptionOperation::Decrypt
+        );
+    }
 }
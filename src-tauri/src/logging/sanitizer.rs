@@ -1,7 +1,15 @@
+use once_cell::sync::Lazy;
 use regex::Regex;
 use serde_json::{Map, Value};
 use std::collections::HashSet;
 
+/// UUIDs are identifiers, not sensitive data, and must survive sanitization
+/// even though their digit runs can look like account numbers
+static UUID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b")
+        .expect("Failed to compile UUID regex")
+});
+
 /// Data sanitizer for removing sensitive information from logs
 #[derive(Debug, Clone)]
 pub struct DataSanitizer {
@@ -16,7 +24,6 @@ pub struct DataSanitizer {
 /// Pattern for detecting sensitive data
 #[derive(Debug, Clone)]
 struct SensitivePattern {
-    #[allow(dead_code)]
     name: String,
     regex: Regex,
     replacement: String,
@@ -85,6 +92,12 @@ impl DataSanitizer {
     /// Add regex patterns for detecting sensitive data
     fn add_patterns(&mut self) {
         let patterns = vec![
+            // Currency amounts with a leading symbol (e.g. $12,345.67)
+            (
+                "currency_amount",
+                r"[$€£¥]\s?\d[\d,]*(?:\.\d+)?",
+                "[AMOUNT-***]",
+            ),
             // Credit card numbers (basic pattern)
             ("credit_card", r"\b(?:\d{4}[-\s]?){3}\d{4}\b", "[CARD-****]"),
             // SSN pattern
@@ -114,14 +127,42 @@ impl DataSanitizer {
         ];
 
         for (name, pattern, replacement) in patterns {
-            if let Ok(regex) = Regex::new(pattern) {
-                self.patterns.push(SensitivePattern {
-                    name: name.to_string(),
-                    regex,
-                    replacement: replacement.to_string(),
-                });
-            }
+            let _ = self.add_rule(name, pattern, replacement);
+        }
+    }
+
+    /// Add a named regex rule; an existing rule with the same name is replaced
+    pub fn add_rule(
+        &mut self,
+        name: &str,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<(), regex::Error> {
+        let regex = Regex::new(pattern)?;
+        let rule = SensitivePattern {
+            name: name.to_string(),
+            regex,
+            replacement: replacement.to_string(),
+        };
+
+        match self.patterns.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = rule,
+            None => self.patterns.push(rule),
         }
+
+        Ok(())
+    }
+
+    /// Remove a named regex rule, returning whether it existed
+    pub fn remove_rule(&mut self, name: &str) -> bool {
+        let initial_len = self.patterns.len();
+        self.patterns.retain(|p| p.name != name);
+        self.patterns.len() != initial_len
+    }
+
+    /// Check if a named regex rule is registered
+    pub fn has_rule(&self, name: &str) -> bool {
+        self.patterns.iter().any(|p| p.name == name)
     }
 
     /// Sanitize a JSON value
@@ -147,9 +188,24 @@ impl DataSanitizer {
 
     /// Sanitize a string value
     pub fn sanitize_string(&self, input: &str) -> String {
-        let mut result = input.to_string();
+        // Sanitize the text between UUIDs so identifiers are preserved
+        let mut result = String::with_capacity(input.len());
+        let mut last_end = 0;
+
+        for uuid in UUID_REGEX.find_iter(input) {
+            result.push_str(&self.apply_patterns(&input[last_end..uuid.start()]));
+            result.push_str(uuid.as_str());
+            last_end = uuid.end();
+        }
+        result.push_str(&self.apply_patterns(&input[last_end..]));
+
+        result
+    }
+
+    /// Apply all regex patterns to a string segment
+    fn apply_patterns(&self, segment: &str) -> String {
+        let mut result = segment.to_string();
 
-        // Apply regex patterns
         for pattern in &self.patterns {
             result = pattern
                 .regex
@@ -160,6 +216,23 @@ impl DataSanitizer {
         result
     }
 
+    /// Sanitize a JSON value and additionally redact the given top-level fields
+    pub fn redact_fields(&self, value: &Value, fields: &[&str]) -> Value {
+        let mut sanitized = self.sanitize_json(value);
+
+        if let Value::Object(map) = &mut sanitized {
+            for field in fields {
+                if let Some(entry) = map.get_mut(*field) {
+                    if !entry.is_null() {
+                        *entry = Value::String(self.replacement.clone());
+                    }
+                }
+            }
+        }
+
+        sanitized
+    }
+
     /// Sanitize a serde_json::Value that might contain sensitive data
     pub fn sanitize_value(&self, value: &Value) -> Value {
         self.sanitize_json(value)
@@ -326,6 +399,51 @@ mod tests {
         assert!(!sanitized.contains("555-123-4567"));
     }
 
+    #[test]
+    fn test_sanitize_account_numbers_and_amounts() {
+        let sanitizer = DataSanitizer::new();
+        let text = "Transaction 550e8400-e29b-41d4-a716-446655440000 on account 4532123456789012 for $12,345.67";
+
+        let sanitized = sanitizer.sanitize_string(text);
+
+        assert!(sanitized.contains("550e8400-e29b-41d4-a716-446655440000"));
+        assert!(!sanitized.contains("4532123456789012"));
+        assert!(!sanitized.contains("$12,345.67"));
+        assert!(sanitized.contains("[AMOUNT-***]"));
+    }
+
+    #[test]
+    fn test_add_and_remove_rule() {
+        let mut sanitizer = DataSanitizer::new();
+        sanitizer
+            .add_rule("iban", r"\bDE\d{20}\b", "[IBAN-***]")
+            .unwrap();
+        assert!(sanitizer.has_rule("iban"));
+
+        let sanitized = sanitizer.sanitize_string("IBAN DE89370400440532013000");
+        assert_eq!(sanitized, "IBAN [IBAN-***]");
+
+        assert!(sanitizer.remove_rule("iban"));
+        assert!(!sanitizer.has_rule("iban"));
+        assert!(sanitizer.add_rule("broken", r"(", "x").is_err());
+    }
+
+    #[test]
+    fn test_redact_fields() {
+        let sanitizer = DataSanitizer::new();
+        let data = json!({
+            "id": "550e8400-e29b-41d4-a716-446655440000",
+            "amount": 125.5,
+            "notes": null
+        });
+
+        let sanitized = sanitizer.redact_fields(&data, &["amount", "notes"]);
+
+        assert_eq!(sanitized["id"], "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(sanitized["amount"], "[REDACTED]");
+        assert!(sanitized["notes"].is_null());
+    }
+
     #[test]
     fn test_partial_sanitizer() {
         let sanitizer = DataSanitizer::partial_sanitizer(&["password"]);
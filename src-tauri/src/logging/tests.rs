@@ -295,6 +295,13 @@ mod logging_tests {
             message: String,
         }
 
+        let params = TestParams {
+            user_id: "user123".to_string(),
+            sensitive_data: "secret".to_string(),
+        };
+        assert_eq!(params.extract_user_id(), Some("user123".to_string()));
+        assert_eq!(params.sensitive_data, "secret");
+
         // Test successful command
         let result = with_simple_logging(
             "test_integration_command",
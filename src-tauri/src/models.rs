@@ -1,5 +1,6 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -43,6 +44,40 @@ pub struct AccountType {
     pub created_at: DateTime<Utc>,
 }
 
+/// Built-in account types whose balances are owed rather than owned
+pub const LIABILITY_ACCOUNT_TYPES: &[&str] = &["credit_card", "loan", "other_liability"];
+
+impl AccountType {
+    /// Whether balances of this account type count against net worth
+    pub fn is_liability(&self) -> bool {
+        !self.is_asset
+    }
+
+    /// Classify an account type id when the account_types row isn't available
+    pub fn is_liability_type_id(account_type_id: &str) -> bool {
+        LIABILITY_ACCOUNT_TYPES.contains(&account_type_id)
+    }
+}
+
+/// Lifecycle status of an account
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    /// Closed with a reconciled final balance; accepts no new transactions
+    Closed,
+}
+
+impl std::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountStatus::Active => write!(f, "active"),
+            AccountStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
 /// Account entity
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
@@ -51,9 +86,29 @@ pub struct Account {
     pub account_type_id: String,
     pub name: String,
     pub balance: Decimal,
+    /// Balance the account held before the first tracked transaction
+    #[serde(default)]
+    pub opening_balance: Decimal,
+    /// Date the opening balance applies from
+    #[serde(default)]
+    pub opening_balance_date: Option<NaiveDate>,
     pub currency: String,
     pub account_number: Option<String>,
     pub is_active: bool,
+    /// Group the account is organised under, if any
+    #[serde(default)]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub status: AccountStatus,
+    /// Date the account was closed, set together with `AccountStatus::Closed`
+    #[serde(default)]
+    pub closing_date: Option<NaiveDate>,
+    /// Category new transactions on the account get when they name none
+    #[serde(default)]
+    pub default_category_id: Option<String>,
+    /// Payee new transactions on the account get when they name none
+    #[serde(default)]
+    pub default_payee: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -70,6 +125,165 @@ impl Entity for Account {
     }
 }
 
+/// Kind of periodic amount an accrual posts to its account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccrualKind {
+    /// A percentage of the account balance
+    Interest,
+    /// A flat amount charged each period
+    Fee,
+}
+
+impl std::fmt::Display for AccrualKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccrualKind::Interest => write!(f, "interest"),
+            AccrualKind::Fee => write!(f, "fee"),
+        }
+    }
+}
+
+/// How often an accrual falls due
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccrualFrequency {
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl AccrualFrequency {
+    /// Length of one period in months
+    pub fn months(&self) -> u32 {
+        match self {
+            AccrualFrequency::Monthly => 1,
+            AccrualFrequency::Quarterly => 3,
+            AccrualFrequency::Yearly => 12,
+        }
+    }
+
+    /// Number of periods in a year, used to split an annual interest rate
+    pub fn periods_per_year(&self) -> u32 {
+        12 / self.months()
+    }
+}
+
+impl std::fmt::Display for AccrualFrequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccrualFrequency::Monthly => write!(f, "monthly"),
+            AccrualFrequency::Quarterly => write!(f, "quarterly"),
+            AccrualFrequency::Yearly => write!(f, "yearly"),
+        }
+    }
+}
+
+/// Periodic interest or fee posted to an account by `apply_accruals`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountAccrual {
+    pub id: String,
+    pub user_id: String,
+    pub account_id: String,
+    pub name: String,
+    pub kind: AccrualKind,
+    /// Flat amount charged each period, for fees
+    pub amount: Option<Decimal>,
+    /// Annual rate as a fraction (e.g. `0.05`), for interest; negative rates charge interest
+    pub annual_rate: Option<Decimal>,
+    pub frequency: AccrualFrequency,
+    pub category_id: Option<String>,
+    /// First due date; later ones step from it by whole periods
+    pub start_date: NaiveDate,
+    /// Latest due date already posted
+    pub last_applied: Option<NaiveDate>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Entity for AccountAccrual {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// Transaction field a categorization rule matches against
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleMatchField {
+    /// Case-insensitive substring of the payee
+    Payee,
+    /// Case-insensitive substring of the description
+    Description,
+    /// Amount within an inclusive `min..max` range; either bound may be omitted
+    Amount,
+}
+
+impl std::fmt::Display for RuleMatchField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleMatchField::Payee => write!(f, "payee"),
+            RuleMatchField::Description => write!(f, "description"),
+            RuleMatchField::Amount => write!(f, "amount"),
+        }
+    }
+}
+
+/// Rule assigning a category to the transactions it matches
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CategorizationRule {
+    pub id: String,
+    pub user_id: String,
+    pub match_field: RuleMatchField,
+    pub pattern: String,
+    pub category_id: String,
+    /// Higher priorities are tried first
+    pub priority: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Entity for CategorizationRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// User-defined group of accounts, e.g. "Retirement"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountGroup {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Entity for AccountGroup {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
 /// Category entity
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
@@ -81,6 +295,9 @@ pub struct Category {
     pub icon: Option<String>,
     pub parent_category_id: Option<String>,
     pub is_income: bool,
+    /// Expenses in this category count towards the deductible tax summary
+    #[serde(default)]
+    pub tax_deductible: bool,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -99,7 +316,7 @@ impl Entity for Category {
 }
 
 /// Transaction entity
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Transaction {
     pub id: String,
     pub user_id: String,
@@ -114,6 +331,15 @@ pub struct Transaction {
     pub reference_number: Option<String>,
     pub payee: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Amount in the currency the transaction was made in, when it differs
+    /// from the account currency; `amount` holds the converted value
+    #[serde(default)]
+    pub original_amount: Option<Decimal>,
+    #[serde(default)]
+    pub original_currency: Option<String>,
+    /// Expense this transaction refunds, for income created by `create_refund`
+    #[serde(default)]
+    pub refunds_transaction_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -131,7 +357,7 @@ impl Entity for Transaction {
 }
 
 /// Transaction type enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum TransactionType {
     Income,
@@ -150,12 +376,13 @@ impl std::fmt::Display for TransactionType {
 }
 
 /// Transaction status enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum TransactionStatus {
     Pending,
     Completed,
     Cancelled,
+    Voided,
 }
 
 impl std::fmt::Display for TransactionStatus {
@@ -164,12 +391,13 @@ impl std::fmt::Display for TransactionStatus {
             TransactionStatus::Pending => write!(f, "pending"),
             TransactionStatus::Completed => write!(f, "completed"),
             TransactionStatus::Cancelled => write!(f, "cancelled"),
+            TransactionStatus::Voided => write!(f, "voided"),
         }
     }
 }
 
 /// Budget Period entity
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BudgetPeriod {
     pub id: String,
     pub user_id: String,
@@ -203,6 +431,9 @@ pub struct Budget {
     pub allocated_amount: Decimal,
     pub spent_amount: Decimal,
     pub notes: Option<String>,
+    /// Whether unspent allocation carries over to the next period
+    #[serde(default)]
+    pub rollover: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -219,8 +450,42 @@ impl Entity for Budget {
     }
 }
 
-/// Goal entity
+/// One category's allocation within a budget template
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct BudgetTemplateAllocation {
+    pub category_id: String,
+    pub allocated_amount: Decimal,
+    #[serde(default)]
+    pub rollover: bool,
+    pub notes: Option<String>,
+}
+
+/// Reusable set of category allocations for creating budget periods
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetTemplate {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub allocations: Vec<BudgetTemplateAllocation>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Entity for BudgetTemplate {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// Goal entity
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Goal {
     pub id: String,
     pub user_id: String,
@@ -249,7 +514,7 @@ impl Entity for Goal {
 }
 
 /// Goal status enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum GoalStatus {
     Active,
@@ -274,6 +539,9 @@ impl std::fmt::Display for GoalStatus {
 pub struct Transfer {
     pub id: String,
     pub user_id: String,
+    /// Owner of the destination account when it differs from `user_id`
+    #[serde(default)]
+    pub to_user_id: Option<String>,
     pub from_account_id: String,
     pub to_account_id: String,
     pub amount: Decimal,
@@ -286,6 +554,13 @@ pub struct Transfer {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Transfer {
+    /// Owner of the destination account
+    pub fn recipient_user_id(&self) -> &str {
+        self.to_user_id.as_deref().unwrap_or(&self.user_id)
+    }
+}
+
 impl Entity for Transfer {
     fn id(&self) -> &str {
         &self.id
@@ -298,6 +573,42 @@ impl Entity for Transfer {
     }
 }
 
+/// User-defined currency accepted alongside the ISO 4217 list (crypto, local scrip)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct CustomCurrency {
+    /// 3-8 uppercase letters or digits
+    pub code: String,
+    /// Number of decimal places, e.g. 8 for BTC
+    pub minor_units: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Net worth snapshot entity (point-in-time totals for historical trending)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetWorthSnapshot {
+    pub id: String,
+    pub user_id: String,
+    pub snapshot_date: NaiveDate,
+    pub total_assets: Decimal,
+    pub total_liabilities: Decimal,
+    pub net_worth: Decimal,
+    pub account_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Entity for NetWorthSnapshot {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
 /// Utility functions for model operations
 impl User {
     pub fn new(username: String, email: Option<String>, password_hash: String) -> Self {
@@ -322,9 +633,16 @@ impl Account {
             account_type_id,
             name,
             balance: Decimal::ZERO,
+            opening_balance: Decimal::ZERO,
+            opening_balance_date: None,
             currency,
             account_number: None,
             is_active: true,
+            group_id: None,
+            status: AccountStatus::Active,
+            closing_date: None,
+            default_category_id: None,
+            default_payee: None,
             created_at: now,
             updated_at: now,
         }
@@ -343,6 +661,7 @@ impl Category {
             icon: None,
             parent_category_id: None,
             is_income,
+            tax_deductible: false,
             is_active: true,
             created_at: now,
             updated_at: now,
@@ -460,6 +779,9 @@ mod tests {
             reference_number: None,
             payee: None,
             tags: None,
+            original_amount: None,
+            original_currency: None,
+            refunds_transaction_id: None,
             created_at: now,
             updated_at: now,
         };
@@ -503,6 +825,7 @@ mod tests {
             allocated_amount: Decimal::new(50000, 2), // $500.00
             spent_amount: Decimal::ZERO,
             notes: None,
+            rollover: false,
             created_at: now,
             updated_at: now,
         };
@@ -583,6 +906,10 @@ mod tests {
         assert_eq!(pending_deserialized, TransactionStatus::Pending);
         assert_eq!(completed_deserialized, TransactionStatus::Completed);
         assert_eq!(cancelled_deserialized, TransactionStatus::Cancelled);
+
+        let voided_json = serde_json::to_string(&TransactionStatus::Voided).unwrap();
+        assert_eq!(voided_json, "\"voided\"");
+        assert_eq!(TransactionStatus::Voided.to_string(), "voided");
     }
 
     #[test]
@@ -63,6 +63,23 @@ impl<'de> Deserialize<'de> for SensitiveData<String> {
     }
 }
 
+/// Sensitive values are sent in the wrapped type's form but never returned
+impl<T: schemars::JsonSchema> schemars::JsonSchema for SensitiveData<T> {
+    fn schema_name() -> String {
+        format!("Sensitive_{}", T::schema_name())
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = T::json_schema(gen).into_object();
+        schema.metadata().write_only = true;
+        schema.into()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+}
+
 /// Type alias for sensitive password data
 pub type SensitivePassword = SensitiveData<String>;
 
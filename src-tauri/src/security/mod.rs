@@ -2,16 +2,238 @@
 ///
 /// This module provides security controls including authentication checks,
 /// rate limiting, input validation, and access controls for encryption operations.
+use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, instrument, warn};
 
+use crate::clock::{system_clock, Clock, SharedClock};
 use crate::error::{FiscusError, FiscusResult};
 
 pub mod data_protection;
 
+/// Permission to read financial data
+pub const PERMISSION_DATA_READ: &str = "data:read";
+/// Permission to create, modify, or delete financial data
+pub const PERMISSION_DATA_WRITE: &str = "data:write";
+/// Marks an observer session, e.g. a partner viewing shared accounts
+pub const PERMISSION_READ_ONLY: &str = "data:read_only";
+/// Permission to see full account numbers instead of the masked form
+pub const PERMISSION_REVEAL_ACCOUNT_NUMBER: &str = "accounts:reveal_number";
+/// Permission that lets a context's `bypass_rate_limit` flag take effect
+pub const PERMISSION_RATE_LIMIT_EXEMPT: &str = "system:rate_limit_exempt";
+
+/// Commands that only read financial data
+const READ_OPERATIONS: &[&str] = &[
+    "get_transactions",
+    "get_transactions_by_cursor",
+    "get_transactions_paginated",
+    "get_transaction_by_id",
+    "get_transfer_by_id",
+    "get_transaction_summary",
+    "get_transaction_stats",
+    "find_duplicate_transactions",
+    "get_user_tags",
+    "export_transactions",
+    "export_user_archive",
+    "export_signed_archive",
+    "list_user_keys",
+    "get_key_lineage",
+    "verify_user_data_integrity",
+    "get_accounts",
+    "get_account_by_id",
+    "get_account_summary",
+    "get_accounts_grouped",
+    "audit_account_balance",
+    "project_payoff",
+    "find_data_inconsistencies",
+    "recover_readable_fields",
+    "get_budget_periods",
+    "get_budget_period_by_id",
+    "get_current_budget_period",
+    "get_budgets",
+    "get_budget_by_id",
+    "get_budget_summary",
+    "get_budget_vs_actual",
+];
+
+/// Commands that create, modify, or delete financial data
+const WRITE_OPERATIONS: &[&str] = &[
+    "create_transaction",
+    "create_transactions_batch",
+    "create_refund",
+    "import_transactions",
+    "import_transactions_json",
+    "update_transaction",
+    "delete_transaction",
+    "create_transfer",
+    "create_cross_user_transfer",
+    "void_transfer",
+    "bulk_transaction_operations",
+    "backfill_payee_indexes",
+    "create_account",
+    "clone_account",
+    "update_account",
+    "delete_account",
+    "set_opening_balance",
+    "repair_account_balance",
+    "close_account",
+    "create_account_group",
+    "assign_account_to_group",
+    "create_account_accrual",
+    "apply_accruals",
+    "merge_categories",
+    "create_categorization_rule",
+    "apply_categorization_rules",
+    "set_account_spending_limit",
+    "set_category_spending_limit",
+    "create_budget_period",
+    "create_budget",
+    "update_budget",
+    "delete_budget",
+    "scale_budget_period",
+    "create_budget_template",
+    "instantiate_budget_from_template",
+    "migrate_data_type_algorithm",
+    "revoke_key",
+    "rekey_after_password_change",
+    "register_custom_currency",
+    "set_exchange_rate",
+];
+
+/// Commands that run before a session exists
+const UNAUTHENTICATED_OPERATIONS: &[&str] = &["create_user", "login_user"];
+
+/// Security context of the active session, if one has been established
+static ACTIVE_CONTEXT: Lazy<RwLock<Option<SecurityContext>>> = Lazy::new(|| RwLock::new(None));
+
+/// Shared access controller used by data commands
+static COMMAND_ACCESS_CONTROLLER: Lazy<AccessController> = Lazy::new(AccessController::new);
+
+/// Set (or clear) the security context that data commands are checked against
+///
+/// `login_user` establishes the context once the password has been verified.
+pub async fn set_active_context(context: Option<SecurityContext>) {
+    *ACTIVE_CONTEXT.write().await = context;
+}
+
+/// Check that the active session may run a data command.
+///
+/// Fails closed: without an active context only the commands in
+/// `UNAUTHENTICATED_OPERATIONS` are allowed.
+pub async fn authorize_command(operation: &str) -> FiscusResult<()> {
+    authorize_command_for(ACTIVE_CONTEXT.read().await.as_ref(), operation).await
+}
+
+/// `authorize_command` against `context`
+async fn authorize_command_for(
+    context: Option<&SecurityContext>,
+    operation: &str,
+) -> FiscusResult<()> {
+    if UNAUTHENTICATED_OPERATIONS.contains(&operation) {
+        return Ok(());
+    }
+
+    match context {
+        Some(context) => {
+            COMMAND_ACCESS_CONTROLLER
+                .check_access(context, operation)
+                .await
+        }
+        None => {
+            warn!(
+                operation = operation,
+                "Command rejected without an active session"
+            );
+            Err(FiscusError::Authorization(
+                "Please log in to continue".to_string(),
+            ))
+        }
+    }
+}
+
+/// Check that the active session acts on behalf of `user_id`.
+///
+/// Fails closed like `authorize_command` when there is no active session.
+pub async fn authorize_user(user_id: &str) -> FiscusResult<()> {
+    ensure_context_user(ACTIVE_CONTEXT.read().await.as_ref(), user_id)
+}
+
+/// Copy of the active session's security context, if any
+pub async fn active_context() -> Option<SecurityContext> {
+    ACTIVE_CONTEXT.read().await.clone()
+}
+
+/// How recently a session must have authenticated to run sensitive operations
+pub const RECENT_AUTH_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// Reject the session unless it authenticated within `max_age`.
+///
+/// Gates high-risk operations such as key rotation and deletion; on failure
+/// the UI should ask for the password again (see `reauthenticate`) and
+/// retry. Fails closed when there is no active session.
+pub fn require_recent_auth(
+    context: Option<&SecurityContext>,
+    max_age: Duration,
+    clock: &dyn Clock,
+) -> FiscusResult<()> {
+    let Some(context) = context else {
+        warn!("Sensitive operation attempted without an active session");
+        return Err(FiscusError::Authentication(
+            "Please log in to continue".to_string(),
+        ));
+    };
+
+    let now = clock.instant();
+    if !context.is_auth_valid_at(max_age, now) {
+        warn!(
+            user_id = %context.user_id,
+            auth_age_secs = context.auth_age_at(now).as_secs(),
+            "Sensitive operation requires re-authentication"
+        );
+        return Err(FiscusError::Authentication(
+            "Please confirm your password to continue".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Mark the active session for `user_id` as having just authenticated
+///
+/// Called once the user has re-entered their password.
+pub async fn refresh_authentication(user_id: &str) -> FiscusResult<()> {
+    let mut active = ACTIVE_CONTEXT.write().await;
+    if let Some(context) = active.as_mut() {
+        ensure_context_user(Some(context), user_id)?;
+        context.authenticated_at = Instant::now();
+    }
+    Ok(())
+}
+
+/// Whether `context` holds `permission`; without a session nothing is granted
+pub(crate) fn context_grants(context: Option<&SecurityContext>, permission: &str) -> bool {
+    context.is_some_and(|context| context.has_permission(permission))
+}
+
+/// Check that `context` acts on behalf of `user_id`; see `authorize_user`
+pub(crate) fn ensure_context_user(
+    context: Option<&SecurityContext>,
+    user_id: &str,
+) -> FiscusResult<()> {
+    match context {
+        Some(context) if context.user_id == user_id => Ok(()),
+        Some(_) => Err(FiscusError::Authorization(
+            "Session is not authorized for this user".to_string(),
+        )),
+        None => Err(FiscusError::Authorization(
+            "Please log in to continue".to_string(),
+        )),
+    }
+}
+
 /// Security context for operations
 #[derive(Debug, Clone)]
 pub struct SecurityContext {
@@ -21,6 +243,10 @@ pub struct SecurityContext {
     pub user_agent: Option<String>,
     pub authenticated_at: Instant,
     pub permissions: Vec<String>,
+    /// Skip per-user rate limits, for trusted internal flows such as
+    /// background jobs. Ignored unless the context also holds
+    /// `PERMISSION_RATE_LIMIT_EXEMPT`.
+    pub bypass_rate_limit: bool,
 }
 
 impl SecurityContext {
@@ -33,6 +259,44 @@ impl SecurityContext {
             user_agent: None,
             authenticated_at: Instant::now(),
             permissions: Vec::new(),
+            bypass_rate_limit: false,
+        }
+    }
+
+    /// Create the context of a user who logged in to their own data
+    pub fn owner(user_id: String) -> Self {
+        Self {
+            permissions: vec![
+                PERMISSION_DATA_READ.to_string(),
+                PERMISSION_DATA_WRITE.to_string(),
+                PERMISSION_REVEAL_ACCOUNT_NUMBER.to_string(),
+            ],
+            ..Self::new(user_id)
+        }
+    }
+
+    /// Create a context for a trusted internal flow acting for `user_id`,
+    /// exempt from per-user rate limits
+    pub fn trusted_system(user_id: String) -> Self {
+        Self {
+            permissions: vec![
+                PERMISSION_DATA_READ.to_string(),
+                PERMISSION_DATA_WRITE.to_string(),
+                PERMISSION_RATE_LIMIT_EXEMPT.to_string(),
+            ],
+            bypass_rate_limit: true,
+            ..Self::new(user_id)
+        }
+    }
+
+    /// Create a context that may view but not modify the user's data
+    pub fn read_only(user_id: String) -> Self {
+        Self {
+            permissions: vec![
+                PERMISSION_DATA_READ.to_string(),
+                PERMISSION_READ_ONLY.to_string(),
+            ],
+            ..Self::new(user_id)
         }
     }
 
@@ -41,9 +305,29 @@ impl SecurityContext {
         self.permissions.contains(&permission.to_string())
     }
 
+    /// Check if the context both asks for and is permitted a rate-limit exemption
+    pub fn is_rate_limit_exempt(&self) -> bool {
+        self.bypass_rate_limit && self.has_permission(PERMISSION_RATE_LIMIT_EXEMPT)
+    }
+
+    /// Check if the context is limited to reading data
+    pub fn is_read_only(&self) -> bool {
+        self.has_permission(PERMISSION_READ_ONLY) && !self.has_permission(PERMISSION_DATA_WRITE)
+    }
+
     /// Check if the authentication is still valid
     pub fn is_auth_valid(&self, max_age: Duration) -> bool {
-        self.authenticated_at.elapsed() < max_age
+        self.is_auth_valid_at(max_age, Instant::now())
+    }
+
+    /// Check if the authentication is still valid at `now`
+    pub fn is_auth_valid_at(&self, max_age: Duration, now: Instant) -> bool {
+        self.auth_age_at(now) < max_age
+    }
+
+    /// Time since authentication as of `now`
+    pub fn auth_age_at(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.authenticated_at)
     }
 }
 
@@ -58,9 +342,14 @@ pub struct SecurityMiddleware {
 impl SecurityMiddleware {
     /// Create a new security middleware instance
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a middleware whose rate limits and session expiry read `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
-            rate_limiter: Arc::new(RwLock::new(RateLimiter::new())),
-            auth_validator: Arc::new(AuthValidator::new()),
+            rate_limiter: Arc::new(RwLock::new(RateLimiter::with_clock(clock.clone()))),
+            auth_validator: Arc::new(AuthValidator::with_clock(clock)),
             access_controller: Arc::new(AccessController::new()),
         }
     }
@@ -87,7 +376,7 @@ impl SecurityMiddleware {
         self.rate_limiter
             .write()
             .await
-            .check_rate_limit(&context.user_id, operation)
+            .check_context_rate_limit(context, operation)
             .await?;
 
         // 3. Check access permissions
@@ -131,6 +420,7 @@ pub struct RateLimiter {
     user_limits: HashMap<String, UserRateLimit>,
     #[allow(dead_code)]
     global_limits: HashMap<String, GlobalRateLimit>,
+    clock: SharedClock,
 }
 
 #[derive(Debug)]
@@ -150,16 +440,22 @@ struct GlobalRateLimit {
 impl RateLimiter {
     /// Create a new rate limiter
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a rate limiter that measures its windows with `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             user_limits: HashMap::new(),
             global_limits: HashMap::new(),
+            clock,
         }
     }
 
     /// Check if a user can perform an operation
     #[instrument(skip(self), fields(user_id = user_id, operation = operation))]
     pub async fn check_rate_limit(&mut self, user_id: &str, operation: &str) -> FiscusResult<()> {
-        let now = Instant::now();
+        let now = self.clock.instant();
 
         // Define rate limits per operation
         let (user_limit, window) = match operation {
@@ -218,6 +514,38 @@ impl RateLimiter {
         Ok(())
     }
 
+    /// Check the rate limit for `context`, letting exempt contexts through
+    ///
+    /// Exempt requests are not counted against the user's limit but are
+    /// logged, as is a bypass request from a context without the permission.
+    #[instrument(skip(self, context), fields(user_id = %context.user_id, operation = operation))]
+    pub async fn check_context_rate_limit(
+        &mut self,
+        context: &SecurityContext,
+        operation: &str,
+    ) -> FiscusResult<()> {
+        if context.is_rate_limit_exempt() {
+            info!(
+                user_id = %context.user_id,
+                session_id = ?context.session_id,
+                operation = operation,
+                "Rate limit bypassed for exempt context"
+            );
+            return Ok(());
+        }
+
+        if context.bypass_rate_limit {
+            warn!(
+                user_id = %context.user_id,
+                operation = operation,
+                required_permission = PERMISSION_RATE_LIMIT_EXEMPT,
+                "Rate limit bypass requested without permission; applying normal limit"
+            );
+        }
+
+        self.check_rate_limit(&context.user_id, operation).await
+    }
+
     /// Get current rate limit status for a user
     pub fn get_rate_limit_status(&self, user_id: &str, operation: &str) -> (usize, usize) {
         let (limit, _) = match operation {
@@ -242,24 +570,33 @@ impl RateLimiter {
 #[derive(Debug)]
 pub struct AuthValidator {
     session_timeout: Duration,
+    clock: SharedClock,
 }
 
 impl AuthValidator {
     /// Create a new authentication validator
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a validator that checks session age against `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             session_timeout: Duration::from_secs(3600), // 1 hour
+            clock,
         }
     }
 
     /// Validate user authentication
     #[instrument(skip(self, context), fields(user_id = %context.user_id))]
     pub async fn validate_authentication(&self, context: &SecurityContext) -> FiscusResult<()> {
+        let now = self.clock.instant();
+
         // Check if authentication is still valid
-        if !context.is_auth_valid(self.session_timeout) {
+        if !context.is_auth_valid_at(self.session_timeout, now) {
             warn!(
                 user_id = %context.user_id,
-                auth_age = ?context.authenticated_at.elapsed(),
+                auth_age = ?context.auth_age_at(now),
                 "Authentication expired"
             );
             return Err(FiscusError::Authentication(
@@ -272,7 +609,7 @@ impl AuthValidator {
 
         debug!(
             user_id = %context.user_id,
-            auth_age = ?context.authenticated_at.elapsed(),
+            auth_age = ?context.auth_age_at(now),
             "Authentication validation passed"
         );
 
@@ -315,6 +652,19 @@ impl AccessController {
             ],
         );
 
+        for operation in READ_OPERATIONS {
+            required_permissions.insert(
+                operation.to_string(),
+                vec![PERMISSION_DATA_READ.to_string()],
+            );
+        }
+        for operation in WRITE_OPERATIONS {
+            required_permissions.insert(
+                operation.to_string(),
+                vec![PERMISSION_DATA_WRITE.to_string()],
+            );
+        }
+
         Self {
             required_permissions,
         }
@@ -331,6 +681,18 @@ impl AccessController {
         // In a production system, you'd implement proper role-based access control
 
         if let Some(required_perms) = self.required_permissions.get(operation) {
+            // Read-only sessions are always blocked from writes
+            if context.is_read_only() && required_perms.iter().any(|p| p == PERMISSION_DATA_WRITE) {
+                warn!(
+                    user_id = %context.user_id,
+                    operation = operation,
+                    "Access denied - read-only session attempted a write"
+                );
+                return Err(FiscusError::Authorization(format!(
+                    "Read-only access does not permit {operation}"
+                )));
+            }
+
             for required_perm in required_perms {
                 if !context.has_permission(required_perm) {
                     warn!(
@@ -384,6 +746,65 @@ impl Default for AccessController {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::{Clock, MockClock};
+
+    #[test]
+    fn test_context_user_must_match() {
+        let context = SecurityContext::new("owner".to_string());
+
+        assert!(ensure_context_user(Some(&context), "owner").is_ok());
+        assert!(matches!(
+            ensure_context_user(Some(&context), "someone-else"),
+            Err(FiscusError::Authorization(_))
+        ));
+        // Without a session nobody may act for any user
+        assert!(matches!(
+            ensure_context_user(None, "anyone"),
+            Err(FiscusError::Authorization(_))
+        ));
+    }
+
+    #[test]
+    fn test_context_grants_requires_permission() {
+        let mut context = SecurityContext::new("owner".to_string());
+        assert!(!context_grants(
+            Some(&context),
+            PERMISSION_REVEAL_ACCOUNT_NUMBER
+        ));
+
+        context
+            .permissions
+            .push(PERMISSION_REVEAL_ACCOUNT_NUMBER.to_string());
+        assert!(context_grants(
+            Some(&context),
+            PERMISSION_REVEAL_ACCOUNT_NUMBER
+        ));
+        assert!(!context_grants(None, PERMISSION_REVEAL_ACCOUNT_NUMBER));
+    }
+
+    #[test]
+    fn test_stale_session_must_reauthenticate() {
+        let clock = MockClock::new();
+        let mut context = SecurityContext::new("owner".to_string());
+        context.authenticated_at = clock.instant();
+
+        clock.advance(Duration::from_secs(60));
+        assert!(require_recent_auth(Some(&context), RECENT_AUTH_MAX_AGE, &clock).is_ok());
+
+        clock.advance(RECENT_AUTH_MAX_AGE);
+        assert!(matches!(
+            require_recent_auth(Some(&context), RECENT_AUTH_MAX_AGE, &clock),
+            Err(FiscusError::Authentication(_))
+        ));
+
+        // Re-entering the password starts a fresh window
+        context.authenticated_at = clock.instant();
+        assert!(require_recent_auth(Some(&context), RECENT_AUTH_MAX_AGE, &clock).is_ok());
+        assert!(matches!(
+            require_recent_auth(None, RECENT_AUTH_MAX_AGE, &clock),
+            Err(FiscusError::Authentication(_))
+        ));
+    }
 
     #[tokio::test]
     async fn test_security_context_creation() {
@@ -412,6 +833,58 @@ mod tests {
         assert_eq!(limit, 100);
     }
 
+    #[tokio::test]
+    async fn test_exempt_context_exceeds_limit_while_normal_context_is_blocked() {
+        let mut rate_limiter = RateLimiter::new();
+        let operation = "rotate_user_keys";
+        let exempt = SecurityContext::trusted_system("recurrence-job".to_string());
+        let normal = SecurityContext::new("user".to_string());
+
+        for _ in 0..20 {
+            assert!(rate_limiter
+                .check_context_rate_limit(&exempt, operation)
+                .await
+                .is_ok());
+        }
+        assert_eq!(
+            rate_limiter.get_rate_limit_status("recurrence-job", operation),
+            (0, 5)
+        );
+
+        for _ in 0..5 {
+            assert!(rate_limiter
+                .check_context_rate_limit(&normal, operation)
+                .await
+                .is_ok());
+        }
+        assert!(matches!(
+            rate_limiter
+                .check_context_rate_limit(&normal, operation)
+                .await,
+            Err(FiscusError::Security(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bypass_flag_without_permission_is_rate_limited() {
+        let mut rate_limiter = RateLimiter::new();
+        let operation = "rotate_user_keys";
+        let mut context = SecurityContext::new("user".to_string());
+        context.bypass_rate_limit = true;
+        assert!(!context.is_rate_limit_exempt());
+
+        for _ in 0..5 {
+            assert!(rate_limiter
+                .check_context_rate_limit(&context, operation)
+                .await
+                .is_ok());
+        }
+        assert!(rate_limiter
+            .check_context_rate_limit(&context, operation)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_auth_validator() {
         let validator = AuthValidator::new();
@@ -421,6 +894,50 @@ mod tests {
         assert!(validator.validate_authentication(&context).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_auth_validator_expires_session_on_mock_clock() {
+        let clock = Arc::new(MockClock::new());
+        let validator = AuthValidator::with_clock(clock.clone());
+        let mut context = SecurityContext::new("test-user".to_string());
+        context.authenticated_at = clock.instant();
+
+        clock.advance(Duration::from_secs(3599));
+        assert!(validator.validate_authentication(&context).await.is_ok());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(matches!(
+            validator.validate_authentication(&context).await,
+            Err(FiscusError::Authentication(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_window_resets_on_mock_clock() {
+        let clock = Arc::new(MockClock::new());
+        let mut rate_limiter = RateLimiter::with_clock(clock.clone());
+        // deepcode ignore NoHardcodedCredentials: <test>
+        let user_id = "test-user";
+        let operation = "rotate_user_keys";
+
+        for _ in 0..5 {
+            assert!(rate_limiter
+                .check_rate_limit(user_id, operation)
+                .await
+                .is_ok());
+        }
+        assert!(rate_limiter
+            .check_rate_limit(user_id, operation)
+            .await
+            .is_err());
+
+        // The hourly window has passed once the clock moves past it
+        clock.advance(Duration::from_secs(3601));
+        assert!(rate_limiter
+            .check_rate_limit(user_id, operation)
+            .await
+            .is_ok());
+    }
+
     #[tokio::test]
     async fn test_access_controller() {
         let controller = AccessController::new();
@@ -432,4 +949,64 @@ mod tests {
             .await
             .is_ok());
     }
+
+    #[tokio::test]
+    async fn test_read_only_context_can_read_but_not_write() {
+        let controller = AccessController::new();
+        let context = SecurityContext::read_only("observer".to_string());
+        assert!(context.is_read_only());
+
+        assert!(controller
+            .check_access(&context, "get_transactions")
+            .await
+            .is_ok());
+
+        match controller
+            .check_access(&context, "create_transaction")
+            .await
+        {
+            Err(FiscusError::Authorization(msg)) => assert!(msg.contains("create_transaction")),
+            other => panic!("Expected authorization error, got {other:?}"),
+        }
+        assert!(controller
+            .check_access(&context, "delete_account")
+            .await
+            .is_err());
+        assert!(controller
+            .check_access(&context, "update_budget")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_commands_fail_closed_without_a_session() {
+        assert!(matches!(
+            authorize_command_for(None, "get_transactions").await,
+            Err(FiscusError::Authorization(_))
+        ));
+        assert!(matches!(
+            authorize_command_for(None, "create_transaction").await,
+            Err(FiscusError::Authorization(_))
+        ));
+        assert!(authorize_command_for(None, "login_user").await.is_ok());
+        assert!(authorize_command_for(None, "create_user").await.is_ok());
+
+        let owner = SecurityContext::owner("owner".to_string());
+        assert!(authorize_command_for(Some(&owner), "create_transaction")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_write_permission_overrides_read_only_flag() {
+        let controller = AccessController::new();
+        let mut context = SecurityContext::read_only("owner".to_string());
+        context.permissions.push(PERMISSION_DATA_WRITE.to_string());
+
+        assert!(!context.is_read_only());
+        assert!(controller
+            .check_access(&context, "create_transaction")
+            .await
+            .is_ok());
+    }
 }
@@ -0,0 +1,104 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+/// Number of events a slow subscriber may fall behind before it starts missing them
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Change notifications published after a transaction or goal mutation commits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransactionEvent {
+    TransactionCreated {
+        user_id: String,
+        transaction_id: String,
+        account_id: String,
+    },
+    TransactionUpdated {
+        user_id: String,
+        transaction_id: String,
+    },
+    BalanceChanged {
+        user_id: String,
+        account_id: String,
+    },
+    GoalMilestoneReached {
+        user_id: String,
+        goal_id: String,
+        milestone: u32,
+    },
+}
+
+impl TransactionEvent {
+    /// Name the event is emitted under to the frontend
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionEvent::TransactionCreated { .. } => "transaction-created",
+            TransactionEvent::TransactionUpdated { .. } => "transaction-updated",
+            TransactionEvent::BalanceChanged { .. } => "balance-changed",
+            TransactionEvent::GoalMilestoneReached { .. } => "goal-milestone-reached",
+        }
+    }
+
+    /// User whose data changed
+    pub fn user_id(&self) -> &str {
+        match self {
+            TransactionEvent::TransactionCreated { user_id, .. }
+            | TransactionEvent::TransactionUpdated { user_id, .. }
+            | TransactionEvent::BalanceChanged { user_id, .. }
+            | TransactionEvent::GoalMilestoneReached { user_id, .. } => user_id,
+        }
+    }
+}
+
+static EVENT_BUS: Lazy<broadcast::Sender<TransactionEvent>> =
+    Lazy::new(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0);
+
+/// Receive every event published from now on
+pub fn subscribe() -> broadcast::Receiver<TransactionEvent> {
+    EVENT_BUS.subscribe()
+}
+
+/// Publish events to all subscribers
+///
+/// Call only once the database transaction has committed. Without subscribers,
+/// as in tests or before the app is set up, this is a no-op.
+pub fn publish(events: impl IntoIterator<Item = TransactionEvent>) {
+    for event in events {
+        if EVENT_BUS.send(event).is_err() {
+            debug!("No subscribers for transaction events");
+            return;
+        }
+    }
+}
+
+/// Forward published events to the frontend through the Tauri event emitter
+pub fn forward_to_frontend<R: Runtime>(app: AppHandle<R>) {
+    let mut receiver = subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app.emit(event.name(), &event) {
+                        warn!(
+                            error = %e,
+                            event = event.name(),
+                            user_id = event.user_id(),
+                            "Failed to emit event to frontend"
+                        );
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(
+                        skipped,
+                        "Frontend event forwarder fell behind and skipped events"
+                    );
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
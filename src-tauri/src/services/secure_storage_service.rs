@@ -1,8 +1,10 @@
 use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio::time::{interval, Duration as TokioDuration};
-use tracing::{error, info, instrument};
+use tokio::sync::{Mutex, Notify, RwLock};
+use tokio::time::{interval, Duration as TokioDuration, MissedTickBehavior};
+use tracing::{debug, error, info, instrument};
 
 use crate::{
     database::secure_storage_repository::SecureStorageRepository,
@@ -25,8 +27,8 @@ pub struct StoreWithExpirationParams<'a> {
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // Public API - fields will be used by consumers
 pub struct SecureStorageConfig {
-    /// How often to run cleanup (in minutes)
-    pub cleanup_interval_minutes: u64,
+    /// How often to run cleanup
+    pub cleanup_interval: TokioDuration,
     /// Default expiration time for data (in hours)
     pub default_expiration_hours: i64,
     /// Maximum number of access attempts before logging warning
@@ -38,20 +40,38 @@ pub struct SecureStorageConfig {
 impl Default for SecureStorageConfig {
     fn default() -> Self {
         Self {
-            cleanup_interval_minutes: 60,     // Run cleanup every hour
-            default_expiration_hours: 24 * 7, // 7 days default expiration
-            max_access_attempts: 1000,        // Log warning after 1000 accesses
+            cleanup_interval: TokioDuration::from_secs(60 * 60), // Run cleanup every hour
+            default_expiration_hours: 24 * 7,                    // 7 days default expiration
+            max_access_attempts: 1000,                           // Log warning after 1000 accesses
             auto_cleanup_enabled: true,
         }
     }
 }
 
+/// Counters describing cleanup runs since the service started
+#[derive(Debug, Clone, Default)]
+pub struct CleanupMetrics {
+    /// Number of completed cleanup runs (automatic and manual)
+    pub runs: u64,
+    /// Total rows removed across all runs
+    pub total_removed: u64,
+    /// Rows removed by the most recent run
+    pub last_removed: u64,
+    /// When the most recent run completed
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
 /// Secure storage service with automatic cleanup and monitoring
 #[allow(dead_code)] // Service fields are used internally
 pub struct SecureStorageService {
     repository: Arc<SecureStorageRepository>,
     config: Arc<RwLock<SecureStorageConfig>>,
     cleanup_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Signals the background cleanup task to stop
+    cleanup_shutdown: Arc<Notify>,
+    /// Held for the duration of a cleanup run so runs never overlap
+    cleanup_lock: Arc<Mutex<()>>,
+    metrics: Arc<RwLock<CleanupMetrics>>,
 }
 
 impl SecureStorageService {
@@ -68,9 +88,30 @@ impl SecureStorageService {
             repository,
             config,
             cleanup_handle: None,
+            cleanup_shutdown: Arc::new(Notify::new()),
+            cleanup_lock: Arc::new(Mutex::new(())),
+            metrics: Arc::new(RwLock::new(CleanupMetrics::default())),
         }
     }
 
+    /// Run one cleanup pass and record it in the metrics
+    ///
+    /// Callers must hold `cleanup_lock`.
+    async fn run_cleanup(
+        repository: &SecureStorageRepository,
+        metrics: &RwLock<CleanupMetrics>,
+    ) -> FiscusResult<u64> {
+        let deleted_count = repository.cleanup_expired().await?;
+
+        let mut metrics = metrics.write().await;
+        metrics.runs += 1;
+        metrics.total_removed += deleted_count;
+        metrics.last_removed = deleted_count;
+        metrics.last_run_at = Some(Utc::now());
+
+        Ok(deleted_count)
+    }
+
     /// Start the automatic cleanup service
     #[instrument(skip(self))]
     #[allow(dead_code)] // Public API method
@@ -82,17 +123,39 @@ impl SecureStorageService {
             return Ok(());
         }
 
-        let cleanup_interval = config.cleanup_interval_minutes;
+        if self.cleanup_handle.is_some() {
+            debug!("Cleanup service is already running");
+            return Ok(());
+        }
+
+        let cleanup_interval = config.cleanup_interval;
         drop(config); // Release the lock
 
+        if cleanup_interval.is_zero() {
+            return Err(FiscusError::InvalidInput(
+                "Cleanup interval must be greater than zero".to_string(),
+            ));
+        }
+
         let repository = Arc::clone(&self.repository);
         let config_arc = Arc::clone(&self.config);
+        let shutdown = Arc::clone(&self.cleanup_shutdown);
+        let cleanup_lock = Arc::clone(&self.cleanup_lock);
+        let metrics = Arc::clone(&self.metrics);
 
         let handle = tokio::spawn(async move {
-            let mut interval = interval(TokioDuration::from_secs(cleanup_interval * 60));
+            let mut interval = interval(cleanup_interval);
+            // A slow run should not cause a burst of catch-up runs
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.notified() => {
+                        info!("Cleanup service received shutdown signal");
+                        break;
+                    }
+                }
 
                 let config = config_arc.read().await;
                 if !config.auto_cleanup_enabled {
@@ -101,7 +164,13 @@ impl SecureStorageService {
                 }
                 drop(config);
 
-                match repository.cleanup_expired().await {
+                // Skip this tick if a manual cleanup is still running
+                let Ok(_guard) = cleanup_lock.try_lock() else {
+                    debug!("Skipping automatic cleanup - another cleanup is in progress");
+                    continue;
+                };
+
+                match Self::run_cleanup(&repository, &metrics).await {
                     Ok(deleted_count) => {
                         if deleted_count > 0 {
                             info!(
@@ -122,7 +191,7 @@ impl SecureStorageService {
 
         self.cleanup_handle = Some(handle);
         info!(
-            interval_minutes = cleanup_interval,
+            interval_secs = cleanup_interval.as_secs(),
             "Started automatic cleanup service"
         );
 
@@ -130,11 +199,17 @@ impl SecureStorageService {
     }
 
     /// Stop the automatic cleanup service
+    ///
+    /// Waits for an in-flight cleanup run to finish instead of aborting it.
     #[instrument(skip(self))]
     #[allow(dead_code)] // Public API method
     pub async fn stop_cleanup_service(&mut self) {
         if let Some(handle) = self.cleanup_handle.take() {
-            handle.abort();
+            // notify_one stores a permit, so the signal is not lost if the task is mid-run
+            self.cleanup_shutdown.notify_one();
+            if let Err(e) = handle.await {
+                error!(error = %e, "Cleanup service task ended abnormally");
+            }
             info!("Stopped automatic cleanup service");
         }
     }
@@ -197,7 +272,8 @@ impl SecureStorageService {
     pub async fn manual_cleanup(&self) -> FiscusResult<CleanupReport> {
         let start_time = Utc::now();
 
-        let deleted_count = self.repository.cleanup_expired().await?;
+        let _guard = self.cleanup_lock.lock().await;
+        let deleted_count = Self::run_cleanup(&self.repository, &self.metrics).await?;
 
         let duration = Utc::now().signed_duration_since(start_time);
 
@@ -216,13 +292,46 @@ impl SecureStorageService {
         Ok(report)
     }
 
+    /// Get cleanup metrics
+    pub async fn get_cleanup_metrics(&self) -> CleanupMetrics {
+        self.metrics.read().await.clone()
+    }
+
     /// Get storage statistics
+    ///
+    /// Service-wide statistics (no `user_id`) include an extra entry with
+    /// `"metric": "cleanup"` describing cleanup runs and rows removed.
     #[instrument(skip(self))]
     pub async fn get_statistics(
         &self,
         user_id: Option<&str>,
-    ) -> FiscusResult<Vec<std::collections::HashMap<String, serde_json::Value>>> {
-        self.repository.get_storage_stats(user_id).await
+    ) -> FiscusResult<Vec<HashMap<String, Value>>> {
+        let mut stats = self.repository.get_storage_stats(user_id).await?;
+
+        if user_id.is_none() {
+            let metrics = self.get_cleanup_metrics().await;
+            stats.push(HashMap::from([
+                ("metric".to_string(), Value::from("cleanup")),
+                ("cleanup_runs".to_string(), Value::from(metrics.runs)),
+                (
+                    "rows_removed".to_string(),
+                    Value::from(metrics.total_removed),
+                ),
+                (
+                    "last_rows_removed".to_string(),
+                    Value::from(metrics.last_removed),
+                ),
+                (
+                    "last_cleanup_at".to_string(),
+                    metrics
+                        .last_run_at
+                        .map(|at| Value::String(at.to_rfc3339()))
+                        .unwrap_or(Value::Null),
+                ),
+            ]));
+        }
+
+        Ok(stats)
     }
 }
 
@@ -249,7 +358,6 @@ static SECURE_STORAGE_SERVICE: tokio::sync::OnceCell<
 > = tokio::sync::OnceCell::const_new();
 
 /// Initialize the global secure storage service
-#[allow(dead_code)] // Public API function
 pub async fn initialize_secure_storage_service(
     db: crate::database::DatabaseConnection,
     config: Option<SecureStorageConfig>,
@@ -286,3 +394,96 @@ pub async fn shutdown_secure_storage_service() -> FiscusResult<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{DatabaseConnection, DatabaseType};
+    use crate::encryption::types::EncryptionAlgorithm;
+    use uuid::Uuid;
+
+    fn create_test_service(cleanup_interval: TokioDuration) -> SecureStorageService {
+        let conn = DatabaseConnection::new(":memory:".to_string(), DatabaseType::SQLite);
+        SecureStorageService::new(
+            conn,
+            Some(SecureStorageConfig {
+                cleanup_interval,
+                ..SecureStorageConfig::default()
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_background_cleanup_removes_expired_entries() {
+        let mut service = create_test_service(TokioDuration::from_millis(20));
+        let user_id = Uuid::new_v4().to_string();
+        let key_id = Uuid::new_v4().to_string();
+
+        // Store an entry that has already expired
+        service
+            .repository()
+            .store(
+                &user_id,
+                "expired_data",
+                "encrypted_test_data_base64",
+                "test_nonce_base64",
+                EncryptionAlgorithm::Aes256Gcm,
+                &key_id,
+                Some(Utc::now() - Duration::hours(1)),
+            )
+            .await
+            .unwrap();
+
+        service.start_cleanup_service().await.unwrap();
+
+        let mut metrics = service.get_cleanup_metrics().await;
+        for _ in 0..50 {
+            if metrics.total_removed > 0 {
+                break;
+            }
+            tokio::time::sleep(TokioDuration::from_millis(20)).await;
+            metrics = service.get_cleanup_metrics().await;
+        }
+
+        service.stop_cleanup_service().await;
+
+        assert_eq!(metrics.total_removed, 1);
+        assert!(metrics.runs >= 1);
+        assert!(service
+            .repository()
+            .retrieve(&user_id, "expired_data")
+            .await
+            .unwrap()
+            .is_none());
+
+        let stats = service.get_statistics(None).await.unwrap();
+        let cleanup = stats
+            .iter()
+            .find(|row| row.get("metric") == Some(&Value::from("cleanup")))
+            .expect("cleanup metrics should be reported");
+        assert_eq!(cleanup.get("rows_removed"), Some(&Value::from(1u64)));
+    }
+
+    #[tokio::test]
+    async fn test_stop_cleanup_service_shuts_down_task() {
+        let mut service = create_test_service(TokioDuration::from_millis(10));
+
+        service.start_cleanup_service().await.unwrap();
+        assert!(service.cleanup_handle.is_some());
+
+        service.stop_cleanup_service().await;
+        assert!(service.cleanup_handle.is_none());
+
+        // The service can be restarted after a clean shutdown
+        service.start_cleanup_service().await.unwrap();
+        service.stop_cleanup_service().await;
+    }
+
+    #[tokio::test]
+    async fn test_zero_cleanup_interval_is_rejected() {
+        let mut service = create_test_service(TokioDuration::ZERO);
+
+        let result = service.start_cleanup_service().await;
+        assert!(matches!(result, Err(FiscusError::InvalidInput(_))));
+    }
+}
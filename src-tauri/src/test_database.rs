@@ -1,9 +1,19 @@
 use mockall::mock;
+use rust_decimal::Decimal;
 use serde_json::Value;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::collections::HashMap;
+use tauri::test::{mock_app, MockRuntime};
+use tauri::{App, Manager};
 use tempfile::NamedTempFile;
+use tokio::sync::{Mutex, MutexGuard};
 
-use crate::error::{FiscusError, FiscusResult};
+use crate::{
+    database::{test_backend, Database, DatabaseType},
+    error::{FiscusError, FiscusResult},
+    models::{Account, Category, User},
+    security::{set_active_context, SecurityContext},
+};
 
 mock! {
     /// Mock database for testing
@@ -15,19 +25,67 @@ mock! {
 }
 
 /// Test database setup utilities
+///
+/// Backed by a real SQLite pool, so migrations and seeded rows are checked by
+/// SQLite itself and can be read back with `fetch_*`.
 #[allow(dead_code)]
 pub struct TestDatabase {
     pub temp_file: Option<NamedTempFile>,
     pub db_url: String,
+    pool: SqlitePool,
+}
+
+/// Map a sqlx failure onto the application error type
+fn sqlx_error(e: sqlx::Error) -> FiscusError {
+    FiscusError::Database(e.to_string())
+}
+
+/// Serializes tests that install a session, since the active context is process-wide
+static SESSION_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Active session for a test; other sessions wait until it is dropped
+pub struct TestSession {
+    _guard: MutexGuard<'static, ()>,
+}
+
+/// Log `user_id` in so that commands pass `authorize_command` and `authorize_user`
+pub async fn sign_in(user_id: &str) -> TestSession {
+    let guard = SESSION_LOCK.lock().await;
+    set_active_context(Some(SecurityContext::owner(user_id.to_string()))).await;
+    TestSession { _guard: guard }
 }
 
 #[allow(dead_code)]
 impl TestDatabase {
+    /// Create an in-memory SQLite database with every migration applied
+    ///
+    /// Handles from `database()` and `app()` run their queries against this
+    /// pool, so commands can be called directly and their effects read back
+    /// with `fetch_*` or `DatabaseUtils`.
+    pub async fn in_memory() -> FiscusResult<Self> {
+        let test_db = Self::new_in_memory().await?;
+        test_db.init_schema().await?;
+        Ok(test_db)
+    }
+
     /// Create a new in-memory SQLite database for testing
+    ///
+    /// The pool holds a single connection that never expires, since each
+    /// connection to `:memory:` opens a separate, empty database.
     pub async fn new_in_memory() -> FiscusResult<Self> {
+        let db_url = "sqlite::memory:".to_string();
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect(&db_url)
+            .await
+            .map_err(sqlx_error)?;
+
         Ok(Self {
             temp_file: None,
-            db_url: "sqlite::memory:".to_string(),
+            db_url,
+            pool,
         })
     }
 
@@ -37,20 +95,206 @@ impl TestDatabase {
             .map_err(|e| FiscusError::Internal(format!("Failed to create temp file: {e}")))?;
 
         let db_url = format!("sqlite:{}", temp_file.path().display());
+        let pool = SqlitePool::connect(&db_url).await.map_err(sqlx_error)?;
 
         Ok(Self {
             temp_file: Some(temp_file),
             db_url,
+            pool,
         })
     }
 
-    /// Initialize the test database with schema
+    /// Database handle whose queries run against this database
+    pub fn database(&self) -> Database {
+        Database::new(self.db_url.clone(), DatabaseType::SQLite).with_test_pool(self.pool.clone())
+    }
+
+    /// Mock app managing `database()`; pass `app.state()` where a command takes `State<Database>`
+    pub fn app(&self) -> App<MockRuntime> {
+        let app = mock_app();
+        app.manage(self.database());
+        app
+    }
+
+    /// Initialize the test database with schema by applying the bundled migrations in order
     pub async fn init_schema(&self) -> FiscusResult<()> {
-        // This would normally use the actual database connection
-        // For now, we'll simulate schema initialization
+        let mut applied = 0;
+
+        for migration in crate::migrations() {
+            if migration.version != applied + 1 {
+                return Err(FiscusError::Internal(format!(
+                    "Migration {} follows version {applied}; migrations must be consecutive",
+                    migration.version
+                )));
+            }
+
+            sqlx::raw_sql(migration.sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| {
+                    FiscusError::Database(format!(
+                        "Migration {} ({}) failed: {e}",
+                        migration.version, migration.description
+                    ))
+                })?;
+            applied = migration.version;
+        }
+
         Ok(())
     }
 
+    /// Run a statement and return the number of affected rows
+    pub async fn execute(&self, query: &str, params: Vec<Value>) -> FiscusResult<u64> {
+        test_backend::execute(&self.pool, query, params).await
+    }
+
+    /// First column of the single row `query` returns, as text
+    pub async fn fetch_text(&self, query: &str, params: Vec<Value>) -> FiscusResult<String> {
+        let mut scalar = sqlx::query_scalar::<_, String>(query);
+        for param in params {
+            scalar = match param {
+                Value::String(text) => scalar.bind(text),
+                other => scalar.bind(other.to_string()),
+            };
+        }
+
+        scalar.fetch_one(&self.pool).await.map_err(sqlx_error)
+    }
+
+    /// Insert a user and return it
+    pub async fn seed_user(&self, username: &str) -> FiscusResult<User> {
+        let user = User::new(
+            username.to_string(),
+            Some(format!("{username}@example.com")),
+            "$argon2id$v=19$m=65536,t=3,p=4$test_salt$test_hash".to_string(),
+        );
+
+        self.execute(
+            r#"
+            INSERT INTO users (id, username, email, password_hash, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            vec![
+                Value::String(user.id.clone()),
+                Value::String(user.username.clone()),
+                user.email.clone().map(Value::String).unwrap_or(Value::Null),
+                Value::String(user.password_hash.clone()),
+                Value::String(user.created_at.to_rfc3339()),
+                Value::String(user.updated_at.to_rfc3339()),
+            ],
+        )
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Insert a USD checking account for `user_id` holding `balance` and return it
+    pub async fn seed_account(&self, user_id: &str, balance: Decimal) -> FiscusResult<Account> {
+        let mut account = Account::new(
+            user_id.to_string(),
+            "checking".to_string(),
+            "Test Checking".to_string(),
+            "USD".to_string(),
+        );
+        account.balance = balance;
+        account.opening_balance = balance;
+
+        self.execute(
+            r#"
+            INSERT INTO accounts (
+                id, user_id, account_type_id, name, initial_balance, current_balance,
+                opening_balance, currency, account_number, is_active, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?5, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            vec![
+                Value::String(account.id.clone()),
+                Value::String(account.user_id.clone()),
+                Value::String(account.account_type_id.clone()),
+                Value::String(account.name.clone()),
+                Value::String(account.balance.to_string()),
+                Value::String(account.currency.clone()),
+                Value::Null,
+                Value::Bool(account.is_active),
+                Value::String(account.created_at.to_rfc3339()),
+                Value::String(account.updated_at.to_rfc3339()),
+            ],
+        )
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Insert an expense category for `user_id`, optionally nested under `parent_id`
+    pub async fn seed_category(
+        &self,
+        user_id: &str,
+        name: &str,
+        parent_id: Option<&str>,
+    ) -> FiscusResult<Category> {
+        let mut category = Category::new(user_id.to_string(), name.to_string(), false);
+        category.parent_category_id = parent_id.map(str::to_string);
+
+        self.execute(
+            r#"
+            INSERT INTO categories (
+                id, user_id, name, parent_category_id, is_income, is_active, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            vec![
+                Value::String(category.id.clone()),
+                Value::String(category.user_id.clone()),
+                Value::String(category.name.clone()),
+                category
+                    .parent_category_id
+                    .clone()
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+                Value::Bool(category.is_income),
+                Value::Bool(category.is_active),
+                Value::String(category.created_at.to_rfc3339()),
+                Value::String(category.updated_at.to_rfc3339()),
+            ],
+        )
+        .await?;
+
+        Ok(category)
+    }
+
+    /// Insert a completed expense of `amount` on `account_id` and return its id
+    pub async fn seed_transaction(
+        &self,
+        user_id: &str,
+        account_id: &str,
+        category_id: Option<&str>,
+        amount: Decimal,
+    ) -> FiscusResult<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        self.execute(
+            r#"
+            INSERT INTO transactions (
+                id, user_id, account_id, category_id, amount, description,
+                transaction_date, transaction_type, status, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'expense', 'completed', ?7, ?7)
+            "#,
+            vec![
+                Value::String(id.clone()),
+                Value::String(user_id.to_string()),
+                Value::String(account_id.to_string()),
+                category_id
+                    .map(|c| Value::String(c.to_string()))
+                    .unwrap_or(Value::Null),
+                Value::String(amount.to_string()),
+                Value::String("Seeded expense".to_string()),
+                Value::String(now),
+            ],
+        )
+        .await?;
+
+        Ok(id)
+    }
+
     /// Seed the database with test data
     pub async fn seed_test_data(&self) -> FiscusResult<TestDataSet> {
         let test_data = TestDataSet::new();
@@ -122,7 +366,6 @@ pub struct TestTransaction {
 #[allow(dead_code)]
 impl TestDataSet {
     pub fn new() -> Self {
-        use rust_decimal::Decimal;
         use uuid::Uuid;
 
         let user1_id = Uuid::new_v4().to_string();
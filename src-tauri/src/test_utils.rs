@@ -89,6 +89,9 @@ impl TestUtils {
             reference_number: None,
             payee: None,
             tags: None,
+            original_amount: None,
+            original_currency: None,
+            refunds_transaction_id: None,
             created_at: now,
             updated_at: now,
         }
@@ -129,6 +132,7 @@ impl TestUtils {
             allocated_amount,
             spent_amount: Decimal::ZERO,
             notes: None,
+            rollover: false,
             created_at: now,
             updated_at: now,
         }
@@ -205,10 +209,15 @@ impl TestUtils {
             description: description.to_string(),
             notes: None,
             transaction_date: Utc::now(),
-            transaction_type: TransactionType::Expense,
+            transaction_type: Some(TransactionType::Expense),
             reference_number: None,
             payee: None,
             tags: None,
+            idempotency_key: None,
+            original_amount: None,
+            original_currency: None,
+            override_limit: false,
+            refunds_transaction_id: None,
         }
     }
 
@@ -226,6 +235,7 @@ impl TestUtils {
             icon: None,
             parent_category_id: None,
             is_income,
+            tax_deductible: false,
         }
     }
 
@@ -239,6 +249,7 @@ impl TestUtils {
             sort_direction: None,
             limit: None,
             offset: None,
+            reveal_account_number: false,
         }
     }
 
@@ -255,10 +266,14 @@ impl TestUtils {
             min_amount: None,
             max_amount: None,
             search: None,
+            payee: None,
+            tag_filter: None,
+            cursor: None,
             sort_by: None,
             sort_direction: None,
             limit: None,
             offset: None,
+            fields: None,
         }
     }
 
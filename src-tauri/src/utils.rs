@@ -1,7 +1,12 @@
-use rust_decimal::Decimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
+use crate::error::{FiscusError, FiscusResult};
+
 /// Utility functions for common data parsing operations
 ///
 /// Parse a decimal value from a JSON HashMap field
@@ -79,6 +84,244 @@ pub fn parse_decimal_from_json_with_default(
         .unwrap_or(default)
 }
 
+/// Message returned when an optimistic concurrency check fails
+pub const STALE_WRITE_MESSAGE: &str = "stale write";
+
+/// Reject an update whose `expected_updated_at` no longer matches the stored row
+///
+/// Updates without an expected timestamp are always allowed, so optimistic
+/// locking stays opt-in for existing clients.
+pub fn ensure_not_stale(
+    current_updated_at: DateTime<Utc>,
+    expected_updated_at: Option<DateTime<Utc>>,
+) -> FiscusResult<()> {
+    match expected_updated_at {
+        Some(expected) if expected != current_updated_at => {
+            Err(FiscusError::Conflict(STALE_WRITE_MESSAGE.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Build the `AND updated_at = ?N` condition guarding an UPDATE against
+/// concurrent edits, together with its parameter
+///
+/// Returns `None` when no expected timestamp was supplied.
+pub fn stale_write_guard(
+    expected_updated_at: Option<DateTime<Utc>>,
+    param_index: usize,
+) -> Option<(String, Value)> {
+    expected_updated_at.map(|expected| {
+        (
+            format!(" AND `updated_at` = ?{param_index}"),
+            Value::String(expected.to_rfc3339()),
+        )
+    })
+}
+
+/// Error for an UPDATE that affected no rows
+///
+/// With an expected timestamp and a row that still exists, the row was
+/// modified concurrently, so this is a stale write rather than a missing row.
+pub fn no_rows_updated_error(
+    expected_updated_at: Option<DateTime<Utc>>,
+    row_exists: bool,
+    not_found_message: &str,
+) -> FiscusError {
+    if expected_updated_at.is_some() && row_exists {
+        FiscusError::Conflict(STALE_WRITE_MESSAGE.to_string())
+    } else {
+        FiscusError::NotFound(not_found_message.to_string())
+    }
+}
+
+/// Decimal places represented by one stored minor unit of an amount
+pub const AMOUNT_MINOR_UNIT_SCALE: u32 = 2;
+
+/// Convert an amount to integer minor units (hundredths)
+///
+/// Sub-cent precision is rounded half away from zero; the decimal amount
+/// remains the authoritative value.
+pub fn decimal_to_minor_units(amount: Decimal) -> FiscusResult<i64> {
+    let rounded = amount.round_dp_with_strategy(
+        AMOUNT_MINOR_UNIT_SCALE,
+        RoundingStrategy::MidpointAwayFromZero,
+    );
+    rounded
+        .checked_mul(Decimal::from(10i64.pow(AMOUNT_MINOR_UNIT_SCALE)))
+        .and_then(|units| units.to_i64())
+        .ok_or_else(|| {
+            FiscusError::Validation("Amount is too large to store in minor units".to_string())
+        })
+}
+
+/// Convert integer minor units (hundredths) back to a decimal amount
+pub fn minor_units_to_decimal(units: i64) -> Decimal {
+    Decimal::new(units, AMOUNT_MINOR_UNIT_SCALE)
+}
+
+/// Hash identifying a transaction by its content, for import deduplication
+///
+/// Covers the account, calendar date, signed amount and description. The
+/// description ignores case and runs of whitespace and the amount ignores
+/// trailing zeros, so the same row from two bank exports hashes the same.
+pub fn transaction_content_hash(
+    account_id: &str,
+    date: NaiveDate,
+    amount: Decimal,
+    description: &str,
+) -> String {
+    let description = description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let date = date.format("%Y-%m-%d").to_string();
+    let amount = amount.normalize().to_string();
+
+    let mut hasher = Sha256::new();
+    for part in [account_id.trim(), &date, &amount, &description] {
+        hasher.update(part.as_bytes());
+        // Unit separator, so shifting text between fields changes the hash
+        hasher.update([0x1f]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Display conventions for a currency
+struct CurrencyFormat {
+    symbol: &'static str,
+    minor_units: u32,
+    decimal_separator: char,
+    group_separator: char,
+    symbol_after: bool,
+}
+
+impl CurrencyFormat {
+    const fn new(
+        symbol: &'static str,
+        minor_units: u32,
+        decimal_separator: char,
+        group_separator: char,
+        symbol_after: bool,
+    ) -> Self {
+        Self {
+            symbol,
+            minor_units,
+            decimal_separator,
+            group_separator,
+            symbol_after,
+        }
+    }
+}
+
+/// Formatting conventions for commonly used currencies
+fn currency_format(currency: &str) -> Option<CurrencyFormat> {
+    let format = match currency {
+        "USD" => CurrencyFormat::new("$", 2, '.', ',', false),
+        "CAD" => CurrencyFormat::new("CA$", 2, '.', ',', false),
+        "AUD" => CurrencyFormat::new("A$", 2, '.', ',', false),
+        "GBP" => CurrencyFormat::new("£", 2, '.', ',', false),
+        "EUR" => CurrencyFormat::new("€", 2, ',', '.', true),
+        "CHF" => CurrencyFormat::new("CHF", 2, '.', '\'', true),
+        "SEK" => CurrencyFormat::new("kr", 2, ',', ' ', true),
+        "JPY" => CurrencyFormat::new("¥", 0, '.', ',', false),
+        "KRW" => CurrencyFormat::new("₩", 0, '.', ',', false),
+        "INR" => CurrencyFormat::new("₹", 2, '.', ',', false),
+        _ => return None,
+    };
+    Some(format)
+}
+
+/// Format an amount for display in the given currency
+///
+/// Applies the currency's minor-unit precision, thousands separators, and
+/// symbol position. Other currencies get the code appended, with the minor
+/// units they were registered with or two decimals, e.g. `1,000.00 XYZ`.
+pub fn format_currency(amount: Decimal, currency: &str) -> String {
+    let code = currency.trim().to_uppercase();
+    let format = currency_format(&code).unwrap_or_else(|| {
+        let minor_units = crate::error::custom_currency_minor_units(&code).unwrap_or(2);
+        CurrencyFormat::new("", minor_units, '.', ',', true)
+    });
+
+    let mut rounded = amount
+        .abs()
+        .round_dp_with_strategy(format.minor_units, RoundingStrategy::MidpointAwayFromZero);
+    rounded.rescale(format.minor_units);
+
+    let digits = rounded.to_string();
+    let (integer_part, fraction_part) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits.as_str(), None),
+    };
+
+    let mut number = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    for (i, digit) in integer_part.chars().enumerate() {
+        if i > 0 && (integer_part.len() - i) % 3 == 0 {
+            number.push(format.group_separator);
+        }
+        number.push(digit);
+    }
+    if let Some(fraction) = fraction_part {
+        number.push(format.decimal_separator);
+        number.push_str(fraction);
+    }
+
+    let sign = if amount.is_sign_negative() && !rounded.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+
+    if format.symbol.is_empty() {
+        format!("{sign}{number} {code}")
+    } else if format.symbol_after {
+        format!("{sign}{number} {}", format.symbol)
+    } else {
+        format!("{sign}{}{number}", format.symbol)
+    }
+}
+
+/// Positive integer from the environment variable `name`, `default` when unset or invalid
+pub(crate) fn env_usize(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(default)
+}
+
+/// Whether the environment variable `name` is `1`, `true` or `yes`
+pub(crate) fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .is_ok_and(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// Positive number of seconds from the environment variable `name`, `default` when unset or invalid
+pub(crate) fn env_duration(name: &str, default: chrono::Duration) -> chrono::Duration {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(chrono::Duration::seconds)
+        .unwrap_or(default)
+}
+
+/// Give a configuration type a `current()` that caches its `from_env()` for the process
+#[macro_export]
+macro_rules! env_config {
+    ($config:ty) => {
+        impl $config {
+            /// Process-wide value, read from the environment once
+            pub fn current() -> &'static Self {
+                static CURRENT: std::sync::OnceLock<$config> = std::sync::OnceLock::new();
+                CURRENT.get_or_init(Self::from_env)
+            }
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,4 +393,189 @@ mod tests {
         let result = parse_decimal_from_json(&data, "amount");
         assert_eq!(result.to_string(), "-123.45");
     }
+
+    #[test]
+    fn test_stale_update_is_rejected() {
+        let stored = Utc::now();
+        let seen_by_client = stored - chrono::Duration::seconds(5);
+
+        let result = ensure_not_stale(stored, Some(seen_by_client));
+        assert!(
+            matches!(result, Err(FiscusError::Conflict(ref msg)) if msg == STALE_WRITE_MESSAGE)
+        );
+
+        let error = no_rows_updated_error(Some(seen_by_client), true, "Transaction not found");
+        assert!(matches!(error, FiscusError::Conflict(_)));
+    }
+
+    #[test]
+    fn test_missing_row_with_expected_timestamp_is_not_found() {
+        let seen_by_client = Utc::now();
+
+        assert!(matches!(
+            no_rows_updated_error(Some(seen_by_client), false, "Account not found"),
+            FiscusError::NotFound(ref msg) if msg == "Account not found"
+        ));
+    }
+
+    #[test]
+    fn test_fresh_update_succeeds() {
+        let stored = Utc::now();
+
+        assert!(ensure_not_stale(stored, Some(stored)).is_ok());
+        // Clients that don't opt in are never rejected
+        assert!(ensure_not_stale(stored, None).is_ok());
+        assert!(matches!(
+            no_rows_updated_error(None, false, "Transaction not found"),
+            FiscusError::NotFound(_)
+        ));
+    }
+
+    #[test]
+    fn test_stale_write_guard_matches_stored_format() {
+        let stored = Utc::now();
+        // updated_at is written with to_rfc3339, so a parsed round trip must compare equal
+        let parsed = DateTime::parse_from_rfc3339(&stored.to_rfc3339())
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let (clause, param) = stale_write_guard(Some(parsed), 7).unwrap();
+        assert_eq!(clause, " AND `updated_at` = ?7");
+        assert_eq!(param, Value::String(stored.to_rfc3339()));
+
+        assert!(stale_write_guard(None, 7).is_none());
+    }
+
+    #[test]
+    fn test_format_currency_usd() {
+        assert_eq!(format_currency(Decimal::new(1000, 0), "USD"), "$1,000.00");
+        assert_eq!(
+            format_currency(Decimal::new(123456789, 2), "usd"),
+            "$1,234,567.89"
+        );
+        assert_eq!(format_currency(Decimal::new(-5, 1), "USD"), "-$0.50");
+        assert_eq!(format_currency(Decimal::new(999, 0), "USD"), "$999.00");
+    }
+
+    #[test]
+    fn test_format_currency_eur_uses_comma_decimal() {
+        assert_eq!(
+            format_currency(Decimal::new(100000, 2), "EUR"),
+            "1.000,00 €"
+        );
+        assert_eq!(
+            format_currency(Decimal::new(-123456, 3), "EUR"),
+            "-123,46 €"
+        );
+    }
+
+    #[test]
+    fn test_format_currency_jpy_has_no_decimals() {
+        assert_eq!(
+            format_currency(Decimal::new(1234567, 0), "JPY"),
+            "¥1,234,567"
+        );
+        assert_eq!(format_currency(Decimal::new(15, 1), "JPY"), "¥2");
+    }
+
+    #[test]
+    fn test_format_currency_unknown_falls_back_to_code() {
+        assert_eq!(
+            format_currency(Decimal::new(1000, 0), "XYZ"),
+            "1,000.00 XYZ"
+        );
+    }
+
+    #[test]
+    fn test_minor_units_round_trip() {
+        assert_eq!(
+            decimal_to_minor_units(Decimal::new(12345, 2)).unwrap(),
+            12345
+        );
+        assert_eq!(decimal_to_minor_units(Decimal::new(-9, 0)).unwrap(), -900);
+        assert_eq!(minor_units_to_decimal(12345), Decimal::new(12345, 2));
+        assert_eq!(minor_units_to_decimal(-900), Decimal::new(-900, 2));
+    }
+
+    #[test]
+    fn test_minor_units_round_sub_cent_amounts() {
+        assert_eq!(
+            decimal_to_minor_units(Decimal::new(10005, 3)).unwrap(),
+            1001
+        );
+        assert_eq!(
+            decimal_to_minor_units(Decimal::new(-10005, 3)).unwrap(),
+            -1001
+        );
+        assert!(matches!(
+            decimal_to_minor_units(Decimal::MAX),
+            Err(FiscusError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_content_hash_ignores_cosmetic_differences() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let hash = transaction_content_hash("acc-1", date, Decimal::new(1250, 2), "COFFEE  Shop");
+
+        assert_eq!(
+            transaction_content_hash("acc-1", date, Decimal::new(12500, 3), " coffee shop "),
+            hash
+        );
+        assert_ne!(
+            transaction_content_hash("acc-1", date, Decimal::new(1251, 2), "COFFEE  Shop"),
+            hash
+        );
+        assert_ne!(
+            transaction_content_hash("acc-2", date, Decimal::new(1250, 2), "COFFEE  Shop"),
+            hash
+        );
+        assert_ne!(
+            transaction_content_hash(
+                "acc-1",
+                date.succ_opt().unwrap(),
+                Decimal::new(1250, 2),
+                "COFFEE  Shop"
+            ),
+            hash
+        );
+        assert_ne!(
+            transaction_content_hash("acc-1", date, Decimal::new(-1250, 2), "COFFEE  Shop"),
+            hash
+        );
+    }
+
+    #[test]
+    fn test_env_helpers_fall_back_on_unset_or_invalid_values() {
+        std::env::remove_var("FISCUS_TEST_ENV_UNSET");
+        assert_eq!(env_usize("FISCUS_TEST_ENV_UNSET", 7), 7);
+        assert!(!env_flag("FISCUS_TEST_ENV_UNSET"));
+        assert_eq!(
+            env_duration("FISCUS_TEST_ENV_UNSET", chrono::Duration::seconds(30)),
+            chrono::Duration::seconds(30)
+        );
+
+        std::env::set_var("FISCUS_TEST_ENV_INVALID", "0");
+        assert_eq!(env_usize("FISCUS_TEST_ENV_INVALID", 7), 7);
+        assert!(!env_flag("FISCUS_TEST_ENV_INVALID"));
+        assert_eq!(
+            env_duration("FISCUS_TEST_ENV_INVALID", chrono::Duration::seconds(30)),
+            chrono::Duration::seconds(30)
+        );
+    }
+
+    #[test]
+    fn test_env_helpers_read_set_values() {
+        std::env::set_var("FISCUS_TEST_ENV_NUMBER", " 12 ");
+        assert_eq!(env_usize("FISCUS_TEST_ENV_NUMBER", 7), 12);
+        assert_eq!(
+            env_duration("FISCUS_TEST_ENV_NUMBER", chrono::Duration::seconds(30)),
+            chrono::Duration::seconds(12)
+        );
+
+        for value in ["1", "true", "YES"] {
+            std::env::set_var("FISCUS_TEST_ENV_FLAG", value);
+            assert!(env_flag("FISCUS_TEST_ENV_FLAG"), "{value}");
+        }
+    }
 }
@@ -274,7 +274,7 @@ async fn test_key_manager() {
         .await
         .expect("Failed to list user keys");
     assert!(
-        user_keys.contains(&data_type.to_string()),
+        user_keys.iter().any(|key| key.data_type == data_type),
         "Should contain the created key type"
     );
 
@@ -29,16 +29,20 @@ import type {
 	CreateUserRequest,
 	Goal,
 	GoalFilters,
+	GoalProgressResponse,
 	LoginRequest,
 	// Response types
 	LoginResponse,
 	PaginatedResponse,
+	ReauthenticateRequest,
 	ReportData,
+	SavingsRatePoint,
 	Transaction,
 	TransactionFilters,
 	TransactionStatsResponse,
 	TransactionSummaryResponse,
 	Transfer,
+	TrendGranularity,
 	UpdateAccountRequest,
 	UpdateBudgetRequest,
 	UpdateCategoryRequest,
@@ -130,6 +134,19 @@ export class FiscusApiClient {
 		}
 	}
 
+	/**
+	 * Confirm the password so sensitive operations accept the session again
+	 * @param request User ID and password
+	 * @returns Promise resolving to success status
+	 */
+	async reauthenticate(request: ReauthenticateRequest): Promise<boolean> {
+		try {
+			return await invoke("reauthenticate", { request });
+		} catch (error) {
+			throw handleApiError(error);
+		}
+	}
+
 	/**
 	 * Get current user information
 	 * @param userId User ID
@@ -219,6 +236,32 @@ export class FiscusApiClient {
 		}
 	}
 
+	/**
+	 * Close an account, reconciling it to its final balance
+	 * @param accountId Account ID
+	 * @param userId User ID
+	 * @param closingDate Date the account closed (YYYY-MM-DD)
+	 * @param finalBalance Balance the account closed with
+	 * @returns Promise resolving to the closed account
+	 */
+	async closeAccount(
+		accountId: string,
+		userId: string,
+		closingDate: string,
+		finalBalance: number,
+	): Promise<Account> {
+		try {
+			return await invoke("close_account", {
+				accountId,
+				userId,
+				closingDate,
+				finalBalance,
+			});
+		} catch (error) {
+			throw handleApiError(error);
+		}
+	}
+
 	/**
 	 * Get account summary for a user
 	 * @param userId User ID
@@ -625,6 +668,29 @@ export class FiscusApiClient {
 		}
 	}
 
+	/**
+	 * Scale every budget allocation in a period by a factor
+	 * @param userId User ID
+	 * @param budgetPeriodId Budget period ID
+	 * @param factor Positive multiplier, e.g. 1.1 for a 10% increase
+	 * @returns Promise resolving to the new total allocated
+	 */
+	async scaleBudgetPeriod(
+		userId: string,
+		budgetPeriodId: string,
+		factor: number,
+	): Promise<number> {
+		try {
+			return await invoke("scale_budget_period", {
+				userId,
+				budgetPeriodId,
+				factor,
+			});
+		} catch (error) {
+			throw handleApiError(error);
+		}
+	}
+
 	/**
 	 * Get budget summary for a user and period
 	 * @param userId User ID
@@ -723,13 +789,13 @@ export class FiscusApiClient {
 	 * @param goalId Goal ID
 	 * @param userId User ID
 	 * @param amount Amount to add to progress
-	 * @returns Promise resolving to updated goal
+	 * @returns Promise resolving to updated goal and newly reached milestones
 	 */
 	async updateGoalProgress(
 		goalId: string,
 		userId: string,
 		amount: number,
-	): Promise<Goal> {
+	): Promise<GoalProgressResponse> {
 		try {
 			return await invoke("update_goal_progress", { goalId, userId, amount });
 		} catch (error) {
@@ -820,6 +886,32 @@ export class FiscusApiClient {
 		}
 	}
 
+	/**
+	 * Get savings rate per period, oldest first
+	 * @param userId User ID
+	 * @param startDate First day of the window (YYYY-MM-DD)
+	 * @param endDate Last day of the window (YYYY-MM-DD)
+	 * @param granularity Optional bucket size (monthly by default)
+	 * @returns Promise resolving to one point per period, including empty ones
+	 */
+	async getSavingsRateTrend(
+		userId: string,
+		startDate: string,
+		endDate: string,
+		granularity?: TrendGranularity,
+	): Promise<SavingsRatePoint[]> {
+		try {
+			return await invoke("get_savings_rate_trend", {
+				userId,
+				startDate,
+				endDate,
+				granularity,
+			});
+		} catch (error) {
+			throw handleApiError(error);
+		}
+	}
+
 	/**
 	 * Get account balance history
 	 * @param userId User ID
@@ -127,6 +127,18 @@ export interface VerifySignatureResponse {
 	verified_at: string; // ISO 8601 datetime
 }
 
+export interface AlgorithmCapabilities {
+	algorithm: EncryptionAlgorithm;
+	encrypt: boolean;
+	sign: boolean;
+	transmission_safe: boolean; // Can encrypt for another party's public key
+}
+
+export interface SupportedAlgorithmsResponse {
+	symmetric: AlgorithmCapabilities[];
+	asymmetric: AlgorithmCapabilities[];
+}
+
 // Error types
 export interface EncryptionError {
 	type:
@@ -211,6 +223,19 @@ export class EncryptionApiClient {
 		}
 	}
 
+	/**
+	 * Get the algorithms this build supports and what each can be used for
+	 */
+	async getSupportedAlgorithms(): Promise<SupportedAlgorithmsResponse> {
+		try {
+			return await invoke<SupportedAlgorithmsResponse>(
+				"get_supported_algorithms",
+			);
+		} catch (error) {
+			throw this.handleError(error);
+		}
+	}
+
 	/**
 	 * Derive a key from password
 	 */
@@ -217,7 +217,7 @@ export const useGoalsStore = create<GoalsStore>()((set, get) => ({
 		set({ loading: true, error: null });
 
 		try {
-			const updatedGoal = await apiClient.updateGoalProgress(
+			const { goal: updatedGoal } = await apiClient.updateGoalProgress(
 				goalId,
 				userId,
 				amount,
@@ -65,6 +65,14 @@ export interface Account {
 	currency: string;
 	account_number?: string;
 	is_active: boolean;
+	/** Closed accounts accept no new transactions */
+	status: "active" | "closed";
+	/** Date the account was closed (YYYY-MM-DD) */
+	closing_date?: string;
+	/** Category new transactions fall back to when they name none */
+	default_category_id?: string;
+	/** Payee new transactions fall back to when they name none */
+	default_payee?: string;
 	created_at: string;
 	updated_at: string;
 }
@@ -116,6 +124,7 @@ export interface PaginatedResponse<T> {
 	page: number;
 	per_page: number;
 	total_pages: number;
+	truncated?: boolean; // Cut short at the backend query soft cap
 }
 
 /**
@@ -262,6 +271,16 @@ export interface ChangePasswordRequest {
 	new_password: string;
 }
 
+/**
+ * Re-authentication request, sent before retrying a sensitive operation
+ */
+export interface ReauthenticateRequest {
+	/** User ID */
+	user_id: string;
+	/** Password */
+	password: string;
+}
+
 /**
  * Create account request
  */
@@ -292,6 +311,10 @@ export interface UpdateAccountRequest {
 	account_number?: string;
 	/** Active status */
 	is_active?: boolean;
+	/** Default category for new transactions; null clears it */
+	default_category_id?: string | null;
+	/** Default payee for new transactions; null clears it */
+	default_payee?: string | null;
 }
 
 /**
@@ -402,6 +425,10 @@ export interface CreateTransferRequest {
 	description: string;
 	/** Transfer date (ISO 8601 format) */
 	transfer_date: string;
+	/** Fee charged on top of the amount, posted as an expense on the source account */
+	fee_amount?: number;
+	/** Category of the fee expense; requires fee_amount */
+	fee_category_id?: string;
 }
 
 /**
@@ -654,6 +681,40 @@ export interface TransactionSummaryResponse {
 	average_transaction: number;
 }
 
+/**
+ * Bucket size for trend reports
+ */
+export type TrendGranularity =
+	| "daily"
+	| "weekly"
+	| "monthly"
+	| "quarterly"
+	| "yearly";
+
+/**
+ * Income, expenses and savings rate for one trend bucket
+ */
+export interface SavingsRatePoint {
+	/** Bucket key, e.g. "2024-03" for monthly buckets */
+	period: string;
+	/** Total income, excluding refunds */
+	income: number;
+	/** Total expenses, less refunds */
+	expenses: number;
+	/** Savings rate as a percentage; null for buckets without income */
+	savings_rate: number | null;
+}
+
+/**
+ * Goal progress update response
+ */
+export interface GoalProgressResponse {
+	/** Goal after the update */
+	goal: Goal;
+	/** Milestone percentages newly reached by the update, ascending */
+	milestones_reached: number[];
+}
+
 // ============================================================================
 // Error Types
 // ============================================================================